@@ -5,8 +5,10 @@ use actix_web::web::Data;
 use lemmy_api_common::{
   blocking,
   check_community_ban,
+  check_community_deleted_or_removed,
   check_downvotes_enabled,
   check_person_block,
+  check_saved_collection_owner,
   comment::*,
   get_local_user_view_from_jwt,
 };
@@ -20,10 +22,10 @@ use lemmy_apub::{
 use lemmy_db_schema::{
   newtypes::LocalUserId,
   source::comment::*,
-  traits::{Likeable, Saveable},
+  traits::{Likeable, Reactable, Saveable},
 };
 use lemmy_db_views::{comment_view::CommentView, local_user_view::LocalUserView};
-use lemmy_utils::{ConnectionId, LemmyError};
+use lemmy_utils::{utils::is_valid_reaction, ConnectionId, LemmyError};
 use lemmy_websocket::{send::send_comment_ws_message, LemmyContext, UserOperation};
 
 use crate::Perform;
@@ -101,9 +103,19 @@ impl Perform for SaveComment {
     let local_user_view =
       get_local_user_view_from_jwt(&data.auth, context.pool(), context.secret()).await?;
 
+    if data.save {
+      check_saved_collection_owner(
+        data.collection_id,
+        local_user_view.person.id,
+        context.pool(),
+      )
+      .await?;
+    }
+
     let comment_saved_form = CommentSavedForm {
       comment_id: data.comment_id,
       person_id: local_user_view.person.id,
+      collection_id: data.collection_id,
     };
 
     if data.save {
@@ -242,3 +254,133 @@ impl Perform for CreateCommentLike {
     .await
   }
 }
+
+#[async_trait::async_trait(?Send)]
+impl Perform for ReactComment {
+  type Response = CommentResponse;
+
+  #[tracing::instrument(skip(context, websocket_id))]
+  async fn perform(
+    &self,
+    context: &Data<LemmyContext>,
+    websocket_id: Option<ConnectionId>,
+  ) -> Result<CommentResponse, LemmyError> {
+    let data: &ReactComment = self;
+    let local_user_view =
+      get_local_user_view_from_jwt(&data.auth, context.pool(), context.secret()).await?;
+
+    if !is_valid_reaction(&data.emoji) {
+      return Err(LemmyError::from_message("invalid_reaction"));
+    }
+
+    let comment_id = data.comment_id;
+    let orig_comment = blocking(context.pool(), move |conn| {
+      CommentView::read(conn, comment_id, None)
+    })
+    .await??;
+
+    check_community_ban(
+      local_user_view.person.id,
+      orig_comment.community.id,
+      context.pool(),
+    )
+    .await?;
+    check_community_deleted_or_removed(orig_comment.community.id, context.pool()).await?;
+    check_person_block(
+      local_user_view.person.id,
+      orig_comment.creator.id,
+      context.pool(),
+    )
+    .await?;
+
+    let reaction_form = CommentReactionForm {
+      comment_id,
+      person_id: local_user_view.person.id,
+      emoji: data.emoji.to_owned(),
+    };
+    let inserted = blocking(context.pool(), move |conn| {
+      CommentReaction::react(conn, &reaction_form)
+    })
+    .await?
+    .map_err(LemmyError::from)
+    .map_err(|e| e.with_message("couldnt_react_to_comment"))?;
+
+    // Reacting twice with the same emoji changes nothing, so there is nothing to federate
+    if inserted > 0 {
+      let object = PostOrComment::Comment(Box::new(orig_comment.comment.into()));
+      Vote::send_reaction(
+        &object,
+        &local_user_view.person.clone().into(),
+        orig_comment.community.id,
+        data.emoji.to_owned(),
+        context,
+      )
+      .await?;
+    }
+
+    send_comment_ws_message(
+      comment_id,
+      UserOperation::ReactComment,
+      websocket_id,
+      None,
+      Some(local_user_view.person.id),
+      vec![],
+      context,
+    )
+    .await
+  }
+}
+
+#[async_trait::async_trait(?Send)]
+impl Perform for UnreactComment {
+  type Response = CommentResponse;
+
+  #[tracing::instrument(skip(context, websocket_id))]
+  async fn perform(
+    &self,
+    context: &Data<LemmyContext>,
+    websocket_id: Option<ConnectionId>,
+  ) -> Result<CommentResponse, LemmyError> {
+    let data: &UnreactComment = self;
+    let local_user_view =
+      get_local_user_view_from_jwt(&data.auth, context.pool(), context.secret()).await?;
+
+    let comment_id = data.comment_id;
+    let orig_comment = blocking(context.pool(), move |conn| {
+      CommentView::read(conn, comment_id, None)
+    })
+    .await??;
+
+    let person_id = local_user_view.person.id;
+    let emoji = data.emoji.to_owned();
+    let removed = blocking(context.pool(), move |conn| {
+      CommentReaction::unreact(conn, person_id, comment_id, &emoji)
+    })
+    .await?
+    .map_err(LemmyError::from)
+    .map_err(|e| e.with_message("couldnt_react_to_comment"))?;
+
+    if removed > 0 {
+      let object = PostOrComment::Comment(Box::new(orig_comment.comment.into()));
+      UndoVote::send_reaction(
+        &object,
+        &local_user_view.person.clone().into(),
+        orig_comment.community.id,
+        data.emoji.to_owned(),
+        context,
+      )
+      .await?;
+    }
+
+    send_comment_ws_message(
+      comment_id,
+      UserOperation::UnreactComment,
+      websocket_id,
+      None,
+      Some(local_user_view.person.id),
+      vec![],
+      context,
+    )
+    .await
+  }
+}
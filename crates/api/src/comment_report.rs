@@ -3,6 +3,7 @@ use actix_web::web::Data;
 use lemmy_api_common::{
   blocking,
   check_community_ban,
+  check_resolution_note,
   comment::*,
   get_local_user_view_from_jwt,
   is_mod_or_admin,
@@ -14,7 +15,7 @@ use lemmy_db_views::{
   comment_report_view::{CommentReportQueryBuilder, CommentReportView},
   comment_view::CommentView,
 };
-use lemmy_utils::{ConnectionId, LemmyError};
+use lemmy_utils::{utils::check_slurs_opt, ConnectionId, LemmyError};
 use lemmy_websocket::{messages::SendModRoomMessage, LemmyContext, UserOperation};
 
 /// Creates a comment report and notifies the moderators of the community
@@ -118,10 +119,13 @@ impl Perform for ResolveCommentReport {
     let person_id = local_user_view.person.id;
     is_mod_or_admin(context.pool(), person_id, report.community.id).await?;
 
+    let resolution_note = check_resolution_note(&data.resolution_note)?;
+    check_slurs_opt(&resolution_note, &context.settings().slur_regex())?;
+
     let resolved = data.resolved;
     let resolve_fun = move |conn: &'_ _| {
       if resolved {
-        CommentReport::resolve(conn, report_id, person_id)
+        CommentReport::resolve(conn, report_id, person_id, resolution_note)
       } else {
         CommentReport::unresolve(conn, report_id, person_id)
       }
@@ -153,6 +157,60 @@ impl Perform for ResolveCommentReport {
   }
 }
 
+/// Passes a comment report on to the instance admins and notifies the moderators of the community
+#[async_trait::async_trait(?Send)]
+impl Perform for EscalateCommentReport {
+  type Response = CommentReportResponse;
+
+  #[tracing::instrument(skip(context, websocket_id))]
+  async fn perform(
+    &self,
+    context: &Data<LemmyContext>,
+    websocket_id: Option<ConnectionId>,
+  ) -> Result<CommentReportResponse, LemmyError> {
+    let data: &EscalateCommentReport = self;
+    let local_user_view =
+      get_local_user_view_from_jwt(&data.auth, context.pool(), context.secret()).await?;
+
+    let report_id = data.report_id;
+    let person_id = local_user_view.person.id;
+    let report = blocking(context.pool(), move |conn| {
+      CommentReportView::read(conn, report_id, person_id)
+    })
+    .await??;
+
+    is_mod_or_admin(context.pool(), person_id, report.community.id).await?;
+    if report.comment_report.resolved {
+      return Err(LemmyError::from_message("report_already_resolved"));
+    }
+
+    blocking(context.pool(), move |conn| {
+      CommentReport::escalate(conn, report_id)
+    })
+    .await?
+    .map_err(LemmyError::from)
+    .map_err(|e| e.with_message("couldnt_escalate_report"))?;
+
+    let comment_report_view = blocking(context.pool(), move |conn| {
+      CommentReportView::read(conn, report_id, person_id)
+    })
+    .await??;
+
+    let res = CommentReportResponse {
+      comment_report_view,
+    };
+
+    context.chat_server().do_send(SendModRoomMessage {
+      op: UserOperation::EscalateCommentReport,
+      response: res.clone(),
+      community_id: report.community.id,
+      websocket_id,
+    });
+
+    Ok(res)
+  }
+}
+
 /// Lists comment reports for a community if an id is supplied
 /// or returns all comment reports for communities a user moderates
 #[async_trait::async_trait(?Send)]
@@ -173,6 +231,7 @@ impl Perform for ListCommentReports {
     let admin = local_user_view.person.admin;
     let community_id = data.community_id;
     let unresolved_only = data.unresolved_only;
+    let escalated_only = data.escalated_only;
 
     let page = data.page;
     let limit = data.limit;
@@ -180,6 +239,7 @@ impl Perform for ListCommentReports {
       CommentReportQueryBuilder::create(conn, person_id, admin)
         .community_id(community_id)
         .unresolved_only(unresolved_only)
+        .escalated_only(escalated_only)
         .page(page)
         .limit(limit)
         .list()
@@ -1,15 +1,20 @@
-use crate::Perform;
+use crate::{Perform, BAN_EXPIRED_REASON};
 use actix_web::web::Data;
 use anyhow::Context;
 use lemmy_api_common::{
   blocking,
   check_community_ban,
   check_community_deleted_or_removed,
+  check_private_instance,
   community::*,
   get_local_user_view_from_jwt,
+  get_local_user_view_from_jwt_opt,
+  is_admin,
   is_mod_or_admin,
 };
 use lemmy_apub::{
+  activities::deletion::apply_pending_deletion,
+  fetcher::search::{search_by_apub_id, SearchableObjects},
   objects::{community::ApubCommunity, person::ApubPerson},
   protocol::activities::{
     community::{
@@ -21,7 +26,11 @@ use lemmy_apub::{
     following::{follow::FollowCommunity as FollowCommunityApub, undo_follow::UndoFollowCommunity},
   },
 };
+use lemmy_apub_lib::object_id::ObjectId;
 use lemmy_db_schema::{
+  clock::Clock,
+  naive_now,
+  newtypes::CommunityId,
   source::{
     comment::Comment,
     community::{
@@ -34,6 +43,8 @@ use lemmy_db_schema::{
       CommunityPersonBanForm,
     },
     community_block::{CommunityBlock, CommunityBlockForm},
+    community_category::CommunityCategory,
+    language::CommunityLanguage,
     moderator::{
       ModAddCommunity,
       ModAddCommunityForm,
@@ -42,20 +53,28 @@ use lemmy_db_schema::{
       ModTransferCommunity,
       ModTransferCommunityForm,
     },
+    pending_deletion::PendingDeletion,
     person::Person,
     post::Post,
     site::Site,
   },
   traits::{Bannable, Blockable, Crud, Followable, Joinable},
+  SortType,
 };
-use lemmy_db_views::comment_view::CommentQueryBuilder;
+use lemmy_db_views::{comment_view::CommentQueryBuilder, post_view::PostQueryBuilder};
 use lemmy_db_views_actor::{
   community_moderator_view::CommunityModeratorView,
   community_view::CommunityView,
   person_view::PersonViewSafe,
 };
 use lemmy_utils::{location_info, utils::naive_from_unix, ConnectionId, LemmyError};
-use lemmy_websocket::{messages::SendCommunityRoomMessage, LemmyContext, UserOperation};
+use lemmy_websocket::{
+  messages::SendCommunityRoomMessage,
+  send::send_community_ws_message,
+  LemmyContext,
+  UserOperation,
+};
+use tracing::warn;
 
 #[async_trait::async_trait(?Send)]
 impl Perform for FollowCommunity {
@@ -130,7 +149,10 @@ impl Perform for FollowCommunity {
       community_view.subscribed = data.follow;
     }
 
-    Ok(CommunityResponse { community_view })
+    Ok(CommunityResponse {
+      community_view,
+      action_taken: None,
+    })
   }
 }
 
@@ -150,35 +172,13 @@ impl Perform for BlockCommunity {
 
     let community_id = data.community_id;
     let person_id = local_user_view.person.id;
-    let community_block_form = CommunityBlockForm {
-      person_id,
-      community_id,
-    };
-
     if data.block {
-      let block = move |conn: &'_ _| CommunityBlock::block(conn, &community_block_form);
-      blocking(context.pool(), block)
-        .await?
-        .map_err(LemmyError::from)
-        .map_err(|e| e.with_message("community_block_already_exists"))?;
-
-      // Also, unfollow the community, and send a federated unfollow
-      let community_follower_form = CommunityFollowerForm {
-        community_id: data.community_id,
+      block_community(&local_user_view.person, community_id, context).await?;
+    } else {
+      let community_block_form = CommunityBlockForm {
         person_id,
-        pending: false,
+        community_id,
       };
-      blocking(context.pool(), move |conn: &'_ _| {
-        CommunityFollower::unfollow(conn, &community_follower_form)
-      })
-      .await?
-      .ok();
-      let community = blocking(context.pool(), move |conn| {
-        Community::read(conn, community_id)
-      })
-      .await??;
-      UndoFollowCommunity::send(&local_user_view.person.into(), &community.into(), context).await?;
-    } else {
       let unblock = move |conn: &'_ _| CommunityBlock::unblock(conn, &community_block_form);
       blocking(context.pool(), unblock)
         .await?
@@ -198,6 +198,42 @@ impl Perform for BlockCommunity {
   }
 }
 
+/// Blocks the community for the person, who also unfollows it
+pub(crate) async fn block_community(
+  person: &Person,
+  community_id: CommunityId,
+  context: &LemmyContext,
+) -> Result<(), LemmyError> {
+  let person_id = person.id;
+  let community_block_form = CommunityBlockForm {
+    person_id,
+    community_id,
+  };
+  let block = move |conn: &'_ _| CommunityBlock::block(conn, &community_block_form);
+  blocking(context.pool(), block)
+    .await?
+    .map_err(LemmyError::from)
+    .map_err(|e| e.with_message("community_block_already_exists"))?;
+
+  // Also, unfollow the community, and send a federated unfollow
+  let community_follower_form = CommunityFollowerForm {
+    community_id,
+    person_id,
+    pending: false,
+  };
+  blocking(context.pool(), move |conn: &'_ _| {
+    CommunityFollower::unfollow(conn, &community_follower_form)
+  })
+  .await?
+  .ok();
+  let community = blocking(context.pool(), move |conn| {
+    Community::read(conn, community_id)
+  })
+  .await??;
+  UndoFollowCommunity::send(&person.clone().into(), &community.into(), context).await?;
+  Ok(())
+}
+
 #[async_trait::async_trait(?Send)]
 impl Perform for BanFromCommunity {
   type Response = BanFromCommunityResponse;
@@ -218,9 +254,11 @@ impl Perform for BanFromCommunity {
     // Verify that only mods or admins can ban
     is_mod_or_admin(context.pool(), local_user_view.person.id, community_id).await?;
 
+    let expires = data.expires.map(naive_from_unix);
     let community_user_ban_form = CommunityPersonBanForm {
       community_id: data.community_id,
       person_id: data.person_id,
+      expires,
     };
 
     let community: ApubCommunity = blocking(context.pool(), move |conn: &'_ _| {
@@ -257,6 +295,8 @@ impl Perform for BanFromCommunity {
         &community,
         &banned_person,
         &local_user_view.person.clone().into(),
+        data.reason.clone(),
+        expires,
         context,
       )
       .await?;
@@ -270,6 +310,7 @@ impl Perform for BanFromCommunity {
         &community,
         &banned_person,
         &local_user_view.person.clone().into(),
+        data.reason.clone(),
         context,
       )
       .await?;
@@ -304,9 +345,6 @@ impl Perform for BanFromCommunity {
     }
 
     // Mod tables
-    // TODO eventually do correct expires
-    let expires = data.expires.map(naive_from_unix);
-
     let form = ModBanFromCommunityForm {
       mod_person_id: local_user_view.person.id,
       other_person_id: data.person_id,
@@ -314,6 +352,7 @@ impl Perform for BanFromCommunity {
       reason: data.reason.to_owned(),
       banned: Some(data.ban),
       expires,
+      public: data.public_modlog,
     };
     blocking(context.pool(), move |conn| {
       ModBanFromCommunity::create(conn, &form)
@@ -342,6 +381,98 @@ impl Perform for BanFromCommunity {
   }
 }
 
+/// Lifts all community bans whose expiry time has passed, as if the mod who issued the ban had
+/// unbanned the person.
+#[tracing::instrument(skip_all)]
+pub async fn lift_expired_community_bans(
+  context: &LemmyContext,
+  clock: &dyn Clock,
+) -> Result<(), LemmyError> {
+  let now = clock.now();
+  let expired = blocking(context.pool(), move |conn| {
+    CommunityPersonBan::list_expired(conn, now)
+  })
+  .await??;
+  for ban in expired {
+    let ban_id = ban.id;
+    if let Err(e) = lift_expired_community_ban(ban, context).await {
+      warn!("Failed to lift community ban {}: {}", ban_id, e);
+    }
+  }
+  Ok(())
+}
+
+async fn lift_expired_community_ban(
+  ban: CommunityPersonBan,
+  context: &LemmyContext,
+) -> Result<(), LemmyError> {
+  let person_id = ban.person_id;
+  let community_id = ban.community_id;
+  let form = CommunityPersonBanForm {
+    community_id,
+    person_id,
+    expires: None,
+  };
+  blocking(context.pool(), move |conn| {
+    CommunityPersonBan::unban(conn, &form)
+  })
+  .await??;
+
+  // Without a mod log entry there is nobody to attribute the unban to, eg if the ban was
+  // received before expiry times were stored
+  let ban_log = blocking(context.pool(), move |conn| {
+    ModBanFromCommunity::read_latest_ban(conn, person_id, community_id)
+  })
+  .await?
+  .ok();
+  let ban_log = match ban_log {
+    Some(b) => b,
+    None => return Ok(()),
+  };
+  let mod_person_id = ban_log.mod_person_id;
+  let form = ModBanFromCommunityForm {
+    mod_person_id,
+    other_person_id: person_id,
+    community_id,
+    reason: Some(BAN_EXPIRED_REASON.to_string()),
+    banned: Some(false),
+    expires: None,
+    public: None,
+  };
+  blocking(context.pool(), move |conn| {
+    ModBanFromCommunity::create(conn, &form)
+  })
+  .await??;
+
+  // Remote communities lift the ban on their own instance and federate it from there. The Undo
+  // has to be signed by the mod, so it can only be sent if they are local as well.
+  let community: ApubCommunity = blocking(context.pool(), move |conn| {
+    Community::read(conn, community_id)
+  })
+  .await??
+  .into();
+  let mod_person: ApubPerson = blocking(context.pool(), move |conn| {
+    Person::read(conn, mod_person_id)
+  })
+  .await??
+  .into();
+  if community.local && mod_person.local {
+    let banned_person: ApubPerson =
+      blocking(context.pool(), move |conn| Person::read(conn, person_id))
+        .await??
+        .into();
+    UndoBlockUserFromCommunity::send(
+      &community,
+      &banned_person,
+      &mod_person,
+      Some(BAN_EXPIRED_REASON.to_string()),
+      context,
+    )
+    .await?;
+  }
+  Ok(())
+}
+
 #[async_trait::async_trait(?Send)]
 impl Perform for AddModToCommunity {
   type Response = AddModToCommunityResponse;
@@ -474,7 +605,7 @@ impl Perform for TransferCommunity {
 
     // Fetch the community mods
     let community_id = data.community_id;
-    let mut community_mods = blocking(context.pool(), move |conn| {
+    let community_mods = blocking(context.pool(), move |conn| {
       CommunityModeratorView::for_community(conn, community_id)
     })
     .await??;
@@ -489,36 +620,15 @@ impl Perform for TransferCommunity {
       return Err(LemmyError::from_message("not_an_admin"));
     }
 
-    // You have to re-do the community_moderator table, reordering it.
-    // Add the transferee to the top
-    let creator_index = community_mods
-      .iter()
-      .position(|r| r.moderator.id == data.person_id)
-      .context(location_info!())?;
-    let creator_person = community_mods.remove(creator_index);
-    community_mods.insert(0, creator_person);
-
-    // Delete all the mods
+    // Move the transferee to the top of the mod list
     let community_id = data.community_id;
+    let person_id = data.person_id;
     blocking(context.pool(), move |conn| {
-      CommunityModerator::delete_for_community(conn, community_id)
+      CommunityModerator::set_top_moderator(conn, community_id, person_id)
     })
-    .await??;
-
-    // TODO: this should probably be a bulk operation
-    // Re-add the mods, in the new order
-    for cmod in &community_mods {
-      let community_moderator_form = CommunityModeratorForm {
-        community_id: cmod.community.id,
-        person_id: cmod.moderator.id,
-      };
-
-      let join = move |conn: &'_ _| CommunityModerator::join(conn, &community_moderator_form);
-      blocking(context.pool(), join)
-        .await?
-        .map_err(LemmyError::from)
-        .map_err(|e| e.with_message("community_moderator_already_exists"))?;
-    }
+    .await?
+    .map_err(LemmyError::from)
+    .map_err(|e| e.with_message("couldnt_update_community"))?;
 
     // Mod tables
     let form = ModTransferCommunityForm {
@@ -549,11 +659,206 @@ impl Perform for TransferCommunity {
     .map_err(LemmyError::from)
     .map_err(|e| e.with_message("couldnt_find_community"))?;
 
+    let community_id = data.community_id;
+    let discussion_languages = blocking(context.pool(), move |conn| {
+      CommunityLanguage::read(conn, community_id)
+    })
+    .await??;
+
     // Return the jwt
     Ok(GetCommunityResponse {
       community_view,
       moderators,
       online: 0,
+      discussion_languages,
+    })
+  }
+}
+
+#[async_trait::async_trait(?Send)]
+impl Perform for SetCommunityCategory {
+  type Response = CommunityResponse;
+
+  #[tracing::instrument(skip(context, websocket_id))]
+  async fn perform(
+    &self,
+    context: &Data<LemmyContext>,
+    websocket_id: Option<ConnectionId>,
+  ) -> Result<CommunityResponse, LemmyError> {
+    let data: &SetCommunityCategory = self;
+    let local_user_view =
+      get_local_user_view_from_jwt(&data.auth, context.pool(), context.secret()).await?;
+
+    let community_id = data.community_id;
+    is_mod_or_admin(context.pool(), local_user_view.person.id, community_id).await?;
+
+    // Categories are local to this instance, so nothing is federated
+    let category_id = data.category_id;
+    blocking(context.pool(), move |conn| {
+      CommunityCategory::assign(conn, community_id, category_id)
+    })
+    .await?
+    .map_err(LemmyError::from)
+    .map_err(|e| e.with_message("couldnt_update_community"))?;
+
+    send_community_ws_message(
+      community_id,
+      UserOperation::SetCommunityCategory,
+      websocket_id,
+      Some(local_user_view.person.id),
+      context,
+    )
+    .await
+  }
+}
+
+#[async_trait::async_trait(?Send)]
+impl Perform for ListPendingDeletions {
+  type Response = ListPendingDeletionsResponse;
+
+  #[tracing::instrument(skip(context, _websocket_id))]
+  async fn perform(
+    &self,
+    context: &Data<LemmyContext>,
+    _websocket_id: Option<ConnectionId>,
+  ) -> Result<ListPendingDeletionsResponse, LemmyError> {
+    let data: &ListPendingDeletions = self;
+    let local_user_view =
+      get_local_user_view_from_jwt(&data.auth, context.pool(), context.secret()).await?;
+
+    let community_id = data.community_id;
+    if let Some(community_id) = community_id {
+      is_mod_or_admin(context.pool(), local_user_view.person.id, community_id).await?;
+    } else {
+      is_admin(&local_user_view)?;
+    }
+
+    let page = data.page;
+    let limit = data.limit;
+    let pending_deletions = blocking(context.pool(), move |conn| {
+      PendingDeletion::list(conn, community_id, page, limit)
+    })
+    .await??;
+
+    Ok(ListPendingDeletionsResponse { pending_deletions })
+  }
+}
+
+#[async_trait::async_trait(?Send)]
+impl Perform for ResolvePendingDeletion {
+  type Response = ResolvePendingDeletionResponse;
+
+  #[tracing::instrument(skip(context, _websocket_id))]
+  async fn perform(
+    &self,
+    context: &Data<LemmyContext>,
+    _websocket_id: Option<ConnectionId>,
+  ) -> Result<ResolvePendingDeletionResponse, LemmyError> {
+    let data: &ResolvePendingDeletion = self;
+    let local_user_view =
+      get_local_user_view_from_jwt(&data.auth, context.pool(), context.secret()).await?;
+
+    let id = data.id;
+    let pending_deletion = blocking(context.pool(), move |conn| PendingDeletion::read(conn, id))
+      .await?
+      .map_err(LemmyError::from)
+      .map_err(|e| e.with_message("couldnt_find_pending_deletion"))?;
+
+    is_mod_or_admin(
+      context.pool(),
+      local_user_view.person.id,
+      pending_deletion.community_id,
+    )
+    .await?;
+
+    if data.approve {
+      apply_pending_deletion(pending_deletion.clone(), context).await?;
+    }
+
+    blocking(context.pool(), move |conn| {
+      PendingDeletion::delete(conn, id)
+    })
+    .await??;
+
+    Ok(ResolvePendingDeletionResponse { pending_deletion })
+  }
+}
+
+/// Remote communities are fetched again for previews at most this often, so that they dont get
+/// flooded with requests
+const PREVIEW_REFETCH_INTERVAL_MINUTES: i64 = 60;
+/// The same as the number of items which Lemmy puts in the community outbox
+const PREVIEW_POSTS_LIMIT: i64 = 20;
+
+#[async_trait::async_trait(?Send)]
+impl Perform for PreviewCommunity {
+  type Response = PreviewCommunityResponse;
+
+  #[tracing::instrument(skip(context, _websocket_id))]
+  async fn perform(
+    &self,
+    context: &Data<LemmyContext>,
+    _websocket_id: Option<ConnectionId>,
+  ) -> Result<PreviewCommunityResponse, LemmyError> {
+    let data: &PreviewCommunity = self;
+    let local_user_view =
+      get_local_user_view_from_jwt_opt(data.auth.as_ref(), context.pool(), context.secret())
+        .await?;
+    check_private_instance(&local_user_view, context.pool()).await?;
+
+    let mut community = match search_by_apub_id(&data.q, context).await {
+      Ok(SearchableObjects::Community(c)) => c,
+      _ => return Err(LemmyError::from_message("couldnt_find_community")),
+    };
+
+    // New posts are only pushed to instances with followers, otherwise they are only in the
+    // outbox. It gets fetched together with the community.
+    let refetch_before = naive_now() - chrono::Duration::minutes(PREVIEW_REFETCH_INTERVAL_MINUTES);
+    if !community.local && community.last_refreshed_at < refetch_before {
+      match ObjectId::<ApubCommunity>::new(community.actor_id.clone())
+        .dereference_forced(context, context.client(), &mut 0)
+        .await
+      {
+        Ok(c) => community = c,
+        Err(e) => warn!("Failed to refetch community {}: {}", community.actor_id, e),
+      }
+    }
+    if community.deleted || community.removed {
+      return Err(LemmyError::from_message("couldnt_find_community"));
+    }
+
+    let person_id = local_user_view.as_ref().map(|l| l.person.id);
+    let show_nsfw = local_user_view.as_ref().map(|l| l.local_user.show_nsfw);
+    let show_bot_accounts = local_user_view
+      .as_ref()
+      .map(|l| l.local_user.show_bot_accounts);
+    let show_scores = local_user_view.as_ref().map(|l| l.local_user.show_scores);
+    let community_id = community.id;
+    let posts = blocking(context.pool(), move |conn| {
+      PostQueryBuilder::create(conn)
+        .sort(SortType::New)
+        .show_nsfw(show_nsfw)
+        .show_bot_accounts(show_bot_accounts)
+        .show_scores(show_scores)
+        .community_id(community_id)
+        .my_person_id(person_id)
+        .limit(PREVIEW_POSTS_LIMIT)
+        .list()
+    })
+    .await?
+    .map_err(LemmyError::from)
+    .map_err(|e| e.with_message("couldnt_get_posts"))?;
+
+    let community_view = blocking(context.pool(), move |conn| {
+      CommunityView::read(conn, community_id, person_id)
+    })
+    .await?
+    .map_err(LemmyError::from)
+    .map_err(|e| e.with_message("couldnt_find_community"))?;
+
+    Ok(PreviewCommunityResponse {
+      community_view,
+      posts,
     })
   }
 }
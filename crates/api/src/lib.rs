@@ -1,6 +1,14 @@
 use actix_web::{web, web::Data};
-use captcha::Captcha;
-use lemmy_api_common::{comment::*, community::*, person::*, post::*, site::*, websocket::*};
+use lemmy_api_common::{
+  comment::*,
+  community::*,
+  person::*,
+  post::*,
+  site::*,
+  token_scope::with_required_scope,
+  websocket::*,
+};
+use lemmy_db_schema::TokenScope;
 use lemmy_utils::{ConnectionId, LemmyError};
 use lemmy_websocket::{serialize_websocket_message, LemmyContext, UserOperation};
 use serde::Deserialize;
@@ -12,9 +20,17 @@ mod local_user;
 mod post;
 mod post_report;
 mod private_message;
+mod purge;
 mod site;
 mod websocket;
 
+pub use community::lift_expired_community_bans;
+pub use local_user::lift_expired_site_bans;
+pub use site::update_instance_software;
+
+/// Mod log reason for bans which are lifted automatically
+const BAN_EXPIRED_REASON: &str = "Ban expired";
+
 #[async_trait::async_trait(?Send)]
 pub trait Perform {
   type Response: serde::ser::Serialize + Send;
@@ -47,10 +63,89 @@ pub async fn match_websocket_operation(
     UserOperation::ApproveRegistrationApplication => {
       do_websocket_operation::<ApproveRegistrationApplication>(context, id, op, data).await
     }
+    UserOperation::ListReceivedDeletions => {
+      do_websocket_operation::<ListReceivedDeletions>(context, id, op, data).await
+    }
+    UserOperation::GetFederatedInstances => {
+      do_websocket_operation::<GetFederatedInstances>(context, id, op, data).await
+    }
+    UserOperation::ListFederationSettings => {
+      do_websocket_operation::<ListFederationSettings>(context, id, op, data).await
+    }
+    UserOperation::AddBlockedInstance => {
+      do_websocket_operation::<AddBlockedInstance>(context, id, op, data).await
+    }
+    UserOperation::RemoveBlockedInstance => {
+      do_websocket_operation::<RemoveBlockedInstance>(context, id, op, data).await
+    }
+    UserOperation::AddAllowedInstance => {
+      do_websocket_operation::<AddAllowedInstance>(context, id, op, data).await
+    }
+    UserOperation::RemoveAllowedInstance => {
+      do_websocket_operation::<RemoveAllowedInstance>(context, id, op, data).await
+    }
+    UserOperation::ListWordFilters => {
+      do_websocket_operation::<ListWordFilters>(context, id, op, data).await
+    }
+    UserOperation::AddWordFilter => {
+      do_websocket_operation::<AddWordFilter>(context, id, op, data).await
+    }
+    UserOperation::PurgePerson => {
+      do_websocket_operation::<PurgePerson>(context, id, op, data).await
+    }
+    UserOperation::PurgeCommunity => {
+      do_websocket_operation::<PurgeCommunity>(context, id, op, data).await
+    }
+    UserOperation::PurgePost => do_websocket_operation::<PurgePost>(context, id, op, data).await,
+    UserOperation::PurgeComment => {
+      do_websocket_operation::<PurgeComment>(context, id, op, data).await
+    }
+    UserOperation::RemoveWordFilter => {
+      do_websocket_operation::<RemoveWordFilter>(context, id, op, data).await
+    }
+    UserOperation::ListLegalHolds => {
+      do_websocket_operation::<ListLegalHolds>(context, id, op, data).await
+    }
+    UserOperation::AddLegalHold => {
+      do_websocket_operation::<AddLegalHold>(context, id, op, data).await
+    }
+    UserOperation::RemoveLegalHold => {
+      do_websocket_operation::<RemoveLegalHold>(context, id, op, data).await
+    }
     UserOperation::BanPerson => do_websocket_operation::<BanPerson>(context, id, op, data).await,
+    UserOperation::RemoveContentAndBan => {
+      do_websocket_operation::<RemoveContentAndBan>(context, id, op, data).await
+    }
     UserOperation::BlockPerson => {
       do_websocket_operation::<BlockPerson>(context, id, op, data).await
     }
+    UserOperation::BlockInstance => {
+      do_websocket_operation::<BlockInstance>(context, id, op, data).await
+    }
+    UserOperation::MoveSaved => do_websocket_operation::<MoveSaved>(context, id, op, data).await,
+    UserOperation::GetSaved => do_websocket_operation::<GetSaved>(context, id, op, data).await,
+    UserOperation::GetPersonStats => {
+      do_websocket_operation::<GetPersonStats>(context, id, op, data).await
+    }
+    UserOperation::GenerateTotpSecret => {
+      do_websocket_operation::<GenerateTotpSecret>(context, id, op, data).await
+    }
+    UserOperation::UpdateTotp => do_websocket_operation::<UpdateTotp>(context, id, op, data).await,
+    UserOperation::ExportAccountData => {
+      do_websocket_operation::<ExportAccountData>(context, id, op, data).await
+    }
+    UserOperation::GetAccountExport => {
+      do_websocket_operation::<GetAccountExport>(context, id, op, data).await
+    }
+    UserOperation::ImportAccountData => {
+      do_websocket_operation::<ImportAccountData>(context, id, op, data).await
+    }
+    UserOperation::RegisterPushSubscription => {
+      do_websocket_operation::<RegisterPushSubscription>(context, id, op, data).await
+    }
+    UserOperation::DeletePushSubscription => {
+      do_websocket_operation::<DeletePushSubscription>(context, id, op, data).await
+    }
     UserOperation::GetPersonMentions => {
       do_websocket_operation::<GetPersonMentions>(context, id, op, data).await
     }
@@ -81,12 +176,39 @@ pub async fn match_websocket_operation(
     UserOperation::GetReportCount => {
       do_websocket_operation::<GetReportCount>(context, id, op, data).await
     }
+    UserOperation::BulkResolveReports => {
+      do_websocket_operation::<BulkResolveReports>(context, id, op, data).await
+    }
     UserOperation::GetUnreadCount => {
       do_websocket_operation::<GetUnreadCount>(context, id, op, data).await
     }
     UserOperation::VerifyEmail => {
       do_websocket_operation::<VerifyEmail>(context, id, op, data).await
     }
+    UserOperation::ResendVerificationEmail => {
+      do_websocket_operation::<ResendVerificationEmail>(context, id, op, data).await
+    }
+    UserOperation::CreateUserToken => {
+      do_websocket_operation::<CreateUserToken>(context, id, op, data).await
+    }
+    UserOperation::ListUserTokens => {
+      do_websocket_operation::<ListUserTokens>(context, id, op, data).await
+    }
+    UserOperation::RevokeUserToken => {
+      do_websocket_operation::<RevokeUserToken>(context, id, op, data).await
+    }
+    UserOperation::GenerateFeedToken => {
+      do_websocket_operation::<GenerateFeedToken>(context, id, op, data).await
+    }
+    UserOperation::ListSessions => {
+      do_websocket_operation::<ListSessions>(context, id, op, data).await
+    }
+    UserOperation::RevokeSession => {
+      do_websocket_operation::<RevokeSession>(context, id, op, data).await
+    }
+    UserOperation::RevokeAllSessions => {
+      do_websocket_operation::<RevokeAllSessions>(context, id, op, data).await
+    }
 
     // Private Message ops
     UserOperation::MarkPrivateMessageAsRead => {
@@ -102,12 +224,18 @@ pub async fn match_websocket_operation(
       do_websocket_operation::<SaveSiteConfig>(context, id, op, data).await
     }
     UserOperation::Search => do_websocket_operation::<Search>(context, id, op, data).await,
+    UserOperation::MentionAutocomplete => {
+      do_websocket_operation::<MentionAutocomplete>(context, id, op, data).await
+    }
     UserOperation::ResolveObject => {
       do_websocket_operation::<ResolveObject>(context, id, op, data).await
     }
     UserOperation::TransferCommunity => {
       do_websocket_operation::<TransferCommunity>(context, id, op, data).await
     }
+    UserOperation::SetCommunityCategory => {
+      do_websocket_operation::<SetCommunityCategory>(context, id, op, data).await
+    }
     UserOperation::TransferSite => {
       do_websocket_operation::<TransferSite>(context, id, op, data).await
     }
@@ -119,23 +247,47 @@ pub async fn match_websocket_operation(
     UserOperation::BlockCommunity => {
       do_websocket_operation::<BlockCommunity>(context, id, op, data).await
     }
+    UserOperation::PreviewCommunity => {
+      do_websocket_operation::<PreviewCommunity>(context, id, op, data).await
+    }
     UserOperation::BanFromCommunity => {
       do_websocket_operation::<BanFromCommunity>(context, id, op, data).await
     }
+    UserOperation::ListPendingDeletions => {
+      do_websocket_operation::<ListPendingDeletions>(context, id, op, data).await
+    }
+    UserOperation::ResolvePendingDeletion => {
+      do_websocket_operation::<ResolvePendingDeletion>(context, id, op, data).await
+    }
     UserOperation::AddModToCommunity => {
       do_websocket_operation::<AddModToCommunity>(context, id, op, data).await
     }
 
     // Post ops
     UserOperation::LockPost => do_websocket_operation::<LockPost>(context, id, op, data).await,
+    UserOperation::ShadowRemovePost => {
+      do_websocket_operation::<ShadowRemovePost>(context, id, op, data).await
+    }
     UserOperation::StickyPost => do_websocket_operation::<StickyPost>(context, id, op, data).await,
+    UserOperation::ListPendingPosts => {
+      do_websocket_operation::<ListPendingPosts>(context, id, op, data).await
+    }
+    UserOperation::ApprovePost => {
+      do_websocket_operation::<ApprovePost>(context, id, op, data).await
+    }
+    UserOperation::RejectPost => do_websocket_operation::<RejectPost>(context, id, op, data).await,
     UserOperation::CreatePostLike => {
       do_websocket_operation::<CreatePostLike>(context, id, op, data).await
     }
+    UserOperation::ReactPost => do_websocket_operation::<ReactPost>(context, id, op, data).await,
+    UserOperation::UnreactPost => {
+      do_websocket_operation::<UnreactPost>(context, id, op, data).await
+    }
     UserOperation::MarkPostAsRead => {
       do_websocket_operation::<MarkPostAsRead>(context, id, op, data).await
     }
     UserOperation::SavePost => do_websocket_operation::<SavePost>(context, id, op, data).await,
+    UserOperation::VotePoll => do_websocket_operation::<VotePoll>(context, id, op, data).await,
     UserOperation::CreatePostReport => {
       do_websocket_operation::<CreatePostReport>(context, id, op, data).await
     }
@@ -145,6 +297,9 @@ pub async fn match_websocket_operation(
     UserOperation::ResolvePostReport => {
       do_websocket_operation::<ResolvePostReport>(context, id, op, data).await
     }
+    UserOperation::EscalatePostReport => {
+      do_websocket_operation::<EscalatePostReport>(context, id, op, data).await
+    }
     UserOperation::GetSiteMetadata => {
       do_websocket_operation::<GetSiteMetadata>(context, id, op, data).await
     }
@@ -159,6 +314,12 @@ pub async fn match_websocket_operation(
     UserOperation::CreateCommentLike => {
       do_websocket_operation::<CreateCommentLike>(context, id, op, data).await
     }
+    UserOperation::ReactComment => {
+      do_websocket_operation::<ReactComment>(context, id, op, data).await
+    }
+    UserOperation::UnreactComment => {
+      do_websocket_operation::<UnreactComment>(context, id, op, data).await
+    }
     UserOperation::CreateCommentReport => {
       do_websocket_operation::<CreateCommentReport>(context, id, op, data).await
     }
@@ -168,6 +329,9 @@ pub async fn match_websocket_operation(
     UserOperation::ResolveCommentReport => {
       do_websocket_operation::<ResolveCommentReport>(context, id, op, data).await
     }
+    UserOperation::EscalateCommentReport => {
+      do_websocket_operation::<EscalateCommentReport>(context, id, op, data).await
+    }
   }
 }
 
@@ -182,32 +346,27 @@ where
   Data: Perform,
 {
   let parsed_data: Data = serde_json::from_str(data)?;
-  let res = parsed_data
-    .perform(&web::Data::new(context), Some(id))
-    .await?;
+  let res = with_required_scope(
+    op.access(),
+    TokenScope::Write,
+    parsed_data.perform(&web::Data::new(context), Some(id)),
+  )
+  .await?;
   serialize_websocket_message(&op, &res)
 }
 
-/// Converts the captcha to a base64 encoded wav audio file
-pub(crate) fn captcha_as_wav_base64(captcha: &Captcha) -> String {
-  let letters = captcha.as_wav();
-
-  let mut concat_letters: Vec<u8> = Vec::new();
-
-  for letter in letters {
-    let bytes = letter.unwrap_or_default();
-    concat_letters.extend(bytes);
-  }
-
-  // Convert to base64
-  base64::encode(concat_letters)
-}
-
 #[cfg(test)]
-mod tests {
+pub(crate) mod tests {
+  use actix::Actor;
+  use diesel::{
+    r2d2::{ConnectionManager, Pool},
+    PgConnection,
+  };
   use lemmy_api_common::check_validator_time;
+  use lemmy_apub_lib::activity_queue::create_activity_queue;
   use lemmy_db_schema::{
     establish_unpooled_connection,
+    get_database_url_from_env,
     source::{
       local_user::{LocalUser, LocalUserForm},
       person::{Person, PersonForm},
@@ -215,7 +374,66 @@ mod tests {
     },
     traits::Crud,
   };
-  use lemmy_utils::{claims::Claims, settings::structs::Settings};
+  use lemmy_utils::{
+    claims::Claims,
+    rate_limit::{rate_limiter::RateLimiter, RateLimit},
+    request::build_user_agent,
+    settings::structs::Settings,
+    LemmyError,
+  };
+  use lemmy_websocket::{chat_server::ChatServer, LemmyContext};
+  use reqwest::Client;
+  use reqwest_middleware::ClientBuilder;
+  use std::sync::Arc;
+  use tokio::sync::Mutex;
+
+  /// Context for calling the handlers, which doesn't federate anything
+  pub(crate) fn init_context() -> LemmyContext {
+    // call this to run migrations
+    establish_unpooled_connection();
+    let settings = Settings::init().unwrap();
+    let rate_limiter = RateLimit {
+      rate_limiter: Arc::new(Mutex::new(RateLimiter::default())),
+      rate_limit_config: settings.rate_limit.to_owned().unwrap_or_default(),
+    };
+    let client = Client::builder()
+      .user_agent(build_user_agent(&settings))
+      .build()
+      .unwrap();
+    let client = ClientBuilder::new(client).build();
+    let activity_queue = create_activity_queue(client.clone()).queue_handle().clone();
+    let secret = Secret {
+      id: 0,
+      jwt_secret: "".to_string(),
+      instance_private_key: None,
+      instance_public_key: None,
+    };
+    let db_url = match get_database_url_from_env() {
+      Ok(url) => url,
+      Err(_) => settings.get_database_url(),
+    };
+    let manager = ConnectionManager::<PgConnection>::new(&db_url);
+    let pool = Pool::builder()
+      .max_size(settings.database.pool_size)
+      .build(manager)
+      .unwrap_or_else(|_| panic!("Error connecting to {}", db_url));
+    async fn x() -> Result<String, LemmyError> {
+      Ok("".to_string())
+    }
+    let chat_server = ChatServer::startup(
+      pool.clone(),
+      rate_limiter,
+      |_, _, _, _| Box::pin(x()),
+      |_, _, _, _| Box::pin(x()),
+      client.clone(),
+      activity_queue.clone(),
+      settings.clone(),
+      secret.clone(),
+      None,
+    )
+    .start();
+    LemmyContext::create(pool, chat_server, client, activity_queue, settings, secret)
+  }
 
   #[test]
   fn test_should_not_validate_user_token_after_password_change() {
@@ -240,6 +458,7 @@ mod tests {
 
     let jwt = Claims::jwt(
       inserted_local_user.id.0,
+      0,
       &secret.jwt_secret,
       &settings.hostname,
     )
@@ -1,66 +1,122 @@
-use crate::{captcha_as_wav_base64, Perform};
+use crate::{community::block_community, Perform, BAN_EXPIRED_REASON};
 use actix_web::web::Data;
 use anyhow::Context;
 use bcrypt::verify;
-use captcha::{gen, Difficulty};
 use chrono::Duration;
+use diesel::{Connection, OptionalExtension};
 use lemmy_api_common::{
   blocking,
+  captcha::captcha_provider,
+  check_community_ban,
+  check_community_deleted_or_removed,
+  check_private_instance,
   check_registration_application,
+  check_removal_reason,
+  check_resolution_note,
+  check_saved_collection_owner,
+  create_login_session,
   get_local_user_view_from_jwt,
+  get_local_user_view_from_jwt_opt,
   is_admin,
+  is_mod_or_admin,
   password_length_check,
   person::*,
   send_email_verification_success,
   send_password_reset_email,
   send_verification_email,
+  token_scope::{check_login_session, PERSONAL_ACCESS_TOKEN_PREFIX},
 };
+use lemmy_apub::{
+  objects::{community::ApubCommunity, person::ApubPerson, post::ApubPost},
+  protocol::activities::{
+    community::{block_user::BlockUserFromCommunity, remove_user_content::RemoveUserContent},
+    following::follow::FollowCommunity as FollowCommunityApub,
+  },
+};
+use lemmy_apub_lib::object_id::ObjectId;
 use lemmy_db_schema::{
+  clock::Clock,
   diesel_option_overwrite,
   diesel_option_overwrite_to_url,
   from_opt_str_to_opt_enum,
   naive_now,
+  newtypes::{CommunityId, LocalUserId, PostId},
   source::{
-    comment::Comment,
-    community::Community,
+    account_export::{AccountExport, AccountExportForm},
+    comment::{Comment, CommentSaved},
+    comment_report::CommentReport,
+    community::{
+      Community,
+      CommunityFollower,
+      CommunityFollowerForm,
+      CommunityPersonBan,
+      CommunityPersonBanForm,
+    },
     email_verification::EmailVerification,
+    feed_token::FeedToken,
+    language::LocalUserLanguage,
     local_user::{LocalUser, LocalUserForm},
+    login_session::LoginSession,
     moderator::*,
     password_reset_request::*,
     person::*,
     person_block::{PersonBlock, PersonBlockForm},
+    person_field::{PersonField, PersonFieldForm, MAX_PERSON_FIELDS},
+    person_instance_block::{PersonInstanceBlock, PersonInstanceBlockForm},
     person_mention::*,
-    post::Post,
+    post::{Post, PostSaved, PostSavedForm},
+    post_report::PostReport,
     private_message::PrivateMessage,
+    push_subscription::{PushSubscription, PushSubscriptionForm},
     site::*,
+    user_token::UserToken,
   },
-  traits::{Blockable, Crud},
+  traits::{Bannable, Blockable, Crud, Followable, Saveable},
+  DbPool,
+  DigestFrequency,
   SortType,
 };
 use lemmy_db_views::{
+  account_archive_view::AccountArchiveView,
   comment_report_view::CommentReportView,
   comment_view::{CommentQueryBuilder, CommentView},
   local_user_view::LocalUserView,
   post_report_view::PostReportView,
+  post_view::PostQueryBuilder,
   private_message_view::PrivateMessageView,
 };
 use lemmy_db_views_actor::{
   community_moderator_view::CommunityModeratorView,
+  community_person_ban_view::CommunityPersonBanView,
   person_mention_view::{PersonMentionQueryBuilder, PersonMentionView},
+  person_stats_view::PersonStatsView,
   person_view::PersonViewSafe,
 };
 use lemmy_utils::{
   claims::Claims,
   location_info,
-  utils::{is_valid_display_name, is_valid_matrix_id, naive_from_unix},
+  totp::{build_totp_url, check_totp_token, generate_totp_secret, is_totp_locked},
+  utils::{
+    check_slurs,
+    check_slurs_opt,
+    generate_random_string,
+    is_valid_display_name,
+    is_valid_matrix_id,
+    naive_from_unix,
+  },
+  web_push::{check_push_endpoint, encrypt_payload},
   ConnectionId,
   LemmyError,
 };
 use lemmy_websocket::{
-  messages::{CaptchaItem, SendAllMessage},
+  messages::{SendAllMessage, SendCommunityRoomMessage, SendModRoomMessage, SendUserRoomMessage},
   LemmyContext,
   UserOperation,
 };
+use serde::Deserialize;
+use std::{collections::HashSet, sync::Mutex};
+use tracing::warn;
+use url::Url;
 
 #[async_trait::async_trait(?Send)]
 impl Perform for Login {
@@ -93,22 +149,32 @@ impl Perform for Login {
       return Err(LemmyError::from_message("password_incorrect"));
     }
 
+    let local_user = &local_user_view.local_user;
+    if local_user.totp_enabled {
+      let token = data
+        .totp_token
+        .as_ref()
+        .ok_or_else(|| LemmyError::from_message("missing_totp_token"))?;
+      check_totp(local_user, token, context.pool()).await?;
+    }
+
     let site = blocking(context.pool(), Site::read_simple).await??;
+    check_registration_application(&site, &local_user_view, context.pool()).await?;
+
     if site.require_email_verification && !local_user_view.local_user.email_verified {
       return Err(LemmyError::from_message("email_not_verified"));
     }
 
-    check_registration_application(&site, &local_user_view, context.pool()).await?;
-
     // Return the jwt
     Ok(LoginResponse {
       jwt: Some(
-        Claims::jwt(
-          local_user_view.local_user.id.0,
-          &context.secret().jwt_secret,
-          &context.settings().hostname,
-        )?
-        .into(),
+        create_login_session(
+          local_user_view.local_user.id,
+          context.pool(),
+          context.secret(),
+          &context.settings(),
+        )
+        .await?,
       ),
       verify_email_sent: false,
       registration_created: false,
@@ -126,39 +192,14 @@ impl Perform for GetCaptcha {
     context: &Data<LemmyContext>,
     _websocket_id: Option<ConnectionId>,
   ) -> Result<Self::Response, LemmyError> {
-    let captcha_settings = context.settings().captcha;
-
-    if !captcha_settings.enabled {
-      return Ok(GetCaptchaResponse { ok: None });
+    let site = blocking(context.pool(), Site::read_simple).await??;
+    match captcha_provider(&site, &context.settings(), context.client())? {
+      Some(provider) => provider.generate(context.pool()).await,
+      None => Ok(GetCaptchaResponse {
+        ok: None,
+        external: None,
+      }),
     }
-
-    let captcha = match captcha_settings.difficulty.as_str() {
-      "easy" => gen(Difficulty::Easy),
-      "medium" => gen(Difficulty::Medium),
-      "hard" => gen(Difficulty::Hard),
-      _ => gen(Difficulty::Medium),
-    };
-
-    let answer = captcha.chars_as_string();
-
-    let png = captcha.as_base64().expect("failed to generate captcha");
-
-    let uuid = uuid::Uuid::new_v4().to_string();
-
-    let wav = captcha_as_wav_base64(&captcha);
-
-    let captcha_item = CaptchaItem {
-      answer,
-      uuid: uuid.to_owned(),
-      expires: naive_now() + Duration::minutes(10), // expires in 10 minutes
-    };
-
-    // Stores the captcha item on the queue
-    context.chat_server().do_send(captcha_item);
-
-    Ok(GetCaptchaResponse {
-      ok: Some(CaptchaResponse { png, wav, uuid }),
-    })
   }
 }
 
@@ -184,6 +225,10 @@ impl Perform for SaveUserSettings {
     let bot_account = data.bot_account;
     let email_deref = data.email.as_deref().map(|e| e.to_owned());
     let email = diesel_option_overwrite(&email_deref);
+    // With a changed email, the password could be reset
+    if email.is_some() {
+      check_login_session(&data.auth)?;
+    }
 
     if let Some(Some(email)) = &email {
       let previous_email = local_user_view.local_user.email.unwrap_or_default();
@@ -229,6 +274,30 @@ impl Perform for SaveUserSettings {
       }
     }
 
+    if let Some(fields) = &data.fields {
+      if fields.len() > MAX_PERSON_FIELDS {
+        return Err(LemmyError::from_message("too_many_profile_fields"));
+      }
+      for field in fields {
+        let name_len = field.name.trim().chars().count();
+        if name_len == 0 || name_len > 50 || field.value.chars().count() > 300 {
+          return Err(LemmyError::from_message("invalid_profile_field"));
+        }
+        check_slurs(&field.name, &context.settings().slur_regex())?;
+        check_slurs(&field.value, &context.settings().slur_regex())?;
+      }
+    }
+
+    let email_digest_frequency = match &data.email_digest_frequency {
+      Some(frequency) => Some(
+        frequency
+          .parse::<DigestFrequency>()
+          .map_err(|_| LemmyError::from_message("invalid_email_digest_frequency"))?
+          .to_string(),
+      ),
+      None => None,
+    };
+
     let local_user_id = local_user_view.local_user.id;
     let person_id = local_user_view.person.id;
     let default_listing_type = data.default_listing_type;
@@ -265,6 +334,30 @@ impl Perform for SaveUserSettings {
     .map_err(LemmyError::from)
     .map_err(|e| e.with_message("user_already_exists"))?;
 
+    if let Some(fields) = &data.fields {
+      let field_forms = fields
+        .iter()
+        .enumerate()
+        .map(|(position, f)| PersonFieldForm {
+          person_id,
+          name: f.name.trim().to_owned(),
+          value: f.value.to_owned(),
+          position: position as i32,
+        })
+        .collect::<Vec<_>>();
+      blocking(context.pool(), move |conn| {
+        PersonField::replace_for_person(conn, person_id, &field_forms)
+      })
+      .await??;
+    }
+
+    if let Some(languages) = data.discussion_languages.clone() {
+      blocking(context.pool(), move |conn| {
+        LocalUserLanguage::replace(conn, local_user_id, &languages)
+      })
+      .await??;
+    }
+
     let local_user_form = LocalUserForm {
       person_id: Some(person_id),
       email,
@@ -282,37 +375,30 @@ impl Perform for SaveUserSettings {
       send_notifications_to_email: data.send_notifications_to_email,
       email_verified: None,
       accepted_application: None,
+      totp_secret: None,
+      totp_enabled: None,
+      email_digest_frequency,
     };
 
     let local_user_res = blocking(context.pool(), move |conn| {
       LocalUser::update(conn, local_user_id, &local_user_form)
     })
     .await?;
-    let updated_local_user = match local_user_res {
-      Ok(u) => u,
-      Err(e) => {
-        let err_type = if e.to_string()
-          == "duplicate key value violates unique constraint \"local_user_email_key\""
-        {
-          "email_already_exists"
-        } else {
-          "user_already_exists"
-        };
-
-        return Err(LemmyError::from(e).with_message(err_type));
-      }
-    };
+    if let Err(e) = local_user_res {
+      let err_type = if e.to_string()
+        == "duplicate key value violates unique constraint \"local_user_email_key\""
+      {
+        "email_already_exists"
+      } else {
+        "user_already_exists"
+      };
+
+      return Err(LemmyError::from(e).with_message(err_type));
+    }
 
     // Return the jwt
     Ok(LoginResponse {
-      jwt: Some(
-        Claims::jwt(
-          updated_local_user.id.0,
-          &context.secret().jwt_secret,
-          &context.settings().hostname,
-        )?
-        .into(),
-      ),
+      jwt: Some(data.auth.clone()),
       verify_email_sent: false,
       registration_created: false,
     })
@@ -353,6 +439,8 @@ impl Perform for ChangePassword {
     let local_user_id = local_user_view.local_user.id;
     let new_password = data.new_password.to_owned();
     let updated_local_user = blocking(context.pool(), move |conn| {
+      // Changing the password logs out all other sessions
+      LoginSession::delete_all_for_local_user(conn, local_user_id)?;
       LocalUser::update_password(conn, local_user_id, &new_password)
     })
     .await??;
@@ -360,12 +448,13 @@ impl Perform for ChangePassword {
     // Return the jwt
     Ok(LoginResponse {
       jwt: Some(
-        Claims::jwt(
-          updated_local_user.id.0,
-          &context.secret().jwt_secret,
-          &context.settings().hostname,
-        )?
-        .into(),
+        create_login_session(
+          updated_local_user.id,
+          context.pool(),
+          context.secret(),
+          &context.settings(),
+        )
+        .await?,
       ),
       verify_email_sent: false,
       registration_created: false,
@@ -489,7 +578,7 @@ impl Perform for BanPerson {
 
       // Comments
       blocking(context.pool(), move |conn: &'_ _| {
-        Comment::update_removed_for_creator(conn, banned_person_id, true)
+        Comment::update_removed_for_creator(conn, banned_person_id, None, true)
       })
       .await??;
     }
@@ -528,255 +617,1488 @@ impl Perform for BanPerson {
   }
 }
 
+/// Lifts all site bans whose expiry time has passed. Site bans aren't federated, so this only
+/// needs to update the database.
+#[tracing::instrument(skip_all)]
+pub async fn lift_expired_site_bans(
+  context: &LemmyContext,
+  clock: &dyn Clock,
+) -> Result<(), LemmyError> {
+  let now = clock.now();
+  let expired = blocking(context.pool(), move |conn| ModBan::list_expired(conn, now)).await??;
+  for ban_log in expired {
+    let person_id = ban_log.other_person_id;
+    blocking(context.pool(), move |conn| {
+      Person::ban_person(conn, person_id, false)
+    })
+    .await??;
+    let form = ModBanForm {
+      mod_person_id: ban_log.mod_person_id,
+      other_person_id: person_id,
+      reason: Some(BAN_EXPIRED_REASON.to_string()),
+      banned: Some(false),
+      expires: None,
+    };
+    blocking(context.pool(), move |conn| ModBan::create(conn, &form)).await??;
+  }
+  Ok(())
+}
+
 #[async_trait::async_trait(?Send)]
-impl Perform for BlockPerson {
-  type Response = BlockPersonResponse;
+impl Perform for RemoveContentAndBan {
+  type Response = RemoveContentAndBanResponse;
 
-  #[tracing::instrument(skip(context, _websocket_id))]
+  #[tracing::instrument(skip(context, websocket_id))]
   async fn perform(
     &self,
     context: &Data<LemmyContext>,
-    _websocket_id: Option<ConnectionId>,
-  ) -> Result<BlockPersonResponse, LemmyError> {
-    let data: &BlockPerson = self;
+    websocket_id: Option<ConnectionId>,
+  ) -> Result<RemoveContentAndBanResponse, LemmyError> {
+    let data: &RemoveContentAndBan = self;
     let local_user_view =
       get_local_user_view_from_jwt(&data.auth, context.pool(), context.secret()).await?;
 
-    let target_id = data.person_id;
-    let person_id = local_user_view.person.id;
-
-    // Don't let a person block themselves
-    if target_id == person_id {
-      return Err(LemmyError::from_message("cant_block_yourself"));
+    let community_id = data.community_id;
+    let banned_person_id = data.person_id;
+    let mod_person_id = local_user_view.person.id;
+    match community_id {
+      Some(community_id) => is_mod_or_admin(context.pool(), mod_person_id, community_id).await?,
+      None => is_admin(&local_user_view)?,
     }
-
-    let person_block_form = PersonBlockForm {
-      person_id,
-      target_id,
+    let reason_override = check_removal_reason(
+      &data.reason,
+      context.settings().moderation.require_removal_reason,
+      data.reason_override,
+      local_user_view.person.admin,
+    )?;
+    let mod_person: ApubPerson = local_user_view.person.clone().into();
+    let expires = data.expires.map(naive_from_unix);
+    let banned_person: ApubPerson = blocking(context.pool(), move |conn| {
+      Person::read(conn, banned_person_id)
+    })
+    .await??
+    .into();
+
+    // Running it again, for example to remove content which was federated in late, shouldn't fail
+    let already_banned = match community_id {
+      Some(community_id) => {
+        blocking(context.pool(), move |conn| {
+          CommunityPersonBanView::get(conn, banned_person_id, community_id).is_ok()
+        })
+        .await?
+      }
+      None => banned_person.banned,
     };
 
-    if data.block {
-      let block = move |conn: &'_ _| PersonBlock::block(conn, &person_block_form);
-      blocking(context.pool(), block)
-        .await?
-        .map_err(LemmyError::from)
-        .map_err(|e| e.with_message("person_block_already_exists"))?;
-    } else {
-      let unblock = move |conn: &'_ _| PersonBlock::unblock(conn, &person_block_form);
-      blocking(context.pool(), unblock)
-        .await?
-        .map_err(LemmyError::from)
-        .map_err(|e| e.with_message("person_block_already_exists"))?;
+    // Ban and remove in a single transaction, so that the person can't keep posting while their
+    // existing posts and comments are being removed. Everything is federated afterwards.
+    let reason = data.reason.clone();
+    let (removed_posts, removed_comments) = blocking(context.pool(), move |conn| {
+      conn.transaction::<_, LemmyError, _>(|| {
+        if !already_banned {
+          if let Some(community_id) = community_id {
+            let ban_form = CommunityPersonBanForm {
+              community_id,
+              person_id: banned_person_id,
+              expires,
+            };
+            CommunityPersonBan::ban(conn, &ban_form)
+              .map_err(LemmyError::from)
+              .map_err(|e| e.with_message("community_user_already_banned"))?;
+            let follower_form = CommunityFollowerForm {
+              community_id,
+              person_id: banned_person_id,
+              pending: false,
+            };
+            CommunityFollower::unfollow(conn, &follower_form)?;
+            let form = ModBanFromCommunityForm {
+              mod_person_id,
+              other_person_id: banned_person_id,
+              community_id,
+              reason: reason.clone(),
+              banned: Some(true),
+              expires,
+              public: None,
+            };
+            ModBanFromCommunity::create(conn, &form)?;
+          } else {
+            Person::ban_person(conn, banned_person_id, true)
+              .map_err(LemmyError::from)
+              .map_err(|e| e.with_message("couldnt_update_user"))?;
+            let form = ModBanForm {
+              mod_person_id,
+              other_person_id: banned_person_id,
+              reason: reason.clone(),
+              banned: Some(true),
+              expires,
+            };
+            ModBan::create(conn, &form)?;
+          }
+        }
+
+        let removed_posts =
+          Post::update_removed_for_creator(conn, banned_person_id, community_id, true)
+            .map_err(LemmyError::from)
+            .map_err(|e| e.with_message("couldnt_update_post"))?;
+        for post in &removed_posts {
+          let form = ModRemovePostForm {
+            mod_person_id,
+            post_id: post.id,
+            removed: Some(true),
+            reason: reason.clone(),
+            reason_translations: None,
+            reason_override,
+            public: None,
+          };
+          ModRemovePost::create(conn, &form)?;
+        }
+
+        let removed_comments =
+          Comment::update_removed_for_creator(conn, banned_person_id, community_id, true)
+            .map_err(LemmyError::from)
+            .map_err(|e| e.with_message("couldnt_update_comment"))?;
+        for comment in &removed_comments {
+          let form = ModRemoveCommentForm {
+            mod_person_id,
+            comment_id: comment.id,
+            removed: Some(true),
+            reason: reason.clone(),
+            reason_translations: None,
+            reason_override,
+            public: None,
+          };
+          ModRemoveComment::create(conn, &form)?;
+        }
+        Ok((removed_posts, removed_comments))
+      })
+    })
+    .await??;
+
+    if let Some(community_id) = community_id {
+      if !already_banned {
+        let community: ApubCommunity = blocking(context.pool(), move |conn| {
+          Community::read(conn, community_id)
+        })
+        .await??
+        .into();
+        BlockUserFromCommunity::send(
+          &community,
+          &banned_person,
+          &mod_person,
+          data.reason.clone(),
+          expires,
+          context,
+        )
+        .await?;
+      }
     }
 
-    // TODO does any federated stuff need to be done here?
+    let mut removed_in_communities: HashSet<CommunityId> =
+      removed_posts.iter().map(|p| p.community_id).collect();
+    let comment_post_ids: Vec<PostId> = removed_comments.iter().map(|c| c.post_id).collect();
+    let comment_community_ids = blocking(context.pool(), move |conn| {
+      Post::list_community_ids(conn, comment_post_ids)
+    })
+    .await??;
+    removed_in_communities.extend(comment_community_ids);
+
+    // A single activity per community, instead of one for each removed post and comment
+    for removed_in_community_id in removed_in_communities {
+      let community: ApubCommunity = blocking(context.pool(), move |conn| {
+        Community::read(conn, removed_in_community_id)
+      })
+      .await??
+      .into();
+      RemoveUserContent::send(
+        &community,
+        &banned_person,
+        &mod_person,
+        data.reason.clone(),
+        context,
+      )
+      .await?;
+    }
 
     let person_view = blocking(context.pool(), move |conn| {
-      PersonViewSafe::read(conn, target_id)
+      PersonViewSafe::read(conn, banned_person_id)
     })
     .await??;
 
-    let res = BlockPersonResponse {
+    let res = RemoveContentAndBanResponse {
       person_view,
-      blocked: data.block,
+      removed_posts: removed_posts.len() as i64,
+      removed_comments: removed_comments.len() as i64,
     };
 
+    let op = UserOperation::RemoveContentAndBan;
+    if let Some(community_id) = community_id {
+      context.chat_server().do_send(SendCommunityRoomMessage {
+        op,
+        response: res.clone(),
+        community_id,
+        websocket_id,
+      });
+    } else {
+      context.chat_server().do_send(SendAllMessage {
+        op,
+        response: res.clone(),
+        websocket_id,
+      });
+    }
+
     Ok(res)
   }
 }
 
 #[async_trait::async_trait(?Send)]
-impl Perform for GetReplies {
-  type Response = GetRepliesResponse;
+impl Perform for GenerateTotpSecret {
+  type Response = GenerateTotpSecretResponse;
 
   #[tracing::instrument(skip(context, _websocket_id))]
   async fn perform(
     &self,
     context: &Data<LemmyContext>,
     _websocket_id: Option<ConnectionId>,
-  ) -> Result<GetRepliesResponse, LemmyError> {
-    let data: &GetReplies = self;
+  ) -> Result<GenerateTotpSecretResponse, LemmyError> {
+    let data: &GenerateTotpSecret = self;
     let local_user_view =
       get_local_user_view_from_jwt(&data.auth, context.pool(), context.secret()).await?;
 
-    let sort: Option<SortType> = from_opt_str_to_opt_enum(&data.sort);
-
-    let page = data.page;
-    let limit = data.limit;
-    let unread_only = data.unread_only;
-    let person_id = local_user_view.person.id;
-    let show_bot_accounts = local_user_view.local_user.show_bot_accounts;
+    // Replacing the secret of an active setup would lock the user out of their account
+    if local_user_view.local_user.totp_enabled {
+      return Err(LemmyError::from_message("totp_already_enabled"));
+    }
 
-    let replies = blocking(context.pool(), move |conn| {
-      CommentQueryBuilder::create(conn)
-        .sort(sort)
-        .unread_only(unread_only)
-        .recipient_id(person_id)
-        .show_bot_accounts(show_bot_accounts)
-        .my_person_id(person_id)
-        .page(page)
-        .limit(limit)
-        .list()
+    let secret = generate_totp_secret();
+    let local_user_id = local_user_view.local_user.id;
+    let form = LocalUserForm {
+      totp_secret: Some(Some(secret.clone())),
+      totp_enabled: Some(false),
+      ..LocalUserForm::default()
+    };
+    blocking(context.pool(), move |conn| {
+      LocalUser::update(conn, local_user_id, &form)
     })
-    .await??;
+    .await?
+    .map_err(LemmyError::from)
+    .map_err(|e| e.with_message("couldnt_update_user"))?;
 
-    Ok(GetRepliesResponse { replies })
+    let totp_secret_url = build_totp_url(
+      &secret,
+      &local_user_view.person.name,
+      &context.settings().hostname,
+    );
+    Ok(GenerateTotpSecretResponse {
+      totp_secret_url: totp_secret_url.into(),
+    })
   }
 }
 
 #[async_trait::async_trait(?Send)]
-impl Perform for GetPersonMentions {
-  type Response = GetPersonMentionsResponse;
+impl Perform for UpdateTotp {
+  type Response = UpdateTotpResponse;
 
   #[tracing::instrument(skip(context, _websocket_id))]
   async fn perform(
     &self,
     context: &Data<LemmyContext>,
     _websocket_id: Option<ConnectionId>,
-  ) -> Result<GetPersonMentionsResponse, LemmyError> {
-    let data: &GetPersonMentions = self;
+  ) -> Result<UpdateTotpResponse, LemmyError> {
+    let data: &UpdateTotp = self;
     let local_user_view =
       get_local_user_view_from_jwt(&data.auth, context.pool(), context.secret()).await?;
 
-    let sort: Option<SortType> = from_opt_str_to_opt_enum(&data.sort);
+    check_totp(
+      &local_user_view.local_user,
+      &data.totp_token,
+      context.pool(),
+    )
+    .await?;
 
-    let page = data.page;
-    let limit = data.limit;
-    let unread_only = data.unread_only;
-    let person_id = local_user_view.person.id;
-    let mentions = blocking(context.pool(), move |conn| {
-      PersonMentionQueryBuilder::create(conn)
-        .recipient_id(person_id)
-        .my_person_id(person_id)
-        .sort(sort)
-        .unread_only(unread_only)
-        .page(page)
-        .limit(limit)
-        .list()
+    // When disabling, also remove the secret so that a new one has to be generated next time
+    let form = LocalUserForm {
+      totp_secret: if data.enabled { None } else { Some(None) },
+      totp_enabled: Some(data.enabled),
+      ..LocalUserForm::default()
+    };
+    let local_user_id = local_user_view.local_user.id;
+    let updated_local_user = blocking(context.pool(), move |conn| {
+      LocalUser::update(conn, local_user_id, &form)
     })
-    .await??;
+    .await?
+    .map_err(LemmyError::from)
+    .map_err(|e| e.with_message("couldnt_update_user"))?;
 
-    Ok(GetPersonMentionsResponse { mentions })
+    Ok(UpdateTotpResponse {
+      enabled: updated_local_user.totp_enabled,
+    })
   }
 }
 
 #[async_trait::async_trait(?Send)]
-impl Perform for MarkPersonMentionAsRead {
-  type Response = PersonMentionResponse;
+impl Perform for ExportAccountData {
+  type Response = ExportAccountDataResponse;
 
   #[tracing::instrument(skip(context, _websocket_id))]
   async fn perform(
     &self,
     context: &Data<LemmyContext>,
     _websocket_id: Option<ConnectionId>,
-  ) -> Result<PersonMentionResponse, LemmyError> {
-    let data: &MarkPersonMentionAsRead = self;
+  ) -> Result<ExportAccountDataResponse, LemmyError> {
+    let data: &ExportAccountData = self;
     let local_user_view =
       get_local_user_view_from_jwt(&data.auth, context.pool(), context.secret()).await?;
+    let local_user_id = local_user_view.local_user.id;
 
-    let person_mention_id = data.person_mention_id;
-    let read_person_mention = blocking(context.pool(), move |conn| {
-      PersonMention::read(conn, person_mention_id)
+    // Generating an archive is expensive, so only allow one per day
+    let latest = blocking(context.pool(), move |conn| {
+      AccountExport::latest_for_local_user(conn, local_user_id).optional()
     })
     .await??;
-
-    if local_user_view.person.id != read_person_mention.recipient_id {
-      return Err(LemmyError::from_message("couldnt_update_comment"));
+    if let Some(latest) = latest {
+      if latest.published > naive_now() - Duration::days(1) {
+        return Err(LemmyError::from_message("account_export_too_recent"));
+      }
     }
 
-    let person_mention_id = read_person_mention.id;
-    let read = data.read;
-    let update_mention =
-      move |conn: &'_ _| PersonMention::update_read(conn, person_mention_id, read);
-    blocking(context.pool(), update_mention)
-      .await?
-      .map_err(LemmyError::from)
-      .map_err(|e| e.with_message("couldnt_update_comment"))?;
-
-    let person_mention_id = read_person_mention.id;
-    let person_id = local_user_view.person.id;
-    let person_mention_view = blocking(context.pool(), move |conn| {
-      PersonMentionView::read(conn, person_mention_id, Some(person_id))
+    let form = AccountExportForm {
+      local_user_id,
+      token: generate_random_string(),
+    };
+    let export = blocking(context.pool(), move |conn| {
+      AccountExport::create(conn, &form)
     })
-    .await??;
+    .await?
+    .map_err(LemmyError::from)
+    .map_err(|e| e.with_message("couldnt_create_account_export"))?;
+
+    let export_id = export.id;
+    let pool = context.pool().clone();
+    actix_rt::spawn(async move {
+      let res = blocking(&pool, move |conn| -> Result<(), LemmyError> {
+        let archive = AccountArchiveView::read(conn, local_user_id)?;
+        AccountExport::finish(conn, export_id, &serde_json::to_string(&archive)?)?;
+        Ok(())
+      })
+      .await;
+      if let Err(e) = res.and_then(|r| r) {
+        warn!("Failed to generate account export {}: {}", export_id, e);
+        // Remove the unfinished export, so that it doesn't block a retry
+        let deleted = blocking(&pool, move |conn| AccountExport::delete(conn, export_id)).await;
+        if let Err(e) = deleted.and_then(|d| d.map_err(LemmyError::from)) {
+          warn!("Failed to delete account export {}: {}", export_id, e);
+        }
+      }
+    });
 
-    Ok(PersonMentionResponse {
-      person_mention_view,
+    Ok(ExportAccountDataResponse {
+      token: export.token.into(),
     })
   }
 }
 
 #[async_trait::async_trait(?Send)]
-impl Perform for MarkAllAsRead {
-  type Response = GetRepliesResponse;
+impl Perform for GetAccountExport {
+  type Response = GetAccountExportResponse;
 
   #[tracing::instrument(skip(context, _websocket_id))]
   async fn perform(
     &self,
     context: &Data<LemmyContext>,
     _websocket_id: Option<ConnectionId>,
-  ) -> Result<GetRepliesResponse, LemmyError> {
-    let data: &MarkAllAsRead = self;
+  ) -> Result<GetAccountExportResponse, LemmyError> {
+    let data: &GetAccountExport = self;
     let local_user_view =
       get_local_user_view_from_jwt(&data.auth, context.pool(), context.secret()).await?;
 
-    let person_id = local_user_view.person.id;
-    let replies = blocking(context.pool(), move |conn| {
-      CommentQueryBuilder::create(conn)
-        .my_person_id(person_id)
-        .recipient_id(person_id)
-        .unread_only(true)
-        .page(1)
-        .limit(999)
-        .list()
+    let token = data.token.to_string();
+    let export = blocking(context.pool(), move |conn| {
+      AccountExport::read_for_token(conn, &token)
     })
-    .await??;
-
-    // TODO: this should probably be a bulk operation
-    // Not easy to do as a bulk operation,
-    // because recipient_id isn't in the comment table
-    for comment_view in &replies {
-      let reply_id = comment_view.comment.id;
-      let mark_as_read = move |conn: &'_ _| Comment::update_read(conn, reply_id, true);
-      blocking(context.pool(), mark_as_read)
-        .await?
-        .map_err(LemmyError::from)
-        .map_err(|e| e.with_message("couldnt_update_comment"))?;
+    .await?
+    .map_err(LemmyError::from)
+    .map_err(|e| e.with_message("couldnt_find_account_export"))?;
+    if export.local_user_id != local_user_view.local_user.id {
+      return Err(LemmyError::from_message("couldnt_find_account_export"));
     }
 
-    // Mark all user mentions as read
-    let update_person_mentions =
-      move |conn: &'_ _| PersonMention::mark_all_as_read(conn, person_id);
-    blocking(context.pool(), update_person_mentions)
-      .await?
-      .map_err(LemmyError::from)
-      .map_err(|e| e.with_message("couldnt_update_comment"))?;
+    let archive = export
+      .data
+      .map(|d| serde_json::from_str::<AccountArchiveView>(&d))
+      .transpose()?;
+    Ok(GetAccountExportResponse {
+      finished: archive.is_some(),
+      archive,
+    })
+  }
+}
 
-    // Mark all private_messages as read
+/// The parts of an account archive which can be imported. Everything else is ignored.
+#[derive(Deserialize)]
+struct ImportableArchive {
+  #[serde(default)]
+  followed_communities: Vec<Url>,
+  #[serde(default)]
+  blocked_communities: Vec<Url>,
+  #[serde(default)]
+  blocked_persons: Vec<Url>,
+  #[serde(default)]
+  saved_posts: Vec<Url>,
+}
+
+/// Each object may need to be fetched from a remote instance, which takes a while
+const MAX_IMPORT_OBJECTS: usize = 1000;
+
+/// Local users whose import is running
+static RUNNING_IMPORTS: Mutex<Vec<LocalUserId>> = Mutex::new(Vec::new());
+
+#[async_trait::async_trait(?Send)]
+impl Perform for ImportAccountData {
+  type Response = ImportAccountDataResponse;
+
+  #[tracing::instrument(skip(context, _websocket_id))]
+  async fn perform(
+    &self,
+    context: &Data<LemmyContext>,
+    _websocket_id: Option<ConnectionId>,
+  ) -> Result<ImportAccountDataResponse, LemmyError> {
+    let data: &ImportAccountData = self;
+    let local_user_view =
+      get_local_user_view_from_jwt(&data.auth, context.pool(), context.secret()).await?;
+    let person = local_user_view.person;
+
+    let archive: ImportableArchive = serde_json::from_str(&data.data)
+      .map_err(LemmyError::from)
+      .map_err(|e| e.with_message("invalid_account_archive"))?;
+    let total = archive.followed_communities.len()
+      + archive.blocked_communities.len()
+      + archive.blocked_persons.len()
+      + archive.saved_posts.len();
+    if total > MAX_IMPORT_OBJECTS {
+      return Err(LemmyError::from_message("account_archive_too_large"));
+    }
+
+    // Only one import at a time per user, as each can keep fetching for a long time
+    let local_user_id = local_user_view.local_user.id;
+    {
+      let mut running = RUNNING_IMPORTS.lock().expect("lock running imports");
+      if running.contains(&local_user_id) {
+        return Err(LemmyError::from_message("account_import_already_running"));
+      }
+      running.push(local_user_id);
+    }
+
+    let context = context.get_ref().clone();
+    actix_rt::spawn(async move {
+      let result = import_archive(&person, archive, &context).await;
+      RUNNING_IMPORTS
+        .lock()
+        .expect("lock running imports")
+        .retain(|id| *id != local_user_id);
+      context.chat_server().do_send(SendUserRoomMessage {
+        op: UserOperation::ImportAccountData,
+        response: result,
+        local_recipient_id: local_user_id,
+        websocket_id: None,
+      });
+    });
+
+    Ok(ImportAccountDataResponse { objects: total })
+  }
+}
+
+/// Imports everything that can be fetched, and skips the other objects
+async fn import_archive(
+  person: &Person,
+  archive: ImportableArchive,
+  context: &LemmyContext,
+) -> AccountImportResult {
+  let mut failed = vec![];
+  let mut followed_communities = 0;
+  for community_id in archive.followed_communities {
+    match import_follow(person, community_id.clone(), context).await {
+      Ok(()) => followed_communities += 1,
+      Err(e) => {
+        warn!("Failed to import follow of {}: {}", community_id, e);
+        failed.push(community_id);
+      }
+    }
+  }
+  let mut blocked_communities = 0;
+  for community_id in archive.blocked_communities {
+    match import_community_block(person, community_id.clone(), context).await {
+      Ok(()) => blocked_communities += 1,
+      Err(e) => {
+        warn!("Failed to import block of {}: {}", community_id, e);
+        failed.push(community_id);
+      }
+    }
+  }
+  let mut blocked_persons = 0;
+  for person_id in archive.blocked_persons {
+    match import_person_block(person, person_id.clone(), context).await {
+      Ok(()) => blocked_persons += 1,
+      Err(e) => {
+        warn!("Failed to import block of {}: {}", person_id, e);
+        failed.push(person_id);
+      }
+    }
+  }
+  let mut saved_posts = 0;
+  for post_id in archive.saved_posts {
+    match import_saved_post(person, post_id.clone(), context).await {
+      Ok(()) => saved_posts += 1,
+      Err(e) => {
+        warn!("Failed to import saved post {}: {}", post_id, e);
+        failed.push(post_id);
+      }
+    }
+  }
+  AccountImportResult {
+    followed_communities,
+    blocked_communities,
+    blocked_persons,
+    saved_posts,
+    failed,
+  }
+}
+
+/// Remote communities only add the follower once they accept the follow
+async fn import_follow(
+  person: &Person,
+  community_id: Url,
+  context: &LemmyContext,
+) -> Result<(), LemmyError> {
+  let community = ObjectId::<ApubCommunity>::new(community_id)
+    .dereference(context, context.client(), &mut 0)
+    .await?;
+  if community.local {
+    check_community_ban(person.id, community.id, context.pool()).await?;
+    check_community_deleted_or_removed(community.id, context.pool()).await?;
+    let form = CommunityFollowerForm {
+      community_id: community.id,
+      person_id: person.id,
+      pending: false,
+    };
+    blocking(context.pool(), move |conn| {
+      CommunityFollower::follow(conn, &form)
+    })
+    .await??;
+  } else {
+    FollowCommunityApub::send(&person.clone().into(), &community, context).await?;
+  }
+  Ok(())
+}
+
+async fn import_community_block(
+  person: &Person,
+  community_id: Url,
+  context: &LemmyContext,
+) -> Result<(), LemmyError> {
+  let community = ObjectId::<ApubCommunity>::new(community_id)
+    .dereference(context, context.client(), &mut 0)
+    .await?;
+  block_community(person, community.id, context).await
+}
+
+async fn import_person_block(
+  person: &Person,
+  target_id: Url,
+  context: &LemmyContext,
+) -> Result<(), LemmyError> {
+  let target = ObjectId::<ApubPerson>::new(target_id)
+    .dereference(context, context.client(), &mut 0)
+    .await?;
+  if target.id == person.id {
+    return Err(LemmyError::from_message("cant_block_yourself"));
+  }
+  let form = PersonBlockForm {
+    person_id: person.id,
+    target_id: target.id,
+    prevent_interaction: None,
+  };
+  blocking(context.pool(), move |conn| PersonBlock::block(conn, &form)).await??;
+  Ok(())
+}
+
+async fn import_saved_post(
+  person: &Person,
+  post_id: Url,
+  context: &LemmyContext,
+) -> Result<(), LemmyError> {
+  let post = ObjectId::<ApubPost>::new(post_id)
+    .dereference(context, context.client(), &mut 0)
+    .await?;
+  let form = PostSavedForm {
+    post_id: post.id,
+    person_id: person.id,
+    collection_id: None,
+  };
+  blocking(context.pool(), move |conn| PostSaved::save(conn, &form)).await??;
+  Ok(())
+}
+
+/// Every subscription gets a request for each notification
+const MAX_PUSH_SUBSCRIPTIONS: usize = 10;
+
+#[async_trait::async_trait(?Send)]
+impl Perform for RegisterPushSubscription {
+  type Response = PushSubscriptionResponse;
+
+  #[tracing::instrument(skip(context, _websocket_id))]
+  async fn perform(
+    &self,
+    context: &Data<LemmyContext>,
+    _websocket_id: Option<ConnectionId>,
+  ) -> Result<PushSubscriptionResponse, LemmyError> {
+    let data: &RegisterPushSubscription = self;
+    let local_user_view =
+      get_local_user_view_from_jwt(&data.auth, context.pool(), context.secret()).await?;
+
+    if context.settings().web_push.is_none() {
+      return Err(LemmyError::from_message("web_push_disabled"));
+    }
+    let endpoint = Url::parse(&data.endpoint)
+      .map_err(LemmyError::from)
+      .map_err(|e| e.with_message("invalid_push_subscription"))?;
+    check_push_endpoint(&endpoint).await?;
+    // Fails if the keys are malformed, so that we don't store subscriptions which can never work
+    encrypt_payload(&data.keys.p256dh, &data.keys.auth, &[])?;
+
+    // Renewing an existing subscription doesn't count towards the limit
+    let local_user_id = local_user_view.local_user.id;
+    let subscriptions = blocking(context.pool(), move |conn| {
+      PushSubscription::list_for_local_user(conn, local_user_id)
+    })
+    .await??;
+    if subscriptions.len() >= MAX_PUSH_SUBSCRIPTIONS
+      && !subscriptions
+        .iter()
+        .any(|s| s.endpoint == endpoint.as_str())
+    {
+      return Err(LemmyError::from_message("too_many_push_subscriptions"));
+    }
+
+    let form = PushSubscriptionForm {
+      local_user_id,
+      endpoint: endpoint.to_string(),
+      p256dh: data.keys.p256dh.clone(),
+      auth: data.keys.auth.clone(),
+    };
+    blocking(context.pool(), move |conn| {
+      PushSubscription::upsert(conn, &form)
+    })
+    .await?
+    .map_err(LemmyError::from)
+    .map_err(|e| e.with_message("couldnt_create_push_subscription"))?;
+
+    Ok(PushSubscriptionResponse {})
+  }
+}
+
+#[async_trait::async_trait(?Send)]
+impl Perform for DeletePushSubscription {
+  type Response = PushSubscriptionResponse;
+
+  #[tracing::instrument(skip(context, _websocket_id))]
+  async fn perform(
+    &self,
+    context: &Data<LemmyContext>,
+    _websocket_id: Option<ConnectionId>,
+  ) -> Result<PushSubscriptionResponse, LemmyError> {
+    let data: &DeletePushSubscription = self;
+    let local_user_view =
+      get_local_user_view_from_jwt(&data.auth, context.pool(), context.secret()).await?;
+
+    let local_user_id = local_user_view.local_user.id;
+    let endpoint = data.endpoint.clone();
+    let deleted = blocking(context.pool(), move |conn| {
+      PushSubscription::delete_for_local_user(conn, local_user_id, &endpoint)
+    })
+    .await??;
+    if deleted == 0 {
+      return Err(LemmyError::from_message("couldnt_find_push_subscription"));
+    }
+
+    Ok(PushSubscriptionResponse {})
+  }
+}
+
+/// Fails unless the token is currently valid for the secret of the user. Each token can only be
+/// used once, and after too many wrong tokens further attempts are rejected for a while.
+async fn check_totp(local_user: &LocalUser, token: &str, pool: &DbPool) -> Result<(), LemmyError> {
+  let secret = local_user
+    .totp_secret
+    .as_ref()
+    .ok_or_else(|| LemmyError::from_message("totp_not_generated"))?;
+  if is_totp_locked(
+    local_user.totp_failed_attempts,
+    local_user.totp_last_failure,
+    naive_now(),
+  ) {
+    return Err(LemmyError::from_message("too_many_totp_attempts"));
+  }
+
+  let local_user_id = local_user.id;
+  if let Some(step) = check_totp_token(secret, token, naive_now().timestamp())? {
+    let unused = blocking(pool, move |conn| {
+      LocalUser::use_totp_step(conn, local_user_id, step)
+    })
+    .await??;
+    if unused {
+      return Ok(());
+    }
+  }
+  blocking(pool, move |conn| {
+    LocalUser::add_totp_failure(conn, local_user_id)
+  })
+  .await??;
+  Err(LemmyError::from_message("incorrect_totp_token"))
+}
+
+#[async_trait::async_trait(?Send)]
+impl Perform for BlockPerson {
+  type Response = BlockPersonResponse;
+
+  #[tracing::instrument(skip(context, _websocket_id))]
+  async fn perform(
+    &self,
+    context: &Data<LemmyContext>,
+    _websocket_id: Option<ConnectionId>,
+  ) -> Result<BlockPersonResponse, LemmyError> {
+    let data: &BlockPerson = self;
+    let local_user_view =
+      get_local_user_view_from_jwt(&data.auth, context.pool(), context.secret()).await?;
+
+    let target_id = data.person_id;
+    let person_id = local_user_view.person.id;
+
+    // Don't let a person block themselves
+    if target_id == person_id {
+      return Err(LemmyError::from_message("cant_block_yourself"));
+    }
+
+    let person_block_form = PersonBlockForm {
+      person_id,
+      target_id,
+      prevent_interaction: data.prevent_interaction,
+    };
+
+    if data.block {
+      let block = move |conn: &'_ _| PersonBlock::block(conn, &person_block_form);
+      blocking(context.pool(), block)
+        .await?
+        .map_err(LemmyError::from)
+        .map_err(|e| e.with_message("person_block_already_exists"))?;
+    } else {
+      let unblock = move |conn: &'_ _| PersonBlock::unblock(conn, &person_block_form);
+      blocking(context.pool(), unblock)
+        .await?
+        .map_err(LemmyError::from)
+        .map_err(|e| e.with_message("person_block_already_exists"))?;
+    }
+
+    // TODO does any federated stuff need to be done here?
+
+    let person_view = blocking(context.pool(), move |conn| {
+      PersonViewSafe::read(conn, target_id)
+    })
+    .await??;
+
+    let res = BlockPersonResponse {
+      person_view,
+      blocked: data.block,
+    };
+
+    Ok(res)
+  }
+}
+
+#[async_trait::async_trait(?Send)]
+impl Perform for BlockInstance {
+  type Response = BlockInstanceResponse;
+
+  #[tracing::instrument(skip(context, _websocket_id))]
+  async fn perform(
+    &self,
+    context: &Data<LemmyContext>,
+    _websocket_id: Option<ConnectionId>,
+  ) -> Result<BlockInstanceResponse, LemmyError> {
+    let data: &BlockInstance = self;
+    let local_user_view =
+      get_local_user_view_from_jwt(&data.auth, context.pool(), context.secret()).await?;
+
+    let domain = data.domain.trim().to_lowercase();
+    let parsed_domain = Url::parse(&format!("https://{}", domain))
+      .ok()
+      .and_then(|u| u.domain().map(ToString::to_string));
+    if parsed_domain.as_ref() != Some(&domain) {
+      return Err(LemmyError::from_message("invalid_domain"));
+    }
+    if domain == context.settings().get_hostname_without_port()? {
+      return Err(LemmyError::from_message("cant_block_local_instance"));
+    }
+
+    let form = PersonInstanceBlockForm {
+      person_id: local_user_view.person.id,
+      domain: domain.clone(),
+    };
+    if data.block {
+      let block = move |conn: &'_ _| PersonInstanceBlock::block(conn, &form);
+      blocking(context.pool(), block)
+        .await?
+        .map_err(LemmyError::from)
+        .map_err(|e| e.with_message("instance_block_already_exists"))?;
+    } else {
+      let unblock = move |conn: &'_ _| PersonInstanceBlock::unblock(conn, &form);
+      blocking(context.pool(), unblock)
+        .await?
+        .map_err(LemmyError::from)
+        .map_err(|e| e.with_message("instance_block_already_exists"))?;
+    }
+
+    Ok(BlockInstanceResponse {
+      domain,
+      blocked: data.block,
+    })
+  }
+}
+
+#[async_trait::async_trait(?Send)]
+impl Perform for MoveSaved {
+  type Response = MoveSavedResponse;
+
+  #[tracing::instrument(skip(context, _websocket_id))]
+  async fn perform(
+    &self,
+    context: &Data<LemmyContext>,
+    _websocket_id: Option<ConnectionId>,
+  ) -> Result<MoveSavedResponse, LemmyError> {
+    let data: &MoveSaved = self;
+    let local_user_view =
+      get_local_user_view_from_jwt(&data.auth, context.pool(), context.secret()).await?;
+
+    if data.post_id.is_none() && data.comment_id.is_none() {
+      return Err(LemmyError::from_message("no_saved_item"));
+    }
+
+    let person_id = local_user_view.person.id;
+    let collection_id = data.collection_id;
+    check_saved_collection_owner(collection_id, person_id, context.pool()).await?;
+
+    if let Some(post_id) = data.post_id {
+      blocking(context.pool(), move |conn| {
+        PostSaved::move_to_collection(conn, person_id, post_id, collection_id)
+      })
+      .await?
+      .map_err(LemmyError::from)
+      .map_err(|e| e.with_message("couldnt_move_saved_item"))?;
+    }
+    if let Some(comment_id) = data.comment_id {
+      blocking(context.pool(), move |conn| {
+        CommentSaved::move_to_collection(conn, person_id, comment_id, collection_id)
+      })
+      .await?
+      .map_err(LemmyError::from)
+      .map_err(|e| e.with_message("couldnt_move_saved_item"))?;
+    }
+
+    Ok(MoveSavedResponse {
+      post_id: data.post_id,
+      comment_id: data.comment_id,
+      collection_id,
+    })
+  }
+}
+
+#[async_trait::async_trait(?Send)]
+impl Perform for GetSaved {
+  type Response = GetSavedResponse;
+
+  #[tracing::instrument(skip(context, _websocket_id))]
+  async fn perform(
+    &self,
+    context: &Data<LemmyContext>,
+    _websocket_id: Option<ConnectionId>,
+  ) -> Result<GetSavedResponse, LemmyError> {
+    let data: &GetSaved = self;
+    let local_user_view =
+      get_local_user_view_from_jwt(&data.auth, context.pool(), context.secret()).await?;
+
+    let person_id = local_user_view.person.id;
+    let collection_id = data.collection_id;
+    check_saved_collection_owner(collection_id, person_id, context.pool()).await?;
+
+    let sort: Option<SortType> = from_opt_str_to_opt_enum(&data.sort);
+    let page = data.page;
+    let limit = data.limit;
+    let show_nsfw = local_user_view.local_user.show_nsfw;
+    let show_bot_accounts = local_user_view.local_user.show_bot_accounts;
+    let show_scores = local_user_view.local_user.show_scores;
+
+    let (posts, comments) = blocking(context.pool(), move |conn| {
+      let posts = PostQueryBuilder::create(conn)
+        .sort(sort)
+        .show_nsfw(show_nsfw)
+        .show_bot_accounts(show_bot_accounts)
+        .show_scores(show_scores)
+        .saved_only(true)
+        .saved_collection_id(collection_id)
+        .my_person_id(person_id)
+        .page(page)
+        .limit(limit)
+        .list()?;
+      let comments = CommentQueryBuilder::create(conn)
+        .sort(sort)
+        .show_bot_accounts(show_bot_accounts)
+        .show_scores(show_scores)
+        .saved_only(true)
+        .saved_collection_id(collection_id)
+        .my_person_id(person_id)
+        .page(page)
+        .limit(limit)
+        .list()?;
+      Ok((posts, comments)) as Result<_, LemmyError>
+    })
+    .await??;
+
+    Ok(GetSavedResponse { posts, comments })
+  }
+}
+
+/// Upper limit for the number of months of activity in `GetPersonStats`
+const MAX_PERSON_STATS_MONTHS: i64 = 120;
+
+#[async_trait::async_trait(?Send)]
+impl Perform for GetPersonStats {
+  type Response = GetPersonStatsResponse;
+
+  #[tracing::instrument(skip(context, _websocket_id))]
+  async fn perform(
+    &self,
+    context: &Data<LemmyContext>,
+    _websocket_id: Option<ConnectionId>,
+  ) -> Result<GetPersonStatsResponse, LemmyError> {
+    let data: &GetPersonStats = self;
+    let local_user_view =
+      get_local_user_view_from_jwt_opt(data.auth.as_ref(), context.pool(), context.secret())
+        .await?;
+    check_private_instance(&local_user_view, context.pool()).await?;
+
+    let person_id = data.person_id;
+    let months = data.months.unwrap_or(12).clamp(1, MAX_PERSON_STATS_MONTHS) as u32;
+    let person_stats_view = blocking(context.pool(), move |conn| {
+      PersonStatsView::read(conn, person_id, months)
+    })
+    .await?
+    .map_err(LemmyError::from)
+    .map_err(|e| e.with_message("couldnt_find_person"))?;
+
+    Ok(GetPersonStatsResponse { person_stats_view })
+  }
+}
+
+#[async_trait::async_trait(?Send)]
+impl Perform for GetReplies {
+  type Response = GetRepliesResponse;
+
+  #[tracing::instrument(skip(context, _websocket_id))]
+  async fn perform(
+    &self,
+    context: &Data<LemmyContext>,
+    _websocket_id: Option<ConnectionId>,
+  ) -> Result<GetRepliesResponse, LemmyError> {
+    let data: &GetReplies = self;
+    let local_user_view =
+      get_local_user_view_from_jwt(&data.auth, context.pool(), context.secret()).await?;
+
+    let sort: Option<SortType> = from_opt_str_to_opt_enum(&data.sort);
+
+    let page = data.page;
+    let limit = data.limit;
+    let unread_only = data.unread_only;
+    let person_id = local_user_view.person.id;
+    let show_bot_accounts = local_user_view.local_user.show_bot_accounts;
+    let show_scores = local_user_view.local_user.show_scores;
+
+    let replies = blocking(context.pool(), move |conn| {
+      CommentQueryBuilder::create(conn)
+        .sort(sort)
+        .unread_only(unread_only)
+        .recipient_id(person_id)
+        .show_bot_accounts(show_bot_accounts)
+        .show_scores(show_scores)
+        .my_person_id(person_id)
+        .page(page)
+        .limit(limit)
+        .list()
+    })
+    .await??;
+
+    Ok(GetRepliesResponse { replies })
+  }
+}
+
+#[async_trait::async_trait(?Send)]
+impl Perform for GetPersonMentions {
+  type Response = GetPersonMentionsResponse;
+
+  #[tracing::instrument(skip(context, _websocket_id))]
+  async fn perform(
+    &self,
+    context: &Data<LemmyContext>,
+    _websocket_id: Option<ConnectionId>,
+  ) -> Result<GetPersonMentionsResponse, LemmyError> {
+    let data: &GetPersonMentions = self;
+    let local_user_view =
+      get_local_user_view_from_jwt(&data.auth, context.pool(), context.secret()).await?;
+
+    let sort: Option<SortType> = from_opt_str_to_opt_enum(&data.sort);
+
+    let page = data.page;
+    let limit = data.limit;
+    let unread_only = data.unread_only;
+    let person_id = local_user_view.person.id;
+    let show_scores = local_user_view.local_user.show_scores;
+    let mentions = blocking(context.pool(), move |conn| {
+      PersonMentionQueryBuilder::create(conn)
+        .recipient_id(person_id)
+        .my_person_id(person_id)
+        .sort(sort)
+        .unread_only(unread_only)
+        .show_scores(show_scores)
+        .page(page)
+        .limit(limit)
+        .list()
+    })
+    .await??;
+
+    Ok(GetPersonMentionsResponse { mentions })
+  }
+}
+
+#[async_trait::async_trait(?Send)]
+impl Perform for MarkPersonMentionAsRead {
+  type Response = PersonMentionResponse;
+
+  #[tracing::instrument(skip(context, _websocket_id))]
+  async fn perform(
+    &self,
+    context: &Data<LemmyContext>,
+    _websocket_id: Option<ConnectionId>,
+  ) -> Result<PersonMentionResponse, LemmyError> {
+    let data: &MarkPersonMentionAsRead = self;
+    let local_user_view =
+      get_local_user_view_from_jwt(&data.auth, context.pool(), context.secret()).await?;
+
+    let person_mention_id = data.person_mention_id;
+    let read_person_mention = blocking(context.pool(), move |conn| {
+      PersonMention::read(conn, person_mention_id)
+    })
+    .await??;
+
+    if local_user_view.person.id != read_person_mention.recipient_id {
+      return Err(LemmyError::from_message("couldnt_update_comment"));
+    }
+
+    let person_mention_id = read_person_mention.id;
+    let read = data.read;
+    let update_mention =
+      move |conn: &'_ _| PersonMention::update_read(conn, person_mention_id, read);
+    blocking(context.pool(), update_mention)
+      .await?
+      .map_err(LemmyError::from)
+      .map_err(|e| e.with_message("couldnt_update_comment"))?;
+
+    let person_mention_id = read_person_mention.id;
+    let person_id = local_user_view.person.id;
+    let person_mention_view = blocking(context.pool(), move |conn| {
+      PersonMentionView::read(conn, person_mention_id, Some(person_id))
+    })
+    .await??;
+
+    Ok(PersonMentionResponse {
+      person_mention_view,
+    })
+  }
+}
+
+#[async_trait::async_trait(?Send)]
+impl Perform for MarkAllAsRead {
+  type Response = GetRepliesResponse;
+
+  #[tracing::instrument(skip(context, _websocket_id))]
+  async fn perform(
+    &self,
+    context: &Data<LemmyContext>,
+    _websocket_id: Option<ConnectionId>,
+  ) -> Result<GetRepliesResponse, LemmyError> {
+    let data: &MarkAllAsRead = self;
+    let local_user_view =
+      get_local_user_view_from_jwt(&data.auth, context.pool(), context.secret()).await?;
+
+    let person_id = local_user_view.person.id;
+    let replies = blocking(context.pool(), move |conn| {
+      CommentQueryBuilder::create(conn)
+        .my_person_id(person_id)
+        .recipient_id(person_id)
+        .unread_only(true)
+        .page(1)
+        .limit(999)
+        .list()
+    })
+    .await??;
+
+    // TODO: this should probably be a bulk operation
+    // Not easy to do as a bulk operation,
+    // because recipient_id isn't in the comment table
+    for comment_view in &replies {
+      let reply_id = comment_view.comment.id;
+      let mark_as_read = move |conn: &'_ _| Comment::update_read(conn, reply_id, true);
+      blocking(context.pool(), mark_as_read)
+        .await?
+        .map_err(LemmyError::from)
+        .map_err(|e| e.with_message("couldnt_update_comment"))?;
+    }
+
+    // Mark all user mentions as read
+    let update_person_mentions =
+      move |conn: &'_ _| PersonMention::mark_all_as_read(conn, person_id);
+    blocking(context.pool(), update_person_mentions)
+      .await?
+      .map_err(LemmyError::from)
+      .map_err(|e| e.with_message("couldnt_update_comment"))?;
+
+    // Mark all private_messages as read
     let update_pm = move |conn: &'_ _| PrivateMessage::mark_all_as_read(conn, person_id);
     blocking(context.pool(), update_pm)
       .await?
       .map_err(LemmyError::from)
       .map_err(|e| e.with_message("couldnt_update_private_message"))?;
 
-    Ok(GetRepliesResponse { replies: vec![] })
+    Ok(GetRepliesResponse { replies: vec![] })
+  }
+}
+
+#[async_trait::async_trait(?Send)]
+impl Perform for PasswordReset {
+  type Response = PasswordResetResponse;
+
+  #[tracing::instrument(skip(self, context, _websocket_id))]
+  async fn perform(
+    &self,
+    context: &Data<LemmyContext>,
+    _websocket_id: Option<ConnectionId>,
+  ) -> Result<PasswordResetResponse, LemmyError> {
+    let data: &PasswordReset = self;
+
+    // Fetch that email
+    let email = data.email.clone();
+    let local_user_view = blocking(context.pool(), move |conn| {
+      LocalUserView::find_by_email(conn, &email)
+    })
+    .await?
+    .map_err(LemmyError::from)
+    .map_err(|e| e.with_message("couldnt_find_that_username_or_email"))?;
+
+    // Email the pure token to the user.
+    send_password_reset_email(&local_user_view, context.pool(), &context.settings()).await?;
+    Ok(PasswordResetResponse {})
+  }
+}
+
+#[async_trait::async_trait(?Send)]
+impl Perform for PasswordChange {
+  type Response = LoginResponse;
+
+  #[tracing::instrument(skip(self, context, _websocket_id))]
+  async fn perform(
+    &self,
+    context: &Data<LemmyContext>,
+    _websocket_id: Option<ConnectionId>,
+  ) -> Result<LoginResponse, LemmyError> {
+    let data: &PasswordChange = self;
+
+    // Fetch the user_id from the token
+    let token = data.token.clone();
+    let local_user_id = blocking(context.pool(), move |conn| {
+      PasswordResetRequest::read_from_token(conn, &token).map(|p| p.local_user_id)
+    })
+    .await??;
+
+    password_length_check(&data.password)?;
+
+    // Make sure passwords match
+    if data.password != data.password_verify {
+      return Err(LemmyError::from_message("passwords_dont_match"));
+    }
+
+    // Access to the email inbox alone must not be enough to get into the account
+    let local_user = blocking(context.pool(), move |conn| {
+      LocalUser::read(conn, local_user_id)
+    })
+    .await??;
+    if local_user.totp_enabled {
+      let token = data
+        .totp_token
+        .as_ref()
+        .ok_or_else(|| LemmyError::from_message("missing_totp_token"))?;
+      check_totp(&local_user, token, context.pool()).await?;
+    }
+
+    // Update the user with the new password
+    let password = data.password.clone();
+    let updated_local_user = blocking(context.pool(), move |conn| {
+      LoginSession::delete_all_for_local_user(conn, local_user_id)?;
+      LocalUser::update_password(conn, local_user_id, &password)
+    })
+    .await?
+    .map_err(LemmyError::from)
+    .map_err(|e| e.with_message("couldnt_update_user"))?;
+
+    // Return the jwt
+    Ok(LoginResponse {
+      jwt: Some(
+        create_login_session(
+          updated_local_user.id,
+          context.pool(),
+          context.secret(),
+          &context.settings(),
+        )
+        .await?,
+      ),
+      verify_email_sent: false,
+      registration_created: false,
+    })
+  }
+}
+
+#[async_trait::async_trait(?Send)]
+impl Perform for GetReportCount {
+  type Response = GetReportCountResponse;
+
+  #[tracing::instrument(skip(context, _websocket_id))]
+  async fn perform(
+    &self,
+    context: &Data<LemmyContext>,
+    _websocket_id: Option<ConnectionId>,
+  ) -> Result<GetReportCountResponse, LemmyError> {
+    let data: &GetReportCount = self;
+    let local_user_view =
+      get_local_user_view_from_jwt(&data.auth, context.pool(), context.secret()).await?;
+
+    let person_id = local_user_view.person.id;
+    let admin = local_user_view.person.admin;
+    let community_id = data.community_id;
+
+    let comment_reports = blocking(context.pool(), move |conn| {
+      CommentReportView::get_report_count(conn, person_id, admin, community_id)
+    })
+    .await??;
+
+    let post_reports = blocking(context.pool(), move |conn| {
+      PostReportView::get_report_count(conn, person_id, admin, community_id)
+    })
+    .await??;
+
+    // Admins also see how many reports were passed on to them
+    let (escalated_comment_reports, escalated_post_reports) = if admin {
+      let counts = blocking(context.pool(), move |conn| {
+        Ok((
+          CommentReportView::get_escalated_report_count(conn)?,
+          PostReportView::get_escalated_report_count(conn)?,
+        )) as Result<(i64, i64), LemmyError>
+      })
+      .await??;
+      (Some(counts.0), Some(counts.1))
+    } else {
+      (None, None)
+    };
+
+    let res = GetReportCountResponse {
+      community_id,
+      comment_reports,
+      post_reports,
+      escalated_comment_reports,
+      escalated_post_reports,
+    };
+
+    Ok(res)
+  }
+}
+
+#[async_trait::async_trait(?Send)]
+impl Perform for BulkResolveReports {
+  type Response = BulkResolveReportsResponse;
+
+  #[tracing::instrument(skip(context, websocket_id))]
+  async fn perform(
+    &self,
+    context: &Data<LemmyContext>,
+    websocket_id: Option<ConnectionId>,
+  ) -> Result<BulkResolveReportsResponse, LemmyError> {
+    let data: &BulkResolveReports = self;
+    let local_user_view =
+      get_local_user_view_from_jwt(&data.auth, context.pool(), context.secret()).await?;
+
+    if data.post_id.is_none() && data.comment_id.is_none() {
+      return Err(LemmyError::from_message("no_report_target"));
+    }
+    let resolution_note = check_resolution_note(&data.resolution_note)?;
+    check_slurs_opt(&resolution_note, &context.settings().slur_regex())?;
+
+    let person_id = local_user_view.person.id;
+    let mut community_ids = vec![];
+
+    let mut post_reports_resolved = 0;
+    if let Some(post_id) = data.post_id {
+      let post = blocking(context.pool(), move |conn| Post::read(conn, post_id)).await??;
+      is_mod_or_admin(context.pool(), person_id, post.community_id).await?;
+      community_ids.push(post.community_id);
+
+      let note = resolution_note.clone();
+      post_reports_resolved = blocking(context.pool(), move |conn| {
+        PostReport::resolve_all_for_post(conn, post_id, person_id, note)
+      })
+      .await?
+      .map_err(LemmyError::from)
+      .map_err(|e| e.with_message("couldnt_resolve_report"))?;
+    }
+
+    let mut comment_reports_resolved = 0;
+    if let Some(comment_id) = data.comment_id {
+      let comment_view = blocking(context.pool(), move |conn| {
+        CommentView::read(conn, comment_id, None)
+      })
+      .await??;
+      is_mod_or_admin(context.pool(), person_id, comment_view.community.id).await?;
+      community_ids.push(comment_view.community.id);
+
+      comment_reports_resolved = blocking(context.pool(), move |conn| {
+        CommentReport::resolve_all_for_comment(conn, comment_id, person_id, resolution_note)
+      })
+      .await?
+      .map_err(LemmyError::from)
+      .map_err(|e| e.with_message("couldnt_resolve_report"))?;
+    }
+
+    let res = BulkResolveReportsResponse {
+      post_reports_resolved: post_reports_resolved as i64,
+      comment_reports_resolved: comment_reports_resolved as i64,
+    };
+
+    community_ids.dedup();
+    for community_id in community_ids {
+      context.chat_server().do_send(SendModRoomMessage {
+        op: UserOperation::BulkResolveReports,
+        response: res.clone(),
+        community_id,
+        websocket_id,
+      });
+    }
+
+    Ok(res)
+  }
+}
+
+#[async_trait::async_trait(?Send)]
+impl Perform for GetUnreadCount {
+  type Response = GetUnreadCountResponse;
+
+  #[tracing::instrument(skip(context, _websocket_id))]
+  async fn perform(
+    &self,
+    context: &Data<LemmyContext>,
+    _websocket_id: Option<ConnectionId>,
+  ) -> Result<Self::Response, LemmyError> {
+    let data = self;
+    let local_user_view =
+      get_local_user_view_from_jwt(&data.auth, context.pool(), context.secret()).await?;
+
+    let person_id = local_user_view.person.id;
+
+    let replies = blocking(context.pool(), move |conn| {
+      CommentView::get_unread_replies(conn, person_id)
+    })
+    .await??;
+
+    let mentions = blocking(context.pool(), move |conn| {
+      PersonMentionView::get_unread_mentions(conn, person_id)
+    })
+    .await??;
+
+    let private_messages = blocking(context.pool(), move |conn| {
+      PrivateMessageView::get_unread_messages(conn, person_id)
+    })
+    .await??;
+
+    let res = Self::Response {
+      replies,
+      mentions,
+      private_messages,
+    };
+
+    Ok(res)
+  }
+}
+
+#[async_trait::async_trait(?Send)]
+impl Perform for VerifyEmail {
+  type Response = VerifyEmailResponse;
+
+  async fn perform(
+    &self,
+    context: &Data<LemmyContext>,
+    _websocket_id: Option<usize>,
+  ) -> Result<Self::Response, LemmyError> {
+    let token = self.token.clone();
+    let verification = blocking(context.pool(), move |conn| {
+      EmailVerification::read_for_token(conn, &token)
+    })
+    .await?
+    .map_err(LemmyError::from)
+    .map_err(|e| e.with_message("token_not_found"))?;
+
+    let form = LocalUserForm {
+      // necessary in case this is a new signup
+      email_verified: Some(true),
+      // necessary in case email of an existing user was changed
+      email: Some(Some(verification.email)),
+      ..LocalUserForm::default()
+    };
+    let local_user_id = verification.local_user_id;
+    blocking(context.pool(), move |conn| {
+      LocalUser::update(conn, local_user_id, &form)
+    })
+    .await??;
+
+    let local_user_view = blocking(context.pool(), move |conn| {
+      LocalUserView::read(conn, local_user_id)
+    })
+    .await??;
+
+    send_email_verification_success(&local_user_view, &context.settings())?;
+
+    blocking(context.pool(), move |conn| {
+      EmailVerification::delete_old_tokens_for_local_user(conn, local_user_id)
+    })
+    .await??;
+
+    Ok(VerifyEmailResponse {})
   }
 }
 
 #[async_trait::async_trait(?Send)]
-impl Perform for PasswordReset {
-  type Response = PasswordResetResponse;
+impl Perform for ResendVerificationEmail {
+  type Response = ResendVerificationEmailResponse;
 
   #[tracing::instrument(skip(self, context, _websocket_id))]
   async fn perform(
     &self,
     context: &Data<LemmyContext>,
     _websocket_id: Option<ConnectionId>,
-  ) -> Result<PasswordResetResponse, LemmyError> {
-    let data: &PasswordReset = self;
-
-    // Fetch that email
-    let email = data.email.clone();
+  ) -> Result<Self::Response, LemmyError> {
+    let email = self.email.clone();
     let local_user_view = blocking(context.pool(), move |conn| {
       LocalUserView::find_by_email(conn, &email)
     })
@@ -784,104 +2106,135 @@ impl Perform for PasswordReset {
     .map_err(LemmyError::from)
     .map_err(|e| e.with_message("couldnt_find_that_username_or_email"))?;
 
-    // Email the pure token to the user.
-    send_password_reset_email(&local_user_view, context.pool(), &context.settings()).await?;
-    Ok(PasswordResetResponse {})
+    if local_user_view.local_user.email_verified {
+      return Err(LemmyError::from_message("email_already_verified"));
+    }
+    // With applications, the verification email is only sent after approval
+    let site = blocking(context.pool(), Site::read_simple).await??;
+    check_registration_application(&site, &local_user_view, context.pool()).await?;
+
+    // Only the newest link stays valid
+    let local_user_id = local_user_view.local_user.id;
+    blocking(context.pool(), move |conn| {
+      EmailVerification::delete_old_tokens_for_local_user(conn, local_user_id)
+    })
+    .await??;
+
+    send_verification_email(
+      local_user_id,
+      &self.email,
+      &local_user_view.person.name,
+      context.pool(),
+      &context.settings(),
+    )
+    .await?;
+
+    Ok(ResendVerificationEmailResponse {})
   }
 }
 
 #[async_trait::async_trait(?Send)]
-impl Perform for PasswordChange {
-  type Response = LoginResponse;
+impl Perform for CreateUserToken {
+  type Response = CreateUserTokenResponse;
 
-  #[tracing::instrument(skip(self, context, _websocket_id))]
+  #[tracing::instrument(skip(context, _websocket_id))]
   async fn perform(
     &self,
     context: &Data<LemmyContext>,
     _websocket_id: Option<ConnectionId>,
-  ) -> Result<LoginResponse, LemmyError> {
-    let data: &PasswordChange = self;
+  ) -> Result<Self::Response, LemmyError> {
+    let data: &CreateUserToken = self;
+    let local_user_view =
+      get_local_user_view_from_jwt(&data.auth, context.pool(), context.secret()).await?;
 
-    // Fetch the user_id from the token
-    let token = data.token.clone();
-    let local_user_id = blocking(context.pool(), move |conn| {
-      PasswordResetRequest::read_from_token(conn, &token).map(|p| p.local_user_id)
+    let name = data.name.trim().to_string();
+    if name.is_empty() || name.chars().count() > 100 {
+      return Err(LemmyError::from_message("invalid_token_name"));
+    }
+    if data.scopes.is_empty() {
+      return Err(LemmyError::from_message("token_scopes_required"));
+    }
+
+    let token = format!(
+      "{}{}",
+      PERSONAL_ACCESS_TOKEN_PREFIX,
+      generate_random_string()
+    );
+    let local_user_id = local_user_view.local_user.id;
+    let scopes = data.scopes.to_owned();
+    let token_ = token.to_owned();
+    let user_token = blocking(context.pool(), move |conn| {
+      UserToken::create(conn, local_user_id, &name, &token_, &scopes)
     })
     .await??;
 
-    password_length_check(&data.password)?;
+    Ok(CreateUserTokenResponse {
+      user_token,
+      token: token.into(),
+    })
+  }
+}
 
-    // Make sure passwords match
-    if data.password != data.password_verify {
-      return Err(LemmyError::from_message("passwords_dont_match"));
-    }
+#[async_trait::async_trait(?Send)]
+impl Perform for ListUserTokens {
+  type Response = ListUserTokensResponse;
 
-    // Update the user with the new password
-    let password = data.password.clone();
-    let updated_local_user = blocking(context.pool(), move |conn| {
-      LocalUser::update_password(conn, local_user_id, &password)
-    })
-    .await?
-    .map_err(LemmyError::from)
-    .map_err(|e| e.with_message("couldnt_update_user"))?;
+  #[tracing::instrument(skip(context, _websocket_id))]
+  async fn perform(
+    &self,
+    context: &Data<LemmyContext>,
+    _websocket_id: Option<ConnectionId>,
+  ) -> Result<Self::Response, LemmyError> {
+    let data: &ListUserTokens = self;
+    let local_user_view =
+      get_local_user_view_from_jwt(&data.auth, context.pool(), context.secret()).await?;
 
-    // Return the jwt
-    Ok(LoginResponse {
-      jwt: Some(
-        Claims::jwt(
-          updated_local_user.id.0,
-          &context.secret().jwt_secret,
-          &context.settings().hostname,
-        )?
-        .into(),
-      ),
-      verify_email_sent: false,
-      registration_created: false,
+    let local_user_id = local_user_view.local_user.id;
+    let user_tokens = blocking(context.pool(), move |conn| {
+      UserToken::list_for_local_user(conn, local_user_id)
     })
+    .await??;
+
+    Ok(ListUserTokensResponse { user_tokens })
   }
 }
 
 #[async_trait::async_trait(?Send)]
-impl Perform for GetReportCount {
-  type Response = GetReportCountResponse;
+impl Perform for RevokeUserToken {
+  type Response = ListUserTokensResponse;
 
   #[tracing::instrument(skip(context, _websocket_id))]
   async fn perform(
     &self,
     context: &Data<LemmyContext>,
     _websocket_id: Option<ConnectionId>,
-  ) -> Result<GetReportCountResponse, LemmyError> {
-    let data: &GetReportCount = self;
+  ) -> Result<Self::Response, LemmyError> {
+    let data: &RevokeUserToken = self;
     let local_user_view =
       get_local_user_view_from_jwt(&data.auth, context.pool(), context.secret()).await?;
 
-    let person_id = local_user_view.person.id;
-    let admin = local_user_view.person.admin;
-    let community_id = data.community_id;
-
-    let comment_reports = blocking(context.pool(), move |conn| {
-      CommentReportView::get_report_count(conn, person_id, admin, community_id)
+    let local_user_id = local_user_view.local_user.id;
+    let token_id = data.id;
+    let deleted = blocking(context.pool(), move |conn| {
+      UserToken::delete_for_local_user(conn, token_id, local_user_id)
     })
     .await??;
+    if deleted == 0 {
+      return Err(LemmyError::from_message("couldnt_find_token"));
+    }
 
-    let post_reports = blocking(context.pool(), move |conn| {
-      PostReportView::get_report_count(conn, person_id, admin, community_id)
+    let user_tokens = blocking(context.pool(), move |conn| {
+      UserToken::list_for_local_user(conn, local_user_id)
     })
     .await??;
 
-    let res = GetReportCountResponse {
-      community_id,
-      comment_reports,
-      post_reports,
-    };
-
-    Ok(res)
+    Ok(ListUserTokensResponse { user_tokens })
   }
 }
 
 #[async_trait::async_trait(?Send)]
-impl Perform for GetUnreadCount {
-  type Response = GetUnreadCountResponse;
+impl Perform for GenerateFeedToken {
+  type Response = GenerateFeedTokenResponse;
 
   #[tracing::instrument(skip(context, _websocket_id))]
   async fn perform(
@@ -889,79 +2242,369 @@ impl Perform for GetUnreadCount {
     context: &Data<LemmyContext>,
     _websocket_id: Option<ConnectionId>,
   ) -> Result<Self::Response, LemmyError> {
-    let data = self;
+    let data: &GenerateFeedToken = self;
     let local_user_view =
       get_local_user_view_from_jwt(&data.auth, context.pool(), context.secret()).await?;
 
-    let person_id = local_user_view.person.id;
+    let feed_token = generate_random_string();
+    let local_user_id = local_user_view.local_user.id;
+    let token_ = feed_token.to_owned();
+    blocking(context.pool(), move |conn| {
+      FeedToken::replace(conn, local_user_id, &token_)
+    })
+    .await?
+    .map_err(LemmyError::from)
+    .map_err(|e| e.with_message("couldnt_generate_feed_token"))?;
 
-    let replies = blocking(context.pool(), move |conn| {
-      CommentView::get_unread_replies(conn, person_id)
+    Ok(GenerateFeedTokenResponse {
+      feed_token: feed_token.into(),
     })
-    .await??;
+  }
+}
 
-    let mentions = blocking(context.pool(), move |conn| {
-      PersonMentionView::get_unread_mentions(conn, person_id)
+/// Id of the login session behind a jwt, personal access tokens don't have one
+fn current_session_id(auth: &str, context: &LemmyContext) -> Option<i32> {
+  Claims::decode(auth, &context.secret().jwt_secret)
+    .ok()
+    .and_then(|c| c.claims.sid)
+}
+
+#[async_trait::async_trait(?Send)]
+impl Perform for ListSessions {
+  type Response = ListSessionsResponse;
+
+  #[tracing::instrument(skip(context, _websocket_id))]
+  async fn perform(
+    &self,
+    context: &Data<LemmyContext>,
+    _websocket_id: Option<ConnectionId>,
+  ) -> Result<Self::Response, LemmyError> {
+    let data: &ListSessions = self;
+    let local_user_view =
+      get_local_user_view_from_jwt(&data.auth, context.pool(), context.secret()).await?;
+
+    let local_user_id = local_user_view.local_user.id;
+    let sessions = blocking(context.pool(), move |conn| {
+      LoginSession::list_for_local_user(conn, local_user_id)
     })
     .await??;
 
-    let private_messages = blocking(context.pool(), move |conn| {
-      PrivateMessageView::get_unread_messages(conn, person_id)
+    Ok(ListSessionsResponse {
+      sessions,
+      current_session_id: current_session_id(&data.auth, context),
+    })
+  }
+}
+
+#[async_trait::async_trait(?Send)]
+impl Perform for RevokeSession {
+  type Response = ListSessionsResponse;
+
+  #[tracing::instrument(skip(context, _websocket_id))]
+  async fn perform(
+    &self,
+    context: &Data<LemmyContext>,
+    _websocket_id: Option<ConnectionId>,
+  ) -> Result<Self::Response, LemmyError> {
+    let data: &RevokeSession = self;
+    let local_user_view =
+      get_local_user_view_from_jwt(&data.auth, context.pool(), context.secret()).await?;
+
+    let local_user_id = local_user_view.local_user.id;
+    let session_id = data.id;
+    let deleted = blocking(context.pool(), move |conn| {
+      LoginSession::delete_for_local_user(conn, session_id, local_user_id)
     })
     .await??;
+    if deleted == 0 {
+      return Err(LemmyError::from_message("couldnt_find_session"));
+    }
 
-    let res = Self::Response {
-      replies,
-      mentions,
-      private_messages,
-    };
+    let sessions = blocking(context.pool(), move |conn| {
+      LoginSession::list_for_local_user(conn, local_user_id)
+    })
+    .await??;
 
-    Ok(res)
+    Ok(ListSessionsResponse {
+      sessions,
+      current_session_id: current_session_id(&data.auth, context),
+    })
   }
 }
 
 #[async_trait::async_trait(?Send)]
-impl Perform for VerifyEmail {
-  type Response = VerifyEmailResponse;
+impl Perform for RevokeAllSessions {
+  type Response = RevokeAllSessionsResponse;
 
+  #[tracing::instrument(skip(context, _websocket_id))]
   async fn perform(
     &self,
     context: &Data<LemmyContext>,
-    _websocket_id: Option<usize>,
+    _websocket_id: Option<ConnectionId>,
   ) -> Result<Self::Response, LemmyError> {
-    let token = self.token.clone();
-    let verification = blocking(context.pool(), move |conn| {
-      EmailVerification::read_for_token(conn, &token)
+    let data: &RevokeAllSessions = self;
+    let local_user_view =
+      get_local_user_view_from_jwt(&data.auth, context.pool(), context.secret()).await?;
+
+    // Also bump the validator time, so that tokens issued before sessions were tracked stop working.
+    // Personal access tokens are revoked too, as this is what to do after an account compromise.
+    let local_user_id = local_user_view.local_user.id;
+    blocking(context.pool(), move |conn| {
+      LoginSession::delete_all_for_local_user(conn, local_user_id)?;
+      UserToken::delete_all_for_local_user(conn, local_user_id)?;
+      LocalUser::update_validator_time(conn, local_user_id)
     })
     .await?
     .map_err(LemmyError::from)
-    .map_err(|e| e.with_message("token_not_found"))?;
+    .map_err(|e| e.with_message("couldnt_update_user"))?;
 
-    let form = LocalUserForm {
-      // necessary in case this is a new signup
-      email_verified: Some(true),
-      // necessary in case email of an existing user was changed
-      email: Some(Some(verification.email)),
+    Ok(RevokeAllSessionsResponse {})
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::tests::init_context;
+  use diesel::{ExpressionMethods, QueryDsl, RunQueryDsl};
+  use lemmy_api_common::{create_login_session, site::ApproveRegistrationApplication};
+  use lemmy_db_schema::{
+    schema::{activity_send_queue, email_verification},
+    source::{
+      comment::CommentForm,
+      community::{CommunityForm, CommunityModerator, CommunityModeratorForm},
+      local_user::{LocalUser, LocalUserForm},
+      person::PersonForm,
+      post::PostForm,
+      registration_application::{RegistrationApplication, RegistrationApplicationForm},
+      site::SiteForm,
+    },
+    traits::Joinable,
+  };
+  use lemmy_db_views_moderator::mod_ban_from_community_view::ModBanFromCommunityView;
+  use serial_test::serial;
+
+  #[actix_rt::test]
+  #[serial]
+  async fn test_remove_content_and_ban_twice() {
+    let context = Data::new(init_context());
+    let conn = context.pool().get().unwrap();
+
+    let person_form = PersonForm {
+      name: "cleanup_mod".into(),
+      ..PersonForm::default()
+    };
+    let moderator = Person::create(&conn, &person_form).unwrap();
+    let local_user_form = LocalUserForm {
+      person_id: Some(moderator.id),
+      password_encrypted: Some("cleanup_pass".to_string()),
       ..LocalUserForm::default()
     };
-    let local_user_id = verification.local_user_id;
-    blocking(context.pool(), move |conn| {
-      LocalUser::update(conn, local_user_id, &form)
-    })
-    .await??;
+    let local_user = LocalUser::create(&conn, &local_user_form).unwrap();
+    let auth = create_login_session(
+      local_user.id,
+      context.pool(),
+      context.secret(),
+      &context.settings(),
+    )
+    .await
+    .unwrap();
+    let person_form = PersonForm {
+      name: "cleanup_spammer".into(),
+      ..PersonForm::default()
+    };
+    let spammer = Person::create(&conn, &person_form).unwrap();
+    let community_form = CommunityForm {
+      name: "cleanup_community".to_string(),
+      title: "nada".to_owned(),
+      ..CommunityForm::default()
+    };
+    let community = Community::create(&conn, &community_form).unwrap();
+    let moderator_form = CommunityModeratorForm {
+      community_id: community.id,
+      person_id: moderator.id,
+    };
+    CommunityModerator::join(&conn, &moderator_form).unwrap();
+    let post_form = PostForm {
+      name: "spam".into(),
+      creator_id: spammer.id,
+      community_id: community.id,
+      ..PostForm::default()
+    };
+    let post = Post::create(&conn, &post_form).unwrap();
+    let comment_form = CommentForm {
+      content: "more spam".into(),
+      creator_id: spammer.id,
+      post_id: post.id,
+      ..CommentForm::default()
+    };
+    Comment::create(&conn, &comment_form).unwrap();
+
+    let remove = RemoveContentAndBan {
+      person_id: spammer.id,
+      community_id: Some(community.id),
+      reason: Some("spam".to_string()),
+      reason_override: None,
+      expires: None,
+      auth,
+    };
+    let first = remove.perform(&context, None).await.unwrap();
+    // Running it again doesn't fail because the person is already banned
+    let second = remove.perform(&context, None).await.unwrap();
+    let is_banned = CommunityPersonBanView::get(&conn, spammer.id, community.id).is_ok();
+    let bans = ModBanFromCommunityView::list(&conn, Some(community.id), None, None, None).unwrap();
+    let read_post = Post::read(&conn, post.id).unwrap();
+
+    diesel::delete(
+      activity_send_queue::table.filter(activity_send_queue::actor_id.eq(&moderator.actor_id)),
+    )
+    .execute(&*conn)
+    .unwrap();
+    Person::delete(&conn, moderator.id).unwrap();
+    Person::delete(&conn, spammer.id).unwrap();
+    Community::delete(&conn, community.id).unwrap();
+
+    assert_eq!((1, 1), (first.removed_posts, first.removed_comments));
+    assert_eq!((0, 0), (second.removed_posts, second.removed_comments));
+    assert!(is_banned);
+    assert_eq!(1, bans.len());
+    assert!(read_post.removed);
+  }
 
-    let local_user_view = blocking(context.pool(), move |conn| {
-      LocalUserView::read(conn, local_user_id)
-    })
-    .await??;
+  #[actix_rt::test]
+  #[serial]
+  async fn test_resend_verification_email_needs_approval() {
+    let context = Data::new(init_context());
+    let conn = context.pool().get().unwrap();
 
-    send_email_verification_success(&local_user_view, &context.settings())?;
+    let person_form = PersonForm {
+      name: "resend_applicant".into(),
+      ..PersonForm::default()
+    };
+    let applicant = Person::create(&conn, &person_form).unwrap();
+    let local_user_form = LocalUserForm {
+      person_id: Some(applicant.id),
+      password_encrypted: Some("resend_pass".to_string()),
+      email: Some(Some("resend_applicant@example.com".to_string())),
+      ..LocalUserForm::default()
+    };
+    let local_user = LocalUser::create(&conn, &local_user_form).unwrap();
+    let application_form = RegistrationApplicationForm {
+      local_user_id: Some(local_user.id),
+      answer: Some("let me in".to_string()),
+      ..RegistrationApplicationForm::default()
+    };
+    RegistrationApplication::create(&conn, &application_form).unwrap();
+    let site_form = SiteForm {
+      name: "resend_site".into(),
+      creator_id: applicant.id,
+      require_application: Some(true),
+      ..SiteForm::default()
+    };
+    let site = Site::create(&conn, &site_form).unwrap();
 
-    blocking(context.pool(), move |conn| {
-      EmailVerification::delete_old_tokens_for_local_user(conn, local_user_id)
-    })
-    .await??;
+    let resend = ResendVerificationEmail {
+      email: "resend_applicant@example.com".into(),
+    };
+    let pending = resend.perform(&context, None).await;
 
-    Ok(VerifyEmailResponse {})
+    Site::delete(&conn, site.id).unwrap();
+    Person::delete(&conn, applicant.id).unwrap();
+
+    assert_eq!(
+      Some("registration_application_pending"),
+      pending.unwrap_err().message
+    );
+  }
+
+  #[actix_rt::test]
+  #[serial]
+  async fn test_verification_email_sent_after_approval() {
+    let context = Data::new(init_context());
+    let conn = context.pool().get().unwrap();
+
+    let person_form = PersonForm {
+      name: "approval_admin".into(),
+      admin: Some(true),
+      ..PersonForm::default()
+    };
+    let admin = Person::create(&conn, &person_form).unwrap();
+    let local_user_form = LocalUserForm {
+      person_id: Some(admin.id),
+      password_encrypted: Some("approval_pass".to_string()),
+      ..LocalUserForm::default()
+    };
+    let admin_local_user = LocalUser::create(&conn, &local_user_form).unwrap();
+    let auth = create_login_session(
+      admin_local_user.id,
+      context.pool(),
+      context.secret(),
+      &context.settings(),
+    )
+    .await
+    .unwrap();
+    let person_form = PersonForm {
+      name: "approval_applicant".into(),
+      ..PersonForm::default()
+    };
+    let applicant = Person::create(&conn, &person_form).unwrap();
+    let local_user_form = LocalUserForm {
+      person_id: Some(applicant.id),
+      password_encrypted: Some("approval_pass".to_string()),
+      email: Some(Some("approval_applicant@example.com".to_string())),
+      ..LocalUserForm::default()
+    };
+    let local_user = LocalUser::register(&conn, &local_user_form).unwrap();
+    let application_form = RegistrationApplicationForm {
+      local_user_id: Some(local_user.id),
+      answer: Some("let me in".to_string()),
+      ..RegistrationApplicationForm::default()
+    };
+    let application = RegistrationApplication::create(&conn, &application_form).unwrap();
+    let site_form = SiteForm {
+      name: "approval_site".into(),
+      creator_id: admin.id,
+      require_application: Some(true),
+      require_email_verification: Some(true),
+      ..SiteForm::default()
+    };
+    let site = Site::create(&conn, &site_form).unwrap();
+
+    let login = Login {
+      username_or_email: "approval_applicant".to_string().into(),
+      password: "approval_pass".to_string().into(),
+      totp_token: None,
+    };
+    // The application is checked first, as there is no email to verify yet
+    let before_approval = login.perform(&context, None).await;
+    let approve = ApproveRegistrationApplication {
+      id: application.id,
+      approve: true,
+      deny_reason: None,
+      auth: auth.to_string(),
+    };
+    // Succeeds even though there is no email server in tests
+    let approved = approve.perform(&context, None).await;
+    let verifications = email_verification::table
+      .filter(email_verification::local_user_id.eq(local_user.id))
+      .count()
+      .get_result::<i64>(&*conn)
+      .unwrap();
+    let after_approval = login.perform(&context, None).await;
+
+    Site::delete(&conn, site.id).unwrap();
+    Person::delete(&conn, applicant.id).unwrap();
+    Person::delete(&conn, admin.id).unwrap();
+
+    assert_eq!(
+      Some("registration_application_pending"),
+      before_approval.unwrap_err().message
+    );
+    assert!(approved.is_ok());
+    assert_eq!(1, verifications);
+    assert_eq!(
+      Some("email_not_verified"),
+      after_approval.unwrap_err().message
+    );
   }
 }
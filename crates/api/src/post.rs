@@ -6,6 +6,8 @@ use lemmy_api_common::{
   check_community_deleted_or_removed,
   check_downvotes_enabled,
   check_person_block,
+  check_removal_reason,
+  check_saved_collection_owner,
   get_local_user_view_from_jwt,
   is_mod_or_admin,
   mark_post_as_read,
@@ -14,10 +16,12 @@ use lemmy_api_common::{
 };
 use lemmy_apub::{
   fetcher::post_or_comment::PostOrComment,
-  objects::post::ApubPost,
+  objects::{community::ApubCommunity, post::ApubPost},
   protocol::activities::{
+    community::feature_post::FeaturePost,
     create_or_update::post::CreateOrUpdatePost,
     voting::{
+      poll_vote::PollVote,
       undo_vote::UndoVote,
       vote::{Vote, VoteType},
     },
@@ -25,12 +29,33 @@ use lemmy_apub::{
   },
 };
 use lemmy_db_schema::{
-  source::{moderator::*, post::*},
-  traits::{Crud, Likeable, Saveable},
+  clock::{Clock, SystemClock},
+  source::{
+    community::Community,
+    moderator::*,
+    person::Person,
+    poll::{Poll, PollOption, PollVote as DbPollVote, PollVoteForm},
+    post::*,
+  },
+  traits::{Crud, Likeable, Reactable, Saveable},
+  SortType,
+};
+use lemmy_db_views::{
+  poll_view::PollView,
+  post_view::{PostQueryBuilder, PostView},
+};
+use lemmy_utils::{
+  request::fetch_site_metadata,
+  utils::is_valid_reaction,
+  ConnectionId,
+  LemmyError,
+};
+use lemmy_websocket::{
+  messages::RemovalInfo,
+  send::{send_post_removal_ws_message, send_post_ws_message},
+  LemmyContext,
+  UserOperation,
 };
-use lemmy_db_views::post_view::PostView;
-use lemmy_utils::{request::fetch_site_metadata, ConnectionId, LemmyError};
-use lemmy_websocket::{send::send_post_ws_message, LemmyContext, UserOperation};
 use std::convert::TryInto;
 
 #[async_trait::async_trait(?Send)]
@@ -121,6 +146,122 @@ impl Perform for CreatePostLike {
   }
 }
 
+#[async_trait::async_trait(?Send)]
+impl Perform for ReactPost {
+  type Response = PostResponse;
+
+  #[tracing::instrument(skip(context, websocket_id))]
+  async fn perform(
+    &self,
+    context: &Data<LemmyContext>,
+    websocket_id: Option<ConnectionId>,
+  ) -> Result<PostResponse, LemmyError> {
+    let data: &ReactPost = self;
+    let local_user_view =
+      get_local_user_view_from_jwt(&data.auth, context.pool(), context.secret()).await?;
+
+    if !is_valid_reaction(&data.emoji) {
+      return Err(LemmyError::from_message("invalid_reaction"));
+    }
+
+    let post_id = data.post_id;
+    let post: ApubPost = blocking(context.pool(), move |conn| Post::read(conn, post_id))
+      .await??
+      .into();
+
+    check_community_ban(local_user_view.person.id, post.community_id, context.pool()).await?;
+    check_community_deleted_or_removed(post.community_id, context.pool()).await?;
+    check_person_block(local_user_view.person.id, post.creator_id, context.pool()).await?;
+
+    let reaction_form = PostReactionForm {
+      post_id,
+      person_id: local_user_view.person.id,
+      emoji: data.emoji.to_owned(),
+    };
+    let inserted = blocking(context.pool(), move |conn| {
+      PostReaction::react(conn, &reaction_form)
+    })
+    .await?
+    .map_err(LemmyError::from)
+    .map_err(|e| e.with_message("couldnt_react_to_post"))?;
+
+    // Reacting twice with the same emoji changes nothing, so there is nothing to federate
+    if inserted > 0 {
+      let community_id = post.community_id;
+      let object = PostOrComment::Post(Box::new(post));
+      Vote::send_reaction(
+        &object,
+        &local_user_view.person.clone().into(),
+        community_id,
+        data.emoji.to_owned(),
+        context,
+      )
+      .await?;
+    }
+
+    send_post_ws_message(
+      post_id,
+      UserOperation::ReactPost,
+      websocket_id,
+      Some(local_user_view.person.id),
+      context,
+    )
+    .await
+  }
+}
+
+#[async_trait::async_trait(?Send)]
+impl Perform for UnreactPost {
+  type Response = PostResponse;
+
+  #[tracing::instrument(skip(context, websocket_id))]
+  async fn perform(
+    &self,
+    context: &Data<LemmyContext>,
+    websocket_id: Option<ConnectionId>,
+  ) -> Result<PostResponse, LemmyError> {
+    let data: &UnreactPost = self;
+    let local_user_view =
+      get_local_user_view_from_jwt(&data.auth, context.pool(), context.secret()).await?;
+
+    let post_id = data.post_id;
+    let post: ApubPost = blocking(context.pool(), move |conn| Post::read(conn, post_id))
+      .await??
+      .into();
+
+    let person_id = local_user_view.person.id;
+    let emoji = data.emoji.to_owned();
+    let removed = blocking(context.pool(), move |conn| {
+      PostReaction::unreact(conn, person_id, post_id, &emoji)
+    })
+    .await?
+    .map_err(LemmyError::from)
+    .map_err(|e| e.with_message("couldnt_react_to_post"))?;
+
+    if removed > 0 {
+      let community_id = post.community_id;
+      let object = PostOrComment::Post(Box::new(post));
+      UndoVote::send_reaction(
+        &object,
+        &local_user_view.person.clone().into(),
+        community_id,
+        data.emoji.to_owned(),
+        context,
+      )
+      .await?;
+    }
+
+    send_post_ws_message(
+      post_id,
+      UserOperation::UnreactPost,
+      websocket_id,
+      Some(local_user_view.person.id),
+      context,
+    )
+    .await
+  }
+}
+
 #[async_trait::async_trait(?Send)]
 impl Perform for MarkPostAsRead {
   type Response = PostResponse;
@@ -227,6 +368,88 @@ impl Perform for LockPost {
   }
 }
 
+#[async_trait::async_trait(?Send)]
+impl Perform for ShadowRemovePost {
+  type Response = PostResponse;
+
+  #[tracing::instrument(skip(context, websocket_id))]
+  async fn perform(
+    &self,
+    context: &Data<LemmyContext>,
+    websocket_id: Option<ConnectionId>,
+  ) -> Result<PostResponse, LemmyError> {
+    let data: &ShadowRemovePost = self;
+    let local_user_view =
+      get_local_user_view_from_jwt(&data.auth, context.pool(), context.secret()).await?;
+
+    let post_id = data.post_id;
+    let orig_post = blocking(context.pool(), move |conn| Post::read(conn, post_id)).await??;
+
+    check_community_ban(
+      local_user_view.person.id,
+      orig_post.community_id,
+      context.pool(),
+    )
+    .await?;
+
+    // Verify that only the mods can shadow remove
+    is_mod_or_admin(
+      context.pool(),
+      local_user_view.person.id,
+      orig_post.community_id,
+    )
+    .await?;
+    let reason_override = if data.shadow_removed {
+      check_removal_reason(
+        &data.reason,
+        context.settings().moderation.require_removal_reason,
+        data.reason_override,
+        local_user_view.person.admin,
+      )?
+    } else {
+      false
+    };
+
+    // Update the post
+    let shadow_removed = data.shadow_removed;
+    blocking(context.pool(), move |conn| {
+      Post::update_shadow_removed(conn, post_id, shadow_removed)
+    })
+    .await?
+    .map_err(LemmyError::from)
+    .map_err(|e| e.with_message("couldnt_update_post"))?;
+
+    // Mod tables
+    let form = ModShadowRemovePostForm {
+      mod_person_id: local_user_view.person.id,
+      post_id: data.post_id,
+      reason: data.reason.to_owned(),
+      shadow_removed: Some(shadow_removed),
+      reason_override,
+    };
+    blocking(context.pool(), move |conn| {
+      ModShadowRemovePost::create(conn, &form)
+    })
+    .await??;
+
+    // Only a local visibility policy, so nothing is federated
+
+    send_post_removal_ws_message(
+      data.post_id,
+      UserOperation::ShadowRemovePost,
+      RemovalInfo {
+        mod_person_id: local_user_view.person.id,
+        reason: data.reason.to_owned(),
+      },
+      websocket_id,
+      Some(local_user_view.person.id),
+      vec![],
+      context,
+    )
+    .await
+  }
+}
+
 #[async_trait::async_trait(?Send)]
 impl Perform for StickyPost {
   type Response = PostResponse;
@@ -260,9 +483,27 @@ impl Perform for StickyPost {
     )
     .await?;
 
+    let community_id = orig_post.community_id;
+    let community: ApubCommunity = blocking(context.pool(), move |conn| {
+      Community::read(conn, community_id)
+    })
+    .await??
+    .into();
+
+    // Remote communities enforce their own limit
+    let stickied = data.stickied;
+    if stickied && !orig_post.stickied && community.local {
+      let stickied_count = blocking(context.pool(), move |conn| {
+        Post::count_stickied(conn, community_id)
+      })
+      .await??;
+      if stickied_count >= context.settings().moderation.max_stickied_posts {
+        return Err(LemmyError::from_message("too_many_stickied_posts"));
+      }
+    }
+
     // Update the post
     let post_id = data.post_id;
-    let stickied = data.stickied;
     let updated_post: ApubPost = blocking(context.pool(), move |conn| {
       Post::update_stickied(conn, post_id, stickied)
     })
@@ -281,11 +522,11 @@ impl Perform for StickyPost {
     .await??;
 
     // Apub updates
-    // TODO stickied should pry work like locked for ease of use
-    CreateOrUpdatePost::send(
-      updated_post,
+    FeaturePost::send(
+      &community,
+      &updated_post,
+      stickied,
       &local_user_view.person.clone().into(),
-      CreateOrUpdateType::Update,
       context,
     )
     .await?;
@@ -301,6 +542,176 @@ impl Perform for StickyPost {
   }
 }
 
+#[async_trait::async_trait(?Send)]
+impl Perform for ListPendingPosts {
+  type Response = ListPendingPostsResponse;
+
+  #[tracing::instrument(skip(context, _websocket_id))]
+  async fn perform(
+    &self,
+    context: &Data<LemmyContext>,
+    _websocket_id: Option<ConnectionId>,
+  ) -> Result<ListPendingPostsResponse, LemmyError> {
+    let data: &ListPendingPosts = self;
+    let local_user_view =
+      get_local_user_view_from_jwt(&data.auth, context.pool(), context.secret()).await?;
+    is_mod_or_admin(context.pool(), local_user_view.person.id, data.community_id).await?;
+
+    let person_id = local_user_view.person.id;
+    let community_id = data.community_id;
+    let page = data.page;
+    let limit = data.limit;
+    let posts = blocking(context.pool(), move |conn| {
+      PostQueryBuilder::create(conn)
+        .community_id(community_id)
+        .my_person_id(person_id)
+        .pending_only(true)
+        .show_nsfw(true)
+        .sort(SortType::New)
+        .page(page)
+        .limit(limit)
+        .list()
+    })
+    .await?
+    .map_err(LemmyError::from)
+    .map_err(|e| e.with_message("couldnt_get_posts"))?;
+
+    Ok(ListPendingPostsResponse { posts })
+  }
+}
+
+#[async_trait::async_trait(?Send)]
+impl Perform for ApprovePost {
+  type Response = PostResponse;
+
+  #[tracing::instrument(skip(context, websocket_id))]
+  async fn perform(
+    &self,
+    context: &Data<LemmyContext>,
+    websocket_id: Option<ConnectionId>,
+  ) -> Result<PostResponse, LemmyError> {
+    let data: &ApprovePost = self;
+    let local_user_view =
+      get_local_user_view_from_jwt(&data.auth, context.pool(), context.secret()).await?;
+
+    let post_id = data.post_id;
+    let orig_post = blocking(context.pool(), move |conn| Post::read(conn, post_id)).await??;
+    is_mod_or_admin(
+      context.pool(),
+      local_user_view.person.id,
+      orig_post.community_id,
+    )
+    .await?;
+    if !orig_post.pending || orig_post.removed || orig_post.deleted {
+      return Err(LemmyError::from_message("post_not_pending"));
+    }
+
+    let updated_post = blocking(context.pool(), move |conn| {
+      Post::update_pending(conn, post_id, false)
+    })
+    .await?
+    .map_err(LemmyError::from)
+    .map_err(|e| e.with_message("couldnt_update_post"))?;
+
+    // The post is only federated now, in the name of its creator
+    let creator_id = updated_post.creator_id;
+    let creator = blocking(context.pool(), move |conn| Person::read(conn, creator_id)).await??;
+    let community_id = updated_post.community_id;
+    let apub_post: ApubPost = updated_post.into();
+    CreateOrUpdatePost::send(
+      apub_post.clone(),
+      &creator.clone().into(),
+      CreateOrUpdateType::Create,
+      context,
+    )
+    .await?;
+    let object = PostOrComment::Post(Box::new(apub_post));
+    Vote::send(
+      &object,
+      &creator.into(),
+      community_id,
+      VoteType::Like,
+      context,
+    )
+    .await?;
+
+    send_post_ws_message(
+      data.post_id,
+      UserOperation::ApprovePost,
+      websocket_id,
+      Some(local_user_view.person.id),
+      context,
+    )
+    .await
+  }
+}
+
+#[async_trait::async_trait(?Send)]
+impl Perform for RejectPost {
+  type Response = PostResponse;
+
+  #[tracing::instrument(skip(context, websocket_id))]
+  async fn perform(
+    &self,
+    context: &Data<LemmyContext>,
+    websocket_id: Option<ConnectionId>,
+  ) -> Result<PostResponse, LemmyError> {
+    let data: &RejectPost = self;
+    let local_user_view =
+      get_local_user_view_from_jwt(&data.auth, context.pool(), context.secret()).await?;
+
+    let post_id = data.post_id;
+    let orig_post = blocking(context.pool(), move |conn| Post::read(conn, post_id)).await??;
+    is_mod_or_admin(
+      context.pool(),
+      local_user_view.person.id,
+      orig_post.community_id,
+    )
+    .await?;
+    if !orig_post.pending || orig_post.removed || orig_post.deleted {
+      return Err(LemmyError::from_message("post_not_pending"));
+    }
+
+    // The post stays pending, so that it doesn't show up in listings
+    blocking(context.pool(), move |conn| {
+      Post::update_removed(conn, post_id, true)
+    })
+    .await?
+    .map_err(LemmyError::from)
+    .map_err(|e| e.with_message("couldnt_update_post"))?;
+
+    let form = ModRemovePostForm {
+      mod_person_id: local_user_view.person.id,
+      post_id,
+      reason: data.reason.to_owned(),
+      removed: Some(true),
+      reason_translations: None,
+      reason_override: false,
+      public: None,
+    };
+    blocking(context.pool(), move |conn| {
+      ModRemovePost::create(conn, &form)
+    })
+    .await??;
+
+    // Pending posts were never federated, so there is nothing to undo
+
+    send_post_removal_ws_message(
+      data.post_id,
+      UserOperation::RejectPost,
+      RemovalInfo {
+        mod_person_id: local_user_view.person.id,
+        reason: data.reason.to_owned(),
+      },
+      websocket_id,
+      Some(local_user_view.person.id),
+      vec![],
+      context,
+    )
+    .await
+  }
+}
+
 #[async_trait::async_trait(?Send)]
 impl Perform for SavePost {
   type Response = PostResponse;
@@ -315,9 +726,19 @@ impl Perform for SavePost {
     let local_user_view =
       get_local_user_view_from_jwt(&data.auth, context.pool(), context.secret()).await?;
 
+    if data.save {
+      check_saved_collection_owner(
+        data.collection_id,
+        local_user_view.person.id,
+        context.pool(),
+      )
+      .await?;
+    }
+
     let post_saved_form = PostSavedForm {
       post_id: data.post_id,
       person_id: local_user_view.person.id,
+      collection_id: data.collection_id,
     };
 
     if data.save {
@@ -348,6 +769,95 @@ impl Perform for SavePost {
   }
 }
 
+#[async_trait::async_trait(?Send)]
+impl Perform for VotePoll {
+  type Response = PollResponse;
+
+  #[tracing::instrument(skip(context, _websocket_id))]
+  async fn perform(
+    &self,
+    context: &Data<LemmyContext>,
+    _websocket_id: Option<ConnectionId>,
+  ) -> Result<PollResponse, LemmyError> {
+    let data: &VotePoll = self;
+    let local_user_view =
+      get_local_user_view_from_jwt(&data.auth, context.pool(), context.secret()).await?;
+
+    let post_id = data.post_id;
+    let post = blocking(context.pool(), move |conn| Post::read(conn, post_id)).await??;
+    check_community_ban(local_user_view.person.id, post.community_id, context.pool()).await?;
+    check_community_deleted_or_removed(post.community_id, context.pool()).await?;
+
+    let person_id = local_user_view.person.id;
+    let option_ids = data.option_ids.clone();
+    let local_poll = post.local;
+    let chosen_options = blocking(context.pool(), move |conn| {
+      let poll = Poll::read_for_post(conn, post_id)
+        .map_err(LemmyError::from)
+        .map_err(|e| e.with_message("couldnt_find_poll"))?;
+      if poll.has_ended(SystemClock.now()) {
+        return Err(LemmyError::from_message("poll_ended"));
+      }
+      if option_ids.is_empty() || (!poll.multiple_choice && option_ids.len() > 1) {
+        return Err(LemmyError::from_message("invalid_poll_vote"));
+      }
+      if !DbPollVote::list_for_person(conn, poll.id, person_id)?.is_empty() {
+        return Err(LemmyError::from_message("already_voted_in_poll"));
+      }
+      let chosen_options: Vec<PollOption> = PollOption::list_for_poll(conn, poll.id)?
+        .into_iter()
+        .filter(|o| option_ids.contains(&o.id))
+        .collect();
+      if chosen_options.len() != option_ids.len() {
+        return Err(LemmyError::from_message("couldnt_find_poll_option"));
+      }
+      for option in &chosen_options {
+        let form = PollVoteForm {
+          poll_option_id: option.id,
+          person_id,
+        };
+        DbPollVote::vote(conn, &form)?;
+      }
+      // Counts of remote polls are updated once the origin instance federates them
+      if local_poll {
+        PollOption::update_vote_counts(conn, poll.id)?;
+      }
+      Ok(chosen_options)
+    })
+    .await??;
+
+    let apub_post: ApubPost = post.into();
+    if local_poll {
+      let creator_id = apub_post.creator_id;
+      let creator = blocking(context.pool(), move |conn| Person::read(conn, creator_id)).await??;
+      CreateOrUpdatePost::send(
+        apub_post,
+        &creator.into(),
+        CreateOrUpdateType::Update,
+        context,
+      )
+      .await?;
+    } else {
+      let option_names = chosen_options.into_iter().map(|o| o.name).collect();
+      PollVote::send(
+        &apub_post,
+        option_names,
+        &local_user_view.person.into(),
+        context,
+      )
+      .await?;
+    }
+
+    let poll_view = blocking(context.pool(), move |conn| {
+      PollView::read_for_post(conn, post_id, Some(person_id))
+    })
+    .await??
+    .ok_or_else(|| LemmyError::from_message("couldnt_find_poll"))?;
+
+    Ok(PollResponse { poll_view })
+  }
+}
+
 #[async_trait::async_trait(?Send)]
 impl Perform for GetSiteMetadata {
   type Response = GetSiteMetadataResponse;
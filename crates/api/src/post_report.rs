@@ -3,10 +3,12 @@ use actix_web::web::Data;
 use lemmy_api_common::{
   blocking,
   check_community_ban,
+  check_resolution_note,
   get_local_user_view_from_jwt,
   is_mod_or_admin,
   post::{
     CreatePostReport,
+    EscalatePostReport,
     ListPostReports,
     ListPostReportsResponse,
     PostReportResponse,
@@ -23,7 +25,7 @@ use lemmy_db_views::{
   post_report_view::{PostReportQueryBuilder, PostReportView},
   post_view::PostView,
 };
-use lemmy_utils::{ConnectionId, LemmyError};
+use lemmy_utils::{utils::check_slurs_opt, ConnectionId, LemmyError};
 use lemmy_websocket::{messages::SendModRoomMessage, LemmyContext, UserOperation};
 
 /// Creates a post report and notifies the moderators of the community
@@ -127,10 +129,13 @@ impl Perform for ResolvePostReport {
     let person_id = local_user_view.person.id;
     is_mod_or_admin(context.pool(), person_id, report.community.id).await?;
 
+    let resolution_note = check_resolution_note(&data.resolution_note)?;
+    check_slurs_opt(&resolution_note, &context.settings().slur_regex())?;
+
     let resolved = data.resolved;
     let resolve_fun = move |conn: &'_ _| {
       if resolved {
-        PostReport::resolve(conn, report_id, person_id)
+        PostReport::resolve(conn, report_id, person_id, resolution_note)
       } else {
         PostReport::unresolve(conn, report_id, person_id)
       }
@@ -159,6 +164,58 @@ impl Perform for ResolvePostReport {
   }
 }
 
+/// Passes a post report on to the instance admins and notifies the moderators of the community
+#[async_trait::async_trait(?Send)]
+impl Perform for EscalatePostReport {
+  type Response = PostReportResponse;
+
+  #[tracing::instrument(skip(context, websocket_id))]
+  async fn perform(
+    &self,
+    context: &Data<LemmyContext>,
+    websocket_id: Option<ConnectionId>,
+  ) -> Result<PostReportResponse, LemmyError> {
+    let data: &EscalatePostReport = self;
+    let local_user_view =
+      get_local_user_view_from_jwt(&data.auth, context.pool(), context.secret()).await?;
+
+    let report_id = data.report_id;
+    let person_id = local_user_view.person.id;
+    let report = blocking(context.pool(), move |conn| {
+      PostReportView::read(conn, report_id, person_id)
+    })
+    .await??;
+
+    is_mod_or_admin(context.pool(), person_id, report.community.id).await?;
+    if report.post_report.resolved {
+      return Err(LemmyError::from_message("report_already_resolved"));
+    }
+
+    blocking(context.pool(), move |conn| {
+      PostReport::escalate(conn, report_id)
+    })
+    .await?
+    .map_err(LemmyError::from)
+    .map_err(|e| e.with_message("couldnt_escalate_report"))?;
+
+    let post_report_view = blocking(context.pool(), move |conn| {
+      PostReportView::read(conn, report_id, person_id)
+    })
+    .await??;
+
+    let res = PostReportResponse { post_report_view };
+
+    context.chat_server().do_send(SendModRoomMessage {
+      op: UserOperation::EscalatePostReport,
+      response: res.clone(),
+      community_id: report.community.id,
+      websocket_id,
+    });
+
+    Ok(res)
+  }
+}
+
 /// Lists post reports for a community if an id is supplied
 /// or returns all post reports for communities a user moderates
 #[async_trait::async_trait(?Send)]
@@ -179,6 +236,7 @@ impl Perform for ListPostReports {
     let admin = local_user_view.person.admin;
     let community_id = data.community_id;
     let unresolved_only = data.unresolved_only;
+    let escalated_only = data.escalated_only;
 
     let page = data.page;
     let limit = data.limit;
@@ -186,6 +244,7 @@ impl Perform for ListPostReports {
       PostReportQueryBuilder::create(conn, person_id, admin)
         .community_id(community_id)
         .unresolved_only(unresolved_only)
+        .escalated_only(escalated_only)
         .page(page)
         .limit(limit)
         .list()
@@ -197,3 +256,128 @@ impl Perform for ListPostReports {
     Ok(res)
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::tests::init_context;
+  use lemmy_api_common::{create_login_session, person::GetReportCount};
+  use lemmy_db_schema::{
+    source::{
+      community::{Community, CommunityForm, CommunityModerator, CommunityModeratorForm},
+      local_user::{LocalUser, LocalUserForm},
+      person::{Person, PersonForm},
+      post::{Post, PostForm},
+    },
+    traits::{Crud, Joinable},
+  };
+  use lemmy_utils::Sensitive;
+  use serial_test::serial;
+
+  async fn login(person: &Person, context: &Data<LemmyContext>) -> Sensitive<String> {
+    let local_user_form = LocalUserForm {
+      person_id: Some(person.id),
+      password_encrypted: Some("escalate_pass".to_string()),
+      ..LocalUserForm::default()
+    };
+    let conn = context.pool().get().unwrap();
+    let local_user = LocalUser::create(&conn, &local_user_form).unwrap();
+    create_login_session(
+      local_user.id,
+      context.pool(),
+      context.secret(),
+      &context.settings(),
+    )
+    .await
+    .unwrap()
+  }
+
+  #[actix_rt::test]
+  #[serial]
+  async fn test_escalate_post_report() {
+    let context = Data::new(init_context());
+    let conn = context.pool().get().unwrap();
+
+    let person_form = PersonForm {
+      name: "escalate_admin".into(),
+      admin: Some(true),
+      ..PersonForm::default()
+    };
+    let admin = Person::create(&conn, &person_form).unwrap();
+    let admin_auth = login(&admin, &context).await;
+    let person_form = PersonForm {
+      name: "escalate_mod".into(),
+      ..PersonForm::default()
+    };
+    let moderator = Person::create(&conn, &person_form).unwrap();
+    let mod_auth = login(&moderator, &context).await;
+    let person_form = PersonForm {
+      name: "escalate_outsider".into(),
+      ..PersonForm::default()
+    };
+    let outsider = Person::create(&conn, &person_form).unwrap();
+    let outsider_auth = login(&outsider, &context).await;
+    let community_form = CommunityForm {
+      name: "escalate_community".to_string(),
+      title: "nada".to_owned(),
+      ..CommunityForm::default()
+    };
+    let community = Community::create(&conn, &community_form).unwrap();
+    let moderator_form = CommunityModeratorForm {
+      community_id: community.id,
+      person_id: moderator.id,
+    };
+    CommunityModerator::join(&conn, &moderator_form).unwrap();
+    let post_form = PostForm {
+      name: "escalate post".into(),
+      creator_id: outsider.id,
+      community_id: community.id,
+      ..PostForm::default()
+    };
+    let post = Post::create(&conn, &post_form).unwrap();
+    let report_form = PostReportForm {
+      creator_id: outsider.id,
+      post_id: post.id,
+      original_post_name: post.name.clone(),
+      original_post_url: None,
+      original_post_body: None,
+      reason: "illegal".to_string(),
+    };
+    let report = PostReport::report(&conn, &report_form).unwrap();
+
+    let escalate = |auth: &Sensitive<String>| EscalatePostReport {
+      report_id: report.id,
+      auth: auth.clone(),
+    };
+    let by_outsider = escalate(&outsider_auth).perform(&context, None).await;
+    let by_mod = escalate(&mod_auth).perform(&context, None).await.unwrap();
+    let report_count = |auth: &Sensitive<String>| GetReportCount {
+      community_id: None,
+      auth: auth.clone(),
+    };
+    let admin_count = report_count(&admin_auth)
+      .perform(&context, None)
+      .await
+      .unwrap();
+    let mod_count = report_count(&mod_auth)
+      .perform(&context, None)
+      .await
+      .unwrap();
+    PostReport::resolve(&conn, report.id, admin.id, None).unwrap();
+    let after_resolve = escalate(&mod_auth).perform(&context, None).await;
+
+    Person::delete(&conn, admin.id).unwrap();
+    Person::delete(&conn, moderator.id).unwrap();
+    Person::delete(&conn, outsider.id).unwrap();
+    Community::delete(&conn, community.id).unwrap();
+
+    assert_eq!(Some("not_a_mod_or_admin"), by_outsider.unwrap_err().message);
+    assert!(by_mod.post_report_view.post_report.escalated);
+    assert_eq!(Some(1), admin_count.escalated_post_reports);
+    assert_eq!(None, mod_count.escalated_post_reports);
+    assert_eq!(
+      Some("report_already_resolved"),
+      after_resolve.unwrap_err().message
+    );
+  }
+}
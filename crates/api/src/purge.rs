@@ -0,0 +1,288 @@
+use crate::Perform;
+use actix_web::web::Data;
+use lemmy_api_common::{blocking, get_local_user_view_from_jwt, is_admin, site::*};
+use lemmy_apub::{
+  activities::{
+    deletion::{send_apub_delete, DeletableObjects},
+    federation_target::FederationTarget,
+  },
+  objects::{community::ApubCommunity, person::ApubPerson},
+};
+use lemmy_db_schema::{
+  newtypes::{CommunityId, DbUrl},
+  source::{comment::Comment, community::Community, moderator::*, person::Person, post::Post},
+  traits::Crud,
+};
+use lemmy_utils::{request::purge_image_from_pictrs, ConnectionId, LemmyError};
+use lemmy_websocket::LemmyContext;
+use tracing::warn;
+
+#[async_trait::async_trait(?Send)]
+impl Perform for PurgePerson {
+  type Response = PurgeItemResponse;
+
+  #[tracing::instrument(skip(context, _websocket_id))]
+  async fn perform(
+    &self,
+    context: &Data<LemmyContext>,
+    _websocket_id: Option<ConnectionId>,
+  ) -> Result<Self::Response, LemmyError> {
+    let data: &PurgePerson = self;
+    let local_user_view =
+      get_local_user_view_from_jwt(&data.auth, context.pool(), context.secret()).await?;
+    is_admin(&local_user_view)?;
+
+    let person_id = data.person_id;
+    let person = blocking(context.pool(), move |conn| Person::read(conn, person_id))
+      .await?
+      .map_err(LemmyError::from)
+      .map_err(|e| e.with_message("couldnt_find_person"))?;
+    if person.admin {
+      return Err(LemmyError::from_message("cant_purge_admin"));
+    }
+
+    let (posts, comments) = blocking(context.pool(), move |conn| {
+      Ok((
+        Post::list_all_for_creator(conn, person_id)?,
+        Comment::list_all_for_creator(conn, person_id)?,
+      )) as Result<(Vec<Post>, Vec<Comment>), LemmyError>
+    })
+    .await??;
+    let post_ids = posts.iter().map(|p| p.id).collect();
+    let mut media_urls = blocking(context.pool(), move |conn| {
+      Post::list_media_urls(conn, post_ids)
+    })
+    .await??;
+    media_urls.extend(person.avatar.clone());
+    media_urls.extend(person.banner.clone());
+
+    // Content of remote persons stays on their instance, so there is nothing to federate
+    if person.local {
+      let admin: ApubPerson = local_user_view.person.clone().into();
+      for post in posts.into_iter().filter(|p| !p.deleted && !p.removed) {
+        let community = read_community(post.community_id, context).await?;
+        let object = DeletableObjects::Post(Box::new(post.into()));
+        send_purge(&admin, community, object, context).await?;
+      }
+      for comment in comments.into_iter().filter(|c| !c.deleted && !c.removed) {
+        let post_id = comment.post_id;
+        let post = blocking(context.pool(), move |conn| Post::read(conn, post_id)).await??;
+        let community = read_community(post.community_id, context).await?;
+        let object = DeletableObjects::Comment(Box::new(comment.into()));
+        send_purge(&admin, community, object, context).await?;
+      }
+    }
+
+    // Everything else of the person is removed with it
+    blocking(context.pool(), move |conn| Person::delete(conn, person_id))
+      .await?
+      .map_err(LemmyError::from)
+      .map_err(|e| e.with_message("couldnt_purge_person"))?;
+    purge_images(media_urls, context).await;
+
+    let form = AdminPurgePersonForm {
+      admin_person_id: local_user_view.person.id,
+      reason: data.reason.to_owned(),
+    };
+    blocking(context.pool(), move |conn| {
+      AdminPurgePerson::create(conn, &form)
+    })
+    .await??;
+
+    Ok(PurgeItemResponse { success: true })
+  }
+}
+
+#[async_trait::async_trait(?Send)]
+impl Perform for PurgeCommunity {
+  type Response = PurgeItemResponse;
+
+  #[tracing::instrument(skip(context, _websocket_id))]
+  async fn perform(
+    &self,
+    context: &Data<LemmyContext>,
+    _websocket_id: Option<ConnectionId>,
+  ) -> Result<Self::Response, LemmyError> {
+    let data: &PurgeCommunity = self;
+    let local_user_view =
+      get_local_user_view_from_jwt(&data.auth, context.pool(), context.secret()).await?;
+    is_admin(&local_user_view)?;
+
+    let community = read_community(data.community_id, context).await?;
+    let community_id = community.id;
+    let mut media_urls = blocking(context.pool(), move |conn| {
+      let post_ids = Post::list_ids_for_community(conn, community_id)?;
+      Post::list_media_urls(conn, post_ids)
+    })
+    .await??;
+    media_urls.extend(community.icon.clone());
+    media_urls.extend(community.banner.clone());
+
+    if community.local {
+      let admin: ApubPerson = local_user_view.person.clone().into();
+      let object = DeletableObjects::Community(Box::new(community.clone()));
+      send_purge(&admin, community, object, context).await?;
+    }
+
+    // Posts and comments of the community are removed with it
+    blocking(context.pool(), move |conn| {
+      Community::delete(conn, community_id)
+    })
+    .await?
+    .map_err(LemmyError::from)
+    .map_err(|e| e.with_message("couldnt_purge_community"))?;
+    purge_images(media_urls, context).await;
+
+    let form = AdminPurgeCommunityForm {
+      admin_person_id: local_user_view.person.id,
+      reason: data.reason.to_owned(),
+    };
+    blocking(context.pool(), move |conn| {
+      AdminPurgeCommunity::create(conn, &form)
+    })
+    .await??;
+
+    Ok(PurgeItemResponse { success: true })
+  }
+}
+
+#[async_trait::async_trait(?Send)]
+impl Perform for PurgePost {
+  type Response = PurgeItemResponse;
+
+  #[tracing::instrument(skip(context, _websocket_id))]
+  async fn perform(
+    &self,
+    context: &Data<LemmyContext>,
+    _websocket_id: Option<ConnectionId>,
+  ) -> Result<Self::Response, LemmyError> {
+    let data: &PurgePost = self;
+    let local_user_view =
+      get_local_user_view_from_jwt(&data.auth, context.pool(), context.secret()).await?;
+    is_admin(&local_user_view)?;
+
+    let post_id = data.post_id;
+    let post = blocking(context.pool(), move |conn| Post::read(conn, post_id))
+      .await?
+      .map_err(LemmyError::from)
+      .map_err(|e| e.with_message("couldnt_find_post"))?;
+    let media_urls = blocking(context.pool(), move |conn| {
+      Post::list_media_urls(conn, vec![post_id])
+    })
+    .await??;
+
+    let community_id = post.community_id;
+    if post.local {
+      let admin: ApubPerson = local_user_view.person.clone().into();
+      let community = read_community(community_id, context).await?;
+      let object = DeletableObjects::Post(Box::new(post.into()));
+      send_purge(&admin, community, object, context).await?;
+    }
+
+    blocking(context.pool(), move |conn| Post::delete(conn, post_id))
+      .await?
+      .map_err(LemmyError::from)
+      .map_err(|e| e.with_message("couldnt_purge_post"))?;
+    purge_images(media_urls, context).await;
+
+    let form = AdminPurgePostForm {
+      admin_person_id: local_user_view.person.id,
+      community_id,
+      reason: data.reason.to_owned(),
+    };
+    blocking(context.pool(), move |conn| {
+      AdminPurgePost::create(conn, &form)
+    })
+    .await??;
+
+    Ok(PurgeItemResponse { success: true })
+  }
+}
+
+#[async_trait::async_trait(?Send)]
+impl Perform for PurgeComment {
+  type Response = PurgeItemResponse;
+
+  #[tracing::instrument(skip(context, _websocket_id))]
+  async fn perform(
+    &self,
+    context: &Data<LemmyContext>,
+    _websocket_id: Option<ConnectionId>,
+  ) -> Result<Self::Response, LemmyError> {
+    let data: &PurgeComment = self;
+    let local_user_view =
+      get_local_user_view_from_jwt(&data.auth, context.pool(), context.secret()).await?;
+    is_admin(&local_user_view)?;
+
+    let comment_id = data.comment_id;
+    let comment = blocking(context.pool(), move |conn| Comment::read(conn, comment_id))
+      .await?
+      .map_err(LemmyError::from)
+      .map_err(|e| e.with_message("couldnt_find_comment"))?;
+
+    let post_id = comment.post_id;
+    if comment.local {
+      let admin: ApubPerson = local_user_view.person.clone().into();
+      let post = blocking(context.pool(), move |conn| Post::read(conn, post_id)).await??;
+      let community = read_community(post.community_id, context).await?;
+      let object = DeletableObjects::Comment(Box::new(comment.into()));
+      send_purge(&admin, community, object, context).await?;
+    }
+
+    // Replies are removed together with the comment
+    blocking(context.pool(), move |conn| {
+      Comment::delete(conn, comment_id)
+    })
+    .await?
+    .map_err(LemmyError::from)
+    .map_err(|e| e.with_message("couldnt_purge_comment"))?;
+
+    let form = AdminPurgeCommentForm {
+      admin_person_id: local_user_view.person.id,
+      post_id,
+      reason: data.reason.to_owned(),
+    };
+    blocking(context.pool(), move |conn| {
+      AdminPurgeComment::create(conn, &form)
+    })
+    .await??;
+
+    Ok(PurgeItemResponse { success: true })
+  }
+}
+
+async fn read_community(
+  community_id: CommunityId,
+  context: &LemmyContext,
+) -> Result<ApubCommunity, LemmyError> {
+  let community = blocking(context.pool(), move |conn| {
+    Community::read(conn, community_id)
+  })
+  .await?
+  .map_err(LemmyError::from)
+  .map_err(|e| e.with_message("couldnt_find_community"))?;
+  Ok(community.into())
+}
+
+/// Purged objects are sent out as a regular Delete, which other instances understand. This has
+/// to happen while the object still exists in the database.
+async fn send_purge(
+  admin: &ApubPerson,
+  community: ApubCommunity,
+  object: DeletableObjects,
+  context: &LemmyContext,
+) -> Result<(), LemmyError> {
+  let target = FederationTarget::Community(community.id);
+  send_apub_delete(admin, &community, object, true, target, context).await
+}
+
+/// Purges the images which were uploaded to this instance. The content is already gone at this
+/// point, so failures are only logged.
+async fn purge_images(urls: Vec<DbUrl>, context: &LemmyContext) {
+  for url in urls {
+    let url = url.into();
+    if let Err(e) = purge_image_from_pictrs(context.client(), &context.settings(), &url).await {
+      warn!("Failed to purge image {}: {}", url, e);
+    }
+  }
+}
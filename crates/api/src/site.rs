@@ -9,7 +9,9 @@ use lemmy_api_common::{
   get_local_user_view_from_jwt,
   get_local_user_view_from_jwt_opt,
   is_admin,
+  localize_reason,
   send_application_approved_email,
+  send_verification_email,
   site::*,
 };
 use lemmy_apub::{
@@ -18,23 +20,33 @@ use lemmy_apub::{
     webfinger::webfinger_resolve,
   },
   objects::community::ApubCommunity,
+  protocol::activities::instance::{block::BlockInstance, undo_block::UndoBlockInstance},
   EndpointType,
 };
 use lemmy_db_schema::{
   diesel_option_overwrite,
   from_opt_str_to_opt_enum,
+  naive_now,
   newtypes::PersonId,
   source::{
+    federation_instance::{FederationInstance, FederationInstanceForm},
+    instance::{Instance, InstanceForm},
+    language::Language,
+    legal_hold::{LegalHold, LegalHoldForm},
     local_user::{LocalUser, LocalUserForm},
     moderator::*,
+    person::Person,
+    received_deletion::ReceivedDeletion,
     registration_application::{RegistrationApplication, RegistrationApplicationForm},
     site::Site,
+    site_word_filter::{SiteWordFilter, SiteWordFilterForm},
   },
   traits::{Crud, DeleteableOrRemoveable},
   DbPool,
   ListingType,
   SearchType,
   SortType,
+  WordFilterAction,
 };
 use lemmy_db_views::{
   comment_view::{CommentQueryBuilder, CommentView},
@@ -51,19 +63,37 @@ use lemmy_db_views_actor::{
   person_view::{PersonQueryBuilder, PersonViewSafe},
 };
 use lemmy_db_views_moderator::{
+  admin_purge_comment_view::AdminPurgeCommentView,
+  admin_purge_community_view::AdminPurgeCommunityView,
+  admin_purge_person_view::AdminPurgePersonView,
+  admin_purge_post_view::AdminPurgePostView,
   mod_add_community_view::ModAddCommunityView,
   mod_add_view::ModAddView,
+  mod_automod_action_view::ModAutomodActionView,
   mod_ban_from_community_view::ModBanFromCommunityView,
   mod_ban_view::ModBanView,
   mod_lock_post_view::ModLockPostView,
   mod_remove_comment_view::ModRemoveCommentView,
   mod_remove_community_view::ModRemoveCommunityView,
   mod_remove_post_view::ModRemovePostView,
+  mod_resolve_appeal_view::ModResolveAppealView,
+  mod_shadow_remove_post_view::ModShadowRemovePostView,
   mod_sticky_post_view::ModStickyPostView,
   mod_transfer_community_view::ModTransferCommunityView,
 };
-use lemmy_utils::{location_info, settings::structs::Settings, version, ConnectionId, LemmyError};
+use lemmy_utils::{
+  location_info,
+  request::fetch_nodeinfo,
+  settings::structs::Settings,
+  utils::{check_word_filter_pattern, is_valid_domain, naive_from_unix},
+  version,
+  web_push::vapid_public_key,
+  ConnectionId,
+  LemmyError,
+};
 use lemmy_websocket::LemmyContext;
+use std::{collections::HashMap, str::FromStr};
+use tracing::warn;
 
 #[async_trait::async_trait(?Send)]
 impl Perform for GetModlog {
@@ -87,7 +117,7 @@ impl Perform for GetModlog {
     let mod_person_id = data.mod_person_id;
     let page = data.page;
     let limit = data.limit;
-    let removed_posts = blocking(context.pool(), move |conn| {
+    let mut removed_posts = blocking(context.pool(), move |conn| {
       ModRemovePostView::list(conn, community_id, mod_person_id, page, limit)
     })
     .await??;
@@ -97,12 +127,17 @@ impl Perform for GetModlog {
     })
     .await??;
 
+    let shadow_removed_posts = blocking(context.pool(), move |conn| {
+      ModShadowRemovePostView::list(conn, community_id, mod_person_id, page, limit)
+    })
+    .await??;
+
     let stickied_posts = blocking(context.pool(), move |conn| {
       ModStickyPostView::list(conn, community_id, mod_person_id, page, limit)
     })
     .await??;
 
-    let removed_comments = blocking(context.pool(), move |conn| {
+    let mut removed_comments = blocking(context.pool(), move |conn| {
       ModRemoveCommentView::list(conn, community_id, mod_person_id, page, limit)
     })
     .await??;
@@ -122,24 +157,83 @@ impl Perform for GetModlog {
     })
     .await??;
 
+    let resolved_appeals = blocking(context.pool(), move |conn| {
+      ModResolveAppealView::list(conn, community_id, mod_person_id, page, limit)
+    })
+    .await??;
+
+    let automod_actions = blocking(context.pool(), move |conn| {
+      ModAutomodActionView::list(conn, community_id, mod_person_id, page, limit)
+    })
+    .await??;
+
+    let admin_purged_posts = blocking(context.pool(), move |conn| {
+      AdminPurgePostView::list(conn, community_id, mod_person_id, page, limit)
+    })
+    .await??;
+
+    let admin_purged_comments = blocking(context.pool(), move |conn| {
+      AdminPurgeCommentView::list(conn, community_id, mod_person_id, page, limit)
+    })
+    .await??;
+
     // These arrays are only for the full modlog, when a community isn't given
-    let (removed_communities, banned, added) = if data.community_id.is_none() {
-      blocking(context.pool(), move |conn| {
-        Ok((
-          ModRemoveCommunityView::list(conn, mod_person_id, page, limit)?,
-          ModBanView::list(conn, mod_person_id, page, limit)?,
-          ModAddView::list(conn, mod_person_id, page, limit)?,
-        )) as Result<_, LemmyError>
-      })
-      .await??
-    } else {
-      (Vec::new(), Vec::new(), Vec::new())
-    };
+    let (mut removed_communities, banned, added, admin_purged_persons, admin_purged_communities) =
+      if data.community_id.is_none() {
+        blocking(context.pool(), move |conn| {
+          Ok((
+            ModRemoveCommunityView::list(conn, mod_person_id, page, limit)?,
+            ModBanView::list(conn, mod_person_id, page, limit)?,
+            ModAddView::list(conn, mod_person_id, page, limit)?,
+            AdminPurgePersonView::list(conn, mod_person_id, page, limit)?,
+            AdminPurgeCommunityView::list(conn, mod_person_id, page, limit)?,
+          )) as Result<_, LemmyError>
+        })
+        .await??
+      } else {
+        (Vec::new(), Vec::new(), Vec::new(), Vec::new(), Vec::new())
+      };
+
+    // Show removal reasons in the language of the user, if a translation exists
+    if let Some(lang) = local_user_view.map(|l| l.local_user.lang) {
+      let default_language = &context.settings().moderation.reason_language;
+      for r in removed_posts.iter_mut().map(|v| &mut v.mod_remove_post) {
+        r.reason = localize_reason(
+          r.reason.take(),
+          &r.reason_translations,
+          &lang,
+          default_language,
+        );
+      }
+      for r in removed_comments
+        .iter_mut()
+        .map(|v| &mut v.mod_remove_comment)
+      {
+        r.reason = localize_reason(
+          r.reason.take(),
+          &r.reason_translations,
+          &lang,
+          default_language,
+        );
+      }
+      for r in removed_communities
+        .iter_mut()
+        .map(|v: &mut ModRemoveCommunityView| &mut v.mod_remove_community)
+      {
+        r.reason = localize_reason(
+          r.reason.take(),
+          &r.reason_translations,
+          &lang,
+          default_language,
+        );
+      }
+    }
 
     // Return the jwt
     Ok(GetModlogResponse {
       removed_posts,
       locked_posts,
+      shadow_removed_posts,
       stickied_posts,
       removed_comments,
       removed_communities,
@@ -148,6 +242,12 @@ impl Perform for GetModlog {
       added_to_community,
       added,
       transferred_to_community,
+      resolved_appeals,
+      automod_actions,
+      admin_purged_persons,
+      admin_purged_communities,
+      admin_purged_posts,
+      admin_purged_comments,
     })
   }
 }
@@ -177,6 +277,7 @@ impl Perform for Search {
     let show_read_posts = local_user_view
       .as_ref()
       .map(|t| t.local_user.show_read_posts);
+    let show_scores = local_user_view.as_ref().map(|t| t.local_user.show_scores);
 
     let person_id = local_user_view.map(|u| u.person.id);
 
@@ -202,6 +303,20 @@ impl Perform for Search {
       None
     };
     let creator_id = data.creator_id;
+    let category_id = data.category_id;
+    let published_after = data.published_after.map(naive_from_unix);
+    let published_before = data.published_before.map(naive_from_unix);
+    if let (Some(after), Some(before)) = (published_after, published_before) {
+      if after >= before {
+        return Err(LemmyError::from_message("invalid_time_range"));
+      }
+    }
+    let domain = data.domain.as_ref().map(|d| d.trim().to_lowercase());
+    if let Some(domain) = &domain {
+      if !is_valid_domain(domain) {
+        return Err(LemmyError::from_message("invalid_domain"));
+      }
+    }
     match search_type {
       SearchType::Posts => {
         posts = blocking(context.pool(), move |conn| {
@@ -210,10 +325,15 @@ impl Perform for Search {
             .show_nsfw(show_nsfw)
             .show_bot_accounts(show_bot_accounts)
             .show_read_posts(show_read_posts)
+            .show_scores(show_scores)
             .listing_type(listing_type)
             .community_id(community_id)
             .community_actor_id(community_actor_id)
             .creator_id(creator_id)
+            .category_id(category_id)
+            .published_after(published_after)
+            .published_before(published_before)
+            .url_domain(domain)
             .my_person_id(person_id)
             .search_term(q)
             .page(page)
@@ -229,9 +349,13 @@ impl Perform for Search {
             .listing_type(listing_type)
             .search_term(q)
             .show_bot_accounts(show_bot_accounts)
+            .show_scores(show_scores)
             .community_id(community_id)
             .community_actor_id(community_actor_id)
             .creator_id(creator_id)
+            .category_id(category_id)
+            .published_after(published_after)
+            .published_before(published_before)
             .my_person_id(person_id)
             .page(page)
             .limit(limit)
@@ -245,6 +369,7 @@ impl Perform for Search {
             .sort(sort)
             .listing_type(listing_type)
             .search_term(q)
+            .category_id(category_id)
             .my_person_id(person_id)
             .page(page)
             .limit(limit)
@@ -267,6 +392,10 @@ impl Perform for Search {
         // If the community or creator is included, dont search communities or users
         let community_or_creator_included =
           data.community_id.is_some() || data.community_name.is_some() || data.creator_id.is_some();
+        let category_included = data.category_id.is_some();
+        // Communities and users can't be filtered by these, and comments have no links
+        let time_range_included = published_after.is_some() || published_before.is_some();
+        let domain_included = domain.is_some();
         let community_actor_id_2 = community_actor_id.to_owned();
 
         posts = blocking(context.pool(), move |conn| {
@@ -275,10 +404,15 @@ impl Perform for Search {
             .show_nsfw(show_nsfw)
             .show_bot_accounts(show_bot_accounts)
             .show_read_posts(show_read_posts)
+            .show_scores(show_scores)
             .listing_type(listing_type)
             .community_id(community_id)
             .community_actor_id(community_actor_id_2)
             .creator_id(creator_id)
+            .category_id(category_id)
+            .published_after(published_after)
+            .published_before(published_before)
+            .url_domain(domain)
             .my_person_id(person_id)
             .search_term(q)
             .page(page)
@@ -290,25 +424,33 @@ impl Perform for Search {
         let q = data.q.to_owned();
         let community_actor_id = community_actor_id.to_owned();
 
-        comments = blocking(context.pool(), move |conn| {
-          CommentQueryBuilder::create(conn)
-            .sort(sort)
-            .listing_type(listing_type)
-            .search_term(q)
-            .show_bot_accounts(show_bot_accounts)
-            .community_id(community_id)
-            .community_actor_id(community_actor_id)
-            .creator_id(creator_id)
-            .my_person_id(person_id)
-            .page(page)
-            .limit(limit)
-            .list()
-        })
-        .await??;
+        comments = if domain_included {
+          vec![]
+        } else {
+          blocking(context.pool(), move |conn| {
+            CommentQueryBuilder::create(conn)
+              .sort(sort)
+              .listing_type(listing_type)
+              .search_term(q)
+              .show_bot_accounts(show_bot_accounts)
+              .show_scores(show_scores)
+              .community_id(community_id)
+              .community_actor_id(community_actor_id)
+              .creator_id(creator_id)
+              .category_id(category_id)
+              .published_after(published_after)
+              .published_before(published_before)
+              .my_person_id(person_id)
+              .page(page)
+              .limit(limit)
+              .list()
+          })
+          .await??
+        };
 
         let q = data.q.to_owned();
 
-        communities = if community_or_creator_included {
+        communities = if community_or_creator_included || time_range_included || domain_included {
           vec![]
         } else {
           blocking(context.pool(), move |conn| {
@@ -316,6 +458,7 @@ impl Perform for Search {
               .sort(sort)
               .listing_type(listing_type)
               .search_term(q)
+              .category_id(category_id)
               .my_person_id(person_id)
               .page(page)
               .limit(limit)
@@ -326,7 +469,11 @@ impl Perform for Search {
 
         let q = data.q.to_owned();
 
-        users = if community_or_creator_included {
+        users = if community_or_creator_included
+          || category_included
+          || time_range_included
+          || domain_included
+        {
           vec![]
         } else {
           blocking(context.pool(), move |conn| {
@@ -347,11 +494,16 @@ impl Perform for Search {
             .show_nsfw(show_nsfw)
             .show_bot_accounts(show_bot_accounts)
             .show_read_posts(show_read_posts)
+            .show_scores(show_scores)
             .listing_type(listing_type)
             .my_person_id(person_id)
             .community_id(community_id)
             .community_actor_id(community_actor_id)
             .creator_id(creator_id)
+            .category_id(category_id)
+            .published_after(published_after)
+            .published_before(published_before)
+            .url_domain(domain)
             .url_search(q)
             .page(page)
             .limit(limit)
@@ -396,6 +548,42 @@ impl Perform for Search {
   }
 }
 
+/// Kept small, as clients only show a short dropdown while typing
+const MENTION_AUTOCOMPLETE_MAX_LIMIT: i64 = 20;
+
+#[async_trait::async_trait(?Send)]
+impl Perform for MentionAutocomplete {
+  type Response = MentionAutocompleteResponse;
+
+  #[tracing::instrument(skip(context, _websocket_id))]
+  async fn perform(
+    &self,
+    context: &Data<LemmyContext>,
+    _websocket_id: Option<ConnectionId>,
+  ) -> Result<MentionAutocompleteResponse, LemmyError> {
+    let data: &MentionAutocomplete = self;
+    let local_user_view =
+      get_local_user_view_from_jwt_opt(data.auth.as_ref(), context.pool(), context.secret())
+        .await?;
+    check_private_instance(&local_user_view, context.pool()).await?;
+
+    let prefix = data.q.trim().trim_start_matches('@').to_owned();
+    if prefix.is_empty() {
+      return Ok(MentionAutocompleteResponse { users: vec![] });
+    }
+    let community_id = data.community_id;
+    let limit = data
+      .limit
+      .unwrap_or(10)
+      .clamp(1, MENTION_AUTOCOMPLETE_MAX_LIMIT);
+    let users = blocking(context.pool(), move |conn| {
+      Person::search_for_mention(conn, community_id, &prefix, limit)
+    })
+    .await??;
+    Ok(MentionAutocompleteResponse { users })
+  }
+}
+
 #[async_trait::async_trait(?Send)]
 impl Perform for ResolveObject {
   type Response = ResolveObjectResponse;
@@ -517,6 +705,11 @@ impl Perform for TransferSite {
       &context.settings().hostname,
     )
     .await?;
+    let vapid_public_key = context
+      .settings()
+      .web_push
+      .and_then(|w| vapid_public_key(&w.vapid_private_key).ok());
+    let all_languages = blocking(context.pool(), Language::read_all).await??;
 
     Ok(GetSiteResponse {
       site_view: Some(site_view),
@@ -526,6 +719,8 @@ impl Perform for TransferSite {
       version: version::VERSION.to_string(),
       my_user: None,
       federated_instances,
+      vapid_public_key,
+      all_languages,
     })
   }
 }
@@ -597,16 +792,11 @@ impl Perform for ListRegistrationApplications {
     is_admin(&local_user_view)?;
 
     let unread_only = data.unread_only;
-    let verified_email_only = blocking(context.pool(), Site::read_simple)
-      .await??
-      .require_email_verification;
-
     let page = data.page;
     let limit = data.limit;
     let registration_applications = blocking(context.pool(), move |conn| {
       RegistrationApplicationQueryBuilder::create(conn)
         .unread_only(unread_only)
-        .verified_email_only(verified_email_only)
         .page(page)
         .limit(limit)
         .list()
@@ -621,6 +811,435 @@ impl Perform for ListRegistrationApplications {
   }
 }
 
+#[async_trait::async_trait(?Send)]
+impl Perform for ListReceivedDeletions {
+  type Response = ListReceivedDeletionsResponse;
+
+  async fn perform(
+    &self,
+    context: &Data<LemmyContext>,
+    _websocket_id: Option<ConnectionId>,
+  ) -> Result<Self::Response, LemmyError> {
+    let data = self;
+    let local_user_view =
+      get_local_user_view_from_jwt(&data.auth, context.pool(), context.secret()).await?;
+
+    // Make sure user is an admin
+    is_admin(&local_user_view)?;
+
+    let actor_id = data.actor_id.clone().into();
+    let page = data.page;
+    let limit = data.limit;
+    let received_deletions = blocking(context.pool(), move |conn| {
+      ReceivedDeletion::list_for_actor(conn, &actor_id, page, limit)
+    })
+    .await??;
+
+    Ok(ListReceivedDeletionsResponse { received_deletions })
+  }
+}
+
+#[async_trait::async_trait(?Send)]
+impl Perform for ListFederationSettings {
+  type Response = FederationSettingsResponse;
+
+  async fn perform(
+    &self,
+    context: &Data<LemmyContext>,
+    _websocket_id: Option<ConnectionId>,
+  ) -> Result<Self::Response, LemmyError> {
+    let data = self;
+    let local_user_view =
+      get_local_user_view_from_jwt(&data.auth, context.pool(), context.secret()).await?;
+    is_admin(&local_user_view)?;
+
+    federation_settings_response(context).await
+  }
+}
+
+#[async_trait::async_trait(?Send)]
+impl Perform for AddBlockedInstance {
+  type Response = FederationSettingsResponse;
+
+  async fn perform(
+    &self,
+    context: &Data<LemmyContext>,
+    _websocket_id: Option<ConnectionId>,
+  ) -> Result<Self::Response, LemmyError> {
+    let data = self;
+    update_federation_instance(&data.auth, &data.domain, true, true, context).await
+  }
+}
+
+#[async_trait::async_trait(?Send)]
+impl Perform for RemoveBlockedInstance {
+  type Response = FederationSettingsResponse;
+
+  async fn perform(
+    &self,
+    context: &Data<LemmyContext>,
+    _websocket_id: Option<ConnectionId>,
+  ) -> Result<Self::Response, LemmyError> {
+    let data = self;
+    update_federation_instance(&data.auth, &data.domain, true, false, context).await
+  }
+}
+
+#[async_trait::async_trait(?Send)]
+impl Perform for AddAllowedInstance {
+  type Response = FederationSettingsResponse;
+
+  async fn perform(
+    &self,
+    context: &Data<LemmyContext>,
+    _websocket_id: Option<ConnectionId>,
+  ) -> Result<Self::Response, LemmyError> {
+    let data = self;
+    update_federation_instance(&data.auth, &data.domain, false, true, context).await
+  }
+}
+
+#[async_trait::async_trait(?Send)]
+impl Perform for RemoveAllowedInstance {
+  type Response = FederationSettingsResponse;
+
+  async fn perform(
+    &self,
+    context: &Data<LemmyContext>,
+    _websocket_id: Option<ConnectionId>,
+  ) -> Result<Self::Response, LemmyError> {
+    let data = self;
+    update_federation_instance(&data.auth, &data.domain, false, false, context).await
+  }
+}
+
+/// Adds the domain to (or removes it from) the blocklist or allowlist, and refreshes the cached
+/// lists which are used for checking incoming activities. Blocks are federated to the instance.
+async fn update_federation_instance(
+  auth: &str,
+  domain: &str,
+  blocked: bool,
+  add: bool,
+  context: &LemmyContext,
+) -> Result<FederationSettingsResponse, LemmyError> {
+  let local_user_view =
+    get_local_user_view_from_jwt(auth, context.pool(), context.secret()).await?;
+  is_admin(&local_user_view)?;
+
+  let domain = domain.trim().to_lowercase();
+  let parsed_domain = url::Url::parse(&format!("https://{}", domain))
+    .ok()
+    .and_then(|u| u.domain().map(ToString::to_string));
+  if parsed_domain.as_ref() != Some(&domain) {
+    return Err(LemmyError::from_message("invalid_domain"));
+  }
+  if domain == context.settings().get_hostname_without_port()? {
+    return Err(LemmyError::from_message("cant_change_local_instance"));
+  }
+
+  let changed_domain = domain.clone();
+  let changed = blocking(context.pool(), move |conn| {
+    let changed = if add {
+      let form = FederationInstanceForm { domain, blocked };
+      FederationInstance::upsert(conn, &form).map(|_| 1)
+    } else {
+      FederationInstance::delete_for_domain(conn, &domain, blocked)
+    }?;
+    Ok(changed) as Result<usize, diesel::result::Error>
+  })
+  .await?
+  .map_err(LemmyError::from)
+  .map_err(|e| e.with_message("couldnt_update_federation_instance"))?;
+  blocking(context.pool(), FederationInstance::refresh_cache).await??;
+  if changed == 0 {
+    return Err(LemmyError::from_message("couldnt_find_federation_instance"));
+  }
+
+  // Let the instance know that it is blocked, so that it can stop sending us activities
+  match (blocked, add) {
+    (true, true) => BlockInstance::send(&changed_domain, context).await?,
+    (true, false) => UndoBlockInstance::send(&changed_domain, context).await?,
+    _ => {}
+  }
+
+  federation_settings_response(context).await
+}
+
+async fn federation_settings_response(
+  context: &LemmyContext,
+) -> Result<FederationSettingsResponse, LemmyError> {
+  let instances = blocking(context.pool(), FederationInstance::list).await??;
+  let federation = context.settings().federation;
+  let instance_lists = FederationInstance::cached_lists()?;
+  Ok(FederationSettingsResponse {
+    enabled: federation.enabled,
+    strict_allowlist: federation.strict_allowlist,
+    allowed_instances: instance_lists.combined_allowlist(&federation.allowed_instances),
+    blocked_instances: instance_lists.combined_blocklist(&federation.blocked_instances),
+    instances,
+  })
+}
+
+#[async_trait::async_trait(?Send)]
+impl Perform for GetFederatedInstances {
+  type Response = GetFederatedInstancesResponse;
+
+  #[tracing::instrument(skip(context, _websocket_id))]
+  async fn perform(
+    &self,
+    context: &Data<LemmyContext>,
+    _websocket_id: Option<ConnectionId>,
+  ) -> Result<Self::Response, LemmyError> {
+    let data = self;
+    let local_user_view =
+      get_local_user_view_from_jwt_opt(data.auth.as_ref(), context.pool(), context.secret())
+        .await?;
+    check_private_instance(&local_user_view, context.pool()).await?;
+
+    let federated_instances = build_federated_instances(
+      context.pool(),
+      &context.settings().federation,
+      &context.settings().hostname,
+    )
+    .await?;
+    let mut instances: HashMap<String, Instance> = blocking(context.pool(), Instance::list)
+      .await??
+      .into_iter()
+      .map(|i| (i.domain.clone(), i))
+      .collect();
+    let mut with_software = |domains: Vec<String>| {
+      domains
+        .into_iter()
+        .map(|domain| FederatedInstance {
+          instance: instances.remove(&domain),
+          domain,
+        })
+        .collect::<Vec<_>>()
+    };
+
+    Ok(GetFederatedInstancesResponse {
+      federated_instances: federated_instances.map(|f| FederatedInstancesWithSoftware {
+        linked: with_software(f.linked),
+        allowed: f.allowed.map(&mut with_software),
+        blocked: f.blocked.map(&mut with_software),
+      }),
+    })
+  }
+}
+
+/// Fetches the nodeinfo of all linked instances, and stores their software and user counts.
+/// Instances which can't be reached keep the previously fetched values.
+#[tracing::instrument(skip_all)]
+pub async fn update_instance_software(context: &LemmyContext) -> Result<(), LemmyError> {
+  let federated_instances = build_federated_instances(
+    context.pool(),
+    &context.settings().federation,
+    &context.settings().hostname,
+  )
+  .await?;
+  let linked = match federated_instances {
+    Some(f) => f.linked,
+    None => return Ok(()),
+  };
+  for domain in linked {
+    let nodeinfo = match fetch_nodeinfo(context.client(), &context.settings(), &domain).await {
+      Ok(nodeinfo) => nodeinfo,
+      Err(e) => {
+        warn!("Failed to fetch nodeinfo of {}: {}", domain, e);
+        continue;
+      }
+    };
+    let (software, version) = match nodeinfo.software {
+      Some(s) => (s.name, s.version),
+      None => (None, None),
+    };
+    let users = nodeinfo.usage.and_then(|u| u.users);
+    let form = InstanceForm {
+      domain,
+      software,
+      version,
+      users: users.as_ref().and_then(|u| u.total),
+      users_active_month: users.as_ref().and_then(|u| u.active_month),
+      updated: Some(naive_now()),
+    };
+    blocking(context.pool(), move |conn| Instance::upsert(conn, &form)).await??;
+  }
+  Ok(())
+}
+
+#[async_trait::async_trait(?Send)]
+impl Perform for ListWordFilters {
+  type Response = WordFiltersResponse;
+
+  async fn perform(
+    &self,
+    context: &Data<LemmyContext>,
+    _websocket_id: Option<ConnectionId>,
+  ) -> Result<Self::Response, LemmyError> {
+    let data = self;
+    let local_user_view =
+      get_local_user_view_from_jwt(&data.auth, context.pool(), context.secret()).await?;
+    is_admin(&local_user_view)?;
+
+    let word_filters = blocking(context.pool(), SiteWordFilter::list).await??;
+    Ok(WordFiltersResponse { word_filters })
+  }
+}
+
+#[async_trait::async_trait(?Send)]
+impl Perform for AddWordFilter {
+  type Response = WordFiltersResponse;
+
+  async fn perform(
+    &self,
+    context: &Data<LemmyContext>,
+    _websocket_id: Option<ConnectionId>,
+  ) -> Result<Self::Response, LemmyError> {
+    let data = self;
+    let local_user_view =
+      get_local_user_view_from_jwt(&data.auth, context.pool(), context.secret()).await?;
+    is_admin(&local_user_view)?;
+
+    let pattern = data.pattern.trim().to_owned();
+    check_word_filter_pattern(&pattern)?;
+    let action = WordFilterAction::from_str(&data.action)
+      .map_err(|_| LemmyError::from_message("invalid_word_filter_action"))?;
+
+    let form = SiteWordFilterForm {
+      pattern,
+      action: action.to_string(),
+    };
+    let word_filters = blocking(context.pool(), move |conn| {
+      SiteWordFilter::upsert(conn, &form)?;
+      SiteWordFilter::refresh_cache(conn)?;
+      SiteWordFilter::list(conn)
+    })
+    .await?
+    .map_err(LemmyError::from)
+    .map_err(|e| e.with_message("couldnt_update_word_filter"))?;
+
+    Ok(WordFiltersResponse { word_filters })
+  }
+}
+
+#[async_trait::async_trait(?Send)]
+impl Perform for RemoveWordFilter {
+  type Response = WordFiltersResponse;
+
+  async fn perform(
+    &self,
+    context: &Data<LemmyContext>,
+    _websocket_id: Option<ConnectionId>,
+  ) -> Result<Self::Response, LemmyError> {
+    let data = self;
+    let local_user_view =
+      get_local_user_view_from_jwt(&data.auth, context.pool(), context.secret()).await?;
+    is_admin(&local_user_view)?;
+
+    let id = data.id;
+    let (deleted, word_filters) = blocking(context.pool(), move |conn| -> Result<_, LemmyError> {
+      let deleted: usize = SiteWordFilter::delete(conn, id)?;
+      SiteWordFilter::refresh_cache(conn)?;
+      Ok((deleted, SiteWordFilter::list(conn)?))
+    })
+    .await?
+    .map_err(|e| e.with_message("couldnt_update_word_filter"))?;
+    if deleted == 0 {
+      return Err(LemmyError::from_message("couldnt_find_word_filter"));
+    }
+
+    Ok(WordFiltersResponse { word_filters })
+  }
+}
+
+#[async_trait::async_trait(?Send)]
+impl Perform for ListLegalHolds {
+  type Response = LegalHoldsResponse;
+
+  async fn perform(
+    &self,
+    context: &Data<LemmyContext>,
+    _websocket_id: Option<ConnectionId>,
+  ) -> Result<Self::Response, LemmyError> {
+    let data = self;
+    let local_user_view =
+      get_local_user_view_from_jwt(&data.auth, context.pool(), context.secret()).await?;
+    is_admin(&local_user_view)?;
+
+    let legal_holds = blocking(context.pool(), LegalHold::list).await??;
+    Ok(LegalHoldsResponse { legal_holds })
+  }
+}
+
+#[async_trait::async_trait(?Send)]
+impl Perform for AddLegalHold {
+  type Response = LegalHoldsResponse;
+
+  async fn perform(
+    &self,
+    context: &Data<LemmyContext>,
+    _websocket_id: Option<ConnectionId>,
+  ) -> Result<Self::Response, LemmyError> {
+    let data = self;
+    let local_user_view =
+      get_local_user_view_from_jwt(&data.auth, context.pool(), context.secret()).await?;
+    is_admin(&local_user_view)?;
+
+    if data.post_id.is_some() == data.comment_id.is_some() {
+      return Err(LemmyError::from_message("invalid_legal_hold"));
+    }
+    let reason = data.reason.trim().to_owned();
+    if reason.is_empty() {
+      return Err(LemmyError::from_message("legal_hold_reason_required"));
+    }
+
+    let form = LegalHoldForm {
+      post_id: data.post_id,
+      comment_id: data.comment_id,
+      admin_person_id: local_user_view.person.id,
+      reason,
+    };
+    let legal_holds = blocking(context.pool(), move |conn| {
+      LegalHold::create(conn, &form)?;
+      LegalHold::list(conn)
+    })
+    .await?
+    .map_err(LemmyError::from)
+    .map_err(|e| e.with_message("couldnt_create_legal_hold"))?;
+
+    Ok(LegalHoldsResponse { legal_holds })
+  }
+}
+
+#[async_trait::async_trait(?Send)]
+impl Perform for RemoveLegalHold {
+  type Response = LegalHoldsResponse;
+
+  async fn perform(
+    &self,
+    context: &Data<LemmyContext>,
+    _websocket_id: Option<ConnectionId>,
+  ) -> Result<Self::Response, LemmyError> {
+    let data = self;
+    let local_user_view =
+      get_local_user_view_from_jwt(&data.auth, context.pool(), context.secret()).await?;
+    is_admin(&local_user_view)?;
+
+    let id = data.id;
+    let (deleted, legal_holds) = blocking(context.pool(), move |conn| -> Result<_, LemmyError> {
+      let deleted: usize = LegalHold::delete(conn, id)?;
+      Ok((deleted, LegalHold::list(conn)?))
+    })
+    .await?
+    .map_err(|e| e.with_message("couldnt_update_legal_hold"))?;
+    if deleted == 0 {
+      return Err(LemmyError::from_message("couldnt_find_legal_hold"));
+    }
+
+    Ok(LegalHoldsResponse { legal_holds })
+  }
+}
+
 #[async_trait::async_trait(?Send)]
 impl Perform for ApproveRegistrationApplication {
   type Response = RegistrationApplicationResponse;
@@ -670,8 +1289,31 @@ impl Perform for ApproveRegistrationApplication {
       })
       .await??;
 
-      if approved_local_user_view.local_user.email.is_some() {
-        send_application_approved_email(&approved_local_user_view, &context.settings())?;
+      // With an application required, the verification email is only sent after approval
+      let require_email_verification = blocking(context.pool(), Site::read_simple)
+        .await??
+        .require_email_verification;
+      let local_user = &approved_local_user_view.local_user;
+      if let Some(email) = &local_user.email {
+        let sent = if require_email_verification && !local_user.email_verified {
+          send_verification_email(
+            local_user.id,
+            email,
+            &approved_local_user_view.person.name,
+            context.pool(),
+            &context.settings(),
+          )
+          .await
+        } else {
+          send_application_approved_email(&approved_local_user_view, &context.settings())
+        };
+        // The approval is already saved, and the verification email can be resent
+        if let Err(e) = sent {
+          warn!(
+            "Failed to send email to approved user {}: {}",
+            approved_local_user_view.person.name, e
+          );
+        }
       }
     }
 
@@ -703,13 +1345,10 @@ impl Perform for GetUnreadRegistrationApplicationCount {
     // Only let admins do this
     is_admin(&local_user_view)?;
 
-    let verified_email_only = blocking(context.pool(), Site::read_simple)
-      .await??
-      .require_email_verification;
-
-    let registration_applications = blocking(context.pool(), move |conn| {
-      RegistrationApplicationView::get_unread_count(conn, verified_email_only)
-    })
+    let registration_applications = blocking(
+      context.pool(),
+      RegistrationApplicationView::get_unread_count,
+    )
     .await??;
 
     Ok(Self::Response {
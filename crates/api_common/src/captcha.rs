@@ -0,0 +1,233 @@
+use crate::{
+  blocking,
+  person::{CaptchaResponse, ExternalCaptcha, GetCaptchaResponse},
+};
+use captcha::{gen, Captcha, Difficulty};
+use lemmy_db_schema::{
+  source::{
+    captcha_answer::{CaptchaAnswer, CaptchaAnswerForm},
+    site::Site,
+  },
+  DbPool,
+};
+use lemmy_utils::{settings::structs::Settings, LemmyError};
+use reqwest_middleware::ClientWithMiddleware;
+use serde::Deserialize;
+
+pub const BUILTIN: &str = "builtin";
+pub const HCAPTCHA: &str = "hcaptcha";
+pub const RECAPTCHA: &str = "recaptcha";
+
+/// Checks that registrations are made by humans
+#[async_trait::async_trait(?Send)]
+pub trait CaptchaProvider {
+  /// Creates a challenge for the registration form
+  async fn generate(&self, pool: &DbPool) -> Result<GetCaptchaResponse, LemmyError>;
+
+  /// Checks the answer which was sent with the registration. External providers only need the
+  /// answer, which is the response token of their widget.
+  async fn verify(
+    &self,
+    uuid: Option<String>,
+    answer: String,
+    pool: &DbPool,
+  ) -> Result<bool, LemmyError>;
+}
+
+/// Returns the captcha provider which is selected in the site settings, or None if captchas are
+/// disabled
+pub fn captcha_provider(
+  site: &Site,
+  settings: &Settings,
+  client: &ClientWithMiddleware,
+) -> Result<Option<Box<dyn CaptchaProvider>>, LemmyError> {
+  if !settings.captcha.enabled {
+    return Ok(None);
+  }
+  if site.captcha_provider == BUILTIN {
+    return Ok(Some(Box::new(BuiltinCaptcha {
+      difficulty: settings.captcha.difficulty.to_owned(),
+    })));
+  }
+
+  let external = SiteVerifyCaptcha {
+    site_key: site
+      .captcha_site_key
+      .to_owned()
+      .ok_or_else(|| LemmyError::from_message("captcha_site_key_required"))?,
+    secret_key: settings
+      .captcha
+      .secret_key
+      .to_owned()
+      .ok_or_else(|| LemmyError::from_message("captcha_secret_key_not_configured"))?,
+    client: client.clone(),
+  };
+  match site.captcha_provider.as_str() {
+    HCAPTCHA => Ok(Some(Box::new(HCaptcha(external)))),
+    RECAPTCHA => Ok(Some(Box::new(ReCaptcha(external)))),
+    _ => Err(LemmyError::from_message("invalid_captcha_provider")),
+  }
+}
+
+/// Makes sure that the provider exists, and that the keys which it needs are configured
+pub fn check_captcha_provider(
+  provider: &str,
+  site_key: &Option<String>,
+  settings: &Settings,
+) -> Result<(), LemmyError> {
+  match provider {
+    BUILTIN => Ok(()),
+    HCAPTCHA | RECAPTCHA if site_key.is_none() => {
+      Err(LemmyError::from_message("captcha_site_key_required"))
+    }
+    HCAPTCHA | RECAPTCHA if settings.captcha.secret_key.is_none() => Err(LemmyError::from_message(
+      "captcha_secret_key_not_configured",
+    )),
+    HCAPTCHA | RECAPTCHA => Ok(()),
+    _ => Err(LemmyError::from_message("invalid_captcha_provider")),
+  }
+}
+
+/// Image and audio captcha which is generated by Lemmy itself
+pub struct BuiltinCaptcha {
+  /// Can be easy, medium, or hard
+  difficulty: String,
+}
+
+#[async_trait::async_trait(?Send)]
+impl CaptchaProvider for BuiltinCaptcha {
+  async fn generate(&self, pool: &DbPool) -> Result<GetCaptchaResponse, LemmyError> {
+    let captcha = match self.difficulty.as_str() {
+      "easy" => gen(Difficulty::Easy),
+      "hard" => gen(Difficulty::Hard),
+      _ => gen(Difficulty::Medium),
+    };
+
+    let png = captcha.as_base64().expect("failed to generate captcha");
+    let wav = captcha_as_wav_base64(&captcha);
+    let form = CaptchaAnswerForm {
+      uuid: uuid::Uuid::new_v4().to_string(),
+      answer: captcha.chars_as_string(),
+    };
+    let inserted = blocking(pool, move |conn| CaptchaAnswer::insert(conn, &form)).await??;
+
+    Ok(GetCaptchaResponse {
+      ok: Some(CaptchaResponse {
+        png,
+        wav,
+        uuid: inserted.uuid,
+      }),
+      external: None,
+    })
+  }
+
+  async fn verify(
+    &self,
+    uuid: Option<String>,
+    answer: String,
+    pool: &DbPool,
+  ) -> Result<bool, LemmyError> {
+    let uuid = uuid.unwrap_or_default();
+    let valid = blocking(pool, move |conn| CaptchaAnswer::check(conn, &uuid, &answer)).await??;
+    Ok(valid)
+  }
+}
+
+/// Converts the captcha to a base64 encoded wav audio file
+fn captcha_as_wav_base64(captcha: &Captcha) -> String {
+  let letters = captcha.as_wav();
+
+  let mut concat_letters: Vec<u8> = Vec::new();
+
+  for letter in letters {
+    let bytes = letter.unwrap_or_default();
+    concat_letters.extend(bytes);
+  }
+
+  // Convert to base64
+  base64::encode(concat_letters)
+}
+
+/// hCaptcha and reCAPTCHA both check the response token of their widget with the same protocol
+struct SiteVerifyCaptcha {
+  site_key: String,
+  secret_key: String,
+  client: ClientWithMiddleware,
+}
+
+#[derive(Deserialize)]
+struct SiteVerifyResponse {
+  success: bool,
+}
+
+impl SiteVerifyCaptcha {
+  fn external(&self, provider: &str) -> GetCaptchaResponse {
+    GetCaptchaResponse {
+      ok: None,
+      external: Some(ExternalCaptcha {
+        provider: provider.to_string(),
+        site_key: self.site_key.to_owned(),
+      }),
+    }
+  }
+
+  async fn site_verify(&self, verify_url: &str, token: &str) -> Result<bool, LemmyError> {
+    let response = self
+      .client
+      .post(verify_url)
+      .form(&[
+        ("secret", self.secret_key.as_str()),
+        ("response", token),
+        ("sitekey", self.site_key.as_str()),
+      ])
+      .send()
+      .await?
+      .json::<SiteVerifyResponse>()
+      .await?;
+    Ok(response.success)
+  }
+}
+
+/// https://docs.hcaptcha.com/#verify-the-user-response-server-side
+pub struct HCaptcha(SiteVerifyCaptcha);
+
+#[async_trait::async_trait(?Send)]
+impl CaptchaProvider for HCaptcha {
+  async fn generate(&self, _pool: &DbPool) -> Result<GetCaptchaResponse, LemmyError> {
+    Ok(self.0.external(HCAPTCHA))
+  }
+
+  async fn verify(
+    &self,
+    _uuid: Option<String>,
+    answer: String,
+    _pool: &DbPool,
+  ) -> Result<bool, LemmyError> {
+    self
+      .0
+      .site_verify("https://hcaptcha.com/siteverify", &answer)
+      .await
+  }
+}
+
+/// https://developers.google.com/recaptcha/docs/verify
+pub struct ReCaptcha(SiteVerifyCaptcha);
+
+#[async_trait::async_trait(?Send)]
+impl CaptchaProvider for ReCaptcha {
+  async fn generate(&self, _pool: &DbPool) -> Result<GetCaptchaResponse, LemmyError> {
+    Ok(self.0.external(RECAPTCHA))
+  }
+
+  async fn verify(
+    &self,
+    _uuid: Option<String>,
+    answer: String,
+    _pool: &DbPool,
+  ) -> Result<bool, LemmyError> {
+    self
+      .0
+      .site_verify("https://www.google.com/recaptcha/api/siteverify", &answer)
+      .await
+  }
+}
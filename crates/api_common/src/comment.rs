@@ -1,7 +1,19 @@
-use lemmy_db_schema::newtypes::{CommentId, CommentReportId, CommunityId, LocalUserId, PostId};
+use lemmy_db_schema::{
+  newtypes::{
+    CommentId,
+    CommentReportId,
+    CommunityId,
+    LanguageId,
+    LocalUserId,
+    PostId,
+    SavedCollectionId,
+  },
+  source::comment_revision::CommentRevision,
+};
 use lemmy_db_views::{comment_report_view::CommentReportView, comment_view::CommentView};
 use lemmy_utils::Sensitive;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct CreateComment {
@@ -9,6 +21,8 @@ pub struct CreateComment {
   pub post_id: PostId,
   pub parent_id: Option<CommentId>,
   pub form_id: Option<String>,
+  /// Has to be one of the community languages, if it is limited to some
+  pub language_id: Option<LanguageId>,
   pub auth: Sensitive<String>,
 }
 
@@ -18,11 +32,26 @@ pub struct GetComment {
   pub auth: Option<Sensitive<String>>,
 }
 
+/// Earlier versions of an edited comment. For deleted or removed comments, only mods and admins
+/// can see them.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GetCommentEditHistory {
+  pub comment_id: CommentId,
+  pub auth: Option<Sensitive<String>>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct GetCommentEditHistoryResponse {
+  /// Oldest first, without the current version
+  pub revisions: Vec<CommentRevision>,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct EditComment {
   pub content: String,
   pub comment_id: CommentId,
   pub form_id: Option<String>,
+  pub language_id: Option<LanguageId>,
   pub auth: Sensitive<String>,
 }
 
@@ -38,6 +67,15 @@ pub struct RemoveComment {
   pub comment_id: CommentId,
   pub removed: bool,
   pub reason: Option<String>,
+  /// Translations of the reason, as map of language code to text
+  pub reason_translations: Option<HashMap<String, String>>,
+  /// Also remove all replies below the comment. When restoring, only the replies which were removed
+  /// together with the comment are restored.
+  pub remove_children: Option<bool>,
+  /// Admins can set this to remove without reason in emergencies, if a reason is required
+  pub reason_override: Option<bool>,
+  /// Set to false to leave the action out of the federated modlog
+  pub public_modlog: Option<bool>,
   pub auth: Sensitive<String>,
 }
 
@@ -52,6 +90,9 @@ pub struct MarkCommentAsRead {
 pub struct SaveComment {
   pub comment_id: CommentId,
   pub save: bool,
+  /// Files the item into one of the user's saved collections. Saving again without a collection
+  /// keeps the current one.
+  pub collection_id: Option<SavedCollectionId>,
   pub auth: Sensitive<String>,
 }
 
@@ -69,6 +110,21 @@ pub struct CreateCommentLike {
   pub auth: Sensitive<String>,
 }
 
+/// Adds an emoji reaction to a comment. A person can react with several different emoji.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ReactComment {
+  pub comment_id: CommentId,
+  pub emoji: String,
+  pub auth: Sensitive<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UnreactComment {
+  pub comment_id: CommentId,
+  pub emoji: String,
+  pub auth: Sensitive<String>,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct GetComments {
   pub type_: Option<String>,
@@ -77,7 +133,11 @@ pub struct GetComments {
   pub limit: Option<i64>,
   pub community_id: Option<CommunityId>,
   pub community_name: Option<String>,
+  pub post_id: Option<PostId>,
   pub saved_only: Option<bool>,
+  /// If set, `page` and `limit` apply to top level comments, which are returned together with
+  /// their replies down to this depth
+  pub max_depth: Option<i32>,
   pub auth: Option<Sensitive<String>>,
 }
 
@@ -86,6 +146,17 @@ pub struct GetCommentsResponse {
   pub comments: Vec<CommentView>,
 }
 
+/// Loads the replies of a collapsed comment, paginated and with `max_depth` like `GetComments`.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GetCommentChildren {
+  pub parent_id: CommentId,
+  pub sort: Option<String>,
+  pub page: Option<i64>,
+  pub limit: Option<i64>,
+  pub max_depth: Option<i32>,
+  pub auth: Option<Sensitive<String>>,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct CreateCommentReport {
   pub comment_id: CommentId,
@@ -102,6 +173,15 @@ pub struct CommentReportResponse {
 pub struct ResolveCommentReport {
   pub report_id: CommentReportId,
   pub resolved: bool,
+  /// Explanation of the resolution, which is stored in the report. Ignored when unresolving.
+  pub resolution_note: Option<String>,
+  pub auth: Sensitive<String>,
+}
+
+/// Passes a report on to the instance admins, eg for content which breaks the site rules
+#[derive(Debug, Serialize, Deserialize)]
+pub struct EscalateCommentReport {
+  pub report_id: CommentReportId,
   pub auth: Sensitive<String>,
 }
 
@@ -111,6 +191,8 @@ pub struct ListCommentReports {
   pub limit: Option<i64>,
   /// Only shows the unresolved reports
   pub unresolved_only: Option<bool>,
+  /// Only shows the reports which mods escalated to the admins
+  pub escalated_only: Option<bool>,
   /// if no community is given, it returns reports for all communities moderated by the auth user
   pub community_id: Option<CommunityId>,
   pub auth: Sensitive<String>,
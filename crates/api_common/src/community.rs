@@ -1,4 +1,15 @@
-use lemmy_db_schema::newtypes::{CommunityId, PersonId};
+use lemmy_db_schema::{
+  newtypes::{CommentId, CommunityId, LanguageId, PersonId, PostId},
+  source::{
+    appeal::Appeal,
+    automod_rule::AutomodRule,
+    community_category::CommunityCategory,
+    community_wiki_page::{CommunityWikiPage, CommunityWikiPageRevision},
+    pending_deletion::PendingDeletion,
+    post_tag::PostTag,
+  },
+};
+use lemmy_db_views::post_view::PostView;
 use lemmy_db_views_actor::{
   community_moderator_view::CommunityModeratorView,
   community_view::CommunityView,
@@ -6,6 +17,7 @@ use lemmy_db_views_actor::{
 };
 use lemmy_utils::Sensitive;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct GetCommunity {
@@ -20,6 +32,23 @@ pub struct GetCommunityResponse {
   pub community_view: CommunityView,
   pub moderators: Vec<CommunityModeratorView>,
   pub online: usize,
+  /// Languages in which posts and comments are allowed. Empty if all are allowed.
+  pub discussion_languages: Vec<LanguageId>,
+}
+
+/// Fetches the latest posts of a community, which also works for remote communities that nobody
+/// on this instance follows
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PreviewCommunity {
+  /// Example: https://xyz.tld/c/star_trek , or !star_trek@xyz.tld
+  pub q: String,
+  pub auth: Option<Sensitive<String>>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PreviewCommunityResponse {
+  pub community_view: CommunityView,
+  pub posts: Vec<PostView>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -30,18 +59,31 @@ pub struct CreateCommunity {
   pub icon: Option<String>,
   pub banner: Option<String>,
   pub nsfw: Option<bool>,
+  /// Posts by users who aren't moderators need to be approved before they are published
+  pub require_post_approval: Option<bool>,
   pub auth: Sensitive<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct CommunityResponse {
   pub community_view: CommunityView,
+  /// Set by the moderation handlers, so that clients can tell which action was actually applied.
+  pub action_taken: Option<CommunityAction>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+pub enum CommunityAction {
+  Deleted,
+  Restored,
+  Removed,
+  Unremoved,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
 pub struct ListCommunities {
   pub type_: Option<String>,
   pub sort: Option<String>,
+  pub category_id: Option<i32>,
   pub page: Option<i64>,
   pub limit: Option<i64>,
   pub auth: Option<Sensitive<String>>,
@@ -60,6 +102,8 @@ pub struct BanFromCommunity {
   pub remove_data: Option<bool>,
   pub reason: Option<String>,
   pub expires: Option<i64>,
+  /// Set to false to leave the action out of the federated modlog
+  pub public_modlog: Option<bool>,
   pub auth: Sensitive<String>,
 }
 
@@ -90,6 +134,9 @@ pub struct EditCommunity {
   pub icon: Option<String>,
   pub banner: Option<String>,
   pub nsfw: Option<bool>,
+  pub require_post_approval: Option<bool>,
+  /// Replaces the allowed languages, if given. An empty list allows all languages.
+  pub discussion_languages: Option<Vec<LanguageId>>,
   pub auth: Sensitive<String>,
 }
 
@@ -105,7 +152,13 @@ pub struct RemoveCommunity {
   pub community_id: CommunityId,
   pub removed: bool,
   pub reason: Option<String>,
+  /// Translations of the reason, as map of language code to text
+  pub reason_translations: Option<HashMap<String, String>>,
   pub expires: Option<i64>,
+  /// Admins can set this to remove without reason in emergencies, if a reason is required
+  pub reason_override: Option<bool>,
+  /// Set to false to leave the action out of the federated modlog
+  pub public_modlog: Option<bool>,
   pub auth: Sensitive<String>,
 }
 
@@ -135,3 +188,258 @@ pub struct TransferCommunity {
   pub person_id: PersonId,
   pub auth: Sensitive<String>,
 }
+
+/// Deletions of local content received from other instances, which are waiting for review. Without
+/// community_id, lists those of all communities (admin only).
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ListPendingDeletions {
+  pub community_id: Option<CommunityId>,
+  pub page: Option<i64>,
+  pub limit: Option<i64>,
+  pub auth: Sensitive<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ListPendingDeletionsResponse {
+  pub pending_deletions: Vec<PendingDeletion>,
+}
+
+/// Apply or discard a pending deletion
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ResolvePendingDeletion {
+  pub id: i32,
+  pub approve: bool,
+  pub auth: Sensitive<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ResolvePendingDeletionResponse {
+  pub pending_deletion: PendingDeletion,
+}
+
+/// Appeal against the removal of your own post or comment, or of a community which you moderate.
+/// Exactly one of post_id, comment_id and community_id must be given.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CreateAppeal {
+  pub post_id: Option<PostId>,
+  pub comment_id: Option<CommentId>,
+  pub community_id: Option<CommunityId>,
+  pub reason: String,
+  pub auth: Sensitive<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct AppealResponse {
+  pub appeal: Appeal,
+}
+
+/// Without community_id, lists the appeals of all communities (admin only).
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ListAppeals {
+  pub community_id: Option<CommunityId>,
+  pub unresolved_only: Option<bool>,
+  pub page: Option<i64>,
+  pub limit: Option<i64>,
+  pub auth: Sensitive<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ListAppealsResponse {
+  pub appeals: Vec<Appeal>,
+}
+
+/// Restore the removed object if grant is true, otherwise uphold the removal
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ResolveAppeal {
+  pub id: i32,
+  pub grant: bool,
+  pub reason: Option<String>,
+  pub auth: Sensitive<String>,
+}
+
+/// Categories are defined by the admins, and can then be assigned to communities
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CreateCommunityCategory {
+  pub name: String,
+  pub auth: Sensitive<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct EditCommunityCategory {
+  pub id: i32,
+  pub name: String,
+  pub auth: Sensitive<String>,
+}
+
+/// The communities of the category are left without one
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DeleteCommunityCategory {
+  pub id: i32,
+  pub auth: Sensitive<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CommunityCategoryResponse {
+  pub community_category: CommunityCategory,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ListCommunityCategories {
+  pub auth: Option<Sensitive<String>>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ListCommunityCategoriesResponse {
+  pub community_categories: Vec<CommunityCategory>,
+}
+
+/// Assigns the community to a category, or removes it from its category if `category_id` is
+/// empty. Can be done by the mods and admins.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SetCommunityCategory {
+  pub community_id: CommunityId,
+  pub category_id: Option<i32>,
+  pub auth: Sensitive<String>,
+}
+
+/// Tags are defined by the moderators, and can then be assigned to posts in the community
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CreatePostTag {
+  pub community_id: CommunityId,
+  pub name: String,
+  pub auth: Sensitive<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct EditPostTag {
+  pub tag_id: i32,
+  pub name: String,
+  pub auth: Sensitive<String>,
+}
+
+/// Also removes the tag from all posts
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DeletePostTag {
+  pub tag_id: i32,
+  pub auth: Sensitive<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PostTagResponse {
+  pub post_tag: PostTag,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ListPostTags {
+  pub community_id: CommunityId,
+  pub auth: Option<Sensitive<String>>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ListPostTagsResponse {
+  pub post_tags: Vec<PostTag>,
+}
+
+/// Automod rules are defined by the moderators, and applied to new or edited posts and comments
+/// of the community. The action is one of Remove, Report or RequireApproval.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CreateAutomodRule {
+  pub community_id: CommunityId,
+  pub title_regex: Option<String>,
+  pub body_regex: Option<String>,
+  pub link_domain: Option<String>,
+  pub account_age_below_days: Option<i32>,
+  pub karma_below: Option<i32>,
+  pub action: String,
+  pub reason: Option<String>,
+  pub auth: Sensitive<String>,
+}
+
+/// Replaces all conditions of the rule
+#[derive(Debug, Serialize, Deserialize)]
+pub struct EditAutomodRule {
+  pub rule_id: i32,
+  pub title_regex: Option<String>,
+  pub body_regex: Option<String>,
+  pub link_domain: Option<String>,
+  pub account_age_below_days: Option<i32>,
+  pub karma_below: Option<i32>,
+  pub action: String,
+  pub reason: Option<String>,
+  pub auth: Sensitive<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DeleteAutomodRule {
+  pub rule_id: i32,
+  pub auth: Sensitive<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct AutomodRuleResponse {
+  pub automod_rule: AutomodRule,
+}
+
+/// Only visible to the moderators
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ListAutomodRules {
+  pub community_id: CommunityId,
+  pub auth: Sensitive<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ListAutomodRulesResponse {
+  pub automod_rules: Vec<AutomodRule>,
+}
+
+/// Wiki pages can only be created and edited by the mods of the community
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CreateWikiPage {
+  pub community_id: CommunityId,
+  pub title: String,
+  pub content: String,
+  pub auth: Sensitive<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct EditWikiPage {
+  pub wiki_page_id: i32,
+  pub content: String,
+  pub auth: Sensitive<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DeleteWikiPage {
+  pub wiki_page_id: i32,
+  pub deleted: bool,
+  pub auth: Sensitive<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct WikiPageResponse {
+  pub wiki_page: CommunityWikiPage,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GetWikiPage {
+  pub wiki_page_id: i32,
+  pub auth: Option<Sensitive<String>>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct GetWikiPageResponse {
+  pub wiki_page: CommunityWikiPage,
+  /// Newest first
+  pub revisions: Vec<CommunityWikiPageRevision>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ListWikiPages {
+  pub community_id: CommunityId,
+  pub auth: Option<Sensitive<String>>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ListWikiPagesResponse {
+  pub wiki_pages: Vec<CommunityWikiPage>,
+}
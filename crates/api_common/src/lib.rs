@@ -1,22 +1,42 @@
+pub mod captcha;
 pub mod comment;
 pub mod community;
 pub mod person;
 pub mod post;
 pub mod site;
+pub mod token_scope;
 pub mod websocket;
 
-use crate::site::FederatedInstances;
+use crate::{
+  site::FederatedInstances,
+  token_scope::{check_token_scopes, is_personal_access_token},
+};
 use lemmy_db_schema::{
-  newtypes::{CommunityId, LocalUserId, PersonId, PostId},
+  clock::Clock,
+  newtypes::{
+    CommunityId,
+    LanguageId,
+    LocalUserId,
+    MultiCommunityId,
+    PersonId,
+    PostId,
+    SavedCollectionId,
+  },
   source::{
     community::Community,
     email_verification::{EmailVerification, EmailVerificationForm},
+    federation_instance::FederationInstance,
+    language::CommunityLanguage,
+    login_session::{LoginSession, LoginSessionForm},
+    multi_community::MultiCommunity,
     password_reset_request::PasswordResetRequest,
     person_block::PersonBlock,
     post::{Post, PostRead, PostReadForm},
     registration_application::RegistrationApplication,
+    saved_collection::SavedCollection,
     secret::Secret,
     site::Site,
+    user_token::UserToken,
   },
   traits::{Crud, Readable},
   DbPool,
@@ -28,12 +48,16 @@ use lemmy_db_views_actor::{
 };
 use lemmy_utils::{
   claims::Claims,
+  client_info::current_client_info,
   email::send_email,
+  metrics::METRICS,
   settings::structs::{FederationConfig, Settings},
-  utils::generate_random_string,
+  utils::{generate_random_string, naive_from_unix},
   LemmyError,
   Sensitive,
 };
+use std::future::Future;
+use tracing::warn;
 use url::Url;
 
 pub async fn blocking<F, T>(pool: &DbPool, f: F) -> Result<T, LemmyError>
@@ -43,6 +67,7 @@ where
 {
   let pool = pool.clone();
   let blocking_span = tracing::info_span!("blocking operation");
+  let _in_flight = METRICS.track_blocking();
   let res = actix_web::web::block(move || {
     let entered = blocking_span.enter();
     let conn = pool.get()?;
@@ -55,6 +80,25 @@ where
   res
 }
 
+/// Websocket messages are sent after the actual change was written to the database and federated.
+/// If sending fails the request still succeeded, so the error is only logged, and the response is
+/// built by `fallback` instead.
+pub async fn send_ws_message_best_effort<T, Fb>(
+  ws_message: impl Future<Output = Result<T, LemmyError>>,
+  fallback: impl FnOnce() -> Fb,
+) -> Result<T, LemmyError>
+where
+  Fb: Future<Output = Result<T, LemmyError>>,
+{
+  match ws_message.await {
+    Ok(res) => Ok(res),
+    Err(e) => {
+      warn!("Failed to send websocket message: {}", e);
+      fallback().await
+    }
+  }
+}
+
 pub async fn is_mod_or_admin(
   pool: &DbPool,
   person_id: PersonId,
@@ -119,6 +163,10 @@ pub async fn get_local_user_view_from_jwt(
   pool: &DbPool,
   secret: &Secret,
 ) -> Result<LocalUserView, LemmyError> {
+  if is_personal_access_token(jwt) {
+    return get_local_user_view_from_token(jwt, pool).await;
+  }
+
   let claims = Claims::decode(jwt, &secret.jwt_secret)
     .map_err(LemmyError::from)
     .map_err(|e| e.with_message("not_logged_in"))?
@@ -126,6 +174,37 @@ pub async fn get_local_user_view_from_jwt(
   let local_user_id = LocalUserId(claims.sub);
   let local_user_view =
     blocking(pool, move |conn| LocalUserView::read(conn, local_user_id)).await??;
+  check_user_valid(&local_user_view)?;
+
+  check_validator_time(&local_user_view.local_user.validator_time, &claims)?;
+  check_login_session(&claims, pool).await?;
+
+  Ok(local_user_view)
+}
+
+/// Authenticates with a personal access token, which needs the scope of the current request
+async fn local_user_id_from_token(token: &str, pool: &DbPool) -> Result<LocalUserId, LemmyError> {
+  let token = token.to_owned();
+  let user_token = blocking(pool, move |conn| UserToken::read_from_token(conn, &token))
+    .await?
+    .map_err(LemmyError::from)
+    .map_err(|e| e.with_message("not_logged_in"))?;
+  check_token_scopes(user_token.token_scopes())?;
+  Ok(user_token.local_user_id)
+}
+
+async fn get_local_user_view_from_token(
+  token: &str,
+  pool: &DbPool,
+) -> Result<LocalUserView, LemmyError> {
+  let local_user_id = local_user_id_from_token(token, pool).await?;
+  let local_user_view =
+    blocking(pool, move |conn| LocalUserView::read(conn, local_user_id)).await??;
+  check_user_valid(&local_user_view)?;
+  Ok(local_user_view)
+}
+
+fn check_user_valid(local_user_view: &LocalUserView) -> Result<(), LemmyError> {
   // Check for a site ban
   if local_user_view.person.banned {
     return Err(LemmyError::from_message("site_ban"));
@@ -135,10 +214,7 @@ pub async fn get_local_user_view_from_jwt(
   if local_user_view.person.deleted {
     return Err(LemmyError::from_message("deleted"));
   }
-
-  check_validator_time(&local_user_view.local_user.validator_time, &claims)?;
-
-  Ok(local_user_view)
+  Ok(())
 }
 
 /// Checks if user's token was issued before user's password reset.
@@ -154,6 +230,45 @@ pub fn check_validator_time(
   }
 }
 
+/// Makes sure that the login session of the token wasn't revoked
+async fn check_login_session(claims: &Claims, pool: &DbPool) -> Result<(), LemmyError> {
+  // Older tokens don't have a session, they can only be revoked through the validator time
+  if let Some(session_id) = claims.sid {
+    let local_user_id = LocalUserId(claims.sub);
+    let valid = blocking(pool, move |conn| {
+      LoginSession::is_valid(conn, session_id, local_user_id)
+    })
+    .await??;
+    if !valid {
+      return Err(LemmyError::from_message("not_logged_in"));
+    }
+  }
+  Ok(())
+}
+
+/// Starts a login session for the client of the current request, and returns its token
+pub async fn create_login_session(
+  local_user_id: LocalUserId,
+  pool: &DbPool,
+  secret: &Secret,
+  settings: &Settings,
+) -> Result<Sensitive<String>, LemmyError> {
+  let client_info = current_client_info();
+  let form = LoginSessionForm {
+    local_user_id,
+    user_agent: client_info.user_agent,
+    ip: client_info.ip,
+  };
+  let session = blocking(pool, move |conn| LoginSession::create(conn, &form)).await??;
+  let jwt = Claims::jwt(
+    local_user_id.0,
+    session.id,
+    &secret.jwt_secret,
+    &settings.hostname,
+  )?;
+  Ok(jwt.into())
+}
+
 pub async fn get_local_user_view_from_jwt_opt(
   jwt: Option<&Sensitive<String>>,
   pool: &DbPool,
@@ -170,6 +285,18 @@ pub async fn get_local_user_settings_view_from_jwt(
   pool: &DbPool,
   secret: &Secret,
 ) -> Result<LocalUserSettingsView, LemmyError> {
+  if is_personal_access_token(jwt) {
+    let local_user_id = local_user_id_from_token(jwt, pool).await?;
+    let local_user_view = blocking(pool, move |conn| {
+      LocalUserSettingsView::read(conn, local_user_id)
+    })
+    .await??;
+    if local_user_view.person.banned {
+      return Err(LemmyError::from_message("site_ban"));
+    }
+    return Ok(local_user_view);
+  }
+
   let claims = Claims::decode(jwt.as_ref(), &secret.jwt_secret)
     .map_err(LemmyError::from)
     .map_err(|e| e.with_message("not_logged_in"))?
@@ -185,6 +312,7 @@ pub async fn get_local_user_settings_view_from_jwt(
   }
 
   check_validator_time(&local_user_view.local_user.validator_time, &claims)?;
+  check_login_session(&claims, pool).await?;
 
   Ok(local_user_view)
 }
@@ -231,6 +359,23 @@ pub async fn check_community_deleted_or_removed(
   }
 }
 
+pub async fn check_language_allowed(
+  community_id: CommunityId,
+  language_id: Option<LanguageId>,
+  pool: &DbPool,
+) -> Result<(), LemmyError> {
+  if let Some(language_id) = language_id {
+    let allowed = blocking(pool, move |conn| {
+      CommunityLanguage::is_allowed(conn, community_id, language_id)
+    })
+    .await??;
+    if !allowed {
+      return Err(LemmyError::from_message("language_not_allowed"));
+    }
+  }
+  Ok(())
+}
+
 pub fn check_post_deleted_or_removed(post: &Post) -> Result<(), LemmyError> {
   if post.deleted || post.removed {
     Err(LemmyError::from_message("deleted"))
@@ -252,6 +397,59 @@ pub async fn check_person_block(
   }
 }
 
+/// Checks that the person isn't blocked from commenting on posts and comments of the other person
+pub async fn check_person_interaction_block(
+  my_id: PersonId,
+  potential_blocker_id: PersonId,
+  pool: &DbPool,
+) -> Result<(), LemmyError> {
+  let prevents_interaction =
+    move |conn: &'_ _| PersonBlock::prevents_interaction(conn, potential_blocker_id, my_id);
+  if blocking(pool, prevents_interaction).await?? {
+    Err(LemmyError::from_message("person_block"))
+  } else {
+    Ok(())
+  }
+}
+
+/// Checks that the saved collection exists and belongs to the person. `None` stands for the
+/// unfiled list and is always allowed.
+pub async fn check_saved_collection_owner(
+  collection_id: Option<SavedCollectionId>,
+  person_id: PersonId,
+  pool: &DbPool,
+) -> Result<(), LemmyError> {
+  if let Some(collection_id) = collection_id {
+    let collection = blocking(pool, move |conn| SavedCollection::read(conn, collection_id))
+      .await?
+      .map_err(LemmyError::from)
+      .map_err(|e| e.with_message("couldnt_find_saved_collection"))?;
+    if collection.person_id != person_id {
+      return Err(LemmyError::from_message("not_saved_collection_owner"));
+    }
+  }
+  Ok(())
+}
+
+pub async fn check_multi_community_owner(
+  multi_community_id: Option<MultiCommunityId>,
+  person_id: Option<PersonId>,
+  pool: &DbPool,
+) -> Result<(), LemmyError> {
+  if let Some(multi_community_id) = multi_community_id {
+    let multi_community = blocking(pool, move |conn| {
+      MultiCommunity::read(conn, multi_community_id)
+    })
+    .await?
+    .map_err(LemmyError::from)
+    .map_err(|e| e.with_message("couldnt_find_multi_community"))?;
+    if Some(multi_community.person_id) != person_id {
+      return Err(LemmyError::from_message("not_multi_community_owner"));
+    }
+  }
+  Ok(())
+}
+
 pub async fn check_downvotes_enabled(score: i16, pool: &DbPool) -> Result<(), LemmyError> {
   if score == -1 {
     let site = blocking(pool, Site::read_simple).await??;
@@ -287,8 +485,9 @@ pub async fn build_federated_instances(
     })
     .await??;
 
-    let allowed = federation.allowed_instances;
-    let blocked = federation.blocked_instances;
+    let instance_lists = FederationInstance::cached_lists()?;
+    let allowed = instance_lists.combined_allowlist(&federation.allowed_instances);
+    let blocked = instance_lists.combined_blocklist(&federation.blocked_instances);
 
     let mut linked = distinct_communities
       .iter()
@@ -335,6 +534,80 @@ pub fn site_description_length_check(description: &str) -> Result<(), LemmyError
   }
 }
 
+/// Trims the note which a moderator attached to a report resolution, and checks its length
+pub fn check_resolution_note(note: &Option<String>) -> Result<Option<String>, LemmyError> {
+  let note = note
+    .as_ref()
+    .map(|n| n.trim().to_string())
+    .filter(|n| !n.is_empty());
+  if note.as_ref().map(|n| n.chars().count() > 1000) == Some(true) {
+    return Err(LemmyError::from_message("resolution_note_too_long"));
+  }
+  Ok(note)
+}
+
+/// Converts the optional unix expiry time of a mod action, which has to be in the future
+pub fn check_expire_time(
+  expires: Option<i64>,
+  clock: &dyn Clock,
+) -> Result<Option<chrono::NaiveDateTime>, LemmyError> {
+  if let Some(expires) = expires.map(naive_from_unix) {
+    if expires <= clock.now() {
+      return Err(LemmyError::from_message("expiry_time_not_in_future"));
+    }
+    Ok(Some(expires))
+  } else {
+    Ok(None)
+  }
+}
+
+/// Checks that a removal has a reason, if this is required by the instance. In emergencies, admins
+/// can remove without reason by setting `reason_override`. Returns true if the override was used,
+/// so that it can be marked in the modlog.
+pub fn check_removal_reason(
+  reason: &Option<String>,
+  reason_required: bool,
+  reason_override: Option<bool>,
+  is_admin: bool,
+) -> Result<bool, LemmyError> {
+  let has_reason = reason
+    .as_ref()
+    .map(|r| !r.trim().is_empty())
+    .unwrap_or(false);
+  if !reason_required || has_reason {
+    Ok(false)
+  } else if reason_override.unwrap_or(false) && is_admin {
+    Ok(true)
+  } else {
+    Err(LemmyError::from_message("removal_reason_required"))
+  }
+}
+
+/// Picks the translation of a removal reason which best matches the given language. Reasons
+/// without translations are assumed to be in the default language of the instance.
+pub fn localize_reason(
+  reason: Option<String>,
+  reason_translations: &Option<serde_json::Value>,
+  lang: &str,
+  default_language: &str,
+) -> Option<String> {
+  if lang == default_language {
+    return reason;
+  }
+  let translations = match reason_translations.as_ref().and_then(|t| t.as_object()) {
+    Some(t) => t,
+    None => return reason,
+  };
+  // Fall back from eg `pt_BR` to `pt`
+  let base_lang = lang.split(['_', '-']).next().unwrap_or(lang);
+  translations
+    .get(lang)
+    .or_else(|| translations.get(base_lang))
+    .and_then(|t| t.as_str())
+    .map(ToString::to_string)
+    .or(reason)
+}
+
 /// Checks for a honeypot. If this field is filled, fail the rest of the function
 pub fn honeypot_check(honeypot: &Option<String>) -> Result<(), LemmyError> {
   if honeypot.is_some() {
@@ -503,3 +776,86 @@ pub async fn check_private_instance_and_federation_enabled(
   }
   Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+  use crate::{
+    check_expire_time,
+    check_removal_reason,
+    localize_reason,
+    send_ws_message_best_effort,
+  };
+  use chrono::{Duration, NaiveDate};
+  use lemmy_db_schema::clock::{Clock, MockClock};
+  use lemmy_utils::LemmyError;
+  use serde_json::json;
+
+  #[test]
+  fn test_send_ws_message_best_effort() {
+    let system = actix_web::rt::System::new();
+
+    let failed = system.block_on(send_ws_message_best_effort(
+      async { Err(LemmyError::from_message("websocket_send_failed")) },
+      || async { Ok("fallback") },
+    ));
+    assert_eq!("fallback", failed.unwrap());
+
+    let sent = system.block_on(send_ws_message_best_effort(
+      async { Ok("sent") },
+      || async { Err(LemmyError::from_message("fallback_called")) },
+    ));
+    assert_eq!("sent", sent.unwrap());
+  }
+
+  #[test]
+  fn test_check_expire_time() {
+    let clock = MockClock::new(NaiveDate::from_ymd(2021, 12, 1).and_hms(12, 0, 0));
+    let now = clock.now().timestamp();
+    let past = now - Duration::days(1).num_seconds();
+    let future = now + Duration::days(1).num_seconds();
+
+    assert!(check_expire_time(None, &clock).unwrap().is_none());
+    assert!(check_expire_time(Some(past), &clock).is_err());
+    assert!(check_expire_time(Some(now), &clock).is_err());
+    assert_eq!(
+      future,
+      check_expire_time(Some(future), &clock)
+        .unwrap()
+        .unwrap()
+        .timestamp()
+    );
+
+    clock.advance(Duration::days(2));
+    assert!(check_expire_time(Some(future), &clock).is_err());
+  }
+
+  #[test]
+  fn test_localize_reason() {
+    let reason = Some("spam".to_string());
+    let translations = Some(json!({ "de": "Spam-Werbung", "pt_BR": "propaganda" }));
+    let localize = |lang| localize_reason(reason.clone(), &translations, lang, "en");
+
+    assert_eq!(reason, localize("en"));
+    assert_eq!(Some("Spam-Werbung".to_string()), localize("de"));
+    assert_eq!(Some("Spam-Werbung".to_string()), localize("de_AT"));
+    assert_eq!(Some("propaganda".to_string()), localize("pt_BR"));
+    assert_eq!(reason, localize("fr"));
+    assert_eq!(reason, localize_reason(reason.clone(), &None, "de", "en"));
+  }
+
+  #[test]
+  fn test_check_removal_reason() {
+    let reason = Some("spam".to_string());
+    let empty = Some(" ".to_string());
+
+    assert!(!check_removal_reason(&None, false, None, false).unwrap());
+    assert!(!check_removal_reason(&reason, true, None, false).unwrap());
+    assert!(check_removal_reason(&None, true, None, false).is_err());
+    assert!(check_removal_reason(&empty, true, None, true).is_err());
+    // Only admins can override
+    assert!(check_removal_reason(&None, true, Some(true), false).is_err());
+    assert!(check_removal_reason(&empty, true, Some(true), true).unwrap());
+    // The override is only noted if it was needed
+    assert!(!check_removal_reason(&reason, true, Some(true), true).unwrap());
+  }
+}
@@ -1,22 +1,48 @@
 use lemmy_db_views::{
+  account_archive_view::AccountArchiveView,
   comment_view::CommentView,
   post_view::PostView,
+  private_message_thread_view::PrivateMessageThreadView,
   private_message_view::PrivateMessageView,
 };
 use lemmy_db_views_actor::{
   community_moderator_view::CommunityModeratorView,
+  multi_community_view::MultiCommunityView,
   person_mention_view::PersonMentionView,
+  person_stats_view::PersonStatsView,
   person_view::PersonViewSafe,
 };
 use lemmy_utils::Sensitive;
 use serde::{Deserialize, Serialize};
+use url::Url;
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Login {
   pub username_or_email: Sensitive<String>,
   pub password: Sensitive<String>,
-}
-use lemmy_db_schema::newtypes::{CommunityId, PersonId, PersonMentionId, PrivateMessageId};
+  /// Required if the user enabled two-factor authentication
+  pub totp_token: Option<String>,
+}
+use lemmy_db_schema::{
+  newtypes::{
+    CommentId,
+    CommunityId,
+    LanguageId,
+    MultiCommunityId,
+    PersonId,
+    PersonMentionId,
+    PostId,
+    PrivateMessageId,
+    SavedCollectionId,
+  },
+  source::{
+    login_session::LoginSession,
+    person_field::PersonField,
+    saved_collection::SavedCollection,
+    user_token::UserToken,
+  },
+  TokenScope,
+};
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Register {
@@ -39,6 +65,9 @@ pub struct GetCaptcha {}
 #[derive(Debug, Serialize, Deserialize)]
 pub struct GetCaptchaResponse {
   pub ok: Option<CaptchaResponse>, // Will be None if captchas are disabled
+  /// Set instead of `ok` if the site uses hCaptcha or reCAPTCHA. The response token of the widget
+  /// is sent as `captcha_answer` when registering.
+  pub external: Option<ExternalCaptcha>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -48,6 +77,14 @@ pub struct CaptchaResponse {
   pub uuid: String,
 }
 
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ExternalCaptcha {
+  /// Either hcaptcha or recaptcha
+  pub provider: String,
+  /// Public key for the widget
+  pub site_key: String,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct SaveUserSettings {
   pub show_nsfw: Option<bool>,
@@ -68,9 +105,22 @@ pub struct SaveUserSettings {
   pub show_bot_accounts: Option<bool>,
   pub show_read_posts: Option<bool>,
   pub show_new_post_notifs: Option<bool>,
+  /// One of `Never`, `Daily` or `Weekly`
+  pub email_digest_frequency: Option<String>,
+  /// Replaces all profile fields, at most four
+  pub fields: Option<Vec<ProfileField>>,
+  /// Only show posts and comments in these languages. An empty list shows all languages.
+  pub discussion_languages: Option<Vec<LanguageId>>,
   pub auth: Sensitive<String>,
 }
 
+/// A key/value pair shown on the profile, eg a link to a website. The value is markdown.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ProfileField {
+  pub name: String,
+  pub value: String,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ChangePassword {
   pub new_password: Sensitive<String>,
@@ -103,11 +153,26 @@ pub struct GetPersonDetails {
 #[derive(Debug, Serialize, Deserialize)]
 pub struct GetPersonDetailsResponse {
   pub person_view: PersonViewSafe,
+  pub fields: Vec<PersonField>,
   pub comments: Vec<CommentView>,
   pub posts: Vec<PostView>,
   pub moderates: Vec<CommunityModeratorView>,
 }
 
+/// Karma per community, posting activity per month and account age of a person
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GetPersonStats {
+  pub person_id: PersonId,
+  /// Number of months for which activity is returned, 12 by default
+  pub months: Option<i64>,
+  pub auth: Option<Sensitive<String>>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct GetPersonStatsResponse {
+  pub person_stats_view: PersonStatsView,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct GetRepliesResponse {
   pub replies: Vec<CommentView>,
@@ -151,10 +216,135 @@ pub struct BanPersonResponse {
   pub banned: bool,
 }
 
+/// Bans a person and removes all their posts and comments. With `community_id` the ban and the
+/// removals are limited to that community, otherwise they are site-wide (admin only).
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RemoveContentAndBan {
+  pub person_id: PersonId,
+  pub community_id: Option<CommunityId>,
+  pub reason: Option<String>,
+  pub reason_override: Option<bool>,
+  pub expires: Option<i64>,
+  pub auth: Sensitive<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RemoveContentAndBanResponse {
+  pub person_view: PersonViewSafe,
+  pub removed_posts: i64,
+  pub removed_comments: i64,
+}
+
+/// Generates a new secret for two-factor authentication. It only takes effect once it is confirmed
+/// with `UpdateTotp`.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GenerateTotpSecret {
+  pub auth: Sensitive<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct GenerateTotpSecretResponse {
+  /// An `otpauth://` url, which clients can show as QR code for authenticator apps
+  pub totp_secret_url: Sensitive<String>,
+}
+
+/// Enables or disables two-factor authentication. Both need a valid token from the authenticator
+/// app.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UpdateTotp {
+  pub totp_token: String,
+  pub enabled: bool,
+  pub auth: Sensitive<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct UpdateTotpResponse {
+  pub enabled: bool,
+}
+
+/// Starts generating an archive of all your data in the background. Once it is finished, it can be
+/// downloaded with `GetAccountExport` and the returned token.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ExportAccountData {
+  pub auth: Sensitive<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ExportAccountDataResponse {
+  pub token: Sensitive<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GetAccountExport {
+  pub token: Sensitive<String>,
+  pub auth: Sensitive<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct GetAccountExportResponse {
+  pub finished: bool,
+  /// Only set once the archive is finished
+  pub archive: Option<AccountArchiveView>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PushSubscriptionKeys {
+  pub p256dh: String,
+  pub auth: String,
+}
+
+/// A subscription from the Push API of the browser, as returned by `PushSubscription.toJSON()`.
+/// The `GetSiteResponse` contains the key which is needed to create it.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RegisterPushSubscription {
+  pub endpoint: String,
+  pub keys: PushSubscriptionKeys,
+  pub auth: Sensitive<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DeletePushSubscription {
+  pub endpoint: String,
+  pub auth: Sensitive<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PushSubscriptionResponse {}
+
+/// Restores the follows, blocks and saved posts from an archive of `GetAccountExport`, which can
+/// come from another instance.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ImportAccountData {
+  /// The archive as json
+  pub data: String,
+  pub auth: Sensitive<String>,
+}
+
+/// The import runs in the background, as each object may need to be fetched from another instance.
+/// Once it is done, an `AccountImportResult` is sent to the websocket user room.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ImportAccountDataResponse {
+  /// How many objects are going to be imported
+  pub objects: usize,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct AccountImportResult {
+  pub followed_communities: usize,
+  pub blocked_communities: usize,
+  pub blocked_persons: usize,
+  pub saved_posts: usize,
+  /// Objects which couldn't be fetched or imported, and were skipped
+  pub failed: Vec<Url>,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct BlockPerson {
   pub person_id: PersonId,
   pub block: bool,
+  /// Also prevent the blocked person from commenting on your posts and comments. Private messages
+  /// are always prevented by a block.
+  pub prevent_interaction: Option<bool>,
   pub auth: Sensitive<String>,
 }
 
@@ -164,6 +354,128 @@ pub struct BlockPersonResponse {
   pub blocked: bool,
 }
 
+/// Hides all posts, comments and communities from the instance for the user
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BlockInstance {
+  pub domain: String,
+  pub block: bool,
+  pub auth: Sensitive<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct BlockInstanceResponse {
+  pub domain: String,
+  pub blocked: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CreateSavedCollection {
+  pub name: String,
+  pub auth: Sensitive<String>,
+}
+
+/// Renames a saved collection
+#[derive(Debug, Serialize, Deserialize)]
+pub struct EditSavedCollection {
+  pub collection_id: SavedCollectionId,
+  pub name: String,
+  pub auth: Sensitive<String>,
+}
+
+/// Deletes a saved collection. The items in it stay saved, but without a collection.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DeleteSavedCollection {
+  pub collection_id: SavedCollectionId,
+  pub auth: Sensitive<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SavedCollectionResponse {
+  pub collection: SavedCollection,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ListSavedCollections {
+  pub auth: Sensitive<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ListSavedCollectionsResponse {
+  pub collections: Vec<SavedCollection>,
+}
+
+/// Creates a named group of communities, whose posts can be listed together with
+/// `GetPosts.multi_community_id`
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CreateMultiCommunity {
+  pub name: String,
+  pub community_ids: Vec<CommunityId>,
+  pub auth: Sensitive<String>,
+}
+
+/// Renames a multi-community and replaces its communities
+#[derive(Debug, Serialize, Deserialize)]
+pub struct EditMultiCommunity {
+  pub multi_community_id: MultiCommunityId,
+  pub name: String,
+  pub community_ids: Vec<CommunityId>,
+  pub auth: Sensitive<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DeleteMultiCommunity {
+  pub multi_community_id: MultiCommunityId,
+  pub auth: Sensitive<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct MultiCommunityResponse {
+  pub multi_community_view: MultiCommunityView,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ListMultiCommunities {
+  pub auth: Sensitive<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ListMultiCommunitiesResponse {
+  pub multi_communities: Vec<MultiCommunityView>,
+}
+
+/// Moves a saved post or comment into another collection. Without `collection_id` it is moved
+/// back into the unfiled list.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct MoveSaved {
+  pub post_id: Option<PostId>,
+  pub comment_id: Option<CommentId>,
+  pub collection_id: Option<SavedCollectionId>,
+  pub auth: Sensitive<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct MoveSavedResponse {
+  pub post_id: Option<PostId>,
+  pub comment_id: Option<CommentId>,
+  pub collection_id: Option<SavedCollectionId>,
+}
+
+/// Lists the saved posts and comments of the user, optionally only those of one collection
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GetSaved {
+  pub collection_id: Option<SavedCollectionId>,
+  pub sort: Option<String>,
+  pub page: Option<i64>,
+  pub limit: Option<i64>,
+  pub auth: Sensitive<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct GetSavedResponse {
+  pub posts: Vec<PostView>,
+  pub comments: Vec<CommentView>,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct GetReplies {
   pub sort: Option<String>,
@@ -216,6 +528,8 @@ pub struct PasswordChange {
   pub token: Sensitive<String>,
   pub password: Sensitive<String>,
   pub password_verify: Sensitive<String>,
+  /// Required if the user enabled two-factor authentication
+  pub totp_token: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -259,6 +573,28 @@ pub struct PrivateMessagesResponse {
   pub private_messages: Vec<PrivateMessageView>,
 }
 
+/// With thread_id, returns the messages of that conversation. Otherwise lists the conversations of
+/// the user, each with its newest messages.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GetPrivateMessageThread {
+  pub thread_id: Option<i32>,
+  pub page: Option<i64>,
+  pub limit: Option<i64>,
+  pub auth: Sensitive<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PrivateMessageConversation {
+  pub thread_view: PrivateMessageThreadView,
+  /// Newest first
+  pub messages: Vec<PrivateMessageView>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct GetPrivateMessageThreadResponse {
+  pub conversations: Vec<PrivateMessageConversation>,
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct PrivateMessageResponse {
   pub private_message_view: PrivateMessageView,
@@ -275,6 +611,24 @@ pub struct GetReportCountResponse {
   pub community_id: Option<CommunityId>,
   pub comment_reports: i64,
   pub post_reports: i64,
+  /// Unresolved reports which mods escalated, only returned to admins
+  pub escalated_comment_reports: Option<i64>,
+  pub escalated_post_reports: Option<i64>,
+}
+
+/// Resolves all open reports of a post and/or a comment at once, eg after removing it
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BulkResolveReports {
+  pub post_id: Option<PostId>,
+  pub comment_id: Option<CommentId>,
+  pub resolution_note: Option<String>,
+  pub auth: Sensitive<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct BulkResolveReportsResponse {
+  pub post_reports_resolved: i64,
+  pub comment_reports_resolved: i64,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -296,3 +650,137 @@ pub struct VerifyEmail {
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct VerifyEmailResponse {}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ResendVerificationEmail {
+  pub email: Sensitive<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ResendVerificationEmailResponse {}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CreateUserToken {
+  /// Describes what the token is used for
+  pub name: String,
+  pub scopes: Vec<TokenScope>,
+  pub auth: Sensitive<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CreateUserTokenResponse {
+  pub user_token: UserToken,
+  /// Only returned once, it can't be read again later
+  pub token: Sensitive<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ListUserTokens {
+  pub auth: Sensitive<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RevokeUserToken {
+  pub id: i32,
+  pub auth: Sensitive<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ListUserTokensResponse {
+  pub user_tokens: Vec<UserToken>,
+}
+
+/// Creates the secret token for the private RSS feeds, like `/feeds/inbox/{token}.xml`. A
+/// previous feed token stops working.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GenerateFeedToken {
+  pub auth: Sensitive<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct GenerateFeedTokenResponse {
+  /// Only returned once, it can't be read again later
+  pub feed_token: Sensitive<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ListSessions {
+  pub auth: Sensitive<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RevokeSession {
+  pub id: i32,
+  pub auth: Sensitive<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ListSessionsResponse {
+  pub sessions: Vec<LoginSession>,
+  /// The session which made this request
+  pub current_session_id: Option<i32>,
+}
+
+/// Logs out everywhere, including the session which made this request, and revokes all personal
+/// access tokens
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RevokeAllSessions {
+  pub auth: Sensitive<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RevokeAllSessionsResponse {}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ListOAuthProviders {}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ListOAuthProvidersResponse {
+  pub oauth_providers: Vec<PublicOAuthProvider>,
+}
+
+/// What clients need to send users to an OpenID Connect provider for authorization
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PublicOAuthProvider {
+  pub id: String,
+  pub display_name: String,
+  pub authorization_endpoint: String,
+  pub client_id: String,
+  pub scopes: String,
+}
+
+/// Starts the authorization code flow of an OpenID Connect provider
+#[derive(Debug, Serialize, Deserialize)]
+pub struct StartOAuth {
+  pub provider: String,
+  /// Where the provider sends the user back to after the authorization
+  pub redirect_uri: String,
+  /// If set, the provider account is linked to this user instead of logging in
+  pub auth: Option<Sensitive<String>>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct StartOAuthResponse {
+  /// The user needs to be sent here. It includes the state and the PKCE code challenge.
+  pub authorization_url: String,
+  pub state: String,
+}
+
+/// Finishes the authorization code flow of an OpenID Connect provider, and logs in the user whose
+/// account is linked to the provider account.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AuthenticateWithOAuth {
+  pub provider: String,
+  /// The code which the provider passed to the redirect uri
+  pub code: Sensitive<String>,
+  /// The state which the provider passed to the redirect uri, as returned by `StartOAuth`
+  pub state: Sensitive<String>,
+  /// Must be the same redirect uri which was passed to `StartOAuth`
+  pub redirect_uri: String,
+  /// Name of a newly created account. Defaults to the preferred username of the provider account.
+  pub username: Option<String>,
+  /// Answer for the registration application of a newly created account, if the site requires one
+  pub answer: Option<String>,
+  /// Needed if the provider account is linked to a user, must be the one who started it
+  pub auth: Option<Sensitive<String>>,
+}
@@ -1,6 +1,15 @@
-use lemmy_db_schema::newtypes::{CommunityId, PostId, PostReportId};
+use lemmy_db_schema::{
+  newtypes::{CommunityId, LanguageId, MultiCommunityId, PostId, PostReportId, SavedCollectionId},
+  source::{
+    post_attachment::PostAttachment,
+    post_draft::PostDraft,
+    post_tag::PostTag,
+    scheduled_post::ScheduledPost,
+  },
+};
 use lemmy_db_views::{
   comment_view::CommentView,
+  poll_view::PollView,
   post_report_view::PostReportView,
   post_view::PostView,
 };
@@ -10,6 +19,7 @@ use lemmy_db_views_actor::{
 };
 use lemmy_utils::{request::SiteMetadata, Sensitive};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use url::Url;
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -20,6 +30,27 @@ pub struct CreatePost {
   pub body: Option<String>,
   pub honeypot: Option<String>,
   pub nsfw: Option<bool>,
+  pub poll: Option<CreatePoll>,
+  /// Ids of tags defined by the community
+  pub tag_ids: Option<Vec<i32>>,
+  /// Has to be one of the community languages, if it is limited to some
+  pub language_id: Option<LanguageId>,
+  pub auth: Sensitive<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CreatePoll {
+  pub options: Vec<String>,
+  pub multiple_choice: Option<bool>,
+  /// Unix timestamp after which no more votes are accepted
+  pub end_time: Option<i64>,
+}
+
+/// Creates a copy of the post in another community, which links back to the original
+#[derive(Serialize, Deserialize, Debug)]
+pub struct CrossPost {
+  pub post_id: PostId,
+  pub community_id: CommunityId,
   pub auth: Sensitive<String>,
 }
 
@@ -41,6 +72,12 @@ pub struct GetPostResponse {
   pub comments: Vec<CommentView>,
   pub moderators: Vec<CommunityModeratorView>,
   pub online: usize,
+  pub poll: Option<PollView>,
+  pub tags: Vec<PostTag>,
+  /// Only federated posts can have attachments
+  pub attachments: Vec<PostAttachment>,
+  /// The original post and other cross-posts of it, in other communities
+  pub cross_posts: Vec<PostView>,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -52,6 +89,12 @@ pub struct GetPosts {
   pub community_id: Option<CommunityId>,
   pub community_name: Option<String>,
   pub saved_only: Option<bool>,
+  /// Hide the posts which were marked as read with `MarkPostAsRead`
+  pub unread_only: Option<bool>,
+  /// Only list posts which have this tag
+  pub tag_id: Option<i32>,
+  /// Lists the posts of all communities in this multi-community of the user
+  pub multi_community_id: Option<MultiCommunityId>,
   pub auth: Option<Sensitive<String>>,
 }
 
@@ -67,6 +110,21 @@ pub struct CreatePostLike {
   pub auth: Sensitive<String>,
 }
 
+/// Adds an emoji reaction to a post. A person can react with several different emoji.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ReactPost {
+  pub post_id: PostId,
+  pub emoji: String,
+  pub auth: Sensitive<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UnreactPost {
+  pub post_id: PostId,
+  pub emoji: String,
+  pub auth: Sensitive<String>,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct EditPost {
   pub post_id: PostId,
@@ -74,6 +132,9 @@ pub struct EditPost {
   pub url: Option<Url>,
   pub body: Option<String>,
   pub nsfw: Option<bool>,
+  /// Replaces all tags of the post, if given
+  pub tag_ids: Option<Vec<i32>>,
+  pub language_id: Option<LanguageId>,
   pub auth: Sensitive<String>,
 }
 
@@ -89,6 +150,12 @@ pub struct RemovePost {
   pub post_id: PostId,
   pub removed: bool,
   pub reason: Option<String>,
+  /// Translations of the reason, as map of language code to text
+  pub reason_translations: Option<HashMap<String, String>>,
+  /// Admins can set this to remove without reason in emergencies, if a reason is required
+  pub reason_override: Option<bool>,
+  /// Set to false to leave the action out of the federated modlog
+  pub public_modlog: Option<bool>,
   pub auth: Sensitive<String>,
 }
 
@@ -106,6 +173,47 @@ pub struct LockPost {
   pub auth: Sensitive<String>,
 }
 
+/// Hides a post from everyone except its creator, the mods of the community and admins, without
+/// removing it. This is not federated.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ShadowRemovePost {
+  pub post_id: PostId,
+  pub shadow_removed: bool,
+  pub reason: Option<String>,
+  /// Admins can set this to remove without reason in emergencies, if a reason is required
+  pub reason_override: Option<bool>,
+  pub auth: Sensitive<String>,
+}
+
+/// Lists the posts of the community which are waiting for approval by the mods, newest first
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ListPendingPosts {
+  pub community_id: CommunityId,
+  pub page: Option<i64>,
+  pub limit: Option<i64>,
+  pub auth: Sensitive<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ListPendingPostsResponse {
+  pub posts: Vec<PostView>,
+}
+
+/// Publishes a pending post, and federates it
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ApprovePost {
+  pub post_id: PostId,
+  pub auth: Sensitive<String>,
+}
+
+/// Removes a pending post, so that it is never published
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RejectPost {
+  pub post_id: PostId,
+  pub reason: Option<String>,
+  pub auth: Sensitive<String>,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct StickyPost {
   pub post_id: PostId,
@@ -113,10 +221,26 @@ pub struct StickyPost {
   pub auth: Sensitive<String>,
 }
 
+/// For a single choice poll, exactly one option has to be given.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct VotePoll {
+  pub post_id: PostId,
+  pub option_ids: Vec<i32>,
+  pub auth: Sensitive<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PollResponse {
+  pub poll_view: PollView,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct SavePost {
   pub post_id: PostId,
   pub save: bool,
+  /// Files the item into one of the user's saved collections. Saving again without a collection
+  /// keeps the current one.
+  pub collection_id: Option<SavedCollectionId>,
   pub auth: Sensitive<String>,
 }
 
@@ -136,6 +260,15 @@ pub struct PostReportResponse {
 pub struct ResolvePostReport {
   pub report_id: PostReportId,
   pub resolved: bool,
+  /// Explanation of the resolution, which is stored in the report. Ignored when unresolving.
+  pub resolution_note: Option<String>,
+  pub auth: Sensitive<String>,
+}
+
+/// Passes a report on to the instance admins, eg for content which breaks the site rules
+#[derive(Debug, Serialize, Deserialize)]
+pub struct EscalatePostReport {
+  pub report_id: PostReportId,
   pub auth: Sensitive<String>,
 }
 
@@ -145,6 +278,8 @@ pub struct ListPostReports {
   pub limit: Option<i64>,
   /// Only shows the unresolved reports
   pub unresolved_only: Option<bool>,
+  /// Only shows the reports which mods escalated to the admins
+  pub escalated_only: Option<bool>,
   /// if no community is given, it returns reports for all communities moderated by the auth user
   pub community_id: Option<CommunityId>,
   pub auth: Sensitive<String>,
@@ -164,3 +299,78 @@ pub struct GetSiteMetadata {
 pub struct GetSiteMetadataResponse {
   pub metadata: SiteMetadata,
 }
+
+/// Stores an unfinished post. Creates a new draft, or overwrites the existing one if `id` is given.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SaveDraft {
+  pub id: Option<i32>,
+  pub name: Option<String>,
+  pub community_id: Option<CommunityId>,
+  pub url: Option<Url>,
+  pub body: Option<String>,
+  pub nsfw: Option<bool>,
+  pub auth: Sensitive<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DraftResponse {
+  pub draft: PostDraft,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ListDrafts {
+  pub page: Option<i64>,
+  pub limit: Option<i64>,
+  pub auth: Sensitive<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ListDraftsResponse {
+  pub drafts: Vec<PostDraft>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DeleteDraft {
+  pub id: i32,
+  pub auth: Sensitive<String>,
+}
+
+/// Stores a post, which is only published and federated once `publish_at` has come
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SchedulePost {
+  pub name: String,
+  pub community_id: CommunityId,
+  pub url: Option<Url>,
+  pub body: Option<String>,
+  pub honeypot: Option<String>,
+  pub nsfw: Option<bool>,
+  /// Has to be one of the community languages, if it is limited to some
+  pub language_id: Option<LanguageId>,
+  /// Unix timestamp, which has to be in the future
+  pub publish_at: i64,
+  pub auth: Sensitive<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ScheduledPostResponse {
+  pub scheduled_post: ScheduledPost,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ListScheduledPosts {
+  pub page: Option<i64>,
+  pub limit: Option<i64>,
+  pub auth: Sensitive<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ListScheduledPostsResponse {
+  pub scheduled_posts: Vec<ScheduledPost>,
+}
+
+/// Cancels a scheduled post before it is published
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DeleteScheduledPost {
+  pub id: i32,
+  pub auth: Sensitive<String>,
+}
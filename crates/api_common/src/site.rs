@@ -1,4 +1,17 @@
-use lemmy_db_schema::newtypes::{CommunityId, PersonId};
+use lemmy_db_schema::{
+  newtypes::{CommentId, CommunityId, LanguageId, PersonId, PostId},
+  source::{
+    custom_emoji::CustomEmoji,
+    federation_instance::FederationInstance,
+    instance::Instance,
+    language::Language,
+    legal_hold::LegalHold,
+    person::PersonSafe,
+    person_instance_block::PersonInstanceBlock,
+    received_deletion::ReceivedDeletion,
+    site_word_filter::SiteWordFilter,
+  },
+};
 use lemmy_db_views::{
   comment_view::CommentView,
   local_user_view::LocalUserSettingsView,
@@ -15,19 +28,27 @@ use lemmy_db_views_actor::{
   person_view::PersonViewSafe,
 };
 use lemmy_db_views_moderator::{
+  admin_purge_comment_view::AdminPurgeCommentView,
+  admin_purge_community_view::AdminPurgeCommunityView,
+  admin_purge_person_view::AdminPurgePersonView,
+  admin_purge_post_view::AdminPurgePostView,
   mod_add_community_view::ModAddCommunityView,
   mod_add_view::ModAddView,
+  mod_automod_action_view::ModAutomodActionView,
   mod_ban_from_community_view::ModBanFromCommunityView,
   mod_ban_view::ModBanView,
   mod_lock_post_view::ModLockPostView,
   mod_remove_comment_view::ModRemoveCommentView,
   mod_remove_community_view::ModRemoveCommunityView,
   mod_remove_post_view::ModRemovePostView,
+  mod_resolve_appeal_view::ModResolveAppealView,
+  mod_shadow_remove_post_view::ModShadowRemovePostView,
   mod_sticky_post_view::ModStickyPostView,
   mod_transfer_community_view::ModTransferCommunityView,
 };
 use lemmy_utils::Sensitive;
 use serde::{Deserialize, Serialize};
+use url::Url;
 
 #[derive(Serialize, Deserialize, Debug)]
 pub struct Search {
@@ -35,6 +56,14 @@ pub struct Search {
   pub community_id: Option<CommunityId>,
   pub community_name: Option<String>,
   pub creator_id: Option<PersonId>,
+  /// Only communities of this category, and their posts and comments
+  pub category_id: Option<i32>,
+  /// Only posts and comments published after this unix time
+  pub published_after: Option<i64>,
+  /// Only posts and comments published before this unix time
+  pub published_before: Option<i64>,
+  /// Only posts linking to this domain or its subdomains, like `example.com`
+  pub domain: Option<String>,
   pub type_: Option<String>,
   pub sort: Option<String>,
   pub listing_type: Option<String>,
@@ -52,6 +81,22 @@ pub struct SearchResponse {
   pub users: Vec<PersonViewSafe>,
 }
 
+/// Lightweight user search for completing `@mentions` while typing, only returns people who posted
+/// or commented in the community.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct MentionAutocomplete {
+  /// Prefix of the name or display name
+  pub q: String,
+  pub community_id: CommunityId,
+  pub limit: Option<i64>,
+  pub auth: Option<Sensitive<String>>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct MentionAutocompleteResponse {
+  pub users: Vec<PersonSafe>,
+}
+
 #[derive(Serialize, Deserialize, Debug)]
 pub struct ResolveObject {
   pub q: String,
@@ -79,6 +124,7 @@ pub struct GetModlog {
 pub struct GetModlogResponse {
   pub removed_posts: Vec<ModRemovePostView>,
   pub locked_posts: Vec<ModLockPostView>,
+  pub shadow_removed_posts: Vec<ModShadowRemovePostView>,
   pub stickied_posts: Vec<ModStickyPostView>,
   pub removed_comments: Vec<ModRemoveCommentView>,
   pub removed_communities: Vec<ModRemoveCommunityView>,
@@ -87,6 +133,12 @@ pub struct GetModlogResponse {
   pub added_to_community: Vec<ModAddCommunityView>,
   pub transferred_to_community: Vec<ModTransferCommunityView>,
   pub added: Vec<ModAddView>,
+  pub resolved_appeals: Vec<ModResolveAppealView>,
+  pub automod_actions: Vec<ModAutomodActionView>,
+  pub admin_purged_persons: Vec<AdminPurgePersonView>,
+  pub admin_purged_communities: Vec<AdminPurgeCommunityView>,
+  pub admin_purged_posts: Vec<AdminPurgePostView>,
+  pub admin_purged_comments: Vec<AdminPurgeCommentView>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -122,6 +174,10 @@ pub struct EditSite {
   pub require_application: Option<bool>,
   pub application_question: Option<String>,
   pub private_instance: Option<bool>,
+  /// One of builtin, hcaptcha or recaptcha
+  pub captcha_provider: Option<String>,
+  /// Public key of the hCaptcha or reCAPTCHA widget
+  pub captcha_site_key: Option<String>,
   pub auth: Sensitive<String>,
 }
 
@@ -144,6 +200,9 @@ pub struct GetSiteResponse {
   pub version: String,
   pub my_user: Option<MyUserInfo>,
   pub federated_instances: Option<FederatedInstances>, // Federation may be disabled
+  /// Key for subscribing to Web Push notifications, only set if they are enabled
+  pub vapid_public_key: Option<String>,
+  pub all_languages: Vec<Language>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -153,6 +212,8 @@ pub struct MyUserInfo {
   pub moderates: Vec<CommunityModeratorView>,
   pub community_blocks: Vec<CommunityBlockView>,
   pub person_blocks: Vec<PersonBlockView>,
+  pub instance_blocks: Vec<PersonInstanceBlock>,
+  pub discussion_languages: Vec<LanguageId>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -184,6 +245,31 @@ pub struct FederatedInstances {
   pub blocked: Option<Vec<String>>,
 }
 
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GetFederatedInstances {
+  pub auth: Option<Sensitive<String>>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GetFederatedInstancesResponse {
+  pub federated_instances: Option<FederatedInstancesWithSoftware>, // Federation may be disabled
+}
+
+/// Same lists as in [`FederatedInstances`], with the nodeinfo of each instance
+#[derive(Debug, Serialize, Deserialize)]
+pub struct FederatedInstancesWithSoftware {
+  pub linked: Vec<FederatedInstance>,
+  pub allowed: Option<Vec<FederatedInstance>>,
+  pub blocked: Option<Vec<FederatedInstance>>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct FederatedInstance {
+  pub domain: String,
+  /// Not set if the nodeinfo of the instance wasn't fetched yet
+  pub instance: Option<Instance>,
+}
+
 #[derive(Serialize, Deserialize)]
 pub struct ListRegistrationApplications {
   /// Only shows the unread applications (IE those without an admin actor)
@@ -198,6 +284,155 @@ pub struct ListRegistrationApplicationsResponse {
   pub registration_applications: Vec<RegistrationApplicationView>,
 }
 
+#[derive(Serialize, Deserialize)]
+pub struct ListReceivedDeletions {
+  /// The remote person or community which sent the deletions
+  pub actor_id: Url,
+  pub page: Option<i64>,
+  pub limit: Option<i64>,
+  pub auth: String,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct ListReceivedDeletionsResponse {
+  pub received_deletions: Vec<ReceivedDeletion>,
+}
+
+/// Unlike the blocklist in the config file, this takes effect without a restart.
+#[derive(Serialize, Deserialize)]
+pub struct AddBlockedInstance {
+  pub domain: String,
+  pub auth: String,
+}
+
+/// Only instances which were blocked through the api can be removed.
+#[derive(Serialize, Deserialize)]
+pub struct RemoveBlockedInstance {
+  pub domain: String,
+  pub auth: String,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct AddAllowedInstance {
+  pub domain: String,
+  pub auth: String,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct RemoveAllowedInstance {
+  pub domain: String,
+  pub auth: String,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct ListFederationSettings {
+  pub auth: String,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct FederationSettingsResponse {
+  pub enabled: bool,
+  pub strict_allowlist: bool,
+  /// Combined from the config file and the instances added through the api
+  pub allowed_instances: Option<Vec<String>>,
+  pub blocked_instances: Option<Vec<String>>,
+  /// Only the instances added through the api
+  pub instances: Vec<FederationInstance>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct ListWordFilters {
+  pub auth: String,
+}
+
+/// Adds a regex pattern to the word filter, or changes the action of an existing one. Unlike the
+/// slur filter in the config file, this takes effect without a restart.
+#[derive(Serialize, Deserialize)]
+pub struct AddWordFilter {
+  pub pattern: String,
+  /// Either `Block` to reject matching content, or `Replace` to treat matches like slurs
+  pub action: String,
+  pub auth: String,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct RemoveWordFilter {
+  pub id: i32,
+  pub auth: String,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct WordFiltersResponse {
+  pub word_filters: Vec<SiteWordFilter>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct ListLegalHolds {
+  pub auth: String,
+}
+
+/// Exempts a post or comment from the automatic purge of deleted and removed content. Exactly one
+/// of `post_id` and `comment_id` has to be given.
+#[derive(Serialize, Deserialize)]
+pub struct AddLegalHold {
+  pub post_id: Option<PostId>,
+  pub comment_id: Option<CommentId>,
+  pub reason: String,
+  pub auth: String,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct RemoveLegalHold {
+  pub id: i32,
+  pub auth: String,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct LegalHoldsResponse {
+  pub legal_holds: Vec<LegalHold>,
+}
+
+/// Custom emoji are added by the admins, and can then be used in posts and comments as
+/// `:shortcode:`
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CreateCustomEmoji {
+  /// Without the surrounding colons
+  pub shortcode: String,
+  pub image_url: Url,
+  pub category: String,
+  pub auth: Sensitive<String>,
+}
+
+/// The shortcode can't be changed, as it is already used in existing content
+#[derive(Debug, Serialize, Deserialize)]
+pub struct EditCustomEmoji {
+  pub id: i32,
+  pub image_url: Url,
+  pub category: String,
+  pub auth: Sensitive<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DeleteCustomEmoji {
+  pub id: i32,
+  pub auth: Sensitive<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CustomEmojiResponse {
+  pub custom_emoji: CustomEmoji,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ListCustomEmojis {
+  pub auth: Option<Sensitive<String>>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ListCustomEmojisResponse {
+  pub custom_emojis: Vec<CustomEmoji>,
+}
+
 #[derive(Serialize, Deserialize)]
 pub struct ApproveRegistrationApplication {
   pub id: i32,
@@ -220,3 +455,41 @@ pub struct GetUnreadRegistrationApplicationCount {
 pub struct GetUnreadRegistrationApplicationCountResponse {
   pub registration_applications: i64,
 }
+
+/// Permanently deletes a person with all their content. Unlike a ban or account deletion, this
+/// can't be undone.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PurgePerson {
+  pub person_id: PersonId,
+  pub reason: Option<String>,
+  pub auth: Sensitive<String>,
+}
+
+/// Permanently deletes a community with all its posts and comments
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PurgeCommunity {
+  pub community_id: CommunityId,
+  pub reason: Option<String>,
+  pub auth: Sensitive<String>,
+}
+
+/// Permanently deletes a post with all its comments
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PurgePost {
+  pub post_id: PostId,
+  pub reason: Option<String>,
+  pub auth: Sensitive<String>,
+}
+
+/// Permanently deletes a comment with all its replies
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PurgeComment {
+  pub comment_id: CommentId,
+  pub reason: Option<String>,
+  pub auth: Sensitive<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PurgeItemResponse {
+  pub success: bool,
+}
@@ -0,0 +1,120 @@
+use lemmy_db_schema::TokenScope;
+use lemmy_utils::LemmyError;
+use std::future::Future;
+
+/// Personal access tokens start with this, to tell them apart from login sessions
+pub const PERSONAL_ACCESS_TOKEN_PREFIX: &str = "lemmy_pat_";
+
+/// What an endpoint needs from a personal access token, in addition to the read or write scope
+/// of the http method
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum EndpointAccess {
+  Regular,
+  /// Moderator and admin endpoints, which also need the moderate scope
+  Moderate,
+  /// Endpoints which manage how the account is accessed. A leaked token must not be able to take
+  /// over the account or create broader tokens, so these need a login session.
+  LoginSession,
+}
+
+/// What a personal access token needs for the current request
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct RequiredScope {
+  scope: TokenScope,
+  access: EndpointAccess,
+}
+
+tokio::task_local! {
+  static REQUIRED_SCOPE: RequiredScope;
+}
+
+/// Runs an API request for an endpoint with the given access. If the request is authenticated with
+/// a personal access token, the token needs the given scope, and the moderate scope for moderator
+/// and admin endpoints. Http requests use the read scope for get and the write scope for other
+/// methods, websocket messages always use the write scope.
+pub async fn with_required_scope<F: Future>(
+  access: EndpointAccess,
+  scope: TokenScope,
+  fut: F,
+) -> F::Output {
+  REQUIRED_SCOPE
+    .scope(RequiredScope { scope, access }, fut)
+    .await
+}
+
+pub fn is_personal_access_token(auth: &str) -> bool {
+  auth.starts_with(PERSONAL_ACCESS_TOKEN_PREFIX)
+}
+
+/// Makes sure that a personal access token allows the current request
+pub(crate) fn check_token_scopes(granted: Vec<TokenScope>) -> Result<(), LemmyError> {
+  let required = REQUIRED_SCOPE.try_with(|r| *r).unwrap_or(RequiredScope {
+    scope: TokenScope::Write,
+    access: EndpointAccess::Regular,
+  });
+  if required.access == EndpointAccess::LoginSession {
+    return Err(LemmyError::from_message("needs_login_session"));
+  }
+  if !granted.contains(&required.scope)
+    || (required.access == EndpointAccess::Moderate && !granted.contains(&TokenScope::Moderate))
+  {
+    return Err(LemmyError::from_message("insufficient_token_scope"));
+  }
+  Ok(())
+}
+
+/// For changes within otherwise allowed endpoints, which could lead to an account takeover
+pub fn check_login_session(auth: &str) -> Result<(), LemmyError> {
+  if is_personal_access_token(auth) {
+    return Err(LemmyError::from_message("needs_login_session"));
+  }
+  Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::token_scope::*;
+
+  #[actix_rt::test]
+  async fn test_token_scopes() {
+    use EndpointAccess::*;
+    let read = with_required_scope(Regular, TokenScope::Read, async {
+      check_token_scopes(vec![TokenScope::Read])
+    })
+    .await;
+    assert!(read.is_ok());
+
+    let write = with_required_scope(Regular, TokenScope::Write, async {
+      check_token_scopes(vec![TokenScope::Read])
+    })
+    .await;
+    assert!(write.is_err());
+
+    // Moderation needs both scopes, also for endpoints which don't use the moderation helpers
+    let moderate_without_write = with_required_scope(Moderate, TokenScope::Write, async {
+      check_token_scopes(vec![TokenScope::Read, TokenScope::Moderate])
+    })
+    .await;
+    assert!(moderate_without_write.is_err());
+    let write_without_moderate = with_required_scope(Moderate, TokenScope::Write, async {
+      check_token_scopes(vec![TokenScope::Write])
+    })
+    .await;
+    assert!(write_without_moderate.is_err());
+    let moderate = with_required_scope(Moderate, TokenScope::Write, async {
+      check_token_scopes(vec![TokenScope::Write, TokenScope::Moderate])
+    })
+    .await;
+    assert!(moderate.is_ok());
+
+    // Account security needs a login session, whatever the scopes
+    let all_scopes = vec![TokenScope::Read, TokenScope::Write, TokenScope::Moderate];
+    let login_session = with_required_scope(LoginSession, TokenScope::Write, async {
+      check_token_scopes(all_scopes)
+    })
+    .await;
+    assert!(login_session.is_err());
+    assert!(check_login_session("lemmy_pat_abc").is_err());
+    assert!(check_login_session("eyJhbGciOiJIUzI1NiJ9").is_ok());
+  }
+}
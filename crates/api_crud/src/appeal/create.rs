@@ -0,0 +1,112 @@
+use crate::PerformCrud;
+use actix_web::web::Data;
+use lemmy_api_common::{
+  blocking,
+  community::{AppealResponse, CreateAppeal},
+  get_local_user_view_from_jwt,
+  is_mod_or_admin,
+};
+use lemmy_db_schema::{
+  source::{
+    appeal::{Appeal, AppealForm},
+    community::Community,
+    post::Post,
+  },
+  traits::Crud,
+};
+use lemmy_db_views::comment_view::CommentView;
+use lemmy_utils::{ConnectionId, LemmyError};
+use lemmy_websocket::{messages::SendModRoomMessage, LemmyContext, UserOperationCrud};
+
+/// Creates an appeal against a removal and notifies the moderators of the community
+#[async_trait::async_trait(?Send)]
+impl PerformCrud for CreateAppeal {
+  type Response = AppealResponse;
+
+  #[tracing::instrument(skip(context, websocket_id))]
+  async fn perform(
+    &self,
+    context: &Data<LemmyContext>,
+    websocket_id: Option<ConnectionId>,
+  ) -> Result<AppealResponse, LemmyError> {
+    let data: &CreateAppeal = self;
+    let local_user_view =
+      get_local_user_view_from_jwt(&data.auth, context.pool(), context.secret()).await?;
+
+    // check size of appeal and check for whitespace
+    let reason = data.reason.trim();
+    if reason.is_empty() {
+      return Err(LemmyError::from_message("appeal_reason_required"));
+    }
+    if reason.chars().count() > 1000 {
+      return Err(LemmyError::from_message("appeal_too_long"));
+    }
+
+    let person_id = local_user_view.person.id;
+    let (creator_id, removed, community_id) =
+      match (data.post_id, data.comment_id, data.community_id) {
+        (Some(post_id), None, None) => {
+          let post = blocking(context.pool(), move |conn| Post::read(conn, post_id)).await??;
+          (Some(post.creator_id), post.removed, post.community_id)
+        }
+        (None, Some(comment_id), None) => {
+          let comment_view = blocking(context.pool(), move |conn| {
+            CommentView::read(conn, comment_id, None)
+          })
+          .await??;
+          (
+            Some(comment_view.comment.creator_id),
+            comment_view.comment.removed,
+            comment_view.community.id,
+          )
+        }
+        (None, None, Some(community_id)) => {
+          let community = blocking(context.pool(), move |conn| {
+            Community::read(conn, community_id)
+          })
+          .await??;
+          (None, community.removed, community.id)
+        }
+        _ => return Err(LemmyError::from_message("appeal_needs_single_object")),
+      };
+
+    // Communities can be appealed by their mods, everything else only by its creator
+    match creator_id {
+      Some(creator_id) => {
+        if creator_id != person_id {
+          return Err(LemmyError::from_message("appeal_not_allowed"));
+        }
+      }
+      None => is_mod_or_admin(context.pool(), person_id, community_id).await?,
+    }
+    if !removed {
+      return Err(LemmyError::from_message("appeal_object_not_removed"));
+    }
+
+    let appeal_form = AppealForm {
+      creator_id: person_id,
+      community_id,
+      post_id: data.post_id,
+      comment_id: data.comment_id,
+      reason: reason.to_owned(),
+    };
+    // Fails if the user already has an open appeal for the same object
+    let appeal = blocking(context.pool(), move |conn| {
+      Appeal::create(conn, &appeal_form)
+    })
+    .await?
+    .map_err(LemmyError::from)
+    .map_err(|e| e.with_message("appeal_already_exists"))?;
+
+    let res = AppealResponse { appeal };
+
+    context.chat_server().do_send(SendModRoomMessage {
+      op: UserOperationCrud::CreateAppeal,
+      response: res.clone(),
+      community_id,
+      websocket_id,
+    });
+
+    Ok(res)
+  }
+}
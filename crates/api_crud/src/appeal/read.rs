@@ -0,0 +1,45 @@
+use crate::PerformCrud;
+use actix_web::web::Data;
+use lemmy_api_common::{
+  blocking,
+  community::{ListAppeals, ListAppealsResponse},
+  get_local_user_view_from_jwt,
+  is_admin,
+  is_mod_or_admin,
+};
+use lemmy_db_schema::source::appeal::Appeal;
+use lemmy_utils::{ConnectionId, LemmyError};
+use lemmy_websocket::LemmyContext;
+
+#[async_trait::async_trait(?Send)]
+impl PerformCrud for ListAppeals {
+  type Response = ListAppealsResponse;
+
+  #[tracing::instrument(skip(context, _websocket_id))]
+  async fn perform(
+    &self,
+    context: &Data<LemmyContext>,
+    _websocket_id: Option<ConnectionId>,
+  ) -> Result<ListAppealsResponse, LemmyError> {
+    let data: &ListAppeals = self;
+    let local_user_view =
+      get_local_user_view_from_jwt(&data.auth, context.pool(), context.secret()).await?;
+
+    let community_id = data.community_id;
+    if let Some(community_id) = community_id {
+      is_mod_or_admin(context.pool(), local_user_view.person.id, community_id).await?;
+    } else {
+      is_admin(&local_user_view)?;
+    }
+
+    let unresolved_only = data.unresolved_only.unwrap_or(false);
+    let page = data.page;
+    let limit = data.limit;
+    let appeals = blocking(context.pool(), move |conn| {
+      Appeal::list(conn, community_id, unresolved_only, page, limit)
+    })
+    .await??;
+
+    Ok(ListAppealsResponse { appeals })
+  }
+}
@@ -0,0 +1,250 @@
+use crate::PerformCrud;
+use actix_web::web::Data;
+use lemmy_api_common::{
+  blocking,
+  comment::RemoveComment,
+  community::{AppealResponse, RemoveCommunity, ResolveAppeal},
+  get_local_user_view_from_jwt,
+  is_admin,
+  is_mod_or_admin,
+  post::RemovePost,
+};
+use lemmy_db_schema::{
+  source::{
+    appeal::{Appeal, AppealStatus},
+    moderator::{ModResolveAppeal, ModResolveAppealForm},
+  },
+  traits::Crud,
+};
+use lemmy_utils::{ConnectionId, LemmyError};
+use lemmy_websocket::{messages::SendModRoomMessage, LemmyContext, UserOperationCrud};
+
+/// Resolves an appeal and notifies the moderators of the community. Granting the appeal restores
+/// the removed object, in the same way as if the mod had restored it directly.
+#[async_trait::async_trait(?Send)]
+impl PerformCrud for ResolveAppeal {
+  type Response = AppealResponse;
+
+  #[tracing::instrument(skip(context, websocket_id))]
+  async fn perform(
+    &self,
+    context: &Data<LemmyContext>,
+    websocket_id: Option<ConnectionId>,
+  ) -> Result<AppealResponse, LemmyError> {
+    let data: &ResolveAppeal = self;
+    let local_user_view =
+      get_local_user_view_from_jwt(&data.auth, context.pool(), context.secret()).await?;
+
+    let appeal_id = data.id;
+    let appeal = blocking(context.pool(), move |conn| Appeal::read(conn, appeal_id))
+      .await?
+      .map_err(LemmyError::from)
+      .map_err(|e| e.with_message("couldnt_find_appeal"))?;
+
+    let person_id = local_user_view.person.id;
+    // Only admins can restore a removed community, so only they can resolve its appeals
+    if appeal.post_id.is_none() && appeal.comment_id.is_none() {
+      is_admin(&local_user_view)?;
+    } else {
+      is_mod_or_admin(context.pool(), person_id, appeal.community_id).await?;
+    }
+    if appeal.status != AppealStatus::Pending {
+      return Err(LemmyError::from_message("appeal_already_resolved"));
+    }
+
+    let status = if data.grant {
+      let reason = data.reason.clone();
+      let auth = data.auth.clone();
+      // Only the appealed comment is restored, not any replies which were removed with it
+      match (appeal.post_id, appeal.comment_id) {
+        (Some(post_id), _) => {
+          let remove = RemovePost {
+            post_id,
+            removed: false,
+            reason,
+            reason_translations: None,
+            reason_override: None,
+            public_modlog: None,
+            auth,
+          };
+          remove.perform(context, websocket_id).await?;
+        }
+        (None, Some(comment_id)) => {
+          let remove = RemoveComment {
+            comment_id,
+            removed: false,
+            reason,
+            reason_translations: None,
+            reason_override: None,
+            remove_children: None,
+            public_modlog: None,
+            auth,
+          };
+          remove.perform(context, websocket_id).await?;
+        }
+        (None, None) => {
+          let remove = RemoveCommunity {
+            community_id: appeal.community_id,
+            removed: false,
+            reason,
+            reason_translations: None,
+            reason_override: None,
+            expires: None,
+            public_modlog: None,
+            auth,
+          };
+          remove.perform(context, websocket_id).await?;
+        }
+      }
+      AppealStatus::Granted
+    } else {
+      AppealStatus::Upheld
+    };
+
+    let appeal = blocking(context.pool(), move |conn| {
+      Appeal::resolve(conn, appeal_id, person_id, status)
+    })
+    .await?
+    .map_err(LemmyError::from)
+    .map_err(|e| e.with_message("appeal_already_resolved"))?;
+
+    // Mod tables
+    let form = ModResolveAppealForm {
+      mod_person_id: person_id,
+      appeal_id,
+      granted: data.grant,
+      reason: data.reason.to_owned(),
+    };
+    blocking(context.pool(), move |conn| {
+      ModResolveAppeal::create(conn, &form)
+    })
+    .await??;
+
+    let res = AppealResponse { appeal };
+
+    context.chat_server().do_send(SendModRoomMessage {
+      op: UserOperationCrud::ResolveAppeal,
+      response: res.clone(),
+      community_id: res.appeal.community_id,
+      websocket_id,
+    });
+
+    Ok(res)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::tests::init_context;
+  use lemmy_api_common::create_login_session;
+  use lemmy_db_schema::{
+    source::{
+      appeal::AppealForm,
+      community::{Community, CommunityForm, CommunityModerator, CommunityModeratorForm},
+      local_user::{LocalUser, LocalUserForm},
+      person::{Person, PersonForm},
+      post::{Post, PostForm},
+    },
+    traits::Joinable,
+  };
+  use serial_test::serial;
+
+  #[actix_rt::test]
+  #[serial]
+  async fn test_community_appeal_needs_admin() {
+    let context = Data::new(init_context());
+    let conn = context.pool().get().unwrap();
+
+    let mut persons = vec![];
+    let mut tokens = vec![];
+    for (name, admin) in [("appeal_mod", false), ("appeal_admin", true)] {
+      let person_form = PersonForm {
+        name: name.into(),
+        admin: Some(admin),
+        ..PersonForm::default()
+      };
+      let person = Person::create(&conn, &person_form).unwrap();
+      let local_user_form = LocalUserForm {
+        person_id: Some(person.id),
+        password_encrypted: Some("appeal_pass".to_string()),
+        ..LocalUserForm::default()
+      };
+      let local_user = LocalUser::create(&conn, &local_user_form).unwrap();
+      let token = create_login_session(
+        local_user.id,
+        context.pool(),
+        context.secret(),
+        &context.settings(),
+      )
+      .await
+      .unwrap();
+      persons.push(person);
+      tokens.push(token);
+    }
+    let person_form = PersonForm {
+      name: "appeal_creator".into(),
+      ..PersonForm::default()
+    };
+    let creator = Person::create(&conn, &person_form).unwrap();
+    let community_form = CommunityForm {
+      name: "appeal_needs_admin".to_string(),
+      title: "nada".to_owned(),
+      removed: Some(true),
+      ..CommunityForm::default()
+    };
+    let community = Community::create(&conn, &community_form).unwrap();
+    let moderator_form = CommunityModeratorForm {
+      community_id: community.id,
+      person_id: persons[0].id,
+    };
+    CommunityModerator::join(&conn, &moderator_form).unwrap();
+    let post_form = PostForm {
+      name: "appealed post".into(),
+      creator_id: creator.id,
+      community_id: community.id,
+      removed: Some(true),
+      ..PostForm::default()
+    };
+    let post = Post::create(&conn, &post_form).unwrap();
+    let mut appeals = vec![];
+    for post_id in [None, Some(post.id)] {
+      let appeal_form = AppealForm {
+        creator_id: creator.id,
+        community_id: community.id,
+        post_id,
+        comment_id: None,
+        reason: "please".to_string(),
+      };
+      appeals.push(Appeal::create(&conn, &appeal_form).unwrap());
+    }
+    let resolve = |id, auth| ResolveAppeal {
+      id,
+      grant: false,
+      reason: None,
+      auth,
+    };
+
+    let community_by_mod = resolve(appeals[0].id, tokens[0].clone())
+      .perform(&context, None)
+      .await;
+    let post_by_mod = resolve(appeals[1].id, tokens[0].clone())
+      .perform(&context, None)
+      .await;
+    let community_by_admin = resolve(appeals[0].id, tokens[1].clone())
+      .perform(&context, None)
+      .await;
+
+    for person in persons.iter().chain([&creator]) {
+      Person::delete(&conn, person.id).unwrap();
+    }
+    Community::delete(&conn, community.id).unwrap();
+
+    assert_eq!(Some("not_an_admin"), community_by_mod.unwrap_err().message);
+    assert_eq!(AppealStatus::Upheld, post_by_mod.unwrap().appeal.status);
+    assert_eq!(
+      AppealStatus::Upheld,
+      community_by_admin.unwrap().appeal.status
+    );
+  }
+}
@@ -0,0 +1,50 @@
+use crate::{automod_rule::check_automod_rule_form, PerformCrud};
+use actix_web::web::Data;
+use lemmy_api_common::{
+  blocking,
+  community::{AutomodRuleResponse, CreateAutomodRule},
+  get_local_user_view_from_jwt,
+  is_mod_or_admin,
+};
+use lemmy_db_schema::{
+  source::automod_rule::{AutomodRule, AutomodRuleForm},
+  traits::Crud,
+};
+use lemmy_utils::{ConnectionId, LemmyError};
+use lemmy_websocket::LemmyContext;
+
+#[async_trait::async_trait(?Send)]
+impl PerformCrud for CreateAutomodRule {
+  type Response = AutomodRuleResponse;
+
+  #[tracing::instrument(skip(context, _websocket_id))]
+  async fn perform(
+    &self,
+    context: &Data<LemmyContext>,
+    _websocket_id: Option<ConnectionId>,
+  ) -> Result<AutomodRuleResponse, LemmyError> {
+    let data: &CreateAutomodRule = self;
+    let local_user_view =
+      get_local_user_view_from_jwt(&data.auth, context.pool(), context.secret()).await?;
+    is_mod_or_admin(context.pool(), local_user_view.person.id, data.community_id).await?;
+
+    let mut form = AutomodRuleForm {
+      community_id: data.community_id,
+      title_regex: data.title_regex.to_owned(),
+      body_regex: data.body_regex.to_owned(),
+      link_domain: data.link_domain.to_owned(),
+      account_age_below_days: data.account_age_below_days,
+      karma_below: data.karma_below,
+      action: data.action.to_owned(),
+      reason: data.reason.to_owned(),
+      updated: None,
+    };
+    check_automod_rule_form(&mut form, context)?;
+    let automod_rule = blocking(context.pool(), move |conn| AutomodRule::create(conn, &form))
+      .await?
+      .map_err(LemmyError::from)
+      .map_err(|e| e.with_message("couldnt_create_automod_rule"))?;
+
+    Ok(AutomodRuleResponse { automod_rule })
+  }
+}
@@ -0,0 +1,48 @@
+use crate::PerformCrud;
+use actix_web::web::Data;
+use lemmy_api_common::{
+  blocking,
+  community::{AutomodRuleResponse, DeleteAutomodRule},
+  get_local_user_view_from_jwt,
+  is_mod_or_admin,
+};
+use lemmy_db_schema::{source::automod_rule::AutomodRule, traits::Crud};
+use lemmy_utils::{ConnectionId, LemmyError};
+use lemmy_websocket::LemmyContext;
+
+#[async_trait::async_trait(?Send)]
+impl PerformCrud for DeleteAutomodRule {
+  type Response = AutomodRuleResponse;
+
+  #[tracing::instrument(skip(context, _websocket_id))]
+  async fn perform(
+    &self,
+    context: &Data<LemmyContext>,
+    _websocket_id: Option<ConnectionId>,
+  ) -> Result<AutomodRuleResponse, LemmyError> {
+    let data: &DeleteAutomodRule = self;
+    let local_user_view =
+      get_local_user_view_from_jwt(&data.auth, context.pool(), context.secret()).await?;
+
+    let rule_id = data.rule_id;
+    let automod_rule = blocking(context.pool(), move |conn| AutomodRule::read(conn, rule_id))
+      .await?
+      .map_err(LemmyError::from)
+      .map_err(|e| e.with_message("couldnt_find_automod_rule"))?;
+    is_mod_or_admin(
+      context.pool(),
+      local_user_view.person.id,
+      automod_rule.community_id,
+    )
+    .await?;
+
+    blocking(context.pool(), move |conn| {
+      AutomodRule::delete(conn, rule_id)
+    })
+    .await?
+    .map_err(LemmyError::from)
+    .map_err(|e| e.with_message("couldnt_delete_automod_rule"))?;
+
+    Ok(AutomodRuleResponse { automod_rule })
+  }
+}
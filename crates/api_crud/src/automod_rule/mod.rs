@@ -0,0 +1,196 @@
+use lemmy_api_common::{blocking, is_mod_or_admin};
+use lemmy_db_schema::{
+  aggregates::person_aggregates::PersonAggregates,
+  source::{
+    automod_rule::{AutomodContent, AutomodRule, AutomodRuleForm},
+    comment::Comment,
+    comment_report::{CommentReport, CommentReportForm},
+    moderator::{ModAutomodAction, ModAutomodActionForm},
+    person::Person,
+    post::Post,
+    post_report::{PostReport, PostReportForm},
+    site::Site,
+  },
+  traits::{Crud, Reportable},
+  AutomodAction,
+};
+use lemmy_utils::{utils::check_slurs_opt, LemmyError};
+use lemmy_websocket::LemmyContext;
+use std::str::FromStr;
+
+mod create;
+mod delete;
+mod read;
+mod update;
+
+const MAX_AUTOMOD_REASON_LENGTH: usize = 1000;
+
+/// Validates the rule, and unsets empty conditions
+fn check_automod_rule_form(
+  form: &mut AutomodRuleForm,
+  context: &LemmyContext,
+) -> Result<(), LemmyError> {
+  AutomodAction::from_str(&form.action)
+    .map_err(|_| LemmyError::from_message("invalid_automod_action"))?;
+
+  let non_empty = |s: &mut Option<String>| {
+    *s = s
+      .take()
+      .map(|s| s.trim().to_owned())
+      .filter(|s| !s.is_empty());
+  };
+  non_empty(&mut form.title_regex);
+  non_empty(&mut form.body_regex);
+  non_empty(&mut form.link_domain);
+  non_empty(&mut form.reason);
+  form.link_domain = form.link_domain.take().map(|d| d.to_lowercase());
+
+  for pattern in form.title_regex.iter().chain(form.body_regex.iter()) {
+    AutomodRule::build_regex(pattern)
+      .map_err(|_| LemmyError::from_message("invalid_automod_rule_pattern"))?;
+  }
+  if form.account_age_below_days.unwrap_or(1) < 1 {
+    return Err(LemmyError::from_message("invalid_automod_account_age"));
+  }
+  if form.title_regex.is_none()
+    && form.body_regex.is_none()
+    && form.link_domain.is_none()
+    && form.account_age_below_days.is_none()
+    && form.karma_below.is_none()
+  {
+    return Err(LemmyError::from_message("automod_rule_without_condition"));
+  }
+
+  if let Some(reason) = &form.reason {
+    if reason.chars().count() > MAX_AUTOMOD_REASON_LENGTH {
+      return Err(LemmyError::from_message("automod_reason_too_long"));
+    }
+  }
+  check_slurs_opt(&form.reason, &context.settings().slur_regex())?;
+  Ok(())
+}
+
+pub(crate) enum AutomodTarget<'a> {
+  Post(&'a Post),
+  Comment(&'a Comment, &'a Post),
+}
+
+/// Applies all automod rules of the community which match a new or edited post or comment, and
+/// logs them in the modlog. Content of moderators and admins is never checked.
+///
+/// Returns true if the content was removed or is waiting for approval, in which case it shouldn't
+/// be federated.
+pub(crate) async fn apply_automod_rules(
+  target: AutomodTarget<'_>,
+  creator: &Person,
+  context: &LemmyContext,
+) -> Result<bool, LemmyError> {
+  let (post, comment) = match target {
+    AutomodTarget::Post(post) => (post.to_owned(), None),
+    AutomodTarget::Comment(comment, post) => (post.to_owned(), Some(comment.to_owned())),
+  };
+  let community_id = post.community_id;
+  if is_mod_or_admin(context.pool(), creator.id, community_id)
+    .await
+    .is_ok()
+  {
+    return Ok(false);
+  }
+
+  let creator_id = creator.id;
+  let (rules, creator_karma) = blocking(context.pool(), move |conn| -> Result<_, LemmyError> {
+    let rules = AutomodRule::list_for_community(conn, community_id)?;
+    if rules.is_empty() {
+      return Ok((rules, 0));
+    }
+    let aggregates = PersonAggregates::read(conn, creator_id)?;
+    Ok((rules, aggregates.post_score + aggregates.comment_score))
+  })
+  .await??;
+
+  let url = post.url.as_ref().map(|u| u.to_string());
+  let content = match &comment {
+    Some(comment) => AutomodContent {
+      title: None,
+      body: Some(&comment.content),
+      url: None,
+      creator_published: creator.published,
+      creator_karma,
+    },
+    None => AutomodContent {
+      title: Some(&post.name),
+      body: post.body.as_deref(),
+      url: url.as_deref(),
+      creator_published: creator.published,
+      creator_karma,
+    },
+  };
+  let matching: Vec<AutomodRule> = rules.into_iter().filter(|r| r.matches(&content)).collect();
+  if matching.is_empty() {
+    return Ok(false);
+  }
+
+  blocking(context.pool(), move |conn| -> Result<bool, LemmyError> {
+    let mut hidden = false;
+    for rule in matching {
+      let action = AutomodAction::from_str(&rule.action)
+        .map_err(|_| LemmyError::from_message("invalid_automod_action"))?;
+      match (action, &comment) {
+        (AutomodAction::Remove, None) => {
+          Post::update_removed(conn, post.id, true)?;
+          hidden = true;
+        }
+        (AutomodAction::RequireApproval, None) => {
+          Post::update_pending(conn, post.id, true)?;
+          hidden = true;
+        }
+        // There is no approval queue for comments, so they are removed until the mods restore them
+        (AutomodAction::Remove | AutomodAction::RequireApproval, Some(comment)) => {
+          Comment::update_removed(conn, comment.id, true)?;
+          hidden = true;
+        }
+        // Reports need a creator, so they are made in the name of the site creator
+        (AutomodAction::Report, _) => {
+          let reporter_id = Site::read_simple(conn)?.creator_id;
+          let reason = rule
+            .reason
+            .clone()
+            .unwrap_or_else(|| format!("Automod rule {}", rule.id));
+          match &comment {
+            Some(comment) => {
+              let form = CommentReportForm {
+                creator_id: reporter_id,
+                comment_id: comment.id,
+                original_comment_text: comment.content.to_owned(),
+                reason,
+              };
+              CommentReport::report(conn, &form)?;
+            }
+            None => {
+              let form = PostReportForm {
+                creator_id: reporter_id,
+                post_id: post.id,
+                original_post_name: post.name.to_owned(),
+                original_post_url: post.url.to_owned(),
+                original_post_body: post.body.to_owned(),
+                reason,
+              };
+              PostReport::report(conn, &form)?;
+            }
+          }
+        }
+      }
+
+      let form = ModAutomodActionForm {
+        automod_rule_id: Some(rule.id),
+        community_id,
+        post_id: Some(post.id),
+        comment_id: comment.as_ref().map(|c| c.id),
+        action: rule.action,
+      };
+      ModAutomodAction::create(conn, &form)?;
+    }
+    Ok(hidden)
+  })
+  .await?
+}
@@ -0,0 +1,36 @@
+use crate::PerformCrud;
+use actix_web::web::Data;
+use lemmy_api_common::{
+  blocking,
+  community::{ListAutomodRules, ListAutomodRulesResponse},
+  get_local_user_view_from_jwt,
+  is_mod_or_admin,
+};
+use lemmy_db_schema::source::automod_rule::AutomodRule;
+use lemmy_utils::{ConnectionId, LemmyError};
+use lemmy_websocket::LemmyContext;
+
+#[async_trait::async_trait(?Send)]
+impl PerformCrud for ListAutomodRules {
+  type Response = ListAutomodRulesResponse;
+
+  #[tracing::instrument(skip(context, _websocket_id))]
+  async fn perform(
+    &self,
+    context: &Data<LemmyContext>,
+    _websocket_id: Option<ConnectionId>,
+  ) -> Result<ListAutomodRulesResponse, LemmyError> {
+    let data: &ListAutomodRules = self;
+    let local_user_view =
+      get_local_user_view_from_jwt(&data.auth, context.pool(), context.secret()).await?;
+    is_mod_or_admin(context.pool(), local_user_view.person.id, data.community_id).await?;
+
+    let community_id = data.community_id;
+    let automod_rules = blocking(context.pool(), move |conn| {
+      AutomodRule::list_for_community(conn, community_id)
+    })
+    .await??;
+
+    Ok(ListAutomodRulesResponse { automod_rules })
+  }
+}
@@ -0,0 +1,64 @@
+use crate::{automod_rule::check_automod_rule_form, PerformCrud};
+use actix_web::web::Data;
+use lemmy_api_common::{
+  blocking,
+  community::{AutomodRuleResponse, EditAutomodRule},
+  get_local_user_view_from_jwt,
+  is_mod_or_admin,
+};
+use lemmy_db_schema::{
+  naive_now,
+  source::automod_rule::{AutomodRule, AutomodRuleForm},
+  traits::Crud,
+};
+use lemmy_utils::{ConnectionId, LemmyError};
+use lemmy_websocket::LemmyContext;
+
+#[async_trait::async_trait(?Send)]
+impl PerformCrud for EditAutomodRule {
+  type Response = AutomodRuleResponse;
+
+  #[tracing::instrument(skip(context, _websocket_id))]
+  async fn perform(
+    &self,
+    context: &Data<LemmyContext>,
+    _websocket_id: Option<ConnectionId>,
+  ) -> Result<AutomodRuleResponse, LemmyError> {
+    let data: &EditAutomodRule = self;
+    let local_user_view =
+      get_local_user_view_from_jwt(&data.auth, context.pool(), context.secret()).await?;
+
+    let rule_id = data.rule_id;
+    let orig_rule = blocking(context.pool(), move |conn| AutomodRule::read(conn, rule_id))
+      .await?
+      .map_err(LemmyError::from)
+      .map_err(|e| e.with_message("couldnt_find_automod_rule"))?;
+    is_mod_or_admin(
+      context.pool(),
+      local_user_view.person.id,
+      orig_rule.community_id,
+    )
+    .await?;
+
+    let mut form = AutomodRuleForm {
+      community_id: orig_rule.community_id,
+      title_regex: data.title_regex.to_owned(),
+      body_regex: data.body_regex.to_owned(),
+      link_domain: data.link_domain.to_owned(),
+      account_age_below_days: data.account_age_below_days,
+      karma_below: data.karma_below,
+      action: data.action.to_owned(),
+      reason: data.reason.to_owned(),
+      updated: Some(naive_now()),
+    };
+    check_automod_rule_form(&mut form, context)?;
+    let automod_rule = blocking(context.pool(), move |conn| {
+      AutomodRule::update(conn, rule_id, &form)
+    })
+    .await?
+    .map_err(LemmyError::from)
+    .map_err(|e| e.with_message("couldnt_update_automod_rule"))?;
+
+    Ok(AutomodRuleResponse { automod_rule })
+  }
+}
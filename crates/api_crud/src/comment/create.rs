@@ -1,9 +1,14 @@
-use crate::PerformCrud;
+use crate::{
+  automod_rule::{apply_automod_rules, AutomodTarget},
+  PerformCrud,
+};
 use actix_web::web::Data;
 use lemmy_api_common::{
   blocking,
   check_community_ban,
   check_community_deleted_or_removed,
+  check_language_allowed,
+  check_person_interaction_block,
   check_post_deleted_or_removed,
   comment::*,
   get_local_user_view_from_jwt,
@@ -29,7 +34,7 @@ use lemmy_db_schema::{
 };
 use lemmy_db_views::comment_view::CommentView;
 use lemmy_utils::{
-  utils::{remove_slurs, scrape_text_for_mentions},
+  utils::{check_blocked_words, remove_slurs, scrape_text_for_mentions},
   ConnectionId,
   LemmyError,
 };
@@ -53,6 +58,7 @@ impl PerformCrud for CreateComment {
     let local_user_view =
       get_local_user_view_from_jwt(&data.auth, context.pool(), context.secret()).await?;
 
+    check_blocked_words(&data.content)?;
     let content_slurs_removed =
       remove_slurs(&data.content.to_owned(), &context.settings().slur_regex());
 
@@ -64,12 +70,20 @@ impl PerformCrud for CreateComment {
     check_community_ban(local_user_view.person.id, community_id, context.pool()).await?;
     check_community_deleted_or_removed(community_id, context.pool()).await?;
     check_post_deleted_or_removed(&post)?;
+    check_language_allowed(community_id, data.language_id, context.pool()).await?;
 
     // Check if post is locked, no new comments
     if post.locked {
       return Err(LemmyError::from_message("locked"));
     }
 
+    if post.pending {
+      return Err(LemmyError::from_message("post_pending_approval"));
+    }
+
+    let person_id = local_user_view.person.id;
+    check_person_interaction_block(person_id, post.creator_id, context.pool()).await?;
+
     // If there's a parent_id, check to make sure that comment is in that post
     if let Some(parent_id) = data.parent_id {
       // Make sure the parent comment exists
@@ -82,6 +96,8 @@ impl PerformCrud for CreateComment {
       if parent.post_id != post_id {
         return Err(LemmyError::from_message("couldnt_create_comment"));
       }
+
+      check_person_interaction_block(person_id, parent.creator_id, context.pool()).await?;
     }
 
     let comment_form = CommentForm {
@@ -89,6 +105,7 @@ impl PerformCrud for CreateComment {
       parent_id: data.parent_id.to_owned(),
       post_id: data.post_id,
       creator_id: local_user_view.person.id,
+      language_id: data.language_id,
       ..CommentForm::default()
     };
 
@@ -118,19 +135,31 @@ impl PerformCrud for CreateComment {
       .map_err(LemmyError::from)
       .map_err(|e| e.with_message("couldnt_create_comment"))?;
 
-    // Scan the comment for user mentions, add those rows
-    let post_id = post.id;
-    let mentions = scrape_text_for_mentions(&comment_form.content);
-    let recipient_ids = send_local_notifs(
-      mentions,
-      &updated_comment,
+    // Comments hidden by automod are neither federated, nor sent as notifications
+    let hidden_by_automod = apply_automod_rules(
+      AutomodTarget::Comment(&updated_comment, &post),
       &local_user_view.person,
-      &post,
-      true,
       context,
     )
     .await?;
 
+    // Scan the comment for user mentions, add those rows
+    let post_id = post.id;
+    let recipient_ids = if hidden_by_automod {
+      vec![]
+    } else {
+      let mentions = scrape_text_for_mentions(&comment_form.content);
+      send_local_notifs(
+        mentions,
+        &updated_comment,
+        &local_user_view.person,
+        &post,
+        true,
+        context,
+      )
+      .await?
+    };
+
     // You like your own comment by default
     let like_form = CommentLikeForm {
       comment_id: inserted_comment.id,
@@ -145,26 +174,27 @@ impl PerformCrud for CreateComment {
       .map_err(LemmyError::from)
       .map_err(|e| e.with_message("couldnt_like_comment"))?;
 
-    let apub_comment: ApubComment = updated_comment.into();
-    CreateOrUpdateComment::send(
-      apub_comment.clone(),
-      &local_user_view.person.clone().into(),
-      CreateOrUpdateType::Create,
-      context,
-      &mut 0,
-    )
-    .await?;
-    let object = PostOrComment::Comment(Box::new(apub_comment));
-    Vote::send(
-      &object,
-      &local_user_view.person.clone().into(),
-      community_id,
-      VoteType::Like,
-      context,
-    )
-    .await?;
+    if !hidden_by_automod {
+      let apub_comment: ApubComment = updated_comment.into();
+      CreateOrUpdateComment::send(
+        apub_comment.clone(),
+        &local_user_view.person.clone().into(),
+        CreateOrUpdateType::Create,
+        context,
+        &mut 0,
+      )
+      .await?;
+      let object = PostOrComment::Comment(Box::new(apub_comment));
+      Vote::send(
+        &object,
+        &local_user_view.person.clone().into(),
+        community_id,
+        VoteType::Like,
+        context,
+      )
+      .await?;
+    }
 
-    let person_id = local_user_view.person.id;
     let comment_id = inserted_comment.id;
     let comment_view = blocking(context.pool(), move |conn| {
       CommentView::read(conn, comment_id, Some(person_id))
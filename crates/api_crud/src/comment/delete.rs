@@ -3,11 +3,18 @@ use actix_web::web::Data;
 use lemmy_api_common::{
   blocking,
   check_community_ban,
+  check_removal_reason,
   comment::*,
   get_local_user_view_from_jwt,
   is_mod_or_admin,
 };
-use lemmy_apub::activities::deletion::{send_apub_delete, send_apub_remove, DeletableObjects};
+use lemmy_apub::{
+  activities::{
+    deletion::{send_apub_delete, send_apub_remove, DeletableObjects, DeletionAction},
+    federation_target::FederationTarget,
+  },
+  objects::community::ApubCommunity,
+};
 use lemmy_db_schema::{
   source::{
     comment::Comment,
@@ -20,7 +27,8 @@ use lemmy_db_schema::{
 use lemmy_db_views::comment_view::CommentView;
 use lemmy_utils::{ConnectionId, LemmyError};
 use lemmy_websocket::{
-  send::{send_comment_ws_message, send_local_notifs},
+  messages::RemovalInfo,
+  send::{send_comment_removal_ws_message, send_comment_ws_message, send_local_notifs},
   LemmyContext,
   UserOperationCrud,
 };
@@ -73,7 +81,7 @@ impl PerformCrud for DeleteComment {
 
     let post_id = updated_comment.post_id;
     let post = blocking(context.pool(), move |conn| Post::read(conn, post_id)).await??;
-    let recipient_ids = send_local_notifs(
+    let mut recipient_ids = send_local_notifs(
       vec![],
       &updated_comment,
       &local_user_view.person,
@@ -82,6 +90,19 @@ impl PerformCrud for DeleteComment {
       context,
     )
     .await?;
+    let object = DeletableObjects::Comment(Box::new(updated_comment.into()));
+    for recipient_id in object
+      .local_notification_recipients(
+        DeletionAction::Delete,
+        local_user_view.person.id,
+        context.pool(),
+      )
+      .await?
+    {
+      if !recipient_ids.contains(&recipient_id) {
+        recipient_ids.push(recipient_id);
+      }
+    }
 
     // Send the apub message
     let community = blocking(context.pool(), move |conn| {
@@ -91,8 +112,9 @@ impl PerformCrud for DeleteComment {
     send_apub_delete(
       &local_user_view.person.clone().into(),
       &community.clone().into(),
-      DeletableObjects::Comment(Box::new(updated_comment.into())),
+      object,
       deleted,
+      FederationTarget::Community(community.id),
       context,
     )
     .await?;
@@ -144,6 +166,21 @@ impl PerformCrud for RemoveComment {
       orig_comment.community.id,
     )
     .await?;
+    let reason_override = if data.removed {
+      check_removal_reason(
+        &data.reason,
+        context.settings().moderation.require_removal_reason,
+        data.reason_override,
+        local_user_view.person.admin,
+      )?
+    } else {
+      false
+    };
+
+    let remove_children = data.remove_children.unwrap_or(false);
+    if remove_children {
+      check_subtree_removal_limits(&orig_comment.comment, context).await?;
+    }
 
     // Do the remove
     let removed = data.removed;
@@ -154,21 +191,58 @@ impl PerformCrud for RemoveComment {
     .map_err(LemmyError::from)
     .map_err(|e| e.with_message("couldnt_update_comment"))?;
 
-    // Mod tables
-    let form = ModRemoveCommentForm {
-      mod_person_id: local_user_view.person.id,
-      comment_id: data.comment_id,
-      removed: Some(removed),
-      reason: data.reason.to_owned(),
+    let updated_children = if remove_children {
+      let parent = updated_comment.clone();
+      blocking(context.pool(), move |conn| {
+        if removed {
+          Comment::update_removed_for_subtree(conn, &parent, true)
+        } else {
+          // Only restore replies which were removed together with the parent, not those which
+          // were removed separately
+          match ModRemoveComment::read_latest_removal(conn, parent.id)? {
+            Some(parent_removal) => Comment::restore_subtree(conn, &parent, &parent_removal),
+            None => Ok(vec![]),
+          }
+        }
+      })
+      .await?
+      .map_err(LemmyError::from)
+      .map_err(|e| e.with_message("couldnt_update_comment"))?
+    } else {
+      vec![]
     };
+
+    // Mod tables
+    let mod_person_id = local_user_view.person.id;
+    let reason = data.reason.to_owned();
+    let reason_translations = data
+      .reason_translations
+      .clone()
+      .map(serde_json::to_value)
+      .transpose()?;
+    let forms = std::iter::once(comment_id)
+      .chain(updated_children.iter().map(|c| c.id))
+      .map(|comment_id| ModRemoveCommentForm {
+        mod_person_id,
+        comment_id,
+        removed: Some(removed),
+        reason: reason.to_owned(),
+        reason_translations: reason_translations.to_owned(),
+        reason_override,
+        public: data.public_modlog,
+      })
+      .collect::<Vec<_>>();
     blocking(context.pool(), move |conn| {
-      ModRemoveComment::create(conn, &form)
+      forms
+        .iter()
+        .map(|form| ModRemoveComment::create(conn, form))
+        .collect::<Result<Vec<_>, _>>()
     })
     .await??;
 
     let post_id = updated_comment.post_id;
     let post = blocking(context.pool(), move |conn| Post::read(conn, post_id)).await??;
-    let recipient_ids = send_local_notifs(
+    let mut recipient_ids = send_local_notifs(
       vec![],
       &updated_comment,
       &local_user_view.person.clone(),
@@ -177,27 +251,47 @@ impl PerformCrud for RemoveComment {
       context,
     )
     .await?;
+    for recipient_id in DeletableObjects::Comment(Box::new(updated_comment.clone().into()))
+      .local_notification_recipients(
+        DeletionAction::Remove,
+        local_user_view.person.id,
+        context.pool(),
+      )
+      .await?
+    {
+      if !recipient_ids.contains(&recipient_id) {
+        recipient_ids.push(recipient_id);
+      }
+    }
 
     // Send the apub message
     let community = blocking(context.pool(), move |conn| {
       Community::read(conn, orig_comment.post.community_id)
     })
     .await??;
-    send_apub_remove(
-      &local_user_view.person.clone().into(),
-      &community.into(),
-      DeletableObjects::Comment(Box::new(updated_comment.into())),
-      data.reason.clone().unwrap_or_else(|| "".to_string()),
-      removed,
-      context,
-    )
-    .await?;
+    let community: ApubCommunity = community.into();
+    for comment in std::iter::once(updated_comment).chain(updated_children) {
+      send_apub_remove(
+        &local_user_view.person.clone().into(),
+        &community,
+        DeletableObjects::Comment(Box::new(comment.into())),
+        data.reason.clone(),
+        data.reason_translations.clone(),
+        removed,
+        FederationTarget::Community(community.id),
+        context,
+      )
+      .await?;
+    }
 
-    send_comment_ws_message(
+    send_comment_removal_ws_message(
       data.comment_id,
       UserOperationCrud::RemoveComment,
+      RemovalInfo {
+        mod_person_id: local_user_view.person.id,
+        reason: data.reason.to_owned(),
+      },
       websocket_id,
-      None, // TODO maybe this might clear other forms
       Some(local_user_view.person.id),
       recipient_ids,
       context,
@@ -205,3 +299,24 @@ impl PerformCrud for RemoveComment {
     .await
   }
 }
+
+/// Makes sure that removing all replies below the comment stays within the configured limits, so
+/// that a single removal can't lock huge numbers of rows.
+async fn check_subtree_removal_limits(
+  parent: &Comment,
+  context: &LemmyContext,
+) -> Result<(), LemmyError> {
+  let limits = context.settings().moderation;
+  let max_depth = parent.depth() + limits.subtree_removal_max_depth;
+  let max_count = limits.subtree_removal_max_count;
+  let parent = parent.to_owned();
+  // Read one more than allowed, to know if the limit is exceeded
+  let children = blocking(context.pool(), move |conn| {
+    Comment::read_subtree(conn, &parent, max_count as i64 + 1)
+  })
+  .await??;
+  if children.len() > max_count || children.iter().any(|c| c.depth() > max_depth) {
+    return Err(LemmyError::from_message("comment_subtree_too_large"));
+  }
+  Ok(())
+}
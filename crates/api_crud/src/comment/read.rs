@@ -5,6 +5,7 @@ use lemmy_api_common::{
   check_private_instance,
   comment::*,
   get_local_user_view_from_jwt_opt,
+  is_mod_or_admin,
 };
 use lemmy_apub::{
   fetcher::webfinger::webfinger_resolve,
@@ -13,6 +14,7 @@ use lemmy_apub::{
 };
 use lemmy_db_schema::{
   from_opt_str_to_opt_enum,
+  source::{comment_revision::CommentRevision, language::LocalUserLanguage},
   traits::DeleteableOrRemoveable,
   ListingType,
   SortType,
@@ -75,6 +77,8 @@ impl PerformCrud for GetComments {
     let show_bot_accounts = local_user_view
       .as_ref()
       .map(|t| t.local_user.show_bot_accounts);
+    let show_scores = local_user_view.as_ref().map(|t| t.local_user.show_scores);
+    let local_user_id = local_user_view.as_ref().map(|t| t.local_user.id);
     let person_id = local_user_view.map(|u| u.person.id);
 
     let sort: Option<SortType> = from_opt_str_to_opt_enum(&data.sort);
@@ -88,18 +92,27 @@ impl PerformCrud for GetComments {
     } else {
       None
     };
+    let post_id = data.post_id;
     let saved_only = data.saved_only;
+    let max_depth = max_tree_depth(data.max_depth);
     let page = data.page;
     let limit = data.limit;
     let mut comments = blocking(context.pool(), move |conn| {
+      let languages = local_user_id
+        .map(|id| LocalUserLanguage::read(conn, id))
+        .transpose()?;
       CommentQueryBuilder::create(conn)
         .listing_type(listing_type)
         .sort(sort)
         .saved_only(saved_only)
         .community_id(community_id)
         .community_actor_id(community_actor_id)
+        .post_id(post_id)
+        .max_depth(max_depth)
+        .languages(languages)
         .my_person_id(person_id)
         .show_bot_accounts(show_bot_accounts)
+        .show_scores(show_scores)
         .page(page)
         .limit(limit)
         .list()
@@ -108,14 +121,117 @@ impl PerformCrud for GetComments {
     .map_err(LemmyError::from)
     .map_err(|e| e.with_message("couldnt_get_comments"))?;
 
-    // Blank out deleted or removed info
-    for cv in comments
-      .iter_mut()
-      .filter(|cv| cv.comment.deleted || cv.comment.removed)
-    {
-      cv.comment = cv.to_owned().comment.blank_out_deleted_or_removed_info();
-    }
+    blank_out_deleted_or_removed(&mut comments);
+
+    Ok(GetCommentsResponse { comments })
+  }
+}
+
+#[async_trait::async_trait(?Send)]
+impl PerformCrud for GetCommentChildren {
+  type Response = GetCommentsResponse;
+
+  #[tracing::instrument(skip(context, _websocket_id))]
+  async fn perform(
+    &self,
+    context: &Data<LemmyContext>,
+    _websocket_id: Option<ConnectionId>,
+  ) -> Result<GetCommentsResponse, LemmyError> {
+    let data: &GetCommentChildren = self;
+    let local_user_view =
+      get_local_user_view_from_jwt_opt(data.auth.as_ref(), context.pool(), context.secret())
+        .await?;
+
+    check_private_instance(&local_user_view, context.pool()).await?;
+
+    let show_bot_accounts = local_user_view
+      .as_ref()
+      .map(|t| t.local_user.show_bot_accounts);
+    let show_scores = local_user_view.as_ref().map(|t| t.local_user.show_scores);
+    let person_id = local_user_view.map(|u| u.person.id);
+
+    let sort: Option<SortType> = from_opt_str_to_opt_enum(&data.sort);
+    let parent_id = data.parent_id;
+    // Without a depth, only the direct replies are returned
+    let max_depth = max_tree_depth(data.max_depth).unwrap_or(0);
+    let page = data.page;
+    let limit = data.limit;
+    let mut comments = blocking(context.pool(), move |conn| {
+      CommentQueryBuilder::create(conn)
+        .sort(sort)
+        .parent_id(parent_id)
+        .max_depth(max_depth)
+        .my_person_id(person_id)
+        .show_bot_accounts(show_bot_accounts)
+        .show_scores(show_scores)
+        .page(page)
+        .limit(limit)
+        .list()
+    })
+    .await?
+    .map_err(LemmyError::from)
+    .map_err(|e| e.with_message("couldnt_get_comments"))?;
+
+    blank_out_deleted_or_removed(&mut comments);
 
     Ok(GetCommentsResponse { comments })
   }
 }
+
+/// Each level of a comment tree is a separate query, so the depth is limited
+const MAX_COMMENT_TREE_DEPTH: i32 = 10;
+
+fn max_tree_depth(max_depth: Option<i32>) -> Option<i32> {
+  max_depth.map(|d| d.clamp(0, MAX_COMMENT_TREE_DEPTH))
+}
+
+fn blank_out_deleted_or_removed(comments: &mut [CommentView]) {
+  for cv in comments
+    .iter_mut()
+    .filter(|cv| cv.comment.deleted || cv.comment.removed)
+  {
+    cv.comment = cv.to_owned().comment.blank_out_deleted_or_removed_info();
+  }
+}
+
+#[async_trait::async_trait(?Send)]
+impl PerformCrud for GetCommentEditHistory {
+  type Response = GetCommentEditHistoryResponse;
+
+  #[tracing::instrument(skip(context, _websocket_id))]
+  async fn perform(
+    &self,
+    context: &Data<LemmyContext>,
+    _websocket_id: Option<ConnectionId>,
+  ) -> Result<GetCommentEditHistoryResponse, LemmyError> {
+    let data: &GetCommentEditHistory = self;
+    let local_user_view =
+      get_local_user_view_from_jwt_opt(data.auth.as_ref(), context.pool(), context.secret())
+        .await?;
+
+    check_private_instance(&local_user_view, context.pool()).await?;
+
+    let comment_id = data.comment_id;
+    let comment_view = blocking(context.pool(), move |conn| {
+      CommentView::read(conn, comment_id, None)
+    })
+    .await?
+    .map_err(LemmyError::from)
+    .map_err(|e| e.with_message("couldnt_find_comment"))?;
+
+    // The history would otherwise reveal the content of deleted and removed comments
+    if comment_view.comment.deleted || comment_view.comment.removed {
+      let person_id = local_user_view
+        .map(|l| l.person.id)
+        .ok_or_else(|| LemmyError::from_message("not_a_mod_or_admin"))?;
+      is_mod_or_admin(context.pool(), person_id, comment_view.community.id).await?;
+    }
+
+    let revisions = blocking(context.pool(), move |conn| {
+      CommentRevision::list_for_comment(conn, comment_id)
+    })
+    .await??;
+
+    Ok(GetCommentEditHistoryResponse { revisions })
+  }
+}
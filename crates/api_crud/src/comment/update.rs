@@ -4,6 +4,7 @@ use lemmy_api_common::{
   blocking,
   check_community_ban,
   check_community_deleted_or_removed,
+  check_language_allowed,
   check_post_deleted_or_removed,
   comment::*,
   get_local_user_view_from_jwt,
@@ -12,10 +13,10 @@ use lemmy_apub::protocol::activities::{
   create_or_update::comment::CreateOrUpdateComment,
   CreateOrUpdateType,
 };
-use lemmy_db_schema::source::comment::Comment;
+use lemmy_db_schema::source::{comment::Comment, comment_revision::CommentRevision};
 use lemmy_db_views::comment_view::CommentView;
 use lemmy_utils::{
-  utils::{remove_slurs, scrape_text_for_mentions},
+  utils::{check_blocked_words, remove_slurs, scrape_text_for_mentions},
   ConnectionId,
   LemmyError,
 };
@@ -25,7 +26,10 @@ use lemmy_websocket::{
   UserOperationCrud,
 };
 
-use crate::PerformCrud;
+use crate::{
+  automod_rule::{apply_automod_rules, AutomodTarget},
+  PerformCrud,
+};
 
 #[async_trait::async_trait(?Send)]
 impl PerformCrud for EditComment {
@@ -56,6 +60,7 @@ impl PerformCrud for EditComment {
     .await?;
     check_community_deleted_or_removed(orig_comment.community.id, context.pool()).await?;
     check_post_deleted_or_removed(&orig_comment.post)?;
+    check_language_allowed(orig_comment.community.id, data.language_id, context.pool()).await?;
 
     // Verify that only the creator can edit
     if local_user_view.person.id != orig_comment.creator.id {
@@ -63,38 +68,58 @@ impl PerformCrud for EditComment {
     }
 
     // Do the update
+    check_blocked_words(&data.content)?;
     let content_slurs_removed =
       remove_slurs(&data.content.to_owned(), &context.settings().slur_regex());
     let comment_id = data.comment_id;
+    let orig_content = orig_comment.comment.clone();
+    let language_id = data.language_id;
     let updated_comment = blocking(context.pool(), move |conn| {
+      CommentRevision::save_if_changed(conn, &orig_content, &content_slurs_removed)?;
+      if let Some(language_id) = language_id {
+        Comment::update_language(conn, comment_id, language_id)?;
+      }
       Comment::update_content(conn, comment_id, &content_slurs_removed)
     })
     .await?
     .map_err(LemmyError::from)
     .map_err(|e| e.with_message("couldnt_update_comment"))?;
 
-    // Do the mentions / recipients
-    let updated_comment_content = updated_comment.content.to_owned();
-    let mentions = scrape_text_for_mentions(&updated_comment_content);
-    let recipient_ids = send_local_notifs(
-      mentions,
-      &updated_comment,
+    let hidden_by_automod = apply_automod_rules(
+      AutomodTarget::Comment(&updated_comment, &orig_comment.post),
       &local_user_view.person,
-      &orig_comment.post,
-      false,
       context,
     )
     .await?;
 
+    // Do the mentions / recipients
+    let recipient_ids = if hidden_by_automod {
+      vec![]
+    } else {
+      let updated_comment_content = updated_comment.content.to_owned();
+      let mentions = scrape_text_for_mentions(&updated_comment_content);
+      send_local_notifs(
+        mentions,
+        &updated_comment,
+        &local_user_view.person,
+        &orig_comment.post,
+        false,
+        context,
+      )
+      .await?
+    };
+
     // Send the apub update
-    CreateOrUpdateComment::send(
-      updated_comment.into(),
-      &local_user_view.person.into(),
-      CreateOrUpdateType::Update,
-      context,
-      &mut 0,
-    )
-    .await?;
+    if !hidden_by_automod {
+      CreateOrUpdateComment::send(
+        updated_comment.into(),
+        &local_user_view.person.into(),
+        CreateOrUpdateType::Update,
+        context,
+        &mut 0,
+      )
+      .await?;
+    }
 
     send_comment_ws_message(
       data.comment_id,
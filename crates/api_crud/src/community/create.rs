@@ -94,6 +94,7 @@ impl PerformCrud for CreateCommunity {
       icon,
       banner,
       nsfw: data.nsfw,
+      require_post_approval: data.require_post_approval,
       actor_id: Some(community_actor_id.to_owned()),
       private_key: Some(Some(keypair.private_key)),
       public_key: keypair.public_key,
@@ -143,6 +144,9 @@ impl PerformCrud for CreateCommunity {
     })
     .await??;
 
-    Ok(CommunityResponse { community_view })
+    Ok(CommunityResponse {
+      community_view,
+      action_taken: None,
+    })
   }
 }
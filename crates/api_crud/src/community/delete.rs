@@ -1,17 +1,38 @@
 use crate::PerformCrud;
 use actix_web::web::Data;
-use lemmy_api_common::{blocking, community::*, get_local_user_view_from_jwt, is_admin};
-use lemmy_apub::activities::deletion::{send_apub_delete, send_apub_remove, DeletableObjects};
+use lemmy_api_common::{
+  blocking,
+  check_expire_time,
+  check_removal_reason,
+  community::*,
+  get_local_user_view_from_jwt,
+  is_admin,
+  send_ws_message_best_effort,
+};
+use lemmy_apub::activities::{
+  deletion::{send_apub_delete, send_apub_remove, DeletableObjects, DeletionAction},
+  federation_target::FederationTarget,
+};
 use lemmy_db_schema::{
+  clock::SystemClock,
+  newtypes::{CommunityId, PersonId},
   source::{
     community::Community,
     moderator::{ModRemoveCommunity, ModRemoveCommunityForm},
   },
   traits::Crud,
 };
-use lemmy_db_views_actor::community_moderator_view::CommunityModeratorView;
-use lemmy_utils::{utils::naive_from_unix, ConnectionId, LemmyError};
-use lemmy_websocket::{send::send_community_ws_message, LemmyContext, UserOperationCrud};
+use lemmy_db_views_actor::{
+  community_moderator_view::CommunityModeratorView,
+  community_view::CommunityView,
+};
+use lemmy_utils::{ConnectionId, LemmyError};
+use lemmy_websocket::{
+  messages::RemovalInfo,
+  send::{send_community_removal_ws_message, send_community_ws_message},
+  LemmyContext,
+  UserOperationCrud,
+};
 
 #[async_trait::async_trait(?Send)]
 impl PerformCrud for DeleteCommunity {
@@ -34,8 +55,9 @@ impl PerformCrud for DeleteCommunity {
     })
     .await??;
 
-    // Make sure deleter is the top mod
-    if local_user_view.person.id != community_mods[0].moderator.id {
+    // Make sure deleter is the top mod, ie the one with the lowest rank
+    let top_mod_id = community_mods.first().map(|m| m.moderator.id);
+    if top_mod_id != Some(local_user_view.person.id) {
       return Err(LemmyError::from_message("no_community_edit_allowed"));
     }
 
@@ -55,18 +77,31 @@ impl PerformCrud for DeleteCommunity {
       &updated_community.clone().into(),
       DeletableObjects::Community(Box::new(updated_community.into())),
       deleted,
+      FederationTarget::Community(community_id),
       context,
     )
     .await?;
 
-    send_community_ws_message(
-      data.community_id,
-      UserOperationCrud::DeleteCommunity,
-      websocket_id,
-      Some(local_user_view.person.id),
-      context,
+    // The community is already updated and federated at this point
+    let person_id = local_user_view.person.id;
+    let mut res = send_ws_message_best_effort(
+      send_community_ws_message(
+        community_id,
+        UserOperationCrud::DeleteCommunity,
+        websocket_id,
+        Some(person_id),
+        context,
+      ),
+      || read_community_response(community_id, person_id, context),
     )
-    .await
+    .await?;
+    res.action_taken = Some(if deleted {
+      CommunityAction::Deleted
+    } else {
+      CommunityAction::Restored
+    });
+
+    Ok(res)
   }
 }
 
@@ -87,6 +122,18 @@ impl PerformCrud for RemoveCommunity {
     // Verify its an admin (only an admin can remove a community)
     is_admin(&local_user_view)?;
 
+    let expires = check_expire_time(data.expires, &SystemClock)?;
+    let reason_override = if data.removed {
+      check_removal_reason(
+        &data.reason,
+        context.settings().moderation.require_removal_reason,
+        data.reason_override,
+        local_user_view.person.admin,
+      )?
+    } else {
+      false
+    };
+
     // Do the remove
     let community_id = data.community_id;
     let removed = data.removed;
@@ -98,13 +145,19 @@ impl PerformCrud for RemoveCommunity {
     .map_err(|e| e.with_message("couldnt_update_community"))?;
 
     // Mod tables
-    let expires = data.expires.map(naive_from_unix);
     let form = ModRemoveCommunityForm {
       mod_person_id: local_user_view.person.id,
       community_id: data.community_id,
       removed: Some(removed),
       reason: data.reason.to_owned(),
+      reason_translations: data
+        .reason_translations
+        .clone()
+        .map(serde_json::to_value)
+        .transpose()?,
       expires,
+      reason_override,
+      public: data.public_modlog,
     };
     blocking(context.pool(), move |conn| {
       ModRemoveCommunity::create(conn, &form)
@@ -112,23 +165,66 @@ impl PerformCrud for RemoveCommunity {
     .await??;
 
     // Apub messages
+    let object = DeletableObjects::Community(Box::new(updated_community.clone().into()));
+    let recipient_ids = object
+      .local_notification_recipients(
+        DeletionAction::Remove,
+        local_user_view.person.id,
+        context.pool(),
+      )
+      .await?;
     send_apub_remove(
       &local_user_view.person.clone().into(),
-      &updated_community.clone().into(),
-      DeletableObjects::Community(Box::new(updated_community.into())),
-      data.reason.clone().unwrap_or_else(|| "".to_string()),
+      &updated_community.into(),
+      object,
+      data.reason.clone(),
+      data.reason_translations.clone(),
       removed,
+      FederationTarget::Community(community_id),
       context,
     )
     .await?;
 
-    send_community_ws_message(
-      data.community_id,
-      UserOperationCrud::RemoveCommunity,
-      websocket_id,
-      Some(local_user_view.person.id),
-      context,
+    // The community is already updated and federated at this point
+    let person_id = local_user_view.person.id;
+    let mut res = send_ws_message_best_effort(
+      send_community_removal_ws_message(
+        community_id,
+        UserOperationCrud::RemoveCommunity,
+        RemovalInfo {
+          mod_person_id: person_id,
+          reason: data.reason.to_owned(),
+        },
+        websocket_id,
+        Some(person_id),
+        recipient_ids,
+        context,
+      ),
+      || read_community_response(community_id, person_id, context),
     )
-    .await
+    .await?;
+    res.action_taken = Some(if removed {
+      CommunityAction::Removed
+    } else {
+      CommunityAction::Unremoved
+    });
+
+    Ok(res)
   }
 }
+
+/// Response for the deleter, in case the websocket message couldn't be sent
+async fn read_community_response(
+  community_id: CommunityId,
+  person_id: PersonId,
+  context: &LemmyContext,
+) -> Result<CommunityResponse, LemmyError> {
+  let community_view = blocking(context.pool(), move |conn| {
+    CommunityView::read(conn, community_id, Some(person_id))
+  })
+  .await??;
+  Ok(CommunityResponse {
+    community_view,
+    action_taken: None,
+  })
+}
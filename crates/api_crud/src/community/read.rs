@@ -14,6 +14,7 @@ use lemmy_apub::{
 use lemmy_apub_lib::object_id::ObjectId;
 use lemmy_db_schema::{
   from_opt_str_to_opt_enum,
+  source::language::CommunityLanguage,
   traits::DeleteableOrRemoveable,
   ListingType,
   SortType,
@@ -87,10 +88,16 @@ impl PerformCrud for GetCommunity {
       .await
       .unwrap_or(1);
 
+    let discussion_languages = blocking(context.pool(), move |conn| {
+      CommunityLanguage::read(conn, community_id)
+    })
+    .await??;
+
     let res = GetCommunityResponse {
       community_view,
       moderators,
       online,
+      discussion_languages,
     };
 
     // Return the jwt
@@ -126,6 +133,7 @@ impl PerformCrud for ListCommunities {
     let sort: Option<SortType> = from_opt_str_to_opt_enum(&data.sort);
     let listing_type: Option<ListingType> = from_opt_str_to_opt_enum(&data.type_);
 
+    let category_id = data.category_id;
     let page = data.page;
     let limit = data.limit;
     let mut communities = blocking(context.pool(), move |conn| {
@@ -133,6 +141,7 @@ impl PerformCrud for ListCommunities {
         .listing_type(listing_type)
         .sort(sort)
         .show_nsfw(show_nsfw)
+        .category_id(category_id)
         .my_person_id(person_id)
         .page(page)
         .limit(limit)
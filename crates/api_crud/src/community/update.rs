@@ -10,7 +10,10 @@ use lemmy_db_schema::{
   diesel_option_overwrite_to_url,
   naive_now,
   newtypes::PersonId,
-  source::community::{Community, CommunityForm},
+  source::{
+    community::{Community, CommunityForm},
+    language::CommunityLanguage,
+  },
   traits::Crud,
 };
 use lemmy_db_views_actor::community_moderator_view::CommunityModeratorView;
@@ -62,16 +65,23 @@ impl PerformCrud for EditCommunity {
       icon,
       banner,
       nsfw: data.nsfw,
+      require_post_approval: data.require_post_approval,
       updated: Some(naive_now()),
       ..CommunityForm::default()
     };
 
     let community_id = data.community_id;
-    let updated_community = blocking(context.pool(), move |conn| {
-      Community::update(conn, community_id, &community_form)
-    })
+    let discussion_languages = data.discussion_languages.clone();
+    let updated_community = blocking(
+      context.pool(),
+      move |conn| -> Result<Community, LemmyError> {
+        if let Some(languages) = discussion_languages {
+          CommunityLanguage::replace(conn, community_id, &languages)?;
+        }
+        Ok(Community::update(conn, community_id, &community_form)?)
+      },
+    )
     .await?
-    .map_err(LemmyError::from)
     .map_err(|e| e.with_message("couldnt_update_community"))?;
 
     UpdateCommunity::send(
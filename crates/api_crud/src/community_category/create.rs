@@ -0,0 +1,44 @@
+use crate::{community_category::check_community_category_name, PerformCrud};
+use actix_web::web::Data;
+use lemmy_api_common::{
+  blocking,
+  community::{CommunityCategoryResponse, CreateCommunityCategory},
+  get_local_user_view_from_jwt,
+  is_admin,
+};
+use lemmy_db_schema::{
+  source::community_category::{CommunityCategory, CommunityCategoryForm},
+  traits::Crud,
+};
+use lemmy_utils::{ConnectionId, LemmyError};
+use lemmy_websocket::LemmyContext;
+
+#[async_trait::async_trait(?Send)]
+impl PerformCrud for CreateCommunityCategory {
+  type Response = CommunityCategoryResponse;
+
+  #[tracing::instrument(skip(context, _websocket_id))]
+  async fn perform(
+    &self,
+    context: &Data<LemmyContext>,
+    _websocket_id: Option<ConnectionId>,
+  ) -> Result<CommunityCategoryResponse, LemmyError> {
+    let data: &CreateCommunityCategory = self;
+    let local_user_view =
+      get_local_user_view_from_jwt(&data.auth, context.pool(), context.secret()).await?;
+    is_admin(&local_user_view)?;
+
+    let form = CommunityCategoryForm {
+      name: check_community_category_name(&data.name, context)?,
+      updated: None,
+    };
+    let community_category = blocking(context.pool(), move |conn| {
+      CommunityCategory::create(conn, &form)
+    })
+    .await?
+    .map_err(LemmyError::from)
+    .map_err(|e| e.with_message("community_category_already_exists"))?;
+
+    Ok(CommunityCategoryResponse { community_category })
+  }
+}
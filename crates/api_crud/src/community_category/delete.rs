@@ -0,0 +1,45 @@
+use crate::PerformCrud;
+use actix_web::web::Data;
+use lemmy_api_common::{
+  blocking,
+  community::{CommunityCategoryResponse, DeleteCommunityCategory},
+  get_local_user_view_from_jwt,
+  is_admin,
+};
+use lemmy_db_schema::{source::community_category::CommunityCategory, traits::Crud};
+use lemmy_utils::{ConnectionId, LemmyError};
+use lemmy_websocket::LemmyContext;
+
+#[async_trait::async_trait(?Send)]
+impl PerformCrud for DeleteCommunityCategory {
+  type Response = CommunityCategoryResponse;
+
+  #[tracing::instrument(skip(context, _websocket_id))]
+  async fn perform(
+    &self,
+    context: &Data<LemmyContext>,
+    _websocket_id: Option<ConnectionId>,
+  ) -> Result<CommunityCategoryResponse, LemmyError> {
+    let data: &DeleteCommunityCategory = self;
+    let local_user_view =
+      get_local_user_view_from_jwt(&data.auth, context.pool(), context.secret()).await?;
+    is_admin(&local_user_view)?;
+
+    let category_id = data.id;
+    let community_category = blocking(context.pool(), move |conn| {
+      CommunityCategory::read(conn, category_id)
+    })
+    .await?
+    .map_err(LemmyError::from)
+    .map_err(|e| e.with_message("couldnt_find_community_category"))?;
+
+    blocking(context.pool(), move |conn| {
+      CommunityCategory::delete(conn, category_id)
+    })
+    .await?
+    .map_err(LemmyError::from)
+    .map_err(|e| e.with_message("couldnt_delete_community_category"))?;
+
+    Ok(CommunityCategoryResponse { community_category })
+  }
+}
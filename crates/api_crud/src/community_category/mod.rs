@@ -0,0 +1,18 @@
+use lemmy_utils::{utils::check_slurs, LemmyError};
+use lemmy_websocket::LemmyContext;
+
+mod create;
+mod delete;
+mod read;
+mod update;
+
+const MAX_COMMUNITY_CATEGORY_LENGTH: usize = 64;
+
+fn check_community_category_name(name: &str, context: &LemmyContext) -> Result<String, LemmyError> {
+  let name = name.trim();
+  if name.is_empty() || name.chars().count() > MAX_COMMUNITY_CATEGORY_LENGTH {
+    return Err(LemmyError::from_message("invalid_community_category_name"));
+  }
+  check_slurs(name, &context.settings().slur_regex())?;
+  Ok(name.to_owned())
+}
@@ -0,0 +1,35 @@
+use crate::PerformCrud;
+use actix_web::web::Data;
+use lemmy_api_common::{
+  blocking,
+  check_private_instance,
+  community::{ListCommunityCategories, ListCommunityCategoriesResponse},
+  get_local_user_view_from_jwt_opt,
+};
+use lemmy_db_schema::source::community_category::CommunityCategory;
+use lemmy_utils::{ConnectionId, LemmyError};
+use lemmy_websocket::LemmyContext;
+
+#[async_trait::async_trait(?Send)]
+impl PerformCrud for ListCommunityCategories {
+  type Response = ListCommunityCategoriesResponse;
+
+  #[tracing::instrument(skip(context, _websocket_id))]
+  async fn perform(
+    &self,
+    context: &Data<LemmyContext>,
+    _websocket_id: Option<ConnectionId>,
+  ) -> Result<ListCommunityCategoriesResponse, LemmyError> {
+    let data: &ListCommunityCategories = self;
+    let local_user_view =
+      get_local_user_view_from_jwt_opt(data.auth.as_ref(), context.pool(), context.secret())
+        .await?;
+    check_private_instance(&local_user_view, context.pool()).await?;
+
+    let community_categories = blocking(context.pool(), CommunityCategory::list).await??;
+
+    Ok(ListCommunityCategoriesResponse {
+      community_categories,
+    })
+  }
+}
@@ -0,0 +1,46 @@
+use crate::{community_category::check_community_category_name, PerformCrud};
+use actix_web::web::Data;
+use lemmy_api_common::{
+  blocking,
+  community::{CommunityCategoryResponse, EditCommunityCategory},
+  get_local_user_view_from_jwt,
+  is_admin,
+};
+use lemmy_db_schema::{
+  naive_now,
+  source::community_category::{CommunityCategory, CommunityCategoryForm},
+  traits::Crud,
+};
+use lemmy_utils::{ConnectionId, LemmyError};
+use lemmy_websocket::LemmyContext;
+
+#[async_trait::async_trait(?Send)]
+impl PerformCrud for EditCommunityCategory {
+  type Response = CommunityCategoryResponse;
+
+  #[tracing::instrument(skip(context, _websocket_id))]
+  async fn perform(
+    &self,
+    context: &Data<LemmyContext>,
+    _websocket_id: Option<ConnectionId>,
+  ) -> Result<CommunityCategoryResponse, LemmyError> {
+    let data: &EditCommunityCategory = self;
+    let local_user_view =
+      get_local_user_view_from_jwt(&data.auth, context.pool(), context.secret()).await?;
+    is_admin(&local_user_view)?;
+
+    let category_id = data.id;
+    let form = CommunityCategoryForm {
+      name: check_community_category_name(&data.name, context)?,
+      updated: Some(naive_now()),
+    };
+    let community_category = blocking(context.pool(), move |conn| {
+      CommunityCategory::update(conn, category_id, &form)
+    })
+    .await?
+    .map_err(LemmyError::from)
+    .map_err(|e| e.with_message("couldnt_update_community_category"))?;
+
+    Ok(CommunityCategoryResponse { community_category })
+  }
+}
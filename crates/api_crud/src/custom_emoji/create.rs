@@ -0,0 +1,48 @@
+use crate::{custom_emoji::check_emoji_category, PerformCrud};
+use actix_web::web::Data;
+use lemmy_api_common::{
+  blocking,
+  get_local_user_view_from_jwt,
+  is_admin,
+  site::{CreateCustomEmoji, CustomEmojiResponse},
+};
+use lemmy_db_schema::{
+  source::custom_emoji::{CustomEmoji, CustomEmojiForm},
+  traits::Crud,
+};
+use lemmy_utils::{utils::is_valid_emoji_shortcode, ConnectionId, LemmyError};
+use lemmy_websocket::LemmyContext;
+
+#[async_trait::async_trait(?Send)]
+impl PerformCrud for CreateCustomEmoji {
+  type Response = CustomEmojiResponse;
+
+  #[tracing::instrument(skip(context, _websocket_id))]
+  async fn perform(
+    &self,
+    context: &Data<LemmyContext>,
+    _websocket_id: Option<ConnectionId>,
+  ) -> Result<CustomEmojiResponse, LemmyError> {
+    let data: &CreateCustomEmoji = self;
+    let local_user_view =
+      get_local_user_view_from_jwt(&data.auth, context.pool(), context.secret()).await?;
+    is_admin(&local_user_view)?;
+
+    if !is_valid_emoji_shortcode(&data.shortcode) {
+      return Err(LemmyError::from_message("invalid_emoji_shortcode"));
+    }
+
+    let form = CustomEmojiForm {
+      shortcode: data.shortcode.to_owned(),
+      image_url: data.image_url.to_owned().into(),
+      category: check_emoji_category(&data.category, context)?,
+      updated: None,
+    };
+    let custom_emoji = blocking(context.pool(), move |conn| CustomEmoji::create(conn, &form))
+      .await?
+      .map_err(LemmyError::from)
+      .map_err(|e| e.with_message("custom_emoji_already_exists"))?;
+
+    Ok(CustomEmojiResponse { custom_emoji })
+  }
+}
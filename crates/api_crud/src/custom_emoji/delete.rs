@@ -0,0 +1,46 @@
+use crate::PerformCrud;
+use actix_web::web::Data;
+use lemmy_api_common::{
+  blocking,
+  get_local_user_view_from_jwt,
+  is_admin,
+  site::{CustomEmojiResponse, DeleteCustomEmoji},
+};
+use lemmy_db_schema::{source::custom_emoji::CustomEmoji, traits::Crud};
+use lemmy_utils::{ConnectionId, LemmyError};
+use lemmy_websocket::LemmyContext;
+
+#[async_trait::async_trait(?Send)]
+impl PerformCrud for DeleteCustomEmoji {
+  type Response = CustomEmojiResponse;
+
+  #[tracing::instrument(skip(context, _websocket_id))]
+  async fn perform(
+    &self,
+    context: &Data<LemmyContext>,
+    _websocket_id: Option<ConnectionId>,
+  ) -> Result<CustomEmojiResponse, LemmyError> {
+    let data: &DeleteCustomEmoji = self;
+    let local_user_view =
+      get_local_user_view_from_jwt(&data.auth, context.pool(), context.secret()).await?;
+    is_admin(&local_user_view)?;
+
+    let emoji_id = data.id;
+    let custom_emoji = blocking(context.pool(), move |conn| {
+      CustomEmoji::read(conn, emoji_id)
+    })
+    .await?
+    .map_err(LemmyError::from)
+    .map_err(|e| e.with_message("couldnt_find_custom_emoji"))?;
+
+    // Existing content keeps the shortcode as plain text
+    blocking(context.pool(), move |conn| {
+      CustomEmoji::delete(conn, emoji_id)
+    })
+    .await?
+    .map_err(LemmyError::from)
+    .map_err(|e| e.with_message("couldnt_delete_custom_emoji"))?;
+
+    Ok(CustomEmojiResponse { custom_emoji })
+  }
+}
@@ -0,0 +1,18 @@
+use lemmy_utils::{utils::check_slurs, LemmyError};
+use lemmy_websocket::LemmyContext;
+
+mod create;
+mod delete;
+mod read;
+mod update;
+
+const MAX_EMOJI_CATEGORY_LENGTH: usize = 64;
+
+fn check_emoji_category(category: &str, context: &LemmyContext) -> Result<String, LemmyError> {
+  let category = category.trim();
+  if category.is_empty() || category.chars().count() > MAX_EMOJI_CATEGORY_LENGTH {
+    return Err(LemmyError::from_message("invalid_emoji_category"));
+  }
+  check_slurs(category, &context.settings().slur_regex())?;
+  Ok(category.to_owned())
+}
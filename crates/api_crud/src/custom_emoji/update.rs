@@ -0,0 +1,55 @@
+use crate::{custom_emoji::check_emoji_category, PerformCrud};
+use actix_web::web::Data;
+use lemmy_api_common::{
+  blocking,
+  get_local_user_view_from_jwt,
+  is_admin,
+  site::{CustomEmojiResponse, EditCustomEmoji},
+};
+use lemmy_db_schema::{
+  naive_now,
+  source::custom_emoji::{CustomEmoji, CustomEmojiForm},
+  traits::Crud,
+};
+use lemmy_utils::{ConnectionId, LemmyError};
+use lemmy_websocket::LemmyContext;
+
+#[async_trait::async_trait(?Send)]
+impl PerformCrud for EditCustomEmoji {
+  type Response = CustomEmojiResponse;
+
+  #[tracing::instrument(skip(context, _websocket_id))]
+  async fn perform(
+    &self,
+    context: &Data<LemmyContext>,
+    _websocket_id: Option<ConnectionId>,
+  ) -> Result<CustomEmojiResponse, LemmyError> {
+    let data: &EditCustomEmoji = self;
+    let local_user_view =
+      get_local_user_view_from_jwt(&data.auth, context.pool(), context.secret()).await?;
+    is_admin(&local_user_view)?;
+
+    let emoji_id = data.id;
+    let orig_emoji = blocking(context.pool(), move |conn| {
+      CustomEmoji::read(conn, emoji_id)
+    })
+    .await?
+    .map_err(LemmyError::from)
+    .map_err(|e| e.with_message("couldnt_find_custom_emoji"))?;
+
+    let form = CustomEmojiForm {
+      shortcode: orig_emoji.shortcode,
+      image_url: data.image_url.to_owned().into(),
+      category: check_emoji_category(&data.category, context)?,
+      updated: Some(naive_now()),
+    };
+    let custom_emoji = blocking(context.pool(), move |conn| {
+      CustomEmoji::update(conn, emoji_id, &form)
+    })
+    .await?
+    .map_err(LemmyError::from)
+    .map_err(|e| e.with_message("couldnt_update_custom_emoji"))?;
+
+    Ok(CustomEmojiResponse { custom_emoji })
+  }
+}
@@ -1,15 +1,35 @@
 use actix_web::{web, web::Data};
-use lemmy_api_common::{comment::*, community::*, person::*, post::*, site::*};
+use lemmy_api_common::{
+  comment::*,
+  community::*,
+  person::*,
+  post::*,
+  site::*,
+  token_scope::with_required_scope,
+};
+use lemmy_db_schema::TokenScope;
 use lemmy_utils::{ConnectionId, LemmyError};
 use lemmy_websocket::{serialize_websocket_message, LemmyContext, UserOperationCrud};
 use serde::Deserialize;
 
+mod appeal;
+mod automod_rule;
 mod comment;
 mod community;
+mod community_category;
+mod custom_emoji;
+mod multi_community;
 mod post;
+mod post_draft;
+mod post_tag;
 mod private_message;
+mod saved_collection;
+mod scheduled_post;
 mod site;
 mod user;
+mod wiki_page;
+
+pub use post::create::publish_scheduled_posts;
 
 #[async_trait::async_trait(?Send)]
 pub trait PerformCrud {
@@ -39,6 +59,15 @@ pub async fn match_websocket_operation_crud(
     UserOperationCrud::DeleteAccount => {
       do_websocket_operation::<DeleteAccount>(context, id, op, data).await
     }
+    UserOperationCrud::ListOAuthProviders => {
+      do_websocket_operation::<ListOAuthProviders>(context, id, op, data).await
+    }
+    UserOperationCrud::StartOAuth => {
+      do_websocket_operation::<StartOAuth>(context, id, op, data).await
+    }
+    UserOperationCrud::AuthenticateWithOAuth => {
+      do_websocket_operation::<AuthenticateWithOAuth>(context, id, op, data).await
+    }
 
     // Private Message ops
     UserOperationCrud::CreatePrivateMessage => {
@@ -53,6 +82,9 @@ pub async fn match_websocket_operation_crud(
     UserOperationCrud::GetPrivateMessages => {
       do_websocket_operation::<GetPrivateMessages>(context, id, op, data).await
     }
+    UserOperationCrud::GetPrivateMessageThread => {
+      do_websocket_operation::<GetPrivateMessageThread>(context, id, op, data).await
+    }
 
     // Site ops
     UserOperationCrud::CreateSite => {
@@ -81,10 +113,95 @@ pub async fn match_websocket_operation_crud(
       do_websocket_operation::<RemoveCommunity>(context, id, op, data).await
     }
 
+    // Appeal ops
+    UserOperationCrud::CreateAppeal => {
+      do_websocket_operation::<CreateAppeal>(context, id, op, data).await
+    }
+    UserOperationCrud::ListAppeals => {
+      do_websocket_operation::<ListAppeals>(context, id, op, data).await
+    }
+    UserOperationCrud::ResolveAppeal => {
+      do_websocket_operation::<ResolveAppeal>(context, id, op, data).await
+    }
+
+    // Community category ops
+    UserOperationCrud::CreateCommunityCategory => {
+      do_websocket_operation::<CreateCommunityCategory>(context, id, op, data).await
+    }
+    UserOperationCrud::ListCommunityCategories => {
+      do_websocket_operation::<ListCommunityCategories>(context, id, op, data).await
+    }
+    UserOperationCrud::EditCommunityCategory => {
+      do_websocket_operation::<EditCommunityCategory>(context, id, op, data).await
+    }
+    UserOperationCrud::DeleteCommunityCategory => {
+      do_websocket_operation::<DeleteCommunityCategory>(context, id, op, data).await
+    }
+
+    // Custom emoji ops
+    UserOperationCrud::CreateCustomEmoji => {
+      do_websocket_operation::<CreateCustomEmoji>(context, id, op, data).await
+    }
+    UserOperationCrud::ListCustomEmojis => {
+      do_websocket_operation::<ListCustomEmojis>(context, id, op, data).await
+    }
+    UserOperationCrud::EditCustomEmoji => {
+      do_websocket_operation::<EditCustomEmoji>(context, id, op, data).await
+    }
+    UserOperationCrud::DeleteCustomEmoji => {
+      do_websocket_operation::<DeleteCustomEmoji>(context, id, op, data).await
+    }
+
+    // Post tag ops
+    UserOperationCrud::CreatePostTag => {
+      do_websocket_operation::<CreatePostTag>(context, id, op, data).await
+    }
+    UserOperationCrud::ListPostTags => {
+      do_websocket_operation::<ListPostTags>(context, id, op, data).await
+    }
+    UserOperationCrud::EditPostTag => {
+      do_websocket_operation::<EditPostTag>(context, id, op, data).await
+    }
+    UserOperationCrud::DeletePostTag => {
+      do_websocket_operation::<DeletePostTag>(context, id, op, data).await
+    }
+    UserOperationCrud::CreateAutomodRule => {
+      do_websocket_operation::<CreateAutomodRule>(context, id, op, data).await
+    }
+    UserOperationCrud::ListAutomodRules => {
+      do_websocket_operation::<ListAutomodRules>(context, id, op, data).await
+    }
+    UserOperationCrud::EditAutomodRule => {
+      do_websocket_operation::<EditAutomodRule>(context, id, op, data).await
+    }
+    UserOperationCrud::DeleteAutomodRule => {
+      do_websocket_operation::<DeleteAutomodRule>(context, id, op, data).await
+    }
+
+    // Wiki page ops
+    UserOperationCrud::CreateWikiPage => {
+      do_websocket_operation::<CreateWikiPage>(context, id, op, data).await
+    }
+    UserOperationCrud::GetWikiPage => {
+      do_websocket_operation::<GetWikiPage>(context, id, op, data).await
+    }
+    UserOperationCrud::ListWikiPages => {
+      do_websocket_operation::<ListWikiPages>(context, id, op, data).await
+    }
+    UserOperationCrud::EditWikiPage => {
+      do_websocket_operation::<EditWikiPage>(context, id, op, data).await
+    }
+    UserOperationCrud::DeleteWikiPage => {
+      do_websocket_operation::<DeleteWikiPage>(context, id, op, data).await
+    }
+
     // Post ops
     UserOperationCrud::CreatePost => {
       do_websocket_operation::<CreatePost>(context, id, op, data).await
     }
+    UserOperationCrud::CrossPost => {
+      do_websocket_operation::<CrossPost>(context, id, op, data).await
+    }
     UserOperationCrud::GetPost => do_websocket_operation::<GetPost>(context, id, op, data).await,
     UserOperationCrud::GetPosts => do_websocket_operation::<GetPosts>(context, id, op, data).await,
     UserOperationCrud::EditPost => do_websocket_operation::<EditPost>(context, id, op, data).await,
@@ -95,6 +212,56 @@ pub async fn match_websocket_operation_crud(
       do_websocket_operation::<RemovePost>(context, id, op, data).await
     }
 
+    // Scheduled post ops
+    UserOperationCrud::SchedulePost => {
+      do_websocket_operation::<SchedulePost>(context, id, op, data).await
+    }
+    UserOperationCrud::ListScheduledPosts => {
+      do_websocket_operation::<ListScheduledPosts>(context, id, op, data).await
+    }
+    UserOperationCrud::DeleteScheduledPost => {
+      do_websocket_operation::<DeleteScheduledPost>(context, id, op, data).await
+    }
+
+    // Post draft ops
+    UserOperationCrud::SaveDraft => {
+      do_websocket_operation::<SaveDraft>(context, id, op, data).await
+    }
+    UserOperationCrud::ListDrafts => {
+      do_websocket_operation::<ListDrafts>(context, id, op, data).await
+    }
+    UserOperationCrud::DeleteDraft => {
+      do_websocket_operation::<DeleteDraft>(context, id, op, data).await
+    }
+
+    // Saved collection ops
+    UserOperationCrud::CreateSavedCollection => {
+      do_websocket_operation::<CreateSavedCollection>(context, id, op, data).await
+    }
+    UserOperationCrud::ListSavedCollections => {
+      do_websocket_operation::<ListSavedCollections>(context, id, op, data).await
+    }
+    UserOperationCrud::EditSavedCollection => {
+      do_websocket_operation::<EditSavedCollection>(context, id, op, data).await
+    }
+    UserOperationCrud::DeleteSavedCollection => {
+      do_websocket_operation::<DeleteSavedCollection>(context, id, op, data).await
+    }
+
+    // Multi-community ops
+    UserOperationCrud::CreateMultiCommunity => {
+      do_websocket_operation::<CreateMultiCommunity>(context, id, op, data).await
+    }
+    UserOperationCrud::ListMultiCommunities => {
+      do_websocket_operation::<ListMultiCommunities>(context, id, op, data).await
+    }
+    UserOperationCrud::EditMultiCommunity => {
+      do_websocket_operation::<EditMultiCommunity>(context, id, op, data).await
+    }
+    UserOperationCrud::DeleteMultiCommunity => {
+      do_websocket_operation::<DeleteMultiCommunity>(context, id, op, data).await
+    }
+
     // Comment ops
     UserOperationCrud::CreateComment => {
       do_websocket_operation::<CreateComment>(context, id, op, data).await
@@ -108,12 +275,18 @@ pub async fn match_websocket_operation_crud(
     UserOperationCrud::RemoveComment => {
       do_websocket_operation::<RemoveComment>(context, id, op, data).await
     }
+    UserOperationCrud::GetCommentEditHistory => {
+      do_websocket_operation::<GetCommentEditHistory>(context, id, op, data).await
+    }
     UserOperationCrud::GetComment => {
       do_websocket_operation::<GetComment>(context, id, op, data).await
     }
     UserOperationCrud::GetComments => {
       do_websocket_operation::<GetComments>(context, id, op, data).await
     }
+    UserOperationCrud::GetCommentChildren => {
+      do_websocket_operation::<GetCommentChildren>(context, id, op, data).await
+    }
   }
 }
 
@@ -128,8 +301,85 @@ where
   Data: PerformCrud,
 {
   let parsed_data: Data = serde_json::from_str(data)?;
-  let res = parsed_data
-    .perform(&web::Data::new(context), Some(id))
-    .await?;
+  let res = with_required_scope(
+    op.access(),
+    TokenScope::Write,
+    parsed_data.perform(&web::Data::new(context), Some(id)),
+  )
+  .await?;
   serialize_websocket_message(&op, &res)
 }
+
+#[cfg(test)]
+pub(crate) mod tests {
+  use actix::Actor;
+  use diesel::{
+    r2d2::{ConnectionManager, Pool},
+    PgConnection,
+  };
+  use lemmy_apub_lib::activity_queue::create_activity_queue;
+  use lemmy_db_schema::{
+    establish_unpooled_connection,
+    get_database_url_from_env,
+    source::secret::Secret,
+  };
+  use lemmy_utils::{
+    rate_limit::{rate_limiter::RateLimiter, RateLimit},
+    request::build_user_agent,
+    settings::structs::Settings,
+    LemmyError,
+  };
+  use lemmy_websocket::{chat_server::ChatServer, LemmyContext};
+  use reqwest::Client;
+  use reqwest_middleware::ClientBuilder;
+  use std::sync::Arc;
+  use tokio::sync::Mutex;
+
+  /// Context for calling the handlers, which doesn't federate anything
+  pub(crate) fn init_context() -> LemmyContext {
+    // call this to run migrations
+    establish_unpooled_connection();
+    let settings = Settings::init().unwrap();
+    let rate_limiter = RateLimit {
+      rate_limiter: Arc::new(Mutex::new(RateLimiter::default())),
+      rate_limit_config: settings.rate_limit.to_owned().unwrap_or_default(),
+    };
+    let client = Client::builder()
+      .user_agent(build_user_agent(&settings))
+      .build()
+      .unwrap();
+    let client = ClientBuilder::new(client).build();
+    let activity_queue = create_activity_queue(client.clone()).queue_handle().clone();
+    let secret = Secret {
+      id: 0,
+      jwt_secret: "".to_string(),
+      instance_private_key: None,
+      instance_public_key: None,
+    };
+    let db_url = match get_database_url_from_env() {
+      Ok(url) => url,
+      Err(_) => settings.get_database_url(),
+    };
+    let manager = ConnectionManager::<PgConnection>::new(&db_url);
+    let pool = Pool::builder()
+      .max_size(settings.database.pool_size)
+      .build(manager)
+      .unwrap_or_else(|_| panic!("Error connecting to {}", db_url));
+    async fn x() -> Result<String, LemmyError> {
+      Ok("".to_string())
+    }
+    let chat_server = ChatServer::startup(
+      pool.clone(),
+      rate_limiter,
+      |_, _, _, _| Box::pin(x()),
+      |_, _, _, _| Box::pin(x()),
+      client.clone(),
+      activity_queue.clone(),
+      settings.clone(),
+      secret.clone(),
+      None,
+    )
+    .start();
+    LemmyContext::create(pool, chat_server, client, activity_queue, settings, secret)
+  }
+}
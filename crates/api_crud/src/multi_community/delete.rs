@@ -0,0 +1,51 @@
+use crate::PerformCrud;
+use actix_web::web::Data;
+use lemmy_api_common::{
+  blocking,
+  check_multi_community_owner,
+  get_local_user_view_from_jwt,
+  person::*,
+};
+use lemmy_db_schema::{source::multi_community::MultiCommunity, traits::Crud};
+use lemmy_db_views_actor::multi_community_view::MultiCommunityView;
+use lemmy_utils::{ConnectionId, LemmyError};
+use lemmy_websocket::LemmyContext;
+
+#[async_trait::async_trait(?Send)]
+impl PerformCrud for DeleteMultiCommunity {
+  type Response = MultiCommunityResponse;
+
+  #[tracing::instrument(skip(context, _websocket_id))]
+  async fn perform(
+    &self,
+    context: &Data<LemmyContext>,
+    _websocket_id: Option<ConnectionId>,
+  ) -> Result<MultiCommunityResponse, LemmyError> {
+    let data: &DeleteMultiCommunity = self;
+    let local_user_view =
+      get_local_user_view_from_jwt(&data.auth, context.pool(), context.secret()).await?;
+
+    let multi_community_id = data.multi_community_id;
+    check_multi_community_owner(
+      Some(multi_community_id),
+      Some(local_user_view.person.id),
+      context.pool(),
+    )
+    .await?;
+
+    let multi_community_view = blocking(context.pool(), move |conn| {
+      MultiCommunityView::read(conn, multi_community_id)
+    })
+    .await??;
+    blocking(context.pool(), move |conn| {
+      MultiCommunity::delete(conn, multi_community_id)
+    })
+    .await?
+    .map_err(LemmyError::from)
+    .map_err(|e| e.with_message("couldnt_delete_multi_community"))?;
+
+    Ok(MultiCommunityResponse {
+      multi_community_view,
+    })
+  }
+}
@@ -0,0 +1,34 @@
+use lemmy_utils::LemmyError;
+
+mod create;
+mod delete;
+mod read;
+mod update;
+
+const MAX_MULTI_COMMUNITY_NAME_LENGTH: usize = 100;
+const MAX_MULTI_COMMUNITY_SIZE: usize = 100;
+
+fn check_multi_community_name(name: &str) -> Result<String, LemmyError> {
+  let name = name.trim();
+  if name.is_empty() || name.chars().count() > MAX_MULTI_COMMUNITY_NAME_LENGTH {
+    return Err(LemmyError::from_message("invalid_multi_community_name"));
+  }
+  Ok(name.to_owned())
+}
+
+fn check_multi_community_size<T>(community_ids: &[T]) -> Result<(), LemmyError> {
+  if community_ids.len() > MAX_MULTI_COMMUNITY_SIZE {
+    return Err(LemmyError::from_message("too_many_communities"));
+  }
+  Ok(())
+}
+
+/// Maps the unique violation on (person_id, name) to a readable error
+fn multi_community_error(e: diesel::result::Error) -> LemmyError {
+  let err_type = if e.to_string().contains("multi_community_person_id_name_key") {
+    "multi_community_already_exists"
+  } else {
+    "couldnt_save_multi_community"
+  };
+  LemmyError::from(e).with_message(err_type)
+}
@@ -0,0 +1,30 @@
+use crate::PerformCrud;
+use actix_web::web::Data;
+use lemmy_api_common::{blocking, get_local_user_view_from_jwt, person::*};
+use lemmy_db_views_actor::multi_community_view::MultiCommunityView;
+use lemmy_utils::{ConnectionId, LemmyError};
+use lemmy_websocket::LemmyContext;
+
+#[async_trait::async_trait(?Send)]
+impl PerformCrud for ListMultiCommunities {
+  type Response = ListMultiCommunitiesResponse;
+
+  #[tracing::instrument(skip(context, _websocket_id))]
+  async fn perform(
+    &self,
+    context: &Data<LemmyContext>,
+    _websocket_id: Option<ConnectionId>,
+  ) -> Result<ListMultiCommunitiesResponse, LemmyError> {
+    let data: &ListMultiCommunities = self;
+    let local_user_view =
+      get_local_user_view_from_jwt(&data.auth, context.pool(), context.secret()).await?;
+
+    let person_id = local_user_view.person.id;
+    let multi_communities = blocking(context.pool(), move |conn| {
+      MultiCommunityView::list_for_person(conn, person_id)
+    })
+    .await??;
+
+    Ok(ListMultiCommunitiesResponse { multi_communities })
+  }
+}
@@ -0,0 +1,69 @@
+use crate::{
+  multi_community::{
+    check_multi_community_name,
+    check_multi_community_size,
+    multi_community_error,
+  },
+  PerformCrud,
+};
+use actix_web::web::Data;
+use diesel::Connection;
+use lemmy_api_common::{
+  blocking,
+  check_multi_community_owner,
+  get_local_user_view_from_jwt,
+  person::*,
+};
+use lemmy_db_schema::{
+  naive_now,
+  source::multi_community::{MultiCommunity, MultiCommunityForm},
+  traits::Crud,
+};
+use lemmy_db_views_actor::multi_community_view::MultiCommunityView;
+use lemmy_utils::{ConnectionId, LemmyError};
+use lemmy_websocket::LemmyContext;
+
+#[async_trait::async_trait(?Send)]
+impl PerformCrud for EditMultiCommunity {
+  type Response = MultiCommunityResponse;
+
+  #[tracing::instrument(skip(context, _websocket_id))]
+  async fn perform(
+    &self,
+    context: &Data<LemmyContext>,
+    _websocket_id: Option<ConnectionId>,
+  ) -> Result<MultiCommunityResponse, LemmyError> {
+    let data: &EditMultiCommunity = self;
+    let local_user_view =
+      get_local_user_view_from_jwt(&data.auth, context.pool(), context.secret()).await?;
+
+    let person_id = local_user_view.person.id;
+    let multi_community_id = data.multi_community_id;
+    check_multi_community_owner(Some(multi_community_id), Some(person_id), context.pool()).await?;
+
+    check_multi_community_size(&data.community_ids)?;
+    let form = MultiCommunityForm {
+      person_id,
+      name: check_multi_community_name(&data.name)?,
+      updated: Some(naive_now()),
+    };
+    let community_ids = data.community_ids.to_owned();
+    blocking(context.pool(), move |conn| {
+      conn.transaction(|| {
+        MultiCommunity::update(conn, multi_community_id, &form)?;
+        MultiCommunity::replace_communities(conn, multi_community_id, &community_ids)
+      })
+    })
+    .await?
+    .map_err(multi_community_error)?;
+
+    let multi_community_view = blocking(context.pool(), move |conn| {
+      MultiCommunityView::read(conn, multi_community_id)
+    })
+    .await??;
+
+    Ok(MultiCommunityResponse {
+      multi_community_view,
+    })
+  }
+}
@@ -1,11 +1,17 @@
-use crate::PerformCrud;
+use crate::{
+  automod_rule::{apply_automod_rules, AutomodTarget},
+  post_tag::replace_post_tags,
+  PerformCrud,
+};
 use actix_web::web::Data;
 use lemmy_api_common::{
   blocking,
   check_community_ban,
   check_community_deleted_or_removed,
+  check_language_allowed,
   get_local_user_view_from_jwt,
   honeypot_check,
+  is_mod_or_admin,
   mark_post_as_read,
   post::*,
 };
@@ -21,16 +27,24 @@ use lemmy_apub::{
   EndpointType,
 };
 use lemmy_db_schema::{
-  source::post::{Post, PostForm, PostLike, PostLikeForm},
+  clock::{Clock, SystemClock},
+  source::{
+    community::Community,
+    person::Person,
+    poll::{Poll, PollForm, PollOption, PollOptionForm},
+    post::{Post, PostForm, PostLike, PostLikeForm},
+    scheduled_post::ScheduledPost,
+  },
   traits::{Crud, Likeable},
 };
 use lemmy_utils::{
   request::fetch_site_data,
-  utils::{check_slurs, check_slurs_opt, clean_url_params, is_valid_post_title},
+  utils::{check_slurs, check_slurs_opt, clean_url_params, is_valid_post_title, naive_from_unix},
   ConnectionId,
   LemmyError,
 };
 use lemmy_websocket::{send::send_post_ws_message, LemmyContext, UserOperationCrud};
+use std::collections::HashSet;
 use tracing::warn;
 use url::Url;
 use webmention::{Webmention, WebmentionError};
@@ -58,76 +72,319 @@ impl PerformCrud for CreatePost {
       return Err(LemmyError::from_message("invalid_post_title"));
     }
 
+    if let Some(poll) = &data.poll {
+      check_poll(poll, &SystemClock, context)?;
+    }
+
     check_community_ban(local_user_view.person.id, data.community_id, context.pool()).await?;
     check_community_deleted_or_removed(data.community_id, context.pool()).await?;
-
-    // Fetch post links and pictrs cached image
-    let data_url = data.url.as_ref();
-    let (metadata_res, pictrs_thumbnail) =
-      fetch_site_data(context.client(), &context.settings(), data_url).await;
-    let (embed_title, embed_description, embed_html) = metadata_res
-      .map(|u| (u.title, u.description, u.html))
-      .unwrap_or((None, None, None));
+    check_language_allowed(data.community_id, data.language_id, context.pool()).await?;
 
     let post_form = PostForm {
       name: data.name.trim().to_owned(),
-      url: data_url.map(|u| clean_url_params(u.to_owned()).into()),
+      url: data
+        .url
+        .as_ref()
+        .map(|u| clean_url_params(u.to_owned()).into()),
       body: data.body.to_owned(),
       community_id: data.community_id,
       creator_id: local_user_view.person.id,
       nsfw: data.nsfw,
-      embed_title,
-      embed_description,
-      embed_html,
-      thumbnail_url: pictrs_thumbnail.map(|u| u.into()),
+      language_id: data.language_id,
       ..PostForm::default()
     };
 
-    let inserted_post =
-      match blocking(context.pool(), move |conn| Post::create(conn, &post_form)).await? {
-        Ok(post) => post,
-        Err(e) => {
-          let err_type = if e.to_string() == "value too long for type character varying(200)" {
-            "post_title_too_long"
-          } else {
-            "couldnt_create_post"
-          };
-
-          return Err(LemmyError::from(e).with_message(err_type));
-        }
-      };
+    create_post(
+      post_form,
+      data.poll.as_ref(),
+      data.tag_ids.as_deref(),
+      &local_user_view.person,
+      websocket_id,
+      context,
+    )
+    .await
+  }
+}
 
-    let inserted_post_id = inserted_post.id;
-    let protocol_and_hostname = context.settings().get_protocol_and_hostname();
-    let updated_post = blocking(context.pool(), move |conn| -> Result<Post, LemmyError> {
-      let apub_id = generate_local_apub_endpoint(
-        EndpointType::Post,
-        &inserted_post_id.to_string(),
-        &protocol_and_hostname,
-      )?;
-      Ok(Post::update_ap_id(conn, inserted_post_id, apub_id)?)
+#[async_trait::async_trait(?Send)]
+impl PerformCrud for CrossPost {
+  type Response = PostResponse;
+
+  #[tracing::instrument(skip(context, websocket_id))]
+  async fn perform(
+    &self,
+    context: &Data<LemmyContext>,
+    websocket_id: Option<ConnectionId>,
+  ) -> Result<PostResponse, LemmyError> {
+    let data: &CrossPost = self;
+    let local_user_view =
+      get_local_user_view_from_jwt(&data.auth, context.pool(), context.secret()).await?;
+
+    // Cross-posts always link to the original post, so that all of them can be listed together
+    let post_id = data.post_id;
+    let original_post = blocking(context.pool(), move |conn| -> Result<Post, LemmyError> {
+      let post = Post::read(conn, post_id)?;
+      Ok(match post.cross_post_of {
+        Some(original_post_id) => Post::read(conn, original_post_id)?,
+        None => post,
+      })
     })
     .await?
-    .map_err(LemmyError::from)
-    .map_err(|e| e.with_message("couldnt_create_post"))?;
-
-    // They like their own post by default
-    let person_id = local_user_view.person.id;
-    let post_id = inserted_post.id;
-    let like_form = PostLikeForm {
-      post_id,
-      person_id,
-      score: 1,
+    .map_err(|e| e.with_message("couldnt_find_post"))?;
+    if original_post.deleted || original_post.removed {
+      return Err(LemmyError::from_message("couldnt_find_post"));
+    }
+
+    let community_id = data.community_id;
+    check_community_ban(local_user_view.person.id, community_id, context.pool()).await?;
+    check_community_deleted_or_removed(community_id, context.pool()).await?;
+
+    let original_post_id = original_post.id;
+    let already_posted = blocking(context.pool(), move |conn| {
+      Post::is_cross_posted_to(conn, original_post_id, community_id)
+    })
+    .await??;
+    if already_posted {
+      return Err(LemmyError::from_message("already_cross_posted"));
+    }
+
+    let post_form = PostForm {
+      name: original_post.name,
+      url: original_post.url,
+      body: original_post.body,
+      community_id,
+      creator_id: local_user_view.person.id,
+      nsfw: Some(original_post.nsfw),
+      cross_post_of: Some(original_post_id),
+      language_id: Some(original_post.language_id),
+      ..PostForm::default()
     };
+    create_post(
+      post_form,
+      None,
+      None,
+      &local_user_view.person,
+      websocket_id,
+      context,
+    )
+    .await
+  }
+}
 
-    let like = move |conn: &'_ _| PostLike::like(conn, &like_form);
-    if blocking(context.pool(), like).await?.is_err() {
-      return Err(LemmyError::from_message("couldnt_like_post"));
+/// Publishes all scheduled posts whose time has come. A post is dropped if its creator was banned
+/// in the meantime, or if the community isn't available anymore.
+#[tracing::instrument(skip_all)]
+pub async fn publish_scheduled_posts(
+  context: &LemmyContext,
+  clock: &dyn Clock,
+) -> Result<(), LemmyError> {
+  let now = clock.now();
+  let due = blocking(context.pool(), move |conn| {
+    ScheduledPost::list_due(conn, now)
+  })
+  .await??;
+  for scheduled_post in due {
+    // Claim it first, so that a failure can never lead to the post being published twice. If
+    // another node or the creator got to it first, it is skipped.
+    let scheduled_post_id = scheduled_post.id;
+    let claimed = blocking(context.pool(), move |conn| {
+      ScheduledPost::claim(conn, scheduled_post_id)
+    })
+    .await??;
+    let scheduled_post = match claimed {
+      Some(scheduled_post) => scheduled_post,
+      None => continue,
+    };
+    if let Err(e) = publish_scheduled_post(scheduled_post, context).await {
+      warn!(
+        "Failed to publish scheduled post {}: {}",
+        scheduled_post_id, e
+      );
     }
+  }
+  Ok(())
+}
 
-    // Mark the post as read
-    mark_post_as_read(person_id, post_id, context.pool()).await?;
+async fn publish_scheduled_post(
+  scheduled_post: ScheduledPost,
+  context: &LemmyContext,
+) -> Result<(), LemmyError> {
+  let creator_id = scheduled_post.creator_id;
+  let creator = blocking(context.pool(), move |conn| Person::read(conn, creator_id)).await??;
+  if creator.banned {
+    return Err(LemmyError::from_message("site_ban"));
+  }
+  if creator.deleted {
+    return Err(LemmyError::from_message("deleted"));
+  }
+  check_community_ban(creator.id, scheduled_post.community_id, context.pool()).await?;
+  check_community_deleted_or_removed(scheduled_post.community_id, context.pool()).await?;
+
+  let post_form = PostForm {
+    name: scheduled_post.name,
+    url: scheduled_post.url,
+    body: scheduled_post.body,
+    community_id: scheduled_post.community_id,
+    creator_id,
+    nsfw: Some(scheduled_post.nsfw),
+    language_id: Some(scheduled_post.language_id),
+    ..PostForm::default()
+  };
+  create_post(post_form, None, None, &creator, None, context).await?;
+  Ok(())
+}
+
+const MAX_POLL_OPTIONS: usize = 10;
+const MAX_POLL_OPTION_LENGTH: usize = 200;
 
+/// Options are identified by name when federating votes, so they need to be unique
+fn check_poll(
+  poll: &CreatePoll,
+  clock: &dyn Clock,
+  context: &LemmyContext,
+) -> Result<(), LemmyError> {
+  let slur_regex = &context.settings().slur_regex();
+  if poll.options.len() < 2 || poll.options.len() > MAX_POLL_OPTIONS {
+    return Err(LemmyError::from_message("invalid_poll_options"));
+  }
+  let mut names = HashSet::new();
+  for option in &poll.options {
+    let option = option.trim();
+    if option.is_empty() || option.chars().count() > MAX_POLL_OPTION_LENGTH {
+      return Err(LemmyError::from_message("invalid_poll_options"));
+    }
+    check_slurs(option, slur_regex)?;
+    if !names.insert(option) {
+      return Err(LemmyError::from_message("duplicate_poll_options"));
+    }
+  }
+  if let Some(end_time) = poll.end_time.map(naive_from_unix) {
+    if end_time <= clock.now() {
+      return Err(LemmyError::from_message("invalid_poll_end_time"));
+    }
+  }
+  Ok(())
+}
+
+/// Inserts a new local post, and federates it
+async fn create_post(
+  mut post_form: PostForm,
+  poll: Option<&CreatePoll>,
+  tag_ids: Option<&[i32]>,
+  creator: &Person,
+  websocket_id: Option<ConnectionId>,
+  context: &LemmyContext,
+) -> Result<PostResponse, LemmyError> {
+  // Fetch post links and pictrs cached image
+  let data_url: Option<Url> = post_form.url.clone().map(Into::into);
+  let (metadata_res, pictrs_thumbnail) =
+    fetch_site_data(context.client(), &context.settings(), data_url.as_ref()).await;
+  let metadata = metadata_res.unwrap_or_default();
+  post_form.embed_title = metadata.title;
+  post_form.embed_description = metadata.description;
+  post_form.embed_html = metadata.html;
+  post_form.embed_video_url = metadata.embed_video_url.map(|u| u.into());
+  post_form.embed_provider_name = metadata.provider_name;
+  post_form.thumbnail_url = pictrs_thumbnail.map(|u| u.into());
+
+  // Posts by users who aren't moderators need to be approved, if the community requires it
+  let community_id = post_form.community_id;
+  let community = blocking(context.pool(), move |conn| {
+    Community::read(conn, community_id)
+  })
+  .await??;
+  if community.require_post_approval
+    && is_mod_or_admin(context.pool(), creator.id, community_id)
+      .await
+      .is_err()
+  {
+    post_form.pending = Some(true);
+  }
+
+  let inserted_post =
+    match blocking(context.pool(), move |conn| Post::create(conn, &post_form)).await? {
+      Ok(post) => post,
+      Err(e) => {
+        let err_type = if e.to_string() == "value too long for type character varying(200)" {
+          "post_title_too_long"
+        } else {
+          "couldnt_create_post"
+        };
+
+        return Err(LemmyError::from(e).with_message(err_type));
+      }
+    };
+
+  let inserted_post_id = inserted_post.id;
+  let protocol_and_hostname = context.settings().get_protocol_and_hostname();
+  let updated_post = blocking(context.pool(), move |conn| -> Result<Post, LemmyError> {
+    let apub_id = generate_local_apub_endpoint(
+      EndpointType::Post,
+      &inserted_post_id.to_string(),
+      &protocol_and_hostname,
+    )?;
+    Ok(Post::update_ap_id(conn, inserted_post_id, apub_id)?)
+  })
+  .await?
+  .map_err(|e| e.with_message("couldnt_create_post"))?;
+
+  if let Some(poll) = poll {
+    let poll_form = PollForm {
+      post_id: inserted_post_id,
+      multiple_choice: poll.multiple_choice.unwrap_or(false),
+      end_time: poll.end_time.map(naive_from_unix),
+    };
+    let options = poll.options.clone();
+    blocking(context.pool(), move |conn| {
+      let poll = Poll::upsert(conn, &poll_form)?;
+      let option_forms: Vec<PollOptionForm> = options
+        .iter()
+        .enumerate()
+        .map(|(position, name)| PollOptionForm {
+          poll_id: poll.id,
+          name: name.trim().to_owned(),
+          position: position as i32,
+          votes: None,
+        })
+        .collect();
+      PollOption::replace_for_poll(conn, poll.id, &option_forms)
+    })
+    .await?
+    .map_err(LemmyError::from)
+    .map_err(|e| e.with_message("couldnt_create_poll"))?;
+  }
+
+  if let Some(tag_ids) = tag_ids {
+    replace_post_tags(
+      inserted_post_id,
+      inserted_post.community_id,
+      tag_ids,
+      context.pool(),
+    )
+    .await?;
+  }
+
+  // They like their own post by default
+  let person_id = creator.id;
+  let post_id = inserted_post.id;
+  let like_form = PostLikeForm {
+    post_id,
+    person_id,
+    score: 1,
+  };
+
+  let like = move |conn: &'_ _| PostLike::like(conn, &like_form);
+  if blocking(context.pool(), like).await?.is_err() {
+    return Err(LemmyError::from_message("couldnt_like_post"));
+  }
+
+  // Mark the post as read
+  mark_post_as_read(person_id, post_id, context.pool()).await?;
+
+  // Pending posts are only federated once they are approved, and posts removed by automod never
+  let hidden_by_automod =
+    apply_automod_rules(AutomodTarget::Post(&updated_post), creator, context).await?;
+  if !updated_post.pending && !hidden_by_automod {
     if let Some(url) = &updated_post.url {
       let mut webmention =
         Webmention::new::<Url>(updated_post.ap_id.clone().into(), url.clone().into())?;
@@ -142,7 +399,7 @@ impl PerformCrud for CreatePost {
     let apub_post: ApubPost = updated_post.into();
     CreateOrUpdatePost::send(
       apub_post.clone(),
-      &local_user_view.person.clone().into(),
+      &creator.clone().into(),
       CreateOrUpdateType::Create,
       context,
     )
@@ -150,20 +407,109 @@ impl PerformCrud for CreatePost {
     let object = PostOrComment::Post(Box::new(apub_post));
     Vote::send(
       &object,
-      &local_user_view.person.clone().into(),
+      &creator.clone().into(),
       inserted_post.community_id,
       VoteType::Like,
       context,
     )
     .await?;
+  }
 
-    send_post_ws_message(
-      inserted_post.id,
-      UserOperationCrud::CreatePost,
-      websocket_id,
-      Some(local_user_view.person.id),
-      context,
+  send_post_ws_message(
+    inserted_post.id,
+    UserOperationCrud::CreatePost,
+    websocket_id,
+    Some(creator.id),
+    context,
+  )
+  .await
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::tests::init_context;
+  use chrono::{Duration, NaiveDate};
+  use diesel::{ExpressionMethods, QueryDsl, RunQueryDsl};
+  use lemmy_db_schema::{
+    clock::MockClock,
+    schema::{activity_send_queue, post},
+    source::{community::CommunityForm, person::PersonForm, scheduled_post::ScheduledPostForm},
+  };
+  use serial_test::serial;
+
+  #[actix_rt::test]
+  async fn test_check_poll_end_time() {
+    let context = init_context();
+    let clock = MockClock::new(NaiveDate::from_ymd(2021, 12, 1).and_hms(12, 0, 0));
+    let poll = CreatePoll {
+      options: vec!["yes".to_string(), "no".to_string()],
+      multiple_choice: None,
+      end_time: Some((clock.now() + Duration::hours(1)).timestamp()),
+    };
+
+    assert!(check_poll(&poll, &clock, &context).is_ok());
+    clock.advance(Duration::hours(1));
+    assert_eq!(
+      Some("invalid_poll_end_time"),
+      check_poll(&poll, &clock, &context).unwrap_err().message
+    );
+  }
+
+  #[actix_rt::test]
+  #[serial]
+  async fn test_publish_scheduled_posts_once() {
+    let context = init_context();
+    let conn = context.pool().get().unwrap();
+
+    let person_form = PersonForm {
+      name: "scheduled_publisher".into(),
+      ..PersonForm::default()
+    };
+    let person = Person::create(&conn, &person_form).unwrap();
+    let community_form = CommunityForm {
+      name: "scheduled_publish".to_string(),
+      title: "nada".to_owned(),
+      ..CommunityForm::default()
+    };
+    let community = Community::create(&conn, &community_form).unwrap();
+    let clock = MockClock::new(NaiveDate::from_ymd(2021, 12, 1).and_hms(12, 0, 0));
+    let schedule = |name: &str| {
+      let form = ScheduledPostForm {
+        creator_id: person.id,
+        community_id: community.id,
+        name: name.into(),
+        url: None,
+        body: None,
+        nsfw: None,
+        publish_at: clock.now(),
+        language_id: None,
+      };
+      ScheduledPost::create(&conn, &form).unwrap()
+    };
+    schedule("published");
+    let claimed_elsewhere = schedule("claimed elsewhere");
+    // Another node already started publishing this one
+    ScheduledPost::claim(&conn, claimed_elsewhere.id).unwrap();
+
+    publish_scheduled_posts(&context, &clock).await.unwrap();
+    publish_scheduled_posts(&context, &clock).await.unwrap();
+    let published = post::table
+      .filter(post::community_id.eq(community.id))
+      .select(post::name)
+      .load::<String>(&conn)
+      .unwrap();
+    let remaining = ScheduledPost::list_for_creator(&conn, person.id, None, None).unwrap();
+
+    diesel::delete(
+      activity_send_queue::table.filter(activity_send_queue::actor_id.eq(&person.actor_id)),
     )
-    .await
+    .execute(&conn)
+    .unwrap();
+    Community::delete(&conn, community.id).unwrap();
+    Person::delete(&conn, person.id).unwrap();
+
+    assert_eq!(vec!["published".to_string()], published);
+    assert!(remaining.is_empty());
   }
 }
@@ -4,11 +4,15 @@ use lemmy_api_common::{
   blocking,
   check_community_ban,
   check_community_deleted_or_removed,
+  check_removal_reason,
   get_local_user_view_from_jwt,
   is_mod_or_admin,
   post::*,
 };
-use lemmy_apub::activities::deletion::{send_apub_delete, send_apub_remove, DeletableObjects};
+use lemmy_apub::activities::{
+  deletion::{send_apub_delete, send_apub_remove, DeletableObjects, DeletionAction},
+  federation_target::FederationTarget,
+};
 use lemmy_db_schema::{
   source::{
     community::Community,
@@ -18,7 +22,12 @@ use lemmy_db_schema::{
   traits::Crud,
 };
 use lemmy_utils::{ConnectionId, LemmyError};
-use lemmy_websocket::{send::send_post_ws_message, LemmyContext, UserOperationCrud};
+use lemmy_websocket::{
+  messages::RemovalInfo,
+  send::{send_post_removal_ws_message, send_post_ws_message},
+  LemmyContext,
+  UserOperationCrud,
+};
 
 #[async_trait::async_trait(?Send)]
 impl PerformCrud for DeletePost {
@@ -68,11 +77,13 @@ impl PerformCrud for DeletePost {
       Community::read(conn, orig_post.community_id)
     })
     .await??;
+    let target = FederationTarget::Community(community.id);
     send_apub_delete(
       &local_user_view.person.clone().into(),
       &community.into(),
       DeletableObjects::Post(Box::new(updated_post.into())),
       deleted,
+      target,
       context,
     )
     .await?;
@@ -119,6 +130,16 @@ impl PerformCrud for RemovePost {
       orig_post.community_id,
     )
     .await?;
+    let reason_override = if data.removed {
+      check_removal_reason(
+        &data.reason,
+        context.settings().moderation.require_removal_reason,
+        data.reason_override,
+        local_user_view.person.admin,
+      )?
+    } else {
+      false
+    };
 
     // Update the post
     let post_id = data.post_id;
@@ -134,6 +155,13 @@ impl PerformCrud for RemovePost {
       post_id: data.post_id,
       removed: Some(removed),
       reason: data.reason.to_owned(),
+      reason_translations: data
+        .reason_translations
+        .clone()
+        .map(serde_json::to_value)
+        .transpose()?,
+      reason_override,
+      public: data.public_modlog,
     };
     blocking(context.pool(), move |conn| {
       ModRemovePost::create(conn, &form)
@@ -145,21 +173,37 @@ impl PerformCrud for RemovePost {
       Community::read(conn, orig_post.community_id)
     })
     .await??;
+    let target = FederationTarget::Community(community.id);
+    let object = DeletableObjects::Post(Box::new(updated_post.into()));
+    let recipient_ids = object
+      .local_notification_recipients(
+        DeletionAction::Remove,
+        local_user_view.person.id,
+        context.pool(),
+      )
+      .await?;
     send_apub_remove(
       &local_user_view.person.clone().into(),
       &community.into(),
-      DeletableObjects::Post(Box::new(updated_post.into())),
-      data.reason.clone().unwrap_or_else(|| "".to_string()),
+      object,
+      data.reason.clone(),
+      data.reason_translations.clone(),
       removed,
+      target,
       context,
     )
     .await?;
 
-    send_post_ws_message(
+    send_post_removal_ws_message(
       data.post_id,
       UserOperationCrud::RemovePost,
+      RemovalInfo {
+        mod_person_id: local_user_view.person.id,
+        reason: data.reason.to_owned(),
+      },
       websocket_id,
       Some(local_user_view.person.id),
+      recipient_ids,
       context,
     )
     .await
@@ -2,6 +2,7 @@ use crate::PerformCrud;
 use actix_web::web::Data;
 use lemmy_api_common::{
   blocking,
+  check_multi_community_owner,
   check_private_instance,
   get_local_user_view_from_jwt_opt,
   mark_post_as_read,
@@ -14,12 +15,14 @@ use lemmy_apub::{
 };
 use lemmy_db_schema::{
   from_opt_str_to_opt_enum,
+  source::{language::LocalUserLanguage, post_attachment::PostAttachment, post_tag::PostTag},
   traits::DeleteableOrRemoveable,
   ListingType,
   SortType,
 };
 use lemmy_db_views::{
   comment_view::CommentQueryBuilder,
+  poll_view::PollView,
   post_view::{PostQueryBuilder, PostView},
 };
 use lemmy_db_views_actor::{
@@ -49,6 +52,7 @@ impl PerformCrud for GetPost {
     let show_bot_accounts = local_user_view
       .as_ref()
       .map(|t| t.local_user.show_bot_accounts);
+    let show_scores = local_user_view.as_ref().map(|t| t.local_user.show_scores);
     let person_id = local_user_view.map(|u| u.person.id);
 
     let id = data.id;
@@ -69,6 +73,7 @@ impl PerformCrud for GetPost {
       CommentQueryBuilder::create(conn)
         .my_person_id(person_id)
         .show_bot_accounts(show_bot_accounts)
+        .show_scores(show_scores)
         .post_id(id)
         .limit(9999)
         .list()
@@ -106,6 +111,37 @@ impl PerformCrud for GetPost {
     })
     .await??;
 
+    let id = data.id;
+    let poll = blocking(context.pool(), move |conn| {
+      PollView::read_for_post(conn, id, person_id)
+    })
+    .await??;
+
+    let id = data.id;
+    let tags = blocking(context.pool(), move |conn| PostTag::list_for_post(conn, id)).await??;
+
+    let id = data.id;
+    let attachments = blocking(context.pool(), move |conn| {
+      PostAttachment::list_for_post(conn, id)
+    })
+    .await??;
+
+    let original_post_id = post_view.post.cross_post_of.unwrap_or(post_view.post.id);
+    let cross_posts = blocking(context.pool(), move |conn| {
+      PostQueryBuilder::create(conn)
+        .sort(SortType::New)
+        .show_bot_accounts(show_bot_accounts)
+        .show_scores(show_scores)
+        .cross_posts_of(original_post_id)
+        .my_person_id(person_id)
+        .limit(9999)
+        .list()
+    })
+    .await??
+    .into_iter()
+    .filter(|p| p.post.id != id)
+    .collect();
+
     let online = context
       .chat_server()
       .send(GetPostUsersOnline { post_id: data.id })
@@ -119,6 +155,10 @@ impl PerformCrud for GetPost {
       comments,
       moderators,
       online,
+      poll,
+      tags,
+      attachments,
+      cross_posts,
     })
   }
 }
@@ -149,6 +189,8 @@ impl PerformCrud for GetPosts {
     let show_read_posts = local_user_view
       .as_ref()
       .map(|t| t.local_user.show_read_posts);
+    let show_scores = local_user_view.as_ref().map(|t| t.local_user.show_scores);
+    let local_user_id = local_user_view.as_ref().map(|t| t.local_user.id);
 
     let sort: Option<SortType> = from_opt_str_to_opt_enum(&data.sort);
     let listing_type: Option<ListingType> = from_opt_str_to_opt_enum(&data.type_);
@@ -164,17 +206,29 @@ impl PerformCrud for GetPosts {
       None
     };
     let saved_only = data.saved_only;
+    let unread_only = data.unread_only;
+    let tag_id = data.tag_id;
+    let multi_community_id = data.multi_community_id;
+    check_multi_community_owner(multi_community_id, person_id, context.pool()).await?;
 
     let mut posts = blocking(context.pool(), move |conn| {
+      let languages = local_user_id
+        .map(|id| LocalUserLanguage::read(conn, id))
+        .transpose()?;
       PostQueryBuilder::create(conn)
         .listing_type(listing_type)
         .sort(sort)
         .show_nsfw(show_nsfw)
         .show_bot_accounts(show_bot_accounts)
         .show_read_posts(show_read_posts)
+        .show_scores(show_scores)
         .community_id(community_id)
         .community_actor_id(community_actor_id)
         .saved_only(saved_only)
+        .unread_only(unread_only)
+        .tag_id(tag_id)
+        .multi_community_id(multi_community_id)
+        .languages(languages)
         .my_person_id(person_id)
         .page(page)
         .limit(limit)
@@ -4,6 +4,7 @@ use lemmy_api_common::{
   blocking,
   check_community_ban,
   check_community_deleted_or_removed,
+  check_language_allowed,
   get_local_user_view_from_jwt,
   post::*,
 };
@@ -24,7 +25,11 @@ use lemmy_utils::{
 };
 use lemmy_websocket::{send::send_post_ws_message, LemmyContext, UserOperationCrud};
 
-use crate::PerformCrud;
+use crate::{
+  automod_rule::{apply_automod_rules, AutomodTarget},
+  post_tag::replace_post_tags,
+  PerformCrud,
+};
 
 #[async_trait::async_trait(?Send)]
 impl PerformCrud for EditPost {
@@ -60,6 +65,7 @@ impl PerformCrud for EditPost {
     )
     .await?;
     check_community_deleted_or_removed(orig_post.community_id, context.pool()).await?;
+    check_language_allowed(orig_post.community_id, data.language_id, context.pool()).await?;
 
     // Verify that only the creator can edit
     if !Post::is_post_creator(local_user_view.person.id, orig_post.creator_id) {
@@ -70,9 +76,7 @@ impl PerformCrud for EditPost {
     let data_url = data.url.as_ref();
     let (metadata_res, pictrs_thumbnail) =
       fetch_site_data(context.client(), &context.settings(), data_url).await;
-    let (embed_title, embed_description, embed_html) = metadata_res
-      .map(|u| (u.title, u.description, u.html))
-      .unwrap_or((None, None, None));
+    let metadata = metadata_res.unwrap_or_default();
 
     let post_form = PostForm {
       creator_id: orig_post.creator_id.to_owned(),
@@ -82,10 +86,13 @@ impl PerformCrud for EditPost {
       body: data.body.to_owned(),
       nsfw: data.nsfw,
       updated: Some(naive_now()),
-      embed_title,
-      embed_description,
-      embed_html,
+      embed_title: metadata.title,
+      embed_description: metadata.description,
+      embed_html: metadata.html,
+      embed_video_url: metadata.embed_video_url.map(|u| u.into()),
+      embed_provider_name: metadata.provider_name,
       thumbnail_url: pictrs_thumbnail.map(|u| u.into()),
+      language_id: data.language_id,
       ..PostForm::default()
     };
 
@@ -107,15 +114,34 @@ impl PerformCrud for EditPost {
       }
     };
 
-    // Send apub update
-    CreateOrUpdatePost::send(
-      updated_post.into(),
-      &local_user_view.person.clone().into(),
-      CreateOrUpdateType::Update,
+    if let Some(tag_ids) = &data.tag_ids {
+      replace_post_tags(
+        updated_post.id,
+        updated_post.community_id,
+        tag_ids,
+        context.pool(),
+      )
+      .await?;
+    }
+
+    let hidden_by_automod = apply_automod_rules(
+      AutomodTarget::Post(&updated_post),
+      &local_user_view.person,
       context,
     )
     .await?;
 
+    // Send apub update, pending posts aren't federated yet
+    if !updated_post.pending && !hidden_by_automod {
+      CreateOrUpdatePost::send(
+        updated_post.into(),
+        &local_user_view.person.clone().into(),
+        CreateOrUpdateType::Update,
+        context,
+      )
+      .await?;
+    }
+
     send_post_ws_message(
       data.post_id,
       UserOperationCrud::EditPost,
@@ -0,0 +1,69 @@
+use crate::PerformCrud;
+use actix_web::web::Data;
+use lemmy_api_common::{blocking, get_local_user_view_from_jwt, post::*};
+use lemmy_db_schema::{
+  naive_now,
+  source::post_draft::{PostDraft, PostDraftForm},
+  traits::Crud,
+};
+use lemmy_utils::{utils::clean_url_params, ConnectionId, LemmyError};
+use lemmy_websocket::LemmyContext;
+
+#[async_trait::async_trait(?Send)]
+impl PerformCrud for SaveDraft {
+  type Response = DraftResponse;
+
+  #[tracing::instrument(skip(context, _websocket_id))]
+  async fn perform(
+    &self,
+    context: &Data<LemmyContext>,
+    _websocket_id: Option<ConnectionId>,
+  ) -> Result<DraftResponse, LemmyError> {
+    let data: &SaveDraft = self;
+    let local_user_view =
+      get_local_user_view_from_jwt(&data.auth, context.pool(), context.secret()).await?;
+
+    // Only the creator can overwrite a draft
+    if let Some(draft_id) = data.id {
+      let orig_draft = blocking(context.pool(), move |conn| PostDraft::read(conn, draft_id))
+        .await?
+        .map_err(LemmyError::from)
+        .map_err(|e| e.with_message("couldnt_find_draft"))?;
+      if orig_draft.creator_id != local_user_view.person.id {
+        return Err(LemmyError::from_message("no_draft_edit_allowed"));
+      }
+    }
+
+    let form = PostDraftForm {
+      creator_id: local_user_view.person.id,
+      community_id: data.community_id,
+      name: data.name.to_owned(),
+      url: data
+        .url
+        .as_ref()
+        .map(|u| clean_url_params(u.to_owned()).into()),
+      body: data.body.to_owned(),
+      nsfw: data.nsfw.unwrap_or(false),
+      updated: data.id.map(|_| naive_now()),
+    };
+    let draft_id = data.id;
+    let draft = blocking(context.pool(), move |conn| match draft_id {
+      Some(draft_id) => PostDraft::update(conn, draft_id, &form),
+      None => PostDraft::create(conn, &form),
+    })
+    .await?;
+    let draft = match draft {
+      Ok(draft) => draft,
+      Err(e) => {
+        let err_type = if e.to_string() == "value too long for type character varying(200)" {
+          "post_title_too_long"
+        } else {
+          "couldnt_save_draft"
+        };
+        return Err(LemmyError::from(e).with_message(err_type));
+      }
+    };
+
+    Ok(DraftResponse { draft })
+  }
+}
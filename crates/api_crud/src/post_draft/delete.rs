@@ -0,0 +1,40 @@
+use crate::PerformCrud;
+use actix_web::web::Data;
+use lemmy_api_common::{blocking, get_local_user_view_from_jwt, post::*};
+use lemmy_db_schema::{source::post_draft::PostDraft, traits::Crud};
+use lemmy_utils::{ConnectionId, LemmyError};
+use lemmy_websocket::LemmyContext;
+
+#[async_trait::async_trait(?Send)]
+impl PerformCrud for DeleteDraft {
+  type Response = DraftResponse;
+
+  #[tracing::instrument(skip(context, _websocket_id))]
+  async fn perform(
+    &self,
+    context: &Data<LemmyContext>,
+    _websocket_id: Option<ConnectionId>,
+  ) -> Result<DraftResponse, LemmyError> {
+    let data: &DeleteDraft = self;
+    let local_user_view =
+      get_local_user_view_from_jwt(&data.auth, context.pool(), context.secret()).await?;
+
+    let draft_id = data.id;
+    let draft = blocking(context.pool(), move |conn| PostDraft::read(conn, draft_id))
+      .await?
+      .map_err(LemmyError::from)
+      .map_err(|e| e.with_message("couldnt_find_draft"))?;
+
+    // Only the creator can delete a draft
+    if draft.creator_id != local_user_view.person.id {
+      return Err(LemmyError::from_message("no_draft_edit_allowed"));
+    }
+
+    blocking(context.pool(), move |conn| {
+      PostDraft::delete(conn, draft_id)
+    })
+    .await??;
+
+    Ok(DraftResponse { draft })
+  }
+}
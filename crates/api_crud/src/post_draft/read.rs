@@ -0,0 +1,32 @@
+use crate::PerformCrud;
+use actix_web::web::Data;
+use lemmy_api_common::{blocking, get_local_user_view_from_jwt, post::*};
+use lemmy_db_schema::source::post_draft::PostDraft;
+use lemmy_utils::{ConnectionId, LemmyError};
+use lemmy_websocket::LemmyContext;
+
+#[async_trait::async_trait(?Send)]
+impl PerformCrud for ListDrafts {
+  type Response = ListDraftsResponse;
+
+  #[tracing::instrument(skip(context, _websocket_id))]
+  async fn perform(
+    &self,
+    context: &Data<LemmyContext>,
+    _websocket_id: Option<ConnectionId>,
+  ) -> Result<ListDraftsResponse, LemmyError> {
+    let data: &ListDrafts = self;
+    let local_user_view =
+      get_local_user_view_from_jwt(&data.auth, context.pool(), context.secret()).await?;
+
+    let person_id = local_user_view.person.id;
+    let page = data.page;
+    let limit = data.limit;
+    let drafts = blocking(context.pool(), move |conn| {
+      PostDraft::list_for_creator(conn, person_id, page, limit)
+    })
+    .await??;
+
+    Ok(ListDraftsResponse { drafts })
+  }
+}
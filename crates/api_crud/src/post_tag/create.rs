@@ -0,0 +1,43 @@
+use crate::{post_tag::check_post_tag_name, PerformCrud};
+use actix_web::web::Data;
+use lemmy_api_common::{
+  blocking,
+  community::{CreatePostTag, PostTagResponse},
+  get_local_user_view_from_jwt,
+  is_mod_or_admin,
+};
+use lemmy_db_schema::{
+  source::post_tag::{PostTag, PostTagForm},
+  traits::Crud,
+};
+use lemmy_utils::{ConnectionId, LemmyError};
+use lemmy_websocket::LemmyContext;
+
+#[async_trait::async_trait(?Send)]
+impl PerformCrud for CreatePostTag {
+  type Response = PostTagResponse;
+
+  #[tracing::instrument(skip(context, _websocket_id))]
+  async fn perform(
+    &self,
+    context: &Data<LemmyContext>,
+    _websocket_id: Option<ConnectionId>,
+  ) -> Result<PostTagResponse, LemmyError> {
+    let data: &CreatePostTag = self;
+    let local_user_view =
+      get_local_user_view_from_jwt(&data.auth, context.pool(), context.secret()).await?;
+    is_mod_or_admin(context.pool(), local_user_view.person.id, data.community_id).await?;
+
+    let form = PostTagForm {
+      community_id: data.community_id,
+      name: check_post_tag_name(&data.name, context)?,
+      updated: None,
+    };
+    let post_tag = blocking(context.pool(), move |conn| PostTag::create(conn, &form))
+      .await?
+      .map_err(LemmyError::from)
+      .map_err(|e| e.with_message("post_tag_already_exists"))?;
+
+    Ok(PostTagResponse { post_tag })
+  }
+}
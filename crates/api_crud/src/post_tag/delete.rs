@@ -0,0 +1,46 @@
+use crate::PerformCrud;
+use actix_web::web::Data;
+use lemmy_api_common::{
+  blocking,
+  community::{DeletePostTag, PostTagResponse},
+  get_local_user_view_from_jwt,
+  is_mod_or_admin,
+};
+use lemmy_db_schema::{source::post_tag::PostTag, traits::Crud};
+use lemmy_utils::{ConnectionId, LemmyError};
+use lemmy_websocket::LemmyContext;
+
+#[async_trait::async_trait(?Send)]
+impl PerformCrud for DeletePostTag {
+  type Response = PostTagResponse;
+
+  #[tracing::instrument(skip(context, _websocket_id))]
+  async fn perform(
+    &self,
+    context: &Data<LemmyContext>,
+    _websocket_id: Option<ConnectionId>,
+  ) -> Result<PostTagResponse, LemmyError> {
+    let data: &DeletePostTag = self;
+    let local_user_view =
+      get_local_user_view_from_jwt(&data.auth, context.pool(), context.secret()).await?;
+
+    let tag_id = data.tag_id;
+    let post_tag = blocking(context.pool(), move |conn| PostTag::read(conn, tag_id))
+      .await?
+      .map_err(LemmyError::from)
+      .map_err(|e| e.with_message("couldnt_find_post_tag"))?;
+    is_mod_or_admin(
+      context.pool(),
+      local_user_view.person.id,
+      post_tag.community_id,
+    )
+    .await?;
+
+    blocking(context.pool(), move |conn| PostTag::delete(conn, tag_id))
+      .await?
+      .map_err(LemmyError::from)
+      .map_err(|e| e.with_message("couldnt_delete_post_tag"))?;
+
+    Ok(PostTagResponse { post_tag })
+  }
+}
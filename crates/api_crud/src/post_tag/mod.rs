@@ -0,0 +1,50 @@
+use lemmy_api_common::blocking;
+use lemmy_db_schema::{
+  newtypes::{CommunityId, PostId},
+  source::post_tag::{PostTag, PostTagAssignment},
+  DbPool,
+};
+use lemmy_utils::{utils::check_slurs, LemmyError};
+use lemmy_websocket::LemmyContext;
+
+mod create;
+mod delete;
+mod read;
+mod update;
+
+const MAX_POST_TAG_LENGTH: usize = 40;
+
+fn check_post_tag_name(name: &str, context: &LemmyContext) -> Result<String, LemmyError> {
+  let name = name.trim();
+  if name.is_empty() || name.chars().count() > MAX_POST_TAG_LENGTH {
+    return Err(LemmyError::from_message("invalid_post_tag_name"));
+  }
+  check_slurs(name, &context.settings().slur_regex())?;
+  Ok(name.to_owned())
+}
+
+/// Sets the tags of a post, which all need to belong to its community
+pub(crate) async fn replace_post_tags(
+  post_id: PostId,
+  community_id: CommunityId,
+  tag_ids: &[i32],
+  pool: &DbPool,
+) -> Result<(), LemmyError> {
+  let mut tag_ids = tag_ids.to_vec();
+  tag_ids.sort_unstable();
+  tag_ids.dedup();
+  blocking(pool, move |conn| {
+    let community_tags = PostTag::list_for_community(conn, community_id)?;
+    if !tag_ids
+      .iter()
+      .all(|t| community_tags.iter().any(|c| c.id == *t))
+    {
+      return Err(LemmyError::from_message("invalid_post_tag"));
+    }
+    PostTagAssignment::replace_for_post(conn, post_id, &tag_ids)
+      .map_err(LemmyError::from)
+      .map_err(|e| e.with_message("couldnt_update_post_tags"))
+  })
+  .await??;
+  Ok(())
+}
@@ -0,0 +1,37 @@
+use crate::PerformCrud;
+use actix_web::web::Data;
+use lemmy_api_common::{
+  blocking,
+  check_private_instance,
+  community::{ListPostTags, ListPostTagsResponse},
+  get_local_user_view_from_jwt_opt,
+};
+use lemmy_db_schema::source::post_tag::PostTag;
+use lemmy_utils::{ConnectionId, LemmyError};
+use lemmy_websocket::LemmyContext;
+
+#[async_trait::async_trait(?Send)]
+impl PerformCrud for ListPostTags {
+  type Response = ListPostTagsResponse;
+
+  #[tracing::instrument(skip(context, _websocket_id))]
+  async fn perform(
+    &self,
+    context: &Data<LemmyContext>,
+    _websocket_id: Option<ConnectionId>,
+  ) -> Result<ListPostTagsResponse, LemmyError> {
+    let data: &ListPostTags = self;
+    let local_user_view =
+      get_local_user_view_from_jwt_opt(data.auth.as_ref(), context.pool(), context.secret())
+        .await?;
+    check_private_instance(&local_user_view, context.pool()).await?;
+
+    let community_id = data.community_id;
+    let post_tags = blocking(context.pool(), move |conn| {
+      PostTag::list_for_community(conn, community_id)
+    })
+    .await??;
+
+    Ok(ListPostTagsResponse { post_tags })
+  }
+}
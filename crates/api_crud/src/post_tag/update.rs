@@ -0,0 +1,57 @@
+use crate::{post_tag::check_post_tag_name, PerformCrud};
+use actix_web::web::Data;
+use lemmy_api_common::{
+  blocking,
+  community::{EditPostTag, PostTagResponse},
+  get_local_user_view_from_jwt,
+  is_mod_or_admin,
+};
+use lemmy_db_schema::{
+  naive_now,
+  source::post_tag::{PostTag, PostTagForm},
+  traits::Crud,
+};
+use lemmy_utils::{ConnectionId, LemmyError};
+use lemmy_websocket::LemmyContext;
+
+#[async_trait::async_trait(?Send)]
+impl PerformCrud for EditPostTag {
+  type Response = PostTagResponse;
+
+  #[tracing::instrument(skip(context, _websocket_id))]
+  async fn perform(
+    &self,
+    context: &Data<LemmyContext>,
+    _websocket_id: Option<ConnectionId>,
+  ) -> Result<PostTagResponse, LemmyError> {
+    let data: &EditPostTag = self;
+    let local_user_view =
+      get_local_user_view_from_jwt(&data.auth, context.pool(), context.secret()).await?;
+
+    let tag_id = data.tag_id;
+    let orig_tag = blocking(context.pool(), move |conn| PostTag::read(conn, tag_id))
+      .await?
+      .map_err(LemmyError::from)
+      .map_err(|e| e.with_message("couldnt_find_post_tag"))?;
+    is_mod_or_admin(
+      context.pool(),
+      local_user_view.person.id,
+      orig_tag.community_id,
+    )
+    .await?;
+
+    let form = PostTagForm {
+      community_id: orig_tag.community_id,
+      name: check_post_tag_name(&data.name, context)?,
+      updated: Some(naive_now()),
+    };
+    let post_tag = blocking(context.pool(), move |conn| {
+      PostTag::update(conn, tag_id, &form)
+    })
+    .await?
+    .map_err(LemmyError::from)
+    .map_err(|e| e.with_message("post_tag_already_exists"))?;
+
+    Ok(PostTagResponse { post_tag })
+  }
+}
@@ -20,8 +20,16 @@ use lemmy_db_schema::{
   traits::Crud,
 };
 use lemmy_db_views::local_user_view::LocalUserView;
-use lemmy_utils::{utils::remove_slurs, ConnectionId, LemmyError};
-use lemmy_websocket::{send::send_pm_ws_message, LemmyContext, UserOperationCrud};
+use lemmy_utils::{
+  utils::{check_blocked_words, remove_slurs},
+  ConnectionId,
+  LemmyError,
+};
+use lemmy_websocket::{
+  send::{send_pm_ws_message, send_push_to_user},
+  LemmyContext,
+  UserOperationCrud,
+};
 
 #[async_trait::async_trait(?Send)]
 impl PerformCrud for CreatePrivateMessage {
@@ -37,6 +45,7 @@ impl PerformCrud for CreatePrivateMessage {
     let local_user_view =
       get_local_user_view_from_jwt(&data.auth, context.pool(), context.secret()).await?;
 
+    check_blocked_words(&data.content)?;
     let content_slurs_removed =
       remove_slurs(&data.content.to_owned(), &context.settings().slur_regex());
 
@@ -111,6 +120,12 @@ impl PerformCrud for CreatePrivateMessage {
         &content_slurs_removed,
         &context.settings(),
       );
+      send_push_to_user(
+        &local_recipient,
+        format!("Message from {}", res.private_message_view.creator.name),
+        &content_slurs_removed,
+        context,
+      );
     }
 
     Ok(res)
@@ -3,10 +3,19 @@ use actix_web::web::Data;
 use lemmy_api_common::{
   blocking,
   get_local_user_view_from_jwt,
-  person::{GetPrivateMessages, PrivateMessagesResponse},
+  person::{
+    GetPrivateMessageThread,
+    GetPrivateMessageThreadResponse,
+    GetPrivateMessages,
+    PrivateMessageConversation,
+    PrivateMessagesResponse,
+  },
+};
+use lemmy_db_schema::{newtypes::PersonId, traits::DeleteableOrRemoveable};
+use lemmy_db_views::{
+  private_message_thread_view::PrivateMessageThreadView,
+  private_message_view::{PrivateMessageQueryBuilder, PrivateMessageView},
 };
-use lemmy_db_schema::traits::DeleteableOrRemoveable;
-use lemmy_db_views::private_message_view::PrivateMessageQueryBuilder;
 use lemmy_utils::{ConnectionId, LemmyError};
 use lemmy_websocket::LemmyContext;
 
@@ -37,19 +46,98 @@ impl PerformCrud for GetPrivateMessages {
     })
     .await??;
 
-    // Blank out deleted or removed info
-    for pmv in messages
-      .iter_mut()
-      .filter(|pmv| pmv.private_message.deleted)
-    {
-      pmv.private_message = pmv
-        .to_owned()
-        .private_message
-        .blank_out_deleted_or_removed_info();
-    }
+    blank_out_deleted(&mut messages);
 
     Ok(PrivateMessagesResponse {
       private_messages: messages,
     })
   }
 }
+
+/// Number of messages which are returned for each conversation, when listing all of them
+const CONVERSATION_PREVIEW_MESSAGES: i64 = 5;
+
+#[async_trait::async_trait(?Send)]
+impl PerformCrud for GetPrivateMessageThread {
+  type Response = GetPrivateMessageThreadResponse;
+
+  #[tracing::instrument(skip(self, context, _websocket_id))]
+  async fn perform(
+    &self,
+    context: &Data<LemmyContext>,
+    _websocket_id: Option<ConnectionId>,
+  ) -> Result<GetPrivateMessageThreadResponse, LemmyError> {
+    let data: &GetPrivateMessageThread = self;
+    let local_user_view =
+      get_local_user_view_from_jwt(data.auth.as_ref(), context.pool(), context.secret()).await?;
+    let person_id = local_user_view.person.id;
+
+    let page = data.page;
+    let limit = data.limit;
+    let conversations = if let Some(thread_id) = data.thread_id {
+      let thread_view = blocking(context.pool(), move |conn| {
+        PrivateMessageThreadView::read(conn, thread_id, person_id)
+      })
+      .await?
+      .map_err(LemmyError::from)
+      .map_err(|e| e.with_message("couldnt_find_private_message_thread"))?;
+      vec![read_conversation(thread_view, person_id, page, limit, context).await?]
+    } else {
+      let thread_views = blocking(context.pool(), move |conn| {
+        PrivateMessageThreadView::list_for_person(conn, person_id, page, limit)
+      })
+      .await??;
+      let mut conversations = vec![];
+      for thread_view in thread_views {
+        conversations.push(
+          read_conversation(
+            thread_view,
+            person_id,
+            None,
+            Some(CONVERSATION_PREVIEW_MESSAGES),
+            context,
+          )
+          .await?,
+        );
+      }
+      conversations
+    };
+
+    Ok(GetPrivateMessageThreadResponse { conversations })
+  }
+}
+
+async fn read_conversation(
+  thread_view: PrivateMessageThreadView,
+  person_id: PersonId,
+  page: Option<i64>,
+  limit: Option<i64>,
+  context: &LemmyContext,
+) -> Result<PrivateMessageConversation, LemmyError> {
+  let thread_id = thread_view.thread.id;
+  let mut messages = blocking(context.pool(), move |conn| {
+    PrivateMessageQueryBuilder::create(conn, person_id)
+      .thread_id(thread_id)
+      .page(page)
+      .limit(limit)
+      .list()
+  })
+  .await??;
+  blank_out_deleted(&mut messages);
+  Ok(PrivateMessageConversation {
+    thread_view,
+    messages,
+  })
+}
+
+fn blank_out_deleted(messages: &mut [PrivateMessageView]) {
+  for pmv in messages
+    .iter_mut()
+    .filter(|pmv| pmv.private_message.deleted)
+  {
+    pmv.private_message = pmv
+      .to_owned()
+      .private_message
+      .blank_out_deleted_or_removed_info();
+  }
+}
@@ -10,7 +10,11 @@ use lemmy_apub::protocol::activities::{
   CreateOrUpdateType,
 };
 use lemmy_db_schema::{source::private_message::PrivateMessage, traits::Crud};
-use lemmy_utils::{utils::remove_slurs, ConnectionId, LemmyError};
+use lemmy_utils::{
+  utils::{check_blocked_words, remove_slurs},
+  ConnectionId,
+  LemmyError,
+};
 use lemmy_websocket::{send::send_pm_ws_message, LemmyContext, UserOperationCrud};
 
 #[async_trait::async_trait(?Send)]
@@ -38,6 +42,7 @@ impl PerformCrud for EditPrivateMessage {
     }
 
     // Doing the update
+    check_blocked_words(&data.content)?;
     let content_slurs_removed = remove_slurs(&data.content, &context.settings().slur_regex());
     let private_message_id = data.private_message_id;
     let updated_private_message = blocking(context.pool(), move |conn| {
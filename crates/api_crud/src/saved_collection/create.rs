@@ -0,0 +1,41 @@
+use crate::{
+  saved_collection::{check_saved_collection_name, saved_collection_error},
+  PerformCrud,
+};
+use actix_web::web::Data;
+use lemmy_api_common::{blocking, get_local_user_view_from_jwt, person::*};
+use lemmy_db_schema::{
+  source::saved_collection::{SavedCollection, SavedCollectionForm},
+  traits::Crud,
+};
+use lemmy_utils::{ConnectionId, LemmyError};
+use lemmy_websocket::LemmyContext;
+
+#[async_trait::async_trait(?Send)]
+impl PerformCrud for CreateSavedCollection {
+  type Response = SavedCollectionResponse;
+
+  #[tracing::instrument(skip(context, _websocket_id))]
+  async fn perform(
+    &self,
+    context: &Data<LemmyContext>,
+    _websocket_id: Option<ConnectionId>,
+  ) -> Result<SavedCollectionResponse, LemmyError> {
+    let data: &CreateSavedCollection = self;
+    let local_user_view =
+      get_local_user_view_from_jwt(&data.auth, context.pool(), context.secret()).await?;
+
+    let form = SavedCollectionForm {
+      person_id: local_user_view.person.id,
+      name: check_saved_collection_name(&data.name)?,
+      updated: None,
+    };
+    let collection = blocking(context.pool(), move |conn| {
+      SavedCollection::create(conn, &form)
+    })
+    .await?
+    .map_err(saved_collection_error)?;
+
+    Ok(SavedCollectionResponse { collection })
+  }
+}
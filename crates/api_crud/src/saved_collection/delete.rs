@@ -0,0 +1,48 @@
+use crate::PerformCrud;
+use actix_web::web::Data;
+use lemmy_api_common::{
+  blocking,
+  check_saved_collection_owner,
+  get_local_user_view_from_jwt,
+  person::*,
+};
+use lemmy_db_schema::{source::saved_collection::SavedCollection, traits::Crud};
+use lemmy_utils::{ConnectionId, LemmyError};
+use lemmy_websocket::LemmyContext;
+
+#[async_trait::async_trait(?Send)]
+impl PerformCrud for DeleteSavedCollection {
+  type Response = SavedCollectionResponse;
+
+  #[tracing::instrument(skip(context, _websocket_id))]
+  async fn perform(
+    &self,
+    context: &Data<LemmyContext>,
+    _websocket_id: Option<ConnectionId>,
+  ) -> Result<SavedCollectionResponse, LemmyError> {
+    let data: &DeleteSavedCollection = self;
+    let local_user_view =
+      get_local_user_view_from_jwt(&data.auth, context.pool(), context.secret()).await?;
+
+    let collection_id = data.collection_id;
+    check_saved_collection_owner(
+      Some(collection_id),
+      local_user_view.person.id,
+      context.pool(),
+    )
+    .await?;
+
+    let collection = blocking(context.pool(), move |conn| {
+      SavedCollection::read(conn, collection_id)
+    })
+    .await??;
+    blocking(context.pool(), move |conn| {
+      SavedCollection::delete(conn, collection_id)
+    })
+    .await?
+    .map_err(LemmyError::from)
+    .map_err(|e| e.with_message("couldnt_delete_saved_collection"))?;
+
+    Ok(SavedCollectionResponse { collection })
+  }
+}
@@ -0,0 +1,29 @@
+use lemmy_utils::LemmyError;
+
+mod create;
+mod delete;
+mod read;
+mod update;
+
+const MAX_SAVED_COLLECTION_NAME_LENGTH: usize = 100;
+
+fn check_saved_collection_name(name: &str) -> Result<String, LemmyError> {
+  let name = name.trim();
+  if name.is_empty() || name.chars().count() > MAX_SAVED_COLLECTION_NAME_LENGTH {
+    return Err(LemmyError::from_message("invalid_saved_collection_name"));
+  }
+  Ok(name.to_owned())
+}
+
+/// Maps the unique violation on (person_id, name) to a readable error
+fn saved_collection_error(e: diesel::result::Error) -> LemmyError {
+  let err_type = if e
+    .to_string()
+    .contains("saved_collection_person_id_name_key")
+  {
+    "saved_collection_already_exists"
+  } else {
+    "couldnt_save_saved_collection"
+  };
+  LemmyError::from(e).with_message(err_type)
+}
@@ -0,0 +1,30 @@
+use crate::PerformCrud;
+use actix_web::web::Data;
+use lemmy_api_common::{blocking, get_local_user_view_from_jwt, person::*};
+use lemmy_db_schema::source::saved_collection::SavedCollection;
+use lemmy_utils::{ConnectionId, LemmyError};
+use lemmy_websocket::LemmyContext;
+
+#[async_trait::async_trait(?Send)]
+impl PerformCrud for ListSavedCollections {
+  type Response = ListSavedCollectionsResponse;
+
+  #[tracing::instrument(skip(context, _websocket_id))]
+  async fn perform(
+    &self,
+    context: &Data<LemmyContext>,
+    _websocket_id: Option<ConnectionId>,
+  ) -> Result<ListSavedCollectionsResponse, LemmyError> {
+    let data: &ListSavedCollections = self;
+    let local_user_view =
+      get_local_user_view_from_jwt(&data.auth, context.pool(), context.secret()).await?;
+
+    let person_id = local_user_view.person.id;
+    let collections = blocking(context.pool(), move |conn| {
+      SavedCollection::list_for_person(conn, person_id)
+    })
+    .await??;
+
+    Ok(ListSavedCollectionsResponse { collections })
+  }
+}
@@ -0,0 +1,51 @@
+use crate::{
+  saved_collection::{check_saved_collection_name, saved_collection_error},
+  PerformCrud,
+};
+use actix_web::web::Data;
+use lemmy_api_common::{
+  blocking,
+  check_saved_collection_owner,
+  get_local_user_view_from_jwt,
+  person::*,
+};
+use lemmy_db_schema::{
+  naive_now,
+  source::saved_collection::{SavedCollection, SavedCollectionForm},
+  traits::Crud,
+};
+use lemmy_utils::{ConnectionId, LemmyError};
+use lemmy_websocket::LemmyContext;
+
+#[async_trait::async_trait(?Send)]
+impl PerformCrud for EditSavedCollection {
+  type Response = SavedCollectionResponse;
+
+  #[tracing::instrument(skip(context, _websocket_id))]
+  async fn perform(
+    &self,
+    context: &Data<LemmyContext>,
+    _websocket_id: Option<ConnectionId>,
+  ) -> Result<SavedCollectionResponse, LemmyError> {
+    let data: &EditSavedCollection = self;
+    let local_user_view =
+      get_local_user_view_from_jwt(&data.auth, context.pool(), context.secret()).await?;
+
+    let person_id = local_user_view.person.id;
+    let collection_id = data.collection_id;
+    check_saved_collection_owner(Some(collection_id), person_id, context.pool()).await?;
+
+    let form = SavedCollectionForm {
+      person_id,
+      name: check_saved_collection_name(&data.name)?,
+      updated: Some(naive_now()),
+    };
+    let collection = blocking(context.pool(), move |conn| {
+      SavedCollection::update(conn, collection_id, &form)
+    })
+    .await?
+    .map_err(saved_collection_error)?;
+
+    Ok(SavedCollectionResponse { collection })
+  }
+}
@@ -0,0 +1,88 @@
+use crate::PerformCrud;
+use actix_web::web::Data;
+use lemmy_api_common::{
+  blocking,
+  check_community_ban,
+  check_community_deleted_or_removed,
+  check_language_allowed,
+  get_local_user_view_from_jwt,
+  honeypot_check,
+  post::*,
+};
+use lemmy_db_schema::{
+  clock::{Clock, SystemClock},
+  source::scheduled_post::{ScheduledPost, ScheduledPostForm},
+  traits::Crud,
+};
+use lemmy_utils::{
+  utils::{check_slurs, check_slurs_opt, clean_url_params, is_valid_post_title, naive_from_unix},
+  ConnectionId,
+  LemmyError,
+};
+use lemmy_websocket::LemmyContext;
+
+#[async_trait::async_trait(?Send)]
+impl PerformCrud for SchedulePost {
+  type Response = ScheduledPostResponse;
+
+  #[tracing::instrument(skip(context, _websocket_id))]
+  async fn perform(
+    &self,
+    context: &Data<LemmyContext>,
+    _websocket_id: Option<ConnectionId>,
+  ) -> Result<ScheduledPostResponse, LemmyError> {
+    let data: &SchedulePost = self;
+    let local_user_view =
+      get_local_user_view_from_jwt(&data.auth, context.pool(), context.secret()).await?;
+
+    let slur_regex = &context.settings().slur_regex();
+    check_slurs(&data.name, slur_regex)?;
+    check_slurs_opt(&data.body, slur_regex)?;
+    honeypot_check(&data.honeypot)?;
+
+    if !is_valid_post_title(&data.name) {
+      return Err(LemmyError::from_message("invalid_post_title"));
+    }
+
+    let publish_at = naive_from_unix(data.publish_at);
+    if publish_at <= SystemClock.now() {
+      return Err(LemmyError::from_message("invalid_publish_time"));
+    }
+
+    // Checked again when the post is published
+    check_community_ban(local_user_view.person.id, data.community_id, context.pool()).await?;
+    check_community_deleted_or_removed(data.community_id, context.pool()).await?;
+    check_language_allowed(data.community_id, data.language_id, context.pool()).await?;
+
+    let form = ScheduledPostForm {
+      creator_id: local_user_view.person.id,
+      community_id: data.community_id,
+      name: data.name.trim().to_owned(),
+      url: data
+        .url
+        .as_ref()
+        .map(|u| clean_url_params(u.to_owned()).into()),
+      body: data.body.to_owned(),
+      nsfw: data.nsfw,
+      publish_at,
+      language_id: data.language_id,
+    };
+    let scheduled_post = blocking(context.pool(), move |conn| {
+      ScheduledPost::create(conn, &form)
+    })
+    .await?;
+    let scheduled_post = match scheduled_post {
+      Ok(scheduled_post) => scheduled_post,
+      Err(e) => {
+        let err_type = if e.to_string() == "value too long for type character varying(200)" {
+          "post_title_too_long"
+        } else {
+          "couldnt_schedule_post"
+        };
+        return Err(LemmyError::from(e).with_message(err_type));
+      }
+    };
+
+    Ok(ScheduledPostResponse { scheduled_post })
+  }
+}
@@ -0,0 +1,44 @@
+use crate::PerformCrud;
+use actix_web::web::Data;
+use lemmy_api_common::{blocking, get_local_user_view_from_jwt, post::*};
+use lemmy_db_schema::{source::scheduled_post::ScheduledPost, traits::Crud};
+use lemmy_utils::{ConnectionId, LemmyError};
+use lemmy_websocket::LemmyContext;
+
+#[async_trait::async_trait(?Send)]
+impl PerformCrud for DeleteScheduledPost {
+  type Response = ScheduledPostResponse;
+
+  #[tracing::instrument(skip(context, _websocket_id))]
+  async fn perform(
+    &self,
+    context: &Data<LemmyContext>,
+    _websocket_id: Option<ConnectionId>,
+  ) -> Result<ScheduledPostResponse, LemmyError> {
+    let data: &DeleteScheduledPost = self;
+    let local_user_view =
+      get_local_user_view_from_jwt(&data.auth, context.pool(), context.secret()).await?;
+
+    let scheduled_post_id = data.id;
+    let scheduled_post = blocking(context.pool(), move |conn| {
+      ScheduledPost::read(conn, scheduled_post_id)
+    })
+    .await?
+    .map_err(LemmyError::from)
+    .map_err(|e| e.with_message("couldnt_find_scheduled_post"))?;
+
+    // Only the creator can cancel a scheduled post
+    if scheduled_post.creator_id != local_user_view.person.id {
+      return Err(LemmyError::from_message("no_post_edit_allowed"));
+    }
+
+    // It may have been published in the meantime
+    blocking(context.pool(), move |conn| {
+      ScheduledPost::claim(conn, scheduled_post_id)
+    })
+    .await??
+    .ok_or_else(|| LemmyError::from_message("couldnt_find_scheduled_post"))?;
+
+    Ok(ScheduledPostResponse { scheduled_post })
+  }
+}
@@ -7,6 +7,10 @@ use lemmy_api_common::{
   person::Register,
   site::*,
 };
+use lemmy_db_schema::source::{
+  language::{Language, LocalUserLanguage},
+  person_instance_block::PersonInstanceBlock,
+};
 use lemmy_db_views::site_view::SiteView;
 use lemmy_db_views_actor::{
   community_block_view::CommunityBlockView,
@@ -15,7 +19,7 @@ use lemmy_db_views_actor::{
   person_block_view::PersonBlockView,
   person_view::PersonViewSafe,
 };
-use lemmy_utils::{version, ConnectionId, LemmyError};
+use lemmy_utils::{version, web_push::vapid_public_key, ConnectionId, LemmyError};
 use lemmy_websocket::{messages::GetUsersOnline, LemmyContext};
 use tracing::info;
 
@@ -132,6 +136,13 @@ impl PerformCrud for GetSite {
       .map_err(LemmyError::from)
       .map_err(|e| e.with_message("system_err_login"))?;
 
+      let instance_blocks = blocking(context.pool(), move |conn| {
+        PersonInstanceBlock::for_person(conn, person_id)
+      })
+      .await?
+      .map_err(LemmyError::from)
+      .map_err(|e| e.with_message("system_err_login"))?;
+
       let moderates = blocking(context.pool(), move |conn| {
         CommunityModeratorView::for_person(conn, person_id)
       })
@@ -139,12 +150,22 @@ impl PerformCrud for GetSite {
       .map_err(LemmyError::from)
       .map_err(|e| e.with_message("system_err_login"))?;
 
+      let local_user_id = local_user_view.local_user.id;
+      let discussion_languages = blocking(context.pool(), move |conn| {
+        LocalUserLanguage::read(conn, local_user_id)
+      })
+      .await?
+      .map_err(LemmyError::from)
+      .map_err(|e| e.with_message("system_err_login"))?;
+
       Some(MyUserInfo {
         local_user_view,
         follows,
         moderates,
         community_blocks,
         person_blocks,
+        instance_blocks,
+        discussion_languages,
       })
     } else {
       None
@@ -156,6 +177,11 @@ impl PerformCrud for GetSite {
       &context.settings().hostname,
     )
     .await?;
+    let vapid_public_key = context
+      .settings()
+      .web_push
+      .and_then(|w| vapid_public_key(&w.vapid_private_key).ok());
+    let all_languages = blocking(context.pool(), Language::read_all).await??;
 
     Ok(GetSiteResponse {
       site_view,
@@ -165,6 +191,8 @@ impl PerformCrud for GetSite {
       version: version::VERSION.to_string(),
       my_user,
       federated_instances,
+      vapid_public_key,
+      all_languages,
     })
   }
 }
@@ -2,11 +2,13 @@ use crate::PerformCrud;
 use actix_web::web::Data;
 use lemmy_api_common::{
   blocking,
+  captcha::check_captcha_provider,
   get_local_user_view_from_jwt,
   is_admin,
   site::{EditSite, SiteResponse},
   site_description_length_check,
 };
+use lemmy_apub::protocol::activities::instance::update::UpdateInstance;
 use lemmy_db_schema::{
   diesel_option_overwrite,
   diesel_option_overwrite_to_url,
@@ -48,11 +50,25 @@ impl PerformCrud for EditSite {
     let application_question = diesel_option_overwrite(&data.application_question);
     let icon = diesel_option_overwrite_to_url(&data.icon)?;
     let banner = diesel_option_overwrite_to_url(&data.banner)?;
+    let captcha_site_key = diesel_option_overwrite(&data.captcha_site_key);
 
     if let Some(Some(desc)) = &description {
       site_description_length_check(desc)?;
     }
 
+    let captcha_provider = data
+      .captcha_provider
+      .to_owned()
+      .unwrap_or_else(|| found_site.captcha_provider.to_owned());
+    let new_captcha_site_key = captcha_site_key
+      .to_owned()
+      .unwrap_or_else(|| found_site.captcha_site_key.to_owned());
+    check_captcha_provider(
+      &captcha_provider,
+      &new_captcha_site_key,
+      &context.settings(),
+    )?;
+
     let site_form = SiteForm {
       creator_id: found_site.creator_id,
       name: data.name.to_owned().unwrap_or(found_site.name),
@@ -69,6 +85,8 @@ impl PerformCrud for EditSite {
       require_application: data.require_application,
       application_question,
       private_instance: data.private_instance,
+      captcha_provider: data.captcha_provider.to_owned(),
+      captcha_site_key,
     };
 
     let update_site = blocking(context.pool(), move |conn| {
@@ -101,6 +119,8 @@ impl PerformCrud for EditSite {
       .map_err(|e| e.with_message("couldnt_set_all_email_verified"))?;
     }
 
+    UpdateInstance::send(context).await?;
+
     let site_view = blocking(context.pool(), SiteView::read).await??;
 
     let res = SiteResponse { site_view };
@@ -1,7 +1,10 @@
 use crate::PerformCrud;
 use actix_web::web::Data;
+use diesel::{Connection, PgConnection};
 use lemmy_api_common::{
   blocking,
+  captcha::captcha_provider,
+  create_login_session,
   honeypot_check,
   password_length_check,
   person::*,
@@ -35,12 +38,11 @@ use lemmy_db_schema::{
 use lemmy_db_views_actor::person_view::PersonViewSafe;
 use lemmy_utils::{
   apub::generate_actor_keypair,
-  claims::Claims,
   utils::{check_slurs, is_valid_actor_name},
   ConnectionId,
   LemmyError,
 };
-use lemmy_websocket::{messages::CheckCaptcha, LemmyContext};
+use lemmy_websocket::LemmyContext;
 
 #[async_trait::async_trait(?Send)]
 impl PerformCrud for Register {
@@ -58,7 +60,8 @@ impl PerformCrud for Register {
     let (mut email_verification, mut require_application) = (false, false);
 
     // Make sure site has open registration
-    if let Ok(site) = blocking(context.pool(), Site::read_simple).await? {
+    let site = blocking(context.pool(), Site::read_simple).await?.ok();
+    if let Some(site) = &site {
       if !site.open_registration {
         return Err(LemmyError::from_message("registration_closed"));
       }
@@ -91,171 +94,49 @@ impl PerformCrud for Register {
     .await??;
 
     // If its not the admin, check the captcha
-    if !no_admins && context.settings().captcha.enabled {
-      let check = context
-        .chat_server()
-        .send(CheckCaptcha {
-          uuid: data
-            .captcha_uuid
-            .to_owned()
-            .unwrap_or_else(|| "".to_string()),
-          answer: data
-            .captcha_answer
-            .to_owned()
-            .unwrap_or_else(|| "".to_string()),
-        })
-        .await?;
-      if !check {
-        return Err(LemmyError::from_message("captcha_incorrect"));
+    if let (false, Some(site)) = (no_admins, &site) {
+      if let Some(provider) = captcha_provider(site, &context.settings(), context.client())? {
+        let check = provider
+          .verify(
+            data.captcha_uuid.to_owned(),
+            data.captcha_answer.to_owned().unwrap_or_default(),
+            context.pool(),
+          )
+          .await?;
+        if !check {
+          return Err(LemmyError::from_message("captcha_incorrect"));
+        }
       }
     }
 
-    check_slurs(&data.username, &context.settings().slur_regex())?;
-
-    let actor_keypair = generate_actor_keypair()?;
-    if !is_valid_actor_name(&data.username, context.settings().actor_name_max_length) {
-      return Err(LemmyError::from_message("invalid_username"));
-    }
-    let actor_id = generate_local_apub_endpoint(
-      EndpointType::Person,
-      &data.username,
-      &context.settings().get_protocol_and_hostname(),
-    )?;
-
-    // We have to create both a person, and local_user
-
-    // Register the new person
-    let person_form = PersonForm {
-      name: data.username.to_owned(),
-      actor_id: Some(actor_id.clone()),
-      private_key: Some(Some(actor_keypair.private_key)),
-      public_key: actor_keypair.public_key,
-      inbox_url: Some(generate_inbox_url(&actor_id)?),
-      shared_inbox_url: Some(Some(generate_shared_inbox_url(&actor_id)?)),
-      admin: Some(no_admins),
-      ..PersonForm::default()
-    };
-
-    // insert the person
-    let inserted_person = blocking(context.pool(), move |conn| {
-      Person::create(conn, &person_form)
-    })
-    .await?
-    .map_err(LemmyError::from)
-    .map_err(|e| e.with_message("user_already_exists"))?;
-
-    // Create the local user
     let local_user_form = LocalUserForm {
-      person_id: Some(inserted_person.id),
       email: Some(data.email.as_deref().map(|s| s.to_owned())),
       password_encrypted: Some(data.password.to_string()),
       show_nsfw: Some(data.show_nsfw),
       email_verified: Some(false),
       ..LocalUserForm::default()
     };
-
-    let inserted_local_user = match blocking(context.pool(), move |conn| {
-      LocalUser::register(conn, &local_user_form)
-    })
-    .await?
-    {
-      Ok(lu) => lu,
-      Err(e) => {
-        let err_type = if e.to_string()
-          == "duplicate key value violates unique constraint \"local_user_email_key\""
-        {
-          "email_already_exists"
-        } else {
-          "user_already_exists"
-        };
-
-        // If the local user creation errored, then delete that person
-        blocking(context.pool(), move |conn| {
-          Person::delete(conn, inserted_person.id)
-        })
-        .await??;
-
-        return Err(LemmyError::from(e).with_message(err_type));
-      }
-    };
-
-    if require_application {
-      // Create the registration application
-      let form = RegistrationApplicationForm {
-        local_user_id: Some(inserted_local_user.id),
-        // We already made sure answer was not null above
-        answer: data.answer.to_owned(),
-        ..RegistrationApplicationForm::default()
-      };
-
-      blocking(context.pool(), move |conn| {
-        RegistrationApplication::create(conn, &form)
-      })
-      .await??;
-    }
-
-    let main_community_keypair = generate_actor_keypair()?;
-
-    // Create the main community if it doesn't exist
-    let protocol_and_hostname = context.settings().get_protocol_and_hostname();
-    let main_community = match blocking(context.pool(), move |conn| {
-      Community::read(conn, CommunityId(2))
-    })
-    .await?
-    {
-      Ok(c) => c,
-      Err(_e) => {
-        let default_community_name = "main";
-        let actor_id = generate_local_apub_endpoint(
-          EndpointType::Community,
-          default_community_name,
-          &protocol_and_hostname,
-        )?;
-        let community_form = CommunityForm {
-          name: default_community_name.to_string(),
-          title: "The Default Community".to_string(),
-          description: Some("The Default Community".to_string()),
-          actor_id: Some(actor_id.to_owned()),
-          private_key: Some(Some(main_community_keypair.private_key)),
-          public_key: main_community_keypair.public_key,
-          followers_url: Some(generate_followers_url(&actor_id)?),
-          inbox_url: Some(generate_inbox_url(&actor_id)?),
-          shared_inbox_url: Some(Some(generate_shared_inbox_url(&actor_id)?)),
-          ..CommunityForm::default()
-        };
-        blocking(context.pool(), move |conn| {
-          Community::create(conn, &community_form)
-        })
-        .await??
-      }
-    };
-
-    // Sign them up for main community no matter what
-    let community_follower_form = CommunityFollowerForm {
-      community_id: main_community.id,
-      person_id: inserted_person.id,
-      pending: false,
-    };
-
-    let follow = move |conn: &'_ _| CommunityFollower::follow(conn, &community_follower_form);
-    blocking(context.pool(), follow)
-      .await?
-      .map_err(LemmyError::from)
-      .map_err(|e| e.with_message("community_follower_already_exists"))?;
-
-    // If its an admin, add them as a mod and follower to main
-    if no_admins {
-      let community_moderator_form = CommunityModeratorForm {
-        community_id: main_community.id,
-        person_id: inserted_person.id,
-      };
-
-      let join = move |conn: &'_ _| CommunityModerator::join(conn, &community_moderator_form);
-      blocking(context.pool(), join)
-        .await?
-        .map_err(LemmyError::from)
-        .map_err(|e| e.with_message("community_moderator_already_exists"))?;
-    }
+    // Create the registration application together with the account
+    let answer = data.answer.to_owned();
+    let (inserted_person, inserted_local_user) = create_local_user(
+      &data.username,
+      local_user_form,
+      no_admins,
+      context,
+      move |conn, local_user| {
+        if require_application {
+          let form = RegistrationApplicationForm {
+            local_user_id: Some(local_user.id),
+            // We already made sure answer was not null above
+            answer,
+            ..RegistrationApplicationForm::default()
+          };
+          RegistrationApplication::create(conn, &form)?;
+        }
+        Ok(())
+      },
+    )
+    .await?;
 
     let mut login_response = LoginResponse {
       jwt: None,
@@ -266,15 +147,17 @@ impl PerformCrud for Register {
     // Log the user in directly if email verification and application aren't required
     if !require_application && !email_verification {
       login_response.jwt = Some(
-        Claims::jwt(
-          inserted_local_user.id.0,
-          &context.secret().jwt_secret,
-          &context.settings().hostname,
-        )?
-        .into(),
+        create_login_session(
+          inserted_local_user.id,
+          context.pool(),
+          context.secret(),
+          &context.settings(),
+        )
+        .await?,
       );
     } else {
-      if email_verification {
+      // If an application is required, the verification email is sent once it was approved
+      if email_verification && !require_application {
         send_verification_email(
           inserted_local_user.id,
           // we check at the beginning of this method that email is set
@@ -295,3 +178,120 @@ impl PerformCrud for Register {
     Ok(login_response)
   }
 }
+
+/// Creates the person and local user of a new account, and makes it follow the main community.
+/// Admins also become moderators of the main community. `then` runs in the same transaction, so
+/// that nothing is left of the account if it fails.
+pub(crate) async fn create_local_user<F>(
+  username: &str,
+  local_user_form: LocalUserForm,
+  admin: bool,
+  context: &LemmyContext,
+  then: F,
+) -> Result<(Person, LocalUser), LemmyError>
+where
+  F: FnOnce(&PgConnection, &LocalUser) -> Result<(), LemmyError> + Send + 'static,
+{
+  check_slurs(username, &context.settings().slur_regex())?;
+
+  let actor_keypair = generate_actor_keypair()?;
+  if !is_valid_actor_name(username, context.settings().actor_name_max_length) {
+    return Err(LemmyError::from_message("invalid_username"));
+  }
+  let actor_id = generate_local_apub_endpoint(
+    EndpointType::Person,
+    username,
+    &context.settings().get_protocol_and_hostname(),
+  )?;
+
+  // We have to create both a person, and local_user
+
+  // Register the new person
+  let person_form = PersonForm {
+    name: username.to_owned(),
+    actor_id: Some(actor_id.clone()),
+    private_key: Some(Some(actor_keypair.private_key)),
+    public_key: actor_keypair.public_key,
+    inbox_url: Some(generate_inbox_url(&actor_id)?),
+    shared_inbox_url: Some(Some(generate_shared_inbox_url(&actor_id)?)),
+    admin: Some(admin),
+    ..PersonForm::default()
+  };
+
+  // In case the main community doesn't exist yet
+  let main_community_keypair = generate_actor_keypair()?;
+  let default_community_name = "main";
+  let actor_id = generate_local_apub_endpoint(
+    EndpointType::Community,
+    default_community_name,
+    &context.settings().get_protocol_and_hostname(),
+  )?;
+  let community_form = CommunityForm {
+    name: default_community_name.to_string(),
+    title: "The Default Community".to_string(),
+    description: Some("The Default Community".to_string()),
+    actor_id: Some(actor_id.to_owned()),
+    private_key: Some(Some(main_community_keypair.private_key)),
+    public_key: main_community_keypair.public_key,
+    followers_url: Some(generate_followers_url(&actor_id)?),
+    inbox_url: Some(generate_inbox_url(&actor_id)?),
+    shared_inbox_url: Some(Some(generate_shared_inbox_url(&actor_id)?)),
+    ..CommunityForm::default()
+  };
+
+  blocking(context.pool(), move |conn| {
+    conn.transaction::<_, LemmyError, _>(|| {
+      // insert the person
+      let inserted_person = Person::create(conn, &person_form)
+        .map_err(LemmyError::from)
+        .map_err(|e| e.with_message("user_already_exists"))?;
+
+      // Create the local user
+      let local_user_form = LocalUserForm {
+        person_id: Some(inserted_person.id),
+        ..local_user_form
+      };
+      let inserted_local_user = LocalUser::register(conn, &local_user_form).map_err(|e| {
+        let err_type = if e.to_string()
+          == "duplicate key value violates unique constraint \"local_user_email_key\""
+        {
+          "email_already_exists"
+        } else {
+          "user_already_exists"
+        };
+        LemmyError::from(e).with_message(err_type)
+      })?;
+
+      // Create the main community if it doesn't exist
+      let main_community = match Community::read(conn, CommunityId(2)) {
+        Ok(c) => c,
+        Err(_e) => Community::create(conn, &community_form)?,
+      };
+
+      // Sign them up for main community no matter what
+      let community_follower_form = CommunityFollowerForm {
+        community_id: main_community.id,
+        person_id: inserted_person.id,
+        pending: false,
+      };
+      CommunityFollower::follow(conn, &community_follower_form)
+        .map_err(LemmyError::from)
+        .map_err(|e| e.with_message("community_follower_already_exists"))?;
+
+      // If its an admin, add them as a mod and follower to main
+      if admin {
+        let community_moderator_form = CommunityModeratorForm {
+          community_id: main_community.id,
+          person_id: inserted_person.id,
+        };
+        CommunityModerator::join(conn, &community_moderator_form)
+          .map_err(LemmyError::from)
+          .map_err(|e| e.with_message("community_moderator_already_exists"))?;
+      }
+
+      then(conn, &inserted_local_user)?;
+      Ok((inserted_person, inserted_local_user))
+    })
+  })
+  .await?
+}
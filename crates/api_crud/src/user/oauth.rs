@@ -0,0 +1,693 @@
+use crate::{user::create::create_local_user, PerformCrud};
+use actix_web::web::Data;
+use lemmy_api_common::{
+  blocking,
+  check_registration_application,
+  create_login_session,
+  get_local_user_view_from_jwt,
+  person::*,
+};
+use lemmy_db_schema::{
+  newtypes::LocalUserId,
+  source::{
+    external_identity::{ExternalIdentity, ExternalIdentityForm},
+    local_user::LocalUserForm,
+    oauth_state::{OAuthState, OAuthStateForm},
+    registration_application::{RegistrationApplication, RegistrationApplicationForm},
+    site::Site,
+  },
+  traits::Crud,
+};
+use lemmy_db_views::local_user_view::LocalUserView;
+use lemmy_utils::{
+  settings::structs::OAuthProviderConfig,
+  utils::generate_random_string,
+  ConnectionId,
+  LemmyError,
+};
+use lemmy_websocket::LemmyContext;
+use rand::{distributions::Alphanumeric, thread_rng, Rng};
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use url::Url;
+
+#[async_trait::async_trait(?Send)]
+impl PerformCrud for ListOAuthProviders {
+  type Response = ListOAuthProvidersResponse;
+
+  #[tracing::instrument(skip(context, _websocket_id))]
+  async fn perform(
+    &self,
+    context: &Data<LemmyContext>,
+    _websocket_id: Option<ConnectionId>,
+  ) -> Result<ListOAuthProvidersResponse, LemmyError> {
+    let oauth_providers = context
+      .settings()
+      .oauth_providers
+      .into_iter()
+      .map(|p| PublicOAuthProvider {
+        id: p.id,
+        display_name: p.display_name,
+        authorization_endpoint: p.authorization_endpoint,
+        client_id: p.client_id,
+        scopes: p.scopes,
+      })
+      .collect();
+    Ok(ListOAuthProvidersResponse { oauth_providers })
+  }
+}
+
+#[async_trait::async_trait(?Send)]
+impl PerformCrud for StartOAuth {
+  type Response = StartOAuthResponse;
+
+  #[tracing::instrument(skip(self, context, _websocket_id))]
+  async fn perform(
+    &self,
+    context: &Data<LemmyContext>,
+    _websocket_id: Option<ConnectionId>,
+  ) -> Result<StartOAuthResponse, LemmyError> {
+    let data: &StartOAuth = self;
+    let provider = find_provider(&data.provider, context)?;
+    let local_user_id = match &data.auth {
+      Some(auth) => Some(
+        get_local_user_view_from_jwt(auth, context.pool(), context.secret())
+          .await?
+          .local_user
+          .id,
+      ),
+      None => None,
+    };
+
+    let form = OAuthStateForm {
+      state: generate_random_string(),
+      provider: provider.id.to_owned(),
+      redirect_uri: data.redirect_uri.to_owned(),
+      code_verifier: generate_code_verifier(),
+      local_user_id,
+    };
+    let oauth_state =
+      blocking(context.pool(), move |conn| OAuthState::create(conn, &form)).await??;
+    let authorization_url = build_authorization_url(&provider, &oauth_state)?;
+
+    Ok(StartOAuthResponse {
+      authorization_url: authorization_url.into(),
+      state: oauth_state.state,
+    })
+  }
+}
+
+#[async_trait::async_trait(?Send)]
+impl PerformCrud for AuthenticateWithOAuth {
+  type Response = LoginResponse;
+
+  #[tracing::instrument(skip(self, context, _websocket_id))]
+  async fn perform(
+    &self,
+    context: &Data<LemmyContext>,
+    _websocket_id: Option<ConnectionId>,
+  ) -> Result<LoginResponse, LemmyError> {
+    let data: &AuthenticateWithOAuth = self;
+    let provider = find_provider(&data.provider, context)?;
+
+    let state = data.state.to_string();
+    let oauth_state = blocking(context.pool(), move |conn| OAuthState::take(conn, &state))
+      .await??
+      .ok_or_else(|| LemmyError::from_message("oauth_state_invalid"))?;
+    let linking_user = match &data.auth {
+      Some(auth) => {
+        Some(get_local_user_view_from_jwt(auth, context.pool(), context.secret()).await?)
+      }
+      None => None,
+    };
+    check_oauth_state(
+      &oauth_state,
+      &provider,
+      &data.redirect_uri,
+      linking_user.as_ref(),
+    )?;
+
+    let user_info = fetch_user_info(&provider, &data.code, &oauth_state, context).await?;
+    authenticate(
+      &provider,
+      &user_info,
+      linking_user,
+      &data.username,
+      &data.answer,
+      context,
+    )
+    .await
+  }
+}
+
+fn find_provider(id: &str, context: &LemmyContext) -> Result<OAuthProviderConfig, LemmyError> {
+  context
+    .settings()
+    .oauth_providers
+    .into_iter()
+    .find(|p| p.id == id)
+    .ok_or_else(|| LemmyError::from_message("oauth_provider_not_found"))
+}
+
+/// The state must have been issued for this provider and redirect uri, and for linking only to the
+/// user who started it. Otherwise someone could get their provider account linked to the account
+/// of a victim, by passing the victim a redirect with their own code.
+fn check_oauth_state(
+  oauth_state: &OAuthState,
+  provider: &OAuthProviderConfig,
+  redirect_uri: &str,
+  linking_user: Option<&LocalUserView>,
+) -> Result<(), LemmyError> {
+  if oauth_state.provider != provider.id
+    || oauth_state.redirect_uri != redirect_uri
+    || oauth_state.local_user_id != linking_user.map(|u| u.local_user.id)
+  {
+    return Err(LemmyError::from_message("oauth_state_invalid"));
+  }
+  Ok(())
+}
+
+/// Code verifier for PKCE, https://datatracker.ietf.org/doc/html/rfc7636#section-4.1
+fn generate_code_verifier() -> String {
+  thread_rng()
+    .sample_iter(&Alphanumeric)
+    .map(char::from)
+    .take(64)
+    .collect()
+}
+
+fn code_challenge(code_verifier: &str) -> String {
+  base64::encode_config(
+    Sha256::digest(code_verifier.as_bytes()),
+    base64::URL_SAFE_NO_PAD,
+  )
+}
+
+fn build_authorization_url(
+  provider: &OAuthProviderConfig,
+  oauth_state: &OAuthState,
+) -> Result<Url, LemmyError> {
+  let mut url = Url::parse(&provider.authorization_endpoint)?;
+  url
+    .query_pairs_mut()
+    .append_pair("response_type", "code")
+    .append_pair("client_id", &provider.client_id)
+    .append_pair("redirect_uri", &oauth_state.redirect_uri)
+    .append_pair("scope", &provider.scopes)
+    .append_pair("state", &oauth_state.state)
+    .append_pair(
+      "code_challenge",
+      &code_challenge(&oauth_state.code_verifier),
+    )
+    .append_pair("code_challenge_method", "S256");
+  Ok(url)
+}
+
+/// Logs in the user whose account is linked to the provider account, links it to the given user,
+/// or creates a new account for it
+async fn authenticate(
+  provider: &OAuthProviderConfig,
+  user_info: &UserInfo,
+  linking_user: Option<LocalUserView>,
+  username: &Option<String>,
+  answer: &Option<String>,
+  context: &LemmyContext,
+) -> Result<LoginResponse, LemmyError> {
+  let provider_id = provider.id.to_owned();
+  let subject = user_info.sub.to_owned();
+  let identity = blocking(context.pool(), move |conn| {
+    ExternalIdentity::read_for_subject(conn, &provider_id, &subject)
+  })
+  .await?
+  .ok();
+
+  let local_user_view = match (identity, linking_user) {
+    (Some(_), Some(_)) => return Err(LemmyError::from_message("oauth_account_already_linked")),
+    (Some(identity), None) => {
+      let local_user_id = identity.local_user_id;
+      let local_user_view = blocking(context.pool(), move |conn| {
+        LocalUserView::read(conn, local_user_id)
+      })
+      .await??;
+
+      // The provider can't ask for the second factor, so it would be bypassed by logging in here
+      if local_user_view.local_user.totp_enabled {
+        return Err(LemmyError::from_message("oauth_totp_enabled"));
+      }
+      let site = blocking(context.pool(), Site::read_simple).await??;
+      check_registration_application(&site, &local_user_view, context.pool()).await?;
+      if site.require_email_verification && !local_user_view.local_user.email_verified {
+        return Err(LemmyError::from_message("email_not_verified"));
+      }
+      local_user_view
+    }
+    (None, Some(local_user_view)) => {
+      link_identity(provider, user_info, local_user_view.local_user.id, context).await?;
+      local_user_view
+    }
+    (None, None) if provider.auto_provision => {
+      match provision_local_user(provider, user_info, username, answer, context).await? {
+        Some(local_user_view) => local_user_view,
+        None => {
+          return Ok(LoginResponse {
+            jwt: None,
+            verify_email_sent: false,
+            registration_created: true,
+          })
+        }
+      }
+    }
+    (None, None) => return Err(LemmyError::from_message("oauth_account_not_linked")),
+  };
+
+  if local_user_view.person.banned {
+    return Err(LemmyError::from_message("site_ban"));
+  }
+  if local_user_view.person.deleted {
+    return Err(LemmyError::from_message("deleted"));
+  }
+
+  Ok(LoginResponse {
+    jwt: Some(
+      create_login_session(
+        local_user_view.local_user.id,
+        context.pool(),
+        context.secret(),
+        &context.settings(),
+      )
+      .await?,
+    ),
+    verify_email_sent: false,
+    registration_created: false,
+  })
+}
+
+#[derive(Deserialize)]
+struct TokenResponse {
+  access_token: String,
+}
+
+/// Claims of the userinfo endpoint, https://openid.net/specs/openid-connect-core-1_0.html#UserInfo
+#[derive(Deserialize)]
+struct UserInfo {
+  sub: String,
+  email: Option<String>,
+  #[serde(default)]
+  email_verified: bool,
+  preferred_username: Option<String>,
+}
+
+/// Exchanges the authorization code for an access token, and uses it to read the claims of the
+/// provider account. The code verifier proves that the code was requested by this instance.
+async fn fetch_user_info(
+  provider: &OAuthProviderConfig,
+  code: &str,
+  oauth_state: &OAuthState,
+  context: &LemmyContext,
+) -> Result<UserInfo, LemmyError> {
+  let token = context
+    .client()
+    .post(&provider.token_endpoint)
+    .form(&[
+      ("grant_type", "authorization_code"),
+      ("code", code),
+      ("redirect_uri", oauth_state.redirect_uri.as_str()),
+      ("code_verifier", oauth_state.code_verifier.as_str()),
+      ("client_id", provider.client_id.as_str()),
+      ("client_secret", provider.client_secret.as_str()),
+    ])
+    .send()
+    .await?
+    .error_for_status()
+    .map_err(LemmyError::from)
+    .map_err(|e| e.with_message("oauth_authorization_invalid"))?
+    .json::<TokenResponse>()
+    .await?;
+
+  let user_info = context
+    .client()
+    .get(&provider.userinfo_endpoint)
+    .bearer_auth(token.access_token)
+    .send()
+    .await?
+    .error_for_status()
+    .map_err(LemmyError::from)
+    .map_err(|e| e.with_message("oauth_authorization_invalid"))?
+    .json::<UserInfo>()
+    .await?;
+  Ok(user_info)
+}
+
+async fn link_identity(
+  provider: &OAuthProviderConfig,
+  user_info: &UserInfo,
+  local_user_id: LocalUserId,
+  context: &LemmyContext,
+) -> Result<(), LemmyError> {
+  let form = ExternalIdentityForm {
+    local_user_id,
+    provider: provider.id.to_owned(),
+    subject: user_info.sub.to_owned(),
+  };
+  blocking(context.pool(), move |conn| {
+    ExternalIdentity::create(conn, &form)
+  })
+  .await?
+  .map_err(LemmyError::from)
+  .map_err(|e| e.with_message("oauth_account_already_linked"))?;
+  Ok(())
+}
+
+/// Creates an account for someone who logs in for the first time. The email is only taken over if
+/// the provider verified it. Returns None if the account needs to wait for its registration
+/// application to be approved.
+async fn provision_local_user(
+  provider: &OAuthProviderConfig,
+  user_info: &UserInfo,
+  username: &Option<String>,
+  answer: &Option<String>,
+  context: &LemmyContext,
+) -> Result<Option<LocalUserView>, LemmyError> {
+  let (mut email_verification, mut require_application) = (false, false);
+  let site = blocking(context.pool(), Site::read_simple).await?.ok();
+  if let Some(site) = &site {
+    if !site.open_registration {
+      return Err(LemmyError::from_message("registration_closed"));
+    }
+    email_verification = site.require_email_verification;
+    require_application = site.require_application;
+  }
+
+  let username = username
+    .as_ref()
+    .or(user_info.preferred_username.as_ref())
+    .ok_or_else(|| LemmyError::from_message("oauth_username_required"))?;
+  let email = user_info
+    .email
+    .to_owned()
+    .filter(|_| user_info.email_verified);
+  if email_verification && email.is_none() {
+    return Err(LemmyError::from_message("email_required"));
+  }
+  if require_application && answer.is_none() {
+    return Err(LemmyError::from_message(
+      "registration_application_answer_required",
+    ));
+  }
+
+  // The account can only be used through the provider, until the password is reset
+  let local_user_form = LocalUserForm {
+    email_verified: Some(email.is_some()),
+    email: Some(email),
+    password_encrypted: Some(generate_random_string()),
+    ..LocalUserForm::default()
+  };
+  // Linking and the registration application happen in the same transaction as the account
+  // creation, so that a failure doesn't leave behind an account which nobody can log in to
+  let (provider_id, subject) = (provider.id.to_owned(), user_info.sub.to_owned());
+  let answer = answer.to_owned();
+  let (_, inserted_local_user) = create_local_user(
+    username,
+    local_user_form,
+    false,
+    context,
+    move |conn, local_user| {
+      let identity_form = ExternalIdentityForm {
+        local_user_id: local_user.id,
+        provider: provider_id,
+        subject,
+      };
+      ExternalIdentity::create(conn, &identity_form)
+        .map_err(LemmyError::from)
+        .map_err(|e| e.with_message("oauth_account_already_linked"))?;
+      if require_application {
+        let form = RegistrationApplicationForm {
+          local_user_id: Some(local_user.id),
+          answer,
+          ..RegistrationApplicationForm::default()
+        };
+        RegistrationApplication::create(conn, &form)?;
+      }
+      Ok(())
+    },
+  )
+  .await?;
+  if require_application {
+    return Ok(None);
+  }
+
+  let local_user_id = inserted_local_user.id;
+  let local_user_view = blocking(context.pool(), move |conn| {
+    LocalUserView::read(conn, local_user_id)
+  })
+  .await??;
+  Ok(Some(local_user_view))
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::{tests::init_context, user::oauth::*};
+  use diesel::{update, ExpressionMethods, QueryDsl, RunQueryDsl};
+  use lemmy_db_schema::{
+    newtypes::CommunityId,
+    schema::{community, local_user},
+    source::{
+      community::{Community, CommunityForm},
+      local_user::LocalUser,
+      person::{Person, PersonForm},
+      site::{Site, SiteForm},
+    },
+    traits::Crud,
+  };
+  use serial_test::serial;
+
+  fn provider() -> OAuthProviderConfig {
+    OAuthProviderConfig {
+      id: "example-idp".to_string(),
+      display_name: "Example".to_string(),
+      authorization_endpoint: "https://idp.example.com/authorize?prompt=login".to_string(),
+      token_endpoint: "https://idp.example.com/token".to_string(),
+      userinfo_endpoint: "https://idp.example.com/userinfo".to_string(),
+      client_id: "lemmy".to_string(),
+      client_secret: "secret".to_string(),
+      scopes: "openid email profile".to_string(),
+      auto_provision: true,
+    }
+  }
+
+  fn user_info(sub: &str, username: &str) -> UserInfo {
+    UserInfo {
+      sub: sub.to_string(),
+      email: Some(format!("{}@example.com", username)),
+      email_verified: true,
+      preferred_username: Some(username.to_string()),
+    }
+  }
+
+  fn oauth_state(local_user_id: Option<LocalUserId>) -> OAuthState {
+    OAuthState {
+      id: 1,
+      state: "abc".to_string(),
+      provider: "example-idp".to_string(),
+      redirect_uri: "https://lemmy.example.com/oauth/callback".to_string(),
+      code_verifier: "dBjftJeZ4CVP-mJ92IJ7cbJUqXcI5WJvZ4SIAAgYrW0".to_string(),
+      local_user_id,
+      published: lemmy_db_schema::naive_now(),
+    }
+  }
+
+  #[test]
+  fn test_authorization_url() {
+    assert_eq!(
+      "R_dF_PNE_77LKSR-PBpfcmS4mSshR6xtYeqgod77rjY",
+      code_challenge("dBjftJeZ4CVP-mJ92IJ7cbJUqXcI5WJvZ4SIAAgYrW0")
+    );
+    assert_eq!(64, generate_code_verifier().len());
+
+    let url = build_authorization_url(&provider(), &oauth_state(None)).unwrap();
+    let pairs: Vec<(String, String)> = url.query_pairs().into_owned().collect();
+    let get = |key: &str| {
+      pairs
+        .iter()
+        .find(|(k, _)| k == key)
+        .map(|(_, v)| v.as_str())
+    };
+    assert_eq!(Some("login"), get("prompt"));
+    assert_eq!(Some("code"), get("response_type"));
+    assert_eq!(Some("abc"), get("state"));
+    assert_eq!(
+      Some("https://lemmy.example.com/oauth/callback"),
+      get("redirect_uri")
+    );
+    assert_eq!(
+      Some("R_dF_PNE_77LKSR-PBpfcmS4mSshR6xtYeqgod77rjY"),
+      get("code_challenge")
+    );
+    assert_eq!(Some("S256"), get("code_challenge_method"));
+  }
+
+  #[actix_rt::test]
+  #[serial]
+  async fn test_authenticate() {
+    let context = init_context();
+    let conn = context.pool().get().unwrap();
+    let provider = provider();
+
+    let new_person = PersonForm {
+      name: "oauth_existing".into(),
+      ..PersonForm::default()
+    };
+    let existing_person = Person::create(&conn, &new_person).unwrap();
+    let new_local_user = LocalUserForm {
+      person_id: Some(existing_person.id),
+      password_encrypted: Some("oauth_existing_pass".to_string()),
+      ..LocalUserForm::default()
+    };
+    let existing_local_user = LocalUser::create(&conn, &new_local_user).unwrap();
+    let existing = LocalUserView::read(&conn, existing_local_user.id).unwrap();
+
+    let site_form = SiteForm {
+      name: "oauth_site".into(),
+      creator_id: existing_person.id,
+      open_registration: Some(true),
+      ..SiteForm::default()
+    };
+    let site = Site::create(&conn, &site_form).unwrap();
+    // New accounts follow the main community, which needs to have id 2
+    let main_community_form = CommunityForm {
+      name: "main".into(),
+      title: "main".into(),
+      public_key: "pubkey".to_string(),
+      ..CommunityForm::default()
+    };
+    let main_community = Community::create(&conn, &main_community_form).unwrap();
+    update(community::table.find(main_community.id))
+      .set(community::id.eq(CommunityId(2)))
+      .execute(&conn)
+      .unwrap();
+
+    // The state of a login can't be used for linking, and the other way around
+    let redirect_uri = "https://lemmy.example.com/oauth/callback";
+    let login_state = oauth_state(None);
+    let link_state = oauth_state(Some(existing_local_user.id));
+    assert!(check_oauth_state(&login_state, &provider, redirect_uri, None).is_ok());
+    assert!(check_oauth_state(&login_state, &provider, redirect_uri, Some(&existing)).is_err());
+    assert!(check_oauth_state(&link_state, &provider, redirect_uri, Some(&existing)).is_ok());
+    assert!(check_oauth_state(&link_state, &provider, redirect_uri, None).is_err());
+    assert!(check_oauth_state(&login_state, &provider, "https://evil.example", None).is_err());
+
+    // Link the provider account to an existing user, then log in with it
+    let linked_info = user_info("oauth-sub-existing", "oauth_existing");
+    let linked = authenticate(
+      &provider,
+      &linked_info,
+      Some(existing),
+      &None,
+      &None,
+      &context,
+    )
+    .await;
+    let identity =
+      ExternalIdentity::read_for_subject(&conn, "example-idp", "oauth-sub-existing").unwrap();
+    let existing = LocalUserView::read(&conn, existing_local_user.id).unwrap();
+    let linked_twice = authenticate(
+      &provider,
+      &linked_info,
+      Some(existing),
+      &None,
+      &None,
+      &context,
+    )
+    .await;
+    let login = authenticate(&provider, &linked_info, None, &None, &None, &context).await;
+
+    // Logging in through the provider must not skip the second factor or the email verification
+    update(local_user::table.find(existing_local_user.id))
+      .set(local_user::totp_enabled.eq(true))
+      .execute(&conn)
+      .unwrap();
+    let totp_login = authenticate(&provider, &linked_info, None, &None, &None, &context).await;
+    update(local_user::table.find(existing_local_user.id))
+      .set(local_user::totp_enabled.eq(false))
+      .execute(&conn)
+      .unwrap();
+    let site_form = SiteForm {
+      require_email_verification: Some(true),
+      ..site_form
+    };
+    Site::update(&conn, site.id, &site_form).unwrap();
+    let unverified_login =
+      authenticate(&provider, &linked_info, None, &None, &None, &context).await;
+    let site_form = SiteForm {
+      require_email_verification: Some(false),
+      ..site_form
+    };
+    Site::update(&conn, site.id, &site_form).unwrap();
+
+    let provisioned_info = user_info("oauth-sub-provisioned", "oauth_provisioned");
+    let provisioned = authenticate(&provider, &provisioned_info, None, &None, &None, &context)
+      .await
+      .unwrap();
+    let provisioned_identity =
+      ExternalIdentity::read_for_subject(&conn, "example-idp", "oauth-sub-provisioned").unwrap();
+    let provisioned_user = LocalUserView::read(&conn, provisioned_identity.local_user_id).unwrap();
+
+    let site_form = SiteForm {
+      open_registration: Some(false),
+      ..site_form
+    };
+    Site::update(&conn, site.id, &site_form).unwrap();
+    let closed_info = user_info("oauth-sub-closed", "oauth_closed");
+    let closed = authenticate(&provider, &closed_info, None, &None, &None, &context).await;
+
+    let site_form = SiteForm {
+      open_registration: Some(true),
+      require_application: Some(true),
+      ..site_form
+    };
+    Site::update(&conn, site.id, &site_form).unwrap();
+    let without_answer = authenticate(&provider, &closed_info, None, &None, &None, &context).await;
+    let answer = Some("I like the fediverse".to_string());
+    let application = authenticate(&provider, &closed_info, None, &None, &answer, &context)
+      .await
+      .unwrap();
+    // Can't log in until the application is approved
+    let pending = authenticate(&provider, &closed_info, None, &None, &None, &context).await;
+    let pending_identity =
+      ExternalIdentity::read_for_subject(&conn, "example-idp", "oauth-sub-closed").unwrap();
+    let pending_user = LocalUserView::read(&conn, pending_identity.local_user_id).unwrap();
+
+    Site::delete(&conn, site.id).unwrap();
+    Community::delete(&conn, CommunityId(2)).unwrap();
+    Person::delete(&conn, existing_person.id).unwrap();
+    Person::delete(&conn, provisioned_user.person.id).unwrap();
+    Person::delete(&conn, pending_user.person.id).unwrap();
+
+    assert!(linked.unwrap().jwt.is_some());
+    assert_eq!(existing_local_user.id, identity.local_user_id);
+    assert!(linked_twice.is_err());
+    assert!(login.unwrap().jwt.is_some());
+    assert_eq!(Some("oauth_totp_enabled"), totp_login.unwrap_err().message);
+    assert_eq!(
+      Some("email_not_verified"),
+      unverified_login.unwrap_err().message
+    );
+
+    assert!(provisioned.jwt.is_some());
+    assert_eq!("oauth_provisioned", provisioned_user.person.name);
+    assert!(provisioned_user.local_user.email_verified);
+
+    assert_eq!(Some("registration_closed"), closed.unwrap_err().message);
+    assert_eq!(
+      Some("registration_application_answer_required"),
+      without_answer.unwrap_err().message
+    );
+    assert!(application.jwt.is_none());
+    assert!(application.registration_created);
+    assert!(!pending_user.local_user.accepted_application);
+    assert_eq!(
+      Some("registration_application_pending"),
+      pending.unwrap_err().message
+    );
+  }
+}
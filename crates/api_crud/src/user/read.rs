@@ -12,7 +12,7 @@ use lemmy_apub::{
   EndpointType,
 };
 use lemmy_apub_lib::object_id::ObjectId;
-use lemmy_db_schema::{from_opt_str_to_opt_enum, SortType};
+use lemmy_db_schema::{from_opt_str_to_opt_enum, source::person_field::PersonField, SortType};
 use lemmy_db_views::{comment_view::CommentQueryBuilder, post_view::PostQueryBuilder};
 use lemmy_db_views_actor::{
   community_moderator_view::CommunityModeratorView,
@@ -45,6 +45,7 @@ impl PerformCrud for GetPersonDetails {
     let show_read_posts = local_user_view
       .as_ref()
       .map(|t| t.local_user.show_read_posts);
+    let show_scores = local_user_view.as_ref().map(|t| t.local_user.show_scores);
 
     let sort: Option<SortType> = from_opt_str_to_opt_enum(&data.sort);
 
@@ -88,6 +89,7 @@ impl PerformCrud for GetPersonDetails {
         .show_nsfw(show_nsfw)
         .show_bot_accounts(show_bot_accounts)
         .show_read_posts(show_read_posts)
+        .show_scores(show_scores)
         .saved_only(saved_only)
         .community_id(community_id)
         .my_person_id(person_id)
@@ -97,6 +99,7 @@ impl PerformCrud for GetPersonDetails {
       let mut comments_query = CommentQueryBuilder::create(conn)
         .my_person_id(person_id)
         .show_bot_accounts(show_bot_accounts)
+        .show_scores(show_scores)
         .sort(sort)
         .saved_only(saved_only)
         .community_id(community_id)
@@ -122,9 +125,15 @@ impl PerformCrud for GetPersonDetails {
     })
     .await??;
 
+    let fields = blocking(context.pool(), move |conn| {
+      PersonField::list_for_person(conn, person_details_id)
+    })
+    .await??;
+
     // Return the jwt
     Ok(GetPersonDetailsResponse {
       person_view,
+      fields,
       moderates,
       comments,
       posts,
@@ -0,0 +1,88 @@
+use crate::{
+  wiki_page::{check_wiki_page_title, save_revision},
+  PerformCrud,
+};
+use actix_web::web::Data;
+use lemmy_api_common::{
+  blocking,
+  check_community_deleted_or_removed,
+  community::{CreateWikiPage, WikiPageResponse},
+  get_local_user_view_from_jwt,
+  is_mod_or_admin,
+};
+use lemmy_apub::{
+  generate_local_apub_endpoint,
+  protocol::activities::{create_or_update::wiki_page::CreateOrUpdateWikiPage, CreateOrUpdateType},
+  EndpointType,
+};
+use lemmy_db_schema::{
+  source::community_wiki_page::{CommunityWikiPage, CommunityWikiPageForm},
+  traits::Crud,
+};
+use lemmy_utils::{utils::check_slurs, ConnectionId, LemmyError};
+use lemmy_websocket::LemmyContext;
+
+#[async_trait::async_trait(?Send)]
+impl PerformCrud for CreateWikiPage {
+  type Response = WikiPageResponse;
+
+  #[tracing::instrument(skip(context, _websocket_id))]
+  async fn perform(
+    &self,
+    context: &Data<LemmyContext>,
+    _websocket_id: Option<ConnectionId>,
+  ) -> Result<WikiPageResponse, LemmyError> {
+    let data: &CreateWikiPage = self;
+    let local_user_view =
+      get_local_user_view_from_jwt(&data.auth, context.pool(), context.secret()).await?;
+    is_mod_or_admin(context.pool(), local_user_view.person.id, data.community_id).await?;
+    check_community_deleted_or_removed(data.community_id, context.pool()).await?;
+
+    let title = check_wiki_page_title(&data.title, context)?;
+    check_slurs(&data.content, &context.settings().slur_regex())?;
+
+    let form = CommunityWikiPageForm {
+      community_id: data.community_id,
+      creator_id: local_user_view.person.id,
+      title,
+      content: data.content.clone(),
+      ..CommunityWikiPageForm::default()
+    };
+    let inserted_page = blocking(context.pool(), move |conn| {
+      CommunityWikiPage::create(conn, &form)
+    })
+    .await?
+    .map_err(LemmyError::from)
+    .map_err(|e| e.with_message("wiki_page_already_exists"))?;
+
+    let inserted_page_id = inserted_page.id;
+    let protocol_and_hostname = context.settings().get_protocol_and_hostname();
+    let wiki_page = blocking(
+      context.pool(),
+      move |conn| -> Result<CommunityWikiPage, LemmyError> {
+        let apub_id = generate_local_apub_endpoint(
+          EndpointType::WikiPage,
+          &inserted_page_id.to_string(),
+          &protocol_and_hostname,
+        )?;
+        Ok(CommunityWikiPage::update_ap_id(
+          conn,
+          inserted_page_id,
+          apub_id,
+        )?)
+      },
+    )
+    .await??;
+    save_revision(&wiki_page, local_user_view.person.id, context.pool()).await?;
+
+    CreateOrUpdateWikiPage::send(
+      wiki_page.clone().into(),
+      &local_user_view.person.into(),
+      CreateOrUpdateType::Create,
+      context,
+    )
+    .await?;
+
+    Ok(WikiPageResponse { wiki_page })
+  }
+}
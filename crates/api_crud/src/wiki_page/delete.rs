@@ -0,0 +1,76 @@
+use crate::PerformCrud;
+use actix_web::web::Data;
+use lemmy_api_common::{
+  blocking,
+  community::{DeleteWikiPage, WikiPageResponse},
+  get_local_user_view_from_jwt,
+  is_mod_or_admin,
+};
+use lemmy_apub::activities::{
+  deletion::{send_apub_delete, DeletableObjects},
+  federation_target::FederationTarget,
+};
+use lemmy_db_schema::{
+  source::{community::Community, community_wiki_page::CommunityWikiPage},
+  traits::Crud,
+};
+use lemmy_utils::{ConnectionId, LemmyError};
+use lemmy_websocket::LemmyContext;
+
+#[async_trait::async_trait(?Send)]
+impl PerformCrud for DeleteWikiPage {
+  type Response = WikiPageResponse;
+
+  #[tracing::instrument(skip(context, _websocket_id))]
+  async fn perform(
+    &self,
+    context: &Data<LemmyContext>,
+    _websocket_id: Option<ConnectionId>,
+  ) -> Result<WikiPageResponse, LemmyError> {
+    let data: &DeleteWikiPage = self;
+    let local_user_view =
+      get_local_user_view_from_jwt(&data.auth, context.pool(), context.secret()).await?;
+
+    let wiki_page_id = data.wiki_page_id;
+    let orig_page = blocking(context.pool(), move |conn| {
+      CommunityWikiPage::read(conn, wiki_page_id)
+    })
+    .await?
+    .map_err(LemmyError::from)
+    .map_err(|e| e.with_message("couldnt_find_wiki_page"))?;
+    if orig_page.deleted == data.deleted {
+      return Err(LemmyError::from_message("couldnt_update_wiki_page"));
+    }
+    is_mod_or_admin(
+      context.pool(),
+      local_user_view.person.id,
+      orig_page.community_id,
+    )
+    .await?;
+
+    let deleted = data.deleted;
+    let wiki_page = blocking(context.pool(), move |conn| {
+      CommunityWikiPage::update_deleted(conn, wiki_page_id, deleted)
+    })
+    .await?
+    .map_err(LemmyError::from)
+    .map_err(|e| e.with_message("couldnt_update_wiki_page"))?;
+
+    let community = blocking(context.pool(), move |conn| {
+      Community::read(conn, orig_page.community_id)
+    })
+    .await??;
+    let target = FederationTarget::Community(community.id);
+    send_apub_delete(
+      &local_user_view.person.into(),
+      &community.into(),
+      DeletableObjects::WikiPage(Box::new(wiki_page.clone().into())),
+      deleted,
+      target,
+      context,
+    )
+    .await?;
+
+    Ok(WikiPageResponse { wiki_page })
+  }
+}
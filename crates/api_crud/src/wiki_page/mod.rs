@@ -0,0 +1,46 @@
+use lemmy_api_common::blocking;
+use lemmy_db_schema::{
+  newtypes::PersonId,
+  source::community_wiki_page::{
+    CommunityWikiPage,
+    CommunityWikiPageRevision,
+    CommunityWikiPageRevisionForm,
+  },
+  DbPool,
+};
+use lemmy_utils::{utils::check_slurs, LemmyError};
+use lemmy_websocket::LemmyContext;
+
+mod create;
+mod delete;
+mod read;
+mod update;
+
+const MAX_WIKI_PAGE_TITLE_LENGTH: usize = 200;
+
+fn check_wiki_page_title(title: &str, context: &LemmyContext) -> Result<String, LemmyError> {
+  let title = title.trim();
+  if title.is_empty() || title.chars().count() > MAX_WIKI_PAGE_TITLE_LENGTH {
+    return Err(LemmyError::from_message("invalid_wiki_page_title"));
+  }
+  check_slurs(title, &context.settings().slur_regex())?;
+  Ok(title.to_owned())
+}
+
+/// Stores the current content of the page in its history
+async fn save_revision(
+  wiki_page: &CommunityWikiPage,
+  editor_id: PersonId,
+  pool: &DbPool,
+) -> Result<(), LemmyError> {
+  let form = CommunityWikiPageRevisionForm {
+    wiki_page_id: wiki_page.id,
+    editor_id,
+    content: wiki_page.content.clone(),
+  };
+  blocking(pool, move |conn| {
+    CommunityWikiPageRevision::create(conn, &form)
+  })
+  .await??;
+  Ok(())
+}
@@ -0,0 +1,78 @@
+use crate::PerformCrud;
+use actix_web::web::Data;
+use lemmy_api_common::{
+  blocking,
+  check_private_instance,
+  community::{GetWikiPage, GetWikiPageResponse, ListWikiPages, ListWikiPagesResponse},
+  get_local_user_view_from_jwt_opt,
+};
+use lemmy_db_schema::{
+  source::community_wiki_page::{CommunityWikiPage, CommunityWikiPageRevision},
+  traits::Crud,
+};
+use lemmy_utils::{ConnectionId, LemmyError};
+use lemmy_websocket::LemmyContext;
+
+#[async_trait::async_trait(?Send)]
+impl PerformCrud for GetWikiPage {
+  type Response = GetWikiPageResponse;
+
+  #[tracing::instrument(skip(context, _websocket_id))]
+  async fn perform(
+    &self,
+    context: &Data<LemmyContext>,
+    _websocket_id: Option<ConnectionId>,
+  ) -> Result<GetWikiPageResponse, LemmyError> {
+    let data: &GetWikiPage = self;
+    let local_user_view =
+      get_local_user_view_from_jwt_opt(data.auth.as_ref(), context.pool(), context.secret())
+        .await?;
+    check_private_instance(&local_user_view, context.pool()).await?;
+
+    let wiki_page_id = data.wiki_page_id;
+    let wiki_page = blocking(context.pool(), move |conn| {
+      CommunityWikiPage::read(conn, wiki_page_id)
+    })
+    .await?
+    .map_err(LemmyError::from)
+    .map_err(|e| e.with_message("couldnt_find_wiki_page"))?;
+    if wiki_page.deleted {
+      return Err(LemmyError::from_message("couldnt_find_wiki_page"));
+    }
+    let revisions = blocking(context.pool(), move |conn| {
+      CommunityWikiPageRevision::list_for_page(conn, wiki_page_id)
+    })
+    .await??;
+
+    Ok(GetWikiPageResponse {
+      wiki_page,
+      revisions,
+    })
+  }
+}
+
+#[async_trait::async_trait(?Send)]
+impl PerformCrud for ListWikiPages {
+  type Response = ListWikiPagesResponse;
+
+  #[tracing::instrument(skip(context, _websocket_id))]
+  async fn perform(
+    &self,
+    context: &Data<LemmyContext>,
+    _websocket_id: Option<ConnectionId>,
+  ) -> Result<ListWikiPagesResponse, LemmyError> {
+    let data: &ListWikiPages = self;
+    let local_user_view =
+      get_local_user_view_from_jwt_opt(data.auth.as_ref(), context.pool(), context.secret())
+        .await?;
+    check_private_instance(&local_user_view, context.pool()).await?;
+
+    let community_id = data.community_id;
+    let wiki_pages = blocking(context.pool(), move |conn| {
+      CommunityWikiPage::list_for_community(conn, community_id)
+    })
+    .await??;
+
+    Ok(ListWikiPagesResponse { wiki_pages })
+  }
+}
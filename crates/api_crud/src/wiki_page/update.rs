@@ -0,0 +1,75 @@
+use crate::{wiki_page::save_revision, PerformCrud};
+use actix_web::web::Data;
+use lemmy_api_common::{
+  blocking,
+  check_community_deleted_or_removed,
+  community::{EditWikiPage, WikiPageResponse},
+  get_local_user_view_from_jwt,
+  is_mod_or_admin,
+};
+use lemmy_apub::protocol::activities::{
+  create_or_update::wiki_page::CreateOrUpdateWikiPage,
+  CreateOrUpdateType,
+};
+use lemmy_db_schema::{source::community_wiki_page::CommunityWikiPage, traits::Crud};
+use lemmy_utils::{utils::check_slurs, ConnectionId, LemmyError};
+use lemmy_websocket::LemmyContext;
+
+#[async_trait::async_trait(?Send)]
+impl PerformCrud for EditWikiPage {
+  type Response = WikiPageResponse;
+
+  #[tracing::instrument(skip(context, _websocket_id))]
+  async fn perform(
+    &self,
+    context: &Data<LemmyContext>,
+    _websocket_id: Option<ConnectionId>,
+  ) -> Result<WikiPageResponse, LemmyError> {
+    let data: &EditWikiPage = self;
+    let local_user_view =
+      get_local_user_view_from_jwt(&data.auth, context.pool(), context.secret()).await?;
+
+    let wiki_page_id = data.wiki_page_id;
+    let orig_page = blocking(context.pool(), move |conn| {
+      CommunityWikiPage::read(conn, wiki_page_id)
+    })
+    .await?
+    .map_err(LemmyError::from)
+    .map_err(|e| e.with_message("couldnt_find_wiki_page"))?;
+    if orig_page.deleted {
+      return Err(LemmyError::from_message("couldnt_find_wiki_page"));
+    }
+    is_mod_or_admin(
+      context.pool(),
+      local_user_view.person.id,
+      orig_page.community_id,
+    )
+    .await?;
+    check_community_deleted_or_removed(orig_page.community_id, context.pool()).await?;
+    check_slurs(&data.content, &context.settings().slur_regex())?;
+
+    if orig_page.content == data.content {
+      return Ok(WikiPageResponse {
+        wiki_page: orig_page,
+      });
+    }
+    let content = data.content.clone();
+    let wiki_page = blocking(context.pool(), move |conn| {
+      CommunityWikiPage::update_content(conn, wiki_page_id, &content)
+    })
+    .await?
+    .map_err(LemmyError::from)
+    .map_err(|e| e.with_message("couldnt_update_wiki_page"))?;
+    save_revision(&wiki_page, local_user_view.person.id, context.pool()).await?;
+
+    CreateOrUpdateWikiPage::send(
+      wiki_page.clone().into(),
+      &local_user_view.person.into(),
+      CreateOrUpdateType::Update,
+      context,
+    )
+    .await?;
+
+    Ok(WikiPageResponse { wiki_page })
+  }
+}
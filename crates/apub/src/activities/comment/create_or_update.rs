@@ -10,7 +10,10 @@ use crate::{
   },
   activity_lists::AnnouncableActivities,
   objects::{comment::ApubComment, community::ApubCommunity, person::ApubPerson},
-  protocol::activities::{create_or_update::comment::CreateOrUpdateComment, CreateOrUpdateType},
+  protocol::{
+    activities::{create_or_update::comment::CreateOrUpdateComment, CreateOrUpdateType},
+    objects::note::NoteTag,
+  },
 };
 use activitystreams_kinds::public;
 use lemmy_api_common::{blocking, check_post_deleted_or_removed};
@@ -66,8 +69,10 @@ impl CreateOrUpdateComment {
     let tagged_users: Vec<ObjectId<ApubPerson>> = create_or_update
       .tag
       .iter()
-      .map(|t| t.href.clone())
-      .map(ObjectId::new)
+      .filter_map(|t| match t {
+        NoteTag::Mention(m) => Some(ObjectId::new(m.href.clone())),
+        NoteTag::Emoji(_) => None,
+      })
       .collect();
     let mut inboxes = vec![];
     for t in tagged_users {
@@ -25,8 +25,11 @@ use lemmy_apub_lib::{
   traits::{ActivityHandler, ActorType},
 };
 use lemmy_db_schema::{
-  source::community::{CommunityModerator, CommunityModeratorForm},
-  traits::Joinable,
+  source::{
+    community::{CommunityModerator, CommunityModeratorForm},
+    moderator::{ModAddCommunity, ModAddCommunityForm},
+  },
+  traits::{Crud, Joinable},
 };
 use lemmy_utils::LemmyError;
 use lemmy_websocket::LemmyContext;
@@ -86,6 +89,10 @@ impl ActivityHandler for AddMod {
     request_counter: &mut i32,
   ) -> Result<(), LemmyError> {
     let community = self.get_community(context, request_counter).await?;
+    let mod_person = self
+      .actor
+      .dereference(context, context.client(), request_counter)
+      .await?;
     let new_mod = self
       .object
       .dereference(context, context.client(), request_counter)
@@ -108,6 +115,17 @@ impl ActivityHandler for AddMod {
       })
       .await??;
     }
+
+    let form = ModAddCommunityForm {
+      mod_person_id: mod_person.id,
+      other_person_id: new_mod.id,
+      community_id: community.id,
+      removed: Some(false),
+    };
+    blocking(context.pool(), move |conn| {
+      ModAddCommunity::create(conn, &form)
+    })
+    .await??;
     // TODO: send websocket notification about added mod
     Ok(())
   }
@@ -1,5 +1,12 @@
 use crate::{
-  activities::{generate_activity_id, send_lemmy_activity, verify_activity, verify_is_public},
+  activities::{
+    community::with_context,
+    generate_activity_id,
+    send_lemmy_activity,
+    send_lemmy_activity_with_context,
+    verify_activity,
+    verify_is_public,
+  },
   activity_lists::AnnouncableActivities,
   http::ActivityCommonFields,
   insert_activity,
@@ -52,9 +59,9 @@ impl AnnounceActivity {
   ) -> Result<(), LemmyError> {
     let announce = AnnounceActivity::new(object.clone(), community, context)?;
     let inboxes = community.get_follower_inboxes(context).await?;
-    send_lemmy_activity(
+    send_lemmy_activity_with_context(
       context,
-      &announce,
+      with_context(&announce, &object, context),
       &announce.id,
       community,
       inboxes.clone(),
@@ -66,7 +73,7 @@ impl AnnounceActivity {
     // compatibility, we also send Announce/Page so that they can follow Lemmy communities.
     use AnnouncableActivities::*;
     let object = match object {
-      CreateOrUpdatePost(c) if c.kind == CreateOrUpdateType::Create => Page(c.object),
+      CreateOrUpdatePost(c) if c.kind == CreateOrUpdateType::Create => Page(*c.object),
       _ => return Ok(()),
     };
     let announce_compat = AnnounceActivity::new(object, community, context)?;
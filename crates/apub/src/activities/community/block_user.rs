@@ -12,6 +12,7 @@ use crate::{
   protocol::activities::community::block_user::BlockUserFromCommunity,
 };
 use activitystreams_kinds::{activity::BlockType, public};
+use chrono::NaiveDateTime;
 use lemmy_api_common::blocking;
 use lemmy_apub_lib::{
   data::Data,
@@ -19,15 +20,18 @@ use lemmy_apub_lib::{
   traits::{ActivityHandler, ActorType},
 };
 use lemmy_db_schema::{
-  source::community::{
-    CommunityFollower,
-    CommunityFollowerForm,
-    CommunityPersonBan,
-    CommunityPersonBanForm,
+  source::{
+    community::{
+      CommunityFollower,
+      CommunityFollowerForm,
+      CommunityPersonBan,
+      CommunityPersonBanForm,
+    },
+    moderator::{ModBanFromCommunity, ModBanFromCommunityForm},
   },
-  traits::{Bannable, Followable},
+  traits::{Bannable, Crud, Followable},
 };
-use lemmy_utils::LemmyError;
+use lemmy_utils::{utils::convert_datetime, LemmyError};
 use lemmy_websocket::LemmyContext;
 
 impl BlockUserFromCommunity {
@@ -35,6 +39,8 @@ impl BlockUserFromCommunity {
     community: &ApubCommunity,
     target: &ApubPerson,
     actor: &ApubPerson,
+    reason: Option<String>,
+    expires: Option<NaiveDateTime>,
     context: &LemmyContext,
   ) -> Result<BlockUserFromCommunity, LemmyError> {
     Ok(BlockUserFromCommunity {
@@ -44,6 +50,8 @@ impl BlockUserFromCommunity {
       cc: vec![community.actor_id()],
       target: ObjectId::new(community.actor_id()),
       kind: BlockType::Block,
+      summary: reason,
+      expires: expires.map(convert_datetime),
       id: generate_activity_id(
         BlockType::Block,
         &context.settings().get_protocol_and_hostname(),
@@ -57,9 +65,11 @@ impl BlockUserFromCommunity {
     community: &ApubCommunity,
     target: &ApubPerson,
     actor: &ApubPerson,
+    reason: Option<String>,
+    expires: Option<NaiveDateTime>,
     context: &LemmyContext,
   ) -> Result<(), LemmyError> {
-    let block = BlockUserFromCommunity::new(community, target, actor, context)?;
+    let block = BlockUserFromCommunity::new(community, target, actor, reason, expires, context)?;
     let block_id = block.id.clone();
 
     let activity = AnnouncableActivities::BlockUserFromCommunity(block);
@@ -93,6 +103,10 @@ impl ActivityHandler for BlockUserFromCommunity {
     request_counter: &mut i32,
   ) -> Result<(), LemmyError> {
     let community = self.get_community(context, request_counter).await?;
+    let mod_person = self
+      .actor
+      .dereference(context, context.client(), request_counter)
+      .await?;
     let blocked_user = self
       .object
       .dereference(context, context.client(), request_counter)
@@ -101,6 +115,7 @@ impl ActivityHandler for BlockUserFromCommunity {
     let community_user_ban_form = CommunityPersonBanForm {
       community_id: community.id,
       person_id: blocked_user.id,
+      expires: self.expires.map(|e| e.naive_local()),
     };
 
     blocking(context.pool(), move |conn: &'_ _| {
@@ -120,6 +135,20 @@ impl ActivityHandler for BlockUserFromCommunity {
     .await?
     .ok();
 
+    let form = ModBanFromCommunityForm {
+      mod_person_id: mod_person.id,
+      other_person_id: blocked_user.id,
+      community_id: community.id,
+      reason: self.summary,
+      banned: Some(true),
+      expires: self.expires.map(|e| e.naive_local()),
+      public: None,
+    };
+    blocking(context.pool(), move |conn| {
+      ModBanFromCommunity::create(conn, &form)
+    })
+    .await??;
+
     Ok(())
   }
 }
@@ -0,0 +1,143 @@
+use crate::{
+  activities::{
+    community::{announce::GetCommunity, send_activity_in_community},
+    generate_activity_id,
+    verify_activity,
+    verify_is_public,
+    verify_mod_action,
+    verify_person_in_community,
+  },
+  activity_lists::AnnouncableActivities,
+  objects::{community::ApubCommunity, person::ApubPerson, post::ApubPost},
+  protocol::activities::community::feature_post::{FeaturePost, FeatureType},
+};
+use activitystreams_kinds::public;
+use lemmy_api_common::blocking;
+use lemmy_apub_lib::{
+  data::Data,
+  object_id::ObjectId,
+  traits::{ActivityHandler, ActorType},
+};
+use lemmy_db_schema::{
+  source::{
+    moderator::{ModStickyPost, ModStickyPostForm},
+    post::Post,
+  },
+  traits::Crud,
+};
+use lemmy_utils::LemmyError;
+use lemmy_websocket::{send::send_post_ws_message, LemmyContext, UserOperation};
+
+impl FeaturePost {
+  #[tracing::instrument(skip_all)]
+  pub async fn send(
+    community: &ApubCommunity,
+    post: &ApubPost,
+    stickied: bool,
+    actor: &ApubPerson,
+    context: &LemmyContext,
+  ) -> Result<(), LemmyError> {
+    let kind = if stickied {
+      FeatureType::Feature
+    } else {
+      FeatureType::Unfeature
+    };
+    let id = generate_activity_id(
+      kind.clone(),
+      &context.settings().get_protocol_and_hostname(),
+    )?;
+    let feature = FeaturePost {
+      actor: ObjectId::new(actor.actor_id()),
+      to: vec![public()],
+      object: ObjectId::new(post.ap_id.clone()),
+      cc: vec![community.actor_id()],
+      target: ObjectId::new(community.actor_id()),
+      kind,
+      id: id.clone(),
+      unparsed: Default::default(),
+    };
+
+    let activity = AnnouncableActivities::FeaturePost(feature);
+    send_activity_in_community(activity, &id, actor, community, vec![], context).await
+  }
+}
+
+#[async_trait::async_trait(?Send)]
+impl ActivityHandler for FeaturePost {
+  type DataType = LemmyContext;
+
+  #[tracing::instrument(skip_all)]
+  async fn verify(
+    &self,
+    context: &Data<LemmyContext>,
+    request_counter: &mut i32,
+  ) -> Result<(), LemmyError> {
+    verify_is_public(&self.to, &self.cc)?;
+    verify_activity(&self.id, self.actor.inner(), &context.settings())?;
+    let community = self.get_community(context, request_counter).await?;
+    verify_person_in_community(&self.actor, &community, context, request_counter).await?;
+    verify_mod_action(&self.actor, &community, context, request_counter).await?;
+    let post = self
+      .object
+      .dereference(context, context.client(), request_counter)
+      .await?;
+    if post.community_id != community.id {
+      return Err(LemmyError::from_message("Post is in a different community"));
+    }
+    Ok(())
+  }
+
+  #[tracing::instrument(skip_all)]
+  async fn receive(
+    self,
+    context: &Data<LemmyContext>,
+    request_counter: &mut i32,
+  ) -> Result<(), LemmyError> {
+    let mod_person = self
+      .actor
+      .dereference(context, context.client(), request_counter)
+      .await?;
+    let post = self
+      .object
+      .dereference(context, context.client(), request_counter)
+      .await?;
+
+    // The post may already be in this state, if it was refetched while parsing the activity
+    let stickied = self.kind == FeatureType::Feature;
+    if post.stickied != stickied {
+      let post_id = post.id;
+      blocking(context.pool(), move |conn| {
+        Post::update_stickied(conn, post_id, stickied)
+      })
+      .await??;
+    }
+
+    let form = ModStickyPostForm {
+      mod_person_id: mod_person.id,
+      post_id: post.id,
+      stickied: Some(stickied),
+    };
+    blocking(context.pool(), move |conn| {
+      ModStickyPost::create(conn, &form)
+    })
+    .await??;
+
+    send_post_ws_message(post.id, UserOperation::StickyPost, None, None, context).await?;
+    Ok(())
+  }
+}
+
+#[async_trait::async_trait(?Send)]
+impl GetCommunity for FeaturePost {
+  #[tracing::instrument(skip_all)]
+  async fn get_community(
+    &self,
+    context: &LemmyContext,
+    request_counter: &mut i32,
+  ) -> Result<ApubCommunity, LemmyError> {
+    self
+      .target
+      .dereference(context, context.client(), request_counter)
+      .await
+  }
+}
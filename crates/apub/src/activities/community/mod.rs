@@ -1,6 +1,7 @@
 use crate::{
-  activities::send_lemmy_activity,
+  activities::send_lemmy_activity_with_context,
   activity_lists::AnnouncableActivities,
+  context::WithContext,
   objects::community::ApubCommunity,
   protocol::activities::community::announce::AnnounceActivity,
 };
@@ -12,7 +13,9 @@ use url::Url;
 pub mod add_mod;
 pub mod announce;
 pub mod block_user;
+pub mod feature_post;
 pub mod remove_mod;
+pub mod remove_user_content;
 pub mod report;
 pub mod undo_block_user;
 pub mod update;
@@ -27,7 +30,16 @@ pub(crate) async fn send_activity_in_community<T: ActorType>(
   context: &LemmyContext,
 ) -> Result<(), LemmyError> {
   inboxes.push(community.shared_inbox_or_inbox_url());
-  send_lemmy_activity(context, &activity, activity_id, actor, inboxes, false).await?;
+  let activity_with_context = with_context(&activity, &activity, context);
+  send_lemmy_activity_with_context(
+    context,
+    activity_with_context,
+    activity_id,
+    actor,
+    inboxes,
+    false,
+  )
+  .await?;
 
   if community.local {
     AnnounceActivity::send(activity, community, context).await?;
@@ -36,6 +48,21 @@ pub(crate) async fn send_activity_in_community<T: ActorType>(
   Ok(())
 }
 
+/// Deletions don't use any Lemmy extensions, so with `federation.minimal_deletion_context` they
+/// (and announces of them) are sent with only the activitystreams context, for compatibility
+/// with strict validators.
+pub(crate) fn with_context<T>(
+  activity: T,
+  object: &AnnouncableActivities,
+  context: &LemmyContext,
+) -> WithContext<T> {
+  if context.settings().federation.minimal_deletion_context && object.is_deletion() {
+    WithContext::new_minimal(activity)
+  } else {
+    WithContext::new(activity)
+  }
+}
+
 #[tracing::instrument(skip_all)]
 async fn get_community_from_moderators_url(
   moderators: &Url,
@@ -47,3 +74,59 @@ async fn get_community_from_moderators_url(
     .dereference(context, context.client(), request_counter)
     .await
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::objects::tests::{file_to_json_object, init_context};
+  use lemmy_apub_lib::activity_queue::create_activity_queue;
+  use serde_json::json;
+  use serial_test::serial;
+
+  fn parse(path: &str) -> AnnouncableActivities {
+    file_to_json_object(&format!("assets/lemmy/activities/{}", path))
+  }
+
+  #[test]
+  fn test_is_deletion() {
+    assert!(parse("deletion/delete_page.json").is_deletion());
+    assert!(parse("deletion/undo_delete_page.json").is_deletion());
+    assert!(parse("deletion/remove_page.json").is_deletion());
+    assert!(parse("deletion/undo_remove_page.json").is_deletion());
+    assert!(!parse("create_or_update/create_page.json").is_deletion());
+    assert!(!parse("community/update_community.json").is_deletion());
+  }
+
+  #[test]
+  fn test_minimal_context() {
+    let delete = parse("deletion/delete_page.json");
+    let json = serde_json::to_value(WithContext::new_minimal(&delete)).unwrap();
+    assert_eq!(
+      json!(["https://www.w3.org/ns/activitystreams"]),
+      json["@context"]
+    );
+    assert_eq!("Delete", json["type"]);
+  }
+
+  #[actix_rt::test]
+  #[serial]
+  async fn test_full_context_by_default() {
+    let client = reqwest::Client::new().into();
+    let manager = create_activity_queue(client);
+    let context = init_context(manager.queue_handle().clone());
+    assert!(!context.settings().federation.minimal_deletion_context);
+
+    let delete = parse("deletion/delete_page.json");
+    let json = serde_json::to_value(with_context(&delete, &delete, &context)).unwrap();
+    let full = serde_json::to_value(WithContext::new(())).unwrap();
+    assert_eq!(full["@context"], json["@context"]);
+    assert!(
+      json["@context"]
+        .as_array()
+        .map(Vec::len)
+        .unwrap_or_default()
+        > 1
+    );
+    assert_eq!("Delete", json["type"]);
+  }
+}
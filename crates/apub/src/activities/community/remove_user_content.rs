@@ -0,0 +1,264 @@
+use crate::{
+  activities::{
+    community::{announce::GetCommunity, send_activity_in_community},
+    generate_activity_id,
+    verify_activity,
+    verify_is_public,
+    verify_mod_action,
+    verify_person_in_community,
+  },
+  activity_lists::AnnouncableActivities,
+  objects::{community::ApubCommunity, person::ApubPerson},
+  protocol::activities::community::remove_user_content::{
+    RemoveUserContent,
+    RemoveUserContentType,
+  },
+};
+use activitystreams_kinds::public;
+use lemmy_api_common::blocking;
+use lemmy_apub_lib::{
+  data::Data,
+  object_id::ObjectId,
+  traits::{ActivityHandler, ActorType},
+};
+use lemmy_db_schema::{
+  source::{
+    comment::Comment,
+    moderator::{ModRemoveComment, ModRemoveCommentForm, ModRemovePost, ModRemovePostForm},
+    post::Post,
+  },
+  traits::Crud,
+};
+use lemmy_utils::LemmyError;
+use lemmy_websocket::LemmyContext;
+use tracing::info;
+
+impl RemoveUserContent {
+  /// Announces the removal of all content of `target` in the community, after it was removed
+  /// locally.
+  #[tracing::instrument(skip_all)]
+  pub async fn send(
+    community: &ApubCommunity,
+    target: &ApubPerson,
+    actor: &ApubPerson,
+    reason: Option<String>,
+    context: &LemmyContext,
+  ) -> Result<(), LemmyError> {
+    let kind = RemoveUserContentType::RemoveUserContent;
+    let id = generate_activity_id(
+      kind.clone(),
+      &context.settings().get_protocol_and_hostname(),
+    )?;
+    let remove = RemoveUserContent {
+      actor: ObjectId::new(actor.actor_id()),
+      to: vec![public()],
+      object: ObjectId::new(target.actor_id()),
+      cc: vec![community.actor_id()],
+      target: ObjectId::new(community.actor_id()),
+      kind,
+      summary: reason,
+      id: id.clone(),
+      unparsed: Default::default(),
+    };
+
+    let activity = AnnouncableActivities::RemoveUserContent(remove);
+    send_activity_in_community(activity, &id, actor, community, vec![], context).await
+  }
+}
+
+#[async_trait::async_trait(?Send)]
+impl ActivityHandler for RemoveUserContent {
+  type DataType = LemmyContext;
+
+  #[tracing::instrument(skip_all)]
+  async fn verify(
+    &self,
+    context: &Data<LemmyContext>,
+    request_counter: &mut i32,
+  ) -> Result<(), LemmyError> {
+    verify_is_public(&self.to, &self.cc)?;
+    verify_activity(&self.id, self.actor.inner(), &context.settings())?;
+    let community = self.get_community(context, request_counter).await?;
+    verify_person_in_community(&self.actor, &community, context, request_counter).await?;
+    verify_mod_action(&self.actor, &community, context, request_counter).await?;
+    Ok(())
+  }
+
+  #[tracing::instrument(skip_all)]
+  async fn receive(
+    self,
+    context: &Data<LemmyContext>,
+    request_counter: &mut i32,
+  ) -> Result<(), LemmyError> {
+    let community = self.get_community(context, request_counter).await?;
+    let mod_person = self
+      .actor
+      .dereference(context, context.client(), request_counter)
+      .await?;
+    let creator = self
+      .object
+      .dereference(context, context.client(), request_counter)
+      .await?;
+    // The content of local users is local, so it would need a review of each item
+    if creator.local && context.settings().federation.review_received_deletions {
+      info!(
+        "Ignoring removal of all content of local user {} in {}",
+        creator.actor_id, community.actor_id
+      );
+      return Ok(());
+    }
+
+    let mod_person_id = mod_person.id;
+    let creator_id = creator.id;
+    let community_id = community.id;
+    let reason = self.summary;
+    blocking(context.pool(), move |conn| -> Result<(), LemmyError> {
+      let posts = Post::update_removed_for_creator(conn, creator_id, Some(community_id), true)?;
+      for post in posts {
+        let form = ModRemovePostForm {
+          mod_person_id,
+          post_id: post.id,
+          reason: reason.clone(),
+          removed: Some(true),
+          reason_translations: None,
+          reason_override: false,
+          public: None,
+        };
+        ModRemovePost::create(conn, &form)?;
+      }
+      let comments =
+        Comment::update_removed_for_creator(conn, creator_id, Some(community_id), true)?;
+      for comment in comments {
+        let form = ModRemoveCommentForm {
+          mod_person_id,
+          comment_id: comment.id,
+          reason: reason.clone(),
+          removed: Some(true),
+          reason_translations: None,
+          reason_override: false,
+          public: None,
+        };
+        ModRemoveComment::create(conn, &form)?;
+      }
+      Ok(())
+    })
+    .await??;
+    Ok(())
+  }
+}
+
+#[async_trait::async_trait(?Send)]
+impl GetCommunity for RemoveUserContent {
+  #[tracing::instrument(skip_all)]
+  async fn get_community(
+    &self,
+    context: &LemmyContext,
+    request_counter: &mut i32,
+  ) -> Result<ApubCommunity, LemmyError> {
+    self
+      .target
+      .dereference(context, context.client(), request_counter)
+      .await
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::objects::tests::{file_to_json_object, init_context};
+  use lemmy_apub_lib::activity_queue::create_activity_queue;
+  use lemmy_db_schema::source::{
+    comment::CommentForm,
+    community::{Community, CommunityForm},
+    person::{Person, PersonForm},
+    post::PostForm,
+  };
+  use serial_test::serial;
+  use url::Url;
+
+  #[actix_rt::test]
+  #[serial]
+  async fn test_receive_remove_user_content() {
+    let client = reqwest::Client::new().into();
+    let manager = create_activity_queue(client);
+    let context = init_context(manager.queue_handle().clone());
+    let conn = context.pool().get().unwrap();
+
+    let remove: RemoveUserContent =
+      file_to_json_object("assets/lemmy/activities/community/remove_user_content.json");
+    let community_form = CommunityForm {
+      name: "main".to_string(),
+      title: "nada".to_owned(),
+      actor_id: Some(remove.target.inner().clone().into()),
+      local: Some(false),
+      ..CommunityForm::default()
+    };
+    let community = Community::create(&conn, &community_form).unwrap();
+    let other_community_form = CommunityForm {
+      name: "other".to_string(),
+      title: "nada".to_owned(),
+      actor_id: Some(
+        Url::parse("http://enterprise.lemmy.ml/c/other")
+          .unwrap()
+          .into(),
+      ),
+      local: Some(false),
+      ..CommunityForm::default()
+    };
+    let other_community = Community::create(&conn, &other_community_form).unwrap();
+    let create_person = |name: &str, actor_id: &Url| {
+      let form = PersonForm {
+        name: name.into(),
+        actor_id: Some(actor_id.clone().into()),
+        local: Some(false),
+        ..PersonForm::default()
+      };
+      Person::create(&conn, &form).unwrap()
+    };
+    let mod_person = create_person("lemmy_beta", remove.actor.inner());
+    let spammer = create_person("lemmy_alpha", remove.object.inner());
+    let create_post = |community_id| {
+      let form = PostForm {
+        name: "spam".into(),
+        creator_id: spammer.id,
+        community_id,
+        local: Some(false),
+        ..PostForm::default()
+      };
+      Post::create(&conn, &form).unwrap()
+    };
+    let post = create_post(community.id);
+    let other_post = create_post(other_community.id);
+    let comment_form = CommentForm {
+      content: "spam".into(),
+      creator_id: spammer.id,
+      post_id: post.id,
+      local: Some(false),
+      ..CommentForm::default()
+    };
+    let comment = Comment::create(&conn, &comment_form).unwrap();
+
+    let mut request_counter = 0;
+    remove
+      .receive(&Data::new(context.clone()), &mut request_counter)
+      .await
+      .unwrap();
+    let post_removed = Post::read(&conn, post.id).unwrap().removed;
+    let other_post_removed = Post::read(&conn, other_post.id).unwrap().removed;
+    let comment_removed = Comment::read(&conn, comment.id).unwrap().removed;
+    let comment_log = ModRemoveComment::read_latest_removal(&conn, comment.id)
+      .unwrap()
+      .unwrap();
+
+    Person::delete(&conn, spammer.id).unwrap();
+    Person::delete(&conn, mod_person.id).unwrap();
+    Community::delete(&conn, community.id).unwrap();
+    Community::delete(&conn, other_community.id).unwrap();
+
+    assert!(post_removed);
+    assert!(!other_post_removed);
+    assert!(comment_removed);
+    assert_eq!(mod_person.id, comment_log.mod_person_id);
+    assert_eq!(Some("spam".to_string()), comment_log.reason);
+  }
+}
@@ -5,6 +5,7 @@ use crate::{
     verify_activity,
     verify_person_in_community,
   },
+  fetcher::user_or_community::UserOrCommunity,
   objects::{community::ApubCommunity, person::ApubPerson},
   protocol::activities::community::report::Report,
   PostOrComment,
@@ -17,17 +18,24 @@ use lemmy_apub_lib::{
   traits::{ActivityHandler, ActorType},
 };
 use lemmy_db_schema::{
+  newtypes::CommunityId,
   source::{
     comment_report::{CommentReport, CommentReportForm},
+    person::Person,
+    post::Post,
     post_report::{PostReport, PostReportForm},
   },
-  traits::Reportable,
+  traits::{Crud, Reportable},
 };
 use lemmy_db_views::{comment_report_view::CommentReportView, post_report_view::PostReportView};
+use lemmy_db_views_actor::community_moderator_view::CommunityModeratorView;
 use lemmy_utils::LemmyError;
 use lemmy_websocket::{messages::SendModRoomMessage, LemmyContext, UserOperation};
+use url::Url;
 
 impl Report {
+  /// Sends the report as a Flag activity to the instance of the community. From there it is
+  /// forwarded to the instance where the reported post or comment was created.
   #[tracing::instrument(skip_all)]
   pub async fn send(
     object_id: ObjectId<PostOrComment>,
@@ -51,16 +59,111 @@ impl Report {
       id: id.clone(),
       unparsed: Default::default(),
     };
-    send_lemmy_activity(
-      context,
-      &report,
-      &id,
-      actor,
-      vec![community.shared_inbox_or_inbox_url()],
-      false,
-    )
-    .await
+    let inboxes = vec![community.shared_inbox_or_inbox_url()];
+    send_lemmy_activity(context, &report, &id, actor, inboxes, false).await?;
+    Report::forward(report.object, report.summary, &community, context).await
   }
+
+  /// Forwards a report in a local community to the instance where the reported content was
+  /// created, as its admins also need to know about it. The Flag is sent by the community, so that
+  /// the identity of the reporter doesn't leave the instances of the reporter and the community.
+  #[tracing::instrument(skip_all)]
+  async fn forward(
+    object_id: ObjectId<PostOrComment>,
+    reason: String,
+    community: &ApubCommunity,
+    context: &LemmyContext,
+  ) -> Result<(), LemmyError> {
+    if !community.local {
+      return Ok(());
+    }
+    let creator_inbox = match creator_inbox(&object_id, context).await? {
+      Some(i) => i,
+      None => return Ok(()),
+    };
+    let kind = FlagType::Flag;
+    let id = generate_activity_id(
+      kind.clone(),
+      &context.settings().get_protocol_and_hostname(),
+    )?;
+    let report = Report {
+      actor: ObjectId::new(community.actor_id()),
+      to: [ObjectId::new(community.actor_id())],
+      object: object_id,
+      summary: reason,
+      kind,
+      id: id.clone(),
+      unparsed: Default::default(),
+    };
+    send_lemmy_activity(context, &report, &id, community, vec![creator_inbox], false).await
+  }
+}
+
+/// Returns the inbox of the instance where the reported content originates, if that is a remote
+/// instance.
+async fn creator_inbox(
+  object_id: &ObjectId<PostOrComment>,
+  context: &LemmyContext,
+) -> Result<Option<Url>, LemmyError> {
+  let creator_id = match object_id.dereference_local(context).await {
+    Ok(PostOrComment::Post(p)) => p.creator_id,
+    Ok(PostOrComment::Comment(c)) => c.creator_id,
+    Err(_) => return Ok(None),
+  };
+  let creator: ApubPerson = blocking(context.pool(), move |conn| Person::read(conn, creator_id))
+    .await??
+    .into();
+  if creator.local {
+    return Ok(None);
+  }
+  Ok(Some(creator.shared_inbox_or_inbox_url()))
+}
+
+/// Reports which are forwarded by a community don't name the reporter, so they are attributed to
+/// the top moderator of the community instead.
+async fn top_moderator(
+  community_id: CommunityId,
+  context: &LemmyContext,
+) -> Result<ApubPerson, LemmyError> {
+  let moderators = blocking(context.pool(), move |conn| {
+    CommunityModeratorView::for_community(conn, community_id)
+  })
+  .await??;
+  let moderator_id = moderators
+    .first()
+    .map(|m| m.moderator.id)
+    .ok_or_else(|| LemmyError::from_message("community_has_no_moderators"))?;
+  let moderator = blocking(context.pool(), move |conn| Person::read(conn, moderator_id)).await??;
+  Ok(moderator.into())
+}
+
+/// A community can only forward reports about its own content
+async fn verify_forwarded_by_community(
+  report: &Report,
+  actor: &ApubCommunity,
+  community: &ApubCommunity,
+  context: &LemmyContext,
+  request_counter: &mut i32,
+) -> Result<(), LemmyError> {
+  let object_community_id = match report
+    .object
+    .dereference(context, context.client(), request_counter)
+    .await?
+  {
+    PostOrComment::Post(post) => post.community_id,
+    PostOrComment::Comment(comment) => {
+      let post_id = comment.post_id;
+      blocking(context.pool(), move |conn| Post::read(conn, post_id))
+        .await??
+        .community_id
+    }
+  };
+  if actor.actor_id() != community.actor_id() || object_community_id != community.id {
+    return Err(LemmyError::from_message(
+      "Report can only be forwarded by the community of the content",
+    ));
+  }
+  Ok(())
 }
 
 #[async_trait::async_trait(?Send)]
@@ -77,7 +180,19 @@ impl ActivityHandler for Report {
     let community = self.to[0]
       .dereference(context, context.client(), request_counter)
       .await?;
-    verify_person_in_community(&self.actor, &community, context, request_counter).await?;
+    match self
+      .actor
+      .dereference(context, context.client(), request_counter)
+      .await?
+    {
+      UserOrCommunity::User(person) => {
+        let person_id = ObjectId::new(person.actor_id());
+        verify_person_in_community(&person_id, &community, context, request_counter).await?;
+      }
+      UserOrCommunity::Community(actor) => {
+        verify_forwarded_by_community(self, &actor, &community, context, request_counter).await?;
+      }
+    }
     Ok(())
   }
 
@@ -87,10 +202,18 @@ impl ActivityHandler for Report {
     context: &Data<LemmyContext>,
     request_counter: &mut i32,
   ) -> Result<(), LemmyError> {
-    let actor = self
-      .actor
+    let community = self.to[0]
       .dereference(context, context.client(), request_counter)
       .await?;
+    let (actor, forward) = match self
+      .actor
+      .dereference(context, context.client(), request_counter)
+      .await?
+    {
+      UserOrCommunity::User(person) => (person, true),
+      UserOrCommunity::Community(_) => (top_moderator(community.id, context).await?, false),
+    };
+    let reason = self.summary.clone();
     match self
       .object
       .dereference(context, context.client(), request_counter)
@@ -117,7 +240,7 @@ impl ActivityHandler for Report {
         .await??;
 
         context.chat_server().do_send(SendModRoomMessage {
-          op: UserOperation::CreateCommentReport,
+          op: UserOperation::CreatePostReport,
           response: PostReportResponse { post_report_view },
           community_id: post.community_id,
           websocket_id: None,
@@ -152,6 +275,132 @@ impl ActivityHandler for Report {
         });
       }
     };
+    if forward {
+      Report::forward(self.object, reason, &community, context).await?;
+    }
     Ok(())
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::objects::tests::{file_to_json_object, init_context};
+  use diesel::{ExpressionMethods, QueryDsl, RunQueryDsl};
+  use lemmy_apub_lib::activity_queue::create_activity_queue;
+  use lemmy_db_schema::{
+    schema::post_report,
+    source::{
+      community::{Community, CommunityForm, CommunityModerator, CommunityModeratorForm},
+      person::PersonForm,
+      post::PostForm,
+    },
+    traits::Joinable,
+  };
+  use serial_test::serial;
+
+  #[actix_rt::test]
+  #[serial]
+  async fn test_receive_forwarded_report() {
+    let client = reqwest::Client::new().into();
+    let manager = create_activity_queue(client);
+    let context = init_context(manager.queue_handle().clone());
+    let conn = context.pool().get().unwrap();
+
+    let report: Report =
+      file_to_json_object("assets/lemmy/activities/community/report_page_forwarded.json");
+    let community_form = CommunityForm {
+      name: "main".to_string(),
+      title: "nada".to_owned(),
+      actor_id: Some(report.to[0].inner().clone().into()),
+      local: Some(false),
+      ..CommunityForm::default()
+    };
+    let community = Community::create(&conn, &community_form).unwrap();
+    let other_community_form = CommunityForm {
+      name: "other".to_string(),
+      title: "nada".to_owned(),
+      actor_id: Some(
+        Url::parse("http://enterprise.lemmy.ml/c/other")
+          .unwrap()
+          .into(),
+      ),
+      local: Some(false),
+      ..CommunityForm::default()
+    };
+    let other_community = Community::create(&conn, &other_community_form).unwrap();
+    let mod_form = PersonForm {
+      name: "lemmy_beta".into(),
+      actor_id: Some(
+        Url::parse("http://enterprise.lemmy.ml/u/lemmy_beta")
+          .unwrap()
+          .into(),
+      ),
+      local: Some(false),
+      ..PersonForm::default()
+    };
+    let mod_person = Person::create(&conn, &mod_form).unwrap();
+    let moderator_form = CommunityModeratorForm {
+      community_id: community.id,
+      person_id: mod_person.id,
+    };
+    CommunityModerator::join(&conn, &moderator_form).unwrap();
+    let creator_form = PersonForm {
+      name: "report_creator".into(),
+      ..PersonForm::default()
+    };
+    let creator = Person::create(&conn, &creator_form).unwrap();
+    let post_form = PostForm {
+      name: "reported".into(),
+      creator_id: creator.id,
+      community_id: community.id,
+      ap_id: Some(report.object.inner().clone().into()),
+      ..PostForm::default()
+    };
+    let post = Post::create(&conn, &post_form).unwrap();
+
+    // Another community can't forward reports about the post
+    let other_community: ApubCommunity = other_community.into();
+    let mut request_counter = 0;
+    let other_res = verify_forwarded_by_community(
+      &report,
+      &other_community,
+      &other_community,
+      &context,
+      &mut request_counter,
+    )
+    .await;
+    let community: ApubCommunity = community.into();
+    let own_res = verify_forwarded_by_community(
+      &report,
+      &community,
+      &community,
+      &context,
+      &mut request_counter,
+    )
+    .await;
+
+    report
+      .receive(&Data::new(context.clone()), &mut request_counter)
+      .await
+      .unwrap();
+    let reports = post_report::table
+      .filter(post_report::post_id.eq(post.id))
+      .load::<PostReport>(&conn)
+      .unwrap();
+
+    Person::delete(&conn, creator.id).unwrap();
+    Person::delete(&conn, mod_person.id).unwrap();
+    Community::delete(&conn, community.id).unwrap();
+    Community::delete(&conn, other_community.id).unwrap();
+
+    assert_eq!(
+      Some("Report can only be forwarded by the community of the content"),
+      other_res.unwrap_err().message
+    );
+    assert!(own_res.is_ok());
+    assert_eq!(1, reports.len());
+    assert_eq!(mod_person.id, reports[0].creator_id);
+    assert_eq!("report this post", reports[0].reason);
+  }
+}
@@ -22,8 +22,11 @@ use lemmy_apub_lib::{
   traits::{ActivityHandler, ActorType},
 };
 use lemmy_db_schema::{
-  source::community::{CommunityPersonBan, CommunityPersonBanForm},
-  traits::Bannable,
+  source::{
+    community::{CommunityPersonBan, CommunityPersonBanForm},
+    moderator::{ModBanFromCommunity, ModBanFromCommunityForm},
+  },
+  traits::{Bannable, Crud},
 };
 use lemmy_utils::LemmyError;
 use lemmy_websocket::LemmyContext;
@@ -34,9 +37,10 @@ impl UndoBlockUserFromCommunity {
     community: &ApubCommunity,
     target: &ApubPerson,
     actor: &ApubPerson,
+    reason: Option<String>,
     context: &LemmyContext,
   ) -> Result<(), LemmyError> {
-    let block = BlockUserFromCommunity::new(community, target, actor, context)?;
+    let block = BlockUserFromCommunity::new(community, target, actor, reason, None, context)?;
 
     let id = generate_activity_id(
       UndoType::Undo,
@@ -84,6 +88,10 @@ impl ActivityHandler for UndoBlockUserFromCommunity {
     request_counter: &mut i32,
   ) -> Result<(), LemmyError> {
     let community = self.get_community(context, request_counter).await?;
+    let mod_person = self
+      .actor
+      .dereference(context, context.client(), request_counter)
+      .await?;
     let blocked_user = self
       .object
       .object
@@ -93,6 +101,7 @@ impl ActivityHandler for UndoBlockUserFromCommunity {
     let community_user_ban_form = CommunityPersonBanForm {
       community_id: community.id,
       person_id: blocked_user.id,
+      expires: None,
     };
 
     blocking(context.pool(), move |conn: &'_ _| {
@@ -100,6 +109,20 @@ impl ActivityHandler for UndoBlockUserFromCommunity {
     })
     .await??;
 
+    let form = ModBanFromCommunityForm {
+      mod_person_id: mod_person.id,
+      other_person_id: blocked_user.id,
+      community_id: community.id,
+      reason: self.object.summary,
+      banned: Some(false),
+      expires: None,
+      public: None,
+    };
+    blocking(context.pool(), move |conn| {
+      ModBanFromCommunity::create(conn, &form)
+    })
+    .await??;
+
     Ok(())
   }
 }
@@ -1,7 +1,20 @@
 use crate::{
   activities::{
-    community::{announce::GetCommunity, send_activity_in_community},
-    deletion::{receive_delete_action, verify_delete_activity, DeletableObjects},
+    community::announce::GetCommunity,
+    deletion::{
+      clear_community_moderators,
+      get_deletable_object_community,
+      hold_unconfirmed_deletion,
+      moderators_collection_community,
+      receive_delete_action,
+      remove::receive_remove_action,
+      send_deletion,
+      store_for_review,
+      verify_delete_activity,
+      verify_delete_moderators_collection,
+      DeletableObjects,
+    },
+    federation_target::FederationTarget,
     generate_activity_id,
     verify_activity,
     verify_is_public,
@@ -11,35 +24,13 @@ use crate::{
   protocol::activities::deletion::delete::Delete,
 };
 use activitystreams_kinds::{activity::DeleteType, public};
-use lemmy_api_common::blocking;
 use lemmy_apub_lib::{
   data::Data,
   object_id::ObjectId,
   traits::{ActivityHandler, ActorType},
 };
-use lemmy_db_schema::{
-  source::{
-    comment::Comment,
-    community::Community,
-    moderator::{
-      ModRemoveComment,
-      ModRemoveCommentForm,
-      ModRemoveCommunity,
-      ModRemoveCommunityForm,
-      ModRemovePost,
-      ModRemovePostForm,
-    },
-    post::Post,
-  },
-  traits::Crud,
-};
 use lemmy_utils::LemmyError;
-use lemmy_websocket::{
-  send::{send_comment_ws_message_simple, send_community_ws_message, send_post_ws_message},
-  LemmyContext,
-  UserOperationCrud,
-};
-use url::Url;
+use lemmy_websocket::LemmyContext;
 
 #[async_trait::async_trait(?Send)]
 impl ActivityHandler for Delete {
@@ -53,9 +44,18 @@ impl ActivityHandler for Delete {
   ) -> Result<(), LemmyError> {
     verify_is_public(&self.to, &[])?;
     verify_activity(&self.id, self.actor.inner(), &context.settings())?;
+    if let Some(community) = moderators_collection_community(&self.object.id, context).await? {
+      return verify_delete_moderators_collection(
+        &self.actor,
+        &community,
+        context,
+        request_counter,
+      )
+      .await;
+    }
     let community = self.get_community(context, request_counter).await?;
     verify_delete_activity(
-      &self.object.id,
+      &self.object,
       &self.actor,
       &community,
       self.summary.is_some(),
@@ -72,9 +72,40 @@ impl ActivityHandler for Delete {
     context: &Data<LemmyContext>,
     request_counter: &mut i32,
   ) -> Result<(), LemmyError> {
+    if let Some(community) = moderators_collection_community(&self.object.id, context).await? {
+      return clear_community_moderators(&community, context).await;
+    }
+    let removal = self.summary.is_some();
+    if hold_unconfirmed_deletion(
+      &self.id,
+      &self.object.id,
+      &self.actor,
+      removal,
+      self.summary.clone().filter(|s| !s.is_empty()),
+      context,
+      request_counter,
+    )
+    .await?
+    {
+      return Ok(());
+    }
+    if store_for_review(
+      &self.id,
+      &self.object.id,
+      &self.actor,
+      removal,
+      self.summary.clone().filter(|s| !s.is_empty()),
+      false,
+      context,
+      request_counter,
+    )
+    .await?
+    {
+      return Ok(());
+    }
     if let Some(reason) = self.summary {
-      // We set reason to empty string if it doesn't exist, to distinguish between delete and
-      // remove. Here we change it back to option, so we don't write it to db.
+      // Older Lemmy versions send removals as Delete with a summary, which is set to an empty
+      // string if there is no reason. Here we change it back to option, so we don't write it to db.
       let reason = if reason.is_empty() {
         None
       } else {
@@ -82,14 +113,23 @@ impl ActivityHandler for Delete {
       };
       receive_remove_action(
         &self.actor,
-        &self.object.id,
+        &self.object,
         reason,
+        None,
         context,
         request_counter,
       )
       .await
     } else {
-      receive_delete_action(&self.object.id, &self.actor, true, context, request_counter).await
+      receive_delete_action(
+        &self.id,
+        &self.object,
+        &self.actor,
+        true,
+        context,
+        request_counter,
+      )
+      .await
     }
   }
 }
@@ -98,7 +138,6 @@ impl Delete {
   pub(in crate::activities::deletion) fn new(
     actor: &ApubPerson,
     object: DeletableObjects,
-    summary: Option<String>,
     context: &LemmyContext,
   ) -> Result<Delete, LemmyError> {
     Ok(Delete {
@@ -106,7 +145,7 @@ impl Delete {
       to: vec![public()],
       object: object.to_tombstone()?,
       kind: DeleteType::Delete,
-      summary,
+      summary: None,
       id: generate_activity_id(
         DeleteType::Delete,
         &context.settings().get_protocol_and_hostname(),
@@ -118,96 +157,18 @@ impl Delete {
   #[tracing::instrument(skip_all)]
   pub(in crate::activities::deletion) async fn send(
     actor: &ApubPerson,
-    community: &ApubCommunity,
     object: DeletableObjects,
-    summary: Option<String>,
+    target: &FederationTarget,
     context: &LemmyContext,
   ) -> Result<(), LemmyError> {
-    let delete = Delete::new(actor, object, summary, context)?;
+    let delete = Delete::new(actor, object, context)?;
     let delete_id = delete.id.clone();
 
     let activity = AnnouncableActivities::Delete(delete);
-    send_activity_in_community(activity, &delete_id, actor, community, vec![], context).await
+    send_deletion(activity, &delete_id, actor, target, context).await
   }
 }
 
-#[tracing::instrument(skip_all)]
-pub(in crate::activities) async fn receive_remove_action(
-  actor: &ObjectId<ApubPerson>,
-  object: &Url,
-  reason: Option<String>,
-  context: &LemmyContext,
-  request_counter: &mut i32,
-) -> Result<(), LemmyError> {
-  let actor = actor
-    .dereference(context, context.client(), request_counter)
-    .await?;
-  use UserOperationCrud::*;
-  match DeletableObjects::read_from_db(object, context).await? {
-    DeletableObjects::Community(community) => {
-      if community.local {
-        return Err(LemmyError::from_message(
-          "Only local admin can remove community",
-        ));
-      }
-      let form = ModRemoveCommunityForm {
-        mod_person_id: actor.id,
-        community_id: community.id,
-        removed: Some(true),
-        reason,
-        expires: None,
-      };
-      blocking(context.pool(), move |conn| {
-        ModRemoveCommunity::create(conn, &form)
-      })
-      .await??;
-      let deleted_community = blocking(context.pool(), move |conn| {
-        Community::update_removed(conn, community.id, true)
-      })
-      .await??;
-
-      send_community_ws_message(deleted_community.id, RemoveCommunity, None, None, context).await?;
-    }
-    DeletableObjects::Post(post) => {
-      let form = ModRemovePostForm {
-        mod_person_id: actor.id,
-        post_id: post.id,
-        removed: Some(true),
-        reason,
-      };
-      blocking(context.pool(), move |conn| {
-        ModRemovePost::create(conn, &form)
-      })
-      .await??;
-      let removed_post = blocking(context.pool(), move |conn| {
-        Post::update_removed(conn, post.id, true)
-      })
-      .await??;
-
-      send_post_ws_message(removed_post.id, RemovePost, None, None, context).await?;
-    }
-    DeletableObjects::Comment(comment) => {
-      let form = ModRemoveCommentForm {
-        mod_person_id: actor.id,
-        comment_id: comment.id,
-        removed: Some(true),
-        reason,
-      };
-      blocking(context.pool(), move |conn| {
-        ModRemoveComment::create(conn, &form)
-      })
-      .await??;
-      let removed_comment = blocking(context.pool(), move |conn| {
-        Comment::update_removed(conn, comment.id, true)
-      })
-      .await??;
-
-      send_comment_ws_message_simple(removed_comment.id, RemoveComment, context).await?;
-    }
-  }
-  Ok(())
-}
-
 #[async_trait::async_trait(?Send)]
 impl GetCommunity for Delete {
   #[tracing::instrument(skip_all)]
@@ -216,18 +177,9 @@ impl GetCommunity for Delete {
     context: &LemmyContext,
     _request_counter: &mut i32,
   ) -> Result<ApubCommunity, LemmyError> {
-    let community_id = match DeletableObjects::read_from_db(&self.object.id, context).await? {
-      DeletableObjects::Community(c) => c.id,
-      DeletableObjects::Comment(c) => {
-        let post = blocking(context.pool(), move |conn| Post::read(conn, c.post_id)).await??;
-        post.community_id
-      }
-      DeletableObjects::Post(p) => p.community_id,
-    };
-    let community = blocking(context.pool(), move |conn| {
-      Community::read(conn, community_id)
-    })
-    .await??;
-    Ok(community.into())
+    if let Some(community) = moderators_collection_community(&self.object.id, context).await? {
+      return Ok(community);
+    }
+    get_deletable_object_community(&self.object, context).await
   }
 }
@@ -1,24 +1,73 @@
 use crate::{
-  activities::{verify_mod_action, verify_person_in_community},
-  objects::{comment::ApubComment, community::ApubCommunity, person::ApubPerson, post::ApubPost},
+  activities::{
+    community::{announce::GetCommunity, send_activity_in_community},
+    deletion::{remove::receive_remove_action, undo_remove::receive_undo_remove_action},
+    federation_target::FederationTarget,
+    verify_mod_action,
+    verify_person_in_community,
+  },
+  activity_lists::AnnouncableActivities,
+  mark_activity_forwarded,
+  objects::{
+    comment::ApubComment,
+    community::ApubCommunity,
+    person::ApubPerson,
+    post::ApubPost,
+    wiki_page::ApubWikiPage,
+  },
   protocol::{
-    activities::deletion::{delete::Delete, undo_delete::UndoDelete},
-    objects::tombstone::Tombstone,
+    activities::deletion::{
+      delete::Delete,
+      remove::Remove,
+      undo_delete::UndoDelete,
+      undo_remove::UndoRemove,
+    },
+    objects::tombstone::{FormerType, Tombstone},
   },
 };
 use lemmy_api_common::blocking;
 use lemmy_apub_lib::{object_id::ObjectId, traits::ApubObject, verify::verify_domains_match};
-use lemmy_db_schema::source::{comment::Comment, community::Community, post::Post};
-use lemmy_utils::LemmyError;
+use lemmy_db_schema::{
+  newtypes::{CommunityId, LocalUserId, PersonId},
+  source::{
+    activity::Activity,
+    comment::Comment,
+    community::{Community, CommunityModerator},
+    community_wiki_page::CommunityWikiPage,
+    local_user::LocalUser,
+    pending_deletion::{PendingDeletion, PendingDeletionForm},
+    person::Person,
+    person_block::PersonBlock,
+    post::Post,
+    received_deletion::ReceivedDeletion,
+  },
+  traits::Crud,
+  DbPool,
+};
+use lemmy_db_views_actor::{
+  community_follower_view::CommunityFollowerView,
+  community_moderator_view::CommunityModeratorView,
+  community_view::CommunityView,
+  person_view::PersonViewSafe,
+};
+use lemmy_utils::{settings::structs::DeletionAttestationConfig, LemmyError};
 use lemmy_websocket::{
-  send::{send_comment_ws_message_simple, send_community_ws_message, send_post_ws_message},
+  send::{send_comment_ws_message, send_community_ws_message, send_post_ws_message},
   LemmyContext,
   UserOperationCrud,
 };
+use serde::{Deserialize, Serialize};
+use std::{
+  collections::{HashMap, HashSet},
+  time::Duration,
+};
+use tracing::{info, warn};
 use url::Url;
 
 pub mod delete;
+pub mod remove;
 pub mod undo_delete;
+pub mod undo_remove;
 
 #[tracing::instrument(skip_all)]
 pub async fn send_apub_delete(
@@ -26,37 +75,395 @@ pub async fn send_apub_delete(
   community: &ApubCommunity,
   object: DeletableObjects,
   deleted: bool,
+  target: FederationTarget,
   context: &LemmyContext,
 ) -> Result<(), LemmyError> {
   if deleted {
-    Delete::send(actor, community, object, None, context).await
+    Delete::send(actor, object, &target, context).await
   } else {
-    UndoDelete::send(actor, community, object, None, context).await
+    UndoDelete::send(actor, community, object, &target, context).await
   }
 }
 
-// TODO: remove reason is actually optional in lemmy. we set an empty string in that case, but its
-//       ugly
 #[tracing::instrument(skip_all)]
+#[allow(clippy::too_many_arguments)]
 pub async fn send_apub_remove(
   actor: &ApubPerson,
   community: &ApubCommunity,
   object: DeletableObjects,
-  reason: String,
+  reason: Option<String>,
+  reason_translations: Option<HashMap<String, String>>,
   removed: bool,
+  target: FederationTarget,
   context: &LemmyContext,
 ) -> Result<(), LemmyError> {
   if removed {
-    Delete::send(actor, community, object, Some(reason), context).await
+    Remove::send(
+      actor,
+      community,
+      object,
+      reason,
+      reason_translations,
+      &target,
+      context,
+    )
+    .await
+  } else {
+    UndoRemove::send(
+      actor,
+      community,
+      object,
+      reason,
+      reason_translations,
+      &target,
+      context,
+    )
+    .await
+  }
+}
+
+/// Sends a deletion activity to the target, unless `federation.hold_deletions` is enabled. In
+/// that case the activity is only stored, and sent out later by [`send_held_deletions`] (always to
+/// the community of the deleted object).
+#[tracing::instrument(skip_all)]
+pub(in crate::activities::deletion) async fn send_deletion(
+  activity: AnnouncableActivities,
+  activity_id: &Url,
+  actor: &ApubPerson,
+  target: &FederationTarget,
+  context: &LemmyContext,
+) -> Result<(), LemmyError> {
+  if context.settings().federation.hold_deletions {
+    info!("Holding back activity {}", activity_id);
+    let ap_id = activity_id.clone().into();
+    let data = serde_json::to_value(&activity)?;
+    blocking(context.pool(), move |conn| {
+      Activity::insert_held(conn, ap_id, data)
+    })
+    .await??;
+    return Ok(());
+  }
+  target.send(activity, activity_id, actor, context).await
+}
+
+/// Sends out all deletions which were held back while `federation.hold_deletions` was enabled,
+/// in the order they were made.
+#[tracing::instrument(skip_all)]
+pub async fn send_held_deletions(context: &LemmyContext) -> Result<(), LemmyError> {
+  if context.settings().federation.hold_deletions {
+    return Ok(());
+  }
+  let held = blocking(context.pool(), Activity::read_held).await??;
+  for held_activity in held {
+    let ap_id = held_activity.ap_id.clone();
+    if let Err(e) = send_held_deletion(held_activity, context).await {
+      warn!("Failed to send held activity {}: {}", ap_id, e);
+    }
+  }
+  Ok(())
+}
+
+#[derive(Deserialize)]
+struct HeldActivityActor {
+  actor: ObjectId<ApubPerson>,
+}
+
+async fn send_held_deletion(
+  held_activity: Activity,
+  context: &LemmyContext,
+) -> Result<(), LemmyError> {
+  let HeldActivityActor { actor } = serde_json::from_value(held_activity.data.clone())?;
+  let activity: AnnouncableActivities = serde_json::from_value(held_activity.data)?;
+  let actor = actor.dereference_local(context).await?;
+  let community = activity.get_community(context, &mut 0).await?;
+
+  // The activity stays held until it was sent, so that it is retried if sending fails
+  let activity_id: Url = held_activity.ap_id.into();
+  send_activity_in_community(activity, &activity_id, &actor, &community, vec![], context).await?;
+  let held_id = held_activity.id;
+  blocking(context.pool(), move |conn| {
+    Activity::release_held(conn, held_id)
+  })
+  .await??;
+  Ok(())
+}
+
+/// If `federation.review_received_deletions` is enabled, deletions and removals of local content
+/// are stored for review by local mods, instead of applying them directly. The same goes for undos
+/// of them, with `restore` set. Returns true in that case.
+#[allow(clippy::too_many_arguments)]
+#[tracing::instrument(skip_all)]
+pub(in crate::activities::deletion) async fn store_for_review(
+  activity_id: &Url,
+  object: &Url,
+  actor: &ObjectId<ApubPerson>,
+  removal: bool,
+  reason: Option<String>,
+  restore: bool,
+  context: &LemmyContext,
+  request_counter: &mut i32,
+) -> Result<bool, LemmyError> {
+  if !context.settings().federation.review_received_deletions {
+    return Ok(false);
+  }
+  let (local, community_id) = match DeletableObjects::read_from_db(object, context).await? {
+    DeletableObjects::Community(c) => (c.local, c.id),
+    DeletableObjects::Post(p) => (p.local, p.community_id),
+    DeletableObjects::Comment(c) => {
+      let post_id = c.post_id;
+      let post = blocking(context.pool(), move |conn| Post::read(conn, post_id)).await??;
+      (c.local, post.community_id)
+    }
+    DeletableObjects::WikiPage(p) => (p.local, p.community_id),
+  };
+  if !local {
+    return Ok(false);
+  }
+
+  store_pending_deletion(
+    activity_id,
+    object,
+    actor,
+    community_id,
+    removal,
+    reason,
+    restore,
+    context,
+    request_counter,
+  )
+  .await?;
+  Ok(true)
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn store_pending_deletion(
+  activity_id: &Url,
+  object: &Url,
+  actor: &ObjectId<ApubPerson>,
+  community_id: CommunityId,
+  removal: bool,
+  reason: Option<String>,
+  restore: bool,
+  context: &LemmyContext,
+  request_counter: &mut i32,
+) -> Result<(), LemmyError> {
+  let actor = actor
+    .dereference(context, context.client(), request_counter)
+    .await?;
+  let form = PendingDeletionForm {
+    actor_id: actor.id,
+    community_id,
+    activity_id: activity_id.clone().into(),
+    object_id: object.clone().into(),
+    removal,
+    reason,
+    restore,
+  };
+  blocking(context.pool(), move |conn| {
+    PendingDeletion::create(conn, &form)
+  })
+  .await??;
+  if restore {
+    return Ok(());
+  }
+  mark_held(activity_id, context).await
+}
+
+/// Notes in the log of received deletions that this one wasn't applied, so that undoing it is
+/// ignored
+async fn mark_held(activity_id: &Url, context: &LemmyContext) -> Result<(), LemmyError> {
+  let activity_id = activity_id.clone().into();
+  blocking(context.pool(), move |conn| {
+    ReceivedDeletion::update_held(conn, &activity_id, true)
+  })
+  .await??;
+  Ok(())
+}
+
+/// Response of the attestation endpoint, see `federation.deletion_attestation`
+#[derive(Deserialize, Serialize)]
+pub(crate) struct Attestation {
+  pub(crate) confirmed: bool,
+}
+
+/// If the deletion comes from one of the partner instances in `federation.deletion_attestation`,
+/// asks the partner to confirm it. Unconfirmed deletions are either discarded or stored for review
+/// by local mods. Returns true if the deletion must not be applied now.
+#[tracing::instrument(skip_all)]
+pub(in crate::activities::deletion) async fn hold_unconfirmed_deletion(
+  activity_id: &Url,
+  object: &Url,
+  actor: &ObjectId<ApubPerson>,
+  removal: bool,
+  reason: Option<String>,
+  context: &LemmyContext,
+  request_counter: &mut i32,
+) -> Result<bool, LemmyError> {
+  let config = match context.settings().federation.deletion_attestation {
+    Some(c) => c,
+    None => return Ok(false),
+  };
+  let endpoint = match attestation_endpoint(&config, actor.inner(), activity_id)? {
+    Some(e) => e,
+    None => return Ok(false),
+  };
+  let confirmed = match request_attestation(&endpoint, config.timeout, context).await {
+    Ok(c) => c,
+    Err(e) => {
+      warn!("Failed to get attestation for {}: {}", activity_id, e);
+      false
+    }
+  };
+  if confirmed {
+    return Ok(false);
+  }
+
+  if config.discard_unconfirmed {
+    info!("Discarding unconfirmed deletion {}", activity_id);
+    mark_held(activity_id, context).await?;
+  } else {
+    let tombstone = Tombstone::new(object.clone());
+    let community = get_deletable_object_community(&tombstone, context).await?;
+    store_pending_deletion(
+      activity_id,
+      object,
+      actor,
+      community.id,
+      removal,
+      reason,
+      false,
+      context,
+      request_counter,
+    )
+    .await?;
+  }
+  Ok(true)
+}
+
+/// Returns the url where the partner instance of the actor confirms the deletion, or None if the
+/// actor isn't from a partner instance.
+fn attestation_endpoint(
+  config: &DeletionAttestationConfig,
+  actor: &Url,
+  activity_id: &Url,
+) -> Result<Option<Url>, LemmyError> {
+  let is_partner = actor
+    .domain()
+    .map(|d| config.partner_instances.iter().any(|p| p == d))
+    .unwrap_or(false);
+  if !is_partner {
+    return Ok(None);
+  }
+  let mut endpoint = actor.join(&config.endpoint)?;
+  endpoint
+    .query_pairs_mut()
+    .append_pair("activity", activity_id.as_str());
+  Ok(Some(endpoint))
+}
+
+/// Serving side of the attestation: whether the activity is a deletion which was sent from this
+/// instance.
+pub(crate) async fn is_local_deletion(
+  activity_id: Url,
+  context: &LemmyContext,
+) -> Result<bool, LemmyError> {
+  let activity_id = activity_id.into();
+  let activity = blocking(context.pool(), move |conn| {
+    Activity::read_from_apub_id(conn, &activity_id).ok()
+  })
+  .await?;
+  Ok(
+    activity
+      .map(|a| a.local && a.data.get("type").and_then(|t| t.as_str()) == Some("Delete"))
+      .unwrap_or(false),
+  )
+}
+
+async fn request_attestation(
+  endpoint: &Url,
+  timeout: u64,
+  context: &LemmyContext,
+) -> Result<bool, LemmyError> {
+  let attestation: Attestation = context
+    .client()
+    .get(endpoint.as_str())
+    .timeout(Duration::from_secs(timeout))
+    .send()
+    .await?
+    .error_for_status()?
+    .json()
+    .await?;
+  Ok(attestation.confirmed)
+}
+
+/// Applies a deletion which was stored by [`store_for_review`], after it was approved by a mod. For
+/// an undo, the object is restored instead.
+#[tracing::instrument(skip_all)]
+pub async fn apply_pending_deletion(
+  pending: PendingDeletion,
+  context: &LemmyContext,
+) -> Result<(), LemmyError> {
+  let actor_id = pending.actor_id;
+  let actor = blocking(context.pool(), move |conn| Person::read(conn, actor_id)).await??;
+  let actor = ObjectId::new(actor.actor_id);
+  let object = Tombstone::new(pending.object_id.into());
+  let activity_id = pending.activity_id;
+  let request_counter = &mut 0;
+  if pending.restore {
+    return if pending.removal {
+      receive_undo_remove_action(&object.id, context).await
+    } else {
+      receive_delete_action(
+        &activity_id.into(),
+        &object,
+        &actor,
+        false,
+        context,
+        request_counter,
+      )
+      .await
+    };
+  }
+  if pending.removal {
+    receive_remove_action(
+      &actor,
+      &object,
+      pending.reason,
+      None,
+      context,
+      request_counter,
+    )
+    .await?;
   } else {
-    UndoDelete::send(actor, community, object, Some(reason), context).await
+    receive_delete_action(
+      &activity_id.clone().into(),
+      &object,
+      &actor,
+      true,
+      context,
+      request_counter,
+    )
+    .await?;
   }
+  blocking(context.pool(), move |conn| {
+    ReceivedDeletion::update_held(conn, &activity_id, false)
+  })
+  .await??;
+  Ok(())
+}
+
+/// Whether an object was deleted by its creator, or removed by a mod or admin
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DeletionAction {
+  Delete,
+  Remove,
 }
 
 pub enum DeletableObjects {
   Community(Box<ApubCommunity>),
   Comment(Box<ApubComment>),
   Post(Box<ApubPost>),
+  WikiPage(Box<ApubWikiPage>),
 }
 
 impl DeletableObjects {
@@ -74,28 +481,220 @@ impl DeletableObjects {
     if let Some(c) = ApubComment::read_from_apub_id(ap_id.clone(), context).await? {
       return Ok(DeletableObjects::Comment(Box::new(c)));
     }
+    if let Some(p) = ApubWikiPage::read_from_apub_id(ap_id.clone(), context).await? {
+      return Ok(DeletableObjects::WikiPage(Box::new(p)));
+    }
     Err(diesel::NotFound.into())
   }
 
+  /// Reads the deleted object directly from the table given by the type in the tombstone. Without
+  /// type (as sent by older Lemmy versions), each table is tried in turn.
+  #[tracing::instrument(skip_all)]
+  pub(crate) async fn read_from_tombstone(
+    tombstone: &Tombstone,
+    context: &LemmyContext,
+  ) -> Result<DeletableObjects, LemmyError> {
+    let ap_id = tombstone.id.clone();
+    let object = match tombstone.former_type {
+      Some(FormerType::Group) => ApubCommunity::read_from_apub_id(ap_id, context)
+        .await?
+        .map(|c| DeletableObjects::Community(Box::new(c))),
+      Some(FormerType::Page) => ApubPost::read_from_apub_id(ap_id, context)
+        .await?
+        .map(|p| DeletableObjects::Post(Box::new(p))),
+      Some(FormerType::Note) => ApubComment::read_from_apub_id(ap_id, context)
+        .await?
+        .map(|c| DeletableObjects::Comment(Box::new(c))),
+      Some(FormerType::Article) => ApubWikiPage::read_from_apub_id(ap_id, context)
+        .await?
+        .map(|p| DeletableObjects::WikiPage(Box::new(p))),
+      Some(FormerType::Other) | None => {
+        return DeletableObjects::read_from_db(&ap_id, context).await
+      }
+    };
+    object.ok_or_else(|| diesel::NotFound.into())
+  }
+
+  /// Returns the persons who should be notified when `actor_id` deletes or removes this object:
+  ///
+  /// - community: its mods and followers, plus the admins if it was removed
+  /// - post or comment: the mods of its community, plus the creator if it was removed
+  /// - wiki page: the mods of its community
+  ///
+  /// Each person is returned only once. The actor and everyone blocking the actor are excluded.
+  pub async fn notification_recipients(
+    &self,
+    action: DeletionAction,
+    actor_id: PersonId,
+    pool: &DbPool,
+  ) -> Result<Vec<PersonId>, LemmyError> {
+    let (community_id, creator_id) = match self {
+      DeletableObjects::Community(c) => (c.id, None),
+      DeletableObjects::Post(p) => (p.community_id, Some(p.creator_id)),
+      DeletableObjects::Comment(c) => {
+        let post_id = c.post_id;
+        let post = blocking(pool, move |conn| Post::read(conn, post_id)).await??;
+        (post.community_id, Some(c.creator_id))
+      }
+      DeletableObjects::WikiPage(p) => (p.community_id, None),
+    };
+    let is_community = matches!(self, DeletableObjects::Community(_));
+
+    blocking(pool, move |conn| {
+      let mut recipients = HashSet::new();
+      recipients.extend(community_mods(conn, community_id)?);
+      if is_community {
+        recipients.extend(
+          CommunityFollowerView::for_community(conn, community_id)?
+            .into_iter()
+            .map(|f| f.follower.id),
+        );
+      }
+      if action == DeletionAction::Remove {
+        if is_community {
+          recipients.extend(
+            PersonViewSafe::admins(conn)?
+              .into_iter()
+              .map(|a| a.person.id),
+          );
+        }
+        recipients.extend(creator_id);
+      }
+
+      recipients.remove(&actor_id);
+      for blocker in PersonBlock::read_blockers(conn, actor_id)? {
+        recipients.remove(&blocker);
+      }
+      Ok(recipients.into_iter().collect())
+    })
+    .await?
+  }
+
+  /// Local users among the [`Self::notification_recipients`], for websocket notifications
+  pub async fn local_notification_recipients(
+    &self,
+    action: DeletionAction,
+    actor_id: PersonId,
+    pool: &DbPool,
+  ) -> Result<Vec<LocalUserId>, LemmyError> {
+    let person_ids = self.notification_recipients(action, actor_id, pool).await?;
+    Ok(
+      blocking(pool, move |conn| {
+        LocalUser::ids_for_persons(conn, person_ids)
+      })
+      .await??,
+    )
+  }
+
   pub(crate) fn to_tombstone(&self) -> Result<Tombstone, LemmyError> {
     match self {
       DeletableObjects::Community(c) => c.to_tombstone(),
       DeletableObjects::Comment(c) => c.to_tombstone(),
       DeletableObjects::Post(p) => p.to_tombstone(),
+      DeletableObjects::WikiPage(p) => p.to_tombstone(),
     }
   }
 }
 
+fn community_mods(
+  conn: &diesel::PgConnection,
+  community_id: CommunityId,
+) -> Result<Vec<PersonId>, diesel::result::Error> {
+  Ok(
+    CommunityModeratorView::for_community(conn, community_id)?
+      .into_iter()
+      .map(|m| m.moderator.id)
+      .collect(),
+  )
+}
+
+/// If `object` is the moderators collection of a remote community, returns that community. The
+/// moderators of local communities can't be deleted by other instances.
 #[tracing::instrument(skip_all)]
-pub(in crate::activities) async fn verify_delete_activity(
+pub(in crate::activities::deletion) async fn moderators_collection_community(
   object: &Url,
+  context: &LemmyContext,
+) -> Result<Option<ApubCommunity>, LemmyError> {
+  let community_id = match object.as_str().strip_suffix("/moderators") {
+    Some(c) => Url::parse(c)?,
+    None => return Ok(None),
+  };
+  let community = ApubCommunity::read_from_apub_id(community_id, context).await?;
+  Ok(community.filter(|c| !c.local))
+}
+
+/// The moderators collection can only be deleted from the community's own instance, by one of its
+/// mods or admins.
+#[tracing::instrument(skip_all)]
+pub(in crate::activities::deletion) async fn verify_delete_moderators_collection(
+  actor: &ObjectId<ApubPerson>,
+  community: &ApubCommunity,
+  context: &LemmyContext,
+  request_counter: &mut i32,
+) -> Result<(), LemmyError> {
+  verify_domains_match(actor.inner(), &community.actor_id.clone().into())?;
+  let actor = actor
+    .dereference(context, context.client(), request_counter)
+    .await?;
+  let actor_id = actor.id;
+  let community_id = community.id;
+  let is_mod_or_admin = blocking(context.pool(), move |conn| {
+    CommunityView::is_mod_or_admin(conn, actor_id, community_id)
+  })
+  .await?;
+  if !is_mod_or_admin {
+    return Err(LemmyError::from_message("not_a_mod_or_admin"));
+  }
+  Ok(())
+}
+
+/// Removes all local moderator records of a community, after its moderators collection was
+/// deleted.
+#[tracing::instrument(skip_all)]
+pub(in crate::activities::deletion) async fn clear_community_moderators(
+  community: &ApubCommunity,
+  context: &LemmyContext,
+) -> Result<(), LemmyError> {
+  let community_id = community.id;
+  blocking(context.pool(), move |conn| {
+    CommunityModerator::delete_for_community(conn, community_id)
+  })
+  .await??;
+  Ok(())
+}
+
+/// Returns the community which the deleted or removed object belongs to
+#[tracing::instrument(skip_all)]
+pub(in crate::activities::deletion) async fn get_deletable_object_community(
+  object: &Tombstone,
+  context: &LemmyContext,
+) -> Result<ApubCommunity, LemmyError> {
+  let community_id = match DeletableObjects::read_from_tombstone(object, context).await? {
+    DeletableObjects::Community(c) => c.id,
+    DeletableObjects::Comment(c) => {
+      let post = blocking(context.pool(), move |conn| Post::read(conn, c.post_id)).await??;
+      post.community_id
+    }
+    DeletableObjects::Post(p) => p.community_id,
+    DeletableObjects::WikiPage(p) => p.community_id,
+  };
+  let community = blocking(context.pool(), move |conn| {
+    Community::read(conn, community_id)
+  })
+  .await??;
+  Ok(community.into())
+}
+
+#[tracing::instrument(skip_all)]
+pub(in crate::activities) async fn verify_delete_activity(
+  object: &Tombstone,
   actor: &ObjectId<ApubPerson>,
   community: &ApubCommunity,
   is_mod_action: bool,
   context: &LemmyContext,
   request_counter: &mut i32,
 ) -> Result<(), LemmyError> {
-  let object = DeletableObjects::read_from_db(object, context).await?;
+  let object = DeletableObjects::read_from_tombstone(object, context).await?;
   match object {
     DeletableObjects::Community(community) => {
       if community.local {
@@ -128,6 +727,11 @@ pub(in crate::activities) async fn verify_delete_activity(
       )
       .await?;
     }
+    DeletableObjects::WikiPage(_) => {
+      // like creating and editing, deleting wiki pages is only allowed for mods
+      verify_person_in_community(actor, community, context, request_counter).await?;
+      verify_mod_action(actor, community, context, request_counter).await?;
+    }
   }
   Ok(())
 }
@@ -156,20 +760,23 @@ async fn verify_delete_activity_post_or_comment(
 ///       because of the mod log
 #[tracing::instrument(skip_all)]
 async fn receive_delete_action(
-  object: &Url,
+  activity_id: &Url,
+  object: &Tombstone,
   actor: &ObjectId<ApubPerson>,
   deleted: bool,
   context: &LemmyContext,
   request_counter: &mut i32,
 ) -> Result<(), LemmyError> {
-  match DeletableObjects::read_from_db(object, context).await? {
+  match DeletableObjects::read_from_tombstone(object, context).await? {
     DeletableObjects::Community(community) => {
-      if community.local {
+      // Forward the action to community followers, but only once per received activity
+      if community.local && mark_activity_forwarded(activity_id, context.pool()).await? {
         let mod_ = actor
           .dereference(context, context.client(), request_counter)
           .await?;
         let object = DeletableObjects::Community(community.clone());
-        send_apub_delete(&mod_, &community.clone(), object, true, context).await?;
+        let target = FederationTarget::Community(community.id);
+        send_apub_delete(&mod_, &community.clone(), object, deleted, target, context).await?;
       }
 
       let community = blocking(context.pool(), move |conn| {
@@ -203,18 +810,531 @@ async fn receive_delete_action(
     }
     DeletableObjects::Comment(comment) => {
       if deleted != comment.deleted {
+        let recipient_ids = DeletableObjects::Comment(comment.clone())
+          .local_notification_recipients(DeletionAction::Delete, comment.creator_id, context.pool())
+          .await?;
         let deleted_comment = blocking(context.pool(), move |conn| {
           Comment::update_deleted(conn, comment.id, deleted)
         })
         .await??;
-        send_comment_ws_message_simple(
+        send_comment_ws_message(
           deleted_comment.id,
           UserOperationCrud::DeleteComment,
+          None,
+          None,
+          None,
+          recipient_ids,
           context,
         )
         .await?;
       }
     }
+    DeletableObjects::WikiPage(wiki_page) => {
+      if deleted != wiki_page.deleted {
+        blocking(context.pool(), move |conn| {
+          CommunityWikiPage::update_deleted(conn, wiki_page.id, deleted)
+        })
+        .await??;
+      }
+    }
   }
   Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{
+    objects::tests::{file_to_json_object, init_context},
+    protocol::activities::deletion::undo_delete::UndoDelete,
+  };
+  use diesel::{ExpressionMethods, QueryDsl, RunQueryDsl};
+  use lemmy_apub_lib::{
+    activity_queue::create_activity_queue,
+    data::Data,
+    traits::ActivityHandler,
+  };
+  use lemmy_db_schema::{
+    newtypes::DbUrl,
+    schema::received_deletion,
+    source::{
+      community::{CommunityForm, CommunityModeratorForm},
+      local_user::LocalUserForm,
+      person::PersonForm,
+      person_block::PersonBlockForm,
+      post::PostForm,
+    },
+    traits::{Blockable, Joinable},
+  };
+  use serial_test::serial;
+
+  #[actix_rt::test]
+  #[serial]
+  async fn test_delete_moderators_collection() {
+    let client = reqwest::Client::new().into();
+    let manager = create_activity_queue(client);
+    let context = init_context(manager.queue_handle().clone());
+    let conn = context.pool().get().unwrap();
+
+    let community_url = Url::parse("https://enterprise.lemmy.ml/c/mods_dissolved").unwrap();
+    let community_form = CommunityForm {
+      name: "mods_dissolved".to_string(),
+      title: "nada".to_owned(),
+      actor_id: Some(community_url.clone().into()),
+      local: Some(false),
+      ..CommunityForm::default()
+    };
+    let community = Community::create(&conn, &community_form).unwrap();
+    let local_community_form = CommunityForm {
+      name: "mods_not_dissolved".to_string(),
+      title: "nada".to_owned(),
+      ..CommunityForm::default()
+    };
+    let local_community = Community::create(&conn, &local_community_form).unwrap();
+    let person_form = PersonForm {
+      name: "dissolved_mod".into(),
+      local: Some(false),
+      ..PersonForm::default()
+    };
+    let person = Person::create(&conn, &person_form).unwrap();
+    for community_id in [community.id, local_community.id] {
+      let form = CommunityModeratorForm {
+        community_id,
+        person_id: person.id,
+      };
+      CommunityModerator::join(&conn, &form).unwrap();
+    }
+
+    let moderators_url = Url::parse(&format!("{}/moderators", community_url)).unwrap();
+    let found = moderators_collection_community(&moderators_url, &context)
+      .await
+      .unwrap()
+      .unwrap();
+    let local_moderators_url =
+      Url::parse(&format!("{}/moderators", local_community.actor_id)).unwrap();
+    let found_local = moderators_collection_community(&local_moderators_url, &context)
+      .await
+      .unwrap();
+    let found_community_url = moderators_collection_community(&community_url, &context)
+      .await
+      .unwrap();
+
+    clear_community_moderators(&found, &context).await.unwrap();
+    let mods = CommunityModeratorView::for_community(&conn, community.id).unwrap();
+    let local_mods = CommunityModeratorView::for_community(&conn, local_community.id).unwrap();
+
+    Person::delete(&conn, person.id).unwrap();
+    Community::delete(&conn, community.id).unwrap();
+    Community::delete(&conn, local_community.id).unwrap();
+
+    assert_eq!(community.id, found.id);
+    assert!(found_local.is_none());
+    assert!(found_community_url.is_none());
+    assert!(mods.is_empty());
+    assert_eq!(1, local_mods.len());
+  }
+
+  #[actix_rt::test]
+  #[serial]
+  async fn test_notification_recipients() {
+    let client = reqwest::Client::new().into();
+    let manager = create_activity_queue(client);
+    let context = init_context(manager.queue_handle().clone());
+    let conn = context.pool().get().unwrap();
+
+    let community_form = CommunityForm {
+      name: "notified_removal".to_string(),
+      title: "nada".to_owned(),
+      ..CommunityForm::default()
+    };
+    let community = Community::create(&conn, &community_form).unwrap();
+    let mut persons = vec![];
+    for name in ["notify_actor", "notify_creator", "notify_blocker"] {
+      let form = PersonForm {
+        name: name.into(),
+        ..PersonForm::default()
+      };
+      let person = Person::create(&conn, &form).unwrap();
+      let form = CommunityModeratorForm {
+        community_id: community.id,
+        person_id: person.id,
+      };
+      CommunityModerator::join(&conn, &form).unwrap();
+      persons.push(person);
+    }
+    let (actor, creator, blocker) = (&persons[0], &persons[1], &persons[2]);
+    let block_form = PersonBlockForm {
+      person_id: blocker.id,
+      target_id: actor.id,
+      prevent_interaction: None,
+    };
+    PersonBlock::block(&conn, &block_form).unwrap();
+    let local_user_form = LocalUserForm {
+      person_id: Some(creator.id),
+      password_encrypted: Some("notify_pass".to_string()),
+      ..LocalUserForm::default()
+    };
+    let creator_local_user = LocalUser::create(&conn, &local_user_form).unwrap();
+    let post_form = PostForm {
+      name: "notified removal".into(),
+      creator_id: creator.id,
+      community_id: community.id,
+      ..PostForm::default()
+    };
+    let post = Post::create(&conn, &post_form).unwrap();
+    let object = DeletableObjects::Post(Box::new(post.clone().into()));
+
+    // The creator is a mod as well, but only notified once. The blocker is not notified.
+    let removal = object
+      .notification_recipients(DeletionAction::Remove, actor.id, context.pool())
+      .await
+      .unwrap();
+    let mut deletion = object
+      .notification_recipients(DeletionAction::Delete, creator.id, context.pool())
+      .await
+      .unwrap();
+    deletion.sort_by_key(|p| p.0);
+    let local_removal = object
+      .local_notification_recipients(DeletionAction::Remove, actor.id, context.pool())
+      .await
+      .unwrap();
+
+    Post::delete(&conn, post.id).unwrap();
+    for person in &persons {
+      Person::delete(&conn, person.id).unwrap();
+    }
+    Community::delete(&conn, community.id).unwrap();
+
+    assert_eq!(vec![creator.id], removal);
+    assert_eq!(vec![actor.id, blocker.id], deletion);
+    assert_eq!(vec![creator_local_user.id], local_removal);
+  }
+
+  #[actix_rt::test]
+  #[serial]
+  async fn test_undo_delete_without_applied_delete() {
+    let client = reqwest::Client::new().into();
+    let manager = create_activity_queue(client);
+    let context = init_context(manager.queue_handle().clone());
+    let conn = context.pool().get().unwrap();
+
+    let undo: UndoDelete =
+      file_to_json_object("assets/lemmy/activities/deletion/undo_delete_page.json");
+    let community_form = CommunityForm {
+      name: "undo_unapplied".to_string(),
+      title: "nada".to_owned(),
+      actor_id: Some(
+        Url::parse("http://enterprise.lemmy.ml/c/undo_unapplied")
+          .unwrap()
+          .into(),
+      ),
+      local: Some(false),
+      ..CommunityForm::default()
+    };
+    let community = Community::create(&conn, &community_form).unwrap();
+    let person_form = PersonForm {
+      name: "undo_unapplied_creator".into(),
+      local: Some(false),
+      ..PersonForm::default()
+    };
+    let person = Person::create(&conn, &person_form).unwrap();
+    // The post was hidden by some other action, the delete which is undone never reached us
+    let post_form = PostForm {
+      name: "undo unapplied".into(),
+      creator_id: person.id,
+      community_id: community.id,
+      deleted: Some(true),
+      ap_id: Some(undo.object.object.id.clone().into()),
+      local: Some(false),
+      ..PostForm::default()
+    };
+    let post = Post::create(&conn, &post_form).unwrap();
+
+    let mut request_counter = 0;
+    undo
+      .receive(&Data::new(context.clone()), &mut request_counter)
+      .await
+      .unwrap();
+    let read_post = Post::read(&conn, post.id).unwrap();
+
+    Post::delete(&conn, post.id).unwrap();
+    Person::delete(&conn, person.id).unwrap();
+    Community::delete(&conn, community.id).unwrap();
+
+    assert!(read_post.deleted);
+  }
+
+  #[actix_rt::test]
+  #[serial]
+  async fn test_read_from_tombstone() {
+    let client = reqwest::Client::new().into();
+    let manager = create_activity_queue(client);
+    let context = init_context(manager.queue_handle().clone());
+    let conn = context.pool().get().unwrap();
+
+    let community_form = CommunityForm {
+      name: "tombstone_types".to_string(),
+      title: "nada".to_owned(),
+      ..CommunityForm::default()
+    };
+    let community = Community::create(&conn, &community_form).unwrap();
+    let person_form = PersonForm {
+      name: "tombstone_creator".into(),
+      ..PersonForm::default()
+    };
+    let person = Person::create(&conn, &person_form).unwrap();
+    let post_id = Url::parse("https://enterprise.lemmy.ml/post/tombstone").unwrap();
+    let post_form = PostForm {
+      name: "tombstone post".into(),
+      creator_id: person.id,
+      community_id: community.id,
+      ap_id: Some(post_id.clone().into()),
+      ..PostForm::default()
+    };
+    let post = Post::create(&conn, &post_form).unwrap();
+
+    let typed = Tombstone::with_former_type(post_id.clone(), FormerType::Page);
+    let typed = DeletableObjects::read_from_tombstone(&typed, &context).await;
+    // With a type, only that table is checked
+    let wrong_type = Tombstone::with_former_type(post_id.clone(), FormerType::Note);
+    let wrong_type = DeletableObjects::read_from_tombstone(&wrong_type, &context).await;
+    // Older versions don't send a type
+    let untyped = Tombstone::new(post_id);
+    let untyped = DeletableObjects::read_from_tombstone(&untyped, &context).await;
+
+    Post::delete(&conn, post.id).unwrap();
+    Person::delete(&conn, person.id).unwrap();
+    Community::delete(&conn, community.id).unwrap();
+
+    assert!(matches!(typed, Ok(DeletableObjects::Post(p)) if p.id == post.id));
+    assert!(wrong_type.is_err());
+    assert!(matches!(untyped, Ok(DeletableObjects::Post(p)) if p.id == post.id));
+  }
+
+  /// Receives the deletion like the inbox does, including the log of received deletions
+  async fn receive_logged<A: ActivityHandler<DataType = LemmyContext> + serde::Serialize>(
+    activity: A,
+    context: &LemmyContext,
+  ) {
+    let value = serde_json::to_value(&activity).unwrap();
+    let form = crate::http::received_deletion_form(&value, true).unwrap();
+    let conn = context.pool().get().unwrap();
+    ReceivedDeletion::create(&conn, &form).unwrap();
+    activity
+      .receive(&Data::new(context.clone()), &mut 0)
+      .await
+      .unwrap();
+  }
+
+  #[actix_rt::test]
+  #[serial]
+  async fn test_undo_delete_round_trip() {
+    let client = reqwest::Client::new().into();
+    let manager = create_activity_queue(client);
+    let context = init_context(manager.queue_handle().clone());
+    let conn = context.pool().get().unwrap();
+
+    let community_form = CommunityForm {
+      name: "undo_round_trip".to_string(),
+      title: "nada".to_owned(),
+      actor_id: Some(
+        Url::parse("https://enterprise.lemmy.ml/c/undo_round_trip")
+          .unwrap()
+          .into(),
+      ),
+      local: Some(false),
+      ..CommunityForm::default()
+    };
+    let community = Community::create(&conn, &community_form).unwrap();
+    let person_form = PersonForm {
+      name: "undo_round_trip_creator".into(),
+      actor_id: Some(
+        Url::parse("https://enterprise.lemmy.ml/u/undo_round_trip_creator")
+          .unwrap()
+          .into(),
+      ),
+      local: Some(false),
+      ..PersonForm::default()
+    };
+    let person = Person::create(&conn, &person_form).unwrap();
+    let post_form = PostForm {
+      name: "undo round trip".into(),
+      creator_id: person.id,
+      community_id: community.id,
+      ap_id: Some(
+        Url::parse("https://enterprise.lemmy.ml/post/undo_round_trip")
+          .unwrap()
+          .into(),
+      ),
+      local: Some(false),
+      ..PostForm::default()
+    };
+    let post = Post::create(&conn, &post_form).unwrap();
+    let actor: ApubPerson = person.clone().into();
+    let apub_community: ApubCommunity = community.clone().into();
+    let object = || DeletableObjects::Post(Box::new(post.clone().into()));
+
+    let delete = Delete::new(&actor, object(), &context).unwrap();
+    let delete_id = delete.id.clone();
+    receive_logged(delete, &context).await;
+    let deleted = Post::read(&conn, post.id).unwrap().deleted;
+
+    // The undo contains a newly generated delete
+    let undo = UndoDelete::new(&actor, &apub_community, object(), &context).unwrap();
+    assert_ne!(delete_id, undo.object.id);
+    receive_logged(undo, &context).await;
+    let restored = !Post::read(&conn, post.id).unwrap().deleted;
+
+    // Undoing a delete which is held for review doesn't restore the post
+    let held_delete = Delete::new(&actor, object(), &context).unwrap();
+    let held_delete_id: DbUrl = held_delete.id.clone().into();
+    receive_logged(held_delete, &context).await;
+    ReceivedDeletion::update_held(&conn, &held_delete_id, true).unwrap();
+    let undo = UndoDelete::new(&actor, &apub_community, object(), &context).unwrap();
+    receive_logged(undo, &context).await;
+    let held_restored = !Post::read(&conn, post.id).unwrap().deleted;
+
+    let actor_id = person.actor_id.clone();
+    diesel::delete(received_deletion::table.filter(received_deletion::actor_id.eq(actor_id)))
+      .execute(&conn)
+      .unwrap();
+    Post::delete(&conn, post.id).unwrap();
+    Person::delete(&conn, person.id).unwrap();
+    Community::delete(&conn, community.id).unwrap();
+
+    assert!(deleted);
+    assert!(restored);
+    assert!(!held_restored);
+  }
+
+  #[actix_rt::test]
+  #[serial]
+  async fn test_apply_pending_restore() {
+    let client = reqwest::Client::new().into();
+    let manager = create_activity_queue(client);
+    let context = init_context(manager.queue_handle().clone());
+    let conn = context.pool().get().unwrap();
+
+    let community_form = CommunityForm {
+      name: "pending_restore".to_string(),
+      title: "nada".to_owned(),
+      ..CommunityForm::default()
+    };
+    let community = Community::create(&conn, &community_form).unwrap();
+    let person_form = PersonForm {
+      name: "pending_restore_creator".into(),
+      ..PersonForm::default()
+    };
+    let person = Person::create(&conn, &person_form).unwrap();
+    let post_form = PostForm {
+      name: "pending restore".into(),
+      creator_id: person.id,
+      community_id: community.id,
+      deleted: Some(true),
+      removed: Some(true),
+      ap_id: Some(
+        Url::parse("https://lemmy-alpha/post/pending_restore")
+          .unwrap()
+          .into(),
+      ),
+      ..PostForm::default()
+    };
+    let post = Post::create(&conn, &post_form).unwrap();
+    let pending = |removal| {
+      let form = PendingDeletionForm {
+        actor_id: person.id,
+        community_id: community.id,
+        activity_id: Url::parse("https://enterprise.lemmy.ml/activities/undo/pending_restore")
+          .unwrap()
+          .into(),
+        object_id: post.ap_id.clone(),
+        removal,
+        reason: None,
+        restore: true,
+      };
+      PendingDeletion::create(&conn, &form).unwrap()
+    };
+
+    apply_pending_deletion(pending(false), &context)
+      .await
+      .unwrap();
+    let undeleted = Post::read(&conn, post.id).unwrap();
+    apply_pending_deletion(pending(true), &context)
+      .await
+      .unwrap();
+    let restored = Post::read(&conn, post.id).unwrap();
+
+    Post::delete(&conn, post.id).unwrap();
+    Person::delete(&conn, person.id).unwrap();
+    Community::delete(&conn, community.id).unwrap();
+
+    assert!(!undeleted.deleted);
+    assert!(undeleted.removed);
+    assert!(!restored.removed);
+  }
+
+  #[test]
+  fn test_attestation_endpoint() {
+    let config = DeletionAttestationConfig {
+      partner_instances: vec!["partner.tld".to_string()],
+      ..DeletionAttestationConfig::default()
+    };
+    let activity_id = Url::parse("https://partner.tld/activities/delete/1").unwrap();
+    let partner = Url::parse("https://partner.tld/u/alice").unwrap();
+    let other = Url::parse("https://other.tld/u/bob").unwrap();
+
+    let endpoint = attestation_endpoint(&config, &partner, &activity_id).unwrap();
+    let other_endpoint = attestation_endpoint(&config, &other, &activity_id).unwrap();
+
+    assert_eq!(
+      "https://partner.tld/attest?activity=https%3A%2F%2Fpartner.tld%2Factivities%2Fdelete%2F1",
+      endpoint.unwrap().as_str()
+    );
+    assert!(other_endpoint.is_none());
+  }
+
+  #[actix_rt::test]
+  #[serial]
+  async fn test_is_local_deletion() {
+    use lemmy_db_schema::source::activity::ActivityForm;
+
+    let client = reqwest::Client::new().into();
+    let manager = create_activity_queue(client);
+    let context = init_context(manager.queue_handle().clone());
+    let conn = context.pool().get().unwrap();
+
+    let create_activity = |kind: &str, local: bool| {
+      let ap_id = Url::parse(&format!(
+        "https://attestation.tld/activities/{}/{}",
+        kind.to_lowercase(),
+        local
+      ))
+      .unwrap();
+      let form = ActivityForm {
+        data: serde_json::json!({ "id": ap_id, "type": kind }),
+        local: Some(local),
+        updated: None,
+        ap_id: ap_id.into(),
+        sensitive: false,
+      };
+      Activity::create(&conn, &form).unwrap()
+    };
+    let local_delete = create_activity("Delete", true);
+    let remote_delete = create_activity("Delete", false);
+    let local_like = create_activity("Like", true);
+    let unknown = Url::parse("https://attestation.tld/activities/delete/unknown").unwrap();
+
+    let mut confirmed = vec![];
+    for id in [&local_delete, &remote_delete, &local_like].map(|a| a.ap_id.clone().into()) {
+      confirmed.push(is_local_deletion(id, &context).await.unwrap());
+    }
+    let unknown_confirmed = is_local_deletion(unknown, &context).await.unwrap();
+
+    for activity in [local_delete, remote_delete, local_like] {
+      Activity::delete(&conn, activity.id).unwrap();
+    }
+
+    assert_eq!(vec![true, false, false], confirmed);
+    assert!(!unknown_confirmed);
+  }
+}
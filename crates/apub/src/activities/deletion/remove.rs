@@ -0,0 +1,482 @@
+use crate::{
+  activities::{
+    community::announce::GetCommunity,
+    deletion::{
+      get_deletable_object_community,
+      send_deletion,
+      store_for_review,
+      verify_delete_activity,
+      DeletableObjects,
+      DeletionAction,
+    },
+    federation_target::FederationTarget,
+    generate_activity_id,
+    verify_activity,
+    verify_is_public,
+  },
+  activity_lists::AnnouncableActivities,
+  objects::{community::ApubCommunity, person::ApubPerson},
+  protocol::{activities::deletion::remove::Remove, objects::tombstone::Tombstone},
+};
+use activitystreams_kinds::{activity::RemoveType, public};
+use lemmy_api_common::blocking;
+use lemmy_apub_lib::{
+  data::Data,
+  object_id::ObjectId,
+  traits::{ActivityHandler, ActorType},
+};
+use lemmy_db_schema::{
+  source::{
+    comment::Comment,
+    community::Community,
+    community_wiki_page::CommunityWikiPage,
+    moderator::{
+      ModRemoveComment,
+      ModRemoveCommentForm,
+      ModRemoveCommunity,
+      ModRemoveCommunityForm,
+      ModRemovePost,
+      ModRemovePostForm,
+    },
+    post::Post,
+  },
+  traits::Crud,
+};
+use lemmy_utils::LemmyError;
+use lemmy_websocket::{
+  messages::RemovalInfo,
+  send::{
+    send_comment_removal_ws_message,
+    send_community_removal_ws_message,
+    send_post_removal_ws_message,
+  },
+  LemmyContext,
+  UserOperationCrud,
+};
+use std::collections::HashMap;
+
+#[async_trait::async_trait(?Send)]
+impl ActivityHandler for Remove {
+  type DataType = LemmyContext;
+
+  #[tracing::instrument(skip_all)]
+  async fn verify(
+    &self,
+    context: &Data<LemmyContext>,
+    request_counter: &mut i32,
+  ) -> Result<(), LemmyError> {
+    verify_is_public(&self.to, &self.cc)?;
+    verify_activity(&self.id, self.actor.inner(), &context.settings())?;
+    let community = self.get_community(context, request_counter).await?;
+    verify_delete_activity(
+      &self.object,
+      &self.actor,
+      &community,
+      true,
+      context,
+      request_counter,
+    )
+    .await?;
+    Ok(())
+  }
+
+  #[tracing::instrument(skip_all)]
+  async fn receive(
+    self,
+    context: &Data<LemmyContext>,
+    request_counter: &mut i32,
+  ) -> Result<(), LemmyError> {
+    if store_for_review(
+      &self.id,
+      &self.object.id,
+      &self.actor,
+      true,
+      self.summary.clone(),
+      false,
+      context,
+      request_counter,
+    )
+    .await?
+    {
+      return Ok(());
+    }
+    receive_remove_action(
+      &self.actor,
+      &self.object,
+      self.summary,
+      self.summary_map,
+      context,
+      request_counter,
+    )
+    .await
+  }
+}
+
+impl Remove {
+  pub(in crate::activities::deletion) fn new(
+    actor: &ApubPerson,
+    community: &ApubCommunity,
+    object: DeletableObjects,
+    reason: Option<String>,
+    reason_translations: Option<HashMap<String, String>>,
+    context: &LemmyContext,
+  ) -> Result<Remove, LemmyError> {
+    // The plain reason is also included in the map, under the default language of this instance
+    let summary_map = reason_translations.map(|mut translations| {
+      if let Some(reason) = &reason {
+        translations
+          .entry(context.settings().moderation.reason_language)
+          .or_insert_with(|| reason.clone());
+      }
+      translations
+    });
+    Ok(Remove {
+      actor: ObjectId::new(actor.actor_id()),
+      to: vec![public()],
+      object: object.to_tombstone()?,
+      cc: vec![community.actor_id()],
+      kind: RemoveType::Remove,
+      summary: reason,
+      summary_map,
+      id: generate_activity_id(
+        RemoveType::Remove,
+        &context.settings().get_protocol_and_hostname(),
+      )?,
+      unparsed: Default::default(),
+    })
+  }
+
+  #[tracing::instrument(skip_all)]
+  pub(in crate::activities::deletion) async fn send(
+    actor: &ApubPerson,
+    community: &ApubCommunity,
+    object: DeletableObjects,
+    reason: Option<String>,
+    reason_translations: Option<HashMap<String, String>>,
+    target: &FederationTarget,
+    context: &LemmyContext,
+  ) -> Result<(), LemmyError> {
+    let remove = Remove::new(
+      actor,
+      community,
+      object,
+      reason,
+      reason_translations,
+      context,
+    )?;
+    let remove_id = remove.id.clone();
+
+    let activity = AnnouncableActivities::Remove(remove);
+    send_deletion(activity, &remove_id, actor, target, context).await
+  }
+}
+
+/// Write removal of an object to the database and the mod log, and send websocket message.
+#[tracing::instrument(skip_all)]
+pub(in crate::activities) async fn receive_remove_action(
+  actor: &ObjectId<ApubPerson>,
+  object: &Tombstone,
+  reason: Option<String>,
+  reason_translations: Option<HashMap<String, String>>,
+  context: &LemmyContext,
+  request_counter: &mut i32,
+) -> Result<(), LemmyError> {
+  let actor = actor
+    .dereference(context, context.client(), request_counter)
+    .await?;
+  use UserOperationCrud::*;
+  let removal = RemovalInfo {
+    mod_person_id: actor.id,
+    reason: reason.clone(),
+  };
+  let reason_translations = reason_translations.map(serde_json::to_value).transpose()?;
+  let object = DeletableObjects::read_from_tombstone(object, context).await?;
+  let recipient_ids = object
+    .local_notification_recipients(DeletionAction::Remove, actor.id, context.pool())
+    .await?;
+  match object {
+    DeletableObjects::Community(community) => {
+      if community.local {
+        return Err(LemmyError::from_message(
+          "Only local admin can remove community",
+        ));
+      }
+      let form = ModRemoveCommunityForm {
+        mod_person_id: actor.id,
+        community_id: community.id,
+        removed: Some(true),
+        reason,
+        reason_translations,
+        expires: None,
+        reason_override: false,
+        public: None,
+      };
+      blocking(context.pool(), move |conn| {
+        ModRemoveCommunity::create(conn, &form)
+      })
+      .await??;
+      let deleted_community = blocking(context.pool(), move |conn| {
+        Community::update_removed(conn, community.id, true)
+      })
+      .await??;
+
+      send_community_removal_ws_message(
+        deleted_community.id,
+        RemoveCommunity,
+        removal,
+        None,
+        None,
+        recipient_ids,
+        context,
+      )
+      .await?;
+    }
+    DeletableObjects::Post(post) => {
+      let form = ModRemovePostForm {
+        mod_person_id: actor.id,
+        post_id: post.id,
+        removed: Some(true),
+        reason,
+        reason_translations,
+        reason_override: false,
+        public: None,
+      };
+      blocking(context.pool(), move |conn| {
+        ModRemovePost::create(conn, &form)
+      })
+      .await??;
+      let removed_post = blocking(context.pool(), move |conn| {
+        Post::update_removed(conn, post.id, true)
+      })
+      .await??;
+
+      send_post_removal_ws_message(
+        removed_post.id,
+        RemovePost,
+        removal,
+        None,
+        None,
+        recipient_ids,
+        context,
+      )
+      .await?;
+    }
+    DeletableObjects::Comment(comment) => {
+      let form = ModRemoveCommentForm {
+        mod_person_id: actor.id,
+        comment_id: comment.id,
+        removed: Some(true),
+        reason,
+        reason_translations,
+        reason_override: false,
+        public: None,
+      };
+      blocking(context.pool(), move |conn| {
+        ModRemoveComment::create(conn, &form)
+      })
+      .await??;
+      let removed_comment = blocking(context.pool(), move |conn| {
+        Comment::update_removed(conn, comment.id, true)
+      })
+      .await??;
+
+      send_comment_removal_ws_message(
+        removed_comment.id,
+        RemoveComment,
+        removal,
+        None,
+        None,
+        recipient_ids,
+        context,
+      )
+      .await?;
+    }
+    // Wiki pages are written by mods, so there is no separate removed state
+    DeletableObjects::WikiPage(wiki_page) => {
+      blocking(context.pool(), move |conn| {
+        CommunityWikiPage::update_deleted(conn, wiki_page.id, true)
+      })
+      .await??;
+    }
+  }
+  Ok(())
+}
+
+#[async_trait::async_trait(?Send)]
+impl GetCommunity for Remove {
+  #[tracing::instrument(skip_all)]
+  async fn get_community(
+    &self,
+    context: &LemmyContext,
+    _request_counter: &mut i32,
+  ) -> Result<ApubCommunity, LemmyError> {
+    get_deletable_object_community(&self.object, context).await
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::objects::tests::init_context;
+  use actix::{Actor, Context, Handler, Message, MessageResult};
+  use lemmy_apub_lib::activity_queue::create_activity_queue;
+  use lemmy_db_schema::source::{community::CommunityForm, person::*, post::PostForm};
+  use lemmy_utils::IpAddr;
+  use lemmy_websocket::messages::{
+    CloseSession,
+    Connect,
+    JoinCommunityRoom,
+    JoinModRoom,
+    WsMessage,
+  };
+  use serde_json::Value;
+  use serial_test::serial;
+  use url::Url;
+
+  /// Collects the websocket messages which the chat server sends to it
+  #[derive(Default)]
+  struct TestSession {
+    messages: Vec<String>,
+  }
+
+  impl Actor for TestSession {
+    type Context = Context<Self>;
+  }
+
+  impl Handler<WsMessage> for TestSession {
+    type Result = ();
+
+    fn handle(&mut self, msg: WsMessage, _: &mut Context<Self>) {
+      self.messages.push(msg.0);
+    }
+  }
+
+  impl Handler<CloseSession> for TestSession {
+    type Result = ();
+
+    fn handle(&mut self, _: CloseSession, _: &mut Context<Self>) {}
+  }
+
+  #[derive(Message)]
+  #[rtype(result = "Vec<Value>")]
+  struct TakeMessages;
+
+  impl Handler<TakeMessages> for TestSession {
+    type Result = MessageResult<TakeMessages>;
+
+    fn handle(&mut self, _: TakeMessages, _: &mut Context<Self>) -> Self::Result {
+      MessageResult(
+        self
+          .messages
+          .drain(..)
+          .map(|m| serde_json::from_str(&m).unwrap())
+          .collect(),
+      )
+    }
+  }
+
+  #[actix_rt::test]
+  #[serial]
+  async fn test_removal_info_only_sent_to_mods() {
+    let client = reqwest::Client::new().into();
+    let manager = create_activity_queue(client);
+    let context = init_context(manager.queue_handle().clone());
+    let conn = context.pool().get().unwrap();
+
+    let community_form = CommunityForm {
+      name: "removal_info".to_string(),
+      title: "nada".to_owned(),
+      local: Some(false),
+      ..CommunityForm::default()
+    };
+    let community = Community::create(&conn, &community_form).unwrap();
+    let mod_form = PersonForm {
+      name: "removal_info_mod".into(),
+      actor_id: Some(
+        Url::parse("https://enterprise.lemmy.ml/u/removal_info_mod")
+          .unwrap()
+          .into(),
+      ),
+      local: Some(false),
+      ..PersonForm::default()
+    };
+    let mod_person = Person::create(&conn, &mod_form).unwrap();
+    let post_form = PostForm {
+      name: "removal info".into(),
+      creator_id: mod_person.id,
+      community_id: community.id,
+      ap_id: Some(
+        Url::parse("https://enterprise.lemmy.ml/post/removal_info")
+          .unwrap()
+          .into(),
+      ),
+      local: Some(false),
+      ..PostForm::default()
+    };
+    let post = Post::create(&conn, &post_form).unwrap();
+
+    let chat_server = context.chat_server();
+    let connect = |session: &actix::Addr<TestSession>| Connect {
+      addr: session.clone().recipient(),
+      close: session.clone().recipient(),
+      ip: IpAddr("127.0.0.1".to_string()),
+    };
+    let mod_session = TestSession::default().start();
+    let mod_id = chat_server.send(connect(&mod_session)).await.unwrap();
+    chat_server
+      .send(JoinModRoom {
+        community_id: community.id,
+        id: mod_id,
+      })
+      .await
+      .unwrap();
+    let other_session = TestSession::default().start();
+    let other_id = chat_server.send(connect(&other_session)).await.unwrap();
+    chat_server
+      .send(JoinCommunityRoom {
+        community_id: community.id,
+        id: other_id,
+      })
+      .await
+      .unwrap();
+
+    receive_remove_action(
+      &ObjectId::new(mod_person.actor_id.clone()),
+      &Tombstone::new(post.ap_id.clone().into()),
+      Some("spam".to_string()),
+      None,
+      &context,
+      &mut 0,
+    )
+    .await
+    .unwrap();
+    // Wait until the chat server has sent out the messages
+    chat_server
+      .send(JoinCommunityRoom {
+        community_id: community.id,
+        id: other_id,
+      })
+      .await
+      .unwrap();
+    let removed = |messages: Vec<Value>| {
+      messages
+        .into_iter()
+        .find(|m| m["op"] == "RemovePost")
+        .unwrap()
+    };
+    let mod_message = removed(mod_session.send(TakeMessages).await.unwrap());
+    let other_message = removed(other_session.send(TakeMessages).await.unwrap());
+
+    Post::delete(&conn, post.id).unwrap();
+    Person::delete(&conn, mod_person.id).unwrap();
+    Community::delete(&conn, community.id).unwrap();
+
+    assert_eq!("spam", mod_message["data"]["removal"]["reason"]);
+    assert_eq!(
+      mod_person.id.0,
+      mod_message["data"]["removal"]["mod_person_id"]
+    );
+    assert_eq!(post.id.0, other_message["data"]["post_view"]["post"]["id"]);
+    assert!(other_message["data"].get("removal").is_none());
+  }
+}
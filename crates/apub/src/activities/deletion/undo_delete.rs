@@ -1,7 +1,15 @@
 use crate::{
   activities::{
-    community::{announce::GetCommunity, send_activity_in_community},
-    deletion::{receive_delete_action, verify_delete_activity, DeletableObjects},
+    community::announce::GetCommunity,
+    deletion::{
+      receive_delete_action,
+      send_deletion,
+      store_for_review,
+      undo_remove::receive_undo_remove_action,
+      verify_delete_activity,
+      DeletableObjects,
+    },
+    federation_target::FederationTarget,
     generate_activity_id,
     verify_activity,
     verify_is_public,
@@ -17,13 +25,10 @@ use lemmy_apub_lib::{
   object_id::ObjectId,
   traits::{ActivityHandler, ActorType},
 };
-use lemmy_db_schema::source::{comment::Comment, community::Community, post::Post};
+use lemmy_db_schema::{newtypes::DbUrl, source::received_deletion::ReceivedDeletion};
 use lemmy_utils::LemmyError;
-use lemmy_websocket::{
-  send::{send_comment_ws_message_simple, send_community_ws_message, send_post_ws_message},
-  LemmyContext,
-  UserOperationCrud,
-};
+use lemmy_websocket::LemmyContext;
+use tracing::info;
 use url::Url;
 
 #[async_trait::async_trait(?Send)]
@@ -41,7 +46,7 @@ impl ActivityHandler for UndoDelete {
     self.object.verify(context, request_counter).await?;
     let community = self.get_community(context, request_counter).await?;
     verify_delete_activity(
-      &self.object.object.id,
+      &self.object.object,
       &self.actor,
       &community,
       self.object.summary.is_some(),
@@ -58,11 +63,36 @@ impl ActivityHandler for UndoDelete {
     context: &Data<LemmyContext>,
     request_counter: &mut i32,
   ) -> Result<(), LemmyError> {
-    if self.object.summary.is_some() {
-      UndoDelete::receive_undo_remove_action(&self.object.object.id, context).await
+    // If the original delete was rejected, held back or never reached us, it wasn't applied
+    // either. Undoing it anyway could restore content which was deleted or removed by someone else.
+    if !self.undoes_applied_deletion(context).await? {
+      info!(
+        "Ignoring undo {}, because delete of {} was not applied",
+        self.id, self.object.object.id
+      );
+      return Ok(());
+    }
+    let removal = self.object.summary.is_some();
+    if store_for_review(
+      &self.id,
+      &self.object.object.id,
+      &self.actor,
+      removal,
+      None,
+      true,
+      context,
+      request_counter,
+    )
+    .await?
+    {
+      return Ok(());
+    }
+    if removal {
+      receive_undo_remove_action(&self.object.object.id, context).await
     } else {
       receive_delete_action(
-        &self.object.object.id,
+        &self.id,
+        &self.object.object,
         &self.actor,
         false,
         context,
@@ -74,69 +104,67 @@ impl ActivityHandler for UndoDelete {
 }
 
 impl UndoDelete {
-  #[tracing::instrument(skip_all)]
-  pub(in crate::activities::deletion) async fn send(
+  pub(in crate::activities::deletion) fn new(
     actor: &ApubPerson,
     community: &ApubCommunity,
     object: DeletableObjects,
-    summary: Option<String>,
     context: &LemmyContext,
-  ) -> Result<(), LemmyError> {
-    let object = Delete::new(actor, object, summary, context)?;
+  ) -> Result<UndoDelete, LemmyError> {
+    let object = Delete::new(actor, object, context)?;
 
     let id = generate_activity_id(
       UndoType::Undo,
       &context.settings().get_protocol_and_hostname(),
     )?;
-    let undo = UndoDelete {
+    Ok(UndoDelete {
       actor: ObjectId::new(actor.actor_id()),
       to: vec![public()],
       object,
       cc: vec![community.actor_id()],
       kind: UndoType::Undo,
-      id: id.clone(),
+      id,
       unparsed: Default::default(),
-    };
-
-    let activity = AnnouncableActivities::UndoDelete(undo);
-    send_activity_in_community(activity, &id, actor, community, vec![], context).await
+    })
   }
 
   #[tracing::instrument(skip_all)]
-  pub(in crate::activities) async fn receive_undo_remove_action(
-    object: &Url,
+  pub(in crate::activities::deletion) async fn send(
+    actor: &ApubPerson,
+    community: &ApubCommunity,
+    object: DeletableObjects,
+    target: &FederationTarget,
     context: &LemmyContext,
   ) -> Result<(), LemmyError> {
-    use UserOperationCrud::*;
-    match DeletableObjects::read_from_db(object, context).await? {
-      DeletableObjects::Community(community) => {
-        if community.local {
-          return Err(LemmyError::from_message(
-            "Only local admin can restore community",
-          ));
-        }
-        let deleted_community = blocking(context.pool(), move |conn| {
-          Community::update_removed(conn, community.id, false)
-        })
-        .await??;
-        send_community_ws_message(deleted_community.id, EditCommunity, None, None, context).await?;
-      }
-      DeletableObjects::Post(post) => {
-        let removed_post = blocking(context.pool(), move |conn| {
-          Post::update_removed(conn, post.id, false)
-        })
-        .await??;
-        send_post_ws_message(removed_post.id, EditPost, None, None, context).await?;
-      }
-      DeletableObjects::Comment(comment) => {
-        let removed_comment = blocking(context.pool(), move |conn| {
-          Comment::update_removed(conn, comment.id, false)
-        })
-        .await??;
-        send_comment_ws_message_simple(removed_comment.id, EditComment, context).await?;
-      }
+    let undo = UndoDelete::new(actor, community, object, context)?;
+    let id = undo.id.clone();
+    let activity = AnnouncableActivities::UndoDelete(undo);
+    send_deletion(activity, &id, actor, target, context).await
+  }
+
+  /// The delete inside of an undo is generated again when sending, so it has a different id than
+  /// the delete which was received before. Instead the undo is matched with the latest deletion of
+  /// the object, which needs to be made by the same actor and applied.
+  async fn undoes_applied_deletion(&self, context: &LemmyContext) -> Result<bool, LemmyError> {
+    let object_id: DbUrl = self.object.object.id.clone().into();
+    let latest = blocking(context.pool(), move |conn| {
+      ReceivedDeletion::read_latest_accepted(conn, &object_id, "Delete")
+    })
+    .await??;
+    if let Some(latest) = latest {
+      let latest_actor: Url = latest.actor_id.into();
+      return Ok(!latest.held && &latest_actor == self.actor.inner());
     }
-    Ok(())
+
+    // Deletions from before the log was started can't be checked
+    let log_started = blocking(context.pool(), ReceivedDeletion::log_started).await??;
+    let deleted_at =
+      match DeletableObjects::read_from_tombstone(&self.object.object, context).await? {
+        DeletableObjects::Community(c) => c.updated.unwrap_or(c.published),
+        DeletableObjects::Post(p) => p.updated.unwrap_or(p.published),
+        DeletableObjects::Comment(c) => c.updated.unwrap_or(c.published),
+        DeletableObjects::WikiPage(p) => p.updated.unwrap_or(p.published),
+      };
+    Ok(matches!(log_started, Some(log_started) if deleted_at < log_started))
   }
 }
 
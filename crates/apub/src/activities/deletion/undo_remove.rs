@@ -0,0 +1,188 @@
+use crate::{
+  activities::{
+    community::announce::GetCommunity,
+    deletion::{send_deletion, store_for_review, verify_delete_activity, DeletableObjects},
+    federation_target::FederationTarget,
+    generate_activity_id,
+    verify_activity,
+    verify_is_public,
+  },
+  activity_lists::AnnouncableActivities,
+  objects::{community::ApubCommunity, person::ApubPerson},
+  protocol::activities::deletion::{remove::Remove, undo_remove::UndoRemove},
+};
+use activitystreams_kinds::{activity::UndoType, public};
+use lemmy_api_common::blocking;
+use lemmy_apub_lib::{
+  data::Data,
+  object_id::ObjectId,
+  traits::{ActivityHandler, ActorType},
+};
+use lemmy_db_schema::source::{
+  comment::Comment,
+  community::Community,
+  community_wiki_page::CommunityWikiPage,
+  moderator::ModRemoveCommunity,
+  post::Post,
+};
+use lemmy_utils::LemmyError;
+use lemmy_websocket::{
+  send::{send_comment_ws_message_simple, send_community_ws_message, send_post_ws_message},
+  LemmyContext,
+  UserOperationCrud,
+};
+use std::collections::HashMap;
+use url::Url;
+
+#[async_trait::async_trait(?Send)]
+impl ActivityHandler for UndoRemove {
+  type DataType = LemmyContext;
+
+  #[tracing::instrument(skip_all)]
+  async fn verify(
+    &self,
+    context: &Data<LemmyContext>,
+    request_counter: &mut i32,
+  ) -> Result<(), LemmyError> {
+    verify_is_public(&self.to, &self.cc)?;
+    verify_activity(&self.id, self.actor.inner(), &context.settings())?;
+    self.object.verify(context, request_counter).await?;
+    let community = self.get_community(context, request_counter).await?;
+    verify_delete_activity(
+      &self.object.object,
+      &self.actor,
+      &community,
+      true,
+      context,
+      request_counter,
+    )
+    .await?;
+    Ok(())
+  }
+
+  #[tracing::instrument(skip_all)]
+  async fn receive(
+    self,
+    context: &Data<LemmyContext>,
+    request_counter: &mut i32,
+  ) -> Result<(), LemmyError> {
+    if store_for_review(
+      &self.id,
+      &self.object.object.id,
+      &self.actor,
+      true,
+      None,
+      true,
+      context,
+      request_counter,
+    )
+    .await?
+    {
+      return Ok(());
+    }
+    receive_undo_remove_action(&self.object.object.id, context).await
+  }
+}
+
+impl UndoRemove {
+  #[tracing::instrument(skip_all)]
+  pub(in crate::activities::deletion) async fn send(
+    actor: &ApubPerson,
+    community: &ApubCommunity,
+    object: DeletableObjects,
+    reason: Option<String>,
+    reason_translations: Option<HashMap<String, String>>,
+    target: &FederationTarget,
+    context: &LemmyContext,
+  ) -> Result<(), LemmyError> {
+    let object = Remove::new(
+      actor,
+      community,
+      object,
+      reason,
+      reason_translations,
+      context,
+    )?;
+
+    let id = generate_activity_id(
+      UndoType::Undo,
+      &context.settings().get_protocol_and_hostname(),
+    )?;
+    let undo = UndoRemove {
+      actor: ObjectId::new(actor.actor_id()),
+      to: vec![public()],
+      object,
+      cc: vec![community.actor_id()],
+      kind: UndoType::Undo,
+      id: id.clone(),
+      unparsed: Default::default(),
+    };
+
+    let activity = AnnouncableActivities::UndoRemove(undo);
+    send_deletion(activity, &id, actor, target, context).await
+  }
+}
+
+#[tracing::instrument(skip_all)]
+pub(in crate::activities) async fn receive_undo_remove_action(
+  object: &Url,
+  context: &LemmyContext,
+) -> Result<(), LemmyError> {
+  use UserOperationCrud::*;
+  match DeletableObjects::read_from_db(object, context).await? {
+    DeletableObjects::Community(community) => {
+      if community.local {
+        return Err(LemmyError::from_message(
+          "Only local admin can restore community",
+        ));
+      }
+      // A remote mod can only revert their own removal, not one which was done by a local admin
+      let community_id = community.id;
+      let removed_by_local_admin = blocking(context.pool(), move |conn| {
+        ModRemoveCommunity::is_removed_by_local_admin(conn, community_id)
+      })
+      .await??;
+      if removed_by_local_admin {
+        return Ok(());
+      }
+      let deleted_community = blocking(context.pool(), move |conn| {
+        Community::update_removed(conn, community.id, false)
+      })
+      .await??;
+      send_community_ws_message(deleted_community.id, EditCommunity, None, None, context).await?;
+    }
+    DeletableObjects::Post(post) => {
+      let removed_post = blocking(context.pool(), move |conn| {
+        Post::update_removed(conn, post.id, false)
+      })
+      .await??;
+      send_post_ws_message(removed_post.id, EditPost, None, None, context).await?;
+    }
+    DeletableObjects::Comment(comment) => {
+      let removed_comment = blocking(context.pool(), move |conn| {
+        Comment::update_removed(conn, comment.id, false)
+      })
+      .await??;
+      send_comment_ws_message_simple(removed_comment.id, EditComment, context).await?;
+    }
+    DeletableObjects::WikiPage(wiki_page) => {
+      blocking(context.pool(), move |conn| {
+        CommunityWikiPage::update_deleted(conn, wiki_page.id, false)
+      })
+      .await??;
+    }
+  }
+  Ok(())
+}
+
+#[async_trait::async_trait(?Send)]
+impl GetCommunity for UndoRemove {
+  #[tracing::instrument(skip_all)]
+  async fn get_community(
+    &self,
+    context: &LemmyContext,
+    request_counter: &mut i32,
+  ) -> Result<ApubCommunity, LemmyError> {
+    self.object.get_community(context, request_counter).await
+  }
+}
@@ -0,0 +1,158 @@
+use crate::{
+  activities::{
+    community::{send_activity_in_community, with_context},
+    send_lemmy_activity_with_context,
+  },
+  activity_lists::AnnouncableActivities,
+  objects::{community::ApubCommunity, person::ApubPerson},
+};
+use lemmy_api_common::blocking;
+use lemmy_apub_lib::traits::ActorType;
+use lemmy_db_schema::{newtypes::CommunityId, source::community::Community, traits::Crud};
+use lemmy_utils::LemmyError;
+use lemmy_websocket::LemmyContext;
+use url::Url;
+
+/// Recipients of an outgoing activity
+#[derive(Clone, Debug, PartialEq)]
+pub enum FederationTarget {
+  /// The community's inbox. If the community is local, it additionally announces the activity to
+  /// its followers.
+  Community(CommunityId),
+  /// All remote followers of the local community with this followers collection
+  Followers(Url),
+  /// Exactly these inboxes
+  Inboxes(Vec<Url>),
+  /// The shared inboxes of these instances, given by domain
+  PartnerInstances(Vec<String>),
+}
+
+impl FederationTarget {
+  /// The inboxes which the activity is delivered to directly. This doesn't include the announce
+  /// which is sent for [`FederationTarget::Community`].
+  pub(crate) async fn inboxes(&self, context: &LemmyContext) -> Result<Vec<Url>, LemmyError> {
+    Ok(match self {
+      FederationTarget::Community(community_id) => {
+        vec![read_community(*community_id, context)
+          .await?
+          .shared_inbox_or_inbox_url()]
+      }
+      FederationTarget::Followers(followers_url) => {
+        let followers_url = followers_url.clone().into();
+        let community: ApubCommunity = blocking(context.pool(), move |conn| {
+          Community::read_from_followers_url(conn, &followers_url)
+        })
+        .await??
+        .into();
+        community.get_follower_inboxes(context).await?
+      }
+      FederationTarget::Inboxes(inboxes) => inboxes.clone(),
+      FederationTarget::PartnerInstances(domains) => domains
+        .iter()
+        .map(|domain| {
+          Url::parse(&format!(
+            "{}://{}/inbox",
+            context.settings().get_protocol_string(),
+            domain
+          ))
+        })
+        .collect::<Result<Vec<Url>, _>>()?,
+    })
+  }
+
+  pub(crate) async fn send(
+    &self,
+    activity: AnnouncableActivities,
+    activity_id: &Url,
+    actor: &ApubPerson,
+    context: &LemmyContext,
+  ) -> Result<(), LemmyError> {
+    if let FederationTarget::Community(community_id) = self {
+      let community = read_community(*community_id, context).await?;
+      return send_activity_in_community(activity, activity_id, actor, &community, vec![], context)
+        .await;
+    }
+    let inboxes = self.inboxes(context).await?;
+    send_lemmy_activity_with_context(
+      context,
+      with_context(&activity, &activity, context),
+      activity_id,
+      actor,
+      inboxes,
+      false,
+    )
+    .await
+  }
+}
+
+async fn read_community(
+  community_id: CommunityId,
+  context: &LemmyContext,
+) -> Result<ApubCommunity, LemmyError> {
+  let community = blocking(context.pool(), move |conn| {
+    Community::read(conn, community_id)
+  })
+  .await??;
+  Ok(community.into())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::objects::tests::init_context;
+  use lemmy_apub_lib::activity_queue::create_activity_queue;
+  use lemmy_db_schema::source::community::CommunityForm;
+  use serial_test::serial;
+
+  #[actix_rt::test]
+  #[serial]
+  async fn test_inboxes() {
+    let client = reqwest::Client::new().into();
+    let manager = create_activity_queue(client);
+    let context = init_context(manager.queue_handle().clone());
+
+    let inbox = Url::parse("https://example.com/u/alice/inbox").unwrap();
+    let explicit = FederationTarget::Inboxes(vec![inbox.clone()]);
+    assert_eq!(vec![inbox], explicit.inboxes(&context).await.unwrap());
+
+    let partners = FederationTarget::PartnerInstances(vec!["partner.tld".to_string()]);
+    let expected = format!(
+      "{}://partner.tld/inbox",
+      context.settings().get_protocol_string()
+    );
+    assert_eq!(
+      vec![Url::parse(&expected).unwrap()],
+      partners.inboxes(&context).await.unwrap()
+    );
+
+    let community_form = CommunityForm {
+      name: "federation_target".to_string(),
+      title: "nada".to_owned(),
+      shared_inbox_url: Some(Some(
+        Url::parse("https://example.com/inbox").unwrap().into(),
+      )),
+      followers_url: Some(
+        Url::parse("https://example.com/c/federation_target/followers")
+          .unwrap()
+          .into(),
+      ),
+      ..CommunityForm::default()
+    };
+    let conn = context.pool().get().unwrap();
+    let community = Community::create(&conn, &community_form).unwrap();
+
+    let community_target = FederationTarget::Community(community.id);
+    let community_inboxes = community_target.inboxes(&context).await.unwrap();
+    // Nobody follows the community
+    let followers_target = FederationTarget::Followers(community.followers_url.clone().into());
+    let follower_inboxes = followers_target.inboxes(&context).await.unwrap();
+
+    Community::delete(&conn, community.id).unwrap();
+
+    assert_eq!(
+      vec![Url::parse("https://example.com/inbox").unwrap()],
+      community_inboxes
+    );
+    assert!(follower_inboxes.is_empty());
+  }
+}
@@ -0,0 +1,83 @@
+use crate::{
+  activities::{
+    generate_activity_id,
+    instance::remote_instance_actor_id,
+    send_lemmy_activity,
+    verify_activity,
+  },
+  objects::instance::InstanceActor,
+  protocol::activities::instance::block::BlockInstance,
+};
+use activitystreams_kinds::activity::BlockType;
+use lemmy_api_common::blocking;
+use lemmy_apub_lib::{
+  data::Data,
+  traits::{ActivityHandler, ActorType},
+  verify::verify_urls_match,
+};
+use lemmy_db_schema::source::instance::Instance;
+use lemmy_utils::LemmyError;
+use lemmy_websocket::LemmyContext;
+
+impl BlockInstance {
+  pub(in crate::activities::instance) fn new(
+    actor: &InstanceActor,
+    domain: &str,
+    context: &LemmyContext,
+  ) -> Result<BlockInstance, LemmyError> {
+    let object = remote_instance_actor_id(domain, &context.settings())?;
+    Ok(BlockInstance {
+      actor: actor.actor_id(),
+      to: vec![object.clone()],
+      object,
+      kind: BlockType::Block,
+      id: generate_activity_id(
+        BlockType::Block,
+        &context.settings().get_protocol_and_hostname(),
+      )?,
+      unparsed: Default::default(),
+    })
+  }
+
+  /// Informs the instance that it was added to the local blocklist
+  #[tracing::instrument(skip_all)]
+  pub async fn send(domain: &str, context: &LemmyContext) -> Result<(), LemmyError> {
+    let actor = InstanceActor::local(context)?;
+    let block = BlockInstance::new(&actor, domain, context)?;
+    let inboxes = vec![block.object.join("inbox")?];
+    send_lemmy_activity(context, &block, &block.id, &actor, inboxes, false).await
+  }
+}
+
+#[async_trait::async_trait(?Send)]
+impl ActivityHandler for BlockInstance {
+  type DataType = LemmyContext;
+
+  #[tracing::instrument(skip_all)]
+  async fn verify(
+    &self,
+    context: &Data<LemmyContext>,
+    _request_counter: &mut i32,
+  ) -> Result<(), LemmyError> {
+    verify_activity(&self.id, &self.actor, &context.settings())?;
+    verify_urls_match(
+      &self.object,
+      &InstanceActor::local_actor_id(&context.settings())?,
+    )?;
+    Ok(())
+  }
+
+  #[tracing::instrument(skip_all)]
+  async fn receive(
+    self,
+    context: &Data<LemmyContext>,
+    _request_counter: &mut i32,
+  ) -> Result<(), LemmyError> {
+    let domain = self.actor.domain().unwrap_or_default().to_string();
+    blocking(context.pool(), move |conn| {
+      Instance::update_blocks_us(conn, &domain, true)
+    })
+    .await??;
+    Ok(())
+  }
+}
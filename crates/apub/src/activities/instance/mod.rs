@@ -0,0 +1,102 @@
+use lemmy_api_common::build_federated_instances;
+use lemmy_utils::{settings::structs::Settings, LemmyError};
+use lemmy_websocket::LemmyContext;
+use url::Url;
+
+pub mod block;
+pub mod undo_block;
+pub mod update;
+
+/// Instance actors of other instances are assumed to be at their root url, like the local one
+fn remote_instance_actor_id(domain: &str, settings: &Settings) -> Result<Url, LemmyError> {
+  Ok(Url::parse(&format!(
+    "{}://{}/",
+    settings.get_protocol_string(),
+    domain
+  ))?)
+}
+
+/// The shared inboxes of all instances which we are federating with
+async fn linked_instance_inboxes(context: &LemmyContext) -> Result<Vec<Url>, LemmyError> {
+  let federated_instances = build_federated_instances(
+    context.pool(),
+    &context.settings().federation,
+    &context.settings().hostname,
+  )
+  .await?;
+  let linked = federated_instances.map(|f| f.linked).unwrap_or_default();
+  linked
+    .iter()
+    .map(|domain| Ok(remote_instance_actor_id(domain, &context.settings())?.join("inbox")?))
+    .collect()
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::{
+    objects::{
+      instance::InstanceActor,
+      tests::{file_to_json_object, init_context},
+    },
+    protocol::activities::instance::{
+      block::BlockInstance,
+      undo_block::UndoBlockInstance,
+      update::UpdateInstance,
+    },
+  };
+  use lemmy_apub_lib::{
+    activity_queue::create_activity_queue,
+    data::Data,
+    traits::ActivityHandler,
+  };
+  use lemmy_db_schema::source::instance::Instance;
+  use serial_test::serial;
+
+  #[actix_rt::test]
+  #[serial]
+  async fn test_receive_instance_activities() {
+    let client = reqwest::Client::new().into();
+    let manager = create_activity_queue(client);
+    let context = Data::new(init_context(manager.queue_handle().clone()));
+    let conn = context.pool().get().unwrap();
+    let domain = "enterprise.lemmy.ml";
+    let mut request_counter = 0;
+
+    // A block is only accepted if it is addressed to this instance
+    let mut block: BlockInstance =
+      file_to_json_object("assets/lemmy/activities/instance/block_instance.json");
+    let wrong_target = block.verify(&context, &mut request_counter).await;
+    block.object = InstanceActor::local_actor_id(&context.settings()).unwrap();
+    block.verify(&context, &mut request_counter).await.unwrap();
+    block.receive(&context, &mut request_counter).await.unwrap();
+    let blocked = Instance::read_for_domain(&conn, domain).unwrap();
+
+    let mut undo: UndoBlockInstance =
+      file_to_json_object("assets/lemmy/activities/instance/undo_block_instance.json");
+    undo.object.object = InstanceActor::local_actor_id(&context.settings()).unwrap();
+    undo.verify(&context, &mut request_counter).await.unwrap();
+    undo.receive(&context, &mut request_counter).await.unwrap();
+    let unblocked = Instance::read_for_domain(&conn, domain).unwrap();
+
+    let update: UpdateInstance =
+      file_to_json_object("assets/lemmy/activities/instance/update_instance.json");
+    update.verify(&context, &mut request_counter).await.unwrap();
+    update
+      .receive(&context, &mut request_counter)
+      .await
+      .unwrap();
+    let updated = Instance::read_for_domain(&conn, domain).unwrap();
+
+    Instance::delete_for_domain(&conn, domain).unwrap();
+
+    assert!(wrong_target.is_err());
+    assert!(blocked.blocks_us);
+    assert!(!unblocked.blocks_us);
+    assert_eq!(Some("Enterprise".to_string()), updated.name);
+    assert_eq!(Some("A test instance".to_string()), updated.description);
+    assert_eq!(
+      "https://enterprise.lemmy.ml/pictrs/image/icon.png",
+      updated.icon.unwrap().to_string()
+    );
+  }
+}
@@ -0,0 +1,69 @@
+use crate::{
+  activities::{generate_activity_id, send_lemmy_activity, verify_activity},
+  objects::instance::InstanceActor,
+  protocol::activities::instance::{block::BlockInstance, undo_block::UndoBlockInstance},
+};
+use activitystreams_kinds::activity::UndoType;
+use lemmy_api_common::blocking;
+use lemmy_apub_lib::{
+  data::Data,
+  traits::{ActivityHandler, ActorType},
+  verify::verify_urls_match,
+};
+use lemmy_db_schema::source::instance::Instance;
+use lemmy_utils::LemmyError;
+use lemmy_websocket::LemmyContext;
+
+impl UndoBlockInstance {
+  /// Informs the instance that it was removed from the local blocklist
+  #[tracing::instrument(skip_all)]
+  pub async fn send(domain: &str, context: &LemmyContext) -> Result<(), LemmyError> {
+    let actor = InstanceActor::local(context)?;
+    let block = BlockInstance::new(&actor, domain, context)?;
+    let id = generate_activity_id(
+      UndoType::Undo,
+      &context.settings().get_protocol_and_hostname(),
+    )?;
+    let undo = UndoBlockInstance {
+      actor: actor.actor_id(),
+      to: block.to.clone(),
+      object: block,
+      kind: UndoType::Undo,
+      id: id.clone(),
+      unparsed: Default::default(),
+    };
+    let inboxes = vec![undo.object.object.join("inbox")?];
+    send_lemmy_activity(context, &undo, &id, &actor, inboxes, false).await
+  }
+}
+
+#[async_trait::async_trait(?Send)]
+impl ActivityHandler for UndoBlockInstance {
+  type DataType = LemmyContext;
+
+  #[tracing::instrument(skip_all)]
+  async fn verify(
+    &self,
+    context: &Data<LemmyContext>,
+    request_counter: &mut i32,
+  ) -> Result<(), LemmyError> {
+    verify_activity(&self.id, &self.actor, &context.settings())?;
+    verify_urls_match(&self.actor, &self.object.actor)?;
+    self.object.verify(context, request_counter).await?;
+    Ok(())
+  }
+
+  #[tracing::instrument(skip_all)]
+  async fn receive(
+    self,
+    context: &Data<LemmyContext>,
+    _request_counter: &mut i32,
+  ) -> Result<(), LemmyError> {
+    let domain = self.actor.domain().unwrap_or_default().to_string();
+    blocking(context.pool(), move |conn| {
+      Instance::update_blocks_us(conn, &domain, false)
+    })
+    .await??;
+    Ok(())
+  }
+}
@@ -0,0 +1,81 @@
+use crate::{
+  activities::{
+    generate_activity_id,
+    instance::linked_instance_inboxes,
+    send_lemmy_activity,
+    verify_activity,
+    verify_is_public,
+  },
+  objects::{get_summary_from_string_or_source, instance::InstanceActor},
+  protocol::activities::instance::update::UpdateInstance,
+};
+use activitystreams_kinds::{activity::UpdateType, public};
+use lemmy_api_common::blocking;
+use lemmy_apub_lib::{
+  data::Data,
+  traits::{ActivityHandler, ActorType},
+  verify::verify_urls_match,
+};
+use lemmy_db_schema::source::instance::{Instance, InstanceActorForm};
+use lemmy_utils::{utils::check_slurs_opt, LemmyError};
+use lemmy_websocket::LemmyContext;
+
+impl UpdateInstance {
+  /// Sends the current site metadata to all linked instances
+  #[tracing::instrument(skip_all)]
+  pub async fn send(context: &LemmyContext) -> Result<(), LemmyError> {
+    let actor = InstanceActor::local(context)?;
+    let id = generate_activity_id(
+      UpdateType::Update,
+      &context.settings().get_protocol_and_hostname(),
+    )?;
+    let update = UpdateInstance {
+      actor: actor.actor_id(),
+      to: vec![public()],
+      object: Box::new(actor.clone().into_apub(context).await?),
+      kind: UpdateType::Update,
+      id: id.clone(),
+      unparsed: Default::default(),
+    };
+    let inboxes = linked_instance_inboxes(context).await?;
+    send_lemmy_activity(context, &update, &id, &actor, inboxes, false).await
+  }
+}
+
+#[async_trait::async_trait(?Send)]
+impl ActivityHandler for UpdateInstance {
+  type DataType = LemmyContext;
+
+  #[tracing::instrument(skip_all)]
+  async fn verify(
+    &self,
+    context: &Data<LemmyContext>,
+    _request_counter: &mut i32,
+  ) -> Result<(), LemmyError> {
+    verify_is_public(&self.to, &[])?;
+    verify_activity(&self.id, &self.actor, &context.settings())?;
+    verify_urls_match(&self.actor, &self.object.id)?;
+    check_slurs_opt(&self.object.name, &context.settings().slur_regex())?;
+    Ok(())
+  }
+
+  #[tracing::instrument(skip_all)]
+  async fn receive(
+    self,
+    context: &Data<LemmyContext>,
+    _request_counter: &mut i32,
+  ) -> Result<(), LemmyError> {
+    let object = self.object;
+    let form = InstanceActorForm {
+      domain: object.id.domain().unwrap_or_default().to_string(),
+      name: object.name,
+      description: get_summary_from_string_or_source(&object.summary, &object.source),
+      icon: object.icon.map(|i| i.url.into()),
+    };
+    blocking(context.pool(), move |conn| {
+      Instance::upsert_actor(conn, &form)
+    })
+    .await??;
+    Ok(())
+  }
+}
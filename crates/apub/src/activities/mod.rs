@@ -1,4 +1,5 @@
 use crate::{
+  activities::send_queue::queue_activity,
   check_is_apub_id_valid,
   context::WithContext,
   generate_moderators_url,
@@ -28,10 +29,14 @@ use uuid::Uuid;
 pub mod comment;
 pub mod community;
 pub mod deletion;
+pub mod federation_target;
 pub mod following;
+pub mod instance;
 pub mod post;
 pub mod private_message;
+pub mod send_queue;
 pub mod voting;
+pub mod wiki_page;
 
 /// Checks that the specified Url actually identifies a Person (by fetching it), and that the person
 /// doesn't have a site ban.
@@ -165,11 +170,30 @@ async fn send_lemmy_activity<T: Serialize>(
   actor: &dyn ActorType,
   inboxes: Vec<Url>,
   sensitive: bool,
+) -> Result<(), LemmyError> {
+  send_lemmy_activity_with_context(
+    context,
+    WithContext::new(activity),
+    activity_id,
+    actor,
+    inboxes,
+    sensitive,
+  )
+  .await
+}
+
+#[tracing::instrument(skip_all)]
+async fn send_lemmy_activity_with_context<T: Serialize>(
+  context: &LemmyContext,
+  activity: WithContext<T>,
+  activity_id: &Url,
+  actor: &dyn ActorType,
+  inboxes: Vec<Url>,
+  sensitive: bool,
 ) -> Result<(), LemmyError> {
   if !context.settings().federation.enabled || inboxes.is_empty() {
     return Ok(());
   }
-  let activity = WithContext::new(activity);
 
   info!("Sending activity {}", activity_id.to_string());
 
@@ -186,13 +210,5 @@ async fn send_lemmy_activity<T: Serialize>(
   let object_value = serde_json::to_value(&activity)?;
   insert_activity(activity_id, object_value, true, sensitive, context.pool()).await?;
 
-  send_activity(
-    activity_id,
-    actor,
-    inboxes,
-    serialised_activity,
-    context.client(),
-    context.activity_queue(),
-  )
-  .await
+  queue_activity(activity_id, actor, inboxes, serialised_activity, context).await
 }
@@ -20,9 +20,17 @@ use lemmy_apub_lib::{
   traits::{ActivityHandler, ActorType, ApubObject},
   verify::{verify_domains_match, verify_urls_match},
 };
-use lemmy_db_schema::{source::community::Community, traits::Crud};
+use lemmy_db_schema::{
+  source::{
+    community::Community,
+    federation_conflict::{FederationConflict, FederationConflictForm},
+    moderator::{ModLockPost, ModLockPostForm, ModStickyPost, ModStickyPostForm},
+  },
+  traits::Crud,
+};
 use lemmy_utils::LemmyError;
 use lemmy_websocket::{send::send_post_ws_message, LemmyContext, UserOperationCrud};
+use tracing::warn;
 
 impl CreateOrUpdatePost {
   pub(crate) async fn new(
@@ -39,7 +47,7 @@ impl CreateOrUpdatePost {
     Ok(CreateOrUpdatePost {
       actor: ObjectId::new(actor.actor_id()),
       to: vec![public()],
-      object: post.into_apub(context).await?,
+      object: Box::new(post.into_apub(context).await?),
       cc: vec![community.actor_id()],
       kind,
       id: id.clone(),
@@ -66,6 +74,39 @@ impl CreateOrUpdatePost {
     let activity = AnnouncableActivities::CreateOrUpdatePost(create_or_update);
     send_activity_in_community(activity, &id, actor, &community, vec![], context).await
   }
+
+  /// Checks if the local version of the post was edited after the incoming one, which can happen
+  /// when updates arrive out of order. In that case the conflict is logged, and the update must
+  /// not be applied.
+  #[tracing::instrument(skip_all)]
+  async fn is_outdated_update(&self, context: &LemmyContext) -> Result<bool, LemmyError> {
+    let local_post = ApubPost::read_from_apub_id(self.object.id.inner().clone(), context).await?;
+    let local_updated = match local_post.and_then(|p| p.updated) {
+      Some(u) => u,
+      None => return Ok(false),
+    };
+    let remote_updated = self.object.updated.map(|u| u.naive_local());
+    if remote_updated.map(|r| r >= local_updated).unwrap_or(false) {
+      return Ok(false);
+    }
+
+    warn!(
+      "Ignoring update {} of post {}, local version is newer",
+      self.id,
+      self.object.id.inner()
+    );
+    let form = FederationConflictForm {
+      object_id: self.object.id.clone().into(),
+      activity_id: self.id.clone().into(),
+      local_updated,
+      remote_updated,
+    };
+    blocking(context.pool(), move |conn| {
+      FederationConflict::create(conn, &form)
+    })
+    .await??;
+    Ok(true)
+  }
 }
 
 #[async_trait::async_trait(?Send)]
@@ -120,7 +161,25 @@ impl ActivityHandler for CreateOrUpdatePost {
     context: &Data<LemmyContext>,
     request_counter: &mut i32,
   ) -> Result<(), LemmyError> {
-    let post = ApubPost::from_apub(self.object, context, request_counter).await?;
+    if self.kind == CreateOrUpdateType::Update && self.is_outdated_update(context).await? {
+      return Ok(());
+    }
+    let old_post = if self.kind == CreateOrUpdateType::Update {
+      ObjectId::<ApubPost>::new(self.object.id.inner().clone())
+        .dereference_local(context)
+        .await
+        .ok()
+    } else {
+      None
+    };
+    let post = ApubPost::from_apub(*self.object, context, request_counter).await?;
+    if let Some(old_post) = old_post {
+      let mod_person = self
+        .actor
+        .dereference(context, context.client(), request_counter)
+        .await?;
+      write_mod_log(&old_post, &post, &mod_person, context).await?;
+    }
 
     let notif_type = match self.kind {
       CreateOrUpdateType::Create => UserOperationCrud::CreatePost,
@@ -131,6 +190,36 @@ impl ActivityHandler for CreateOrUpdatePost {
   }
 }
 
+/// Updates which lock or sticky a post can only come from mods, so they are recorded in the mod log
+/// like the same action done locally.
+async fn write_mod_log(
+  old_post: &ApubPost,
+  new_post: &ApubPost,
+  mod_person: &ApubPerson,
+  context: &LemmyContext,
+) -> Result<(), LemmyError> {
+  if old_post.locked != new_post.locked {
+    let form = ModLockPostForm {
+      mod_person_id: mod_person.id,
+      post_id: new_post.id,
+      locked: Some(new_post.locked),
+    };
+    blocking(context.pool(), move |conn| ModLockPost::create(conn, &form)).await??;
+  }
+  if old_post.stickied != new_post.stickied {
+    let form = ModStickyPostForm {
+      mod_person_id: mod_person.id,
+      post_id: new_post.id,
+      stickied: Some(new_post.stickied),
+    };
+    blocking(context.pool(), move |conn| {
+      ModStickyPost::create(conn, &form)
+    })
+    .await??;
+  }
+  Ok(())
+}
+
 #[async_trait::async_trait(?Send)]
 impl GetCommunity for CreateOrUpdatePost {
   #[tracing::instrument(skip_all)]
@@ -145,3 +234,81 @@ impl GetCommunity for CreateOrUpdatePost {
       .await
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::objects::tests::{file_to_json_object, init_context};
+  use chrono::NaiveDate;
+  use diesel::RunQueryDsl;
+  use lemmy_apub_lib::activity_queue::create_activity_queue;
+  use lemmy_db_schema::source::{
+    community::CommunityForm,
+    person::{Person, PersonForm},
+    post::{Post, PostForm},
+  };
+  use serial_test::serial;
+  use url::Url;
+
+  #[actix_rt::test]
+  #[serial]
+  async fn test_outdated_update() {
+    let client = reqwest::Client::new().into();
+    let manager = create_activity_queue(client);
+    let context = init_context(manager.queue_handle().clone());
+    let conn = context.pool().get().unwrap();
+
+    let person_form = PersonForm {
+      name: "outdated_update".into(),
+      ..PersonForm::default()
+    };
+    let person = Person::create(&conn, &person_form).unwrap();
+    let community_form = CommunityForm {
+      name: "outdated_update".to_string(),
+      title: "nada".to_owned(),
+      ..CommunityForm::default()
+    };
+    let community = Community::create(&conn, &community_form).unwrap();
+    let post_id: Url = Url::parse("http://ds9.lemmy.ml/post/1").unwrap();
+    let edited_later = NaiveDate::from_ymd(2021, 10, 30).and_hms(0, 0, 0);
+    let post_form = PostForm {
+      name: "edited locally".into(),
+      creator_id: person.id,
+      community_id: community.id,
+      ap_id: Some(post_id.clone().into()),
+      updated: Some(edited_later),
+      ..PostForm::default()
+    };
+    let post = Post::create(&conn, &post_form).unwrap();
+
+    let update: CreateOrUpdatePost =
+      file_to_json_object("assets/lemmy/activities/create_or_update/update_page.json");
+    let outdated = update.is_outdated_update(&context).await.unwrap();
+    let conflicts = FederationConflict::list_for_object(&conn, &post_id.clone().into()).unwrap();
+
+    // An update which is newer than the local version is applied as usual
+    let edited_earlier = NaiveDate::from_ymd(2021, 10, 28).and_hms(0, 0, 0);
+    let post_form = PostForm {
+      updated: Some(edited_earlier),
+      ..post_form
+    };
+    Post::update(&conn, post.id, &post_form).unwrap();
+    let newer = update.is_outdated_update(&context).await.unwrap();
+
+    for conflict in &conflicts {
+      diesel::delete(conflict).execute(&conn).unwrap();
+    }
+    Post::delete(&conn, post.id).unwrap();
+    Person::delete(&conn, person.id).unwrap();
+    Community::delete(&conn, community.id).unwrap();
+
+    assert!(outdated);
+    assert_eq!(1, conflicts.len());
+    assert_eq!(
+      Some(edited_later),
+      conflicts.first().map(|c| c.local_updated)
+    );
+    assert!(conflicts[0].remote_updated.is_some());
+    assert!(!newer);
+  }
+}
@@ -1,11 +1,16 @@
 use crate::{
   activities::{generate_activity_id, send_lemmy_activity, verify_activity, verify_person},
+  mentions::Mention,
   objects::{person::ApubPerson, private_message::ApubPrivateMessage},
-  protocol::activities::{
-    private_message::create_or_update::CreateOrUpdatePrivateMessage,
-    CreateOrUpdateType,
+  protocol::{
+    activities::{
+      private_message::create_or_update::CreateOrUpdatePrivateMessage,
+      CreateOrUpdateType,
+    },
+    objects::chat_message::{ChatMessage, ChatMessageType},
   },
 };
+use activitystreams_kinds::link::MentionType;
 use lemmy_api_common::blocking;
 use lemmy_apub_lib::{
   data::Data,
@@ -13,9 +18,17 @@ use lemmy_apub_lib::{
   traits::{ActivityHandler, ActorType, ApubObject},
   verify::verify_domains_match,
 };
-use lemmy_db_schema::{source::person::Person, traits::Crud};
+use lemmy_db_schema::{
+  source::{instance::Instance, person::Person},
+  traits::Crud,
+};
+use lemmy_db_views::local_user_view::LocalUserView;
 use lemmy_utils::LemmyError;
-use lemmy_websocket::{send::send_pm_ws_message, LemmyContext, UserOperationCrud};
+use lemmy_websocket::{
+  send::{send_pm_ws_message, send_push_to_user},
+  LemmyContext,
+  UserOperationCrud,
+};
 
 impl CreateOrUpdatePrivateMessage {
   #[tracing::instrument(skip_all)]
@@ -39,7 +52,12 @@ impl CreateOrUpdatePrivateMessage {
       id: id.clone(),
       actor: ObjectId::new(actor.actor_id()),
       to: [ObjectId::new(recipient.actor_id())],
-      object: private_message.into_apub(context).await?,
+      object: to_recipient_format(
+        private_message.into_apub(context).await?,
+        &recipient,
+        context,
+      )
+      .await?,
       kind,
       unparsed: Default::default(),
     };
@@ -48,6 +66,42 @@ impl CreateOrUpdatePrivateMessage {
   }
 }
 
+/// Lemmy understands ChatMessage, but other software like Mastodon only handles direct messages
+/// as Note which mentions the recipient. Recipients on instances with unknown software get a
+/// ChatMessage, as before.
+async fn to_recipient_format(
+  mut object: ChatMessage,
+  recipient: &ApubPerson,
+  context: &LemmyContext,
+) -> Result<ChatMessage, LemmyError> {
+  let domain = recipient
+    .actor_id()
+    .domain()
+    .map(ToString::to_string)
+    .unwrap_or_default();
+  let software = blocking(context.pool(), move |conn| {
+    Instance::read_for_domain(conn, &domain)
+  })
+  .await?
+  .ok()
+  .and_then(|i| i.software);
+  if matches!(software.as_deref(), None | Some("lemmy")) {
+    return Ok(object);
+  }
+
+  object.r#type = ChatMessageType::Note;
+  object.tag = vec![Mention {
+    href: recipient.actor_id(),
+    name: Some(format!(
+      "@{}@{}",
+      recipient.name,
+      recipient.actor_id().domain().unwrap_or_default()
+    )),
+    kind: MentionType::Mention,
+  }];
+  Ok(object)
+}
+
 #[async_trait::async_trait(?Send)]
 impl ActivityHandler for CreateOrUpdatePrivateMessage {
   type DataType = LemmyContext;
@@ -78,7 +132,22 @@ impl ActivityHandler for CreateOrUpdatePrivateMessage {
       CreateOrUpdateType::Create => UserOperationCrud::CreatePrivateMessage,
       CreateOrUpdateType::Update => UserOperationCrud::EditPrivateMessage,
     };
-    send_pm_ws_message(private_message.id, notif_type, None, context).await?;
+    let res = send_pm_ws_message(private_message.id, notif_type, None, context).await?;
+
+    let view = res.private_message_view;
+    if self.kind == CreateOrUpdateType::Create && view.recipient.local {
+      let recipient_id = view.recipient.id;
+      let local_recipient = blocking(context.pool(), move |conn| {
+        LocalUserView::read_person(conn, recipient_id)
+      })
+      .await??;
+      send_push_to_user(
+        &local_recipient,
+        format!("Message from {}", view.creator.name),
+        &view.private_message.content,
+        context,
+      );
+    }
 
     Ok(())
   }
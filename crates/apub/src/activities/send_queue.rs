@@ -0,0 +1,223 @@
+use crate::activities::send_activity;
+use futures::{stream, StreamExt};
+use lemmy_api_common::blocking;
+use lemmy_apub_lib::{activity_queue::deliver_activity, traits::ActorType};
+use lemmy_db_schema::{
+  clock::Clock,
+  source::{
+    activity_send_queue::{InstanceSendFailure, QueuedActivity, QueuedActivityForm},
+    community::Community,
+    person::Person,
+  },
+};
+use lemmy_utils::{request_id::current_request_id, LemmyError};
+use lemmy_websocket::LemmyContext;
+use std::{collections::HashMap, env};
+use tracing::warn;
+use url::Url;
+
+/// After this many failed attempts, a delivery is dropped
+const MAX_RETRIES: i32 = 10;
+const BATCH_SIZE: i64 = 100;
+/// Number of instances which are delivered to at the same time
+const MAX_PARALLEL_INSTANCES: usize = 10;
+/// Claimed deliveries are retried after this time, if the worker didn't finish them
+const CLAIM_DURATION_SECONDS: i64 = 10 * 60;
+const FIRST_RETRY_DELAY_SECONDS: i64 = 30;
+const MAX_RETRY_DELAY_SECONDS: i64 = 24 * 60 * 60;
+
+/// Stores the activity in the send queue, so that the delivery survives a restart. With
+/// `APUB_TESTING_SEND_SYNC` set, it is sent immediately instead.
+pub(crate) async fn queue_activity(
+  activity_id: &Url,
+  actor: &dyn ActorType,
+  inboxes: Vec<&Url>,
+  activity: String,
+  context: &LemmyContext,
+) -> Result<(), LemmyError> {
+  if env::var("APUB_TESTING_SEND_SYNC").is_ok() {
+    return send_activity(
+      activity_id,
+      actor,
+      inboxes,
+      activity,
+      context.client(),
+      context.activity_queue(),
+    )
+    .await;
+  }
+
+  let forms: Vec<QueuedActivityForm> = inboxes
+    .into_iter()
+    .map(|inbox| QueuedActivityForm {
+      activity_id: activity_id.to_owned().into(),
+      inbox: inbox.to_owned().into(),
+      actor_id: actor.actor_id().into(),
+      activity: activity.clone(),
+      request_id: current_request_id(),
+    })
+    .collect();
+  blocking(context.pool(), move |conn| {
+    QueuedActivity::enqueue(conn, &forms)
+  })
+  .await??;
+  Ok(())
+}
+
+/// Claims the queued activities which are due and delivers them. Instances are served in
+/// parallel, but each one only gets a single delivery at a time, so that a slow instance can't
+/// hold up the others.
+#[tracing::instrument(skip_all)]
+pub async fn deliver_queued_activities(
+  context: &LemmyContext,
+  clock: &dyn Clock,
+) -> Result<(), LemmyError> {
+  let now = clock.now();
+  let claimed_until = now + chrono::Duration::seconds(CLAIM_DURATION_SECONDS);
+  let due = blocking(context.pool(), move |conn| {
+    QueuedActivity::claim_due(conn, now, claimed_until, BATCH_SIZE)
+  })
+  .await??;
+
+  let mut by_instance: HashMap<String, Vec<QueuedActivity>> = HashMap::new();
+  for queued in due {
+    let inbox: Url = queued.inbox.clone().into();
+    let domain = inbox.domain().unwrap_or_default().to_string();
+    by_instance.entry(domain).or_default().push(queued);
+  }
+  stream::iter(by_instance)
+    .for_each_concurrent(MAX_PARALLEL_INSTANCES, |(domain, queued)| {
+      deliver_to_instance(domain, queued, clock, context)
+    })
+    .await;
+  Ok(())
+}
+
+/// Delivers the activities one after another, in the order they were queued
+async fn deliver_to_instance(
+  domain: String,
+  queued: Vec<QueuedActivity>,
+  clock: &dyn Clock,
+  context: &LemmyContext,
+) {
+  for queued in queued {
+    if let Err(e) = deliver_queued_activity(queued, &domain, clock, context).await {
+      warn!("Failed to process queued activity: {}", e);
+    }
+  }
+}
+
+async fn deliver_queued_activity(
+  queued: QueuedActivity,
+  domain: &str,
+  clock: &dyn Clock,
+  context: &LemmyContext,
+) -> Result<(), LemmyError> {
+  let queued_id = queued.id;
+  let inbox: Url = queued.inbox.clone().into();
+
+  // Don't even try while the instance is known to be failing, but keep the delivery. This is
+  // checked before every delivery, so that a failure also postpones the rest of the batch.
+  let domain_ = domain.to_string();
+  let failure = blocking(context.pool(), move |conn| {
+    InstanceSendFailure::read_for_domain(conn, &domain_)
+  })
+  .await??;
+  if let Some(failure) = failure {
+    let retry_at = failure.last_failure + retry_delay(failure.failures);
+    if retry_at > clock.now() {
+      blocking(context.pool(), move |conn| {
+        QueuedActivity::postpone(conn, queued_id, retry_at)
+      })
+      .await??;
+      return Ok(());
+    }
+  }
+
+  let actor_id: Url = queued.actor_id.clone().into();
+  let private_key =
+    blocking(context.pool(), move |conn| read_private_key(conn, actor_id)).await??;
+  let private_key = match private_key {
+    Some(k) => k,
+    None => {
+      warn!(
+        "Dropping activity {}, actor {} has no private key",
+        queued.activity_id, queued.actor_id
+      );
+      blocking(context.pool(), move |conn| {
+        QueuedActivity::delete(conn, queued_id)
+      })
+      .await??;
+      return Ok(());
+    }
+  };
+
+  let activity_id: Url = queued.activity_id.into();
+  let actor_id: Url = queued.actor_id.into();
+  let result = deliver_activity(
+    &activity_id,
+    &inbox,
+    &actor_id,
+    queued.activity,
+    private_key,
+    queued.request_id.as_deref(),
+    context.client(),
+  )
+  .await;
+
+  let now = clock.now();
+  let retries = queued.retries + 1;
+  let domain = domain.to_string();
+  blocking(context.pool(), move |conn| {
+    if let Err(e) = result {
+      InstanceSendFailure::record_failure(conn, &domain, now)?;
+      if retries >= MAX_RETRIES {
+        warn!(
+          "Giving up on {} after {} attempts: {}",
+          activity_id, retries, e
+        );
+        QueuedActivity::delete(conn, queued_id)?;
+      } else {
+        QueuedActivity::mark_failed(conn, queued_id, now + retry_delay(retries))?;
+      }
+    } else {
+      InstanceSendFailure::clear(conn, &domain)?;
+      QueuedActivity::delete(conn, queued_id)?;
+    }
+    Ok(()) as Result<(), diesel::result::Error>
+  })
+  .await??;
+  Ok(())
+}
+
+/// Doubles with each consecutive failure, up to a day
+fn retry_delay(failures: i32) -> chrono::Duration {
+  let exponent = (failures - 1).clamp(0, 20) as u32;
+  let seconds = FIRST_RETRY_DELAY_SECONDS.saturating_mul(2i64.pow(exponent));
+  chrono::Duration::seconds(seconds.min(MAX_RETRY_DELAY_SECONDS))
+}
+
+/// Activities are only sent by local persons and communities
+fn read_private_key(
+  conn: &diesel::PgConnection,
+  actor_id: Url,
+) -> Result<Option<String>, diesel::result::Error> {
+  if let Some(person) = Person::read_from_apub_id(conn, actor_id.clone())? {
+    return Ok(person.private_key);
+  }
+  Ok(Community::read_from_apub_id(conn, actor_id)?.and_then(|c| c.private_key))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_retry_delay() {
+    assert_eq!(30, retry_delay(1).num_seconds());
+    assert_eq!(60, retry_delay(2).num_seconds());
+    assert_eq!(30 * 256, retry_delay(9).num_seconds());
+    assert_eq!(MAX_RETRY_DELAY_SECONDS, retry_delay(15).num_seconds());
+    assert_eq!(MAX_RETRY_DELAY_SECONDS, retry_delay(i32::MAX).num_seconds());
+  }
+}
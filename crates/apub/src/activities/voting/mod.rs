@@ -1,10 +1,10 @@
 use lemmy_api_common::blocking;
 use lemmy_db_schema::{
   source::{
-    comment::{CommentLike, CommentLikeForm},
-    post::{PostLike, PostLikeForm},
+    comment::{CommentLike, CommentLikeForm, CommentReaction, CommentReactionForm},
+    post::{PostLike, PostLikeForm, PostReaction, PostReactionForm},
   },
-  traits::Likeable,
+  traits::{Likeable, Reactable},
 };
 use lemmy_utils::LemmyError;
 use lemmy_websocket::{
@@ -18,6 +18,7 @@ use crate::{
   protocol::activities::voting::vote::VoteType,
 };
 
+pub mod poll_vote;
 pub mod undo_vote;
 pub mod vote;
 
@@ -103,3 +104,83 @@ async fn undo_vote_post(
   send_post_ws_message(post_id, UserOperation::CreatePostLike, None, None, context).await?;
   Ok(())
 }
+
+#[tracing::instrument(skip_all)]
+async fn react_comment(
+  emoji: String,
+  actor: ApubPerson,
+  comment: &ApubComment,
+  context: &LemmyContext,
+) -> Result<(), LemmyError> {
+  let comment_id = comment.id;
+  let reaction_form = CommentReactionForm {
+    comment_id,
+    person_id: actor.id,
+    emoji,
+  };
+  blocking(context.pool(), move |conn| {
+    CommentReaction::react(conn, &reaction_form)
+  })
+  .await??;
+
+  send_comment_ws_message_simple(comment_id, UserOperation::ReactComment, context).await?;
+  Ok(())
+}
+
+#[tracing::instrument(skip_all)]
+async fn react_post(
+  emoji: String,
+  actor: ApubPerson,
+  post: &ApubPost,
+  context: &LemmyContext,
+) -> Result<(), LemmyError> {
+  let post_id = post.id;
+  let reaction_form = PostReactionForm {
+    post_id,
+    person_id: actor.id,
+    emoji,
+  };
+  blocking(context.pool(), move |conn| {
+    PostReaction::react(conn, &reaction_form)
+  })
+  .await??;
+
+  send_post_ws_message(post_id, UserOperation::ReactPost, None, None, context).await?;
+  Ok(())
+}
+
+#[tracing::instrument(skip_all)]
+async fn unreact_comment(
+  emoji: String,
+  actor: ApubPerson,
+  comment: &ApubComment,
+  context: &LemmyContext,
+) -> Result<(), LemmyError> {
+  let comment_id = comment.id;
+  let person_id = actor.id;
+  blocking(context.pool(), move |conn| {
+    CommentReaction::unreact(conn, person_id, comment_id, &emoji)
+  })
+  .await??;
+
+  send_comment_ws_message_simple(comment_id, UserOperation::UnreactComment, context).await?;
+  Ok(())
+}
+
+#[tracing::instrument(skip_all)]
+async fn unreact_post(
+  emoji: String,
+  actor: ApubPerson,
+  post: &ApubPost,
+  context: &LemmyContext,
+) -> Result<(), LemmyError> {
+  let post_id = post.id;
+  let person_id = actor.id;
+  blocking(context.pool(), move |conn| {
+    PostReaction::unreact(conn, person_id, post_id, &emoji)
+  })
+  .await??;
+
+  send_post_ws_message(post_id, UserOperation::UnreactPost, None, None, context).await?;
+  Ok(())
+}
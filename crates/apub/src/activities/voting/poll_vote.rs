@@ -0,0 +1,153 @@
+use crate::{
+  activities::{
+    generate_activity_id,
+    send_lemmy_activity,
+    verify_activity,
+    verify_person_in_community,
+  },
+  objects::{community::ApubCommunity, person::ApubPerson, post::ApubPost},
+  protocol::activities::{
+    create_or_update::post::CreateOrUpdatePost,
+    voting::poll_vote::{PollVote, PollVoteNote},
+    CreateOrUpdateType,
+  },
+};
+use activitystreams_kinds::{activity::CreateType, object::NoteType};
+use lemmy_api_common::blocking;
+use lemmy_apub_lib::{
+  data::Data,
+  object_id::ObjectId,
+  traits::{ActivityHandler, ActorType},
+  verify::verify_domains_match,
+};
+use lemmy_db_schema::{
+  clock::{Clock, SystemClock},
+  source::{
+    community::Community,
+    person::Person,
+    poll::{Poll, PollOption, PollVote as DbPollVote, PollVoteForm},
+  },
+  traits::Crud,
+};
+use lemmy_utils::LemmyError;
+use lemmy_websocket::LemmyContext;
+
+impl PollVote {
+  /// Sends the chosen options of a remote poll to the instance of the poll creator, which then
+  /// federates the new vote counts.
+  #[tracing::instrument(skip_all)]
+  pub async fn send(
+    post: &ApubPost,
+    option_names: Vec<String>,
+    actor: &ApubPerson,
+    context: &LemmyContext,
+  ) -> Result<(), LemmyError> {
+    let creator_id = post.creator_id;
+    let creator: ApubPerson = blocking(context.pool(), move |conn| Person::read(conn, creator_id))
+      .await??
+      .into();
+    let protocol_and_hostname = context.settings().get_protocol_and_hostname();
+    let inbox = vec![creator.shared_inbox_or_inbox_url()];
+
+    for name in option_names {
+      let id = generate_activity_id(CreateType::Create, &protocol_and_hostname)?;
+      let vote = PollVote {
+        actor: ObjectId::new(actor.actor_id()),
+        to: vec![creator.actor_id()],
+        object: PollVoteNote {
+          r#type: NoteType::Note,
+          id: generate_activity_id(NoteType::Note, &protocol_and_hostname)?,
+          name,
+          in_reply_to: ObjectId::new(post.ap_id.clone()),
+          attributed_to: ObjectId::new(actor.actor_id()),
+          to: vec![creator.actor_id()],
+          unparsed: Default::default(),
+        },
+        kind: CreateType::Create,
+        id: id.clone(),
+        unparsed: Default::default(),
+      };
+      send_lemmy_activity(context, &vote, &id, actor, inbox.clone(), true).await?;
+    }
+    Ok(())
+  }
+}
+
+#[async_trait::async_trait(?Send)]
+impl ActivityHandler for PollVote {
+  type DataType = LemmyContext;
+
+  #[tracing::instrument(skip_all)]
+  async fn verify(
+    &self,
+    context: &Data<LemmyContext>,
+    request_counter: &mut i32,
+  ) -> Result<(), LemmyError> {
+    verify_activity(&self.id, self.actor.inner(), &context.settings())?;
+    verify_domains_match(self.actor.inner(), &self.object.id)?;
+    verify_domains_match(self.actor.inner(), self.object.attributed_to.inner())?;
+    let post = self.object.in_reply_to.dereference_local(context).await?;
+    if !post.local {
+      return Err(LemmyError::from_message("poll_not_local"));
+    }
+    let community_id = post.community_id;
+    let community: ApubCommunity = blocking(context.pool(), move |conn| {
+      Community::read(conn, community_id)
+    })
+    .await??
+    .into();
+    verify_person_in_community(&self.actor, &community, context, request_counter).await?;
+    Ok(())
+  }
+
+  #[tracing::instrument(skip_all)]
+  async fn receive(
+    self,
+    context: &Data<LemmyContext>,
+    request_counter: &mut i32,
+  ) -> Result<(), LemmyError> {
+    let actor = self
+      .actor
+      .dereference(context, context.client(), request_counter)
+      .await?;
+    let post = self.object.in_reply_to.dereference_local(context).await?;
+    let post_id = post.id;
+    let person_id = actor.id;
+    let option_name = self.object.name;
+    let voted = blocking(context.pool(), move |conn| -> Result<bool, LemmyError> {
+      let poll = Poll::read_for_post(conn, post_id)?;
+      if poll.has_ended(SystemClock.now()) {
+        return Err(LemmyError::from_message("poll_ended"));
+      }
+      let option = PollOption::list_for_poll(conn, poll.id)?
+        .into_iter()
+        .find(|o| o.name == option_name)
+        .ok_or_else(|| LemmyError::from_message("couldnt_find_poll_option"))?;
+      // In a single choice poll, only the first vote counts
+      if !poll.multiple_choice && !DbPollVote::list_for_person(conn, poll.id, person_id)?.is_empty()
+      {
+        return Ok(false);
+      }
+      let form = PollVoteForm {
+        poll_option_id: option.id,
+        person_id,
+      };
+      let inserted = DbPollVote::vote(conn, &form)? > 0;
+      if inserted {
+        PollOption::update_vote_counts(conn, poll.id)?;
+      }
+      Ok(inserted)
+    })
+    .await??;
+
+    if voted {
+      let creator_id = post.creator_id;
+      let creator: ApubPerson =
+        blocking(context.pool(), move |conn| Person::read(conn, creator_id))
+          .await??
+          .into();
+      CreateOrUpdatePost::send(post, &creator, CreateOrUpdateType::Update, context).await?;
+    }
+    Ok(())
+  }
+}
@@ -5,7 +5,7 @@ use crate::{
     verify_activity,
     verify_is_public,
     verify_person_in_community,
-    voting::{undo_vote_comment, undo_vote_post},
+    voting::{undo_vote_comment, undo_vote_post, unreact_comment, unreact_post},
   },
   activity_lists::AnnouncableActivities,
   objects::{community::ApubCommunity, person::ApubPerson},
@@ -24,7 +24,7 @@ use lemmy_apub_lib::{
   verify::verify_urls_match,
 };
 use lemmy_db_schema::{newtypes::CommunityId, source::community::Community, traits::Crud};
-use lemmy_utils::LemmyError;
+use lemmy_utils::{utils::is_valid_reaction, LemmyError};
 use lemmy_websocket::LemmyContext;
 
 impl UndoVote {
@@ -35,6 +35,30 @@ impl UndoVote {
     community_id: CommunityId,
     kind: VoteType,
     context: &LemmyContext,
+  ) -> Result<(), LemmyError> {
+    UndoVote::send_with_content(object, actor, community_id, kind, None, context).await
+  }
+
+  /// Removes an emoji reaction
+  #[tracing::instrument(skip_all)]
+  pub async fn send_reaction(
+    object: &PostOrComment,
+    actor: &ApubPerson,
+    community_id: CommunityId,
+    emoji: String,
+    context: &LemmyContext,
+  ) -> Result<(), LemmyError> {
+    let kind = VoteType::Like;
+    UndoVote::send_with_content(object, actor, community_id, kind, Some(emoji), context).await
+  }
+
+  async fn send_with_content(
+    object: &PostOrComment,
+    actor: &ApubPerson,
+    community_id: CommunityId,
+    kind: VoteType,
+    content: Option<String>,
+    context: &LemmyContext,
   ) -> Result<(), LemmyError> {
     let community: ApubCommunity = blocking(context.pool(), move |conn| {
       Community::read(conn, community_id)
@@ -42,7 +66,7 @@ impl UndoVote {
     .await??
     .into();
 
-    let object = Vote::new(object, actor, &community, kind.clone(), context)?;
+    let object = Vote::new(object, actor, &community, kind, content, context)?;
     let id = generate_activity_id(
       UndoType::Undo,
       &context.settings().get_protocol_and_hostname(),
@@ -95,9 +119,11 @@ impl ActivityHandler for UndoVote {
       .object
       .dereference(context, context.client(), request_counter)
       .await?;
-    match object {
-      PostOrComment::Post(p) => undo_vote_post(actor, &p, context).await,
-      PostOrComment::Comment(c) => undo_vote_comment(actor, &c, context).await,
+    match (object, self.object.content.filter(|c| is_valid_reaction(c))) {
+      (PostOrComment::Post(p), Some(emoji)) => unreact_post(emoji, actor, &p, context).await,
+      (PostOrComment::Comment(c), Some(emoji)) => unreact_comment(emoji, actor, &c, context).await,
+      (PostOrComment::Post(p), None) => undo_vote_post(actor, &p, context).await,
+      (PostOrComment::Comment(c), None) => undo_vote_comment(actor, &c, context).await,
     }
   }
 }
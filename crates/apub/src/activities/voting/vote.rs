@@ -5,7 +5,7 @@ use crate::{
     verify_activity,
     verify_is_public,
     verify_person_in_community,
-    voting::{vote_comment, vote_post},
+    voting::{react_comment, react_post, vote_comment, vote_post},
   },
   activity_lists::AnnouncableActivities,
   objects::{community::ApubCommunity, person::ApubPerson},
@@ -24,7 +24,7 @@ use lemmy_db_schema::{
   source::{community::Community, post::Post},
   traits::Crud,
 };
-use lemmy_utils::LemmyError;
+use lemmy_utils::{utils::is_valid_reaction, LemmyError};
 use lemmy_websocket::LemmyContext;
 
 impl Vote {
@@ -33,6 +33,7 @@ impl Vote {
     actor: &ApubPerson,
     community: &ApubCommunity,
     kind: VoteType,
+    content: Option<String>,
     context: &LemmyContext,
   ) -> Result<Vote, LemmyError> {
     Ok(Vote {
@@ -42,6 +43,7 @@ impl Vote {
       cc: vec![community.actor_id()],
       kind: kind.clone(),
       id: generate_activity_id(kind, &context.settings().get_protocol_and_hostname())?,
+      content,
       unparsed: Default::default(),
     })
   }
@@ -53,13 +55,37 @@ impl Vote {
     community_id: CommunityId,
     kind: VoteType,
     context: &LemmyContext,
+  ) -> Result<(), LemmyError> {
+    Vote::send_with_content(object, actor, community_id, kind, None, context).await
+  }
+
+  /// Sends an emoji reaction, as Like with the emoji as content
+  #[tracing::instrument(skip_all)]
+  pub async fn send_reaction(
+    object: &PostOrComment,
+    actor: &ApubPerson,
+    community_id: CommunityId,
+    emoji: String,
+    context: &LemmyContext,
+  ) -> Result<(), LemmyError> {
+    let kind = VoteType::Like;
+    Vote::send_with_content(object, actor, community_id, kind, Some(emoji), context).await
+  }
+
+  async fn send_with_content(
+    object: &PostOrComment,
+    actor: &ApubPerson,
+    community_id: CommunityId,
+    kind: VoteType,
+    content: Option<String>,
+    context: &LemmyContext,
   ) -> Result<(), LemmyError> {
     let community = blocking(context.pool(), move |conn| {
       Community::read(conn, community_id)
     })
     .await??
     .into();
-    let vote = Vote::new(object, actor, &community, kind, context)?;
+    let vote = Vote::new(object, actor, &community, kind, content, context)?;
     let vote_id = vote.id.clone();
 
     let activity = AnnouncableActivities::Vote(vote);
@@ -98,9 +124,13 @@ impl ActivityHandler for Vote {
       .object
       .dereference(context, context.client(), request_counter)
       .await?;
-    match object {
-      PostOrComment::Post(p) => vote_post(&self.kind, actor, &p, context).await,
-      PostOrComment::Comment(c) => vote_comment(&self.kind, actor, &c, context).await,
+    // Other software may send reactions which Lemmy doesn't support, like custom emojis. These
+    // are counted as normal votes.
+    match (object, self.content.filter(|c| is_valid_reaction(c))) {
+      (PostOrComment::Post(p), Some(emoji)) => react_post(emoji, actor, &p, context).await,
+      (PostOrComment::Comment(c), Some(emoji)) => react_comment(emoji, actor, &c, context).await,
+      (PostOrComment::Post(p), None) => vote_post(&self.kind, actor, &p, context).await,
+      (PostOrComment::Comment(c), None) => vote_comment(&self.kind, actor, &c, context).await,
     }
   }
 }
@@ -0,0 +1,144 @@
+use crate::{
+  activities::{
+    check_community_deleted_or_removed,
+    community::{announce::GetCommunity, send_activity_in_community},
+    generate_activity_id,
+    verify_activity,
+    verify_is_public,
+    verify_mod_action,
+    verify_person_in_community,
+  },
+  activity_lists::AnnouncableActivities,
+  objects::{community::ApubCommunity, person::ApubPerson, wiki_page::ApubWikiPage},
+  protocol::activities::{create_or_update::wiki_page::CreateOrUpdateWikiPage, CreateOrUpdateType},
+};
+use activitystreams_kinds::public;
+use lemmy_api_common::blocking;
+use lemmy_apub_lib::{
+  data::Data,
+  object_id::ObjectId,
+  traits::{ActivityHandler, ActorType, ApubObject},
+  verify::{verify_domains_match, verify_urls_match},
+};
+use lemmy_db_schema::{
+  source::{
+    community::Community,
+    community_wiki_page::{CommunityWikiPageRevision, CommunityWikiPageRevisionForm},
+  },
+  traits::Crud,
+};
+use lemmy_utils::LemmyError;
+use lemmy_websocket::LemmyContext;
+
+impl CreateOrUpdateWikiPage {
+  #[tracing::instrument(skip_all)]
+  pub async fn send(
+    wiki_page: ApubWikiPage,
+    actor: &ApubPerson,
+    kind: CreateOrUpdateType,
+    context: &LemmyContext,
+  ) -> Result<(), LemmyError> {
+    let community_id = wiki_page.community_id;
+    let community: ApubCommunity = blocking(context.pool(), move |conn| {
+      Community::read(conn, community_id)
+    })
+    .await??
+    .into();
+
+    let id = generate_activity_id(
+      kind.clone(),
+      &context.settings().get_protocol_and_hostname(),
+    )?;
+    let create_or_update = CreateOrUpdateWikiPage {
+      actor: ObjectId::new(actor.actor_id()),
+      to: vec![public()],
+      object: wiki_page.into_apub(context).await?,
+      cc: vec![community.actor_id()],
+      kind,
+      id: id.clone(),
+      unparsed: Default::default(),
+    };
+    let activity = AnnouncableActivities::CreateOrUpdateWikiPage(create_or_update);
+    send_activity_in_community(activity, &id, actor, &community, vec![], context).await
+  }
+}
+
+#[async_trait::async_trait(?Send)]
+impl ActivityHandler for CreateOrUpdateWikiPage {
+  type DataType = LemmyContext;
+
+  #[tracing::instrument(skip_all)]
+  async fn verify(
+    &self,
+    context: &Data<LemmyContext>,
+    request_counter: &mut i32,
+  ) -> Result<(), LemmyError> {
+    verify_is_public(&self.to, &self.cc)?;
+    verify_activity(&self.id, self.actor.inner(), &context.settings())?;
+    let community = self.get_community(context, request_counter).await?;
+    verify_person_in_community(&self.actor, &community, context, request_counter).await?;
+    check_community_deleted_or_removed(&community)?;
+    // Wiki pages are maintained by the mods, any mod can edit a page which another one created
+    verify_mod_action(&self.actor, &community, context, request_counter).await?;
+    if self.kind == CreateOrUpdateType::Create {
+      verify_domains_match(self.actor.inner(), self.object.id.inner())?;
+      verify_urls_match(self.actor.inner(), self.object.attributed_to.inner())?;
+    }
+    ApubWikiPage::verify(
+      &self.object,
+      self.object.id.inner(),
+      context,
+      request_counter,
+    )
+    .await?;
+    Ok(())
+  }
+
+  #[tracing::instrument(skip_all)]
+  async fn receive(
+    self,
+    context: &Data<LemmyContext>,
+    request_counter: &mut i32,
+  ) -> Result<(), LemmyError> {
+    let old_content = ObjectId::<ApubWikiPage>::new(self.object.id.inner().clone())
+      .dereference_local(context)
+      .await
+      .ok()
+      .map(|p| p.content.clone());
+    let wiki_page = ApubWikiPage::from_apub(self.object, context, request_counter).await?;
+
+    // The revision is attributed to the mod who sent the activity, not the page creator
+    if old_content.as_ref() != Some(&wiki_page.content) {
+      let editor = self
+        .actor
+        .dereference(context, context.client(), request_counter)
+        .await?;
+      let form = CommunityWikiPageRevisionForm {
+        wiki_page_id: wiki_page.id,
+        editor_id: editor.id,
+        content: wiki_page.content.clone(),
+      };
+      blocking(context.pool(), move |conn| {
+        CommunityWikiPageRevision::create(conn, &form)
+      })
+      .await??;
+    }
+    Ok(())
+  }
+}
+
+#[async_trait::async_trait(?Send)]
+impl GetCommunity for CreateOrUpdateWikiPage {
+  #[tracing::instrument(skip_all)]
+  async fn get_community(
+    &self,
+    context: &LemmyContext,
+    request_counter: &mut i32,
+  ) -> Result<ApubCommunity, LemmyError> {
+    self
+      .object
+      .audience
+      .dereference(context, context.client(), request_counter)
+      .await
+  }
+}
@@ -7,24 +7,36 @@ use crate::{
         add_mod::AddMod,
         announce::AnnounceActivity,
         block_user::BlockUserFromCommunity,
+        feature_post::FeaturePost,
         remove_mod::RemoveMod,
+        remove_user_content::RemoveUserContent,
         report::Report,
         undo_block_user::UndoBlockUserFromCommunity,
         update::UpdateCommunity,
       },
-      create_or_update::{comment::CreateOrUpdateComment, post::CreateOrUpdatePost},
-      deletion::{delete::Delete, undo_delete::UndoDelete},
+      create_or_update::{
+        comment::CreateOrUpdateComment,
+        post::CreateOrUpdatePost,
+        wiki_page::CreateOrUpdateWikiPage,
+      },
+      deletion::{
+        delete::Delete,
+        remove::Remove,
+        undo_delete::UndoDelete,
+        undo_remove::UndoRemove,
+      },
       following::{
         accept::AcceptFollowCommunity,
         follow::FollowCommunity,
         undo_follow::UndoFollowCommunity,
       },
+      instance::{block::BlockInstance, undo_block::UndoBlockInstance, update::UpdateInstance},
       private_message::{
         create_or_update::CreateOrUpdatePrivateMessage,
         delete::DeletePrivateMessage,
         undo_delete::UndoDeletePrivateMessage,
       },
-      voting::{undo_vote::UndoVote, vote::Vote},
+      voting::{poll_vote::PollVote, undo_vote::UndoVote, vote::Vote},
     },
     objects::page::Page,
   },
@@ -38,10 +50,15 @@ use serde::{Deserialize, Serialize};
 #[serde(untagged)]
 #[activity_handler(LemmyContext)]
 pub enum SharedInboxActivities {
+  /// Direct messages from Mastodon are sent as Create/Note, which would otherwise be parsed as
+  /// comment. Only notes which are addressed to a single person end up here.
+  CreateOrUpdatePrivateMessage(Box<CreateOrUpdatePrivateMessage>),
   GroupInboxActivities(Box<GroupInboxActivities>),
   // Note, pm activities need to be at the end, otherwise comments will end up here. We can probably
   // avoid this problem by replacing createpm.object with our own struct, instead of NoteExt.
   PersonInboxActivities(Box<PersonInboxActivities>),
+  /// Has to come last, as a block of an instance is a subset of a block from a community
+  InstanceInboxActivities(Box<InstanceInboxActivities>),
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize, ActivityHandler)]
@@ -59,20 +76,34 @@ pub enum GroupInboxActivities {
 #[activity_handler(LemmyContext)]
 pub enum PersonInboxActivities {
   AcceptFollowCommunity(AcceptFollowCommunity),
+  /// Has to come before comments, as direct messages can also be sent as Create/Note
+  CreateOrUpdatePrivateMessage(CreateOrUpdatePrivateMessage),
   /// Some activities can also be sent from user to user, eg a comment with mentions
   AnnouncableActivities(AnnouncableActivities),
-  CreateOrUpdatePrivateMessage(CreateOrUpdatePrivateMessage),
+  /// Has to come after comments, which are also sent as Create/Note
+  PollVote(PollVote),
   DeletePrivateMessage(DeletePrivateMessage),
   UndoDeletePrivateMessage(UndoDeletePrivateMessage),
   AnnounceActivity(AnnounceActivity),
 }
 
+/// Activities which are sent by instance actors
+#[derive(Clone, Debug, Deserialize, Serialize, ActivityHandler)]
+#[serde(untagged)]
+#[activity_handler(LemmyContext)]
+pub enum InstanceInboxActivities {
+  Update(Box<UpdateInstance>),
+  Block(Box<BlockInstance>),
+  UndoBlock(Box<UndoBlockInstance>),
+}
+
 #[derive(Clone, Debug, Deserialize, Serialize, ActivityHandler)]
 #[serde(untagged)]
 #[activity_handler(LemmyContext)]
 pub enum AnnouncableActivities {
   CreateOrUpdateComment(CreateOrUpdateComment),
   CreateOrUpdatePost(CreateOrUpdatePost),
+  CreateOrUpdateWikiPage(CreateOrUpdateWikiPage),
   Vote(Vote),
   UndoVote(UndoVote),
   Delete(Delete),
@@ -82,10 +113,27 @@ pub enum AnnouncableActivities {
   UndoBlockUserFromCommunity(UndoBlockUserFromCommunity),
   AddMod(AddMod),
   RemoveMod(RemoveMod),
+  FeaturePost(FeaturePost),
+  RemoveUserContent(RemoveUserContent),
+  Remove(Remove),
+  UndoRemove(UndoRemove),
   // For compatibility with Pleroma/Mastodon (send only)
   Page(Page),
 }
 
+impl AnnouncableActivities {
+  pub(crate) fn is_deletion(&self) -> bool {
+    matches!(
+      self,
+      AnnouncableActivities::Delete(_)
+        | AnnouncableActivities::UndoDelete(_)
+        | AnnouncableActivities::Remove(_)
+        | AnnouncableActivities::UndoRemove(_)
+        | AnnouncableActivities::RemoveUserContent(_)
+    )
+  }
+}
+
 #[async_trait::async_trait(?Send)]
 impl GetCommunity for AnnouncableActivities {
   #[tracing::instrument(skip(self, context))]
@@ -98,6 +146,7 @@ impl GetCommunity for AnnouncableActivities {
     let community = match self {
       CreateOrUpdateComment(a) => a.get_community(context, request_counter).await?,
       CreateOrUpdatePost(a) => a.get_community(context, request_counter).await?,
+      CreateOrUpdateWikiPage(a) => a.get_community(context, request_counter).await?,
       Vote(a) => a.get_community(context, request_counter).await?,
       UndoVote(a) => a.get_community(context, request_counter).await?,
       Delete(a) => a.get_community(context, request_counter).await?,
@@ -107,6 +156,10 @@ impl GetCommunity for AnnouncableActivities {
       UndoBlockUserFromCommunity(a) => a.get_community(context, request_counter).await?,
       AddMod(a) => a.get_community(context, request_counter).await?,
       RemoveMod(a) => a.get_community(context, request_counter).await?,
+      FeaturePost(a) => a.get_community(context, request_counter).await?,
+      RemoveUserContent(a) => a.get_community(context, request_counter).await?,
+      Remove(a) => a.get_community(context, request_counter).await?,
+      UndoRemove(a) => a.get_community(context, request_counter).await?,
       Page(_) => unimplemented!(),
     };
     Ok(community)
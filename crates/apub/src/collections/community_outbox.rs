@@ -1,11 +1,11 @@
 use crate::{
   activity_lists::AnnouncableActivities,
-  collections::CommunityContext,
+  collections::{outbox_last_page, outbox_page_url, CommunityContext, OUTBOX_PAGE_SIZE},
   generate_outbox_url,
   objects::post::ApubPost,
   protocol::{
     activities::community::announce::AnnounceActivity,
-    collections::group_outbox::GroupOutbox,
+    collections::{group_outbox::GroupOutbox, outbox_page::OutboxPage},
   },
 };
 use activitystreams_kinds::collection::OrderedCollectionType;
@@ -20,8 +20,44 @@ use lemmy_db_schema::source::post::Post;
 use lemmy_utils::LemmyError;
 use url::Url;
 
+/// The newest posts of the community, and the total number of posts in it
 #[derive(Clone, Debug)]
-pub(crate) struct ApubCommunityOutbox(Vec<ApubPost>);
+pub(crate) struct ApubCommunityOutbox(Vec<ApubPost>, i64);
+
+impl ApubCommunityOutbox {
+  /// Returns a single page of the outbox of a local community, for remote instances which want
+  /// to backfill older posts.
+  #[tracing::instrument(skip_all)]
+  pub(crate) async fn read_page(
+    page: i64,
+    data: &CommunityContext,
+  ) -> Result<OutboxPage<AnnounceActivity>, LemmyError> {
+    let community_id = data.0.id;
+    let (posts, total_items) = blocking(data.1.pool(), move |conn| {
+      Ok::<_, LemmyError>((
+        Post::list_for_community(conn, community_id, Some(page), Some(OUTBOX_PAGE_SIZE))?,
+        Post::count_for_community(conn, community_id)?,
+      ))
+    })
+    .await??;
+    let ordered_items = announce_posts(posts.into_iter().map(Into::into).collect(), data).await?;
+    let outbox_id = generate_outbox_url(&data.0.actor_id)?.into();
+    OutboxPage::new(&outbox_id, page, total_items, ordered_items)
+  }
+}
+
+async fn announce_posts(
+  posts: Vec<ApubPost>,
+  data: &CommunityContext,
+) -> Result<Vec<AnnounceActivity>, LemmyError> {
+  let mut announces = vec![];
+  for post in posts {
+    let page = post.into_apub(&data.1).await?;
+    let announcable = AnnouncableActivities::Page(page);
+    announces.push(AnnounceActivity::new(announcable, &data.0, &data.1)?);
+  }
+  Ok(announces)
+}
 
 #[async_trait::async_trait(?Send)]
 impl ApubObject for ApubCommunityOutbox {
@@ -41,14 +77,15 @@ impl ApubObject for ApubCommunityOutbox {
     // Only read from database if its a local community, otherwise fetch over http
     if data.0.local {
       let community_id = data.0.id;
-      let post_list: Vec<ApubPost> = blocking(data.1.pool(), move |conn| {
-        Post::list_for_community(conn, community_id)
+      let (post_list, total_items) = blocking(data.1.pool(), move |conn| {
+        Ok::<_, LemmyError>((
+          Post::list_for_community(conn, community_id, None, Some(OUTBOX_PAGE_SIZE))?,
+          Post::count_for_community(conn, community_id)?,
+        ))
       })
-      .await??
-      .into_iter()
-      .map(Into::into)
-      .collect();
-      Ok(Some(ApubCommunityOutbox(post_list)))
+      .await??;
+      let post_list = post_list.into_iter().map(Into::into).collect();
+      Ok(Some(ApubCommunityOutbox(post_list, total_items)))
     } else {
       Ok(None)
     }
@@ -61,19 +98,15 @@ impl ApubObject for ApubCommunityOutbox {
 
   #[tracing::instrument(skip_all)]
   async fn into_apub(self, data: &Self::DataType) -> Result<Self::ApubType, LemmyError> {
-    let mut ordered_items = vec![];
-    for post in self.0 {
-      let page = post.into_apub(&data.1).await?;
-      let announcable = AnnouncableActivities::Page(page);
-      let announce = AnnounceActivity::new(announcable, &data.0, &data.1)?;
-      ordered_items.push(announce);
-    }
-
+    let id: Url = generate_outbox_url(&data.0.actor_id)?.into();
+    let total_items = self.1;
     Ok(GroupOutbox {
       r#type: OrderedCollectionType::OrderedCollection,
-      id: generate_outbox_url(&data.0.actor_id)?.into(),
-      total_items: ordered_items.len() as i32,
-      ordered_items,
+      total_items: total_items as i32,
+      ordered_items: announce_posts(self.0, data).await?,
+      first: Some(outbox_page_url(&id, 1)?),
+      last: Some(outbox_page_url(&id, outbox_last_page(total_items))?),
+      id,
     })
   }
 
@@ -100,8 +133,8 @@ impl ApubObject for ApubCommunityOutbox {
     request_counter: &mut i32,
   ) -> Result<Self, LemmyError> {
     let mut outbox_activities = apub.ordered_items;
-    if outbox_activities.len() > 20 {
-      outbox_activities = outbox_activities[0..20].to_vec();
+    if outbox_activities.len() > OUTBOX_PAGE_SIZE as usize {
+      outbox_activities = outbox_activities[0..OUTBOX_PAGE_SIZE as usize].to_vec();
     }
 
     // We intentionally ignore errors here. This is because the outbox might contain posts from old
@@ -116,6 +149,6 @@ impl ApubObject for ApubCommunityOutbox {
     }
 
     // This return value is unused, so just set an empty vec
-    Ok(ApubCommunityOutbox { 0: vec![] })
+    Ok(ApubCommunityOutbox(vec![], 0))
   }
 }
@@ -1,9 +1,23 @@
+use lemmy_utils::LemmyError;
 use lemmy_websocket::LemmyContext;
+use url::Url;
 
 use crate::objects::community::ApubCommunity;
 
 pub(crate) mod community_moderators;
 pub(crate) mod community_outbox;
 
+/// Number of activities in each page of a person or community outbox
+pub(crate) const OUTBOX_PAGE_SIZE: i64 = 20;
+
 /// Put community in the data, so we dont have to read it again from the database.
 pub(crate) struct CommunityContext(pub ApubCommunity, pub LemmyContext);
+
+pub(crate) fn outbox_page_url(outbox_id: &Url, page: i64) -> Result<Url, LemmyError> {
+  Ok(Url::parse(&format!("{}?page={}", outbox_id, page))?)
+}
+
+/// Number of the last outbox page, there is always at least one (possibly empty) page.
+pub(crate) fn outbox_last_page(total_items: i64) -> i64 {
+  ((total_items + OUTBOX_PAGE_SIZE - 1) / OUTBOX_PAGE_SIZE).max(1)
+}
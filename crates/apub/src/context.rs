@@ -5,6 +5,12 @@ static CONTEXT: Lazy<Vec<serde_json::Value>> = Lazy::new(|| {
   serde_json::from_str(include_str!("../assets/lemmy/context.json")).expect("parse context")
 });
 
+static MINIMAL_CONTEXT: Lazy<Vec<serde_json::Value>> = Lazy::new(|| {
+  vec![serde_json::Value::from(
+    "https://www.w3.org/ns/activitystreams",
+  )]
+});
+
 #[derive(Serialize, Deserialize, Debug)]
 pub(crate) struct WithContext<T> {
   #[serde(rename = "@context")]
@@ -21,6 +27,13 @@ impl<T> WithContext<T> {
       inner,
     }
   }
+  /// Only includes the activitystreams vocabulary, without any Lemmy extensions.
+  pub(crate) fn new_minimal(inner: T) -> WithContext<T> {
+    WithContext {
+      context: (*MINIMAL_CONTEXT).clone(),
+      inner,
+    }
+  }
   pub(crate) fn inner(self) -> T {
     self.inner
   }
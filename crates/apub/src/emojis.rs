@@ -0,0 +1,95 @@
+use crate::protocol::ImageObject;
+use lemmy_api_common::blocking;
+use lemmy_db_schema::source::custom_emoji::CustomEmoji;
+use lemmy_utils::{
+  utils::{render_emojis, scrape_text_for_emojis},
+  LemmyError,
+};
+use lemmy_websocket::LemmyContext;
+use serde::{Deserialize, Serialize};
+use serde_with::skip_serializing_none;
+use url::Url;
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub enum EmojiType {
+  Emoji,
+}
+
+/// A custom emoji which is used in the content, in the format of Mastodon
+#[skip_serializing_none]
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Emoji {
+  pub(crate) r#type: EmojiType,
+  pub(crate) id: Option<Url>,
+  /// The shortcode, including the surrounding colons
+  pub(crate) name: String,
+  pub(crate) icon: ImageObject,
+}
+
+/// Builds the tags for all custom emoji of this instance which are used in the text
+#[tracing::instrument(skip_all)]
+pub async fn collect_emoji_tags(
+  text: &str,
+  context: &LemmyContext,
+) -> Result<Vec<Emoji>, LemmyError> {
+  let shortcodes = scrape_text_for_emojis(text);
+  if shortcodes.is_empty() {
+    return Ok(vec![]);
+  }
+  let emojis = blocking(context.pool(), move |conn| {
+    CustomEmoji::list_for_shortcodes(conn, shortcodes)
+  })
+  .await??;
+  let protocol_and_hostname = context.settings().get_protocol_and_hostname();
+  emojis
+    .into_iter()
+    .map(|e| {
+      Ok(Emoji {
+        r#type: EmojiType::Emoji,
+        id: Some(Url::parse(&format!(
+          "{}/emoji/{}",
+          protocol_and_hostname, e.shortcode
+        ))?),
+        name: format!(":{}:", e.shortcode),
+        icon: ImageObject::new(e.image_url),
+      })
+    })
+    .collect()
+}
+
+/// Custom emoji of other instances aren't known here, so they are converted to inline images
+pub fn render_remote_emojis<'a>(text: &str, emojis: impl Iterator<Item = &'a Emoji>) -> String {
+  let emojis: Vec<(String, Url)> = emojis
+    .map(|e| (e.name.trim_matches(':').to_string(), e.icon.url.clone()))
+    .collect();
+  render_emojis(text, &emojis)
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::emojis::*;
+
+  #[test]
+  fn test_render_remote_emojis() {
+    let tags: Vec<Emoji> = serde_json::from_str(
+      r#"[{
+        "id": "https://mastodon.example/emojis/1021",
+        "type": "Emoji",
+        "name": ":blobcat:",
+        "updated": "2022-01-20T14:20:53Z",
+        "icon": {
+          "type": "Image",
+          "mediaType": "image/png",
+          "url": "https://mastodon.example/system/custom_emojis/blobcat.png"
+        }
+      }]"#,
+    )
+    .unwrap();
+
+    assert_eq!(
+      "Hi ![:blobcat:](https://mastodon.example/system/custom_emojis/blobcat.png \"emoji\") :other:",
+      render_remote_emojis("Hi :blobcat: :other:", tags.iter())
+    );
+  }
+}
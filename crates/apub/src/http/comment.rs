@@ -1,8 +1,8 @@
 use crate::{
-  http::{create_apub_response, create_apub_tombstone_response},
+  http::{create_apub_response, create_apub_tombstone_response, verify_signed_fetch},
   objects::comment::ApubComment,
 };
-use actix_web::{web, web::Path, HttpResponse};
+use actix_web::{web, web::Path, HttpRequest, HttpResponse};
 use diesel::result::Error::NotFound;
 use lemmy_api_common::blocking;
 use lemmy_apub_lib::traits::ApubObject;
@@ -19,9 +19,11 @@ pub(crate) struct CommentQuery {
 /// Return the ActivityPub json representation of a local comment over HTTP.
 #[tracing::instrument(skip_all)]
 pub(crate) async fn get_apub_comment(
+  request: HttpRequest,
   info: Path<CommentQuery>,
   context: web::Data<LemmyContext>,
 ) -> Result<HttpResponse, LemmyError> {
+  verify_signed_fetch(&request, &context).await?;
   let id = CommentId(info.comment_id.parse::<i32>()?);
   let comment: ApubComment = blocking(context.pool(), move |conn| Comment::read(conn, id))
     .await??
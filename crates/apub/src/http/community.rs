@@ -13,8 +13,11 @@ use crate::{
     create_apub_tombstone_response,
     payload_to_string,
     receive_activity,
+    verify_signed_fetch,
     ActivityCommonFields,
+    OutboxQuery,
   },
+  mark_activity_forwarded,
   objects::community::ApubCommunity,
   protocol::{
     activities::community::announce::AnnounceActivity,
@@ -81,12 +84,13 @@ pub(in crate::http) async fn receive_group_inbox(
   context: &LemmyContext,
 ) -> Result<HttpResponse, LemmyError> {
   let actor_id = ObjectId::new(activity_data.actor.clone());
+  let activity_id = activity_data.id.clone();
   let res = receive_activity(request, activity.clone(), activity_data, context).await?;
 
   if let GroupInboxActivities::AnnouncableActivities(announcable) = activity {
     let community = announcable.get_community(context, &mut 0).await?;
     verify_person_in_community(&actor_id, &community, context, &mut 0).await?;
-    if community.local {
+    if community.local && mark_activity_forwarded(&activity_id, context.pool()).await? {
       AnnounceActivity::send(*announcable, &community, context).await?;
     }
   }
@@ -96,9 +100,11 @@ pub(in crate::http) async fn receive_group_inbox(
 
 /// Returns an empty followers collection, only populating the size (for privacy).
 pub(crate) async fn get_apub_community_followers(
+  request: HttpRequest,
   info: web::Path<CommunityQuery>,
   context: web::Data<LemmyContext>,
 ) -> Result<HttpResponse, LemmyError> {
+  verify_signed_fetch(&request, &context).await?;
   let community = blocking(context.pool(), move |conn| {
     Community::read_from_name(conn, &info.community_name)
   })
@@ -107,18 +113,25 @@ pub(crate) async fn get_apub_community_followers(
   Ok(create_apub_response(&followers))
 }
 
-/// Returns the community outbox, which is populated by a maximum of 20 posts (but no other
-/// activites like votes or comments).
+/// Returns the community outbox, which is populated by the newest 20 posts (but no other
+/// activites like votes or comments). Older posts can be read with the `page` parameter.
 pub(crate) async fn get_apub_community_outbox(
+  request: HttpRequest,
   info: web::Path<CommunityQuery>,
+  query: web::Query<OutboxQuery>,
   context: web::Data<LemmyContext>,
 ) -> Result<HttpResponse, LemmyError> {
+  verify_signed_fetch(&request, &context).await?;
   let community = blocking(context.pool(), move |conn| {
     Community::read_from_name(conn, &info.community_name)
   })
   .await??;
   let id = ObjectId::new(generate_outbox_url(&community.actor_id)?);
   let outbox_data = CommunityContext(community.into(), context.get_ref().clone());
+  if let Some(page) = query.page {
+    let page = ApubCommunityOutbox::read_page(page.max(1), &outbox_data).await?;
+    return Ok(create_apub_response(&page));
+  }
   let outbox: ApubCommunityOutbox = id
     .dereference(&outbox_data, context.client(), &mut 0)
     .await?;
@@ -127,9 +140,11 @@ pub(crate) async fn get_apub_community_outbox(
 
 #[tracing::instrument(skip_all)]
 pub(crate) async fn get_apub_community_moderators(
+  request: HttpRequest,
   info: web::Path<CommunityQuery>,
   context: web::Data<LemmyContext>,
 ) -> Result<HttpResponse, LemmyError> {
+  verify_signed_fetch(&request, &context).await?;
   let community: ApubCommunity = blocking(context.pool(), move |conn| {
     Community::read_from_name(conn, &info.community_name)
   })
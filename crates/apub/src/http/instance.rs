@@ -0,0 +1,42 @@
+use crate::{
+  activity_lists::InstanceInboxActivities,
+  check_is_apub_id_valid,
+  http::{create_apub_response, fetch_public_key, receive_verified_activity, ActivityCommonFields},
+  objects::instance::InstanceActor,
+};
+use actix_web::{web, HttpRequest, HttpResponse};
+use lemmy_apub_lib::{
+  signatures::{signature_key_id, verify_signature},
+  verify::verify_urls_match,
+};
+use lemmy_utils::LemmyError;
+use lemmy_websocket::LemmyContext;
+
+/// Return the ActivityPub json representation of the instance actor. Unlike objects, this is
+/// never behind signed fetch, as other instances need it to verify our signatures.
+#[tracing::instrument(skip_all)]
+pub(crate) async fn get_apub_instance_actor(
+  context: web::Data<LemmyContext>,
+) -> Result<HttpResponse, LemmyError> {
+  let instance = InstanceActor::local(&context)?;
+  Ok(create_apub_response(&instance.into_apub(&context).await?))
+}
+
+/// Instance actors aren't stored locally, so their key is fetched with `fetch_public_key`, which
+/// caches it and limits fetches per host.
+#[tracing::instrument(skip_all)]
+pub(in crate::http) async fn receive_instance_inbox(
+  activity: InstanceInboxActivities,
+  activity_data: ActivityCommonFields,
+  request: HttpRequest,
+  context: &LemmyContext,
+) -> Result<HttpResponse, LemmyError> {
+  check_is_apub_id_valid(&activity_data.actor, false, &context.settings())?;
+  let key_id = signature_key_id(&request)?;
+  let mut actor_id = key_id.clone();
+  actor_id.set_fragment(None);
+  verify_urls_match(&actor_id, &activity_data.actor)?;
+  let public_key = fetch_public_key(&actor_id, &key_id, context).await?;
+  verify_signature(&request, &public_key)?;
+  receive_verified_activity(activity, activity_data, context, &mut 0).await
+}
@@ -0,0 +1,95 @@
+use std::{
+  collections::HashMap,
+  time::{Duration, Instant},
+};
+use url::Url;
+
+/// How long fetched keys are used before fetching them again
+const KEY_TTL: Duration = Duration::from_secs(60 * 60);
+/// Keys which are fetched from a single host within one window, after that requests are rejected
+const MAX_FETCHES_PER_HOST: u32 = 10;
+const FETCH_WINDOW: Duration = Duration::from_secs(60);
+/// Bounds the memory, as anyone can make us fetch keys
+const MAX_ENTRIES: usize = 10_000;
+
+/// Public keys of actors which aren't stored locally, like instance actors, so that verifying their
+/// signatures doesn't need a request to the other instance each time. Key fetches are limited per
+/// host, so that requests with made up key ids can't be used to flood other servers.
+#[derive(Default)]
+pub(crate) struct KeyCache {
+  keys: HashMap<Url, (String, Instant)>,
+  fetches: HashMap<String, (Instant, u32)>,
+}
+
+impl KeyCache {
+  pub(crate) fn get(&self, key_id: &Url, now: Instant) -> Option<String> {
+    self
+      .keys
+      .get(key_id)
+      .filter(|(_, fetched)| now.duration_since(*fetched) < KEY_TTL)
+      .map(|(key, _)| key.to_owned())
+  }
+
+  pub(crate) fn insert(&mut self, key_id: Url, key: String, now: Instant) {
+    if self.keys.len() >= MAX_ENTRIES {
+      self
+        .keys
+        .retain(|_, (_, fetched)| now.duration_since(*fetched) < KEY_TTL);
+    }
+    if self.keys.len() >= MAX_ENTRIES {
+      self.keys.clear();
+    }
+    self.keys.insert(key_id, (key, now));
+  }
+
+  /// Counts a key fetch from the host, and returns false if too many keys were fetched from it
+  pub(crate) fn allow_fetch(&mut self, host: &str, now: Instant) -> bool {
+    if self.fetches.len() >= MAX_ENTRIES {
+      self
+        .fetches
+        .retain(|_, (started, _)| now.duration_since(*started) < FETCH_WINDOW);
+    }
+    let (started, count) = self.fetches.entry(host.to_owned()).or_insert((now, 0));
+    if now.duration_since(*started) >= FETCH_WINDOW {
+      *started = now;
+      *count = 0;
+    }
+    *count += 1;
+    *count <= MAX_FETCHES_PER_HOST
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::http::key_cache::*;
+
+  #[test]
+  fn test_key_cache() {
+    let mut cache = KeyCache::default();
+    let now = Instant::now();
+    let key_id = Url::parse("https://example.com/#main-key").unwrap();
+
+    assert_eq!(None, cache.get(&key_id, now));
+    cache.insert(key_id.clone(), "key".to_string(), now);
+    assert_eq!(
+      Some("key".to_string()),
+      cache.get(&key_id, now + Duration::from_secs(60))
+    );
+    assert_eq!(None, cache.get(&key_id, now + KEY_TTL));
+  }
+
+  #[test]
+  fn test_allow_fetch() {
+    let mut cache = KeyCache::default();
+    let now = Instant::now();
+
+    for _ in 0..MAX_FETCHES_PER_HOST {
+      assert!(cache.allow_fetch("example.com", now));
+    }
+    assert!(!cache.allow_fetch("example.com", now));
+    // Other hosts have their own limit
+    assert!(cache.allow_fetch("other.example.com", now));
+    // The limit resets with the next window
+    assert!(cache.allow_fetch("example.com", now + FETCH_WINDOW));
+  }
+}
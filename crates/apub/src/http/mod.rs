@@ -1,9 +1,15 @@
 use crate::{
-  activity_lists::SharedInboxActivities,
+  activities::deletion::{is_local_deletion, Attestation},
+  activity_lists::{PersonInboxActivities, SharedInboxActivities},
   check_is_apub_id_valid,
   context::WithContext,
   fetcher::user_or_community::UserOrCommunity,
-  http::{community::receive_group_inbox, person::receive_person_inbox},
+  http::{
+    community::receive_group_inbox,
+    instance::receive_instance_inbox,
+    key_cache::KeyCache,
+    person::receive_person_inbox,
+  },
   insert_activity,
 };
 use actix_web::{
@@ -12,30 +18,39 @@ use actix_web::{
   HttpRequest,
   HttpResponse,
 };
-use anyhow::Context;
+use anyhow::{anyhow, Context};
 use futures::StreamExt;
 use http::StatusCode;
 use lemmy_api_common::blocking;
 use lemmy_apub_lib::{
   data::Data,
   object_id::ObjectId,
-  signatures::verify_signature,
+  signatures::{build_fetch_request, signature_key_id, verify_signature},
   traits::{ActivityHandler, ActorType},
   APUB_JSON_CONTENT_TYPE,
 };
-use lemmy_db_schema::source::activity::Activity;
+use lemmy_db_schema::source::{
+  activity::Activity,
+  received_deletion::{ReceivedDeletion, ReceivedDeletionForm},
+};
 use lemmy_utils::{location_info, LemmyError};
 use lemmy_websocket::LemmyContext;
+use once_cell::sync::Lazy;
 use serde::{Deserialize, Serialize};
-use std::{fmt::Debug, io::Read};
+use std::{fmt::Debug, io::Read, time::Instant};
+use tokio::sync::Mutex;
 use tracing::info;
 use url::Url;
 
 mod comment;
 mod community;
+mod instance;
+mod key_cache;
+mod modlog;
 mod person;
 mod post;
 pub mod routes;
+mod wiki_page;
 
 #[tracing::instrument(skip_all)]
 pub async fn shared_inbox(
@@ -48,12 +63,19 @@ pub async fn shared_inbox(
   let activity_data: ActivityCommonFields = serde_json::from_str(&unparsed)?;
   let activity = serde_json::from_str::<WithContext<SharedInboxActivities>>(&unparsed)?;
   match activity.inner() {
+    SharedInboxActivities::CreateOrUpdatePrivateMessage(m) => {
+      let activity = PersonInboxActivities::CreateOrUpdatePrivateMessage(*m);
+      receive_person_inbox(activity, activity_data, request, &context).await
+    }
     SharedInboxActivities::GroupInboxActivities(g) => {
       receive_group_inbox(*g, activity_data, request, &context).await
     }
     SharedInboxActivities::PersonInboxActivities(p) => {
       receive_person_inbox(*p, activity_data, request, &context).await
     }
+    SharedInboxActivities::InstanceInboxActivities(i) => {
+      receive_instance_inbox(*i, activity_data, request, &context).await
+    }
   }
 }
 
@@ -93,20 +115,46 @@ where
 {
   check_is_apub_id_valid(&activity_data.actor, false, &context.settings())?;
   let request_counter = &mut 0;
-  let actor = ObjectId::<UserOrCommunity>::new(activity_data.actor)
+  let actor = ObjectId::<UserOrCommunity>::new(activity_data.actor.clone())
     .dereference(context, context.client(), request_counter)
     .await?;
   verify_signature(&request, &actor.public_key())?;
+  receive_verified_activity(activity, activity_data, context, request_counter).await
+}
 
+/// Verifies and receives an activity whose http signature was already checked
+#[tracing::instrument(skip_all, fields(activity_id = %activity_data.id, actor = %activity_data.actor))]
+pub(in crate::http) async fn receive_verified_activity<'a, T>(
+  activity: T,
+  activity_data: ActivityCommonFields,
+  context: &LemmyContext,
+  request_counter: &mut i32,
+) -> Result<HttpResponse, LemmyError>
+where
+  T: ActivityHandler<DataType = LemmyContext>
+    + Clone
+    + Deserialize<'a>
+    + Serialize
+    + std::fmt::Debug
+    + Send
+    + 'static,
+{
   info!("Verifying activity {}", activity_data.id.to_string());
-  activity
+  let object_value = serde_json::to_value(&activity)?;
+  let verified = activity
     .verify(&Data::new(context.clone()), request_counter)
-    .await?;
+    .await;
+  if let Some(form) = received_deletion_form(&object_value, verified.is_ok()) {
+    blocking(context.pool(), move |conn| {
+      ReceivedDeletion::create(conn, &form)
+    })
+    .await??;
+  }
+  verified?;
   assert_activity_not_local(&activity_data.id, &context.settings().hostname)?;
 
   // Log the activity, so we avoid receiving and parsing it twice. Note that this could still happen
   // if we receive the same activity twice in very quick succession.
-  let object_value = serde_json::to_value(&activity)?;
   insert_activity(&activity_data.id, object_value, false, true, context.pool()).await?;
 
   info!("Receiving activity {}", activity_data.id.to_string());
@@ -116,6 +164,112 @@ where
   Ok(HttpResponse::Ok().finish())
 }
 
+/// In signed fetch mode, objects are only served to requests which are signed by an actor from an
+/// allowed instance. Actors themselves are always public, as their keys are needed to verify
+/// signatures.
+pub(in crate::http) async fn verify_signed_fetch(
+  request: &HttpRequest,
+  context: &LemmyContext,
+) -> Result<(), LemmyError> {
+  if !context.settings().federation.signed_fetch {
+    return Ok(());
+  }
+  let key_id = signature_key_id(request).map_err(|e| e.with_message("signed_fetch_required"))?;
+  let mut actor_id = key_id.clone();
+  actor_id.set_fragment(None);
+  check_is_apub_id_valid(&actor_id, false, &context.settings())?;
+
+  let public_key = match ObjectId::<UserOrCommunity>::new(actor_id.clone())
+    .dereference_local(context)
+    .await
+  {
+    Ok(actor) => actor.public_key(),
+    Err(_) => fetch_public_key(&actor_id, &key_id, context).await?,
+  };
+  verify_signature(request, &public_key).map_err(|e| e.with_message("invalid_signature"))
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ActorWithKey {
+  public_key: ActorKey,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ActorKey {
+  id: Url,
+  owner: Url,
+  public_key_pem: String,
+}
+
+static KEY_CACHE: Lazy<Mutex<KeyCache>> = Lazy::new(Default::default);
+
+/// Fetches the key of an actor which isn't stored locally. The actor itself isn't parsed or stored,
+/// as it can be of a type which Lemmy doesn't support, like the instance actors of other software.
+/// Fetched keys are cached, and fetches are limited per host.
+pub(in crate::http) async fn fetch_public_key(
+  actor_id: &Url,
+  key_id: &Url,
+  context: &LemmyContext,
+) -> Result<String, LemmyError> {
+  let now = Instant::now();
+  {
+    let mut cache = KEY_CACHE.lock().await;
+    if let Some(key) = cache.get(key_id, now) {
+      return Ok(key);
+    }
+    if !cache.allow_fetch(actor_id.host_str().unwrap_or_default(), now) {
+      return Err(LemmyError::from_message("too_many_key_fetches"));
+    }
+  }
+
+  let request = build_fetch_request(context.client(), actor_id)?;
+  let actor: ActorWithKey = context.client().execute(request).await?.json().await?;
+  if &actor.public_key.id != key_id || &actor.public_key.owner != actor_id {
+    return Err(anyhow!("Key {} doesn't belong to actor {}", key_id, actor_id).into());
+  }
+  let key = actor.public_key.public_key_pem;
+  KEY_CACHE
+    .lock()
+    .await
+    .insert(key_id.to_owned(), key.to_owned(), now);
+  Ok(key)
+}
+
+/// If the received activity is a deletion or removal (or an undo or announce of one), returns it
+/// in the form which is logged for admins.
+pub(crate) fn received_deletion_form(
+  activity: &serde_json::Value,
+  accepted: bool,
+) -> Option<ReceivedDeletionForm> {
+  let mut activity = activity;
+  if activity["type"] == "Announce" {
+    activity = &activity["object"];
+  }
+  let (kind, deletion) = if activity["type"] == "Undo" {
+    (
+      format!("Undo{}", activity["object"]["type"].as_str()?),
+      &activity["object"],
+    )
+  } else {
+    (activity["type"].as_str()?.to_string(), activity)
+  };
+  if !matches!(deletion["type"].as_str(), Some("Delete") | Some("Remove")) {
+    return None;
+  }
+  // The deleted object can be given either as id or as tombstone
+  let object = &deletion["object"];
+  let object_id = object.as_str().or_else(|| object["id"].as_str())?;
+  Some(ReceivedDeletionForm {
+    actor_id: Url::parse(activity["actor"].as_str()?).ok()?.into(),
+    activity_id: Url::parse(activity["id"].as_str()?).ok()?.into(),
+    kind,
+    object_id: Url::parse(object_id).ok()?.into(),
+    accepted,
+  })
+}
+
 /// Convert the data to json and turn it into an HTTP Response with the correct ActivityPub
 /// headers.
 fn create_apub_response<T>(data: &T) -> HttpResponse
@@ -143,6 +297,12 @@ where
     .json(WithContext::new(data))
 }
 
+/// Page parameter for person and community outboxes
+#[derive(Deserialize)]
+pub(crate) struct OutboxQuery {
+  page: Option<i64>,
+}
+
 #[derive(Deserialize)]
 pub struct ActivityQuery {
   type_: String,
@@ -152,9 +312,11 @@ pub struct ActivityQuery {
 /// Return the ActivityPub json representation of a local activity over HTTP.
 #[tracing::instrument(skip_all)]
 pub(crate) async fn get_activity(
+  request: HttpRequest,
   info: web::Path<ActivityQuery>,
   context: web::Data<LemmyContext>,
 ) -> Result<HttpResponse, LemmyError> {
+  verify_signed_fetch(&request, &context).await?;
   let settings = context.settings();
   let activity_id = Url::parse(&format!(
     "{}/activities/{}/{}",
@@ -176,6 +338,23 @@ pub(crate) async fn get_activity(
   }
 }
 
+#[derive(Deserialize)]
+pub struct AttestationQuery {
+  activity: String,
+}
+
+/// Confirms to partner instances that a deletion was really sent by this instance, see
+/// `federation.deletion_attestation`.
+#[tracing::instrument(skip_all)]
+pub(crate) async fn get_deletion_attestation(
+  info: web::Query<AttestationQuery>,
+  context: web::Data<LemmyContext>,
+) -> Result<HttpResponse, LemmyError> {
+  let activity_id = Url::parse(&info.activity)?;
+  let confirmed = is_local_deletion(activity_id, &context).await?;
+  Ok(HttpResponse::Ok().json(Attestation { confirmed }))
+}
+
 fn assert_activity_not_local(id: &Url, hostname: &str) -> Result<(), LemmyError> {
   let activity_domain = id.domain().context(location_info!())?;
 
@@ -188,3 +367,62 @@ fn assert_activity_not_local(id: &Url, hostname: &str) -> Result<(), LemmyError>
   }
   Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+  use crate::{
+    http::*,
+    objects::tests::{file_to_json_object, init_context},
+  };
+  use lemmy_apub_lib::activity_queue::create_activity_queue;
+  use serial_test::serial;
+
+  #[test]
+  fn test_received_deletion_form() {
+    let undo_delete = file_to_json_object::<serde_json::Value>(
+      "assets/lemmy/activities/deletion/undo_delete_page.json",
+    );
+    let form = received_deletion_form(&undo_delete, false).unwrap();
+    assert_eq!("UndoDelete", form.kind);
+    assert_eq!("http://ds9.lemmy.ml/post/1", form.object_id.to_string());
+    assert!(!form.accepted);
+
+    let remove =
+      file_to_json_object::<serde_json::Value>("assets/lemmy/activities/deletion/remove_page.json");
+    let form = received_deletion_form(&remove, true).unwrap();
+    assert_eq!("Remove", form.kind);
+
+    let announce = file_to_json_object::<serde_json::Value>(
+      "assets/lemmy/activities/community/announce_create_page.json",
+    );
+    assert!(received_deletion_form(&announce, true).is_none());
+  }
+
+  #[actix_rt::test]
+  #[serial]
+  async fn test_fetch_public_key_cached() {
+    let client = reqwest::Client::new().into();
+    let manager = create_activity_queue(client);
+    let context = init_context(manager.queue_handle().clone());
+    // Nothing is ever fetched from these hosts, the requests would fail
+    let actor_id = Url::parse("https://cached.invalid/").unwrap();
+    let key_id = Url::parse("https://cached.invalid/#main-key").unwrap();
+    KEY_CACHE
+      .lock()
+      .await
+      .insert(key_id.clone(), "cached key".to_string(), Instant::now());
+    let cached = fetch_public_key(&actor_id, &key_id, &context).await;
+
+    let actor_id = Url::parse("https://limited.invalid/").unwrap();
+    let key_id = Url::parse("https://limited.invalid/#main-key").unwrap();
+    while KEY_CACHE
+      .lock()
+      .await
+      .allow_fetch("limited.invalid", Instant::now())
+    {}
+    let limited = fetch_public_key(&actor_id, &key_id, &context).await;
+
+    assert_eq!("cached key", cached.unwrap());
+    assert_eq!(Some("too_many_key_fetches"), limited.unwrap_err().message);
+  }
+}
@@ -0,0 +1,225 @@
+use crate::{
+  http::{create_apub_response, verify_signed_fetch},
+  protocol::{
+    activities::{
+      community::{
+        block_user::BlockUserFromCommunity,
+        undo_block_user::UndoBlockUserFromCommunity,
+      },
+      deletion::{remove::Remove, undo_remove::UndoRemove},
+    },
+    collections::instance_modlog::{InstanceModlog, ModlogActivity},
+    objects::tombstone::Tombstone,
+  },
+};
+use activitystreams_kinds::{
+  activity::{BlockType, RemoveType, UndoType},
+  collection::OrderedCollectionPageType,
+  public,
+};
+use actix_web::{web, HttpRequest, HttpResponse};
+use chrono::NaiveDateTime;
+use lemmy_api_common::blocking;
+use lemmy_apub_lib::object_id::ObjectId;
+use lemmy_db_schema::source::person::PersonSafe;
+use lemmy_db_views_moderator::public_modlog_view::PublicModlogView;
+use lemmy_utils::{utils::convert_datetime, LemmyError};
+use lemmy_websocket::LemmyContext;
+use serde::Deserialize;
+use url::Url;
+
+/// Number of entries per page
+const MODLOG_PAGE_SIZE: i64 = 20;
+
+#[derive(Deserialize)]
+pub(crate) struct ModlogQuery {
+  page: Option<i64>,
+}
+
+/// Removals of posts, comments and communities and bans from communities which are marked as
+/// public, only including those done in local communities. Only the moderator, the affected object
+/// and the reason are exposed.
+#[tracing::instrument(skip_all)]
+pub(crate) async fn get_apub_modlog(
+  request: HttpRequest,
+  info: web::Query<ModlogQuery>,
+  context: web::Data<LemmyContext>,
+) -> Result<HttpResponse, LemmyError> {
+  verify_signed_fetch(&request, &context).await?;
+  if !context.settings().moderation.public_modlog {
+    return Ok(HttpResponse::NotFound().finish());
+  }
+  let page = info.page.unwrap_or(1).max(1);
+  let limit = Some(MODLOG_PAGE_SIZE);
+
+  let views = blocking(context.pool(), move |conn| {
+    PublicModlogView::list(conn, Some(page), limit)
+  })
+  .await??;
+  let has_next = views.len() as i64 == MODLOG_PAGE_SIZE;
+
+  let base = format!("{}/modlog", context.settings().get_protocol_and_hostname());
+  let mut activities = vec![];
+  for view in views {
+    let activity = match view {
+      PublicModlogView::RemovePost(p) => {
+        let r = p.mod_remove_post;
+        ModlogEntry {
+          id: Url::parse(&format!("{}/remove_post/{}", base, r.id))?,
+          moderator: p.moderator,
+          object: p.post.ap_id.into(),
+          community: p.community.actor_id.into(),
+          reason: r.reason,
+          reason_translations: r.reason_translations,
+          removed: r.removed.unwrap_or(true),
+        }
+        .into_activity()
+      }
+      PublicModlogView::RemoveComment(c) => {
+        let r = c.mod_remove_comment;
+        ModlogEntry {
+          id: Url::parse(&format!("{}/remove_comment/{}", base, r.id))?,
+          moderator: c.moderator,
+          object: c.comment.ap_id.into(),
+          community: c.community.actor_id.into(),
+          reason: r.reason,
+          reason_translations: r.reason_translations,
+          removed: r.removed.unwrap_or(true),
+        }
+        .into_activity()
+      }
+      PublicModlogView::RemoveCommunity(c) => {
+        let r = c.mod_remove_community;
+        let community: Url = c.community.actor_id.into();
+        ModlogEntry {
+          id: Url::parse(&format!("{}/remove_community/{}", base, r.id))?,
+          moderator: c.moderator,
+          object: community.clone(),
+          community,
+          reason: r.reason,
+          reason_translations: r.reason_translations,
+          removed: r.removed.unwrap_or(true),
+        }
+        .into_activity()
+      }
+      PublicModlogView::BanFromCommunity(b) => {
+        let r = b.mod_ban_from_community;
+        ban_into_activity(
+          Url::parse(&format!("{}/ban_from_community/{}", base, r.id))?,
+          b.moderator,
+          b.banned_person.actor_id.into(),
+          b.community.actor_id.into(),
+          r.reason,
+          r.banned.unwrap_or(true),
+          r.expires,
+        )
+      }
+    };
+    activities.push(activity);
+  }
+
+  let modlog = InstanceModlog {
+    r#type: OrderedCollectionPageType::OrderedCollectionPage,
+    id: Url::parse(&format!("{}?page={}", base, page))?,
+    ordered_items: activities,
+    next: if has_next {
+      Some(Url::parse(&format!("{}?page={}", base, page + 1))?)
+    } else {
+      None
+    },
+  };
+  Ok(create_apub_response(&modlog))
+}
+
+struct ModlogEntry {
+  id: Url,
+  moderator: PersonSafe,
+  object: Url,
+  community: Url,
+  reason: Option<String>,
+  reason_translations: Option<serde_json::Value>,
+  removed: bool,
+}
+
+impl ModlogEntry {
+  fn into_activity(self) -> ModlogActivity {
+    let actor = ObjectId::new(self.moderator.actor_id);
+    let remove_id = if self.removed {
+      self.id.clone()
+    } else {
+      let mut remove_id = self.id.clone();
+      remove_id.set_fragment(Some("remove"));
+      remove_id
+    };
+    let remove = Remove {
+      actor: actor.clone(),
+      to: vec![public()],
+      object: Tombstone::new(self.object),
+      cc: vec![self.community.clone()],
+      kind: RemoveType::Remove,
+      summary: self.reason,
+      summary_map: self
+        .reason_translations
+        .and_then(|t| serde_json::from_value(t).ok()),
+      id: remove_id,
+      unparsed: Default::default(),
+    };
+    if self.removed {
+      ModlogActivity::Remove(remove)
+    } else {
+      ModlogActivity::UndoRemove(UndoRemove {
+        actor,
+        to: vec![public()],
+        object: remove,
+        cc: vec![self.community],
+        kind: UndoType::Undo,
+        id: self.id,
+        unparsed: Default::default(),
+      })
+    }
+  }
+}
+
+fn ban_into_activity(
+  id: Url,
+  moderator: PersonSafe,
+  banned_person: Url,
+  community: Url,
+  reason: Option<String>,
+  banned: bool,
+  expires: Option<NaiveDateTime>,
+) -> ModlogActivity {
+  let actor = ObjectId::new(moderator.actor_id);
+  let block_id = if banned {
+    id.clone()
+  } else {
+    let mut block_id = id.clone();
+    block_id.set_fragment(Some("block"));
+    block_id
+  };
+  let block = BlockUserFromCommunity {
+    actor: actor.clone(),
+    to: vec![public()],
+    object: ObjectId::new(banned_person),
+    cc: vec![community.clone()],
+    target: ObjectId::new(community.clone()),
+    kind: BlockType::Block,
+    summary: reason,
+    expires: expires.map(convert_datetime),
+    id: block_id,
+    unparsed: Default::default(),
+  };
+  if banned {
+    ModlogActivity::BlockUserFromCommunity(block)
+  } else {
+    ModlogActivity::UndoBlockUserFromCommunity(UndoBlockUserFromCommunity {
+      actor,
+      to: vec![public()],
+      object: block,
+      cc: vec![community],
+      kind: UndoType::Undo,
+      id,
+      unparsed: Default::default(),
+    })
+  }
+}
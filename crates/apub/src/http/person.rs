@@ -6,7 +6,9 @@ use crate::{
     create_apub_tombstone_response,
     payload_to_string,
     receive_activity,
+    verify_signed_fetch,
     ActivityCommonFields,
+    OutboxQuery,
   },
   objects::person::ApubPerson,
   protocol::collections::person_outbox::PersonOutbox,
@@ -71,15 +73,25 @@ pub(in crate::http) async fn receive_person_inbox(
   receive_activity(request, activity, activity_data, context).await
 }
 
+/// Returns the root of the person outbox, or a single page of it if the `page` parameter is given.
+/// Pages contain the Create activities for the posts of the person.
 #[tracing::instrument(skip_all)]
 pub(crate) async fn get_apub_person_outbox(
+  request: HttpRequest,
   info: web::Path<PersonQuery>,
+  query: web::Query<OutboxQuery>,
   context: web::Data<LemmyContext>,
 ) -> Result<HttpResponse, LemmyError> {
+  verify_signed_fetch(&request, &context).await?;
   let person = blocking(context.pool(), move |conn| {
     Person::find_by_name(conn, &info.user_name)
   })
   .await??;
-  let outbox = PersonOutbox::new(person).await?;
-  Ok(create_apub_response(&outbox))
+  if let Some(page) = query.page {
+    let page = PersonOutbox::read_page(person, page.max(1), &context).await?;
+    Ok(create_apub_response(&page))
+  } else {
+    let outbox = PersonOutbox::new(person, &context).await?;
+    Ok(create_apub_response(&outbox))
+  }
 }
@@ -1,8 +1,8 @@
 use crate::{
-  http::{create_apub_response, create_apub_tombstone_response},
+  http::{create_apub_response, create_apub_tombstone_response, verify_signed_fetch},
   objects::post::ApubPost,
 };
-use actix_web::{web, HttpResponse};
+use actix_web::{web, HttpRequest, HttpResponse};
 use diesel::result::Error::NotFound;
 use lemmy_api_common::blocking;
 use lemmy_apub_lib::traits::ApubObject;
@@ -19,9 +19,11 @@ pub(crate) struct PostQuery {
 /// Return the ActivityPub json representation of a local post over HTTP.
 #[tracing::instrument(skip_all)]
 pub(crate) async fn get_apub_post(
+  request: HttpRequest,
   info: web::Path<PostQuery>,
   context: web::Data<LemmyContext>,
 ) -> Result<HttpResponse, LemmyError> {
+  verify_signed_fetch(&request, &context).await?;
   let id = PostId(info.post_id.parse::<i32>()?);
   let post: ApubPost = blocking(context.pool(), move |conn| Post::read(conn, id))
     .await??
@@ -8,9 +8,13 @@ use crate::http::{
     get_apub_community_outbox,
   },
   get_activity,
+  get_deletion_attestation,
+  instance::get_apub_instance_actor,
+  modlog::get_apub_modlog,
   person::{get_apub_person_http, get_apub_person_outbox, person_inbox},
   post::get_apub_post,
   shared_inbox,
+  wiki_page::get_apub_wiki_page,
 };
 use actix_web::{dev::RequestHead, guard::Guard, http::Method, *};
 use http_signature_normalization_actix::digest::middleware::VerifyDigest;
@@ -20,6 +24,12 @@ use sha2::{Digest, Sha256};
 pub fn config(cfg: &mut web::ServiceConfig, settings: &Settings) {
   if settings.federation.enabled {
     println!("federation enabled, host is {}", settings.hostname);
+    let attestation_endpoint = settings
+      .federation
+      .deletion_attestation
+      .clone()
+      .unwrap_or_default()
+      .endpoint;
 
     cfg
       .route(
@@ -45,7 +55,22 @@ pub fn config(cfg: &mut web::ServiceConfig, settings: &Settings) {
       )
       .route("/post/{post_id}", web::get().to(get_apub_post))
       .route("/comment/{comment_id}", web::get().to(get_apub_comment))
-      .route("/activities/{type_}/{id}", web::get().to(get_activity));
+      .route(
+        "/wiki_page/{wiki_page_id}",
+        web::get().to(get_apub_wiki_page),
+      )
+      .route("/activities/{type_}/{id}", web::get().to(get_activity))
+      .route("/modlog", web::get().to(get_apub_modlog))
+      .route(
+        &attestation_endpoint,
+        web::get().to(get_deletion_attestation),
+      )
+      .route(
+        "/",
+        web::get()
+          .guard(ApubAcceptGuard)
+          .to(get_apub_instance_actor),
+      );
 
     cfg.service(
       web::scope("")
@@ -77,3 +102,18 @@ impl Guard for InboxRequestGuard {
     false
   }
 }
+
+/// The root path is also used by the frontend, so only requests for activitypub json are routed to
+/// the instance actor.
+struct ApubAcceptGuard;
+
+impl Guard for ApubAcceptGuard {
+  fn check(&self, request: &RequestHead) -> bool {
+    request
+      .headers
+      .get("Accept")
+      .and_then(|val| val.to_str().ok())
+      .map(|val| val.contains("application/activity+json") || val.contains("application/ld+json"))
+      .unwrap_or(false)
+  }
+}
@@ -0,0 +1,42 @@
+use crate::{
+  http::{create_apub_response, create_apub_tombstone_response, verify_signed_fetch},
+  objects::wiki_page::ApubWikiPage,
+};
+use actix_web::{web, HttpRequest, HttpResponse};
+use diesel::result::Error::NotFound;
+use lemmy_api_common::blocking;
+use lemmy_apub_lib::traits::ApubObject;
+use lemmy_db_schema::{source::community_wiki_page::CommunityWikiPage, traits::Crud};
+use lemmy_utils::LemmyError;
+use lemmy_websocket::LemmyContext;
+use serde::Deserialize;
+
+#[derive(Deserialize)]
+pub(crate) struct WikiPageQuery {
+  wiki_page_id: String,
+}
+
+/// Return the ActivityPub json representation of a local wiki page over HTTP.
+#[tracing::instrument(skip_all)]
+pub(crate) async fn get_apub_wiki_page(
+  request: HttpRequest,
+  info: web::Path<WikiPageQuery>,
+  context: web::Data<LemmyContext>,
+) -> Result<HttpResponse, LemmyError> {
+  verify_signed_fetch(&request, &context).await?;
+  let id = info.wiki_page_id.parse::<i32>()?;
+  let wiki_page: ApubWikiPage = blocking(context.pool(), move |conn| {
+    CommunityWikiPage::read(conn, id)
+  })
+  .await??
+  .into();
+  if !wiki_page.local {
+    return Err(NotFound.into());
+  }
+
+  if !wiki_page.deleted {
+    Ok(create_apub_response(&wiki_page.into_apub(&context).await?))
+  } else {
+    Ok(create_apub_tombstone_response(&wiki_page.to_tombstone()?))
+  }
+}
@@ -1,9 +1,13 @@
 use crate::fetcher::post_or_comment::PostOrComment;
 use anyhow::Context;
 use lemmy_api_common::blocking;
-use lemmy_db_schema::{newtypes::DbUrl, source::activity::Activity, DbPool};
+use lemmy_db_schema::{
+  newtypes::DbUrl,
+  source::{activity::Activity, federation_instance::FederationInstance},
+  DbPool,
+};
 use lemmy_utils::{location_info, settings::structs::Settings, LemmyError};
-use serde::{Deserialize, Deserializer};
+use serde::{de::DeserializeOwned, Deserialize, Deserializer};
 use std::net::IpAddr;
 use url::{ParseError, Url};
 
@@ -11,6 +15,7 @@ pub mod activities;
 pub(crate) mod activity_lists;
 pub(crate) mod collections;
 mod context;
+pub(crate) mod emojis;
 pub mod fetcher;
 pub mod http;
 pub(crate) mod mentions;
@@ -26,6 +31,8 @@ pub mod protocol;
 /// - URL being in the allowlist (if it is active)
 /// - URL not being in the blocklist (if it is active)
 ///
+/// The allowlist and blocklist are combined from the config file and the ones set through the api.
+///
 /// `use_strict_allowlist` should be true only when parsing a remote community, or when parsing a
 /// post/comment in a local community.
 #[tracing::instrument(skip(settings))]
@@ -68,14 +75,17 @@ pub(crate) fn check_is_apub_id_valid(
   // TODO: might be good to put the part above in one method, and below in another
   //       (which only gets called in apub::objects)
   //        -> no that doesnt make sense, we still need the code below for blocklist and strict allowlist
-  if let Some(blocked) = settings.to_owned().federation.blocked_instances {
+  let instance_lists = FederationInstance::cached_lists()?;
+  if let Some(blocked) = instance_lists.combined_blocklist(&settings.federation.blocked_instances) {
     if blocked.contains(&domain) {
       let error = LemmyError::from(anyhow::anyhow!("{} is in federation blocklist", domain));
       return Err(error.with_message("federation_blocked"));
     }
   }
 
-  if let Some(mut allowed) = settings.to_owned().federation.allowed_instances {
+  if let Some(mut allowed) =
+    instance_lists.combined_allowlist(&settings.federation.allowed_instances)
+  {
     // Only check allowlist if this is a community, or strict allowlist is enabled.
     let strict_allowlist = settings.to_owned().federation.strict_allowlist;
     if use_strict_allowlist || strict_allowlist {
@@ -112,12 +122,28 @@ where
   })
 }
 
+/// Skips entries which can't be parsed, eg of types which are not supported
+pub(crate) fn deserialize_skip_error<'de, T, D>(deserializer: D) -> Result<Vec<T>, D::Error>
+where
+  T: DeserializeOwned,
+  D: Deserializer<'de>,
+{
+  let values: Vec<serde_json::Value> = Deserialize::deserialize(deserializer)?;
+  Ok(
+    values
+      .into_iter()
+      .filter_map(|v| serde_json::from_value(v).ok())
+      .collect(),
+  )
+}
+
 pub enum EndpointType {
   Community,
   Person,
   Post,
   Comment,
   PrivateMessage,
+  WikiPage,
 }
 
 /// Generates an apub endpoint for a given domain, IE xyz.tld
@@ -132,6 +158,7 @@ pub fn generate_local_apub_endpoint(
     EndpointType::Post => "post",
     EndpointType::Comment => "comment",
     EndpointType::PrivateMessage => "private_message",
+    EndpointType::WikiPage => "wiki_page",
   };
 
   Ok(Url::parse(&format!("{}/{}/{}", domain, point, name))?.into())
@@ -178,10 +205,29 @@ async fn insert_activity(
   sensitive: bool,
   pool: &DbPool,
 ) -> Result<(), LemmyError> {
-  let ap_id = ap_id.to_owned().into();
+  let ap_id: DbUrl = ap_id.to_owned().into();
   blocking(pool, move |conn| {
-    Activity::insert(conn, ap_id, activity, local, sensitive)
+    match Activity::insert(conn, ap_id.clone(), activity, local, sensitive) {
+      Ok(_) => Ok(()),
+      // A held activity which is sent out now is already stored
+      Err(e) => match Activity::read_from_apub_id(conn, &ap_id) {
+        Ok(stored) if stored.held => Ok(()),
+        _ => Err(e),
+      },
+    }
   })
   .await??;
   Ok(())
 }
+
+/// Marks a received activity as forwarded to community followers. Returns false if it was already
+/// forwarded before, which means that it must not be sent out again (otherwise a follower which
+/// sends it back to us could cause an announce loop).
+#[tracing::instrument(skip(pool))]
+pub(crate) async fn mark_activity_forwarded(
+  ap_id: &Url,
+  pool: &DbPool,
+) -> Result<bool, LemmyError> {
+  let ap_id = ap_id.to_owned().into();
+  Ok(blocking(pool, move |conn| Activity::mark_as_forwarded(conn, &ap_id)).await??)
+}
@@ -21,7 +21,7 @@ use url::Url;
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct Mention {
   pub href: Url,
-  name: Option<String>,
+  pub name: Option<String>,
   #[serde(rename = "type")]
   pub kind: MentionType,
 }
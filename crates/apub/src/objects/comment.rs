@@ -1,11 +1,13 @@
 use crate::{
   activities::{verify_is_public, verify_person_in_community},
   check_is_apub_id_valid,
+  emojis::{collect_emoji_tags, render_remote_emojis},
   mentions::collect_non_local_mentions,
+  objects::{language_code, language_from_maps, language_map},
   protocol::{
     objects::{
-      note::{Note, SourceCompat},
-      tombstone::Tombstone,
+      note::{Note, NoteTag, SourceCompat},
+      tombstone::{FormerType, Tombstone},
     },
     Source,
   },
@@ -24,6 +26,7 @@ use lemmy_apub_lib::{
 use lemmy_db_schema::{
   source::{
     comment::{Comment, CommentForm},
+    comment_revision::CommentRevision,
     community::Community,
     person::Person,
     post::Post,
@@ -31,7 +34,7 @@ use lemmy_db_schema::{
   traits::Crud,
 };
 use lemmy_utils::{
-  utils::{convert_datetime, markdown_to_html, remove_slurs},
+  utils::{check_blocked_words, convert_datetime, markdown_to_html, remove_slurs},
   LemmyError,
 };
 use lemmy_websocket::LemmyContext;
@@ -111,6 +114,17 @@ impl ApubObject for ApubComment {
     };
     let maa =
       collect_non_local_mentions(&self, ObjectId::new(community.actor_id), context, &mut 0).await?;
+    let mut tag: Vec<NoteTag> = maa.tags.into_iter().map(NoteTag::Mention).collect();
+    let emojis = collect_emoji_tags(&self.content, context).await?;
+    tag.extend(emojis.into_iter().map(NoteTag::Emoji));
+    let comment_id = self.id;
+    let revision = blocking(context.pool(), move |conn| {
+      CommentRevision::count_for_comment(conn, comment_id)
+    })
+    .await??;
+
+    let language = language_code(self.language_id, context).await?;
+    let content = markdown_to_html(&self.content);
 
     let note = Note {
       r#type: NoteType::Note,
@@ -118,16 +132,18 @@ impl ApubObject for ApubComment {
       attributed_to: ObjectId::new(creator.actor_id),
       to: vec![public()],
       cc: maa.ccs,
-      content: markdown_to_html(&self.content),
+      content_map: language_map(&language, &content),
+      content,
       media_type: Some(MediaTypeHtml::Html),
       source: SourceCompat::Lemmy(Source {
         content: self.content.clone(),
         media_type: MediaTypeMarkdown::Markdown,
+        revision: Some(revision).filter(|r| *r > 0),
       }),
       in_reply_to,
       published: Some(convert_datetime(self.published)),
       updated: self.updated.map(convert_datetime),
-      tag: maa.tags,
+      tag,
       unparsed: Default::default(),
     };
 
@@ -135,7 +151,10 @@ impl ApubObject for ApubComment {
   }
 
   fn to_tombstone(&self) -> Result<Tombstone, LemmyError> {
-    Ok(Tombstone::new(self.ap_id.clone().into()))
+    Ok(Tombstone::with_former_type(
+      self.ap_id.clone().into(),
+      FormerType::Note,
+    ))
   }
 
   #[tracing::instrument(skip_all)]
@@ -188,7 +207,11 @@ impl ApubObject for ApubComment {
     } else {
       parse_html(&note.content)
     };
+    check_blocked_words(&content)?;
+    let content = render_remote_emojis(&content, note.emojis());
     let content_slurs_removed = remove_slurs(&content, &context.settings().slur_regex());
+    let object_id: Url = note.id.clone().into();
+    let language_id = language_from_maps(&[&note.content_map], context).await?;
 
     let form = CommentForm {
       creator_id: creator.id,
@@ -202,8 +225,16 @@ impl ApubObject for ApubComment {
       deleted: None,
       ap_id: Some(note.id.into()),
       local: Some(false),
+      language_id: Some(language_id),
     };
-    let comment = blocking(context.pool(), move |conn| Comment::upsert(conn, &form)).await??;
+    let comment = blocking(context.pool(), move |conn| -> Result<Comment, LemmyError> {
+      // Keep the previous version for the edit history
+      if let Some(existing) = Comment::read_from_apub_id(conn, object_id)? {
+        CommentRevision::save_if_changed(conn, &existing, &form.content)?;
+      }
+      Ok(Comment::upsert(conn, &form)?)
+    })
+    .await??;
     Ok(comment.into())
   }
 }
@@ -274,6 +305,50 @@ pub(crate) mod tests {
     cleanup(data, &context);
   }
 
+  #[actix_rt::test]
+  #[serial]
+  async fn test_parse_lemmy_comment_edit() {
+    let client = reqwest::Client::new().into();
+    let manager = create_activity_queue(client);
+    let context = init_context(manager.queue_handle().clone());
+    let url = Url::parse("https://enterprise.lemmy.ml/comment/38741").unwrap();
+    let data = prepare_comment_test(&url, &context).await;
+
+    let json: Note = file_to_json_object("assets/lemmy/objects/note.json");
+    let comment = ApubComment::from_apub(json.clone(), &context, &mut 0)
+      .await
+      .unwrap();
+    let mut edited_json = json.clone();
+    edited_json.content = "<p>edited</p>".to_string();
+    if let SourceCompat::Lemmy(source) = &mut edited_json.source {
+      source.content = "edited".to_string();
+    }
+    // Receiving the same version again doesn't add a revision
+    ApubComment::from_apub(edited_json.clone(), &context, &mut 0)
+      .await
+      .unwrap();
+    let edited = ApubComment::from_apub(edited_json, &context, &mut 0)
+      .await
+      .unwrap();
+
+    let conn = context.pool().get().unwrap();
+    let revisions = CommentRevision::list_for_comment(&conn, comment.id).unwrap();
+    assert_eq!(1, revisions.len());
+    assert_eq!(comment.content, revisions[0].content);
+    assert_eq!("edited", edited.content);
+
+    let comment_id = edited.id;
+    let to_apub = edited.into_apub(&context).await.unwrap();
+    if let SourceCompat::Lemmy(source) = to_apub.source {
+      assert_eq!(Some(1), source.revision);
+    } else {
+      panic!("missing source");
+    }
+
+    Comment::delete(&conn, comment_id).unwrap();
+    cleanup(data, &context);
+  }
+
   #[actix_rt::test]
   #[serial]
   async fn test_parse_pleroma_comment() {
@@ -4,7 +4,11 @@ use crate::{
   generate_moderators_url,
   generate_outbox_url,
   protocol::{
-    objects::{group::Group, tombstone::Tombstone, Endpoints},
+    objects::{
+      group::Group,
+      tombstone::{FormerType, Tombstone},
+      Endpoints,
+    },
     ImageObject,
     Source,
   },
@@ -83,6 +87,7 @@ impl ApubObject for ApubCommunity {
     let source = self.description.clone().map(|bio| Source {
       content: bio,
       media_type: MediaTypeMarkdown::Markdown,
+      revision: None,
     });
     let icon = self.icon.clone().map(ImageObject::new);
     let image = self.banner.clone().map(ImageObject::new);
@@ -115,7 +120,10 @@ impl ApubObject for ApubCommunity {
   }
 
   fn to_tombstone(&self) -> Result<Tombstone, LemmyError> {
-    Ok(Tombstone::new(self.actor_id()))
+    Ok(Tombstone::with_former_type(
+      self.actor_id(),
+      FormerType::Group,
+    ))
   }
 
   #[tracing::instrument(skip_all)]
@@ -0,0 +1,107 @@
+use crate::protocol::{
+  objects::{instance::Instance, Endpoints},
+  ImageObject,
+  Source,
+};
+use activitystreams_kinds::actor::ApplicationType;
+use anyhow::anyhow;
+use lemmy_api_common::blocking;
+use lemmy_apub_lib::{traits::ActorType, values::MediaTypeMarkdown};
+use lemmy_db_schema::source::site::Site;
+use lemmy_utils::{
+  settings::structs::Settings,
+  utils::{convert_datetime, markdown_to_html},
+  LemmyError,
+};
+use lemmy_websocket::LemmyContext;
+use url::Url;
+
+/// The actor of the local instance. It is used to sign object fetches, so that they are accepted by
+/// instances which require signatures, and to federate the site metadata and instance blocks.
+#[derive(Clone, Debug)]
+pub struct InstanceActor {
+  actor_id: Url,
+  private_key: String,
+  public_key: String,
+}
+
+impl InstanceActor {
+  pub fn local(context: &LemmyContext) -> Result<InstanceActor, LemmyError> {
+    let secret = context.secret();
+    match (&secret.instance_private_key, &secret.instance_public_key) {
+      (Some(private_key), Some(public_key)) => Ok(InstanceActor {
+        actor_id: InstanceActor::local_actor_id(&context.settings())?,
+        private_key: private_key.clone(),
+        public_key: public_key.clone(),
+      }),
+      _ => Err(anyhow!("Instance actor has no keypair").into()),
+    }
+  }
+
+  /// The instance actor is served at the root url of the instance
+  pub fn local_actor_id(settings: &Settings) -> Result<Url, LemmyError> {
+    Ok(Url::parse(&format!(
+      "{}/",
+      settings.get_protocol_and_hostname()
+    ))?)
+  }
+
+  /// Includes the site metadata, if the site has been set up already
+  pub(crate) async fn into_apub(self, context: &LemmyContext) -> Result<Instance, LemmyError> {
+    let site = blocking(context.pool(), Site::read_simple).await?.ok();
+    let sidebar = site.as_ref().and_then(|s| s.sidebar.clone());
+    Ok(Instance {
+      r#type: ApplicationType::Application,
+      preferred_username: context.settings().hostname,
+      name: site.as_ref().map(|s| s.name.clone()),
+      summary: sidebar.as_ref().map(|s| markdown_to_html(s)),
+      source: sidebar.map(|s| Source {
+        content: s,
+        media_type: MediaTypeMarkdown::Markdown,
+        revision: None,
+      }),
+      icon: site
+        .as_ref()
+        .and_then(|s| s.icon.clone())
+        .map(ImageObject::new),
+      image: site
+        .as_ref()
+        .and_then(|s| s.banner.clone())
+        .map(ImageObject::new),
+      inbox: self.inbox_url(),
+      endpoints: Some(Endpoints {
+        shared_inbox: Some(self.inbox_url()),
+      }),
+      public_key: self.get_public_key()?,
+      published: site.as_ref().map(|s| convert_datetime(s.published)),
+      updated: site.and_then(|s| s.updated).map(convert_datetime),
+      id: self.actor_id,
+      unparsed: Default::default(),
+    })
+  }
+}
+
+impl ActorType for InstanceActor {
+  fn actor_id(&self) -> Url {
+    self.actor_id.clone()
+  }
+
+  fn public_key(&self) -> String {
+    self.public_key.clone()
+  }
+
+  fn private_key(&self) -> Option<String> {
+    Some(self.private_key.clone())
+  }
+
+  fn inbox_url(&self) -> Url {
+    self
+      .actor_id
+      .join("inbox")
+      .expect("instance actor inbox url")
+  }
+
+  fn shared_inbox_url(&self) -> Option<Url> {
+    None
+  }
+}
@@ -1,11 +1,18 @@
 use crate::protocol::Source;
 use html2md::parse_html;
+use lemmy_api_common::blocking;
+use lemmy_db_schema::{newtypes::LanguageId, source::language::Language};
+use lemmy_utils::LemmyError;
+use lemmy_websocket::LemmyContext;
+use std::collections::BTreeMap;
 
 pub mod comment;
 pub mod community;
+pub mod instance;
 pub mod person;
 pub mod post;
 pub mod private_message;
+pub mod wiki_page;
 
 pub(crate) fn get_summary_from_string_or_source(
   raw: &Option<String>,
@@ -18,6 +25,48 @@ pub(crate) fn get_summary_from_string_or_source(
   }
 }
 
+/// Code of the language, or None if it is undetermined
+pub(crate) async fn language_code(
+  language_id: LanguageId,
+  context: &LemmyContext,
+) -> Result<Option<String>, LemmyError> {
+  if language_id == LanguageId::default() {
+    return Ok(None);
+  }
+  let language = blocking(context.pool(), move |conn| {
+    Language::read_from_id(conn, language_id)
+  })
+  .await??;
+  Ok(Some(language.code))
+}
+
+/// Builds a `contentMap` or `nameMap`, which is how ActivityStreams marks the language of a value
+pub(crate) fn language_map(code: &Option<String>, value: &str) -> Option<BTreeMap<String, String>> {
+  code
+    .as_ref()
+    .map(|c| vec![(c.clone(), value.to_string())].into_iter().collect())
+}
+
+/// Takes the language from the first of the given maps which has an entry
+pub(crate) async fn language_from_maps(
+  maps: &[&Option<BTreeMap<String, String>>],
+  context: &LemmyContext,
+) -> Result<LanguageId, LemmyError> {
+  let code = maps
+    .iter()
+    .filter_map(|m| m.as_ref())
+    .find_map(|m| m.keys().next().cloned());
+  match code {
+    Some(code) => Ok(
+      blocking(context.pool(), move |conn| {
+        Language::read_id_from_code(conn, &code)
+      })
+      .await??,
+    ),
+    None => Ok(LanguageId::default()),
+  }
+}
+
 #[cfg(test)]
 pub(crate) mod tests {
   use actix::Actor;
@@ -63,6 +112,8 @@ pub(crate) mod tests {
     let secret = Secret {
       id: 0,
       jwt_secret: "".to_string(),
+      instance_private_key: None,
+      instance_public_key: None,
     };
     let db_url = match get_database_url_from_env() {
       Ok(url) => url,
@@ -85,6 +136,7 @@ pub(crate) mod tests {
       activity_queue.clone(),
       settings.clone(),
       secret.clone(),
+      None,
     )
     .start();
     LemmyContext::create(pool, chat_server, client, activity_queue, settings, secret)
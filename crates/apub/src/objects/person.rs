@@ -4,7 +4,7 @@ use crate::{
   objects::get_summary_from_string_or_source,
   protocol::{
     objects::{
-      person::{Person, UserTypes},
+      person::{Person, PropertyValue, PropertyValueType, UserTypes},
       Endpoints,
     },
     ImageObject,
@@ -12,6 +12,7 @@ use crate::{
   },
 };
 use chrono::NaiveDateTime;
+use html2md::parse_html;
 use lemmy_api_common::blocking;
 use lemmy_apub_lib::{
   object_id::ObjectId,
@@ -21,7 +22,10 @@ use lemmy_apub_lib::{
 };
 use lemmy_db_schema::{
   naive_now,
-  source::person::{Person as DbPerson, PersonForm},
+  source::{
+    person::{Person as DbPerson, PersonForm},
+    person_field::{PersonField, PersonFieldForm, MAX_PERSON_FIELDS},
+  },
 };
 use lemmy_utils::{
   utils::{check_slurs, check_slurs_opt, convert_datetime, markdown_to_html},
@@ -81,7 +85,7 @@ impl ApubObject for ApubPerson {
   }
 
   #[tracing::instrument(skip_all)]
-  async fn into_apub(self, _pool: &LemmyContext) -> Result<Person, LemmyError> {
+  async fn into_apub(self, context: &LemmyContext) -> Result<Person, LemmyError> {
     let kind = if self.bot_account {
       UserTypes::Service
     } else {
@@ -90,9 +94,22 @@ impl ApubObject for ApubPerson {
     let source = self.bio.clone().map(|bio| Source {
       content: bio,
       media_type: MediaTypeMarkdown::Markdown,
+      revision: None,
     });
     let icon = self.avatar.clone().map(ImageObject::new);
     let image = self.banner.clone().map(ImageObject::new);
+    let person_id = self.id;
+    let attachment = blocking(context.pool(), move |conn| {
+      PersonField::list_for_person(conn, person_id)
+    })
+    .await??
+    .into_iter()
+    .map(|f| PropertyValue {
+      kind: PropertyValueType::PropertyValue,
+      name: f.name,
+      value: markdown_to_html(&f.value),
+    })
+    .collect();
 
     let person = Person {
       kind,
@@ -111,6 +128,7 @@ impl ApubObject for ApubPerson {
       },
       public_key: self.get_public_key()?,
       updated: self.updated.map(convert_datetime),
+      attachment,
       unparsed: Default::default(),
       inbox: self.inbox_url.clone().into(),
     };
@@ -136,6 +154,10 @@ impl ApubObject for ApubPerson {
     check_slurs_opt(&person.name, slur_regex)?;
     let bio = get_summary_from_string_or_source(&person.summary, &person.source);
     check_slurs_opt(&bio, slur_regex)?;
+    for field in &person.attachment {
+      check_slurs(&field.name, slur_regex)?;
+      check_slurs(&field.value, slur_regex)?;
+    }
     Ok(())
   }
 
@@ -145,6 +167,7 @@ impl ApubObject for ApubPerson {
     context: &LemmyContext,
     _request_counter: &mut i32,
   ) -> Result<ApubPerson, LemmyError> {
+    let attachment = person.attachment;
     let person_form = PersonForm {
       name: person.preferred_username,
       display_name: Some(person.name),
@@ -173,6 +196,23 @@ impl ApubObject for ApubPerson {
       DbPerson::upsert(conn, &person_form)
     })
     .await??;
+
+    let person_id = person.id;
+    let field_forms: Vec<PersonFieldForm> = attachment
+      .into_iter()
+      .take(MAX_PERSON_FIELDS)
+      .enumerate()
+      .map(|(position, f)| PersonFieldForm {
+        person_id,
+        name: f.name,
+        value: parse_html(&f.value),
+        position: position as i32,
+      })
+      .collect();
+    blocking(context.pool(), move |conn| {
+      PersonField::replace_for_person(conn, person_id, &field_forms)
+    })
+    .await??;
     Ok(person.into())
   }
 }
@@ -232,6 +272,12 @@ pub(crate) mod tests {
     assert_eq!(person.display_name, Some("Jean-Luc Picard".to_string()));
     assert!(!person.local);
     assert_eq!(person.bio.as_ref().unwrap().len(), 39);
+    let fields = PersonField::list_for_person(&context.pool().get().unwrap(), person.id).unwrap();
+    assert_eq!(1, fields.len());
+    assert_eq!("Website", fields[0].name);
+    assert!(fields[0].value.contains("https://enterprise.lemmy.ml"));
+    let json = person.clone().into_apub(&context).await.unwrap();
+    assert_eq!(1, json.attachment.len());
 
     DbPerson::delete(&*context.pool().get().unwrap(), person.id).unwrap();
   }
@@ -1,14 +1,30 @@
 use crate::{
   activities::{verify_is_public, verify_person_in_community},
   check_is_apub_id_valid,
+  emojis::{collect_emoji_tags, render_remote_emojis},
+  objects::{language_code, language_from_maps, language_map},
   protocol::{
-    objects::{page::Page, tombstone::Tombstone},
+    objects::{
+      page::{
+        Attachment,
+        AttachmentType,
+        HashtagType,
+        Page,
+        PageTag,
+        PageType,
+        PostTagObject,
+        QuestionOption,
+        QuestionOptionReplies,
+      },
+      tombstone::{FormerType, Tombstone},
+    },
     ImageObject,
     Source,
   },
 };
-use activitystreams_kinds::{object::PageType, public};
+use activitystreams_kinds::{collection::CollectionType, object::NoteType, public};
 use chrono::NaiveDateTime;
+use diesel::OptionalExtension;
 use lemmy_api_common::blocking;
 use lemmy_apub_lib::{
   object_id::ObjectId,
@@ -21,19 +37,25 @@ use lemmy_db_schema::{
   source::{
     community::Community,
     person::Person,
+    poll::{Poll, PollForm, PollOption, PollOptionForm},
     post::{Post, PostForm},
+    post_attachment::{PostAttachment, PostAttachmentForm},
+    post_tag::{PostTag, PostTagAssignment, PostTagForm},
   },
   traits::Crud,
 };
 use lemmy_utils::{
   request::fetch_site_data,
-  utils::{check_slurs, convert_datetime, markdown_to_html, remove_slurs},
+  utils::{check_blocked_words, check_slurs, convert_datetime, markdown_to_html, remove_slurs},
   LemmyError,
 };
 use lemmy_websocket::LemmyContext;
 use std::ops::Deref;
 use url::Url;
 
+/// Attachments beyond this are dropped
+const MAX_ATTACHMENTS: usize = 20;
+
 #[derive(Clone, Debug)]
 pub struct ApubPost(Post);
 
@@ -99,17 +121,84 @@ impl ApubObject for ApubPost {
     let source = self.body.clone().map(|body| Source {
       content: body,
       media_type: MediaTypeMarkdown::Markdown,
+      revision: None,
     });
     let image = self.thumbnail_url.clone().map(ImageObject::new);
 
+    let post_id = self.id;
+    let poll = blocking(context.pool(), move |conn| {
+      Poll::read_for_post(conn, post_id)
+        .optional()?
+        .map(|poll| PollOption::list_for_poll(conn, poll.id).map(|options| (poll, options)))
+        .transpose()
+    })
+    .await??;
+    let (r#type, one_of, any_of, end_time) = match poll {
+      Some((poll, options)) => {
+        let options = options
+          .into_iter()
+          .map(|o| QuestionOption {
+            r#type: NoteType::Note,
+            name: o.name,
+            replies: QuestionOptionReplies {
+              r#type: CollectionType::Collection,
+              total_items: o.votes,
+            },
+          })
+          .collect();
+        let end_time = poll.end_time.map(convert_datetime);
+        if poll.multiple_choice {
+          (PageType::Question, None, Some(options), end_time)
+        } else {
+          (PageType::Question, Some(options), None, end_time)
+        }
+      }
+      None => (PageType::Page, None, None, None),
+    };
+
+    let mut tag: Vec<PageTag> = blocking(context.pool(), move |conn| {
+      PostTag::list_for_post(conn, post_id)
+    })
+    .await??
+    .into_iter()
+    .map(|t| {
+      PageTag::Hashtag(PostTagObject {
+        r#type: HashtagType::Hashtag,
+        name: t.name,
+      })
+    })
+    .collect();
+    if let Some(body) = &self.body {
+      let emojis = collect_emoji_tags(body, context).await?;
+      tag.extend(emojis.into_iter().map(PageTag::Emoji));
+    }
+
+    let attachment = blocking(context.pool(), move |conn| {
+      PostAttachment::list_for_post(conn, post_id)
+    })
+    .await??
+    .into_iter()
+    .map(|a| Attachment {
+      r#type: attachment_type(a.media_type.as_deref()),
+      url: a.url.into(),
+      media_type: a.media_type,
+      name: a.name,
+    })
+    .collect();
+
+    let language = language_code(self.language_id, context).await?;
+    let content = self.body.as_ref().map(|b| markdown_to_html(b));
+
     let page = Page {
-      r#type: PageType::Page,
+      r#type,
       id: ObjectId::new(self.ap_id.clone()),
       attributed_to: ObjectId::new(creator.actor_id),
       to: vec![community.actor_id.into(), public()],
       cc: vec![],
+      name_map: language_map(&language, &self.name),
       name: self.name.clone(),
-      content: self.body.as_ref().map(|b| markdown_to_html(b)),
+      content_map: content.as_ref().and_then(|c| language_map(&language, c)),
+      content,
       media_type: Some(MediaTypeHtml::Html),
       source,
       url: self.url.clone().map(|u| u.into()),
@@ -119,13 +208,21 @@ impl ApubObject for ApubPost {
       stickied: Some(self.stickied),
       published: Some(convert_datetime(self.published)),
       updated: self.updated.map(convert_datetime),
+      one_of,
+      any_of,
+      end_time,
+      tag,
+      attachment,
       unparsed: Default::default(),
     };
     Ok(page)
   }
 
   fn to_tombstone(&self) -> Result<Tombstone, LemmyError> {
-    Ok(Tombstone::new(self.ap_id.clone().into()))
+    Ok(Tombstone::with_former_type(
+      self.ap_id.clone().into(),
+      FormerType::Page,
+    ))
   }
 
   #[tracing::instrument(skip_all)]
@@ -145,6 +242,18 @@ impl ApubObject for ApubPost {
     check_is_apub_id_valid(page.id.inner(), community.local, &context.settings())?;
     verify_person_in_community(&page.attributed_to, &community, context, request_counter).await?;
     check_slurs(&page.name, &context.settings().slur_regex())?;
+    if let Some(source) = &page.source {
+      check_blocked_words(&source.content)?;
+    }
+    for option in page.poll_options() {
+      check_slurs(&option.name, &context.settings().slur_regex())?;
+    }
+    for tag in page.hashtags() {
+      check_slurs(&tag.name, &context.settings().slur_regex())?;
+    }
+    for name in page.attachment.iter().filter_map(|a| a.name.as_ref()) {
+      check_slurs(name, &context.settings().slur_regex())?;
+    }
     verify_domains_match(page.attributed_to.inner(), page.id.inner())?;
     verify_is_public(&page.to, &page.cc)?;
     Ok(())
@@ -162,23 +271,41 @@ impl ApubObject for ApubPost {
       .await?;
     let community = page.extract_community(context, request_counter).await?;
 
+    let body_slurs_removed = page
+      .source
+      .as_ref()
+      .map(|s| render_remote_emojis(&s.content, page.emojis()))
+      .map(|b| remove_slurs(&b, &context.settings().slur_regex()));
+    let tag_names: Vec<String> = page.hashtags().map(|t| t.name.clone()).collect();
+
+    // Software which only sends attachments has its first one shown as the post link
+    let first_attachment = page.attachment.first().map(|a| a.url.clone());
+    let url = page.url.or(first_attachment);
     let thumbnail_url: Option<Url> = page.image.map(|i| i.url);
-    let (metadata_res, pictrs_thumbnail) = if let Some(url) = &page.url {
+    let (metadata_res, pictrs_thumbnail) = if let Some(url) = &url {
       fetch_site_data(context.client(), &context.settings(), Some(url)).await
     } else {
       (None, thumbnail_url)
     };
-    let (embed_title, embed_description, embed_html) = metadata_res
-      .map(|u| (u.title, u.description, u.html))
-      .unwrap_or((None, None, None));
+    let metadata = metadata_res.unwrap_or_default();
 
-    let body_slurs_removed = page
-      .source
-      .as_ref()
-      .map(|s| remove_slurs(&s.content, &context.settings().slur_regex()));
+    let poll = if page.one_of.is_some() || page.any_of.is_some() {
+      let options: Vec<(String, i32)> = page
+        .one_of
+        .iter()
+        .chain(page.any_of.iter())
+        .flatten()
+        .map(|o| (o.name.clone(), o.replies.total_items))
+        .collect();
+      let end_time = page.end_time.map(|e| e.naive_local());
+      Some((page.any_of.is_some(), end_time, options))
+    } else {
+      None
+    };
+    let language_id = language_from_maps(&[&page.content_map, &page.name_map], context).await?;
     let form = PostForm {
       name: page.name,
-      url: page.url.map(|u| u.into()),
+      url: url.map(|u| u.into()),
       body: body_slurs_removed,
       creator_id: creator.id,
       community_id: community.id,
@@ -189,18 +316,105 @@ impl ApubObject for ApubPost {
       deleted: None,
       nsfw: page.sensitive,
       stickied: page.stickied,
-      embed_title,
-      embed_description,
-      embed_html,
+      embed_title: metadata.title,
+      embed_description: metadata.description,
+      embed_html: metadata.html,
+      embed_video_url: metadata.embed_video_url.map(|u| u.into()),
+      embed_provider_name: metadata.provider_name,
       thumbnail_url: pictrs_thumbnail.map(|u| u.into()),
       ap_id: Some(page.id.into()),
       local: Some(false),
+      cross_post_of: None,
+      language_id: Some(language_id),
+      pending: None,
     };
     let post = blocking(context.pool(), move |conn| Post::upsert(conn, &form)).await??;
+
+    // Vote counts of remote polls are always taken from the origin instance
+    if let Some((multiple_choice, end_time, options)) = poll {
+      let poll_form = PollForm {
+        post_id: post.id,
+        multiple_choice,
+        end_time,
+      };
+      blocking(context.pool(), move |conn| {
+        let poll = Poll::upsert(conn, &poll_form)?;
+        let option_forms: Vec<PollOptionForm> = options
+          .into_iter()
+          .enumerate()
+          .map(|(position, (name, votes))| PollOptionForm {
+            poll_id: poll.id,
+            name,
+            position: position as i32,
+            votes: Some(votes),
+          })
+          .collect();
+        PollOption::replace_for_poll(conn, poll.id, &option_forms)
+      })
+      .await??;
+    }
+
+    // Tags of remote communities are created as they come in, local communities only accept the
+    // tags which their mods defined
+    let post_id = post.id;
+    let community_id = community.id;
+    let community_local = community.local;
+    blocking(context.pool(), move |conn| {
+      let tag_ids = if community_local {
+        PostTag::list_for_community(conn, community_id)?
+          .into_iter()
+          .filter(|t| tag_names.contains(&t.name))
+          .map(|t| t.id)
+          .collect::<Vec<i32>>()
+      } else {
+        tag_names
+          .into_iter()
+          .map(|name| {
+            let form = PostTagForm {
+              community_id,
+              name,
+              updated: None,
+            };
+            PostTag::upsert(conn, &form).map(|t| t.id)
+          })
+          .collect::<Result<Vec<i32>, _>>()?
+      };
+      PostTagAssignment::replace_for_post(conn, post_id, &tag_ids)
+    })
+    .await??;
+
+    let attachment_forms: Vec<PostAttachmentForm> = page
+      .attachment
+      .into_iter()
+      .take(MAX_ATTACHMENTS)
+      .enumerate()
+      .map(|(position, a)| PostAttachmentForm {
+        post_id,
+        url: a.url.into(),
+        media_type: a.media_type,
+        name: a.name,
+        position: position as i32,
+      })
+      .collect();
+    blocking(context.pool(), move |conn| {
+      PostAttachment::replace_for_post(conn, post_id, &attachment_forms)
+    })
+    .await??;
     Ok(post.into())
   }
 }
 
+/// Only the media type is stored, so the attachment type is derived from it
+fn attachment_type(media_type: Option<&str>) -> AttachmentType {
+  match media_type.and_then(|m| m.split('/').next()) {
+    None => AttachmentType::Link,
+    Some("image") => AttachmentType::Image,
+    Some("video") => AttachmentType::Video,
+    Some("audio") => AttachmentType::Audio,
+    Some(_) => AttachmentType::Document,
+  }
+}
+
 #[cfg(test)]
 mod tests {
   use super::*;
@@ -211,6 +425,7 @@ mod tests {
     tests::{file_to_json_object, init_context},
   };
   use lemmy_apub_lib::activity_queue::create_activity_queue;
+  use lemmy_db_schema::source::language::Language;
   use serial_test::serial;
 
   #[actix_rt::test]
@@ -240,8 +455,67 @@ mod tests {
     assert!(post.stickied);
     assert_eq!(request_counter, 0);
 
+    let conn = context.pool().get().unwrap();
+    let tags = PostTag::list_for_post(&conn, post.id).unwrap();
+    assert_eq!(
+      vec!["Discussion"],
+      tags.iter().map(|t| t.name.as_str()).collect::<Vec<_>>()
+    );
+    let attachments = PostAttachment::list_for_post(&conn, post.id).unwrap();
+    assert_eq!(2, attachments.len());
+    assert_eq!(Some("A test image".to_string()), attachments[0].name);
+    let english = Language::read_id_from_code(&conn, "en").unwrap();
+    assert_eq!(english, post.language_id);
+    let page = post.clone().into_apub(&context).await.unwrap();
+    assert!(page.name_map.as_ref().unwrap().contains_key("en"));
+    assert_eq!("Discussion", page.hashtags().next().unwrap().name);
+    assert_eq!(2, page.attachment.len());
+    assert!(matches!(page.attachment[1].r#type, AttachmentType::Link));
+
     Post::delete(&*context.pool().get().unwrap(), post.id).unwrap();
     Person::delete(&*context.pool().get().unwrap(), person.id).unwrap();
     Community::delete(&*context.pool().get().unwrap(), community.id).unwrap();
   }
+
+  #[actix_rt::test]
+  #[serial]
+  async fn test_parse_lemmy_question() {
+    let client = reqwest::Client::new().into();
+    let manager = create_activity_queue(client);
+    let context = init_context(manager.queue_handle().clone());
+    let community = parse_lemmy_community(&context).await;
+    let person = parse_lemmy_person(&context).await;
+
+    let json: Page = file_to_json_object("assets/lemmy/objects/question.json");
+    let url = Url::parse("https://enterprise.lemmy.ml/post/55144").unwrap();
+    let mut request_counter = 0;
+    ApubPost::verify(&json, &url, &context, &mut request_counter)
+      .await
+      .unwrap();
+    let post = ApubPost::from_apub(json, &context, &mut request_counter)
+      .await
+      .unwrap();
+
+    let conn = context.pool().get().unwrap();
+    let poll = Poll::read_for_post(&conn, post.id).unwrap();
+    let options = PollOption::list_for_poll(&conn, poll.id).unwrap();
+    assert!(!poll.multiple_choice);
+    assert!(poll.end_time.is_some());
+    assert_eq!(
+      vec![("Tea", 3), ("Synthehol", 1)],
+      options
+        .iter()
+        .map(|o| (o.name.as_str(), o.votes))
+        .collect::<Vec<_>>()
+    );
+
+    let page = post.clone().into_apub(&context).await.unwrap();
+    assert!(matches!(page.r#type, PageType::Question));
+    assert_eq!(2, page.one_of.unwrap().len());
+    assert!(page.any_of.is_none());
+
+    Post::delete(&conn, post.id).unwrap();
+    Person::delete(&conn, person.id).unwrap();
+    Community::delete(&conn, community.id).unwrap();
+  }
 }
@@ -85,14 +85,17 @@ impl ApubObject for ApubPrivateMessage {
       id: ObjectId::new(self.ap_id.clone()),
       attributed_to: ObjectId::new(creator.actor_id),
       to: [ObjectId::new(recipient.actor_id)],
+      cc: vec![],
       content: markdown_to_html(&self.content),
       media_type: Some(MediaTypeHtml::Html),
       source: Some(Source {
         content: self.content.clone(),
         media_type: MediaTypeMarkdown::Markdown,
+        revision: None,
       }),
       published: Some(convert_datetime(self.published)),
       updated: self.updated.map(convert_datetime),
+      tag: vec![],
       unparsed: Default::default(),
     };
     Ok(note)
@@ -248,4 +251,45 @@ mod tests {
     PrivateMessage::delete(&*context.pool().get().unwrap(), pm.id).unwrap();
     cleanup(data, &context);
   }
+
+  #[actix_rt::test]
+  #[serial]
+  async fn test_parse_mastodon_pm() {
+    let client = reqwest::Client::new().into();
+    let manager = create_activity_queue(client);
+    let context = init_context(manager.queue_handle().clone());
+    let url = Url::parse("https://enterprise.lemmy.ml/private_message/1621").unwrap();
+    let data = prepare_comment_test(&url, &context).await;
+    let mastodon_person = file_to_json_object("assets/mastodon/objects/person.json");
+    let mastodon_person_url = Url::parse("https://mastodon.madrid/users/felix").unwrap();
+    ApubPerson::verify(&mastodon_person, &mastodon_person_url, &context, &mut 0)
+      .await
+      .unwrap();
+    let mastodon_person = ApubPerson::from_apub(mastodon_person, &context, &mut 0)
+      .await
+      .unwrap();
+    let mastodon_url =
+      Url::parse("https://mastodon.madrid/users/felix/statuses/107811312883430145").unwrap();
+    let json: ChatMessage = file_to_json_object("assets/mastodon/objects/private_message.json");
+    let mut request_counter = 0;
+    ApubPrivateMessage::verify(&json, &mastodon_url, &context, &mut request_counter)
+      .await
+      .unwrap();
+    let pm = ApubPrivateMessage::from_apub(json, &context, &mut request_counter)
+      .await
+      .unwrap();
+
+    assert_eq!(pm.ap_id, mastodon_url.into());
+    assert_eq!(pm.creator_id, mastodon_person.id);
+    assert_eq!(pm.recipient_id, data.0.id);
+    assert_eq!(
+      pm.content,
+      "[@picard](https://enterprise.lemmy.ml/u/picard) hello from mastodon"
+    );
+    assert_eq!(request_counter, 0);
+
+    PrivateMessage::delete(&context.pool().get().unwrap(), pm.id).unwrap();
+    Person::delete(&context.pool().get().unwrap(), mastodon_person.id).unwrap();
+    cleanup(data, &context);
+  }
 }
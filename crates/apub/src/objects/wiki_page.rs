@@ -0,0 +1,235 @@
+use crate::{
+  activities::verify_person_in_community,
+  check_is_apub_id_valid,
+  protocol::{
+    objects::{
+      article::Article,
+      tombstone::{FormerType, Tombstone},
+    },
+    Source,
+  },
+};
+use activitystreams_kinds::{object::ArticleType, public};
+use chrono::NaiveDateTime;
+use html2md::parse_html;
+use lemmy_api_common::blocking;
+use lemmy_apub_lib::{
+  object_id::ObjectId,
+  traits::ApubObject,
+  values::{MediaTypeHtml, MediaTypeMarkdown},
+  verify::verify_domains_match,
+};
+use lemmy_db_schema::{
+  source::{
+    community::Community,
+    community_wiki_page::{CommunityWikiPage, CommunityWikiPageForm},
+    person::Person,
+  },
+  traits::Crud,
+};
+use lemmy_utils::{
+  utils::{check_slurs, convert_datetime, markdown_to_html},
+  LemmyError,
+};
+use lemmy_websocket::LemmyContext;
+use std::ops::Deref;
+use url::Url;
+
+#[derive(Clone, Debug)]
+pub struct ApubWikiPage(CommunityWikiPage);
+
+impl Deref for ApubWikiPage {
+  type Target = CommunityWikiPage;
+  fn deref(&self) -> &Self::Target {
+    &self.0
+  }
+}
+
+impl From<CommunityWikiPage> for ApubWikiPage {
+  fn from(p: CommunityWikiPage) -> Self {
+    ApubWikiPage(p)
+  }
+}
+
+#[async_trait::async_trait(?Send)]
+impl ApubObject for ApubWikiPage {
+  type DataType = LemmyContext;
+  type ApubType = Article;
+  type TombstoneType = Tombstone;
+
+  fn last_refreshed_at(&self) -> Option<NaiveDateTime> {
+    None
+  }
+
+  #[tracing::instrument(skip_all)]
+  async fn read_from_apub_id(
+    object_id: Url,
+    context: &LemmyContext,
+  ) -> Result<Option<Self>, LemmyError> {
+    Ok(
+      blocking(context.pool(), move |conn| {
+        CommunityWikiPage::read_from_apub_id(conn, object_id)
+      })
+      .await??
+      .map(Into::into),
+    )
+  }
+
+  #[tracing::instrument(skip_all)]
+  async fn delete(self, context: &LemmyContext) -> Result<(), LemmyError> {
+    if !self.deleted {
+      blocking(context.pool(), move |conn| {
+        CommunityWikiPage::update_deleted(conn, self.id, true)
+      })
+      .await??;
+    }
+    Ok(())
+  }
+
+  #[tracing::instrument(skip_all)]
+  async fn into_apub(self, context: &LemmyContext) -> Result<Article, LemmyError> {
+    let creator_id = self.creator_id;
+    let creator = blocking(context.pool(), move |conn| Person::read(conn, creator_id)).await??;
+    let community_id = self.community_id;
+    let community = blocking(context.pool(), move |conn| {
+      Community::read(conn, community_id)
+    })
+    .await??;
+
+    let article = Article {
+      r#type: ArticleType::Article,
+      id: ObjectId::new(self.ap_id.clone()),
+      attributed_to: ObjectId::new(creator.actor_id),
+      to: vec![public()],
+      audience: ObjectId::new(community.actor_id),
+      name: self.title.clone(),
+      content: markdown_to_html(&self.content),
+      media_type: Some(MediaTypeHtml::Html),
+      source: Some(Source {
+        content: self.content.clone(),
+        media_type: MediaTypeMarkdown::Markdown,
+        revision: None,
+      }),
+      published: Some(convert_datetime(self.published)),
+      updated: self.updated.map(convert_datetime),
+      unparsed: Default::default(),
+    };
+    Ok(article)
+  }
+
+  fn to_tombstone(&self) -> Result<Tombstone, LemmyError> {
+    Ok(Tombstone::with_former_type(
+      self.ap_id.clone().into(),
+      FormerType::Article,
+    ))
+  }
+
+  #[tracing::instrument(skip_all)]
+  async fn verify(
+    article: &Article,
+    expected_domain: &Url,
+    context: &LemmyContext,
+    request_counter: &mut i32,
+  ) -> Result<(), LemmyError> {
+    verify_domains_match(article.id.inner(), expected_domain)?;
+    verify_domains_match(article.attributed_to.inner(), article.id.inner())?;
+    let community = article
+      .audience
+      .dereference(context, context.client(), request_counter)
+      .await?;
+    check_is_apub_id_valid(article.id.inner(), community.local, &context.settings())?;
+    verify_person_in_community(&article.attributed_to, &community, context, request_counter)
+      .await?;
+    let slur_regex = &context.settings().slur_regex();
+    check_slurs(&article.name, slur_regex)?;
+    if let Some(source) = &article.source {
+      check_slurs(&source.content, slur_regex)?;
+    }
+    Ok(())
+  }
+
+  #[tracing::instrument(skip_all)]
+  async fn from_apub(
+    article: Article,
+    context: &LemmyContext,
+    request_counter: &mut i32,
+  ) -> Result<ApubWikiPage, LemmyError> {
+    let creator = article
+      .attributed_to
+      .dereference(context, context.client(), request_counter)
+      .await?;
+    let community = article
+      .audience
+      .dereference(context, context.client(), request_counter)
+      .await?;
+    let content = if let Some(source) = article.source {
+      source.content
+    } else {
+      parse_html(&article.content)
+    };
+
+    let form = CommunityWikiPageForm {
+      community_id: community.id,
+      creator_id: creator.id,
+      title: article.name,
+      content,
+      deleted: None,
+      published: article.published.map(|u| u.naive_local()),
+      updated: article.updated.map(|u| u.naive_local()),
+      ap_id: Some(article.id.into()),
+      local: Some(false),
+    };
+    let page = blocking(context.pool(), move |conn| {
+      CommunityWikiPage::upsert(conn, &form)
+    })
+    .await??;
+    Ok(page.into())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::objects::{
+    community::tests::parse_lemmy_community,
+    person::tests::parse_lemmy_person,
+    tests::{file_to_json_object, init_context},
+  };
+  use assert_json_diff::assert_json_include;
+  use lemmy_apub_lib::activity_queue::create_activity_queue;
+  use serial_test::serial;
+
+  #[actix_rt::test]
+  #[serial]
+  async fn test_parse_lemmy_wiki_page() {
+    let client = reqwest::Client::new().into();
+    let manager = create_activity_queue(client);
+    let context = init_context(manager.queue_handle().clone());
+    let community = parse_lemmy_community(&context).await;
+    let person = parse_lemmy_person(&context).await;
+
+    let json: Article = file_to_json_object("assets/lemmy/objects/article.json");
+    let url = Url::parse("https://enterprise.lemmy.ml/wiki_page/55").unwrap();
+    let mut request_counter = 0;
+    ApubWikiPage::verify(&json, &url, &context, &mut request_counter)
+      .await
+      .unwrap();
+    let page = ApubWikiPage::from_apub(json.clone(), &context, &mut request_counter)
+      .await
+      .unwrap();
+
+    assert_eq!(page.ap_id, url.into());
+    assert_eq!(page.title, "Community rules");
+    assert_eq!(page.community_id, community.id);
+    assert!(!page.local);
+    assert_eq!(request_counter, 0);
+
+    let page_id = page.id;
+    let to_apub = page.into_apub(&context).await.unwrap();
+    assert_json_include!(actual: json, expected: to_apub);
+
+    CommunityWikiPage::delete(&context.pool().get().unwrap(), page_id).unwrap();
+    Person::delete(&context.pool().get().unwrap(), person.id).unwrap();
+    Community::delete(&context.pool().get().unwrap(), community.id).unwrap();
+  }
+}
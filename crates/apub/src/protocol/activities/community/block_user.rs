@@ -3,10 +3,13 @@ use crate::{
   protocol::Unparsed,
 };
 use activitystreams_kinds::activity::BlockType;
+use chrono::{DateTime, FixedOffset};
 use lemmy_apub_lib::object_id::ObjectId;
 use serde::{Deserialize, Serialize};
+use serde_with::skip_serializing_none;
 use url::Url;
 
+#[skip_serializing_none]
 #[derive(Clone, Debug, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct BlockUserFromCommunity {
@@ -19,6 +22,9 @@ pub struct BlockUserFromCommunity {
   pub(crate) target: ObjectId<ApubCommunity>,
   #[serde(rename = "type")]
   pub(crate) kind: BlockType,
+  /// Reason for the ban
+  pub(crate) summary: Option<String>,
+  pub(crate) expires: Option<DateTime<FixedOffset>>,
   pub(crate) id: Url,
   #[serde(flatten)]
   pub(crate) unparsed: Unparsed,
@@ -0,0 +1,32 @@
+use crate::{
+  objects::{community::ApubCommunity, person::ApubPerson, post::ApubPost},
+  protocol::Unparsed,
+};
+use lemmy_apub_lib::object_id::ObjectId;
+use serde::{Deserialize, Serialize};
+use strum_macros::Display;
+use url::Url;
+
+/// Stickies or unstickies a post in the community which is the `target`. Only sent by mods.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FeaturePost {
+  pub(crate) actor: ObjectId<ApubPerson>,
+  #[serde(deserialize_with = "crate::deserialize_one_or_many")]
+  pub(crate) to: Vec<Url>,
+  pub(crate) object: ObjectId<ApubPost>,
+  #[serde(deserialize_with = "crate::deserialize_one_or_many")]
+  pub(crate) cc: Vec<Url>,
+  pub(crate) target: ObjectId<ApubCommunity>,
+  #[serde(rename = "type")]
+  pub(crate) kind: FeatureType,
+  pub(crate) id: Url,
+  #[serde(flatten)]
+  pub(crate) unparsed: Unparsed,
+}
+
+#[derive(Clone, Debug, Display, Deserialize, Serialize, PartialEq)]
+pub enum FeatureType {
+  Feature,
+  Unfeature,
+}
@@ -1,7 +1,9 @@
 pub mod add_mod;
 pub mod announce;
 pub mod block_user;
+pub mod feature_post;
 pub mod remove_mod;
+pub mod remove_user_content;
 pub mod report;
 pub mod undo_block_user;
 pub mod update;
@@ -13,7 +15,9 @@ mod tests {
       add_mod::AddMod,
       announce::AnnounceActivity,
       block_user::BlockUserFromCommunity,
+      feature_post::FeaturePost,
       remove_mod::RemoveMod,
+      remove_user_content::RemoveUserContent,
       report::Report,
       undo_block_user::UndoBlockUserFromCommunity,
       update::UpdateCommunity,
@@ -37,10 +41,16 @@ mod tests {
       "assets/lemmy/activities/community/undo_block_user.json",
     );
 
+    test_parse_lemmy_item::<FeaturePost>("assets/lemmy/activities/community/feature_post.json");
+    test_parse_lemmy_item::<RemoveUserContent>(
+      "assets/lemmy/activities/community/remove_user_content.json",
+    );
+
     test_parse_lemmy_item::<UpdateCommunity>(
       "assets/lemmy/activities/community/update_community.json",
     );
 
     test_parse_lemmy_item::<Report>("assets/lemmy/activities/community/report_page.json");
+    test_parse_lemmy_item::<Report>("assets/lemmy/activities/community/report_page_forwarded.json");
   }
 }
@@ -0,0 +1,37 @@
+use crate::{
+  objects::{community::ApubCommunity, person::ApubPerson},
+  protocol::Unparsed,
+};
+use lemmy_apub_lib::object_id::ObjectId;
+use serde::{Deserialize, Serialize};
+use serde_with::skip_serializing_none;
+use strum_macros::Display;
+use url::Url;
+
+/// Removes all posts and comments of the person in the community which is the `target`. Used
+/// instead of a separate `Remove` for each item, when mods clean up after a spam account. Only
+/// sent by mods.
+#[skip_serializing_none]
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RemoveUserContent {
+  pub(crate) actor: ObjectId<ApubPerson>,
+  #[serde(deserialize_with = "crate::deserialize_one_or_many")]
+  pub(crate) to: Vec<Url>,
+  pub(crate) object: ObjectId<ApubPerson>,
+  #[serde(deserialize_with = "crate::deserialize_one_or_many")]
+  pub(crate) cc: Vec<Url>,
+  pub(crate) target: ObjectId<ApubCommunity>,
+  #[serde(rename = "type")]
+  pub(crate) kind: RemoveUserContentType,
+  /// Reason for the removal
+  pub(crate) summary: Option<String>,
+  pub(crate) id: Url,
+  #[serde(flatten)]
+  pub(crate) unparsed: Unparsed,
+}
+
+#[derive(Clone, Debug, Display, Deserialize, Serialize, PartialEq)]
+pub enum RemoveUserContentType {
+  RemoveUserContent,
+}
@@ -1,6 +1,6 @@
 use crate::{
-  fetcher::post_or_comment::PostOrComment,
-  objects::{community::ApubCommunity, person::ApubPerson},
+  fetcher::{post_or_comment::PostOrComment, user_or_community::UserOrCommunity},
+  objects::community::ApubCommunity,
   protocol::Unparsed,
 };
 use activitystreams_kinds::activity::FlagType;
@@ -11,7 +11,8 @@ use url::Url;
 #[derive(Clone, Debug, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Report {
-  pub(crate) actor: ObjectId<ApubPerson>,
+  /// The reporter, or the community when it forwards a report to the instance of the content
+  pub(crate) actor: ObjectId<UserOrCommunity>,
   pub(crate) to: [ObjectId<ApubCommunity>; 1],
   pub(crate) object: ObjectId<PostOrComment>,
   pub(crate) summary: String,
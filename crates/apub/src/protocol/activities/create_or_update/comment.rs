@@ -1,7 +1,10 @@
 use crate::{
-  mentions::Mention,
   objects::person::ApubPerson,
-  protocol::{activities::CreateOrUpdateType, objects::note::Note, Unparsed},
+  protocol::{
+    activities::CreateOrUpdateType,
+    objects::note::{Note, NoteTag},
+    Unparsed,
+  },
 };
 use lemmy_apub_lib::object_id::ObjectId;
 use serde::{Deserialize, Serialize};
@@ -17,7 +20,7 @@ pub struct CreateOrUpdateComment {
   #[serde(deserialize_with = "crate::deserialize_one_or_many")]
   pub(crate) cc: Vec<Url>,
   #[serde(default)]
-  pub(crate) tag: Vec<Mention>,
+  pub(crate) tag: Vec<NoteTag>,
   #[serde(rename = "type")]
   pub(crate) kind: CreateOrUpdateType,
   pub(crate) id: Url,
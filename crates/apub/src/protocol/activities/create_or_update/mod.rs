@@ -1,5 +1,6 @@
 pub mod comment;
 pub mod post;
+pub mod wiki_page;
 
 #[cfg(test)]
 mod tests {
@@ -7,7 +8,11 @@ mod tests {
     context::WithContext,
     objects::tests::file_to_json_object,
     protocol::{
-      activities::create_or_update::{comment::CreateOrUpdateComment, post::CreateOrUpdatePost},
+      activities::create_or_update::{
+        comment::CreateOrUpdateComment,
+        post::CreateOrUpdatePost,
+        wiki_page::CreateOrUpdateWikiPage,
+      },
       tests::test_parse_lemmy_item,
     },
   };
@@ -23,6 +28,9 @@ mod tests {
     test_parse_lemmy_item::<CreateOrUpdateComment>(
       "assets/lemmy/activities/create_or_update/create_note.json",
     );
+    test_parse_lemmy_item::<CreateOrUpdateWikiPage>(
+      "assets/lemmy/activities/create_or_update/update_article.json",
+    );
 
     file_to_json_object::<WithContext<CreateOrUpdateComment>>(
       "assets/pleroma/activities/create_note.json",
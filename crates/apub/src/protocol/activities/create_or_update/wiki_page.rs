@@ -0,0 +1,23 @@
+use crate::{
+  objects::person::ApubPerson,
+  protocol::{activities::CreateOrUpdateType, objects::article::Article, Unparsed},
+};
+use lemmy_apub_lib::object_id::ObjectId;
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateOrUpdateWikiPage {
+  pub(crate) actor: ObjectId<ApubPerson>,
+  #[serde(deserialize_with = "crate::deserialize_one_or_many")]
+  pub(crate) to: Vec<Url>,
+  pub(crate) object: Article,
+  #[serde(deserialize_with = "crate::deserialize_one_or_many")]
+  pub(crate) cc: Vec<Url>,
+  #[serde(rename = "type")]
+  pub(crate) kind: CreateOrUpdateType,
+  pub(crate) id: Url,
+  #[serde(flatten)]
+  pub(crate) unparsed: Unparsed,
+}
@@ -1,19 +1,44 @@
 pub mod delete;
+pub mod remove;
 pub mod undo_delete;
+pub mod undo_remove;
 
 #[cfg(test)]
 mod tests {
   use crate::protocol::{
-    activities::deletion::{delete::Delete, undo_delete::UndoDelete},
+    activities::deletion::{
+      delete::Delete,
+      remove::Remove,
+      undo_delete::UndoDelete,
+      undo_remove::UndoRemove,
+    },
+    objects::tombstone::{FormerType, Tombstone},
     tests::test_parse_lemmy_item,
   };
 
   #[actix_rt::test]
   async fn test_parse_lemmy_deletion() {
-    test_parse_lemmy_item::<Delete>("assets/lemmy/activities/deletion/remove_note.json");
-    test_parse_lemmy_item::<Delete>("assets/lemmy/activities/deletion/delete_page.json");
+    let remove =
+      test_parse_lemmy_item::<Delete>("assets/lemmy/activities/deletion/remove_note.json");
+    assert_eq!(Some(FormerType::Note), remove.object.former_type);
+    let delete =
+      test_parse_lemmy_item::<Delete>("assets/lemmy/activities/deletion/delete_page.json");
+    assert_eq!(Some(FormerType::Page), delete.object.former_type);
+    test_parse_lemmy_item::<Delete>("assets/lemmy/activities/deletion/delete_moderators.json");
 
     test_parse_lemmy_item::<UndoDelete>("assets/lemmy/activities/deletion/undo_remove_note.json");
-    test_parse_lemmy_item::<UndoDelete>("assets/lemmy/activities/deletion/undo_delete_page.json");
+    let undo =
+      test_parse_lemmy_item::<UndoDelete>("assets/lemmy/activities/deletion/undo_delete_page.json");
+    assert_eq!(None, undo.object.object.former_type);
+    let other_software = serde_json::json!({
+      "id": "https://example.com/events/1",
+      "type": "Tombstone",
+      "formerType": "Event"
+    });
+    let tombstone: Tombstone = serde_json::from_value(other_software).unwrap();
+    assert_eq!(Some(FormerType::Other), tombstone.former_type);
+
+    test_parse_lemmy_item::<Remove>("assets/lemmy/activities/deletion/remove_page.json");
+    test_parse_lemmy_item::<UndoRemove>("assets/lemmy/activities/deletion/undo_remove_page.json");
   }
 }
@@ -0,0 +1,33 @@
+use crate::{
+  objects::person::ApubPerson,
+  protocol::{objects::tombstone::Tombstone, Unparsed},
+};
+use activitystreams_kinds::activity::RemoveType;
+use lemmy_apub_lib::object_id::ObjectId;
+use serde::{Deserialize, Serialize};
+use serde_with::skip_serializing_none;
+use std::collections::HashMap;
+use url::Url;
+
+/// A mod or admin removing a post, comment or community. Users deleting their own content use
+/// `Delete` instead.
+#[skip_serializing_none]
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Remove {
+  pub(crate) actor: ObjectId<ApubPerson>,
+  #[serde(deserialize_with = "crate::deserialize_one_or_many")]
+  pub(crate) to: Vec<Url>,
+  pub(crate) object: Tombstone,
+  #[serde(deserialize_with = "crate::deserialize_one_or_many")]
+  pub(crate) cc: Vec<Url>,
+  #[serde(rename = "type")]
+  pub(crate) kind: RemoveType,
+  /// Reason for the removal
+  pub(crate) summary: Option<String>,
+  /// Translations of the reason, keyed by language code
+  pub(crate) summary_map: Option<HashMap<String, String>>,
+  pub(crate) id: Url,
+  #[serde(flatten)]
+  pub(crate) unparsed: Unparsed,
+}
@@ -0,0 +1,20 @@
+use crate::protocol::Unparsed;
+use activitystreams_kinds::activity::BlockType;
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+/// Sent by an instance actor to the instance actor of another instance, when an admin adds that
+/// instance to the blocklist.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BlockInstance {
+  pub(crate) actor: Url,
+  #[serde(deserialize_with = "crate::deserialize_one_or_many")]
+  pub(crate) to: Vec<Url>,
+  pub(crate) object: Url,
+  #[serde(rename = "type")]
+  pub(crate) kind: BlockType,
+  pub(crate) id: Url,
+  #[serde(flatten)]
+  pub(crate) unparsed: Unparsed,
+}
@@ -0,0 +1,26 @@
+pub mod block;
+pub mod undo_block;
+pub mod update;
+
+#[cfg(test)]
+mod tests {
+  use crate::protocol::{
+    activities::instance::{
+      block::BlockInstance,
+      undo_block::UndoBlockInstance,
+      update::UpdateInstance,
+    },
+    tests::test_parse_lemmy_item,
+  };
+
+  #[actix_rt::test]
+  async fn test_parse_lemmy_instance() {
+    test_parse_lemmy_item::<UpdateInstance>(
+      "assets/lemmy/activities/instance/update_instance.json",
+    );
+    test_parse_lemmy_item::<BlockInstance>("assets/lemmy/activities/instance/block_instance.json");
+    test_parse_lemmy_item::<UndoBlockInstance>(
+      "assets/lemmy/activities/instance/undo_block_instance.json",
+    );
+  }
+}
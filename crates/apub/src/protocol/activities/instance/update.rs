@@ -0,0 +1,19 @@
+use crate::protocol::{objects::instance::Instance, Unparsed};
+use activitystreams_kinds::activity::UpdateType;
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+/// Sent by an instance actor to linked instances when the site metadata changes.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateInstance {
+  pub(crate) actor: Url,
+  #[serde(deserialize_with = "crate::deserialize_one_or_many")]
+  pub(crate) to: Vec<Url>,
+  pub(crate) object: Box<Instance>,
+  #[serde(rename = "type")]
+  pub(crate) kind: UpdateType,
+  pub(crate) id: Url,
+  #[serde(flatten)]
+  pub(crate) unparsed: Unparsed,
+}
@@ -5,6 +5,7 @@ pub mod community;
 pub mod create_or_update;
 pub mod deletion;
 pub mod following;
+pub mod instance;
 pub mod private_message;
 pub mod voting;
 
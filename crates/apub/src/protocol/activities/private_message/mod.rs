@@ -4,13 +4,17 @@ pub mod undo_delete;
 
 #[cfg(test)]
 mod tests {
-  use crate::protocol::{
-    activities::private_message::{
-      create_or_update::CreateOrUpdatePrivateMessage,
-      delete::DeletePrivateMessage,
-      undo_delete::UndoDeletePrivateMessage,
+  use crate::{
+    activity_lists::PersonInboxActivities,
+    objects::tests::file_to_json_object,
+    protocol::{
+      activities::private_message::{
+        create_or_update::CreateOrUpdatePrivateMessage,
+        delete::DeletePrivateMessage,
+        undo_delete::UndoDeletePrivateMessage,
+      },
+      tests::test_parse_lemmy_item,
     },
-    tests::test_parse_lemmy_item,
   };
 
   #[actix_rt::test]
@@ -25,4 +29,23 @@ mod tests {
       "assets/lemmy/activities/private_message/undo_delete.json",
     );
   }
+
+  #[actix_rt::test]
+  async fn test_parse_mastodon_private_message() {
+    let activity = file_to_json_object::<PersonInboxActivities>(
+      "assets/mastodon/activities/create_private_message.json",
+    );
+    assert!(matches!(
+      activity,
+      PersonInboxActivities::CreateOrUpdatePrivateMessage(_)
+    ));
+
+    // Public notes are comments, even though they have the same type
+    let activity =
+      file_to_json_object::<PersonInboxActivities>("assets/mastodon/activities/create_note.json");
+    assert!(matches!(
+      activity,
+      PersonInboxActivities::AnnouncableActivities(_)
+    ));
+  }
 }
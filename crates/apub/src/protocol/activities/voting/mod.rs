@@ -1,19 +1,27 @@
+pub mod poll_vote;
 pub mod undo_vote;
 pub mod vote;
 
 #[cfg(test)]
 mod tests {
-  use crate::protocol::{
-    activities::voting::{undo_vote::UndoVote, vote::Vote},
-    tests::test_parse_lemmy_item,
+  use crate::{
+    context::WithContext,
+    objects::tests::file_to_json_object,
+    protocol::{
+      activities::voting::{poll_vote::PollVote, undo_vote::UndoVote, vote::Vote},
+      tests::test_parse_lemmy_item,
+    },
   };
 
   #[actix_rt::test]
   async fn test_parse_lemmy_voting() {
     test_parse_lemmy_item::<Vote>("assets/lemmy/activities/voting/like_note.json");
     test_parse_lemmy_item::<Vote>("assets/lemmy/activities/voting/dislike_page.json");
+    test_parse_lemmy_item::<Vote>("assets/lemmy/activities/voting/like_note_reaction.json");
 
     test_parse_lemmy_item::<UndoVote>("assets/lemmy/activities/voting/undo_like_note.json");
     test_parse_lemmy_item::<UndoVote>("assets/lemmy/activities/voting/undo_dislike_page.json");
+
+    file_to_json_object::<WithContext<PollVote>>("assets/mastodon/activities/poll_vote.json");
   }
 }
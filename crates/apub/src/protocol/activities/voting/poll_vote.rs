@@ -0,0 +1,39 @@
+use crate::{
+  objects::{person::ApubPerson, post::ApubPost},
+  protocol::Unparsed,
+};
+use activitystreams_kinds::{activity::CreateType, object::NoteType};
+use lemmy_apub_lib::object_id::ObjectId;
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+/// Vote in a poll, in the same format as Mastodon. There is one activity for each chosen option,
+/// which is only sent to the creator of the poll.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PollVote {
+  pub(crate) actor: ObjectId<ApubPerson>,
+  #[serde(deserialize_with = "crate::deserialize_one_or_many")]
+  pub(crate) to: Vec<Url>,
+  pub(crate) object: PollVoteNote,
+  #[serde(rename = "type")]
+  pub(crate) kind: CreateType,
+  pub(crate) id: Url,
+  #[serde(flatten)]
+  pub(crate) unparsed: Unparsed,
+}
+
+/// Unlike a comment, this has no content. The name is the chosen option.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PollVoteNote {
+  pub(crate) r#type: NoteType,
+  pub(crate) id: Url,
+  pub(crate) name: String,
+  pub(crate) in_reply_to: ObjectId<ApubPost>,
+  pub(crate) attributed_to: ObjectId<ApubPerson>,
+  #[serde(deserialize_with = "crate::deserialize_one_or_many")]
+  pub(crate) to: Vec<Url>,
+  #[serde(flatten)]
+  pub(crate) unparsed: Unparsed,
+}
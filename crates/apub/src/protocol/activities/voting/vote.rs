@@ -22,6 +22,9 @@ pub struct Vote {
   #[serde(rename = "type")]
   pub(crate) kind: VoteType,
   pub(crate) id: Url,
+  /// Set to an emoji for reactions, which are sent as Like for compatibility with Pleroma
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub(crate) content: Option<String>,
   #[serde(flatten)]
   pub(crate) unparsed: Unparsed,
 }
@@ -1,8 +1,12 @@
 use crate::protocol::activities::community::announce::AnnounceActivity;
 use activitystreams_kinds::collection::OrderedCollectionType;
 use serde::{Deserialize, Serialize};
+use serde_with::skip_serializing_none;
 use url::Url;
 
+/// Root of the community outbox. It includes the newest posts directly, older ones can be fetched
+/// page by page starting from `first`.
+#[skip_serializing_none]
 #[derive(Clone, Debug, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct GroupOutbox {
@@ -10,4 +14,6 @@ pub struct GroupOutbox {
   pub(crate) id: Url,
   pub(crate) total_items: i32,
   pub(crate) ordered_items: Vec<AnnounceActivity>,
+  pub(crate) first: Option<Url>,
+  pub(crate) last: Option<Url>,
 }
@@ -0,0 +1,28 @@
+use crate::protocol::activities::{
+  community::{block_user::BlockUserFromCommunity, undo_block_user::UndoBlockUserFromCommunity},
+  deletion::{remove::Remove, undo_remove::UndoRemove},
+};
+use activitystreams_kinds::collection::OrderedCollectionPageType;
+use serde::{Deserialize, Serialize};
+use serde_with::skip_serializing_none;
+use url::Url;
+
+/// Public feed of the removals and bans done by mods of local communities, newest first.
+#[skip_serializing_none]
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InstanceModlog {
+  pub(crate) r#type: OrderedCollectionPageType,
+  pub(crate) id: Url,
+  pub(crate) ordered_items: Vec<ModlogActivity>,
+  pub(crate) next: Option<Url>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(untagged)]
+pub enum ModlogActivity {
+  UndoRemove(UndoRemove),
+  Remove(Remove),
+  UndoBlockUserFromCommunity(UndoBlockUserFromCommunity),
+  BlockUserFromCommunity(BlockUserFromCommunity),
+}
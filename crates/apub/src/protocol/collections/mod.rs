@@ -1,15 +1,20 @@
 pub(crate) mod group_followers;
 pub(crate) mod group_moderators;
 pub(crate) mod group_outbox;
+pub(crate) mod instance_modlog;
+pub(crate) mod outbox_page;
 pub(crate) mod person_outbox;
 
 #[cfg(test)]
 mod tests {
   use crate::protocol::{
+    activities::create_or_update::post::CreateOrUpdatePost,
     collections::{
       group_followers::GroupFollowers,
       group_moderators::GroupModerators,
       group_outbox::GroupOutbox,
+      instance_modlog::{InstanceModlog, ModlogActivity},
+      outbox_page::OutboxPage,
       person_outbox::PersonOutbox,
     },
     tests::test_parse_lemmy_item,
@@ -22,5 +27,21 @@ mod tests {
     assert_eq!(outbox.ordered_items.len() as i32, outbox.total_items);
     test_parse_lemmy_item::<GroupModerators>("assets/lemmy/collections/group_moderators.json");
     test_parse_lemmy_item::<PersonOutbox>("assets/lemmy/collections/person_outbox.json");
+    let outbox_page = test_parse_lemmy_item::<OutboxPage<CreateOrUpdatePost>>(
+      "assets/lemmy/collections/person_outbox_page.json",
+    );
+    assert_eq!(outbox_page.ordered_items.len(), 1);
+    assert!(outbox_page.next.is_none());
+    let modlog =
+      test_parse_lemmy_item::<InstanceModlog>("assets/lemmy/collections/instance_modlog.json");
+    assert!(matches!(modlog.ordered_items[0], ModlogActivity::Remove(_)));
+    assert!(matches!(
+      modlog.ordered_items[1],
+      ModlogActivity::UndoRemove(_)
+    ));
+    assert!(matches!(
+      modlog.ordered_items[2],
+      ModlogActivity::BlockUserFromCommunity(_)
+    ));
   }
 }
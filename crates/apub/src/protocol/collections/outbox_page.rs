@@ -0,0 +1,46 @@
+use crate::collections::{outbox_last_page, outbox_page_url};
+use activitystreams_kinds::collection::OrderedCollectionPageType;
+use lemmy_utils::LemmyError;
+use serde::{Deserialize, Serialize};
+use serde_with::skip_serializing_none;
+use url::Url;
+
+/// A single page of a person or community outbox, newest activities first.
+#[skip_serializing_none]
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OutboxPage<T> {
+  pub(crate) r#type: OrderedCollectionPageType,
+  pub(crate) id: Url,
+  pub(crate) part_of: Url,
+  pub(crate) ordered_items: Vec<T>,
+  pub(crate) next: Option<Url>,
+  pub(crate) prev: Option<Url>,
+}
+
+impl<T> OutboxPage<T> {
+  pub(crate) fn new(
+    outbox_id: &Url,
+    page: i64,
+    total_items: i64,
+    ordered_items: Vec<T>,
+  ) -> Result<OutboxPage<T>, LemmyError> {
+    let last_page = outbox_last_page(total_items);
+    Ok(OutboxPage {
+      r#type: OrderedCollectionPageType::OrderedCollectionPage,
+      id: outbox_page_url(outbox_id, page)?,
+      part_of: outbox_id.clone(),
+      ordered_items,
+      next: if page < last_page {
+        Some(outbox_page_url(outbox_id, page + 1)?)
+      } else {
+        None
+      },
+      prev: if page > 1 {
+        Some(outbox_page_url(outbox_id, (page - 1).min(last_page))?)
+      } else {
+        None
+      },
+    })
+  }
+}
@@ -1,26 +1,90 @@
-use crate::generate_outbox_url;
+use crate::{
+  collections::{outbox_last_page, outbox_page_url, OUTBOX_PAGE_SIZE},
+  generate_outbox_url,
+  objects::{community::ApubCommunity, person::ApubPerson, post::ApubPost},
+  protocol::{
+    activities::{create_or_update::post::CreateOrUpdatePost, CreateOrUpdateType},
+    collections::outbox_page::OutboxPage,
+  },
+};
 use activitystreams_kinds::collection::OrderedCollectionType;
-use lemmy_db_schema::source::person::Person;
+use lemmy_api_common::blocking;
+use lemmy_db_schema::{
+  source::{community::Community, person::Person, post::Post},
+  traits::Crud,
+};
 use lemmy_utils::LemmyError;
+use lemmy_websocket::LemmyContext;
 use serde::{Deserialize, Serialize};
+use serde_with::skip_serializing_none;
 use url::Url;
 
+/// Root of the person outbox, the posts of the person can be fetched page by page starting from
+/// `first`.
+#[skip_serializing_none]
 #[derive(Clone, Debug, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub(crate) struct PersonOutbox {
   r#type: OrderedCollectionType,
   id: Url,
-  ordered_items: Vec<()>,
-  total_items: i32,
+  total_items: i64,
+  first: Option<Url>,
+  last: Option<Url>,
 }
 
 impl PersonOutbox {
-  pub(crate) async fn new(user: Person) -> Result<PersonOutbox, LemmyError> {
+  pub(crate) async fn new(
+    user: Person,
+    context: &LemmyContext,
+  ) -> Result<PersonOutbox, LemmyError> {
+    let id: Url = generate_outbox_url(&user.actor_id)?.into();
+    let person_id = user.id;
+    let total_items = blocking(context.pool(), move |conn| {
+      Post::count_for_creator(conn, person_id)
+    })
+    .await??;
     Ok(PersonOutbox {
       r#type: OrderedCollectionType::OrderedCollection,
-      id: generate_outbox_url(&user.actor_id)?.into(),
-      ordered_items: vec![],
-      total_items: 0,
+      first: Some(outbox_page_url(&id, 1)?),
+      last: Some(outbox_page_url(&id, outbox_last_page(total_items))?),
+      id,
+      total_items,
     })
   }
+
+  /// Returns a single page of the outbox, consisting of the Create activities for the posts of the
+  /// person.
+  pub(crate) async fn read_page(
+    user: Person,
+    page: i64,
+    context: &LemmyContext,
+  ) -> Result<OutboxPage<CreateOrUpdatePost>, LemmyError> {
+    let person_id = user.id;
+    let (posts, total_items) = blocking(context.pool(), move |conn| {
+      let posts = Post::list_for_creator(conn, person_id, Some(page), Some(OUTBOX_PAGE_SIZE))?
+        .into_iter()
+        .map(|p| Ok((Community::read(conn, p.community_id)?, p)))
+        .collect::<Result<Vec<_>, LemmyError>>()?;
+      Ok::<_, LemmyError>((posts, Post::count_for_creator(conn, person_id)?))
+    })
+    .await??;
+
+    let outbox_id: Url = generate_outbox_url(&user.actor_id)?.into();
+    let actor: ApubPerson = user.into();
+    let mut ordered_items = vec![];
+    for (community, post) in posts {
+      let community: ApubCommunity = community.into();
+      let post: ApubPost = post.into();
+      let create = CreateOrUpdatePost::new(
+        post,
+        &actor,
+        &community,
+        CreateOrUpdateType::Create,
+        context,
+      )
+      .await?;
+      ordered_items.push(create);
+    }
+    OutboxPage::new(&outbox_id, page, total_items, ordered_items)
+  }
 }
@@ -15,6 +15,9 @@ pub(crate) mod objects;
 pub struct Source {
   pub(crate) content: String,
   pub(crate) media_type: MediaTypeMarkdown,
+  /// Number of earlier versions, only set for comments which were edited
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub(crate) revision: Option<i64>,
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
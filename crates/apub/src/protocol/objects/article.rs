@@ -0,0 +1,32 @@
+use crate::{
+  objects::{community::ApubCommunity, person::ApubPerson, wiki_page::ApubWikiPage},
+  protocol::{Source, Unparsed},
+};
+use activitystreams_kinds::object::ArticleType;
+use chrono::{DateTime, FixedOffset};
+use lemmy_apub_lib::{object_id::ObjectId, values::MediaTypeHtml};
+use serde::{Deserialize, Serialize};
+use serde_with::skip_serializing_none;
+use url::Url;
+
+/// A wiki page of a community
+#[skip_serializing_none]
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Article {
+  pub(crate) r#type: ArticleType,
+  pub(crate) id: ObjectId<ApubWikiPage>,
+  pub(crate) attributed_to: ObjectId<ApubPerson>,
+  #[serde(deserialize_with = "crate::deserialize_one_or_many")]
+  pub(crate) to: Vec<Url>,
+  /// The community which the page belongs to
+  pub(crate) audience: ObjectId<ApubCommunity>,
+  pub(crate) name: String,
+  pub(crate) content: String,
+  pub(crate) media_type: Option<MediaTypeHtml>,
+  pub(crate) source: Option<Source>,
+  pub(crate) published: Option<DateTime<FixedOffset>>,
+  pub(crate) updated: Option<DateTime<FixedOffset>>,
+  #[serde(flatten)]
+  pub(crate) unparsed: Unparsed,
+}
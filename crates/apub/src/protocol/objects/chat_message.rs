@@ -1,11 +1,14 @@
 use crate::{
+  mentions::Mention,
   objects::{person::ApubPerson, private_message::ApubPrivateMessage},
   protocol::{Source, Unparsed},
 };
+use activitystreams_kinds::public;
 use chrono::{DateTime, FixedOffset};
 use lemmy_apub_lib::{object_id::ObjectId, values::MediaTypeHtml};
-use serde::{Deserialize, Serialize};
+use serde::{de::Error, Deserialize, Deserializer, Serialize};
 use serde_with::skip_serializing_none;
+use url::Url;
 
 #[skip_serializing_none]
 #[derive(Clone, Debug, Deserialize, Serialize)]
@@ -14,18 +17,57 @@ pub struct ChatMessage {
   pub(crate) r#type: ChatMessageType,
   pub(crate) id: ObjectId<ApubPrivateMessage>,
   pub(crate) attributed_to: ObjectId<ApubPerson>,
+  #[serde(deserialize_with = "deserialize_direct_recipient")]
   pub(crate) to: [ObjectId<ApubPerson>; 1],
+  /// Always empty for direct messages, otherwise the object is a post or comment with restricted
+  /// visibility
+  #[serde(default, deserialize_with = "deserialize_empty_cc")]
+  #[serde(skip_serializing_if = "Vec::is_empty")]
+  pub(crate) cc: Vec<Url>,
   pub(crate) content: String,
   pub(crate) media_type: Option<MediaTypeHtml>,
   pub(crate) source: Option<Source>,
   pub(crate) published: Option<DateTime<FixedOffset>>,
   pub(crate) updated: Option<DateTime<FixedOffset>>,
+  /// Mastodon only shows a Note as direct message if the recipient is mentioned
+  #[serde(default, deserialize_with = "crate::deserialize_skip_error")]
+  #[serde(skip_serializing_if = "Vec::is_empty")]
+  pub(crate) tag: Vec<Mention>,
   #[serde(flatten)]
   pub(crate) unparsed: Unparsed,
 }
 
 /// https://docs.pleroma.social/backend/development/ap_extensions/#chatmessages
+///
+/// Mastodon and others send direct messages as Note which is only addressed to the recipient.
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub enum ChatMessageType {
   ChatMessage,
+  Note,
+}
+
+/// Accepts a single recipient, either as plain value or as list. Fails for public notes, so that
+/// comments never end up as private messages.
+fn deserialize_direct_recipient<'de, D>(
+  deserializer: D,
+) -> Result<[ObjectId<ApubPerson>; 1], D::Error>
+where
+  D: Deserializer<'de>,
+{
+  let to: Vec<Url> = crate::deserialize_one_or_many(deserializer)?;
+  match to.as_slice() {
+    [recipient] if recipient != &public() => Ok([ObjectId::new(recipient.clone())]),
+    _ => Err(D::Error::custom("not a direct message")),
+  }
+}
+
+fn deserialize_empty_cc<'de, D>(deserializer: D) -> Result<Vec<Url>, D::Error>
+where
+  D: Deserializer<'de>,
+{
+  let cc: Vec<Url> = crate::deserialize_one_or_many(deserializer)?;
+  if !cc.is_empty() {
+    return Err(D::Error::custom("not a direct message"));
+  }
+  Ok(cc)
 }
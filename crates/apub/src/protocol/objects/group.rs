@@ -88,6 +88,9 @@ impl Group {
       followers_url: Some(self.followers.into()),
       inbox_url: Some(self.inbox.into()),
       shared_inbox_url: Some(self.endpoints.shared_inbox.map(|s| s.into())),
+      require_post_approval: None,
+      // Categories are assigned separately on each instance
+      category_id: None,
     }
   }
 }
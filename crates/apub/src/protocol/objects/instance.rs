@@ -0,0 +1,32 @@
+use crate::protocol::{objects::Endpoints, ImageObject, Source, Unparsed};
+use activitystreams_kinds::actor::ApplicationType;
+use chrono::{DateTime, FixedOffset};
+use lemmy_apub_lib::signatures::PublicKey;
+use serde::{Deserialize, Serialize};
+use serde_with::skip_serializing_none;
+use url::Url;
+
+/// The actor of an instance, which is served at its root url
+#[skip_serializing_none]
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Instance {
+  pub(crate) r#type: ApplicationType,
+  pub(crate) id: Url,
+  pub(crate) preferred_username: String,
+  /// site name
+  pub(crate) name: Option<String>,
+  /// site sidebar
+  pub(crate) summary: Option<String>,
+  pub(crate) source: Option<Source>,
+  pub(crate) icon: Option<ImageObject>,
+  /// site banner
+  pub(crate) image: Option<ImageObject>,
+  pub(crate) inbox: Url,
+  pub(crate) endpoints: Option<Endpoints>,
+  pub(crate) public_key: PublicKey,
+  pub(crate) published: Option<DateTime<FixedOffset>>,
+  pub(crate) updated: Option<DateTime<FixedOffset>>,
+  #[serde(flatten)]
+  pub(crate) unparsed: Unparsed,
+}
@@ -1,8 +1,10 @@
 use serde::{Deserialize, Serialize};
 use url::Url;
 
+pub(crate) mod article;
 pub(crate) mod chat_message;
 pub(crate) mod group;
+pub(crate) mod instance;
 pub(crate) mod note;
 pub(crate) mod page;
 pub(crate) mod person;
@@ -21,7 +23,15 @@ mod tests {
     context::WithContext,
     objects::tests::file_to_json_object,
     protocol::{
-      objects::{chat_message::ChatMessage, group::Group, note::Note, page::Page, person::Person},
+      objects::{
+        article::Article,
+        chat_message::ChatMessage,
+        group::Group,
+        instance::Instance,
+        note::Note,
+        page::Page,
+        person::Person,
+      },
       tests::test_parse_lemmy_item,
     },
   };
@@ -30,9 +40,12 @@ mod tests {
   async fn test_parse_object() {
     test_parse_lemmy_item::<Person>("assets/lemmy/objects/person.json");
     test_parse_lemmy_item::<Group>("assets/lemmy/objects/group.json");
+    test_parse_lemmy_item::<Instance>("assets/lemmy/objects/instance.json");
     test_parse_lemmy_item::<Page>("assets/lemmy/objects/page.json");
+    test_parse_lemmy_item::<Page>("assets/lemmy/objects/question.json");
     test_parse_lemmy_item::<Note>("assets/lemmy/objects/note.json");
     test_parse_lemmy_item::<ChatMessage>("assets/lemmy/objects/chat_message.json");
+    test_parse_lemmy_item::<Article>("assets/lemmy/objects/article.json");
 
     file_to_json_object::<WithContext<Person>>("assets/pleroma/objects/person.json");
     file_to_json_object::<WithContext<Note>>("assets/pleroma/objects/note.json");
@@ -43,5 +56,6 @@ mod tests {
 
     file_to_json_object::<Person>("assets/mastodon/objects/person.json");
     file_to_json_object::<Note>("assets/mastodon/objects/note.json");
+    file_to_json_object::<ChatMessage>("assets/mastodon/objects/private_message.json");
   }
 }
@@ -1,4 +1,5 @@
 use crate::{
+  emojis::Emoji,
   fetcher::post_or_comment::PostOrComment,
   mentions::Mention,
   objects::{comment::ApubComment, person::ApubPerson, post::ApubPost},
@@ -13,7 +14,7 @@ use lemmy_utils::LemmyError;
 use lemmy_websocket::LemmyContext;
 use serde::{Deserialize, Serialize};
 use serde_with::skip_serializing_none;
-use std::ops::Deref;
+use std::{collections::BTreeMap, ops::Deref};
 use url::Url;
 
 #[skip_serializing_none]
@@ -29,6 +30,8 @@ pub struct Note {
   #[serde(deserialize_with = "crate::deserialize_one_or_many")]
   pub(crate) cc: Vec<Url>,
   pub(crate) content: String,
+  /// Same as `content`, keyed by the language code
+  pub(crate) content_map: Option<BTreeMap<String, String>>,
   pub(crate) media_type: Option<MediaTypeHtml>,
   #[serde(default)]
   pub(crate) source: SourceCompat,
@@ -36,11 +39,19 @@ pub struct Note {
   pub(crate) published: Option<DateTime<FixedOffset>>,
   pub(crate) updated: Option<DateTime<FixedOffset>>,
   #[serde(default)]
-  pub(crate) tag: Vec<Mention>,
+  pub(crate) tag: Vec<NoteTag>,
   #[serde(flatten)]
   pub(crate) unparsed: Unparsed,
 }
 
+/// Persons which are mentioned in the comment, and custom emoji which are used in it
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(untagged)]
+pub enum NoteTag {
+  Mention(Mention),
+  Emoji(Emoji),
+}
+
 /// Pleroma puts a raw string in the source, so we have to handle it here for deserialization to work
 #[derive(Clone, Debug, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
@@ -58,6 +69,13 @@ impl Default for SourceCompat {
 }
 
 impl Note {
+  pub(crate) fn emojis(&self) -> impl Iterator<Item = &Emoji> {
+    self.tag.iter().filter_map(|t| match t {
+      NoteTag::Emoji(e) => Some(e),
+      NoteTag::Mention(_) => None,
+    })
+  }
+
   pub(crate) async fn get_parents(
     &self,
     context: &LemmyContext,
@@ -1,8 +1,9 @@
 use crate::{
+  emojis::Emoji,
   objects::{community::ApubCommunity, person::ApubPerson, post::ApubPost},
   protocol::{ImageObject, Source, Unparsed},
 };
-use activitystreams_kinds::object::PageType;
+use activitystreams_kinds::{collection::CollectionType, object::NoteType};
 use chrono::{DateTime, FixedOffset};
 use lemmy_apub_lib::{
   data::Data,
@@ -14,6 +15,7 @@ use lemmy_utils::LemmyError;
 use lemmy_websocket::LemmyContext;
 use serde::{Deserialize, Serialize};
 use serde_with::skip_serializing_none;
+use std::collections::BTreeMap;
 use url::Url;
 
 #[skip_serializing_none]
@@ -29,7 +31,10 @@ pub struct Page {
   #[serde(deserialize_with = "crate::deserialize_one_or_many")]
   pub(crate) cc: Vec<Url>,
   pub(crate) name: String,
+  /// Same as `name` and `content`, keyed by the language code
+  pub(crate) name_map: Option<BTreeMap<String, String>>,
   pub(crate) content: Option<String>,
+  pub(crate) content_map: Option<BTreeMap<String, String>>,
   pub(crate) media_type: Option<MediaTypeHtml>,
   pub(crate) source: Option<Source>,
   pub(crate) url: Option<Url>,
@@ -39,11 +44,104 @@ pub struct Page {
   pub(crate) stickied: Option<bool>,
   pub(crate) published: Option<DateTime<FixedOffset>>,
   pub(crate) updated: Option<DateTime<FixedOffset>>,
+  /// Poll options, if the post is a single choice poll
+  pub(crate) one_of: Option<Vec<QuestionOption>>,
+  /// Poll options, if the post is a multiple choice poll
+  pub(crate) any_of: Option<Vec<QuestionOption>>,
+  pub(crate) end_time: Option<DateTime<FixedOffset>>,
+  /// Tags which the community defined for its posts, and custom emoji which are used in the body
+  #[serde(default)]
+  pub(crate) tag: Vec<PageTag>,
+  /// Further media or links, Lemmy itself only uses `url`
+  #[serde(default, skip_serializing_if = "Vec::is_empty")]
+  pub(crate) attachment: Vec<Attachment>,
   #[serde(flatten)]
   pub(crate) unparsed: Unparsed,
 }
 
+/// Posts with a poll are sent as `Question`, the same as Mastodon polls
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub enum PageType {
+  Page,
+  Question,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QuestionOption {
+  pub(crate) r#type: NoteType,
+  pub(crate) name: String,
+  pub(crate) replies: QuestionOptionReplies,
+}
+
+/// Only the number of votes is federated, not the voters
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QuestionOptionReplies {
+  pub(crate) r#type: CollectionType,
+  pub(crate) total_items: i32,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub enum HashtagType {
+  Hashtag,
+}
+
+/// Tags are only identified by their name within the community
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PostTagObject {
+  pub(crate) r#type: HashtagType,
+  pub(crate) name: String,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(untagged)]
+pub enum PageTag {
+  Hashtag(PostTagObject),
+  Emoji(Emoji),
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub enum AttachmentType {
+  Link,
+  Document,
+  Image,
+  Video,
+  Audio,
+}
+
+/// Links use `href` instead of `url`
+#[skip_serializing_none]
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Attachment {
+  pub(crate) r#type: AttachmentType,
+  #[serde(alias = "href")]
+  pub(crate) url: Url,
+  pub(crate) media_type: Option<String>,
+  pub(crate) name: Option<String>,
+}
+
 impl Page {
+  pub(crate) fn poll_options(&self) -> impl Iterator<Item = &QuestionOption> {
+    self.one_of.iter().chain(self.any_of.iter()).flatten()
+  }
+
+  pub(crate) fn hashtags(&self) -> impl Iterator<Item = &PostTagObject> {
+    self.tag.iter().filter_map(|t| match t {
+      PageTag::Hashtag(h) => Some(h),
+      PageTag::Emoji(_) => None,
+    })
+  }
+
+  pub(crate) fn emojis(&self) -> impl Iterator<Item = &Emoji> {
+    self.tag.iter().filter_map(|t| match t {
+      PageTag::Emoji(e) => Some(e),
+      PageTag::Hashtag(_) => None,
+    })
+  }
+
   /// Only mods can change the post's stickied/locked status. So if either of these is changed from
   /// the current value, it is a mod action and needs to be verified as such.
   ///
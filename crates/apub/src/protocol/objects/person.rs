@@ -39,6 +39,27 @@ pub struct Person {
   pub(crate) public_key: PublicKey,
   pub(crate) published: Option<DateTime<FixedOffset>>,
   pub(crate) updated: Option<DateTime<FixedOffset>>,
+  /// Profile fields
+  #[serde(
+    default,
+    deserialize_with = "crate::deserialize_skip_error",
+    skip_serializing_if = "Vec::is_empty"
+  )]
+  pub(crate) attachment: Vec<PropertyValue>,
   #[serde(flatten)]
   pub(crate) unparsed: Unparsed,
 }
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub enum PropertyValueType {
+  PropertyValue,
+}
+
+/// A profile field in the format of Mastodon, the value is html
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct PropertyValue {
+  #[serde(rename = "type")]
+  pub(crate) kind: PropertyValueType,
+  pub(crate) name: String,
+  pub(crate) value: String,
+}
@@ -10,6 +10,24 @@ pub struct Tombstone {
   pub(crate) id: Url,
   #[serde(rename = "type")]
   kind: TombstoneType,
+  /// Type of the deleted object. Not sent by older Lemmy versions.
+  pub(crate) former_type: Option<FormerType>,
+}
+
+/// Object types which Lemmy uses for deletable objects
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq)]
+pub enum FormerType {
+  /// A community
+  Group,
+  /// A post
+  Page,
+  /// A comment
+  Note,
+  /// A wiki page
+  Article,
+  /// Any type which Lemmy doesn't use, treated the same as no type
+  #[serde(other)]
+  Other,
 }
 
 impl Tombstone {
@@ -17,6 +35,14 @@ impl Tombstone {
     Tombstone {
       id,
       kind: TombstoneType::Tombstone,
+      former_type: None,
+    }
+  }
+
+  pub fn with_former_type(id: Url, former_type: FormerType) -> Tombstone {
+    Tombstone {
+      former_type: Some(former_type),
+      ..Tombstone::new(id)
     }
   }
 }
@@ -9,11 +9,11 @@ use background_jobs::{
   QueueHandle,
   WorkerConfig,
 };
-use lemmy_utils::{location_info, LemmyError};
+use lemmy_utils::{location_info, request_id::current_request_id, LemmyError};
 use reqwest_middleware::ClientWithMiddleware;
 use serde::{Deserialize, Serialize};
 use std::{env, fmt::Debug, future::Future, pin::Pin};
-use tracing::{info, warn};
+use tracing::{field, info, warn, Span};
 use url::Url;
 
 pub async fn send_activity(
@@ -31,6 +31,7 @@ pub async fn send_activity(
       actor_id: actor.actor_id(),
       activity: activity.clone(),
       private_key: actor.private_key().context(location_info!())?,
+      request_id: current_request_id(),
     };
     if env::var("APUB_TESTING_SEND_SYNC").is_ok() {
       do_send(message, client).await?;
@@ -49,6 +50,7 @@ struct SendActivityTask {
   actor_id: Url,
   activity: String,
   private_key: String,
+  request_id: Option<String>,
 }
 
 /// Signs the activity with the sending actor's key, and delivers to the given inbox. Also retries
@@ -67,24 +69,56 @@ impl ActixJob for SendActivityTask {
 }
 
 async fn do_send(task: SendActivityTask, client: &ClientWithMiddleware) -> Result<(), Error> {
-  info!("Sending {} to {}", task.activity_id, task.inbox);
-  let result = sign_and_send(
-    client,
+  deliver_activity(
+    &task.activity_id,
     &task.inbox,
-    task.activity.clone(),
     &task.actor_id,
-    task.private_key.to_owned(),
+    task.activity,
+    task.private_key,
+    task.request_id.as_deref(),
+    client,
   )
-  .await;
+  .await
+}
+
+/// Signs the activity and delivers it to a single inbox, without retrying. Returns an error if the
+/// receiving instance couldn't be reached or had a server error, so that the caller can try again
+/// later. Other error responses are only logged, as a retry wouldn't change them.
+///
+/// `request_id` is the id of the request which created the activity, so that deliveries can be
+/// traced back to it.
+#[tracing::instrument(skip_all, fields(%activity_id, %inbox, request_id = field::Empty))]
+pub async fn deliver_activity(
+  activity_id: &Url,
+  inbox: &Url,
+  actor_id: &Url,
+  activity: String,
+  private_key: String,
+  request_id: Option<&str>,
+  client: &ClientWithMiddleware,
+) -> Result<(), Error> {
+  if let Some(request_id) = request_id {
+    Span::current().record("request_id", &request_id);
+  }
+  info!("Sending {} to {}", activity_id, inbox);
+  let result = sign_and_send(client, inbox, activity, actor_id, private_key).await;
 
   match result {
     Ok(o) => {
-      if !o.status().is_success() {
+      let status = o.status();
+      if status.is_server_error() {
+        return Err(anyhow!(
+          "Failed to send activity {} to {}: status {}",
+          activity_id,
+          inbox,
+          status
+        ));
+      } else if !status.is_success() {
         warn!(
           "Send {} to {} failed with status {}: {}",
-          task.activity_id,
-          task.inbox,
-          o.status(),
+          activity_id,
+          inbox,
+          status,
           o.text().await?
         );
       }
@@ -92,8 +126,8 @@ async fn do_send(task: SendActivityTask, client: &ClientWithMiddleware) -> Resul
     Err(e) => {
       return Err(anyhow!(
         "Failed to send activity {} to {}: {}",
-        &task.activity_id,
-        task.inbox,
+        activity_id,
+        inbox,
         e
       ));
     }
@@ -1,4 +1,4 @@
-use crate::{traits::ApubObject, APUB_JSON_CONTENT_TYPE};
+use crate::{signatures::build_fetch_request, traits::ApubObject};
 use activitystreams::chrono::{Duration as ChronoDuration, NaiveDateTime, Utc};
 use anyhow::anyhow;
 use diesel::NotFound;
@@ -9,7 +9,6 @@ use serde::{Deserialize, Serialize};
 use std::{
   fmt::{Debug, Display, Formatter},
   marker::PhantomData,
-  time::Duration,
 };
 use tracing::info;
 use url::Url;
@@ -75,6 +74,23 @@ where
     }
   }
 
+  /// Like [[dereference]], but remote objects are always fetched over http, even if the copy in
+  /// the database isn't outdated yet.
+  pub async fn dereference_forced(
+    &self,
+    data: &<Kind as ApubObject>::DataType,
+    client: &ClientWithMiddleware,
+    request_counter: &mut i32,
+  ) -> Result<Kind, LemmyError> {
+    let db_object = self.dereference_from_db(data).await?;
+    if self.0.domain() == Some(&Settings::get().get_hostname_without_port()?) {
+      return db_object.ok_or_else(|| NotFound {}.into());
+    }
+    self
+      .dereference_from_http(data, client, request_counter, db_object)
+      .await
+  }
+
   /// Fetch an object from the local db. Instead of falling back to http, this throws an error if
   /// the object is not found in the database.
   pub async fn dereference_local(
@@ -110,12 +126,10 @@ where
       return Err(LemmyError::from(anyhow!("Request retry limit reached")));
     }
 
-    let res = retry(|| {
-      client
-        .get(self.0.as_str())
-        .header("Accept", APUB_JSON_CONTENT_TYPE)
-        .timeout(Duration::from_secs(60))
-        .send()
+    let res = retry(|| async {
+      let request = build_fetch_request(client, self.inner())
+        .map_err(|e| reqwest_middleware::Error::Middleware(anyhow!(e.to_string())))?;
+      client.execute(request).await
     })
     .await?;
 
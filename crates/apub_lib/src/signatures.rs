@@ -1,26 +1,62 @@
 use crate::APUB_JSON_CONTENT_TYPE;
+use activitystreams::chrono::Utc;
 use actix_web::HttpRequest;
 use anyhow::anyhow;
 use http::{header::HeaderName, HeaderMap, HeaderValue};
 use http_signature_normalization_actix::Config as ConfigActix;
-use http_signature_normalization_reqwest::prelude::{Config, SignExt};
+use http_signature_normalization_reqwest::prelude::{Config, Sign, SignExt};
 use lemmy_utils::LemmyError;
-use once_cell::sync::Lazy;
+use once_cell::sync::{Lazy, OnceCell};
 use openssl::{
   hash::MessageDigest,
   pkey::PKey,
   sign::{Signer, Verifier},
 };
-use reqwest::Response;
+use reqwest::{Request, Response};
 use reqwest_middleware::ClientWithMiddleware;
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
-use std::str::FromStr;
+use std::{str::FromStr, time::Duration};
 use tracing::debug;
 use url::Url;
 
 static CONFIG2: Lazy<ConfigActix> = Lazy::new(ConfigActix::new);
 static HTTP_SIG_CONFIG: Lazy<Config> = Lazy::new(Config::new);
+/// Mastodon doesn't understand the `(created)` field, so fetches are signed over the Date header
+static FETCH_SIG_CONFIG: Lazy<Config> = Lazy::new(|| Config::new().mastodon_compat());
+
+/// Key id and private key which outgoing fetches are signed with, only set in signed fetch mode
+static FETCH_SIGNING_KEY: OnceCell<(String, String)> = OnceCell::new();
+
+/// Signs all following object fetches with the key of the given actor. This is needed to fetch
+/// from instances which only serve signed requests, like Mastodon in secure mode.
+pub fn enable_signed_fetch(actor_id: &Url, private_key: String) {
+  let key_id = format!("{}#main-key", actor_id);
+  FETCH_SIGNING_KEY.set((key_id, private_key)).ok();
+}
+
+/// Builds a GET request for the activitypub object at `url`, which is signed if signed fetch is
+/// enabled.
+pub fn build_fetch_request(
+  client: &ClientWithMiddleware,
+  url: &Url,
+) -> Result<Request, LemmyError> {
+  let request = client
+    .get(url.as_str())
+    .header("Accept", APUB_JSON_CONTENT_TYPE)
+    .timeout(Duration::from_secs(60));
+  match FETCH_SIGNING_KEY.get() {
+    Some((key_id, private_key)) => request
+      .header(
+        "Date",
+        Utc::now().format("%a, %d %b %Y %H:%M:%S GMT").to_string(),
+      )
+      .signature(&FETCH_SIG_CONFIG, key_id, |signing_string| {
+        sign_string(private_key, signing_string)
+      }),
+    None => Ok(request.build()?),
+  }
+}
 
 /// Creates an HTTP post request to `inbox_url`, with the given `client` and `headers`, and
 /// `activity` as request body. The request is signed with `private_key` and then sent.
@@ -52,13 +88,7 @@ pub async fn sign_and_send(
       signing_key_id,
       Sha256::new(),
       activity,
-      move |signing_string| {
-        let private_key = PKey::private_key_from_pem(private_key.as_bytes())?;
-        let mut signer = Signer::new(MessageDigest::sha256(), &private_key)?;
-        signer.update(signing_string.as_bytes())?;
-
-        Ok(base64::encode(signer.sign_to_vec()?)) as Result<_, LemmyError>
-      },
+      move |signing_string| sign_string(&private_key, signing_string),
     )
     .await?;
 
@@ -67,6 +97,23 @@ pub async fn sign_and_send(
   Ok(response)
 }
 
+fn sign_string(private_key: &str, signing_string: &str) -> Result<String, LemmyError> {
+  let private_key = PKey::private_key_from_pem(private_key.as_bytes())?;
+  let mut signer = Signer::new(MessageDigest::sha256(), &private_key)?;
+  signer.update(signing_string.as_bytes())?;
+  Ok(base64::encode(signer.sign_to_vec()?))
+}
+
+/// Returns the key id from the HTTP signature of the request, without verifying it.
+pub fn signature_key_id(request: &HttpRequest) -> Result<Url, LemmyError> {
+  let unverified = CONFIG2.begin_verify(
+    request.method(),
+    request.uri().path_and_query(),
+    request.headers().clone(),
+  )?;
+  Ok(Url::parse(unverified.key_id())?)
+}
+
 /// Verifies the HTTP signature on an incoming inbox request.
 pub fn verify_signature(request: &HttpRequest, public_key: &str) -> Result<(), LemmyError> {
   let verified = CONFIG2
@@ -1,4 +1,4 @@
-use crate::{newtypes::CommentId, schema::comment_aggregates};
+use crate::{functions, newtypes::CommentId, schema::comment_aggregates};
 use diesel::{result::Error, *};
 use serde::{Deserialize, Serialize};
 
@@ -13,6 +13,10 @@ pub struct CommentAggregates {
   pub upvotes: i64,
   pub downvotes: i64,
   pub published: chrono::NaiveDateTime,
+  /// Number of direct replies
+  pub child_count: i32,
+  /// Refreshed periodically by `update_hot_ranks`
+  pub hot_rank: i32,
 }
 
 impl CommentAggregates {
@@ -21,6 +25,25 @@ impl CommentAggregates {
       .filter(comment_aggregates::comment_id.eq(comment_id))
       .first::<Self>(conn)
   }
+
+  /// Recalculates the stored hot ranks of comments which were published since the given time.
+  /// Older comments which still have a rank are set to 0.
+  pub fn update_hot_ranks(conn: &PgConnection, since: chrono::NaiveDateTime) -> Result<(), Error> {
+    use crate::schema::comment_aggregates::dsl::*;
+    conn.transaction(|| {
+      diesel::update(comment_aggregates.filter(published.gt(since)))
+        .set(hot_rank.eq(functions::hot_rank(score, published)))
+        .execute(conn)?;
+      diesel::update(
+        comment_aggregates
+          .filter(hot_rank.gt(0))
+          .filter(published.le(since)),
+      )
+      .set(hot_rank.eq(0))
+      .execute(conn)?;
+      Ok(())
+    })
+  }
 }
 
 #[cfg(test)]
@@ -91,7 +114,7 @@ mod tests {
       ..CommentForm::default()
     };
 
-    let _inserted_child_comment = Comment::create(&conn, &child_comment_form).unwrap();
+    let inserted_child_comment = Comment::create(&conn, &child_comment_form).unwrap();
 
     let comment_like = CommentLikeForm {
       comment_id: inserted_comment.id,
@@ -107,6 +130,7 @@ mod tests {
     assert_eq!(1, comment_aggs_before_delete.score);
     assert_eq!(1, comment_aggs_before_delete.upvotes);
     assert_eq!(0, comment_aggs_before_delete.downvotes);
+    assert_eq!(1, comment_aggs_before_delete.child_count);
 
     // Add a post dislike from the other person
     let comment_dislike = CommentLikeForm {
@@ -131,6 +155,11 @@ mod tests {
     assert_eq!(0, after_like_remove.upvotes);
     assert_eq!(1, after_like_remove.downvotes);
 
+    // Deleting the reply updates the child count of its parent
+    Comment::delete(&conn, inserted_child_comment.id).unwrap();
+    let after_reply_delete = CommentAggregates::read(&conn, inserted_comment.id).unwrap();
+    assert_eq!(0, after_reply_delete.child_count);
+
     // Remove the parent post
     Post::delete(&conn, inserted_post.id).unwrap();
 
@@ -17,6 +17,8 @@ pub struct CommunityAggregates {
   pub users_active_week: i64,
   pub users_active_month: i64,
   pub users_active_half_year: i64,
+  /// Updated periodically by `update_trending_scores`
+  pub trending_score: f64,
 }
 
 impl CommunityAggregates {
@@ -25,6 +27,16 @@ impl CommunityAggregates {
       .filter(community_aggregates::community_id.eq(community_id))
       .first::<Self>(conn)
   }
+
+  /// Recalculates the trending score of all communities, from their recent subscriber growth and
+  /// activity
+  pub fn update_trending_scores(conn: &PgConnection) -> Result<usize, Error> {
+    sql_query(
+      "update community_aggregates ca set trending_score = t.score_ \
+       from community_aggregates_trending() t where ca.community_id = t.community_id_",
+    )
+    .execute(conn)
+  }
 }
 
 #[cfg(test)]
@@ -143,6 +155,13 @@ mod tests {
     assert_eq!(0, another_community_aggs.posts);
     assert_eq!(0, another_community_aggs.comments);
 
+    // The community with new posts and comments trends more than the one with only a subscriber
+    CommunityAggregates::update_trending_scores(&conn).unwrap();
+    let trending = CommunityAggregates::read(&conn, inserted_community.id).unwrap();
+    let another_trending = CommunityAggregates::read(&conn, another_inserted_community.id).unwrap();
+    assert!(another_trending.trending_score > 0.0);
+    assert!(trending.trending_score > another_trending.trending_score);
+
     // Unfollow test
     CommunityFollower::unfollow(&conn, &second_person_follow).unwrap();
     let after_unfollow = CommunityAggregates::read(&conn, inserted_community.id).unwrap();
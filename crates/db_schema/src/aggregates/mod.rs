@@ -1,5 +1,8 @@
 pub mod comment_aggregates;
 pub mod community_aggregates;
 pub mod person_aggregates;
+pub mod person_community_karma;
+pub mod person_monthly_activity;
 pub mod post_aggregates;
+pub mod reaction_count;
 pub mod site_aggregates;
@@ -0,0 +1,144 @@
+use crate::{
+  newtypes::{CommunityId, PersonId},
+  schema::person_community_karma,
+};
+use diesel::{result::Error, *};
+use serde::{Deserialize, Serialize};
+
+/// Backed by a database view, which sums up the post and comment aggregates of the person
+#[derive(Queryable, Associations, PartialEq, Debug, Serialize, Deserialize, Clone)]
+#[table_name = "person_community_karma"]
+pub struct PersonCommunityKarma {
+  pub person_id: PersonId,
+  pub community_id: CommunityId,
+  pub post_count: i64,
+  pub post_score: i64,
+  pub comment_count: i64,
+  pub comment_score: i64,
+}
+
+impl PersonCommunityKarma {
+  pub fn read(
+    conn: &PgConnection,
+    person_id: PersonId,
+    community_id: CommunityId,
+  ) -> Result<Self, Error> {
+    person_community_karma::table
+      .filter(person_community_karma::person_id.eq(person_id))
+      .filter(person_community_karma::community_id.eq(community_id))
+      .first::<Self>(conn)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::{
+    aggregates::{
+      person_community_karma::PersonCommunityKarma,
+      person_monthly_activity::PersonMonthlyActivity,
+    },
+    establish_unpooled_connection,
+    source::{
+      comment::{Comment, CommentForm, CommentLike, CommentLikeForm},
+      community::{Community, CommunityForm},
+      person::{Person, PersonForm},
+      post::{Post, PostForm, PostLike, PostLikeForm},
+    },
+    traits::{Crud, Likeable},
+  };
+  use serial_test::serial;
+
+  #[test]
+  #[serial]
+  fn test_crud() {
+    let conn = establish_unpooled_connection();
+
+    let new_person = PersonForm {
+      name: "thommy_karma_agg".into(),
+      ..PersonForm::default()
+    };
+    let inserted_person = Person::create(&conn, &new_person).unwrap();
+
+    let another_person = PersonForm {
+      name: "jerry_karma_agg".into(),
+      ..PersonForm::default()
+    };
+    let another_inserted_person = Person::create(&conn, &another_person).unwrap();
+
+    let new_community = CommunityForm {
+      name: "TIL_karma_agg".into(),
+      title: "nada".to_owned(),
+      ..CommunityForm::default()
+    };
+    let inserted_community = Community::create(&conn, &new_community).unwrap();
+
+    let other_community = CommunityForm {
+      name: "TIL_karma_agg_2".into(),
+      title: "nada".to_owned(),
+      ..CommunityForm::default()
+    };
+    let other_inserted_community = Community::create(&conn, &other_community).unwrap();
+
+    let new_post = PostForm {
+      name: "A test post".into(),
+      creator_id: inserted_person.id,
+      community_id: inserted_community.id,
+      ..PostForm::default()
+    };
+    let inserted_post = Post::create(&conn, &new_post).unwrap();
+
+    let post_like = PostLikeForm {
+      post_id: inserted_post.id,
+      person_id: another_inserted_person.id,
+      score: 1,
+    };
+    PostLike::like(&conn, &post_like).unwrap();
+
+    // A comment by the person in another community
+    let other_post = PostForm {
+      name: "Another test post".into(),
+      creator_id: another_inserted_person.id,
+      community_id: other_inserted_community.id,
+      ..PostForm::default()
+    };
+    let other_inserted_post = Post::create(&conn, &other_post).unwrap();
+
+    let comment_form = CommentForm {
+      content: "A test comment".into(),
+      creator_id: inserted_person.id,
+      post_id: other_inserted_post.id,
+      ..CommentForm::default()
+    };
+    let inserted_comment = Comment::create(&conn, &comment_form).unwrap();
+
+    let comment_like = CommentLikeForm {
+      comment_id: inserted_comment.id,
+      person_id: another_inserted_person.id,
+      post_id: other_inserted_post.id,
+      score: -1,
+    };
+    CommentLike::like(&conn, &comment_like).unwrap();
+
+    let karma =
+      PersonCommunityKarma::read(&conn, inserted_person.id, inserted_community.id).unwrap();
+    let other_karma =
+      PersonCommunityKarma::read(&conn, inserted_person.id, other_inserted_community.id).unwrap();
+    let activity = PersonMonthlyActivity::list_for_person(&conn, inserted_person.id, 12).unwrap();
+
+    Community::delete(&conn, inserted_community.id).unwrap();
+    Community::delete(&conn, other_inserted_community.id).unwrap();
+    Person::delete(&conn, inserted_person.id).unwrap();
+    Person::delete(&conn, another_inserted_person.id).unwrap();
+
+    assert_eq!(1, karma.post_count);
+    assert_eq!(1, karma.post_score);
+    assert_eq!(0, karma.comment_count);
+    assert_eq!(0, karma.comment_score);
+    assert_eq!(0, other_karma.post_count);
+    assert_eq!(1, other_karma.comment_count);
+    assert_eq!(-1, other_karma.comment_score);
+    assert_eq!(1, activity.len());
+    assert_eq!(1, activity[0].post_count);
+    assert_eq!(1, activity[0].comment_count);
+  }
+}
@@ -0,0 +1,36 @@
+use crate::{naive_now, newtypes::PersonId, schema::person_monthly_activity};
+use chrono::{Datelike, NaiveDate};
+use diesel::{result::Error, *};
+use serde::{Deserialize, Serialize};
+
+/// Backed by a database view. Months without any posts or comments have no row.
+#[derive(Queryable, Associations, PartialEq, Debug, Serialize, Deserialize, Clone)]
+#[table_name = "person_monthly_activity"]
+pub struct PersonMonthlyActivity {
+  pub person_id: PersonId,
+  /// The first day of the month
+  pub month: chrono::NaiveDateTime,
+  pub post_count: i64,
+  pub comment_count: i64,
+}
+
+impl PersonMonthlyActivity {
+  /// Lists the activity during the last `months` calendar months, including the current one,
+  /// oldest first
+  pub fn list_for_person(
+    conn: &PgConnection,
+    person_id: PersonId,
+    months: u32,
+  ) -> Result<Vec<Self>, Error> {
+    let now = naive_now();
+    // Months since year 0, so that going back crosses year boundaries
+    let start = (now.year() * 12 + now.month0() as i32) - (months.max(1) as i32 - 1);
+    let since = NaiveDate::from_ymd(start.div_euclid(12), start.rem_euclid(12) as u32 + 1, 1)
+      .and_hms(0, 0, 0);
+    person_monthly_activity::table
+      .filter(person_monthly_activity::person_id.eq(person_id))
+      .filter(person_monthly_activity::month.ge(since))
+      .order_by(person_monthly_activity::month)
+      .load::<Self>(conn)
+  }
+}
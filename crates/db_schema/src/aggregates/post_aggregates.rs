@@ -1,4 +1,4 @@
-use crate::{newtypes::PostId, schema::post_aggregates};
+use crate::{functions, newtypes::PostId, schema::post_aggregates};
 use diesel::{result::Error, *};
 use serde::{Deserialize, Serialize};
 
@@ -17,6 +17,12 @@ pub struct PostAggregates {
   pub published: chrono::NaiveDateTime,
   pub newest_comment_time_necro: chrono::NaiveDateTime, // A newest comment time, limited to 2 days, to prevent necrobumping
   pub newest_comment_time: chrono::NaiveDateTime,
+  /// When the post was stickied, to list stickied posts in this order
+  pub stickied_at: Option<chrono::NaiveDateTime>,
+  /// Refreshed periodically by `update_hot_ranks`
+  pub hot_rank: i32,
+  /// Like `hot_rank`, but based on the newest comment time
+  pub hot_rank_active: i32,
 }
 
 impl PostAggregates {
@@ -25,6 +31,36 @@ impl PostAggregates {
       .filter(post_aggregates::post_id.eq(post_id))
       .first::<Self>(conn)
   }
+
+  /// Recalculates the stored hot ranks of posts which were published or commented on since the
+  /// given time. Older posts which still have a rank are set to 0, as their rank would be
+  /// negligible anyway.
+  pub fn update_hot_ranks(conn: &PgConnection, since: chrono::NaiveDateTime) -> Result<(), Error> {
+    use crate::schema::post_aggregates::dsl::*;
+    conn.transaction(|| {
+      diesel::update(post_aggregates.filter(published.gt(since)))
+        .set(hot_rank.eq(functions::hot_rank(score, published)))
+        .execute(conn)?;
+      diesel::update(post_aggregates.filter(newest_comment_time_necro.gt(since)))
+        .set(hot_rank_active.eq(functions::hot_rank(score, newest_comment_time_necro)))
+        .execute(conn)?;
+      diesel::update(
+        post_aggregates
+          .filter(hot_rank.gt(0))
+          .filter(published.le(since)),
+      )
+      .set(hot_rank.eq(0))
+      .execute(conn)?;
+      diesel::update(
+        post_aggregates
+          .filter(hot_rank_active.gt(0))
+          .filter(newest_comment_time_necro.le(since)),
+      )
+      .set(hot_rank_active.eq(0))
+      .execute(conn)?;
+      Ok(())
+    })
+  }
 }
 
 #[cfg(test)]
@@ -32,6 +68,7 @@ mod tests {
   use crate::{
     aggregates::post_aggregates::PostAggregates,
     establish_unpooled_connection,
+    naive_now,
     source::{
       comment::{Comment, CommentForm},
       community::{Community, CommunityForm},
@@ -128,6 +165,17 @@ mod tests {
     assert_eq!(1, post_aggs_after_dislike.upvotes);
     assert_eq!(1, post_aggs_after_dislike.downvotes);
 
+    // The stored hot rank is only refreshed by the scheduled task
+    assert_eq!(1728, post_aggs_after_dislike.hot_rank);
+    PostAggregates::update_hot_ranks(&conn, naive_now() - chrono::Duration::days(7)).unwrap();
+    let post_aggs_after_rank_update = PostAggregates::read(&conn, inserted_post.id).unwrap();
+    assert_eq!(1370, post_aggs_after_rank_update.hot_rank);
+    assert_eq!(1370, post_aggs_after_rank_update.hot_rank_active);
+    PostAggregates::update_hot_ranks(&conn, naive_now()).unwrap();
+    let post_aggs_after_expiry = PostAggregates::read(&conn, inserted_post.id).unwrap();
+    assert_eq!(0, post_aggs_after_expiry.hot_rank);
+    assert_eq!(0, post_aggs_after_expiry.hot_rank_active);
+
     // Remove the parent comment
     Comment::delete(&conn, inserted_comment.id).unwrap();
     let after_comment_delete = PostAggregates::read(&conn, inserted_post.id).unwrap();
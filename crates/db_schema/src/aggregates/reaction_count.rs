@@ -0,0 +1,26 @@
+use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, hash::Hash};
+
+/// How many persons reacted to a post or comment with the same emoji
+#[derive(PartialEq, Debug, Serialize, Deserialize, Clone)]
+pub struct ReactionCount {
+  pub emoji: String,
+  pub count: i64,
+}
+
+impl ReactionCount {
+  /// Diesel can't combine group by with a count, so the reactions are counted here. Takes the
+  /// item and emoji of each reaction, and returns the most used emojis first.
+  pub(crate) fn count<T: Hash + Eq>(reactions: Vec<(T, String)>) -> Vec<(T, ReactionCount)> {
+    let mut counts: HashMap<(T, String), i64> = HashMap::new();
+    for reaction in reactions {
+      *counts.entry(reaction).or_insert(0) += 1;
+    }
+    let mut counts = counts
+      .into_iter()
+      .map(|((item, emoji), count)| (item, ReactionCount { emoji, count }))
+      .collect::<Vec<_>>();
+    counts.sort_by(|(_, a), (_, b)| b.count.cmp(&a.count).then_with(|| a.emoji.cmp(&b.emoji)));
+    counts
+  }
+}
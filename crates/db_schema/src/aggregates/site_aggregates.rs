@@ -69,6 +69,8 @@ mod tests {
       require_application: None,
       application_question: None,
       private_instance: None,
+      captcha_provider: None,
+      captcha_site_key: None,
     };
 
     Site::create(&conn, &site_form).unwrap();
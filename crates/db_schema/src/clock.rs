@@ -0,0 +1,60 @@
+use crate::naive_now;
+use chrono::{Duration, NaiveDateTime};
+use std::sync::Mutex;
+
+/// Source of the current time for expiry and scheduled deletion logic, so that it can be tested
+/// without depending on the wall clock.
+pub trait Clock {
+  fn now(&self) -> NaiveDateTime;
+}
+
+/// The actual current time
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+  fn now(&self) -> NaiveDateTime {
+    naive_now()
+  }
+}
+
+/// For tests: a clock which only moves forward when it is advanced manually
+pub struct MockClock {
+  now: Mutex<NaiveDateTime>,
+}
+
+impl MockClock {
+  pub fn new(now: NaiveDateTime) -> MockClock {
+    MockClock {
+      now: Mutex::new(now),
+    }
+  }
+
+  pub fn advance(&self, duration: Duration) {
+    let mut now = self.now.lock().expect("lock mock clock");
+    *now += duration;
+  }
+}
+
+impl Clock for MockClock {
+  fn now(&self) -> NaiveDateTime {
+    *self.now.lock().expect("lock mock clock")
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::clock::{Clock, MockClock};
+  use chrono::{Duration, NaiveDate};
+
+  #[test]
+  fn test_mock_clock() {
+    let start = NaiveDate::from_ymd(2021, 12, 1).and_hms(12, 0, 0);
+    let clock = MockClock::new(start);
+    assert_eq!(start, clock.now());
+    clock.advance(Duration::days(90));
+    assert_eq!(
+      NaiveDate::from_ymd(2022, 3, 1).and_hms(12, 0, 0),
+      clock.now()
+    );
+  }
+}
@@ -0,0 +1,120 @@
+use crate::{naive_now, newtypes::LocalUserId, source::account_export::*};
+use chrono::NaiveDateTime;
+use diesel::{dsl::*, result::Error, *};
+
+impl AccountExport {
+  pub fn create(conn: &PgConnection, form: &AccountExportForm) -> Result<Self, Error> {
+    use crate::schema::account_export::dsl::*;
+    insert_into(account_export)
+      .values(form)
+      .get_result::<Self>(conn)
+  }
+
+  pub fn read_for_token(conn: &PgConnection, for_token: &str) -> Result<Self, Error> {
+    use crate::schema::account_export::dsl::*;
+    account_export
+      .filter(token.eq(for_token))
+      .first::<Self>(conn)
+  }
+
+  pub fn latest_for_local_user(
+    conn: &PgConnection,
+    for_local_user_id: LocalUserId,
+  ) -> Result<Self, Error> {
+    use crate::schema::account_export::dsl::*;
+    account_export
+      .filter(local_user_id.eq(for_local_user_id))
+      .order_by(published.desc())
+      .first::<Self>(conn)
+  }
+
+  /// Stores the generated archive
+  pub fn finish(conn: &PgConnection, export_id: i32, archive: &str) -> Result<Self, Error> {
+    use crate::schema::account_export::dsl::*;
+    update(account_export.find(export_id))
+      .set((data.eq(archive), finished.eq(naive_now())))
+      .get_result::<Self>(conn)
+  }
+
+  pub fn delete(conn: &PgConnection, export_id: i32) -> Result<usize, Error> {
+    use crate::schema::account_export::dsl::*;
+    delete(account_export.find(export_id)).execute(conn)
+  }
+
+  pub fn delete_older_than(conn: &PgConnection, before: NaiveDateTime) -> Result<usize, Error> {
+    use crate::schema::account_export::dsl::*;
+    delete(account_export.filter(published.lt(before))).execute(conn)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::{
+    establish_unpooled_connection,
+    naive_now,
+    source::{account_export::*, local_user::*, person::*},
+    traits::Crud,
+  };
+  use chrono::Duration;
+  use serial_test::serial;
+
+  #[test]
+  #[serial]
+  fn test_account_export() {
+    let conn = establish_unpooled_connection();
+
+    let new_person = PersonForm {
+      name: "account_export_person".into(),
+      ..PersonForm::default()
+    };
+    let inserted_person = Person::create(&conn, &new_person).unwrap();
+    let new_local_user = LocalUserForm {
+      person_id: Some(inserted_person.id),
+      password_encrypted: Some("account_export_pass".to_string()),
+      ..LocalUserForm::default()
+    };
+    let inserted_local_user = LocalUser::create(&conn, &new_local_user).unwrap();
+
+    let form = AccountExportForm {
+      local_user_id: inserted_local_user.id,
+      token: "account_export_token".to_string(),
+    };
+    let inserted_export = AccountExport::create(&conn, &form).unwrap();
+    assert!(inserted_export.data.is_none());
+    assert!(inserted_export.finished.is_none());
+
+    let finished_export = AccountExport::finish(&conn, inserted_export.id, "{}").unwrap();
+    assert_eq!(Some("{}".to_string()), finished_export.data);
+    assert!(finished_export.finished.is_some());
+    assert_eq!(
+      finished_export,
+      AccountExport::read_for_token(&conn, "account_export_token").unwrap()
+    );
+    assert_eq!(
+      finished_export,
+      AccountExport::latest_for_local_user(&conn, inserted_local_user.id).unwrap()
+    );
+
+    let form = AccountExportForm {
+      local_user_id: inserted_local_user.id,
+      token: "account_export_failed_token".to_string(),
+    };
+    let failed_export = AccountExport::create(&conn, &form).unwrap();
+    assert_eq!(1, AccountExport::delete(&conn, failed_export.id).unwrap());
+    assert_eq!(
+      finished_export,
+      AccountExport::latest_for_local_user(&conn, inserted_local_user.id).unwrap()
+    );
+
+    assert_eq!(
+      0,
+      AccountExport::delete_older_than(&conn, naive_now() - Duration::days(1)).unwrap()
+    );
+    assert_eq!(
+      1,
+      AccountExport::delete_older_than(&conn, naive_now() + Duration::days(1)).unwrap()
+    );
+
+    Person::delete(&conn, inserted_person.id).unwrap();
+  }
+}
@@ -64,9 +64,60 @@ impl Activity {
     activity.filter(ap_id.eq(object_id)).first::<Self>(conn)
   }
 
+  /// Marks a received activity as forwarded to the followers of a local community. Returns false if
+  /// it was already forwarded before, in which case it must not be sent out again.
+  pub fn mark_as_forwarded(conn: &PgConnection, object_id: &DbUrl) -> Result<bool, Error> {
+    use crate::schema::activity::dsl::*;
+    let rows = diesel::update(
+      activity
+        .filter(ap_id.eq(object_id))
+        .filter(forwarded.eq(false)),
+    )
+    .set(forwarded.eq(true))
+    .execute(conn)?;
+    Ok(rows > 0)
+  }
+
+  /// Stores a local activity which must not be sent out yet.
+  pub fn insert_held(conn: &PgConnection, ap_id_: DbUrl, data_: Value) -> Result<Activity, Error> {
+    use crate::schema::activity::dsl::*;
+    insert_into(activity)
+      .values((
+        ap_id.eq(ap_id_),
+        data.eq(data_),
+        local.eq(true),
+        sensitive.eq(false),
+        held.eq(true),
+      ))
+      .get_result::<Self>(conn)
+  }
+
+  /// All held activities, oldest first
+  pub fn read_held(conn: &PgConnection) -> Result<Vec<Activity>, Error> {
+    use crate::schema::activity::dsl::*;
+    activity
+      .filter(held.eq(true))
+      .order_by(published.asc())
+      .then_order_by(id.asc())
+      .load::<Self>(conn)
+  }
+
+  /// Called once a held activity was sent out, after which it is kept like any other activity
+  pub fn release_held(conn: &PgConnection, activity_id: i32) -> Result<Activity, Error> {
+    use crate::schema::activity::dsl::*;
+    diesel::update(activity.find(activity_id))
+      .set(held.eq(false))
+      .get_result::<Self>(conn)
+  }
+
   pub fn delete_olds(conn: &PgConnection) -> Result<usize, Error> {
     use crate::schema::activity::dsl::*;
-    diesel::delete(activity.filter(published.lt(now - 6.months()))).execute(conn)
+    diesel::delete(
+      activity
+        .filter(published.lt(now - 6.months()))
+        .filter(held.eq(false)),
+    )
+    .execute(conn)
   }
 }
 
@@ -134,15 +185,36 @@ mod tests {
       sensitive: Some(false),
       published: inserted_activity.published,
       updated: None,
+      forwarded: false,
+      held: false,
     };
 
     let read_activity = Activity::read(&conn, inserted_activity.id).unwrap();
     let read_activity_by_apub_id = Activity::read_from_apub_id(&conn, &ap_id).unwrap();
+    let first_forward = Activity::mark_as_forwarded(&conn, &ap_id).unwrap();
+    let second_forward = Activity::mark_as_forwarded(&conn, &ap_id).unwrap();
+
+    let held_ap_id: DbUrl = Url::parse(
+      "https://enterprise.lemmy.ml/activities/delete/2d0b1d8e-4c5e-4f3a-9a36-89aa2c7f0b5e",
+    )
+    .unwrap()
+    .into();
+    let held_activity = Activity::insert_held(&conn, held_ap_id, Value::Null).unwrap();
+    let read_held = Activity::read_held(&conn).unwrap();
+    let released = Activity::release_held(&conn, held_activity.id).unwrap();
+    let read_held_after_release = Activity::read_held(&conn).unwrap();
     Person::delete(&conn, inserted_creator.id).unwrap();
     Activity::delete(&conn, inserted_activity.id).unwrap();
+    Activity::delete(&conn, held_activity.id).unwrap();
 
     assert_eq!(expected_activity, read_activity);
     assert_eq!(expected_activity, read_activity_by_apub_id);
     assert_eq!(expected_activity, inserted_activity);
+    assert!(first_forward);
+    assert!(!second_forward);
+    assert!(held_activity.held);
+    assert_eq!(vec![held_activity], read_held);
+    assert!(!released.held);
+    assert!(read_held_after_release.is_empty());
   }
 }
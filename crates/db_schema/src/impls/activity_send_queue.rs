@@ -0,0 +1,192 @@
+use crate::source::activity_send_queue::*;
+use chrono::NaiveDateTime;
+use diesel::{
+  insert_into,
+  result::Error,
+  Connection,
+  ExpressionMethods,
+  OptionalExtension,
+  PgConnection,
+  QueryDsl,
+  RunQueryDsl,
+};
+
+impl QueuedActivity {
+  pub fn enqueue(conn: &PgConnection, forms: &[QueuedActivityForm]) -> Result<usize, Error> {
+    use crate::schema::activity_send_queue::dsl::*;
+    insert_into(activity_send_queue).values(forms).execute(conn)
+  }
+
+  /// Claims deliveries which should be attempted at or before the given time, oldest first, by
+  /// moving their next attempt to `claimed_until`. Rows which are locked by another worker are
+  /// skipped, so that concurrent workers never send the same delivery. If the worker dies before
+  /// finishing, the deliveries become due again once the claim expires.
+  pub fn claim_due(
+    conn: &PgConnection,
+    before: NaiveDateTime,
+    claimed_until: NaiveDateTime,
+    limit: i64,
+  ) -> Result<Vec<Self>, Error> {
+    use crate::schema::activity_send_queue::dsl::*;
+    conn.transaction(|| {
+      let ids = activity_send_queue
+        .select(id)
+        .filter(next_attempt.le(before))
+        .order_by(next_attempt.asc())
+        .then_order_by(id.asc())
+        .limit(limit)
+        .for_update()
+        .skip_locked()
+        .load::<i32>(conn)?;
+      let mut claimed = diesel::update(activity_send_queue.filter(id.eq_any(ids)))
+        .set(next_attempt.eq(claimed_until))
+        .get_results::<Self>(conn)?;
+      claimed.sort_by_key(|q| q.id);
+      Ok(claimed)
+    })
+  }
+
+  /// Number of deliveries which are waiting in the queue, including those which are postponed
+  pub fn count(conn: &PgConnection) -> Result<i64, Error> {
+    use crate::schema::activity_send_queue::dsl::*;
+    activity_send_queue.count().get_result(conn)
+  }
+
+  pub fn delete(conn: &PgConnection, queued_id: i32) -> Result<usize, Error> {
+    use crate::schema::activity_send_queue::dsl::*;
+    diesel::delete(activity_send_queue.find(queued_id)).execute(conn)
+  }
+
+  /// Counts a failed delivery, and schedules the next attempt
+  pub fn mark_failed(
+    conn: &PgConnection,
+    queued_id: i32,
+    retry_at: NaiveDateTime,
+  ) -> Result<Self, Error> {
+    use crate::schema::activity_send_queue::dsl::*;
+    diesel::update(activity_send_queue.find(queued_id))
+      .set((retries.eq(retries + 1), next_attempt.eq(retry_at)))
+      .get_result::<Self>(conn)
+  }
+
+  /// Moves the next attempt without counting it as failure
+  pub fn postpone(
+    conn: &PgConnection,
+    queued_id: i32,
+    retry_at: NaiveDateTime,
+  ) -> Result<Self, Error> {
+    use crate::schema::activity_send_queue::dsl::*;
+    diesel::update(activity_send_queue.find(queued_id))
+      .set(next_attempt.eq(retry_at))
+      .get_result::<Self>(conn)
+  }
+}
+
+impl InstanceSendFailure {
+  pub fn list(conn: &PgConnection) -> Result<Vec<Self>, Error> {
+    use crate::schema::instance_send_failure::dsl::*;
+    instance_send_failure.load::<Self>(conn)
+  }
+
+  pub fn read_for_domain(conn: &PgConnection, for_domain: &str) -> Result<Option<Self>, Error> {
+    use crate::schema::instance_send_failure::dsl::*;
+    instance_send_failure
+      .filter(domain.eq(for_domain))
+      .first::<Self>(conn)
+      .optional()
+  }
+
+  pub fn record_failure(
+    conn: &PgConnection,
+    for_domain: &str,
+    failed_at: NaiveDateTime,
+  ) -> Result<Self, Error> {
+    use crate::schema::instance_send_failure::dsl::*;
+    insert_into(instance_send_failure)
+      .values((
+        domain.eq(for_domain),
+        failures.eq(1),
+        last_failure.eq(failed_at),
+      ))
+      .on_conflict(domain)
+      .do_update()
+      .set((failures.eq(failures + 1), last_failure.eq(failed_at)))
+      .get_result::<Self>(conn)
+  }
+
+  /// Called after a successful delivery
+  pub fn clear(conn: &PgConnection, for_domain: &str) -> Result<usize, Error> {
+    use crate::schema::instance_send_failure::dsl::*;
+    diesel::delete(instance_send_failure.filter(domain.eq(for_domain))).execute(conn)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::{establish_unpooled_connection, naive_now, source::activity_send_queue::*};
+  use chrono::Duration;
+  use serial_test::serial;
+  use url::Url;
+
+  #[test]
+  #[serial]
+  fn test_activity_send_queue() {
+    let conn = establish_unpooled_connection();
+
+    let url = |u: &str| Url::parse(u).unwrap().into();
+    let form = |inbox: &str| QueuedActivityForm {
+      activity_id: url("https://example.com/activities/create/1"),
+      inbox: url(inbox),
+      actor_id: url("https://example.com/u/sender"),
+      activity: "{}".to_string(),
+      request_id: Some("8e6f1b8c-3e0b-4c1f-9d6a-2f4f3b1e7a10".to_string()),
+    };
+    let forms = [
+      form("https://first.example.com/inbox"),
+      form("https://second.example.com/inbox"),
+    ];
+    let inserted = QueuedActivity::enqueue(&conn, &forms).unwrap();
+    let now = naive_now();
+    let claimed_until = now + Duration::minutes(10);
+    let due = QueuedActivity::claim_due(&conn, now, claimed_until, 10).unwrap();
+    let due_while_claimed = QueuedActivity::claim_due(&conn, now, claimed_until, 10).unwrap();
+
+    let later = now + Duration::minutes(5);
+    let failed = QueuedActivity::mark_failed(&conn, due[0].id, later).unwrap();
+    let postponed = QueuedActivity::postpone(&conn, due[1].id, later).unwrap();
+    let due_after_failure = QueuedActivity::claim_due(&conn, now, claimed_until, 10).unwrap();
+    let due_later = QueuedActivity::claim_due(&conn, later, claimed_until, 1).unwrap();
+
+    InstanceSendFailure::record_failure(&conn, "first.example.com", now).unwrap();
+    let failure_for_domain =
+      InstanceSendFailure::read_for_domain(&conn, "first.example.com").unwrap();
+    let no_failure = InstanceSendFailure::read_for_domain(&conn, "second.example.com").unwrap();
+    let failure = InstanceSendFailure::record_failure(&conn, "first.example.com", later).unwrap();
+    let cleared = InstanceSendFailure::clear(&conn, "first.example.com").unwrap();
+    let failures_after_clear = InstanceSendFailure::list(&conn).unwrap();
+
+    for queued in &due {
+      QueuedActivity::delete(&conn, queued.id).unwrap();
+    }
+
+    assert_eq!(2, inserted);
+    assert_eq!(2, due.len());
+    assert!(due.iter().all(|q| q.next_attempt > later));
+    assert!(due_while_claimed.is_empty());
+    assert_eq!(1, failed.retries);
+    assert_eq!(0, postponed.retries);
+    assert!(due_after_failure.is_empty());
+    assert_eq!(1, due_later.len());
+    assert_eq!(failed.id, due_later[0].id);
+    assert!(due_later[0].next_attempt > later);
+    assert_eq!(
+      1,
+      failure_for_domain.map(|f| f.failures).unwrap_or_default()
+    );
+    assert!(no_failure.is_none());
+    assert_eq!(2, failure.failures);
+    assert!(failure.last_failure > now);
+    assert_eq!(1, cleared);
+    assert!(failures_after_clear.is_empty());
+  }
+}
@@ -0,0 +1,177 @@
+use crate::{
+  limit_and_offset,
+  naive_now,
+  newtypes::{CommunityId, PersonId},
+  source::appeal::*,
+  traits::Crud,
+};
+use diesel::{
+  insert_into,
+  result::Error,
+  update,
+  ExpressionMethods,
+  PgConnection,
+  QueryDsl,
+  RunQueryDsl,
+};
+
+impl Crud for Appeal {
+  type Form = AppealForm;
+  type IdType = i32;
+  fn create(conn: &PgConnection, form: &Self::Form) -> Result<Self, Error> {
+    use crate::schema::appeal::dsl::*;
+    insert_into(appeal).values(form).get_result::<Self>(conn)
+  }
+
+  fn read(conn: &PgConnection, id_: Self::IdType) -> Result<Self, Error> {
+    use crate::schema::appeal::dsl::*;
+    appeal.find(id_).first::<Self>(conn)
+  }
+
+  fn update(conn: &PgConnection, id_: Self::IdType, form: &Self::Form) -> Result<Self, Error> {
+    use crate::schema::appeal::dsl::*;
+    update(appeal.find(id_)).set(form).get_result::<Self>(conn)
+  }
+
+  fn delete(conn: &PgConnection, id_: Self::IdType) -> Result<usize, Error> {
+    use crate::schema::appeal::dsl::*;
+    diesel::delete(appeal.find(id_)).execute(conn)
+  }
+}
+
+impl Appeal {
+  /// Sets the final status of a pending appeal. Fails with `NotFound` if the appeal was already
+  /// resolved.
+  pub fn resolve(
+    conn: &PgConnection,
+    appeal_id: i32,
+    by_resolver_id: PersonId,
+    new_status: AppealStatus,
+  ) -> Result<Self, Error> {
+    use crate::schema::appeal::dsl::*;
+    update(
+      appeal
+        .find(appeal_id)
+        .filter(status.eq(AppealStatus::Pending)),
+    )
+    .set((
+      status.eq(new_status),
+      resolver_id.eq(by_resolver_id),
+      updated.eq(naive_now()),
+    ))
+    .get_result::<Self>(conn)
+  }
+
+  /// Oldest first, optionally only for a single community
+  pub fn list(
+    conn: &PgConnection,
+    for_community_id: Option<CommunityId>,
+    unresolved_only: bool,
+    page: Option<i64>,
+    limit: Option<i64>,
+  ) -> Result<Vec<Self>, Error> {
+    use crate::schema::appeal::dsl::*;
+    let mut query = appeal.into_boxed();
+    if let Some(for_community_id) = for_community_id {
+      query = query.filter(community_id.eq(for_community_id));
+    }
+    if unresolved_only {
+      query = query.filter(status.eq(AppealStatus::Pending));
+    }
+    let (limit, offset) = limit_and_offset(page, limit);
+    query
+      .order_by(published.asc())
+      .then_order_by(id.asc())
+      .limit(limit)
+      .offset(offset)
+      .load::<Self>(conn)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::{
+    establish_unpooled_connection,
+    source::{appeal::*, community::*, moderator::*, person::*, post::*},
+    traits::Crud,
+  };
+  use serial_test::serial;
+
+  #[test]
+  #[serial]
+  fn test_crud() {
+    let conn = establish_unpooled_connection();
+
+    let person_form = PersonForm {
+      name: "appeal_creator".into(),
+      ..PersonForm::default()
+    };
+    let person = Person::create(&conn, &person_form).unwrap();
+    let mod_form = PersonForm {
+      name: "appeal_mod".into(),
+      ..PersonForm::default()
+    };
+    let mod_ = Person::create(&conn, &mod_form).unwrap();
+
+    let community_form = CommunityForm {
+      name: "appeal_community".to_string(),
+      title: "nada".to_owned(),
+      ..CommunityForm::default()
+    };
+    let community = Community::create(&conn, &community_form).unwrap();
+
+    let post_form = PostForm {
+      name: "appealed post".into(),
+      creator_id: person.id,
+      community_id: community.id,
+      removed: Some(true),
+      ..PostForm::default()
+    };
+    let post = Post::create(&conn, &post_form).unwrap();
+
+    let appeal_form = AppealForm {
+      creator_id: person.id,
+      community_id: community.id,
+      post_id: Some(post.id),
+      comment_id: None,
+      reason: "it was on topic".into(),
+    };
+    let appeal = Appeal::create(&conn, &appeal_form).unwrap();
+    // Only one open appeal per object and user
+    let duplicate = Appeal::create(&conn, &appeal_form);
+    let unresolved = Appeal::list(&conn, Some(community.id), true, None, None).unwrap();
+
+    let upheld = Appeal::resolve(&conn, appeal.id, mod_.id, AppealStatus::Upheld).unwrap();
+    let resolved_again = Appeal::resolve(&conn, appeal.id, mod_.id, AppealStatus::Granted);
+    let mod_resolve_appeal_form = ModResolveAppealForm {
+      mod_person_id: mod_.id,
+      appeal_id: appeal.id,
+      granted: false,
+      reason: None,
+    };
+    let inserted_mod_resolve_appeal =
+      ModResolveAppeal::create(&conn, &mod_resolve_appeal_form).unwrap();
+    let read_mod_resolve_appeal =
+      ModResolveAppeal::read(&conn, inserted_mod_resolve_appeal.id).unwrap();
+
+    // After the first appeal was resolved, a new one can be filed
+    let second_appeal = Appeal::create(&conn, &appeal_form).unwrap();
+    let unresolved_after = Appeal::list(&conn, Some(community.id), true, None, None).unwrap();
+    let all = Appeal::list(&conn, Some(community.id), false, None, None).unwrap();
+
+    Post::delete(&conn, post.id).unwrap();
+    Community::delete(&conn, community.id).unwrap();
+    Person::delete(&conn, person.id).unwrap();
+    Person::delete(&conn, mod_.id).unwrap();
+
+    assert_eq!(AppealStatus::Pending, appeal.status);
+    assert!(duplicate.is_err());
+    assert_eq!(vec![appeal], unresolved);
+    assert_eq!(AppealStatus::Upheld, upheld.status);
+    assert_eq!(Some(mod_.id), upheld.resolver_id);
+    assert!(resolved_again.is_err());
+    assert_eq!(inserted_mod_resolve_appeal, read_mod_resolve_appeal);
+    assert_eq!(vec![second_appeal.clone()], unresolved_after);
+    assert_eq!(vec![upheld, second_appeal], all);
+  }
+}
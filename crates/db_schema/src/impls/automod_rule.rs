@@ -0,0 +1,231 @@
+use crate::{naive_now, newtypes::CommunityId, source::automod_rule::*, traits::Crud};
+use chrono::Duration;
+use diesel::{dsl::*, result::Error, *};
+use once_cell::sync::Lazy;
+use regex::{Regex, RegexBuilder};
+use url::Url;
+
+static LINK_REGEX: Lazy<Regex> =
+  Lazy::new(|| Regex::new(r"https?://[^\s<>()\[\]]+").expect("compile regex"));
+
+impl Crud for AutomodRule {
+  type Form = AutomodRuleForm;
+  type IdType = i32;
+  fn create(conn: &PgConnection, form: &Self::Form) -> Result<Self, Error> {
+    use crate::schema::automod_rule::dsl::*;
+    insert_into(automod_rule)
+      .values(form)
+      .get_result::<Self>(conn)
+  }
+
+  fn read(conn: &PgConnection, id_: Self::IdType) -> Result<Self, Error> {
+    use crate::schema::automod_rule::dsl::*;
+    automod_rule.find(id_).first::<Self>(conn)
+  }
+
+  fn update(conn: &PgConnection, id_: Self::IdType, form: &Self::Form) -> Result<Self, Error> {
+    use crate::schema::automod_rule::dsl::*;
+    diesel::update(automod_rule.find(id_))
+      .set(form)
+      .get_result::<Self>(conn)
+  }
+
+  fn delete(conn: &PgConnection, id_: Self::IdType) -> Result<usize, Error> {
+    use crate::schema::automod_rule::dsl::*;
+    diesel::delete(automod_rule.find(id_)).execute(conn)
+  }
+}
+
+impl AutomodRule {
+  /// Ordered by creation, which is also the order in which rules are applied
+  pub fn list_for_community(
+    conn: &PgConnection,
+    for_community_id: CommunityId,
+  ) -> Result<Vec<Self>, Error> {
+    use crate::schema::automod_rule::dsl::*;
+    automod_rule
+      .filter(community_id.eq(for_community_id))
+      .order_by(id.asc())
+      .load::<Self>(conn)
+  }
+
+  /// Patterns are matched case insensitive, anywhere in the text
+  pub fn build_regex(pattern: &str) -> Result<Regex, regex::Error> {
+    RegexBuilder::new(pattern).case_insensitive(true).build()
+  }
+
+  /// Returns true if all conditions of the rule match. A title or url condition never matches a
+  /// comment.
+  pub fn matches(&self, content: &AutomodContent) -> bool {
+    let regex_matches = |pattern: &Option<String>, text: Option<&str>| match pattern {
+      Some(pattern) => match (Self::build_regex(pattern), text) {
+        (Ok(regex), Some(text)) => regex.is_match(text),
+        _ => false,
+      },
+      None => true,
+    };
+    if !regex_matches(&self.title_regex, content.title)
+      || !regex_matches(&self.body_regex, content.body)
+    {
+      return false;
+    }
+
+    if let Some(domain) = &self.link_domain {
+      let domain = domain.to_lowercase();
+      let body_links = content
+        .body
+        .map(|b| {
+          LINK_REGEX
+            .find_iter(b)
+            .map(|m| m.as_str())
+            .collect::<Vec<_>>()
+        })
+        .unwrap_or_default();
+      let links_to_domain = content
+        .url
+        .into_iter()
+        .chain(body_links)
+        .filter_map(|l| Url::parse(l).ok())
+        .filter_map(|l| l.host_str().map(str::to_lowercase))
+        .any(|host| host == domain || host.ends_with(&format!(".{}", domain)));
+      if !links_to_domain {
+        return false;
+      }
+    }
+
+    if let Some(days) = self.account_age_below_days {
+      if content.creator_published < naive_now() - Duration::days(days.into()) {
+        return false;
+      }
+    }
+
+    if let Some(karma) = self.karma_below {
+      if content.creator_karma >= karma.into() {
+        return false;
+      }
+    }
+
+    true
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::{
+    establish_unpooled_connection,
+    naive_now,
+    source::{automod_rule::*, community::*, moderator::*, person::*, post::*},
+    traits::Crud,
+    AutomodAction,
+  };
+  use chrono::Duration;
+  use serial_test::serial;
+
+  #[test]
+  #[serial]
+  fn test_automod_rule() {
+    let conn = establish_unpooled_connection();
+
+    let new_person = PersonForm {
+      name: "automod_person".into(),
+      ..PersonForm::default()
+    };
+    let inserted_person = Person::create(&conn, &new_person).unwrap();
+
+    let new_community = CommunityForm {
+      name: "automod_community".to_string(),
+      title: "nada".to_owned(),
+      ..CommunityForm::default()
+    };
+    let inserted_community = Community::create(&conn, &new_community).unwrap();
+
+    let new_post = PostForm {
+      name: "A test post".into(),
+      creator_id: inserted_person.id,
+      community_id: inserted_community.id,
+      ..PostForm::default()
+    };
+    let inserted_post = Post::create(&conn, &new_post).unwrap();
+
+    let rule_form = AutomodRuleForm {
+      community_id: inserted_community.id,
+      title_regex: Some(r"\bfree\b".into()),
+      body_regex: None,
+      link_domain: Some("spam.example".into()),
+      account_age_below_days: Some(7),
+      karma_below: None,
+      action: AutomodAction::Remove.to_string(),
+      reason: Some("Spam".into()),
+      updated: None,
+    };
+    let rule = AutomodRule::create(&conn, &rule_form).unwrap();
+    assert_eq!(
+      vec![rule.clone()],
+      AutomodRule::list_for_community(&conn, inserted_community.id).unwrap()
+    );
+
+    let content = AutomodContent {
+      title: Some("Get FREE stuff"),
+      body: Some("More at <https://www.spam.example/offer>"),
+      url: None,
+      creator_published: naive_now(),
+      creator_karma: 0,
+    };
+    assert!(rule.matches(&content));
+    // All conditions need to match
+    let old_account = AutomodContent {
+      creator_published: naive_now() - Duration::days(30),
+      ..content
+    };
+    assert!(!rule.matches(&old_account));
+    let other_domain = AutomodContent {
+      body: None,
+      url: Some("https://notspam.example/"),
+      ..content
+    };
+    assert!(!rule.matches(&other_domain));
+    // Comments have no title
+    let comment = AutomodContent {
+      title: None,
+      ..content
+    };
+    assert!(!rule.matches(&comment));
+
+    let karma_rule = AutomodRule::update(
+      &conn,
+      rule.id,
+      &AutomodRuleForm {
+        title_regex: None,
+        link_domain: None,
+        account_age_below_days: None,
+        karma_below: Some(10),
+        ..rule_form
+      },
+    )
+    .unwrap();
+    assert_eq!(None, karma_rule.title_regex);
+    assert!(karma_rule.matches(&comment));
+    let high_karma = AutomodContent {
+      creator_karma: 10,
+      ..comment
+    };
+    assert!(!karma_rule.matches(&high_karma));
+
+    // The modlog entry is kept when its rule is deleted
+    let action_form = ModAutomodActionForm {
+      automod_rule_id: Some(rule.id),
+      community_id: inserted_community.id,
+      post_id: Some(inserted_post.id),
+      comment_id: None,
+      action: AutomodAction::Remove.to_string(),
+    };
+    let action = ModAutomodAction::create(&conn, &action_form).unwrap();
+    assert_eq!(1, AutomodRule::delete(&conn, rule.id).unwrap());
+    let action_after_delete = ModAutomodAction::read(&conn, action.id).unwrap();
+    assert_eq!(None, action_after_delete.automod_rule_id);
+
+    Post::delete(&conn, inserted_post.id).unwrap();
+    Community::delete(&conn, inserted_community.id).unwrap();
+    Person::delete(&conn, inserted_person.id).unwrap();
+  }
+}
@@ -0,0 +1,56 @@
+use crate::{naive_now, schema::captcha_answer::dsl::*, source::captcha_answer::*};
+use diesel::{dsl::*, result::Error, PgConnection, *};
+
+impl CaptchaAnswer {
+  pub fn insert(conn: &PgConnection, form: &CaptchaAnswerForm) -> Result<Self, Error> {
+    insert_into(captcha_answer)
+      .values(form)
+      .get_result::<Self>(conn)
+  }
+
+  /// Checks the answer, case insensitively. Each captcha can only be checked once, and is only
+  /// valid for ten minutes.
+  pub fn check(conn: &PgConnection, captcha_uuid: &str, given_answer: &str) -> Result<bool, Error> {
+    let found = delete(captcha_answer.filter(uuid.eq(captcha_uuid))).get_results::<Self>(conn)?;
+    Ok(found.iter().any(|c| {
+      c.published > naive_now() - chrono::Duration::minutes(10)
+        && c.answer.to_lowercase() == given_answer.to_lowercase()
+    }))
+  }
+
+  /// Removes the answers of captchas which were never checked
+  pub fn delete_expired(conn: &PgConnection) -> Result<usize, Error> {
+    delete(captcha_answer.filter(published.lt(now - 10.minutes()))).execute(conn)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::{establish_unpooled_connection, source::captcha_answer::*};
+  use serial_test::serial;
+
+  #[test]
+  #[serial]
+  fn test_captcha_answer() {
+    let conn = establish_unpooled_connection();
+
+    let form = |captcha_uuid: &str| CaptchaAnswerForm {
+      uuid: captcha_uuid.to_string(),
+      answer: "XyZ12".to_string(),
+    };
+    CaptchaAnswer::insert(&conn, &form("fe0f1c40-correct")).unwrap();
+    CaptchaAnswer::insert(&conn, &form("fe0f1c40-wrong")).unwrap();
+
+    let correct = CaptchaAnswer::check(&conn, "fe0f1c40-correct", "xyz12").unwrap();
+    let checked_again = CaptchaAnswer::check(&conn, "fe0f1c40-correct", "xyz12").unwrap();
+    let wrong = CaptchaAnswer::check(&conn, "fe0f1c40-wrong", "abc").unwrap();
+    let unknown = CaptchaAnswer::check(&conn, "fe0f1c40-unknown", "xyz12").unwrap();
+    let expired = CaptchaAnswer::delete_expired(&conn).unwrap();
+
+    assert!(correct);
+    assert!(!checked_again);
+    assert!(!wrong);
+    assert!(!unknown);
+    assert_eq!(0, expired);
+  }
+}
@@ -1,19 +1,34 @@
 use crate::{
+  aggregates::reaction_count::ReactionCount,
+  apub_id_variants,
+  functions::search_headline,
   naive_now,
-  newtypes::{CommentId, DbUrl, PersonId},
-  source::comment::{
-    Comment,
-    CommentForm,
-    CommentLike,
-    CommentLikeForm,
-    CommentSaved,
-    CommentSavedForm,
+  newtypes::{CommentId, CommunityId, DbUrl, LanguageId, PersonId, SavedCollectionId},
+  source::{
+    comment::{
+      Comment,
+      CommentForm,
+      CommentLike,
+      CommentLikeForm,
+      CommentReaction,
+      CommentReactionForm,
+      CommentSaved,
+      CommentSavedForm,
+    },
+    comment_revision::CommentRevision,
+    moderator::ModRemoveComment,
   },
-  traits::{Crud, DeleteableOrRemoveable, Likeable, Saveable},
+  traits::{Crud, DeleteableOrRemoveable, Likeable, Reactable, Saveable},
 };
 use diesel::{dsl::*, result::Error, *};
+use std::collections::HashMap;
 use url::Url;
 
+/// Number of comments which are changed per transaction, when updating all comments of a user
+const CREATOR_CHUNK_SIZE: i64 = 500;
+
+const PERMADELETED_CONTENT: &str = "*Permananently Deleted*";
+
 impl Comment {
   pub fn update_ap_id(
     conn: &PgConnection,
@@ -27,18 +42,64 @@ impl Comment {
       .get_result::<Self>(conn)
   }
 
+  /// Excerpts of the comments with the matches of the search term highlighted
+  pub fn search_snippets(
+    conn: &PgConnection,
+    comment_ids: Vec<CommentId>,
+    search_term: &str,
+  ) -> Result<Vec<(CommentId, String)>, Error> {
+    use crate::schema::comment::dsl::*;
+    comment
+      .filter(id.eq_any(comment_ids))
+      .select((id, search_headline(content, search_term, language_id)))
+      .load::<(CommentId, String)>(conn)
+  }
+
+  /// All comments of the creator, including deleted and removed ones
+  pub fn list_all_for_creator(
+    conn: &PgConnection,
+    for_creator_id: PersonId,
+  ) -> Result<Vec<Self>, Error> {
+    use crate::schema::comment::dsl::*;
+    comment
+      .filter(creator_id.eq(for_creator_id))
+      .order_by(id)
+      .load::<Self>(conn)
+  }
+
+  /// Permanently deletes all comments of the creator, in chunks with a separate transaction each.
   pub fn permadelete_for_creator(
     conn: &PgConnection,
     for_creator_id: PersonId,
   ) -> Result<Vec<Self>, Error> {
     use crate::schema::comment::dsl::*;
-    diesel::update(comment.filter(creator_id.eq(for_creator_id)))
-      .set((
-        content.eq("*Permananently Deleted*"),
-        deleted.eq(true),
-        updated.eq(naive_now()),
-      ))
-      .get_results::<Self>(conn)
+    // The edit history would otherwise still contain the deleted content
+    CommentRevision::delete_for_creator(conn, for_creator_id)?;
+    let mut res = vec![];
+    loop {
+      // Already deleted comments are skipped, so an interrupted run can simply be repeated
+      let deleted_chunk = conn.transaction(|| {
+        let ids = comment
+          .filter(creator_id.eq(for_creator_id))
+          .filter(deleted.eq(false).or(content.ne(PERMADELETED_CONTENT)))
+          .select(id)
+          .order_by(id)
+          .limit(CREATOR_CHUNK_SIZE)
+          .load::<CommentId>(conn)?;
+        diesel::update(comment.filter(id.eq_any(ids)))
+          .set((
+            content.eq(PERMADELETED_CONTENT),
+            deleted.eq(true),
+            updated.eq(naive_now()),
+          ))
+          .get_results::<Self>(conn)
+      })?;
+      let done = (deleted_chunk.len() as i64) < CREATOR_CHUNK_SIZE;
+      res.extend(deleted_chunk);
+      if done {
+        return Ok(res);
+      }
+    }
   }
 
   pub fn update_deleted(
@@ -63,17 +124,174 @@ impl Comment {
       .get_result::<Self>(conn)
   }
 
-  pub fn update_removed_for_creator(
+  /// Permanently deletes comments which were deleted by their creator (or removed, if
+  /// `purge_removed` is true) before the given time. Comments with replies, unresolved reports or
+  /// a legal hold are kept.
+  pub fn purge_expired(
     conn: &PgConnection,
-    for_creator_id: PersonId,
+    purge_removed: bool,
+    before: chrono::NaiveDateTime,
+  ) -> Result<usize, Error> {
+    use crate::schema::{comment::dsl::*, comment_alias_1, comment_report, legal_hold};
+    let replies = comment_alias_1::table.filter(comment_alias_1::parent_id.eq(id.nullable()));
+    let unresolved_reports = comment_report::table
+      .filter(comment_report::comment_id.eq(id))
+      .filter(comment_report::resolved.eq(false));
+    let holds = legal_hold::table.filter(legal_hold::comment_id.eq(id.nullable()));
+    let expired = comment
+      .filter(updated.lt(before))
+      .filter(not(exists(replies)))
+      .filter(not(exists(unresolved_reports)))
+      .filter(not(exists(holds)));
+    if purge_removed {
+      diesel::delete(expired.filter(removed.eq(true))).execute(conn)
+    } else {
+      diesel::delete(expired.filter(deleted.eq(true)).filter(removed.eq(false))).execute(conn)
+    }
+  }
+
+  /// Reads replies below the given comment, at any depth. At most `limit` comments are returned.
+  pub fn read_subtree(
+    conn: &PgConnection,
+    parent: &Comment,
+    limit: i64,
+  ) -> Result<Vec<Self>, Error> {
+    use crate::schema::comment::dsl::*;
+    comment
+      .filter(path.like(format!("{}.%", parent.path)))
+      .order_by(path)
+      .limit(limit)
+      .load::<Self>(conn)
+  }
+
+  /// Number of replies below the given comment, at any depth.
+  pub fn count_subtree(conn: &PgConnection, parent: &Comment) -> Result<i64, Error> {
+    use crate::schema::comment::dsl::*;
+    comment
+      .filter(path.like(format!("{}.%", parent.path)))
+      .count()
+      .get_result(conn)
+  }
+
+  /// Removes or restores all replies below the given comment (but not the comment itself). Only
+  /// returns the replies which were changed.
+  pub fn update_removed_for_subtree(
+    conn: &PgConnection,
+    parent: &Comment,
     new_removed: bool,
   ) -> Result<Vec<Self>, Error> {
     use crate::schema::comment::dsl::*;
-    diesel::update(comment.filter(creator_id.eq(for_creator_id)))
-      .set((removed.eq(new_removed), updated.eq(naive_now())))
+    diesel::update(
+      comment
+        .filter(path.like(format!("{}.%", parent.path)))
+        .filter(removed.ne(new_removed)),
+    )
+    .set((removed.eq(new_removed), updated.eq(naive_now())))
+    .get_results::<Self>(conn)
+  }
+
+  /// Restores the replies below the given comment which were removed together with it. These are
+  /// recognized by their latest mod log entry, which has to match the removal of the parent. Replies
+  /// which were removed separately, before or after the parent, stay removed.
+  pub fn restore_subtree(
+    conn: &PgConnection,
+    parent: &Comment,
+    parent_removal: &ModRemoveComment,
+  ) -> Result<Vec<Self>, Error> {
+    use crate::schema::{comment::dsl::*, mod_remove_comment};
+    let removed_replies = comment
+      .filter(path.like(format!("{}.%", parent.path)))
+      .filter(removed.eq(true))
+      .select(id)
+      .load::<CommentId>(conn)?;
+    let entries = mod_remove_comment::table
+      .filter(mod_remove_comment::comment_id.eq_any(removed_replies))
+      .order_by(mod_remove_comment::when_.desc())
+      .then_order_by(mod_remove_comment::id.desc())
+      .load::<ModRemoveComment>(conn)?;
+
+    let mut latest_entries = HashMap::new();
+    for entry in entries {
+      latest_entries.entry(entry.comment_id).or_insert(entry);
+    }
+    let restore_ids = latest_entries
+      .into_values()
+      .filter(|e| {
+        e.removed.unwrap_or(true)
+          && e.mod_person_id == parent_removal.mod_person_id
+          && e.reason == parent_removal.reason
+          && e.when_ >= parent_removal.when_
+      })
+      .filter_map(|e| e.comment_id)
+      .collect::<Vec<_>>();
+    diesel::update(comment.filter(id.eq_any(restore_ids)))
+      .set((removed.eq(false), updated.eq(naive_now())))
       .get_results::<Self>(conn)
   }
 
+  /// Number of levels between the thread root and this comment, with top-level comments at 0.
+  pub fn depth(&self) -> usize {
+    self.path.matches('.').count().saturating_sub(1)
+  }
+
+  /// Removes or restores all comments of the creator, optionally only those in the given
+  /// community. Spam accounts can have thousands of comments, so this is done in chunks with a
+  /// separate transaction each, instead of locking all rows at once.
+  pub fn update_removed_for_creator(
+    conn: &PgConnection,
+    for_creator_id: PersonId,
+    for_community_id: Option<CommunityId>,
+    new_removed: bool,
+  ) -> Result<Vec<Self>, Error> {
+    let mut res = vec![];
+    loop {
+      let chunk = Comment::update_removed_for_creator_chunk(
+        conn,
+        for_creator_id,
+        for_community_id,
+        new_removed,
+        CREATOR_CHUNK_SIZE,
+      )?;
+      let done = (chunk.len() as i64) < CREATOR_CHUNK_SIZE;
+      res.extend(chunk);
+      if done {
+        return Ok(res);
+      }
+    }
+  }
+
+  /// Removes or restores up to `chunk_size` comments of the creator which don't have the new state
+  /// yet. As already processed comments are skipped, an interrupted run can simply be repeated.
+  pub fn update_removed_for_creator_chunk(
+    conn: &PgConnection,
+    for_creator_id: PersonId,
+    for_community_id: Option<CommunityId>,
+    new_removed: bool,
+    chunk_size: i64,
+  ) -> Result<Vec<Self>, Error> {
+    use crate::schema::{comment::dsl::*, post};
+    conn.transaction(|| {
+      let mut query = comment
+        .filter(creator_id.eq(for_creator_id))
+        .filter(removed.ne(new_removed))
+        .into_boxed();
+      if let Some(for_community_id) = for_community_id {
+        let community_posts = post::table
+          .filter(post::community_id.eq(for_community_id))
+          .select(post::id);
+        query = query.filter(post_id.eq_any(community_posts));
+      }
+      let ids = query
+        .select(id)
+        .order_by(id)
+        .limit(chunk_size)
+        .load::<CommentId>(conn)?;
+      diesel::update(comment.filter(id.eq_any(ids)))
+        .set((removed.eq(new_removed), updated.eq(naive_now())))
+        .get_results::<Self>(conn)
+    })
+  }
+
   pub fn update_read(
     conn: &PgConnection,
     comment_id: CommentId,
@@ -96,6 +314,17 @@ impl Comment {
       .get_result::<Self>(conn)
   }
 
+  pub fn update_language(
+    conn: &PgConnection,
+    comment_id: CommentId,
+    new_language_id: LanguageId,
+  ) -> Result<Self, Error> {
+    use crate::schema::comment::dsl::*;
+    diesel::update(comment.find(comment_id))
+      .set(language_id.eq(new_language_id))
+      .get_result::<Self>(conn)
+  }
+
   pub fn upsert(conn: &PgConnection, comment_form: &CommentForm) -> Result<Comment, Error> {
     use crate::schema::comment::dsl::*;
     insert_into(comment)
@@ -107,10 +336,10 @@ impl Comment {
   }
   pub fn read_from_apub_id(conn: &PgConnection, object_id: Url) -> Result<Option<Self>, Error> {
     use crate::schema::comment::dsl::*;
-    let object_id: DbUrl = object_id.into();
+    let object_ids = apub_id_variants(object_id);
     Ok(
       comment
-        .filter(ap_id.eq(object_id))
+        .filter(ap_id.eq_any(object_ids))
         .first::<Comment>(conn)
         .ok()
         .map(Into::into),
@@ -177,6 +406,45 @@ impl Likeable for CommentLike {
   }
 }
 
+impl Reactable for CommentReaction {
+  type Form = CommentReactionForm;
+  type IdType = CommentId;
+  fn react(conn: &PgConnection, form: &CommentReactionForm) -> Result<usize, Error> {
+    use crate::schema::comment_reaction::dsl::*;
+    insert_into(comment_reaction)
+      .values(form)
+      .on_conflict((comment_id, person_id, emoji))
+      .do_nothing()
+      .execute(conn)
+  }
+  fn unreact(
+    conn: &PgConnection,
+    person_id: PersonId,
+    comment_id: CommentId,
+    emoji: &str,
+  ) -> Result<usize, Error> {
+    use crate::schema::comment_reaction::dsl;
+    diesel::delete(
+      dsl::comment_reaction
+        .filter(dsl::comment_id.eq(comment_id))
+        .filter(dsl::person_id.eq(person_id))
+        .filter(dsl::emoji.eq(emoji)),
+    )
+    .execute(conn)
+  }
+  fn counts(
+    conn: &PgConnection,
+    comment_ids: Vec<CommentId>,
+  ) -> Result<Vec<(CommentId, ReactionCount)>, Error> {
+    use crate::schema::comment_reaction::dsl;
+    let reactions = dsl::comment_reaction
+      .filter(dsl::comment_id.eq_any(comment_ids))
+      .select((dsl::comment_id, dsl::emoji))
+      .load::<(CommentId, String)>(conn)?;
+    Ok(ReactionCount::count(reactions))
+  }
+}
+
 impl Saveable for CommentSaved {
   type Form = CommentSavedForm;
   fn save(conn: &PgConnection, comment_saved_form: &CommentSavedForm) -> Result<Self, Error> {
@@ -199,6 +467,26 @@ impl Saveable for CommentSaved {
   }
 }
 
+impl CommentSaved {
+  /// Files an already saved comment into the given collection, or back into the unfiled list if
+  /// `new_collection_id` is `None`.
+  pub fn move_to_collection(
+    conn: &PgConnection,
+    for_person_id: PersonId,
+    for_comment_id: CommentId,
+    new_collection_id: Option<SavedCollectionId>,
+  ) -> Result<Self, Error> {
+    use crate::schema::comment_saved::dsl::*;
+    diesel::update(
+      comment_saved
+        .filter(person_id.eq(for_person_id))
+        .filter(comment_id.eq(for_comment_id)),
+    )
+    .set(collection_id.eq(new_collection_id))
+    .get_result::<Self>(conn)
+  }
+}
+
 impl DeleteableOrRemoveable for Comment {
   fn blank_out_deleted_or_removed_info(mut self) -> Self {
     self.content = "".into();
@@ -210,9 +498,12 @@ impl DeleteableOrRemoveable for Comment {
 mod tests {
   use crate::{
     establish_unpooled_connection,
+    naive_now,
+    newtypes::CommentId,
     source::{
       comment::*,
       community::{Community, CommunityForm},
+      moderator::{ModRemoveComment, ModRemoveCommentForm},
       person::{Person, PersonForm},
       post::*,
     },
@@ -271,6 +562,8 @@ mod tests {
       updated: None,
       ap_id: inserted_comment.ap_id.to_owned(),
       local: true,
+      path: format!("0.{}", inserted_comment.id),
+      language_id: Default::default(),
     };
 
     let child_comment_form = CommentForm {
@@ -283,6 +576,21 @@ mod tests {
 
     let inserted_child_comment = Comment::create(&conn, &child_comment_form).unwrap();
 
+    let grandchild_comment_form = CommentForm {
+      content: "A grandchild comment".into(),
+      creator_id: inserted_person.id,
+      post_id: inserted_post.id,
+      parent_id: Some(inserted_child_comment.id),
+      ..CommentForm::default()
+    };
+
+    let inserted_grandchild_comment = Comment::create(&conn, &grandchild_comment_form).unwrap();
+    let subtree = Comment::read_subtree(&conn, &inserted_comment, 10).unwrap();
+    let limited_subtree = Comment::read_subtree(&conn, &inserted_comment, 1).unwrap();
+    let subtree_count = Comment::count_subtree(&conn, &inserted_comment).unwrap();
+    let removed_subtree =
+      Comment::update_removed_for_subtree(&conn, &inserted_child_comment, true).unwrap();
+
     // Comment Like
     let comment_like_form = CommentLikeForm {
       comment_id: inserted_comment.id,
@@ -306,6 +614,7 @@ mod tests {
     let comment_saved_form = CommentSavedForm {
       comment_id: inserted_comment.id,
       person_id: inserted_person.id,
+      collection_id: None,
     };
 
     let inserted_comment_saved = CommentSaved::save(&conn, &comment_saved_form).unwrap();
@@ -315,6 +624,7 @@ mod tests {
       comment_id: inserted_comment.id,
       person_id: inserted_person.id,
       published: inserted_comment_saved.published,
+      collection_id: None,
     };
 
     let read_comment = Comment::read(&conn, inserted_comment.id).unwrap();
@@ -322,6 +632,7 @@ mod tests {
     let like_removed = CommentLike::remove(&conn, inserted_person.id, inserted_comment.id).unwrap();
     let saved_removed = CommentSaved::unsave(&conn, &comment_saved_form).unwrap();
     let num_deleted = Comment::delete(&conn, inserted_comment.id).unwrap();
+    Comment::delete(&conn, inserted_grandchild_comment.id).unwrap();
     Comment::delete(&conn, inserted_child_comment.id).unwrap();
     Post::delete(&conn, inserted_post.id).unwrap();
     Community::delete(&conn, inserted_community.id).unwrap();
@@ -336,8 +647,337 @@ mod tests {
       expected_comment.id,
       inserted_child_comment.parent_id.unwrap()
     );
+    assert_eq!(
+      format!("0.{}.{}", inserted_comment.id, inserted_child_comment.id),
+      inserted_child_comment.path
+    );
+    assert_eq!(0, inserted_comment.depth());
+    assert_eq!(2, inserted_grandchild_comment.depth());
+    assert_eq!(
+      vec![inserted_child_comment.id, inserted_grandchild_comment.id],
+      subtree.iter().map(|c| c.id).collect::<Vec<_>>()
+    );
+    assert_eq!(1, limited_subtree.len());
+    assert_eq!(2, subtree_count);
+    assert_eq!(1, removed_subtree.len());
+    assert_eq!(inserted_grandchild_comment.id, removed_subtree[0].id);
+    assert!(removed_subtree[0].removed);
     assert_eq!(1, like_removed);
     assert_eq!(1, saved_removed);
     assert_eq!(1, num_deleted);
   }
+
+  #[test]
+  #[serial]
+  fn test_restore_subtree() {
+    let conn = establish_unpooled_connection();
+
+    let person_form = PersonForm {
+      name: "subtree_restorer".into(),
+      ..PersonForm::default()
+    };
+    let person = Person::create(&conn, &person_form).unwrap();
+    let other_mod_form = PersonForm {
+      name: "subtree_other_mod".into(),
+      ..PersonForm::default()
+    };
+    let other_mod = Person::create(&conn, &other_mod_form).unwrap();
+
+    let community_form = CommunityForm {
+      name: "subtree_restore".to_string(),
+      title: "nada".to_owned(),
+      ..CommunityForm::default()
+    };
+    let community = Community::create(&conn, &community_form).unwrap();
+
+    let post_form = PostForm {
+      name: "A test post".into(),
+      creator_id: person.id,
+      community_id: community.id,
+      ..PostForm::default()
+    };
+    let post = Post::create(&conn, &post_form).unwrap();
+
+    let create_comment = |parent_id| {
+      let form = CommentForm {
+        content: "A comment".into(),
+        creator_id: person.id,
+        post_id: post.id,
+        parent_id,
+        ..CommentForm::default()
+      };
+      Comment::create(&conn, &form).unwrap()
+    };
+    let remove = |mod_person_id, comment: &Comment, reason: &str| {
+      Comment::update_removed(&conn, comment.id, true).unwrap();
+      let form = ModRemoveCommentForm {
+        mod_person_id,
+        comment_id: comment.id,
+        reason: Some(reason.to_string()),
+        removed: Some(true),
+        reason_translations: None,
+        reason_override: false,
+        public: None,
+      };
+      ModRemoveComment::create(&conn, &form).unwrap()
+    };
+
+    let parent = create_comment(None);
+    let removed_with_parent = create_comment(Some(parent.id));
+    let removed_before = create_comment(Some(parent.id));
+    let removed_after = create_comment(Some(removed_with_parent.id));
+
+    remove(other_mod.id, &removed_before, "off topic");
+    let parent_removal = remove(person.id, &parent, "spam");
+    let cascaded = Comment::update_removed_for_subtree(&conn, &parent, true).unwrap();
+    for c in &cascaded {
+      remove(person.id, c, "spam");
+    }
+    remove(other_mod.id, &removed_after, "off topic");
+
+    let restored = Comment::restore_subtree(&conn, &parent, &parent_removal).unwrap();
+    let read_removed_before = Comment::read(&conn, removed_before.id).unwrap();
+    let read_removed_after = Comment::read(&conn, removed_after.id).unwrap();
+
+    Post::delete(&conn, post.id).unwrap();
+    Community::delete(&conn, community.id).unwrap();
+    Person::delete(&conn, person.id).unwrap();
+    Person::delete(&conn, other_mod.id).unwrap();
+
+    // The subtree removal skips the reply which was already removed
+    let mut cascaded_ids = cascaded.iter().map(|c| c.id).collect::<Vec<_>>();
+    cascaded_ids.sort_by_key(|i| i.0);
+    let mut expected_cascaded_ids = vec![removed_with_parent.id, removed_after.id];
+    expected_cascaded_ids.sort_by_key(|i| i.0);
+    assert_eq!(expected_cascaded_ids, cascaded_ids);
+
+    assert_eq!(
+      vec![removed_with_parent.id],
+      restored.iter().map(|c| c.id).collect::<Vec<_>>()
+    );
+    assert!(!restored[0].removed);
+    assert!(read_removed_before.removed);
+    assert!(read_removed_after.removed);
+  }
+
+  #[test]
+  #[serial]
+  fn test_update_removed_for_creator_chunks() {
+    let conn = establish_unpooled_connection();
+
+    let person_form = PersonForm {
+      name: "chunked_spammer".into(),
+      ..PersonForm::default()
+    };
+    let person = Person::create(&conn, &person_form).unwrap();
+    let community_form = CommunityForm {
+      name: "chunked_spam".to_string(),
+      title: "nada".to_owned(),
+      ..CommunityForm::default()
+    };
+    let community = Community::create(&conn, &community_form).unwrap();
+    let post_form = PostForm {
+      name: "spam".into(),
+      creator_id: person.id,
+      community_id: community.id,
+      ..PostForm::default()
+    };
+    let post = Post::create(&conn, &post_form).unwrap();
+    let comments = (0..5)
+      .map(|_| {
+        let form = CommentForm {
+          content: "spam".into(),
+          creator_id: person.id,
+          post_id: post.id,
+          ..CommentForm::default()
+        };
+        Comment::create(&conn, &form).unwrap()
+      })
+      .collect::<Vec<_>>();
+    // Removed separately before, so it is skipped
+    Comment::update_removed(&conn, comments[1].id, true).unwrap();
+
+    let mut chunks = vec![];
+    loop {
+      let chunk =
+        Comment::update_removed_for_creator_chunk(&conn, person.id, None, true, 2).unwrap();
+      if chunk.is_empty() {
+        break;
+      }
+      let mut ids = chunk.into_iter().map(|c| c.id).collect::<Vec<_>>();
+      ids.sort_by_key(|id| id.0);
+      chunks.push(ids);
+    }
+    // Interrupting and repeating doesn't process any comment twice
+    let repeated = Comment::update_removed_for_creator(&conn, person.id, None, true).unwrap();
+
+    Post::delete(&conn, post.id).unwrap();
+    Community::delete(&conn, community.id).unwrap();
+    Person::delete(&conn, person.id).unwrap();
+
+    assert_eq!(
+      vec![
+        vec![comments[0].id, comments[2].id],
+        vec![comments[3].id, comments[4].id],
+      ],
+      chunks
+    );
+    assert!(repeated.is_empty());
+  }
+
+  #[test]
+  #[serial]
+  fn test_update_removed_for_creator_in_community() {
+    let conn = establish_unpooled_connection();
+
+    let person_form = PersonForm {
+      name: "community_spammer".into(),
+      ..PersonForm::default()
+    };
+    let person = Person::create(&conn, &person_form).unwrap();
+    let create_post = |name: &str| {
+      let community_form = CommunityForm {
+        name: name.to_string(),
+        title: "nada".to_owned(),
+        ..CommunityForm::default()
+      };
+      let community = Community::create(&conn, &community_form).unwrap();
+      let post_form = PostForm {
+        name: "spam".into(),
+        creator_id: person.id,
+        community_id: community.id,
+        ..PostForm::default()
+      };
+      Post::create(&conn, &post_form).unwrap()
+    };
+    let spammed_post = create_post("spammed_community");
+    let other_post = create_post("other_community");
+    let create_comment = |post: &Post| {
+      let form = CommentForm {
+        content: "spam".into(),
+        creator_id: person.id,
+        post_id: post.id,
+        ..CommentForm::default()
+      };
+      Comment::create(&conn, &form).unwrap()
+    };
+    let spammed_comment = create_comment(&spammed_post);
+    let other_comment = create_comment(&other_post);
+
+    let removed =
+      Comment::update_removed_for_creator(&conn, person.id, Some(spammed_post.community_id), true)
+        .unwrap();
+    let read_other_comment = Comment::read(&conn, other_comment.id).unwrap();
+
+    Community::delete(&conn, spammed_post.community_id).unwrap();
+    Community::delete(&conn, other_post.community_id).unwrap();
+    Person::delete(&conn, person.id).unwrap();
+
+    assert_eq!(
+      vec![spammed_comment.id],
+      removed.iter().map(|c| c.id).collect::<Vec<_>>()
+    );
+    assert!(removed[0].removed);
+    assert!(!read_other_comment.removed);
+  }
+
+  #[test]
+  #[serial]
+  fn test_purge_expired() {
+    use crate::{
+      schema::{comment, post},
+      source::comment_report::{CommentReport, CommentReportForm},
+      traits::Reportable,
+    };
+    use diesel::{ExpressionMethods, QueryDsl, RunQueryDsl};
+
+    let conn = establish_unpooled_connection();
+
+    let new_person = PersonForm {
+      name: "purge_expired".into(),
+      ..PersonForm::default()
+    };
+    let person = Person::create(&conn, &new_person).unwrap();
+    let new_community = CommunityForm {
+      name: "purge_expired".to_string(),
+      title: "nada".to_owned(),
+      ..CommunityForm::default()
+    };
+    let community = Community::create(&conn, &new_community).unwrap();
+    let new_post = PostForm {
+      name: "A post which is purged".into(),
+      creator_id: person.id,
+      community_id: community.id,
+      deleted: Some(true),
+      ..PostForm::default()
+    };
+    let post = Post::create(&conn, &new_post).unwrap();
+
+    let create_comment = |parent_id: Option<CommentId>, deleted: bool, removed: bool| {
+      let form = CommentForm {
+        content: "purge".into(),
+        creator_id: person.id,
+        post_id: post.id,
+        parent_id,
+        deleted: Some(deleted),
+        removed: Some(removed),
+        ..CommentForm::default()
+      };
+      Comment::create(&conn, &form).unwrap()
+    };
+    let with_reply = create_comment(None, true, false);
+    let reply = create_comment(Some(with_reply.id), true, false);
+    let reported = create_comment(None, true, false);
+    let removed = create_comment(None, false, true);
+    let kept = create_comment(None, false, false);
+    let report_form = CommentReportForm {
+      creator_id: person.id,
+      comment_id: reported.id,
+      original_comment_text: "purge".into(),
+      reason: "legal hold".into(),
+    };
+    CommentReport::report(&conn, &report_form).unwrap();
+
+    let long_ago = naive_now() - chrono::Duration::days(100);
+    let before = naive_now() - chrono::Duration::days(90);
+    diesel::update(comment::table.filter(comment::post_id.eq(post.id)))
+      .set(comment::updated.eq(long_ago))
+      .execute(&conn)
+      .unwrap();
+    diesel::update(post::table.find(post.id))
+      .set(post::updated.eq(long_ago))
+      .execute(&conn)
+      .unwrap();
+
+    // First the reply is purged, and its parent only in the next run
+    let purged_1 = Comment::purge_expired(&conn, false, before).unwrap();
+    let purged_2 = Comment::purge_expired(&conn, false, before).unwrap();
+    // The post still has comments
+    let purged_posts_1 = Post::purge_expired(&conn, false, before).unwrap();
+    let purged_removed = Comment::purge_expired(&conn, true, before).unwrap();
+    let remaining: Vec<CommentId> = comment::table
+      .filter(comment::post_id.eq(post.id))
+      .select(comment::id)
+      .order_by(comment::id)
+      .load(&conn)
+      .unwrap();
+
+    Comment::delete(&conn, reported.id).unwrap();
+    Comment::delete(&conn, kept.id).unwrap();
+    let purged_posts_2 = Post::purge_expired(&conn, false, before).unwrap();
+    let post_exists = Post::read(&conn, post.id).is_ok();
+
+    Community::delete(&conn, community.id).unwrap();
+    Person::delete(&conn, person.id).unwrap();
+
+    assert_eq!(1, purged_1);
+    assert_eq!(1, purged_2);
+    assert_eq!(0, purged_posts_1);
+    assert_eq!(1, purged_removed);
+    assert_eq!(vec![reported.id, kept.id], remaining);
+    assert!(Comment::read(&conn, reply.id).is_err());
+    assert!(Comment::read(&conn, removed.id).is_err());
+    assert_eq!(1, purged_posts_2);
+    assert!(!post_exists);
+  }
 }
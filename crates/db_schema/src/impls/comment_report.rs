@@ -1,6 +1,6 @@
 use crate::{
   naive_now,
-  newtypes::{CommentReportId, PersonId},
+  newtypes::{CommentId, CommentReportId, PersonId},
   source::comment_report::{CommentReport, CommentReportForm},
   traits::Reportable,
 };
@@ -25,16 +25,19 @@ impl Reportable for CommentReport {
   /// * `conn` - the postgres connection
   /// * `report_id` - the id of the report to resolve
   /// * `by_resolver_id` - the id of the user resolving the report
+  /// * `note` - an optional explanation of the resolution
   fn resolve(
     conn: &PgConnection,
     report_id: Self::IdType,
     by_resolver_id: PersonId,
+    note: Option<String>,
   ) -> Result<usize, Error> {
     use crate::schema::comment_report::dsl::*;
     update(comment_report.find(report_id))
       .set((
         resolved.eq(true),
         resolver_id.eq(by_resolver_id),
+        resolution_note.eq(note),
         updated.eq(naive_now()),
       ))
       .execute(conn)
@@ -55,8 +58,49 @@ impl Reportable for CommentReport {
       .set((
         resolved.eq(false),
         resolver_id.eq(by_resolver_id),
+        resolution_note.eq(None::<String>),
         updated.eq(naive_now()),
       ))
       .execute(conn)
   }
+
+  /// escalates a comment report to the admins
+  ///
+  /// * `conn` - the postgres connection
+  /// * `report_id` - the id of the report to escalate
+  fn escalate(conn: &PgConnection, report_id: Self::IdType) -> Result<usize, Error> {
+    use crate::schema::comment_report::dsl::*;
+    update(comment_report.find(report_id))
+      .set((escalated.eq(true), updated.eq(naive_now())))
+      .execute(conn)
+  }
+}
+
+impl CommentReport {
+  /// resolves all open reports of a comment, for example once it has been removed
+  ///
+  /// * `conn` - the postgres connection
+  /// * `for_comment_id` - the id of the reported comment
+  /// * `by_resolver_id` - the id of the user resolving the reports
+  /// * `note` - an optional explanation of the resolution
+  pub fn resolve_all_for_comment(
+    conn: &PgConnection,
+    for_comment_id: CommentId,
+    by_resolver_id: PersonId,
+    note: Option<String>,
+  ) -> Result<usize, Error> {
+    use crate::schema::comment_report::dsl::*;
+    update(
+      comment_report
+        .filter(comment_id.eq(for_comment_id))
+        .filter(resolved.eq(false)),
+    )
+    .set((
+      resolved.eq(true),
+      resolver_id.eq(by_resolver_id),
+      resolution_note.eq(note),
+      updated.eq(naive_now()),
+    ))
+    .execute(conn)
+  }
 }
@@ -0,0 +1,132 @@
+use crate::{
+  newtypes::{CommentId, PersonId},
+  source::{comment::Comment, comment_revision::*},
+};
+use diesel::{dsl::*, result::Error, *};
+
+impl CommentRevision {
+  /// Stores the current content of the comment, so that it is kept when the comment is edited
+  pub fn save_current(conn: &PgConnection, comment: &Comment) -> Result<Self, Error> {
+    use crate::schema::comment_revision::dsl::*;
+    let form = CommentRevisionForm {
+      comment_id: comment.id,
+      content: comment.content.clone(),
+      published: comment.updated.unwrap_or(comment.published),
+    };
+    insert_into(comment_revision)
+      .values(form)
+      .get_result::<Self>(conn)
+  }
+
+  /// Keeps the current content in the edit history, if the comment is about to be changed
+  pub fn save_if_changed(
+    conn: &PgConnection,
+    comment: &Comment,
+    new_content: &str,
+  ) -> Result<Option<Self>, Error> {
+    if comment.content == new_content {
+      return Ok(None);
+    }
+    Self::save_current(conn, comment).map(Some)
+  }
+
+  /// Oldest first
+  pub fn list_for_comment(
+    conn: &PgConnection,
+    for_comment_id: CommentId,
+  ) -> Result<Vec<Self>, Error> {
+    use crate::schema::comment_revision::dsl::*;
+    comment_revision
+      .filter(comment_id.eq(for_comment_id))
+      .order_by(published.asc())
+      .then_order_by(id.asc())
+      .load::<Self>(conn)
+  }
+
+  pub fn count_for_comment(conn: &PgConnection, for_comment_id: CommentId) -> Result<i64, Error> {
+    use crate::schema::comment_revision::dsl::*;
+    comment_revision
+      .filter(comment_id.eq(for_comment_id))
+      .select(count_star())
+      .first::<i64>(conn)
+  }
+
+  /// Removes the edit history of all comments by the creator
+  pub fn delete_for_creator(conn: &PgConnection, for_creator_id: PersonId) -> Result<usize, Error> {
+    use crate::schema::{comment, comment_revision::dsl::*};
+    let creator_comments = comment::table
+      .filter(comment::creator_id.eq(for_creator_id))
+      .select(comment::id);
+    delete(comment_revision.filter(comment_id.eq_any(creator_comments))).execute(conn)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::{
+    establish_unpooled_connection,
+    source::{comment::*, comment_revision::CommentRevision, community::*, person::*, post::*},
+    traits::Crud,
+  };
+  use serial_test::serial;
+
+  #[test]
+  #[serial]
+  fn test_revisions() {
+    let conn = establish_unpooled_connection();
+
+    let person_form = PersonForm {
+      name: "comment_revision_creator".into(),
+      ..PersonForm::default()
+    };
+    let person = Person::create(&conn, &person_form).unwrap();
+    let community_form = CommunityForm {
+      name: "comment_revision_community".to_string(),
+      title: "nada".to_owned(),
+      ..CommunityForm::default()
+    };
+    let community = Community::create(&conn, &community_form).unwrap();
+    let post_form = PostForm {
+      name: "A test post".into(),
+      creator_id: person.id,
+      community_id: community.id,
+      ..PostForm::default()
+    };
+    let post = Post::create(&conn, &post_form).unwrap();
+    let comment_form = CommentForm {
+      content: "first version".into(),
+      creator_id: person.id,
+      post_id: post.id,
+      ..CommentForm::default()
+    };
+    let comment = Comment::create(&conn, &comment_form).unwrap();
+
+    CommentRevision::save_if_changed(&conn, &comment, "second version").unwrap();
+    let edited = Comment::update_content(&conn, comment.id, "second version").unwrap();
+    let unchanged = CommentRevision::save_if_changed(&conn, &edited, "second version").unwrap();
+    CommentRevision::save_if_changed(&conn, &edited, "third version").unwrap();
+    Comment::update_content(&conn, comment.id, "third version").unwrap();
+
+    let revisions = CommentRevision::list_for_comment(&conn, comment.id).unwrap();
+    let count = CommentRevision::count_for_comment(&conn, comment.id).unwrap();
+    let num_deleted = CommentRevision::delete_for_creator(&conn, person.id).unwrap();
+    let count_after_delete = CommentRevision::count_for_comment(&conn, comment.id).unwrap();
+
+    Community::delete(&conn, community.id).unwrap();
+    Person::delete(&conn, person.id).unwrap();
+
+    assert_eq!(
+      vec!["first version", "second version"],
+      revisions
+        .iter()
+        .map(|r| r.content.as_str())
+        .collect::<Vec<_>>()
+    );
+    assert_eq!(comment.published, revisions[0].published);
+    assert_eq!(edited.updated, Some(revisions[1].published));
+    assert!(unchanged.is_none());
+    assert_eq!(2, count);
+    assert_eq!(2, num_deleted);
+    assert_eq!(0, count_after_delete);
+  }
+}
@@ -1,4 +1,5 @@
 use crate::{
+  apub_id_variants,
   naive_now,
   newtypes::{CommunityId, DbUrl, PersonId},
   source::community::{
@@ -14,7 +15,16 @@ use crate::{
   },
   traits::{Bannable, Crud, DeleteableOrRemoveable, Followable, Joinable},
 };
-use diesel::{dsl::*, result::Error, ExpressionMethods, PgConnection, QueryDsl, RunQueryDsl};
+use chrono::NaiveDateTime;
+use diesel::{
+  dsl::*,
+  result::Error,
+  Connection,
+  ExpressionMethods,
+  PgConnection,
+  QueryDsl,
+  RunQueryDsl,
+};
 use url::Url;
 
 mod safe_type {
@@ -34,6 +44,8 @@ mod safe_type {
     local,
     icon,
     banner,
+    require_post_approval,
+    category_id,
   );
 
   impl ToSafe for Community {
@@ -53,6 +65,8 @@ mod safe_type {
         local,
         icon,
         banner,
+        require_post_approval,
+        category_id,
       )
     }
   }
@@ -99,6 +113,18 @@ impl Community {
       .first::<Self>(conn)
   }
 
+  /// Only local communities, as followers of remote communities aren't known
+  pub fn read_from_followers_url(
+    conn: &PgConnection,
+    followers_url_: &DbUrl,
+  ) -> Result<Community, Error> {
+    use crate::schema::community::dsl::*;
+    community
+      .filter(local.eq(true))
+      .filter(followers_url.eq(followers_url_))
+      .first::<Self>(conn)
+  }
+
   pub fn update_deleted(
     conn: &PgConnection,
     community_id: CommunityId,
@@ -137,10 +163,10 @@ impl Community {
   }
   pub fn read_from_apub_id(conn: &PgConnection, object_id: Url) -> Result<Option<Self>, Error> {
     use crate::schema::community::dsl::*;
-    let object_id: DbUrl = object_id.into();
+    let object_ids = apub_id_variants(object_id);
     Ok(
       community
-        .filter(actor_id.eq(object_id))
+        .filter(actor_id.eq_any(object_ids))
         .first::<Community>(conn)
         .ok()
         .map(Into::into),
@@ -203,6 +229,31 @@ impl CommunityModerator {
     diesel::delete(community_moderator.filter(community_id.eq(for_community_id))).execute(conn)
   }
 
+  /// Moves the given mod to the top of the community's mod list, keeping the order of the others
+  pub fn set_top_moderator(
+    conn: &PgConnection,
+    for_community_id: CommunityId,
+    for_person_id: PersonId,
+  ) -> Result<Self, Error> {
+    use crate::schema::community_moderator::dsl::*;
+    conn.transaction(|| {
+      let moderator = community_moderator
+        .filter(community_id.eq(for_community_id))
+        .filter(person_id.eq(for_person_id))
+        .first::<Self>(conn)?;
+      diesel::update(
+        community_moderator
+          .filter(community_id.eq(for_community_id))
+          .filter(rank.lt(moderator.rank)),
+      )
+      .set(rank.eq(rank + 1))
+      .execute(conn)?;
+      diesel::update(community_moderator.find(moderator.id))
+        .set(rank.eq(0))
+        .get_result::<Self>(conn)
+    })
+  }
+
   pub fn get_person_moderated_communities(
     conn: &PgConnection,
     for_person_id: PersonId,
@@ -241,6 +292,16 @@ impl Bannable for CommunityPersonBan {
   }
 }
 
+impl CommunityPersonBan {
+  /// Bans with an expiry time which has passed, and which should be lifted
+  pub fn list_expired(conn: &PgConnection, before: NaiveDateTime) -> Result<Vec<Self>, Error> {
+    use crate::schema::community_person_ban::dsl::*;
+    community_person_ban
+      .filter(expires.lt(before))
+      .load::<Self>(conn)
+  }
+}
+
 impl Followable for CommunityFollower {
   type Form = CommunityFollowerForm;
   fn follow(
@@ -299,10 +360,13 @@ impl Followable for CommunityFollower {
 mod tests {
   use crate::{
     establish_unpooled_connection,
+    naive_now,
     source::{community::*, person::*},
     traits::{Bannable, Crud, Followable, Joinable},
   };
+  use chrono::Duration;
   use serial_test::serial;
+  use url::Url;
 
   #[test]
   #[serial]
@@ -345,6 +409,8 @@ mod tests {
       followers_url: inserted_community.followers_url.to_owned(),
       inbox_url: inserted_community.inbox_url.to_owned(),
       shared_inbox_url: None,
+      require_post_approval: false,
+      category_id: None,
     };
 
     let community_follower_form = CommunityFollowerForm {
@@ -377,11 +443,28 @@ mod tests {
       community_id: inserted_community.id,
       person_id: inserted_person.id,
       published: inserted_community_moderator.published,
+      rank: 0,
     };
 
+    let new_person_2 = PersonForm {
+      name: "bobbee_2".into(),
+      ..PersonForm::default()
+    };
+    let inserted_person_2 = Person::create(&conn, &new_person_2).unwrap();
+    let community_moderator_form_2 = CommunityModeratorForm {
+      community_id: inserted_community.id,
+      person_id: inserted_person_2.id,
+    };
+    let inserted_community_moderator_2 =
+      CommunityModerator::join(&conn, &community_moderator_form_2).unwrap();
+    let new_top_moderator =
+      CommunityModerator::set_top_moderator(&conn, inserted_community.id, inserted_person_2.id)
+        .unwrap();
+
     let community_person_ban_form = CommunityPersonBanForm {
       community_id: inserted_community.id,
       person_id: inserted_person.id,
+      expires: None,
     };
 
     let inserted_community_person_ban =
@@ -392,6 +475,7 @@ mod tests {
       community_id: inserted_community.id,
       person_id: inserted_person.id,
       published: inserted_community_person_ban.published,
+      expires: None,
     };
 
     let read_community = Community::read(&conn, inserted_community.id).unwrap();
@@ -402,12 +486,16 @@ mod tests {
     let unban = CommunityPersonBan::unban(&conn, &community_person_ban_form).unwrap();
     let num_deleted = Community::delete(&conn, inserted_community.id).unwrap();
     Person::delete(&conn, inserted_person.id).unwrap();
+    Person::delete(&conn, inserted_person_2.id).unwrap();
 
     assert_eq!(expected_community, read_community);
     assert_eq!(expected_community, inserted_community);
     assert_eq!(expected_community, updated_community);
     assert_eq!(expected_community_follower, inserted_community_follower);
     assert_eq!(expected_community_moderator, inserted_community_moderator);
+    assert_eq!(1, inserted_community_moderator_2.rank);
+    assert_eq!(inserted_person_2.id, new_top_moderator.person_id);
+    assert_eq!(0, new_top_moderator.rank);
     assert_eq!(expected_community_person_ban, inserted_community_person_ban);
     assert_eq!(1, ignored_community);
     assert_eq!(1, left_community);
@@ -415,4 +503,90 @@ mod tests {
     // assert_eq!(2, loaded_count);
     assert_eq!(1, num_deleted);
   }
+
+  #[test]
+  #[serial]
+  fn test_read_from_apub_id_non_canonical() {
+    let conn = establish_unpooled_connection();
+
+    let actor_id = Url::parse("https://enterprise.lemmy.ml/c/canonical").unwrap();
+    let new_community = CommunityForm {
+      name: "canonical".into(),
+      title: "nada".to_owned(),
+      public_key: "nada".to_owned(),
+      actor_id: Some(actor_id.clone().into()),
+      local: Some(false),
+      ..CommunityForm::default()
+    };
+    let inserted_community = Community::create(&conn, &new_community).unwrap();
+
+    let exact = Community::read_from_apub_id(&conn, actor_id).unwrap();
+    let trailing_slash = Community::read_from_apub_id(
+      &conn,
+      Url::parse("https://enterprise.lemmy.ml/c/canonical/").unwrap(),
+    )
+    .unwrap();
+    let host_case = Community::read_from_apub_id(
+      &conn,
+      Url::parse("https://Enterprise.LEMMY.ml/c/canonical").unwrap(),
+    )
+    .unwrap();
+    let other = Community::read_from_apub_id(
+      &conn,
+      Url::parse("https://enterprise.lemmy.ml/c/canonical2").unwrap(),
+    )
+    .unwrap();
+    Community::delete(&conn, inserted_community.id).unwrap();
+
+    assert_eq!(Some(inserted_community.id), exact.map(|c| c.id));
+    assert_eq!(Some(inserted_community.id), trailing_slash.map(|c| c.id));
+    assert_eq!(Some(inserted_community.id), host_case.map(|c| c.id));
+    assert!(other.is_none());
+  }
+
+  #[test]
+  #[serial]
+  fn test_list_expired_bans() {
+    let conn = establish_unpooled_connection();
+
+    let person_form = PersonForm {
+      name: "expired_ban_person".into(),
+      ..PersonForm::default()
+    };
+    let person = Person::create(&conn, &person_form).unwrap();
+    let community_form = |name: &str| CommunityForm {
+      name: name.to_string(),
+      title: "nada".to_owned(),
+      ..CommunityForm::default()
+    };
+    let expired_community = Community::create(&conn, &community_form("expired_ban")).unwrap();
+    let active_community = Community::create(&conn, &community_form("active_ban")).unwrap();
+    let permanent_community = Community::create(&conn, &community_form("permanent_ban")).unwrap();
+
+    let now = naive_now();
+    let ban = |community_id, expires| {
+      let form = CommunityPersonBanForm {
+        community_id,
+        person_id: person.id,
+        expires,
+      };
+      CommunityPersonBan::ban(&conn, &form).unwrap()
+    };
+    let expired_ban = ban(expired_community.id, Some(now - Duration::hours(1)));
+    ban(active_community.id, Some(now + Duration::hours(1)));
+    ban(permanent_community.id, None);
+
+    let list = CommunityPersonBan::list_expired(&conn, now).unwrap();
+
+    for c in [&expired_community, &active_community, &permanent_community] {
+      Community::delete(&conn, c.id).unwrap();
+    }
+    Person::delete(&conn, person.id).unwrap();
+
+    let listed_for_person = list
+      .into_iter()
+      .filter(|b| b.person_id == person.id)
+      .collect::<Vec<_>>();
+    assert_eq!(vec![expired_ban], listed_for_person);
+  }
 }
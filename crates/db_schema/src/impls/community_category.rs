@@ -0,0 +1,99 @@
+use crate::{
+  newtypes::CommunityId,
+  source::{community::Community, community_category::*},
+  traits::Crud,
+};
+use diesel::{dsl::*, result::Error, *};
+
+impl Crud for CommunityCategory {
+  type Form = CommunityCategoryForm;
+  type IdType = i32;
+  fn create(conn: &PgConnection, form: &Self::Form) -> Result<Self, Error> {
+    use crate::schema::community_category::dsl::*;
+    insert_into(community_category)
+      .values(form)
+      .get_result::<Self>(conn)
+  }
+
+  fn read(conn: &PgConnection, id_: Self::IdType) -> Result<Self, Error> {
+    use crate::schema::community_category::dsl::*;
+    community_category.find(id_).first::<Self>(conn)
+  }
+
+  fn update(conn: &PgConnection, id_: Self::IdType, form: &Self::Form) -> Result<Self, Error> {
+    use crate::schema::community_category::dsl::*;
+    diesel::update(community_category.find(id_))
+      .set(form)
+      .get_result::<Self>(conn)
+  }
+
+  /// Communities of the category are left without one
+  fn delete(conn: &PgConnection, id_: Self::IdType) -> Result<usize, Error> {
+    use crate::schema::community_category::dsl::*;
+    diesel::delete(community_category.find(id_)).execute(conn)
+  }
+}
+
+impl CommunityCategory {
+  /// Ordered by name
+  pub fn list(conn: &PgConnection) -> Result<Vec<Self>, Error> {
+    use crate::schema::community_category::dsl::*;
+    community_category.order_by(name.asc()).load::<Self>(conn)
+  }
+
+  /// Assigns the community to the category, or removes it from its category with None
+  pub fn assign(
+    conn: &PgConnection,
+    for_community_id: CommunityId,
+    for_category_id: Option<i32>,
+  ) -> Result<Community, Error> {
+    use crate::schema::community::dsl::*;
+    diesel::update(community.find(for_community_id))
+      .set(category_id.eq(for_category_id))
+      .get_result::<Community>(conn)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::{
+    establish_unpooled_connection,
+    source::{community::*, community_category::*},
+    traits::Crud,
+  };
+  use serial_test::serial;
+
+  #[test]
+  #[serial]
+  fn test_community_category() {
+    let conn = establish_unpooled_connection();
+
+    let new_community = CommunityForm {
+      name: "test_community_category".into(),
+      title: "nada".to_owned(),
+      public_key: "nada".to_owned(),
+      ..CommunityForm::default()
+    };
+    let inserted_community = Community::create(&conn, &new_community).unwrap();
+
+    let form = CommunityCategoryForm {
+      name: "Test Technology".to_string(),
+      updated: None,
+    };
+    let category = CommunityCategory::create(&conn, &form).unwrap();
+    // Names are unique
+    assert!(CommunityCategory::create(&conn, &form).is_err());
+
+    let assigned =
+      CommunityCategory::assign(&conn, inserted_community.id, Some(category.id)).unwrap();
+    let listed = CommunityCategory::list(&conn).unwrap();
+    CommunityCategory::delete(&conn, category.id).unwrap();
+    let after_delete = Community::read(&conn, inserted_community.id).unwrap();
+
+    Community::delete(&conn, inserted_community.id).unwrap();
+
+    assert_eq!(Some(category.id), assigned.category_id);
+    assert!(listed.contains(&category));
+    assert_eq!(None, after_delete.category_id);
+  }
+}
@@ -0,0 +1,188 @@
+use crate::{
+  apub_id_variants,
+  naive_now,
+  newtypes::{CommunityId, DbUrl},
+  source::community_wiki_page::*,
+  traits::Crud,
+};
+use diesel::{dsl::*, result::Error, *};
+use url::Url;
+
+impl Crud for CommunityWikiPage {
+  type Form = CommunityWikiPageForm;
+  type IdType = i32;
+  fn create(conn: &PgConnection, form: &Self::Form) -> Result<Self, Error> {
+    use crate::schema::community_wiki_page::dsl::*;
+    insert_into(community_wiki_page)
+      .values(form)
+      .get_result::<Self>(conn)
+  }
+
+  fn read(conn: &PgConnection, id_: Self::IdType) -> Result<Self, Error> {
+    use crate::schema::community_wiki_page::dsl::*;
+    community_wiki_page.find(id_).first::<Self>(conn)
+  }
+
+  fn update(conn: &PgConnection, id_: Self::IdType, form: &Self::Form) -> Result<Self, Error> {
+    use crate::schema::community_wiki_page::dsl::*;
+    diesel::update(community_wiki_page.find(id_))
+      .set(form)
+      .get_result::<Self>(conn)
+  }
+
+  fn delete(conn: &PgConnection, id_: Self::IdType) -> Result<usize, Error> {
+    use crate::schema::community_wiki_page::dsl::*;
+    diesel::delete(community_wiki_page.find(id_)).execute(conn)
+  }
+}
+
+impl CommunityWikiPage {
+  pub fn update_ap_id(conn: &PgConnection, page_id: i32, apub_id: DbUrl) -> Result<Self, Error> {
+    use crate::schema::community_wiki_page::dsl::*;
+    diesel::update(community_wiki_page.find(page_id))
+      .set(ap_id.eq(apub_id))
+      .get_result::<Self>(conn)
+  }
+
+  pub fn update_content(
+    conn: &PgConnection,
+    page_id: i32,
+    new_content: &str,
+  ) -> Result<Self, Error> {
+    use crate::schema::community_wiki_page::dsl::*;
+    diesel::update(community_wiki_page.find(page_id))
+      .set((content.eq(new_content), updated.eq(naive_now())))
+      .get_result::<Self>(conn)
+  }
+
+  pub fn update_deleted(
+    conn: &PgConnection,
+    page_id: i32,
+    new_deleted: bool,
+  ) -> Result<Self, Error> {
+    use crate::schema::community_wiki_page::dsl::*;
+    diesel::update(community_wiki_page.find(page_id))
+      .set((deleted.eq(new_deleted), updated.eq(naive_now())))
+      .get_result::<Self>(conn)
+  }
+
+  /// Ordered by title, without deleted pages
+  pub fn list_for_community(
+    conn: &PgConnection,
+    for_community_id: CommunityId,
+  ) -> Result<Vec<Self>, Error> {
+    use crate::schema::community_wiki_page::dsl::*;
+    community_wiki_page
+      .filter(community_id.eq(for_community_id))
+      .filter(deleted.eq(false))
+      .order_by(title.asc())
+      .load::<Self>(conn)
+  }
+
+  pub fn upsert(conn: &PgConnection, form: &CommunityWikiPageForm) -> Result<Self, Error> {
+    use crate::schema::community_wiki_page::dsl::*;
+    insert_into(community_wiki_page)
+      .values(form)
+      .on_conflict(ap_id)
+      .do_update()
+      .set(form)
+      .get_result::<Self>(conn)
+  }
+
+  pub fn read_from_apub_id(conn: &PgConnection, object_id: Url) -> Result<Option<Self>, Error> {
+    use crate::schema::community_wiki_page::dsl::*;
+    let object_ids = apub_id_variants(object_id);
+    Ok(
+      community_wiki_page
+        .filter(ap_id.eq_any(object_ids))
+        .first::<Self>(conn)
+        .ok(),
+    )
+  }
+}
+
+impl CommunityWikiPageRevision {
+  pub fn create(conn: &PgConnection, form: &CommunityWikiPageRevisionForm) -> Result<Self, Error> {
+    use crate::schema::community_wiki_page_revision::dsl::*;
+    insert_into(community_wiki_page_revision)
+      .values(form)
+      .get_result::<Self>(conn)
+  }
+
+  /// Newest first
+  pub fn list_for_page(conn: &PgConnection, for_wiki_page_id: i32) -> Result<Vec<Self>, Error> {
+    use crate::schema::community_wiki_page_revision::dsl::*;
+    community_wiki_page_revision
+      .filter(wiki_page_id.eq(for_wiki_page_id))
+      .order_by(published.desc())
+      .then_order_by(id.desc())
+      .load::<Self>(conn)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::{
+    establish_unpooled_connection,
+    source::{community::*, community_wiki_page::*, person::*},
+    traits::Crud,
+  };
+  use serial_test::serial;
+
+  #[test]
+  #[serial]
+  fn test_wiki_page() {
+    let conn = establish_unpooled_connection();
+
+    let person_form = PersonForm {
+      name: "wiki_page_person".into(),
+      ..PersonForm::default()
+    };
+    let person = Person::create(&conn, &person_form).unwrap();
+    let community_form = CommunityForm {
+      name: "wiki_page_community".to_string(),
+      title: "nada".to_owned(),
+      ..CommunityForm::default()
+    };
+    let community = Community::create(&conn, &community_form).unwrap();
+
+    let page_form = |title: &str| CommunityWikiPageForm {
+      community_id: community.id,
+      creator_id: person.id,
+      title: title.to_string(),
+      content: "first version".into(),
+      ..CommunityWikiPageForm::default()
+    };
+    let rules = CommunityWikiPage::create(&conn, &page_form("Rules")).unwrap();
+    let faq = CommunityWikiPage::create(&conn, &page_form("FAQ")).unwrap();
+    // Titles are unique within the community
+    assert!(CommunityWikiPage::create(&conn, &page_form("FAQ")).is_err());
+
+    let revision_form = |content: &str| CommunityWikiPageRevisionForm {
+      wiki_page_id: rules.id,
+      editor_id: person.id,
+      content: content.to_string(),
+    };
+    CommunityWikiPageRevision::create(&conn, &revision_form("first version")).unwrap();
+    let edited = CommunityWikiPage::update_content(&conn, rules.id, "second version").unwrap();
+    CommunityWikiPageRevision::create(&conn, &revision_form("second version")).unwrap();
+    let revisions = CommunityWikiPageRevision::list_for_page(&conn, rules.id).unwrap();
+
+    CommunityWikiPage::update_deleted(&conn, faq.id, true).unwrap();
+    let listed = CommunityWikiPage::list_for_community(&conn, community.id).unwrap();
+
+    Community::delete(&conn, community.id).unwrap();
+    Person::delete(&conn, person.id).unwrap();
+
+    assert_eq!("second version", edited.content);
+    assert!(edited.updated.is_some());
+    assert_eq!(
+      vec!["second version", "first version"],
+      revisions
+        .iter()
+        .map(|r| r.content.as_str())
+        .collect::<Vec<_>>()
+    );
+    assert_eq!(vec![edited], listed);
+  }
+}
@@ -0,0 +1,105 @@
+use crate::{source::custom_emoji::*, traits::Crud};
+use diesel::{dsl::*, result::Error, *};
+
+impl Crud for CustomEmoji {
+  type Form = CustomEmojiForm;
+  type IdType = i32;
+  fn create(conn: &PgConnection, form: &Self::Form) -> Result<Self, Error> {
+    use crate::schema::custom_emoji::dsl::*;
+    insert_into(custom_emoji)
+      .values(form)
+      .get_result::<Self>(conn)
+  }
+
+  fn read(conn: &PgConnection, id_: Self::IdType) -> Result<Self, Error> {
+    use crate::schema::custom_emoji::dsl::*;
+    custom_emoji.find(id_).first::<Self>(conn)
+  }
+
+  fn update(conn: &PgConnection, id_: Self::IdType, form: &Self::Form) -> Result<Self, Error> {
+    use crate::schema::custom_emoji::dsl::*;
+    diesel::update(custom_emoji.find(id_))
+      .set(form)
+      .get_result::<Self>(conn)
+  }
+
+  fn delete(conn: &PgConnection, id_: Self::IdType) -> Result<usize, Error> {
+    use crate::schema::custom_emoji::dsl::*;
+    diesel::delete(custom_emoji.find(id_)).execute(conn)
+  }
+}
+
+impl CustomEmoji {
+  /// Ordered by category and shortcode
+  pub fn list(conn: &PgConnection) -> Result<Vec<Self>, Error> {
+    use crate::schema::custom_emoji::dsl::*;
+    custom_emoji
+      .order_by(category.asc())
+      .then_order_by(shortcode.asc())
+      .load::<Self>(conn)
+  }
+
+  /// Returns the emoji which exist for the given shortcodes, unknown ones are skipped
+  pub fn list_for_shortcodes(
+    conn: &PgConnection,
+    shortcodes: Vec<String>,
+  ) -> Result<Vec<Self>, Error> {
+    use crate::schema::custom_emoji::dsl::*;
+    custom_emoji
+      .filter(shortcode.eq_any(shortcodes))
+      .order_by(shortcode.asc())
+      .load::<Self>(conn)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::{establish_unpooled_connection, source::custom_emoji::*, traits::Crud};
+  use serial_test::serial;
+  use url::Url;
+
+  #[test]
+  #[serial]
+  fn test_custom_emoji() {
+    let conn = establish_unpooled_connection();
+
+    let form = CustomEmojiForm {
+      shortcode: "test_blobcat".to_string(),
+      image_url: Url::parse("https://example.com/emoji/blobcat.png")
+        .unwrap()
+        .into(),
+      category: "Blobs".to_string(),
+      updated: None,
+    };
+    let blobcat = CustomEmoji::create(&conn, &form).unwrap();
+    // Shortcodes are unique
+    assert!(CustomEmoji::create(&conn, &form).is_err());
+
+    let other_form = CustomEmojiForm {
+      shortcode: "test_ablobfox".to_string(),
+      ..form.clone()
+    };
+    let ablobfox = CustomEmoji::create(&conn, &other_form).unwrap();
+    let updated_form = CustomEmojiForm {
+      category: "Animals".to_string(),
+      updated: Some(crate::naive_now()),
+      ..other_form
+    };
+    let ablobfox = CustomEmoji::update(&conn, ablobfox.id, &updated_form).unwrap();
+
+    let listed = CustomEmoji::list(&conn).unwrap();
+    let for_shortcodes = CustomEmoji::list_for_shortcodes(
+      &conn,
+      vec!["test_blobcat".to_string(), "unknown".to_string()],
+    )
+    .unwrap();
+
+    CustomEmoji::delete(&conn, blobcat.id).unwrap();
+    CustomEmoji::delete(&conn, ablobfox.id).unwrap();
+
+    assert_eq!(vec![ablobfox.clone(), blobcat.clone()], listed);
+    assert_eq!(vec![blobcat.clone()], for_shortcodes);
+    assert_eq!("Animals", ablobfox.category);
+    assert!(CustomEmoji::read(&conn, blobcat.id).is_err());
+  }
+}
@@ -0,0 +1,89 @@
+use crate::{newtypes::LocalUserId, source::external_identity::*};
+use diesel::{result::Error, *};
+
+impl ExternalIdentity {
+  pub fn create(conn: &PgConnection, form: &ExternalIdentityForm) -> Result<Self, Error> {
+    use crate::schema::external_identity::dsl::*;
+    insert_into(external_identity)
+      .values(form)
+      .get_result::<Self>(conn)
+  }
+
+  pub fn read_for_subject(
+    conn: &PgConnection,
+    for_provider: &str,
+    for_subject: &str,
+  ) -> Result<Self, Error> {
+    use crate::schema::external_identity::dsl::*;
+    external_identity
+      .filter(provider.eq(for_provider))
+      .filter(subject.eq(for_subject))
+      .first::<Self>(conn)
+  }
+
+  pub fn list_for_local_user(
+    conn: &PgConnection,
+    for_local_user_id: LocalUserId,
+  ) -> Result<Vec<Self>, Error> {
+    use crate::schema::external_identity::dsl::*;
+    external_identity
+      .filter(local_user_id.eq(for_local_user_id))
+      .order_by(published.asc())
+      .load::<Self>(conn)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::{
+    establish_unpooled_connection,
+    source::{external_identity::*, local_user::*, person::*},
+    traits::Crud,
+  };
+  use serial_test::serial;
+
+  #[test]
+  #[serial]
+  fn test_external_identity() {
+    let conn = establish_unpooled_connection();
+
+    let new_person = PersonForm {
+      name: "external_identity_person".into(),
+      ..PersonForm::default()
+    };
+    let inserted_person = Person::create(&conn, &new_person).unwrap();
+    let new_local_user = LocalUserForm {
+      person_id: Some(inserted_person.id),
+      password_encrypted: Some("external_identity_pass".to_string()),
+      ..LocalUserForm::default()
+    };
+    let inserted_local_user = LocalUser::create(&conn, &new_local_user).unwrap();
+
+    let form = ExternalIdentityForm {
+      local_user_id: inserted_local_user.id,
+      provider: "example-idp".to_string(),
+      subject: "248289761001".to_string(),
+    };
+    let inserted_identity = ExternalIdentity::create(&conn, &form).unwrap();
+    // The same account of a provider can only be linked once
+    assert!(ExternalIdentity::create(&conn, &form).is_err());
+
+    let other_provider = ExternalIdentityForm {
+      provider: "other-idp".to_string(),
+      ..form
+    };
+    let other_identity = ExternalIdentity::create(&conn, &other_provider).unwrap();
+
+    assert_eq!(
+      inserted_identity,
+      ExternalIdentity::read_for_subject(&conn, "example-idp", "248289761001").unwrap()
+    );
+    assert!(ExternalIdentity::read_for_subject(&conn, "example-idp", "unknown").is_err());
+    assert_eq!(
+      vec![inserted_identity, other_identity],
+      ExternalIdentity::list_for_local_user(&conn, inserted_local_user.id).unwrap()
+    );
+
+    Person::delete(&conn, inserted_person.id).unwrap();
+  }
+}
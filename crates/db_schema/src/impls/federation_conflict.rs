@@ -0,0 +1,57 @@
+use crate::{newtypes::DbUrl, source::federation_conflict::*};
+use diesel::{insert_into, result::Error, ExpressionMethods, PgConnection, QueryDsl, RunQueryDsl};
+
+impl FederationConflict {
+  pub fn create(conn: &PgConnection, form: &FederationConflictForm) -> Result<Self, Error> {
+    use crate::schema::federation_conflict::dsl::*;
+    insert_into(federation_conflict)
+      .values(form)
+      .get_result::<Self>(conn)
+  }
+
+  /// Newest first
+  pub fn list_for_object(conn: &PgConnection, for_object_id: &DbUrl) -> Result<Vec<Self>, Error> {
+    use crate::schema::federation_conflict::dsl::*;
+    federation_conflict
+      .filter(object_id.eq(for_object_id))
+      .order_by(published.desc())
+      .then_order_by(id.desc())
+      .load::<Self>(conn)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::{
+    establish_unpooled_connection,
+    naive_now,
+    newtypes::DbUrl,
+    source::federation_conflict::*,
+  };
+  use diesel::RunQueryDsl;
+  use serial_test::serial;
+  use url::Url;
+
+  #[test]
+  #[serial]
+  fn test_federation_conflict() {
+    let conn = establish_unpooled_connection();
+
+    let object_id: DbUrl = Url::parse("https://example.com/post/1").unwrap().into();
+    let form = FederationConflictForm {
+      object_id: object_id.clone(),
+      activity_id: Url::parse("https://example.com/activities/update/1")
+        .unwrap()
+        .into(),
+      local_updated: naive_now(),
+      remote_updated: None,
+    };
+    let inserted = FederationConflict::create(&conn, &form).unwrap();
+    let listed = FederationConflict::list_for_object(&conn, &object_id).unwrap();
+    diesel::delete(&inserted).execute(&conn).unwrap();
+
+    assert_eq!(vec![inserted.clone()], listed);
+    assert_eq!(object_id, inserted.object_id);
+    assert_eq!(None, inserted.remote_updated);
+  }
+}
@@ -0,0 +1,157 @@
+use crate::source::federation_instance::*;
+use diesel::{insert_into, result::Error, ExpressionMethods, PgConnection, QueryDsl, RunQueryDsl};
+use lemmy_utils::LemmyError;
+use once_cell::sync::Lazy;
+use std::sync::RwLock;
+
+/// Copy of the federation_instance table, so that checking the id of every incoming object doesn't
+/// need a database query. It has to be refreshed after each change to the table. Changes made by
+/// other processes are picked up by a scheduled task, which refreshes it every minute.
+static CACHED_LISTS: Lazy<RwLock<FederationInstanceLists>> = Lazy::new(Default::default);
+
+impl FederationInstance {
+  /// Adds the instance to the allowlist or blocklist, moving it over if it is already on the other
+  pub fn upsert(conn: &PgConnection, form: &FederationInstanceForm) -> Result<Self, Error> {
+    use crate::schema::federation_instance::dsl::*;
+    insert_into(federation_instance)
+      .values(form)
+      .on_conflict(domain)
+      .do_update()
+      .set(form)
+      .get_result::<Self>(conn)
+  }
+
+  pub fn delete_for_domain(
+    conn: &PgConnection,
+    for_domain: &str,
+    for_blocked: bool,
+  ) -> Result<usize, Error> {
+    use crate::schema::federation_instance::dsl::*;
+    diesel::delete(
+      federation_instance
+        .filter(domain.eq(for_domain))
+        .filter(blocked.eq(for_blocked)),
+    )
+    .execute(conn)
+  }
+
+  pub fn list(conn: &PgConnection) -> Result<Vec<Self>, Error> {
+    use crate::schema::federation_instance::dsl::*;
+    federation_instance
+      .order_by(domain.asc())
+      .load::<Self>(conn)
+  }
+
+  pub fn refresh_cache(conn: &PgConnection) -> Result<(), LemmyError> {
+    let (blocked, allowed): (Vec<_>, Vec<_>) = FederationInstance::list(conn)?
+      .into_iter()
+      .partition(|i| i.blocked);
+    let lists = FederationInstanceLists {
+      allowed: allowed.into_iter().map(|i| i.domain).collect(),
+      blocked: blocked.into_iter().map(|i| i.domain).collect(),
+    };
+    *CACHED_LISTS
+      .write()
+      .map_err(|_| LemmyError::from_message("federation_instance_cache_poisoned"))? = lists;
+    Ok(())
+  }
+
+  pub fn cached_lists() -> Result<FederationInstanceLists, LemmyError> {
+    Ok(
+      CACHED_LISTS
+        .read()
+        .map_err(|_| LemmyError::from_message("federation_instance_cache_poisoned"))?
+        .to_owned(),
+    )
+  }
+}
+
+impl FederationInstanceLists {
+  /// Combines the blocklist from the config file with this one
+  pub fn combined_blocklist(&self, config_blocked: &Option<Vec<String>>) -> Option<Vec<String>> {
+    combine(config_blocked, &self.blocked)
+  }
+
+  /// Combines the allowlist from the config file with this one. Like in the config, the allowlist
+  /// is only active if it contains at least one instance.
+  pub fn combined_allowlist(&self, config_allowed: &Option<Vec<String>>) -> Option<Vec<String>> {
+    combine(config_allowed, &self.allowed)
+  }
+}
+
+fn combine(config: &Option<Vec<String>>, db: &[String]) -> Option<Vec<String>> {
+  if config.is_none() && db.is_empty() {
+    return None;
+  }
+  let mut combined = config.to_owned().unwrap_or_default();
+  combined.extend_from_slice(db);
+  combined.sort_unstable();
+  combined.dedup();
+  Some(combined)
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::{establish_unpooled_connection, source::federation_instance::*};
+  use serial_test::serial;
+
+  #[test]
+  #[serial]
+  fn test_federation_instance() {
+    let conn = establish_unpooled_connection();
+
+    let blocked_form = FederationInstanceForm {
+      domain: "blocked.example.com".to_string(),
+      blocked: true,
+    };
+    let blocked = FederationInstance::upsert(&conn, &blocked_form).unwrap();
+    let allowed_form = FederationInstanceForm {
+      domain: "allowed.example.com".to_string(),
+      blocked: false,
+    };
+    FederationInstance::upsert(&conn, &allowed_form).unwrap();
+    // Blocking an allowed instance removes it from the allowlist
+    let moved_form = FederationInstanceForm {
+      domain: "moved.example.com".to_string(),
+      blocked: false,
+    };
+    FederationInstance::upsert(&conn, &moved_form).unwrap();
+    let moved_form = FederationInstanceForm {
+      blocked: true,
+      ..moved_form
+    };
+    FederationInstance::upsert(&conn, &moved_form).unwrap();
+    FederationInstance::refresh_cache(&conn).unwrap();
+    let lists = FederationInstance::cached_lists().unwrap();
+
+    let not_removed =
+      FederationInstance::delete_for_domain(&conn, "blocked.example.com", false).unwrap();
+    for domain in ["blocked.example.com", "moved.example.com"] {
+      FederationInstance::delete_for_domain(&conn, domain, true).unwrap();
+    }
+    FederationInstance::delete_for_domain(&conn, "allowed.example.com", false).unwrap();
+    FederationInstance::refresh_cache(&conn).unwrap();
+    let lists_after_delete = FederationInstance::cached_lists().unwrap();
+
+    assert!(blocked.blocked);
+    assert_eq!(vec!["allowed.example.com".to_string()], lists.allowed);
+    assert_eq!(
+      vec![
+        "blocked.example.com".to_string(),
+        "moved.example.com".to_string()
+      ],
+      lists.blocked
+    );
+    assert_eq!(0, not_removed);
+    assert_eq!(FederationInstanceLists::default(), lists_after_delete);
+
+    assert_eq!(None, lists_after_delete.combined_allowlist(&None));
+    assert_eq!(
+      Some(vec![
+        "allowed.example.com".to_string(),
+        "config.example.com".to_string()
+      ]),
+      lists.combined_allowlist(&Some(vec!["config.example.com".to_string()]))
+    );
+  }
+}
@@ -0,0 +1,77 @@
+use crate::{hash_token, naive_now, newtypes::LocalUserId, source::feed_token::*};
+use diesel::{dsl::*, result::Error, *};
+
+impl FeedToken {
+  /// Stores the hash of a new feed token for the user. A previous token stops working.
+  pub fn replace(
+    conn: &PgConnection,
+    for_local_user_id: LocalUserId,
+    token: &str,
+  ) -> Result<Self, Error> {
+    use crate::schema::feed_token::dsl::*;
+    let form = FeedTokenForm {
+      local_user_id: for_local_user_id,
+      token_hash: hash_token(token),
+      published: naive_now(),
+    };
+    insert_into(feed_token)
+      .values(&form)
+      .on_conflict(local_user_id)
+      .do_update()
+      .set(&form)
+      .get_result::<Self>(conn)
+  }
+
+  pub fn read_from_token(conn: &PgConnection, token: &str) -> Result<Self, Error> {
+    use crate::schema::feed_token::dsl::*;
+    feed_token
+      .filter(token_hash.eq(hash_token(token)))
+      .first::<Self>(conn)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::{
+    establish_unpooled_connection,
+    source::{feed_token::*, local_user::*, person::*},
+    traits::Crud,
+  };
+  use serial_test::serial;
+
+  #[test]
+  #[serial]
+  fn test_feed_token() {
+    let conn = establish_unpooled_connection();
+
+    let new_person = PersonForm {
+      name: "feed_token_person".into(),
+      ..PersonForm::default()
+    };
+    let inserted_person = Person::create(&conn, &new_person).unwrap();
+    let new_local_user = LocalUserForm {
+      person_id: Some(inserted_person.id),
+      password_encrypted: Some("feed_token_pass".to_string()),
+      ..LocalUserForm::default()
+    };
+    let inserted_local_user = LocalUser::create(&conn, &new_local_user).unwrap();
+
+    let inserted_token = FeedToken::replace(&conn, inserted_local_user.id, "first").unwrap();
+    assert_ne!("first", inserted_token.token_hash);
+    let read_token = FeedToken::read_from_token(&conn, "first").unwrap();
+    assert_eq!(inserted_token, read_token);
+
+    // There is only one feed token per user, the old one stops working
+    let replaced_token = FeedToken::replace(&conn, inserted_local_user.id, "second").unwrap();
+    assert_eq!(inserted_token.id, replaced_token.id);
+    assert!(FeedToken::read_from_token(&conn, "first").is_err());
+    assert_eq!(
+      inserted_local_user.id,
+      FeedToken::read_from_token(&conn, "second")
+        .unwrap()
+        .local_user_id
+    );
+
+    Person::delete(&conn, inserted_person.id).unwrap();
+  }
+}
@@ -0,0 +1,105 @@
+use crate::source::instance::*;
+use diesel::{insert_into, result::Error, ExpressionMethods, PgConnection, QueryDsl, RunQueryDsl};
+
+impl Instance {
+  /// Stores the nodeinfo of the instance, replacing the previously fetched one
+  pub fn upsert(conn: &PgConnection, form: &InstanceForm) -> Result<Self, Error> {
+    use crate::schema::instance::dsl::*;
+    insert_into(instance)
+      .values(form)
+      .on_conflict(domain)
+      .do_update()
+      .set(form)
+      .get_result::<Self>(conn)
+  }
+
+  /// Stores the metadata which the instance federated with its instance actor
+  pub fn upsert_actor(conn: &PgConnection, form: &InstanceActorForm) -> Result<Self, Error> {
+    use crate::schema::instance::dsl::*;
+    insert_into(instance)
+      .values(form)
+      .on_conflict(domain)
+      .do_update()
+      .set(form)
+      .get_result::<Self>(conn)
+  }
+
+  pub fn update_blocks_us(
+    conn: &PgConnection,
+    for_domain: &str,
+    for_blocks_us: bool,
+  ) -> Result<Self, Error> {
+    use crate::schema::instance::dsl::*;
+    insert_into(instance)
+      .values((domain.eq(for_domain), blocks_us.eq(for_blocks_us)))
+      .on_conflict(domain)
+      .do_update()
+      .set(blocks_us.eq(for_blocks_us))
+      .get_result::<Self>(conn)
+  }
+
+  pub fn read_for_domain(conn: &PgConnection, for_domain: &str) -> Result<Self, Error> {
+    use crate::schema::instance::dsl::*;
+    instance.filter(domain.eq(for_domain)).first::<Self>(conn)
+  }
+
+  pub fn list(conn: &PgConnection) -> Result<Vec<Self>, Error> {
+    use crate::schema::instance::dsl::*;
+    instance.order_by(domain.asc()).load::<Self>(conn)
+  }
+
+  pub fn delete_for_domain(conn: &PgConnection, for_domain: &str) -> Result<usize, Error> {
+    use crate::schema::instance::dsl::*;
+    diesel::delete(instance.filter(domain.eq(for_domain))).execute(conn)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::{establish_unpooled_connection, naive_now, source::instance::*};
+  use serial_test::serial;
+
+  #[test]
+  #[serial]
+  fn test_instance() {
+    let conn = establish_unpooled_connection();
+
+    let form = InstanceForm {
+      domain: "nodeinfo.example.com".to_string(),
+      software: Some("lemmy".to_string()),
+      version: Some("0.14.0".to_string()),
+      users: Some(100),
+      users_active_month: Some(10),
+      updated: Some(naive_now()),
+    };
+    let inserted = Instance::upsert(&conn, &form).unwrap();
+    let updated_form = InstanceForm {
+      version: Some("0.15.0".to_string()),
+      users: None,
+      ..form
+    };
+    let updated = Instance::upsert(&conn, &updated_form).unwrap();
+    let actor_form = InstanceActorForm {
+      domain: "nodeinfo.example.com".to_string(),
+      name: Some("Example".to_string()),
+      description: None,
+      icon: None,
+    };
+    let with_actor = Instance::upsert_actor(&conn, &actor_form).unwrap();
+    let blocking = Instance::update_blocks_us(&conn, "nodeinfo.example.com", true).unwrap();
+    let instances = Instance::list(&conn).unwrap();
+    let read = Instance::read_for_domain(&conn, "nodeinfo.example.com").unwrap();
+    let num_deleted = Instance::delete_for_domain(&conn, "nodeinfo.example.com").unwrap();
+
+    assert_eq!(inserted.id, updated.id);
+    assert_eq!(Some("0.15.0".to_string()), updated.version);
+    assert_eq!(None, updated.users);
+    assert_eq!(Some("0.15.0".to_string()), with_actor.version);
+    assert_eq!(Some("Example".to_string()), with_actor.name);
+    assert!(!with_actor.blocks_us);
+    assert!(blocking.blocks_us);
+    assert_eq!(blocking, read);
+    assert_eq!(vec![blocking], instances);
+    assert_eq!(1, num_deleted);
+  }
+}
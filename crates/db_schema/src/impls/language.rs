@@ -0,0 +1,182 @@
+use crate::{
+  newtypes::{CommunityId, LanguageId, LocalUserId},
+  source::language::*,
+};
+use diesel::{dsl::*, result::Error, *};
+
+impl Language {
+  /// Ordered by id, so that undetermined comes first
+  pub fn read_all(conn: &PgConnection) -> Result<Vec<Self>, Error> {
+    use crate::schema::language::dsl::*;
+    language.order_by(id.asc()).load::<Self>(conn)
+  }
+
+  pub fn read_from_id(conn: &PgConnection, id_: LanguageId) -> Result<Self, Error> {
+    use crate::schema::language::dsl::*;
+    language.find(id_).first::<Self>(conn)
+  }
+
+  /// Falls back to undetermined for unknown codes. Region subtags like in `en-US` are ignored.
+  pub fn read_id_from_code(conn: &PgConnection, code_: &str) -> Result<LanguageId, Error> {
+    use crate::schema::language::dsl::*;
+    let primary = code_.split('-').next().unwrap_or_default().to_lowercase();
+    language
+      .filter(code.eq(primary))
+      .select(id)
+      .first::<LanguageId>(conn)
+      .optional()
+      .map(|l| l.unwrap_or_default())
+  }
+}
+
+impl LocalUserLanguage {
+  pub fn read(
+    conn: &PgConnection,
+    for_local_user_id: LocalUserId,
+  ) -> Result<Vec<LanguageId>, Error> {
+    use crate::schema::local_user_language::dsl::*;
+    local_user_language
+      .filter(local_user_id.eq(for_local_user_id))
+      .select(language_id)
+      .order_by(language_id.asc())
+      .load::<LanguageId>(conn)
+  }
+
+  /// Sets exactly the given languages for the user. An empty list means all languages.
+  pub fn replace(
+    conn: &PgConnection,
+    for_local_user_id: LocalUserId,
+    language_ids: &[LanguageId],
+  ) -> Result<(), Error> {
+    use crate::schema::local_user_language::dsl::*;
+    conn.transaction(|| {
+      delete(local_user_language.filter(local_user_id.eq(for_local_user_id))).execute(conn)?;
+      let forms: Vec<LocalUserLanguageForm> = language_ids
+        .iter()
+        .map(|l| LocalUserLanguageForm {
+          local_user_id: for_local_user_id,
+          language_id: *l,
+        })
+        .collect();
+      insert_into(local_user_language)
+        .values(&forms)
+        .on_conflict_do_nothing()
+        .execute(conn)?;
+      Ok(())
+    })
+  }
+}
+
+impl CommunityLanguage {
+  pub fn read(
+    conn: &PgConnection,
+    for_community_id: CommunityId,
+  ) -> Result<Vec<LanguageId>, Error> {
+    use crate::schema::community_language::dsl::*;
+    community_language
+      .filter(community_id.eq(for_community_id))
+      .select(language_id)
+      .order_by(language_id.asc())
+      .load::<LanguageId>(conn)
+  }
+
+  /// Sets exactly the given languages for the community. An empty list means all languages.
+  pub fn replace(
+    conn: &PgConnection,
+    for_community_id: CommunityId,
+    language_ids: &[LanguageId],
+  ) -> Result<(), Error> {
+    use crate::schema::community_language::dsl::*;
+    conn.transaction(|| {
+      delete(community_language.filter(community_id.eq(for_community_id))).execute(conn)?;
+      let forms: Vec<CommunityLanguageForm> = language_ids
+        .iter()
+        .map(|l| CommunityLanguageForm {
+          community_id: for_community_id,
+          language_id: *l,
+        })
+        .collect();
+      insert_into(community_language)
+        .values(&forms)
+        .on_conflict_do_nothing()
+        .execute(conn)?;
+      Ok(())
+    })
+  }
+
+  /// Undetermined is always allowed, as is any language if the community didn't set any
+  pub fn is_allowed(
+    conn: &PgConnection,
+    for_community_id: CommunityId,
+    for_language_id: LanguageId,
+  ) -> Result<bool, Error> {
+    if for_language_id == LanguageId::default() {
+      return Ok(true);
+    }
+    let allowed = CommunityLanguage::read(conn, for_community_id)?;
+    Ok(allowed.is_empty() || allowed.contains(&for_language_id))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::{
+    establish_unpooled_connection,
+    newtypes::LanguageId,
+    source::{community::*, language::*, local_user::*, person::*},
+    traits::Crud,
+  };
+  use serial_test::serial;
+
+  #[test]
+  #[serial]
+  fn test_language() {
+    let conn = establish_unpooled_connection();
+
+    let all = Language::read_all(&conn).unwrap();
+    assert_eq!("und", all[0].code);
+    let english = Language::read_id_from_code(&conn, "en-US").unwrap();
+    assert_eq!("en", Language::read_from_id(&conn, english).unwrap().code);
+    let german = Language::read_id_from_code(&conn, "de").unwrap();
+    assert_eq!(
+      LanguageId::default(),
+      Language::read_id_from_code(&conn, "xx").unwrap()
+    );
+
+    let new_person = PersonForm {
+      name: "language_person".into(),
+      ..PersonForm::default()
+    };
+    let inserted_person = Person::create(&conn, &new_person).unwrap();
+    let new_local_user = LocalUserForm {
+      person_id: Some(inserted_person.id),
+      password_encrypted: Some("language_pass".to_string()),
+      ..LocalUserForm::default()
+    };
+    let inserted_local_user = LocalUser::create(&conn, &new_local_user).unwrap();
+
+    LocalUserLanguage::replace(&conn, inserted_local_user.id, &[german, english]).unwrap();
+    LocalUserLanguage::replace(&conn, inserted_local_user.id, &[english]).unwrap();
+    assert_eq!(
+      vec![english],
+      LocalUserLanguage::read(&conn, inserted_local_user.id).unwrap()
+    );
+
+    let new_community = CommunityForm {
+      name: "language_community".to_string(),
+      title: "nada".to_owned(),
+      ..CommunityForm::default()
+    };
+    let inserted_community = Community::create(&conn, &new_community).unwrap();
+    assert!(CommunityLanguage::is_allowed(&conn, inserted_community.id, german).unwrap());
+    CommunityLanguage::replace(&conn, inserted_community.id, &[english]).unwrap();
+    assert!(!CommunityLanguage::is_allowed(&conn, inserted_community.id, german).unwrap());
+    assert!(CommunityLanguage::is_allowed(&conn, inserted_community.id, english).unwrap());
+    assert!(
+      CommunityLanguage::is_allowed(&conn, inserted_community.id, LanguageId::default()).unwrap()
+    );
+
+    Community::delete(&conn, inserted_community.id).unwrap();
+    Person::delete(&conn, inserted_person.id).unwrap();
+  }
+}
@@ -0,0 +1,127 @@
+use crate::source::legal_hold::*;
+use diesel::{insert_into, result::Error, ExpressionMethods, PgConnection, QueryDsl, RunQueryDsl};
+
+impl LegalHold {
+  pub fn create(conn: &PgConnection, form: &LegalHoldForm) -> Result<Self, Error> {
+    use crate::schema::legal_hold::dsl::*;
+    insert_into(legal_hold)
+      .values(form)
+      .get_result::<Self>(conn)
+  }
+
+  pub fn delete(conn: &PgConnection, legal_hold_id: i32) -> Result<usize, Error> {
+    use crate::schema::legal_hold::dsl::*;
+    diesel::delete(legal_hold.find(legal_hold_id)).execute(conn)
+  }
+
+  pub fn list(conn: &PgConnection) -> Result<Vec<Self>, Error> {
+    use crate::schema::legal_hold::dsl::*;
+    legal_hold.order_by(published.desc()).load::<Self>(conn)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::{
+    establish_unpooled_connection,
+    naive_now,
+    schema::{comment, post},
+    source::{
+      comment::{Comment, CommentForm},
+      community::{Community, CommunityForm},
+      legal_hold::*,
+      person::{Person, PersonForm},
+      post::{Post, PostForm},
+    },
+    traits::Crud,
+  };
+  use diesel::{ExpressionMethods, QueryDsl, RunQueryDsl};
+  use serial_test::serial;
+
+  #[test]
+  #[serial]
+  fn test_legal_hold_prevents_purge() {
+    let conn = establish_unpooled_connection();
+
+    let new_person = PersonForm {
+      name: "legal_hold".into(),
+      ..PersonForm::default()
+    };
+    let person = Person::create(&conn, &new_person).unwrap();
+    let new_community = CommunityForm {
+      name: "legal_hold".to_string(),
+      title: "nada".to_owned(),
+      ..CommunityForm::default()
+    };
+    let community = Community::create(&conn, &new_community).unwrap();
+    let new_post = PostForm {
+      name: "A post under legal hold".into(),
+      creator_id: person.id,
+      community_id: community.id,
+      deleted: Some(true),
+      ..PostForm::default()
+    };
+    let held_post = Post::create(&conn, &new_post).unwrap();
+    let other_post = Post::create(&conn, &new_post).unwrap();
+    let new_comment = CommentForm {
+      content: "held".into(),
+      creator_id: person.id,
+      post_id: other_post.id,
+      removed: Some(true),
+      ..CommentForm::default()
+    };
+    let held_comment = Comment::create(&conn, &new_comment).unwrap();
+
+    let long_ago = naive_now() - chrono::Duration::days(100);
+    let before = naive_now() - chrono::Duration::days(90);
+    diesel::update(comment::table.find(held_comment.id))
+      .set(comment::updated.eq(long_ago))
+      .execute(&conn)
+      .unwrap();
+    diesel::update(post::table.filter(post::community_id.eq(community.id)))
+      .set(post::updated.eq(long_ago))
+      .execute(&conn)
+      .unwrap();
+
+    let post_hold = LegalHold::create(
+      &conn,
+      &LegalHoldForm {
+        post_id: Some(held_post.id),
+        comment_id: None,
+        admin_person_id: person.id,
+        reason: "court order".into(),
+      },
+    )
+    .unwrap();
+    let comment_hold = LegalHold::create(
+      &conn,
+      &LegalHoldForm {
+        post_id: None,
+        comment_id: Some(held_comment.id),
+        admin_person_id: person.id,
+        reason: "court order".into(),
+      },
+    )
+    .unwrap();
+    let listed = LegalHold::list(&conn).unwrap();
+
+    let purged_comments_held = Comment::purge_expired(&conn, true, before).unwrap();
+    // The other post still has the held comment
+    let purged_posts_held = Post::purge_expired(&conn, false, before).unwrap();
+    let held_post_exists = Post::read(&conn, held_post.id).is_ok();
+
+    LegalHold::delete(&conn, comment_hold.id).unwrap();
+    let purged_comments_released = Comment::purge_expired(&conn, true, before).unwrap();
+    let deleted_post_hold = LegalHold::delete(&conn, post_hold.id).unwrap();
+
+    Community::delete(&conn, community.id).unwrap();
+    Person::delete(&conn, person.id).unwrap();
+
+    assert_eq!(2, listed.len());
+    assert_eq!(0, purged_comments_held);
+    assert_eq!(0, purged_posts_held);
+    assert!(held_post_exists);
+    assert_eq!(1, purged_comments_released);
+    assert_eq!(1, deleted_post_hold);
+  }
+}
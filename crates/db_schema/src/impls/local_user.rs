@@ -1,11 +1,13 @@
 use crate::{
   naive_now,
-  newtypes::LocalUserId,
+  newtypes::{LocalUserId, PersonId},
   schema::local_user::dsl::*,
   source::local_user::{LocalUser, LocalUserForm},
   traits::Crud,
+  DigestFrequency,
 };
 use bcrypt::{hash, DEFAULT_COST};
+use chrono::NaiveDateTime;
 use diesel::{dsl::*, result::Error, *};
 
 mod safe_settings_type {
@@ -33,12 +35,14 @@ mod safe_settings_type {
     show_new_post_notifs,
     email_verified,
     accepted_application,
+    totp_enabled,
+    email_digest_frequency,
   );
 
   impl ToSafeSettings for LocalUser {
     type SafeSettingsColumns = Columns;
 
-    /// Includes everything but the hashed password
+    /// Includes everything but the hashed password and the totp secret
     fn safe_settings_columns_tuple() -> Self::SafeSettingsColumns {
       (
         id,
@@ -58,6 +62,8 @@ mod safe_settings_type {
         show_new_post_notifs,
         email_verified,
         accepted_application,
+        totp_enabled,
+        email_digest_frequency,
       )
     }
   }
@@ -90,6 +96,88 @@ impl LocalUser {
       .get_result::<Self>(conn)
   }
 
+  /// Makes all JWTs which were issued until now invalid, including those without a login session
+  pub fn update_validator_time(
+    conn: &PgConnection,
+    local_user_id: LocalUserId,
+  ) -> Result<Self, Error> {
+    diesel::update(local_user.find(local_user_id))
+      .set(validator_time.eq(naive_now()))
+      .get_result::<Self>(conn)
+  }
+
+  /// Users with an email address whose digest of the given frequency wasn't sent since `before`
+  pub fn list_due_for_email_digest(
+    conn: &PgConnection,
+    frequency: DigestFrequency,
+    before: NaiveDateTime,
+  ) -> Result<Vec<Self>, Error> {
+    local_user
+      .filter(email_digest_frequency.eq(frequency.to_string()))
+      .filter(email.is_not_null())
+      .filter(email_digest_sent.is_null().or(email_digest_sent.lt(before)))
+      .load::<Self>(conn)
+  }
+
+  pub fn update_email_digest_sent(
+    conn: &PgConnection,
+    local_user_id: LocalUserId,
+    sent: NaiveDateTime,
+  ) -> Result<Self, Error> {
+    diesel::update(local_user.find(local_user_id))
+      .set(email_digest_sent.eq(sent))
+      .get_result::<Self>(conn)
+  }
+
+  /// Accepts a totp token of the given time step, unless a token of the same or a later step was
+  /// already used. Returns false in that case.
+  pub fn use_totp_step(
+    conn: &PgConnection,
+    local_user_id: LocalUserId,
+    step: i64,
+  ) -> Result<bool, Error> {
+    let updated = diesel::update(
+      local_user
+        .find(local_user_id)
+        .filter(totp_last_step.is_null().or(totp_last_step.lt(step))),
+    )
+    .set((totp_last_step.eq(step), totp_failed_attempts.eq(0)))
+    .execute(conn)?;
+    Ok(updated == 1)
+  }
+
+  pub fn add_totp_failure(conn: &PgConnection, local_user_id: LocalUserId) -> Result<Self, Error> {
+    diesel::update(local_user.find(local_user_id))
+      .set((
+        totp_failed_attempts.eq(totp_failed_attempts + 1),
+        totp_last_failure.eq(naive_now()),
+      ))
+      .get_result::<Self>(conn)
+  }
+
+  /// The local user ids of the given persons. Remote persons are skipped.
+  pub fn ids_for_persons(
+    conn: &PgConnection,
+    person_ids: Vec<PersonId>,
+  ) -> Result<Vec<LocalUserId>, Error> {
+    local_user
+      .filter(person_id.eq_any(person_ids))
+      .select(id)
+      .load::<LocalUserId>(conn)
+  }
+
+  /// Whether the person wants to see vote scores. Always true for remote persons.
+  pub fn show_scores(conn: &PgConnection, for_person_id: PersonId) -> Result<bool, Error> {
+    Ok(
+      local_user
+        .filter(person_id.eq(for_person_id))
+        .select(show_scores)
+        .first::<bool>(conn)
+        .optional()?
+        .unwrap_or(true),
+    )
+  }
+
   pub fn set_all_users_email_verified(conn: &PgConnection) -> Result<Vec<Self>, Error> {
     diesel::update(local_user)
       .set(email_verified.eq(true))
@@ -129,3 +217,54 @@ impl Crud for LocalUser {
       .get_result::<Self>(conn)
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use crate::{
+    establish_unpooled_connection,
+    source::{local_user::*, person::*},
+    traits::Crud,
+  };
+  use serial_test::serial;
+
+  #[test]
+  #[serial]
+  fn test_totp_attempts() {
+    let conn = establish_unpooled_connection();
+
+    let new_person = PersonForm {
+      name: "totp_attempts_person".into(),
+      ..PersonForm::default()
+    };
+    let inserted_person = Person::create(&conn, &new_person).unwrap();
+    let new_local_user = LocalUserForm {
+      person_id: Some(inserted_person.id),
+      password_encrypted: Some("totp_attempts_pass".to_string()),
+      ..LocalUserForm::default()
+    };
+    let inserted_local_user = LocalUser::create(&conn, &new_local_user).unwrap();
+    let local_user_id = inserted_local_user.id;
+
+    let failed = LocalUser::add_totp_failure(&conn, local_user_id).unwrap();
+    let failed_again = LocalUser::add_totp_failure(&conn, local_user_id).unwrap();
+    let first_use = LocalUser::use_totp_step(&conn, local_user_id, 100).unwrap();
+    // The same token, or one of an earlier step, can't be used again
+    let replayed = LocalUser::use_totp_step(&conn, local_user_id, 100).unwrap();
+    let earlier = LocalUser::use_totp_step(&conn, local_user_id, 99).unwrap();
+    let later = LocalUser::use_totp_step(&conn, local_user_id, 101).unwrap();
+    let read_local_user = LocalUser::read(&conn, local_user_id).unwrap();
+
+    Person::delete(&conn, inserted_person.id).unwrap();
+
+    assert_eq!(0, inserted_local_user.totp_failed_attempts);
+    assert_eq!(1, failed.totp_failed_attempts);
+    assert!(failed.totp_last_failure.is_some());
+    assert_eq!(2, failed_again.totp_failed_attempts);
+    assert!(first_use);
+    assert!(!replayed);
+    assert!(!earlier);
+    assert!(later);
+    assert_eq!(Some(101), read_local_user.totp_last_step);
+    assert_eq!(0, read_local_user.totp_failed_attempts);
+  }
+}
@@ -0,0 +1,123 @@
+use crate::{newtypes::LocalUserId, source::login_session::*};
+use diesel::{dsl::*, result::Error, *};
+
+impl LoginSession {
+  pub fn create(conn: &PgConnection, form: &LoginSessionForm) -> Result<Self, Error> {
+    use crate::schema::login_session::dsl::*;
+    insert_into(login_session)
+      .values(form)
+      .get_result::<Self>(conn)
+  }
+
+  /// Checks that the session wasn't revoked, and records that it was used
+  pub fn is_valid(
+    conn: &PgConnection,
+    session_id: i32,
+    for_local_user_id: LocalUserId,
+  ) -> Result<bool, Error> {
+    use crate::schema::login_session::dsl::*;
+    let session = login_session
+      .find(session_id)
+      .filter(local_user_id.eq(for_local_user_id));
+    // Only write the time every few minutes, instead of on each request
+    update(session.filter(last_used.lt(now - 5.minutes())))
+      .set(last_used.eq(now))
+      .execute(conn)?;
+    select(exists(session)).get_result(conn)
+  }
+
+  pub fn list_for_local_user(
+    conn: &PgConnection,
+    for_local_user_id: LocalUserId,
+  ) -> Result<Vec<Self>, Error> {
+    use crate::schema::login_session::dsl::*;
+    login_session
+      .filter(local_user_id.eq(for_local_user_id))
+      .order_by(last_used.desc())
+      .load::<Self>(conn)
+  }
+
+  /// Revokes a session, if it belongs to the given user
+  pub fn delete_for_local_user(
+    conn: &PgConnection,
+    session_id: i32,
+    for_local_user_id: LocalUserId,
+  ) -> Result<usize, Error> {
+    use crate::schema::login_session::dsl::*;
+    delete(
+      login_session
+        .find(session_id)
+        .filter(local_user_id.eq(for_local_user_id)),
+    )
+    .execute(conn)
+  }
+
+  pub fn delete_all_for_local_user(
+    conn: &PgConnection,
+    for_local_user_id: LocalUserId,
+  ) -> Result<usize, Error> {
+    use crate::schema::login_session::dsl::*;
+    delete(login_session.filter(local_user_id.eq(for_local_user_id))).execute(conn)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::{
+    establish_unpooled_connection,
+    source::{local_user::*, login_session::*, person::*},
+    traits::Crud,
+  };
+  use serial_test::serial;
+
+  #[test]
+  #[serial]
+  fn test_login_session() {
+    let conn = establish_unpooled_connection();
+
+    let new_person = PersonForm {
+      name: "login_session_person".into(),
+      ..PersonForm::default()
+    };
+    let inserted_person = Person::create(&conn, &new_person).unwrap();
+    let new_local_user = LocalUserForm {
+      person_id: Some(inserted_person.id),
+      password_encrypted: Some("login_session_pass".to_string()),
+      ..LocalUserForm::default()
+    };
+    let inserted_local_user = LocalUser::create(&conn, &new_local_user).unwrap();
+    let local_user_id = inserted_local_user.id;
+
+    let form = LoginSessionForm {
+      local_user_id,
+      user_agent: Some("Mozilla/5.0".to_string()),
+      ip: Some("203.0.113.7".to_string()),
+    };
+    let first = LoginSession::create(&conn, &form).unwrap();
+    let second = LoginSession::create(&conn, &form).unwrap();
+    let third = LoginSession::create(&conn, &form).unwrap();
+
+    assert!(LoginSession::is_valid(&conn, first.id, local_user_id).unwrap());
+    assert_eq!(
+      3,
+      LoginSession::list_for_local_user(&conn, local_user_id)
+        .unwrap()
+        .len()
+    );
+
+    assert_eq!(
+      1,
+      LoginSession::delete_for_local_user(&conn, first.id, local_user_id).unwrap()
+    );
+    assert!(!LoginSession::is_valid(&conn, first.id, local_user_id).unwrap());
+    assert!(LoginSession::is_valid(&conn, second.id, local_user_id).unwrap());
+
+    assert_eq!(
+      2,
+      LoginSession::delete_all_for_local_user(&conn, local_user_id).unwrap()
+    );
+    assert!(!LoginSession::is_valid(&conn, third.id, local_user_id).unwrap());
+
+    Person::delete(&conn, inserted_person.id).unwrap();
+  }
+}
@@ -1,4 +1,9 @@
-use crate::{source::moderator::*, traits::Crud};
+use crate::{
+  newtypes::{CommentId, CommunityId, PersonId},
+  source::moderator::*,
+  traits::Crud,
+};
+use chrono::NaiveDateTime;
 use diesel::{dsl::*, result::Error, *};
 
 impl Crud for ModRemovePost {
@@ -24,6 +29,18 @@ impl Crud for ModRemovePost {
   }
 }
 
+impl ModRemovePost {
+  /// Entries whose post was permanently deleted. They are kept for the audit history, but not shown
+  /// in the modlog.
+  pub fn list_orphaned(conn: &PgConnection) -> Result<Vec<Self>, Error> {
+    use crate::schema::mod_remove_post::dsl::*;
+    mod_remove_post
+      .filter(post_id.is_null())
+      .order_by(when_.desc())
+      .load::<Self>(conn)
+  }
+}
+
 impl Crud for ModLockPost {
   type Form = ModLockPostForm;
   type IdType = i32;
@@ -47,6 +64,79 @@ impl Crud for ModLockPost {
   }
 }
 
+impl Crud for ModShadowRemovePost {
+  type Form = ModShadowRemovePostForm;
+  type IdType = i32;
+  fn read(conn: &PgConnection, from_id: i32) -> Result<Self, Error> {
+    use crate::schema::mod_shadow_remove_post::dsl::*;
+    mod_shadow_remove_post.find(from_id).first::<Self>(conn)
+  }
+
+  fn create(conn: &PgConnection, form: &ModShadowRemovePostForm) -> Result<Self, Error> {
+    use crate::schema::mod_shadow_remove_post::dsl::*;
+    insert_into(mod_shadow_remove_post)
+      .values(form)
+      .get_result::<Self>(conn)
+  }
+
+  fn update(
+    conn: &PgConnection,
+    from_id: i32,
+    form: &ModShadowRemovePostForm,
+  ) -> Result<Self, Error> {
+    use crate::schema::mod_shadow_remove_post::dsl::*;
+    diesel::update(mod_shadow_remove_post.find(from_id))
+      .set(form)
+      .get_result::<Self>(conn)
+  }
+}
+
+impl Crud for ModResolveAppeal {
+  type Form = ModResolveAppealForm;
+  type IdType = i32;
+  fn read(conn: &PgConnection, from_id: i32) -> Result<Self, Error> {
+    use crate::schema::mod_resolve_appeal::dsl::*;
+    mod_resolve_appeal.find(from_id).first::<Self>(conn)
+  }
+
+  fn create(conn: &PgConnection, form: &ModResolveAppealForm) -> Result<Self, Error> {
+    use crate::schema::mod_resolve_appeal::dsl::*;
+    insert_into(mod_resolve_appeal)
+      .values(form)
+      .get_result::<Self>(conn)
+  }
+
+  fn update(conn: &PgConnection, from_id: i32, form: &ModResolveAppealForm) -> Result<Self, Error> {
+    use crate::schema::mod_resolve_appeal::dsl::*;
+    diesel::update(mod_resolve_appeal.find(from_id))
+      .set(form)
+      .get_result::<Self>(conn)
+  }
+}
+
+impl Crud for ModAutomodAction {
+  type Form = ModAutomodActionForm;
+  type IdType = i32;
+  fn read(conn: &PgConnection, from_id: i32) -> Result<Self, Error> {
+    use crate::schema::mod_automod_action::dsl::*;
+    mod_automod_action.find(from_id).first::<Self>(conn)
+  }
+
+  fn create(conn: &PgConnection, form: &ModAutomodActionForm) -> Result<Self, Error> {
+    use crate::schema::mod_automod_action::dsl::*;
+    insert_into(mod_automod_action)
+      .values(form)
+      .get_result::<Self>(conn)
+  }
+
+  fn update(conn: &PgConnection, from_id: i32, form: &ModAutomodActionForm) -> Result<Self, Error> {
+    use crate::schema::mod_automod_action::dsl::*;
+    diesel::update(mod_automod_action.find(from_id))
+      .set(form)
+      .get_result::<Self>(conn)
+  }
+}
+
 impl Crud for ModStickyPost {
   type Form = ModStickyPostForm;
   type IdType = i32;
@@ -93,6 +183,32 @@ impl Crud for ModRemoveComment {
   }
 }
 
+impl ModRemoveComment {
+  /// Entries whose comment was permanently deleted, see [`ModRemovePost::list_orphaned`]
+  pub fn list_orphaned(conn: &PgConnection) -> Result<Vec<Self>, Error> {
+    use crate::schema::mod_remove_comment::dsl::*;
+    mod_remove_comment
+      .filter(comment_id.is_null())
+      .order_by(when_.desc())
+      .load::<Self>(conn)
+  }
+
+  /// The most recent removal of the comment, ignoring restores
+  pub fn read_latest_removal(
+    conn: &PgConnection,
+    for_comment_id: CommentId,
+  ) -> Result<Option<Self>, Error> {
+    use crate::schema::mod_remove_comment::dsl::*;
+    mod_remove_comment
+      .filter(comment_id.eq(for_comment_id))
+      .filter(removed.eq(true))
+      .order_by(when_.desc())
+      .then_order_by(id.desc())
+      .first::<Self>(conn)
+      .optional()
+  }
+}
+
 impl Crud for ModRemoveCommunity {
   type Form = ModRemoveCommunityForm;
   type IdType = i32;
@@ -120,6 +236,36 @@ impl Crud for ModRemoveCommunity {
   }
 }
 
+impl ModRemoveCommunity {
+  /// Entries whose community was permanently deleted, see [`ModRemovePost::list_orphaned`]
+  pub fn list_orphaned(conn: &PgConnection) -> Result<Vec<Self>, Error> {
+    use crate::schema::mod_remove_community::dsl::*;
+    mod_remove_community
+      .filter(community_id.is_null())
+      .order_by(when_.desc())
+      .load::<Self>(conn)
+  }
+
+  /// Returns true if the most recent removal action by a local admin on this community is still in
+  /// effect, ie it was a removal and not a restore.
+  pub fn is_removed_by_local_admin(
+    conn: &PgConnection,
+    for_community_id: CommunityId,
+  ) -> Result<bool, Error> {
+    use crate::schema::{mod_remove_community::dsl::*, person};
+    let last_local_removed = mod_remove_community
+      .inner_join(person::table)
+      .filter(community_id.eq(for_community_id))
+      .filter(person::local.eq(true))
+      .order_by(when_.desc())
+      .then_order_by(id.desc())
+      .select(removed)
+      .first::<Option<bool>>(conn)
+      .optional()?;
+    Ok(matches!(last_local_removed, Some(r) if r.unwrap_or(true)))
+  }
+}
+
 impl Crud for ModBanFromCommunity {
   type Form = ModBanFromCommunityForm;
   type IdType = i32;
@@ -147,6 +293,23 @@ impl Crud for ModBanFromCommunity {
   }
 }
 
+impl ModBanFromCommunity {
+  /// The mod log entry of the most recent ban of the person from the community
+  pub fn read_latest_ban(
+    conn: &PgConnection,
+    for_person_id: PersonId,
+    for_community_id: CommunityId,
+  ) -> Result<Self, Error> {
+    use crate::schema::mod_ban_from_community::dsl::*;
+    mod_ban_from_community
+      .filter(other_person_id.eq(for_person_id))
+      .filter(community_id.eq(for_community_id))
+      .filter(banned.eq(true))
+      .order_by(id.desc())
+      .first::<Self>(conn)
+  }
+}
+
 impl Crud for ModBan {
   type Form = ModBanForm;
   type IdType = i32;
@@ -168,6 +331,34 @@ impl Crud for ModBan {
   }
 }
 
+impl ModBan {
+  /// Site bans which are still in effect, but whose expiry time has passed. The person table only
+  /// has a flag, so the expiry is taken from the latest mod log entry for each banned person.
+  pub fn list_expired(conn: &PgConnection, before: NaiveDateTime) -> Result<Vec<Self>, Error> {
+    use crate::schema::{mod_ban::dsl::*, person};
+    let candidates = mod_ban
+      .inner_join(person::table.on(person::id.eq(other_person_id)))
+      .filter(person::banned.eq(true))
+      .filter(banned.eq(true))
+      .filter(expires.lt(before))
+      .select(crate::schema::mod_ban::all_columns)
+      .load::<Self>(conn)?;
+    let mut expired = vec![];
+    for ban in candidates {
+      // Banning again or unbanning manually supersedes the expiry of an earlier ban
+      let later_entries = mod_ban
+        .filter(other_person_id.eq(ban.other_person_id))
+        .filter(id.gt(ban.id))
+        .count()
+        .get_result::<i64>(conn)?;
+      if later_entries == 0 {
+        expired.push(ban);
+      }
+    }
+    Ok(expired)
+  }
+}
+
 impl Crud for ModAddCommunity {
   type Form = ModAddCommunityForm;
   type IdType = i32;
@@ -239,6 +430,106 @@ impl Crud for ModAdd {
   }
 }
 
+impl Crud for AdminPurgePerson {
+  type Form = AdminPurgePersonForm;
+  type IdType = i32;
+  fn read(conn: &PgConnection, from_id: i32) -> Result<Self, Error> {
+    use crate::schema::admin_purge_person::dsl::*;
+    admin_purge_person.find(from_id).first::<Self>(conn)
+  }
+
+  fn create(conn: &PgConnection, form: &AdminPurgePersonForm) -> Result<Self, Error> {
+    use crate::schema::admin_purge_person::dsl::*;
+    insert_into(admin_purge_person)
+      .values(form)
+      .get_result::<Self>(conn)
+  }
+
+  fn update(conn: &PgConnection, from_id: i32, form: &AdminPurgePersonForm) -> Result<Self, Error> {
+    use crate::schema::admin_purge_person::dsl::*;
+    diesel::update(admin_purge_person.find(from_id))
+      .set(form)
+      .get_result::<Self>(conn)
+  }
+}
+
+impl Crud for AdminPurgeCommunity {
+  type Form = AdminPurgeCommunityForm;
+  type IdType = i32;
+  fn read(conn: &PgConnection, from_id: i32) -> Result<Self, Error> {
+    use crate::schema::admin_purge_community::dsl::*;
+    admin_purge_community.find(from_id).first::<Self>(conn)
+  }
+
+  fn create(conn: &PgConnection, form: &AdminPurgeCommunityForm) -> Result<Self, Error> {
+    use crate::schema::admin_purge_community::dsl::*;
+    insert_into(admin_purge_community)
+      .values(form)
+      .get_result::<Self>(conn)
+  }
+
+  fn update(
+    conn: &PgConnection,
+    from_id: i32,
+    form: &AdminPurgeCommunityForm,
+  ) -> Result<Self, Error> {
+    use crate::schema::admin_purge_community::dsl::*;
+    diesel::update(admin_purge_community.find(from_id))
+      .set(form)
+      .get_result::<Self>(conn)
+  }
+}
+
+impl Crud for AdminPurgePost {
+  type Form = AdminPurgePostForm;
+  type IdType = i32;
+  fn read(conn: &PgConnection, from_id: i32) -> Result<Self, Error> {
+    use crate::schema::admin_purge_post::dsl::*;
+    admin_purge_post.find(from_id).first::<Self>(conn)
+  }
+
+  fn create(conn: &PgConnection, form: &AdminPurgePostForm) -> Result<Self, Error> {
+    use crate::schema::admin_purge_post::dsl::*;
+    insert_into(admin_purge_post)
+      .values(form)
+      .get_result::<Self>(conn)
+  }
+
+  fn update(conn: &PgConnection, from_id: i32, form: &AdminPurgePostForm) -> Result<Self, Error> {
+    use crate::schema::admin_purge_post::dsl::*;
+    diesel::update(admin_purge_post.find(from_id))
+      .set(form)
+      .get_result::<Self>(conn)
+  }
+}
+
+impl Crud for AdminPurgeComment {
+  type Form = AdminPurgeCommentForm;
+  type IdType = i32;
+  fn read(conn: &PgConnection, from_id: i32) -> Result<Self, Error> {
+    use crate::schema::admin_purge_comment::dsl::*;
+    admin_purge_comment.find(from_id).first::<Self>(conn)
+  }
+
+  fn create(conn: &PgConnection, form: &AdminPurgeCommentForm) -> Result<Self, Error> {
+    use crate::schema::admin_purge_comment::dsl::*;
+    insert_into(admin_purge_comment)
+      .values(form)
+      .get_result::<Self>(conn)
+  }
+
+  fn update(
+    conn: &PgConnection,
+    from_id: i32,
+    form: &AdminPurgeCommentForm,
+  ) -> Result<Self, Error> {
+    use crate::schema::admin_purge_comment::dsl::*;
+    diesel::update(admin_purge_comment.find(from_id))
+      .set(form)
+      .get_result::<Self>(conn)
+  }
+}
+
 #[cfg(test)]
 mod tests {
   use crate::{
@@ -302,16 +593,22 @@ mod tests {
       post_id: inserted_post.id,
       reason: None,
       removed: None,
+      reason_translations: Some(serde_json::json!({ "de": "Spam" })),
+      reason_override: false,
+      public: None,
     };
     let inserted_mod_remove_post = ModRemovePost::create(&conn, &mod_remove_post_form).unwrap();
     let read_mod_remove_post = ModRemovePost::read(&conn, inserted_mod_remove_post.id).unwrap();
     let expected_mod_remove_post = ModRemovePost {
       id: inserted_mod_remove_post.id,
-      post_id: inserted_post.id,
+      post_id: Some(inserted_post.id),
       mod_person_id: inserted_mod.id,
       reason: None,
       removed: Some(true),
       when_: inserted_mod_remove_post.when_,
+      reason_translations: Some(serde_json::json!({ "de": "Spam" })),
+      reason_override: false,
+      public: true,
     };
 
     // lock post
@@ -331,6 +628,29 @@ mod tests {
       when_: inserted_mod_lock_post.when_,
     };
 
+    // shadow remove post
+
+    let mod_shadow_remove_post_form = ModShadowRemovePostForm {
+      mod_person_id: inserted_mod.id,
+      post_id: inserted_post.id,
+      reason: None,
+      shadow_removed: None,
+      reason_override: false,
+    };
+    let inserted_mod_shadow_remove_post =
+      ModShadowRemovePost::create(&conn, &mod_shadow_remove_post_form).unwrap();
+    let read_mod_shadow_remove_post =
+      ModShadowRemovePost::read(&conn, inserted_mod_shadow_remove_post.id).unwrap();
+    let expected_mod_shadow_remove_post = ModShadowRemovePost {
+      id: inserted_mod_shadow_remove_post.id,
+      post_id: inserted_post.id,
+      mod_person_id: inserted_mod.id,
+      reason: None,
+      shadow_removed: Some(true),
+      when_: inserted_mod_shadow_remove_post.when_,
+      reason_override: false,
+    };
+
     // sticky post
 
     let mod_sticky_post_form = ModStickyPostForm {
@@ -355,6 +675,9 @@ mod tests {
       comment_id: inserted_comment.id,
       reason: None,
       removed: None,
+      reason_translations: None,
+      reason_override: false,
+      public: None,
     };
     let inserted_mod_remove_comment =
       ModRemoveComment::create(&conn, &mod_remove_comment_form).unwrap();
@@ -362,11 +685,14 @@ mod tests {
       ModRemoveComment::read(&conn, inserted_mod_remove_comment.id).unwrap();
     let expected_mod_remove_comment = ModRemoveComment {
       id: inserted_mod_remove_comment.id,
-      comment_id: inserted_comment.id,
+      comment_id: Some(inserted_comment.id),
       mod_person_id: inserted_mod.id,
       reason: None,
       removed: Some(true),
       when_: inserted_mod_remove_comment.when_,
+      reason_translations: None,
+      reason_override: false,
+      public: true,
     };
 
     // community
@@ -376,7 +702,10 @@ mod tests {
       community_id: inserted_community.id,
       reason: None,
       removed: None,
+      reason_translations: None,
       expires: None,
+      reason_override: false,
+      public: None,
     };
     let inserted_mod_remove_community =
       ModRemoveCommunity::create(&conn, &mod_remove_community_form).unwrap();
@@ -384,12 +713,15 @@ mod tests {
       ModRemoveCommunity::read(&conn, inserted_mod_remove_community.id).unwrap();
     let expected_mod_remove_community = ModRemoveCommunity {
       id: inserted_mod_remove_community.id,
-      community_id: inserted_community.id,
+      community_id: Some(inserted_community.id),
       mod_person_id: inserted_mod.id,
       reason: None,
       removed: Some(true),
       expires: None,
       when_: inserted_mod_remove_community.when_,
+      reason_translations: None,
+      reason_override: false,
+      public: true,
     };
 
     // ban from community
@@ -401,6 +733,7 @@ mod tests {
       reason: None,
       banned: None,
       expires: None,
+      public: None,
     };
     let inserted_mod_ban_from_community =
       ModBanFromCommunity::create(&conn, &mod_ban_from_community_form).unwrap();
@@ -415,6 +748,7 @@ mod tests {
       banned: Some(true),
       expires: None,
       when_: inserted_mod_ban_from_community.when_,
+      public: true,
     };
 
     // ban
@@ -476,6 +810,42 @@ mod tests {
       when_: inserted_mod_add.when_,
     };
 
+    // admin purge post
+
+    let admin_purge_post_form = AdminPurgePostForm {
+      admin_person_id: inserted_mod.id,
+      community_id: inserted_community.id,
+      reason: Some("spam".into()),
+    };
+    let inserted_admin_purge_post = AdminPurgePost::create(&conn, &admin_purge_post_form).unwrap();
+    let read_admin_purge_post = AdminPurgePost::read(&conn, inserted_admin_purge_post.id).unwrap();
+    let expected_admin_purge_post = AdminPurgePost {
+      id: inserted_admin_purge_post.id,
+      admin_person_id: inserted_mod.id,
+      community_id: inserted_community.id,
+      reason: Some("spam".into()),
+      when_: inserted_admin_purge_post.when_,
+    };
+
+    // admin purge comment
+
+    let admin_purge_comment_form = AdminPurgeCommentForm {
+      admin_person_id: inserted_mod.id,
+      post_id: inserted_post.id,
+      reason: None,
+    };
+    let inserted_admin_purge_comment =
+      AdminPurgeComment::create(&conn, &admin_purge_comment_form).unwrap();
+    let read_admin_purge_comment =
+      AdminPurgeComment::read(&conn, inserted_admin_purge_comment.id).unwrap();
+    let expected_admin_purge_comment = AdminPurgeComment {
+      id: inserted_admin_purge_comment.id,
+      admin_person_id: inserted_mod.id,
+      post_id: inserted_post.id,
+      reason: None,
+      when_: inserted_admin_purge_comment.when_,
+    };
+
     Comment::delete(&conn, inserted_comment.id).unwrap();
     Post::delete(&conn, inserted_post.id).unwrap();
     Community::delete(&conn, inserted_community.id).unwrap();
@@ -484,6 +854,7 @@ mod tests {
 
     assert_eq!(expected_mod_remove_post, read_mod_remove_post);
     assert_eq!(expected_mod_lock_post, read_mod_lock_post);
+    assert_eq!(expected_mod_shadow_remove_post, read_mod_shadow_remove_post);
     assert_eq!(expected_mod_sticky_post, read_mod_sticky_post);
     assert_eq!(expected_mod_remove_comment, read_mod_remove_comment);
     assert_eq!(expected_mod_remove_community, read_mod_remove_community);
@@ -491,5 +862,158 @@ mod tests {
     assert_eq!(expected_mod_ban, read_mod_ban);
     assert_eq!(expected_mod_add_community, read_mod_add_community);
     assert_eq!(expected_mod_add, read_mod_add);
+    assert_eq!(expected_admin_purge_post, read_admin_purge_post);
+    assert_eq!(expected_admin_purge_comment, read_admin_purge_comment);
+  }
+
+  #[test]
+  #[serial]
+  fn test_is_removed_by_local_admin() {
+    let conn = establish_unpooled_connection();
+
+    let local_admin_form = PersonForm {
+      name: "local_admin_rm".into(),
+      admin: Some(true),
+      ..PersonForm::default()
+    };
+    let local_admin = Person::create(&conn, &local_admin_form).unwrap();
+
+    let remote_mod_form = PersonForm {
+      name: "remote_mod_rm".into(),
+      local: Some(false),
+      ..PersonForm::default()
+    };
+    let remote_mod = Person::create(&conn, &remote_mod_form).unwrap();
+
+    let community_form = CommunityForm {
+      name: "remote_removed_community".to_string(),
+      title: "nada".to_owned(),
+      local: Some(false),
+      ..CommunityForm::default()
+    };
+    let community = Community::create(&conn, &community_form).unwrap();
+
+    let remove = |mod_person_id, removed| {
+      let form = ModRemoveCommunityForm {
+        mod_person_id,
+        community_id: community.id,
+        reason: None,
+        removed: Some(removed),
+        reason_translations: None,
+        expires: None,
+        reason_override: false,
+        public: None,
+      };
+      ModRemoveCommunity::create(&conn, &form).unwrap();
+      ModRemoveCommunity::is_removed_by_local_admin(&conn, community.id).unwrap()
+    };
+
+    let nothing_yet = ModRemoveCommunity::is_removed_by_local_admin(&conn, community.id).unwrap();
+    // Remote actions never count as local removal
+    let after_remote_remove = remove(remote_mod.id, true);
+    let after_local_remove = remove(local_admin.id, true);
+    // A remote restore doesn't override the local admin removal
+    let after_remote_restore = remove(remote_mod.id, false);
+    let after_local_restore = remove(local_admin.id, false);
+
+    Community::delete(&conn, community.id).unwrap();
+    Person::delete(&conn, local_admin.id).unwrap();
+    Person::delete(&conn, remote_mod.id).unwrap();
+
+    assert!(!nothing_yet);
+    assert!(!after_remote_remove);
+    assert!(after_local_remove);
+    assert!(after_remote_restore);
+    assert!(!after_local_restore);
+  }
+
+  /// Permanently deleting the target of a removal keeps the mod log entry, without the target.
+  #[test]
+  #[serial]
+  fn test_removal_entries_kept_after_purge() {
+    let conn = establish_unpooled_connection();
+
+    let person_form = PersonForm {
+      name: "orphan_mod".into(),
+      ..PersonForm::default()
+    };
+    let person = Person::create(&conn, &person_form).unwrap();
+
+    let community_form = CommunityForm {
+      name: "orphan_community".to_string(),
+      title: "nada".to_owned(),
+      ..CommunityForm::default()
+    };
+    let community = Community::create(&conn, &community_form).unwrap();
+
+    let post_form = PostForm {
+      name: "orphan post".into(),
+      creator_id: person.id,
+      community_id: community.id,
+      ..PostForm::default()
+    };
+    let post = Post::create(&conn, &post_form).unwrap();
+
+    let comment_form = CommentForm {
+      content: "orphan comment".into(),
+      creator_id: person.id,
+      post_id: post.id,
+      ..CommentForm::default()
+    };
+    let comment = Comment::create(&conn, &comment_form).unwrap();
+
+    let remove_comment_form = ModRemoveCommentForm {
+      mod_person_id: person.id,
+      comment_id: comment.id,
+      reason: None,
+      removed: None,
+      reason_translations: None,
+      reason_override: false,
+      public: None,
+    };
+    let remove_comment = ModRemoveComment::create(&conn, &remove_comment_form).unwrap();
+    let remove_post_form = ModRemovePostForm {
+      mod_person_id: person.id,
+      post_id: post.id,
+      reason: None,
+      removed: None,
+      reason_translations: None,
+      reason_override: false,
+      public: None,
+    };
+    let remove_post = ModRemovePost::create(&conn, &remove_post_form).unwrap();
+    let remove_community_form = ModRemoveCommunityForm {
+      mod_person_id: person.id,
+      community_id: community.id,
+      reason: None,
+      removed: None,
+      reason_translations: None,
+      expires: None,
+      reason_override: false,
+      public: None,
+    };
+    let remove_community = ModRemoveCommunity::create(&conn, &remove_community_form).unwrap();
+
+    Comment::delete(&conn, comment.id).unwrap();
+    let comment_entry_after_delete = ModRemoveComment::read(&conn, remove_comment.id).unwrap();
+    Post::delete(&conn, post.id).unwrap();
+    let post_entry_after_delete = ModRemovePost::read(&conn, remove_post.id).unwrap();
+    Community::delete(&conn, community.id).unwrap();
+    let community_entry_after_delete =
+      ModRemoveCommunity::read(&conn, remove_community.id).unwrap();
+    let orphaned_comments = ModRemoveComment::list_orphaned(&conn).unwrap();
+    let orphaned_posts = ModRemovePost::list_orphaned(&conn).unwrap();
+    let orphaned_communities = ModRemoveCommunity::list_orphaned(&conn).unwrap();
+    // The entries are only deleted together with the mod
+    Person::delete(&conn, person.id).unwrap();
+    let post_entry_after_mod_delete = ModRemovePost::read(&conn, remove_post.id);
+
+    assert_eq!(None, comment_entry_after_delete.comment_id);
+    assert_eq!(None, post_entry_after_delete.post_id);
+    assert_eq!(None, community_entry_after_delete.community_id);
+    assert!(orphaned_comments.contains(&comment_entry_after_delete));
+    assert!(orphaned_posts.contains(&post_entry_after_delete));
+    assert!(orphaned_communities.contains(&community_entry_after_delete));
+    assert!(post_entry_after_mod_delete.is_err());
   }
 }
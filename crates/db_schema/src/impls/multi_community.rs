@@ -0,0 +1,168 @@
+use crate::{
+  newtypes::{CommunityId, MultiCommunityId, PersonId},
+  source::multi_community::*,
+  traits::Crud,
+};
+use diesel::{
+  delete,
+  insert_into,
+  result::Error,
+  Connection,
+  ExpressionMethods,
+  PgConnection,
+  QueryDsl,
+  RunQueryDsl,
+};
+
+impl Crud for MultiCommunity {
+  type Form = MultiCommunityForm;
+  type IdType = MultiCommunityId;
+  fn create(conn: &PgConnection, form: &Self::Form) -> Result<Self, Error> {
+    use crate::schema::multi_community::dsl::*;
+    insert_into(multi_community)
+      .values(form)
+      .get_result::<Self>(conn)
+  }
+
+  fn read(conn: &PgConnection, id_: Self::IdType) -> Result<Self, Error> {
+    use crate::schema::multi_community::dsl::*;
+    multi_community.find(id_).first::<Self>(conn)
+  }
+
+  fn update(conn: &PgConnection, id_: Self::IdType, form: &Self::Form) -> Result<Self, Error> {
+    use crate::schema::multi_community::dsl::*;
+    diesel::update(multi_community.find(id_))
+      .set(form)
+      .get_result::<Self>(conn)
+  }
+
+  fn delete(conn: &PgConnection, id_: Self::IdType) -> Result<usize, Error> {
+    use crate::schema::multi_community::dsl::*;
+    diesel::delete(multi_community.find(id_)).execute(conn)
+  }
+}
+
+impl MultiCommunity {
+  pub fn list_for_person(conn: &PgConnection, for_person_id: PersonId) -> Result<Vec<Self>, Error> {
+    use crate::schema::multi_community::dsl::*;
+    multi_community
+      .filter(person_id.eq(for_person_id))
+      .order_by(name)
+      .load::<Self>(conn)
+  }
+
+  pub fn list_community_ids(
+    conn: &PgConnection,
+    for_multi_community_id: MultiCommunityId,
+  ) -> Result<Vec<CommunityId>, Error> {
+    use crate::schema::multi_community_community::dsl::*;
+    multi_community_community
+      .filter(multi_community_id.eq(for_multi_community_id))
+      .select(community_id)
+      .order_by(community_id)
+      .load::<CommunityId>(conn)
+  }
+
+  /// Sets exactly the given communities for the multi-community
+  pub fn replace_communities(
+    conn: &PgConnection,
+    for_multi_community_id: MultiCommunityId,
+    community_ids: &[CommunityId],
+  ) -> Result<(), Error> {
+    use crate::schema::multi_community_community::dsl::*;
+    conn.transaction(|| {
+      delete(multi_community_community.filter(multi_community_id.eq(for_multi_community_id)))
+        .execute(conn)?;
+      let forms: Vec<MultiCommunityCommunityForm> = community_ids
+        .iter()
+        .map(|c| MultiCommunityCommunityForm {
+          multi_community_id: for_multi_community_id,
+          community_id: *c,
+        })
+        .collect();
+      insert_into(multi_community_community)
+        .values(&forms)
+        .on_conflict_do_nothing()
+        .execute(conn)?;
+      Ok(())
+    })
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::{
+    establish_unpooled_connection,
+    naive_now,
+    source::{community::*, multi_community::*, person::*},
+    traits::Crud,
+  };
+  use serial_test::serial;
+
+  #[test]
+  #[serial]
+  fn test_crud() {
+    let conn = establish_unpooled_connection();
+
+    let person_form = PersonForm {
+      name: "multi_community_owner".into(),
+      ..PersonForm::default()
+    };
+    let person = Person::create(&conn, &person_form).unwrap();
+
+    let community_form = CommunityForm {
+      name: "multi_community_one".to_string(),
+      title: "nada".to_owned(),
+      ..CommunityForm::default()
+    };
+    let community_one = Community::create(&conn, &community_form).unwrap();
+    let community_form = CommunityForm {
+      name: "multi_community_two".to_string(),
+      ..community_form
+    };
+    let community_two = Community::create(&conn, &community_form).unwrap();
+
+    let form = MultiCommunityForm {
+      person_id: person.id,
+      name: "Science".into(),
+      updated: None,
+    };
+    let inserted = MultiCommunity::create(&conn, &form).unwrap();
+    // Names are unique per person
+    assert!(MultiCommunity::create(&conn, &form).is_err());
+
+    let update_form = MultiCommunityForm {
+      name: "Physics".into(),
+      updated: Some(naive_now()),
+      ..form
+    };
+    let updated = MultiCommunity::update(&conn, inserted.id, &update_form).unwrap();
+    let listed = MultiCommunity::list_for_person(&conn, person.id).unwrap();
+
+    MultiCommunity::replace_communities(
+      &conn,
+      inserted.id,
+      &[community_two.id, community_one.id, community_two.id],
+    )
+    .unwrap();
+    let both = MultiCommunity::list_community_ids(&conn, inserted.id).unwrap();
+    MultiCommunity::replace_communities(&conn, inserted.id, &[community_two.id]).unwrap();
+    let replaced = MultiCommunity::list_community_ids(&conn, inserted.id).unwrap();
+
+    // Removing a community also removes it from the multi-community
+    Community::delete(&conn, community_two.id).unwrap();
+    let after_community_delete = MultiCommunity::list_community_ids(&conn, inserted.id).unwrap();
+
+    let num_deleted = MultiCommunity::delete(&conn, inserted.id).unwrap();
+    Community::delete(&conn, community_one.id).unwrap();
+    Person::delete(&conn, person.id).unwrap();
+
+    assert_eq!("Physics", updated.name);
+    assert!(updated.updated.is_some());
+    assert_eq!(vec![updated], listed);
+    assert_eq!(vec![community_one.id, community_two.id], both);
+    assert_eq!(vec![community_two.id], replaced);
+    assert!(after_community_delete.is_empty());
+    assert_eq!(1, num_deleted);
+  }
+}
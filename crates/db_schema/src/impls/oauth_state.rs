@@ -0,0 +1,71 @@
+use crate::{naive_now, schema::oauth_state::dsl::*, source::oauth_state::*};
+use diesel::{dsl::*, result::Error, PgConnection, *};
+
+impl OAuthState {
+  pub fn create(conn: &PgConnection, form: &OAuthStateForm) -> Result<Self, Error> {
+    insert_into(oauth_state)
+      .values(form)
+      .get_result::<Self>(conn)
+  }
+
+  /// Reads the authorization request with the given state. Each state can only be used once, and
+  /// is only valid for ten minutes.
+  pub fn take(conn: &PgConnection, for_state: &str) -> Result<Option<Self>, Error> {
+    let found = delete(oauth_state.filter(state.eq(for_state))).get_results::<Self>(conn)?;
+    Ok(
+      found
+        .into_iter()
+        .find(|s| s.published > naive_now() - chrono::Duration::minutes(10)),
+    )
+  }
+
+  /// Removes the authorization requests which were never finished
+  pub fn delete_expired(conn: &PgConnection) -> Result<usize, Error> {
+    delete(oauth_state.filter(published.lt(now - 10.minutes()))).execute(conn)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::{establish_unpooled_connection, schema::oauth_state, source::oauth_state::*};
+  use diesel::{ExpressionMethods, QueryDsl, RunQueryDsl};
+  use serial_test::serial;
+
+  #[test]
+  #[serial]
+  fn test_oauth_state() {
+    let conn = establish_unpooled_connection();
+
+    let form = |for_state: &str| OAuthStateForm {
+      state: for_state.to_string(),
+      provider: "example-idp".to_string(),
+      redirect_uri: "https://lemmy.example.com/oauth/callback".to_string(),
+      code_verifier: "verifier".to_string(),
+      local_user_id: None,
+    };
+    let inserted = OAuthState::create(&conn, &form("oauth_state_fresh")).unwrap();
+    let expired = OAuthState::create(&conn, &form("oauth_state_expired")).unwrap();
+    diesel::update(oauth_state::table.find(expired.id))
+      .set(oauth_state::published.eq(expired.published - chrono::Duration::minutes(11)))
+      .execute(&conn)
+      .unwrap();
+    OAuthState::create(&conn, &form("oauth_state_unused")).unwrap();
+    diesel::update(oauth_state::table.filter(oauth_state::state.eq("oauth_state_unused")))
+      .set(oauth_state::published.eq(expired.published - chrono::Duration::minutes(11)))
+      .execute(&conn)
+      .unwrap();
+
+    assert_eq!(
+      Some(inserted),
+      OAuthState::take(&conn, "oauth_state_fresh").unwrap()
+    );
+    // Can't be used twice
+    assert_eq!(None, OAuthState::take(&conn, "oauth_state_fresh").unwrap());
+    assert_eq!(
+      None,
+      OAuthState::take(&conn, "oauth_state_expired").unwrap()
+    );
+    assert_eq!(None, OAuthState::take(&conn, "unknown").unwrap());
+    assert_eq!(1, OAuthState::delete_expired(&conn).unwrap());
+  }
+}
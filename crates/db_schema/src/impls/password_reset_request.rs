@@ -1,11 +1,11 @@
 use crate::{
+  hash_token,
   newtypes::LocalUserId,
   schema::password_reset_request::dsl::*,
   source::password_reset_request::*,
   traits::Crud,
 };
 use diesel::{dsl::*, result::Error, PgConnection, *};
-use sha2::{Digest, Sha256};
 
 impl Crud for PasswordResetRequest {
   type Form = PasswordResetRequestForm;
@@ -37,9 +37,7 @@ impl PasswordResetRequest {
     from_local_user_id: LocalUserId,
     token: &str,
   ) -> Result<PasswordResetRequest, Error> {
-    let mut hasher = Sha256::new();
-    hasher.update(token);
-    let token_hash: String = bytes_to_hex(hasher.finalize().to_vec());
+    let token_hash = hash_token(token);
 
     let form = PasswordResetRequestForm {
       local_user_id: from_local_user_id,
@@ -49,9 +47,7 @@ impl PasswordResetRequest {
     Self::create(conn, &form)
   }
   pub fn read_from_token(conn: &PgConnection, token: &str) -> Result<PasswordResetRequest, Error> {
-    let mut hasher = Sha256::new();
-    hasher.update(token);
-    let token_hash: String = bytes_to_hex(hasher.finalize().to_vec());
+    let token_hash = hash_token(token);
     password_reset_request
       .filter(token_encrypted.eq(token_hash))
       .filter(published.gt(now - 1.days()))
@@ -59,14 +55,6 @@ impl PasswordResetRequest {
   }
 }
 
-fn bytes_to_hex(bytes: Vec<u8>) -> String {
-  let mut str = String::new();
-  for byte in bytes {
-    str = format!("{}{:02x}", str, byte);
-  }
-  str
-}
-
 #[cfg(test)]
 mod tests {
   use crate::{
@@ -0,0 +1,120 @@
+use crate::{limit_and_offset, newtypes::CommunityId, source::pending_deletion::*, traits::Crud};
+use diesel::{insert_into, result::Error, ExpressionMethods, PgConnection, QueryDsl, RunQueryDsl};
+
+impl Crud for PendingDeletion {
+  type Form = PendingDeletionForm;
+  type IdType = i32;
+  fn create(conn: &PgConnection, form: &Self::Form) -> Result<Self, Error> {
+    use crate::schema::pending_deletion::dsl::*;
+    insert_into(pending_deletion)
+      .values(form)
+      .get_result::<Self>(conn)
+  }
+
+  fn read(conn: &PgConnection, id_: Self::IdType) -> Result<Self, Error> {
+    use crate::schema::pending_deletion::dsl::*;
+    pending_deletion.find(id_).first::<Self>(conn)
+  }
+
+  fn update(conn: &PgConnection, id_: Self::IdType, form: &Self::Form) -> Result<Self, Error> {
+    use crate::schema::pending_deletion::dsl::*;
+    diesel::update(pending_deletion.find(id_))
+      .set(form)
+      .get_result::<Self>(conn)
+  }
+
+  fn delete(conn: &PgConnection, id_: Self::IdType) -> Result<usize, Error> {
+    use crate::schema::pending_deletion::dsl::*;
+    diesel::delete(pending_deletion.find(id_)).execute(conn)
+  }
+}
+
+impl PendingDeletion {
+  /// Oldest first, optionally only for a single community
+  pub fn list(
+    conn: &PgConnection,
+    for_community_id: Option<CommunityId>,
+    page: Option<i64>,
+    limit: Option<i64>,
+  ) -> Result<Vec<Self>, Error> {
+    use crate::schema::pending_deletion::dsl::*;
+    let mut query = pending_deletion.into_boxed();
+    if let Some(for_community_id) = for_community_id {
+      query = query.filter(community_id.eq(for_community_id));
+    }
+    let (limit, offset) = limit_and_offset(page, limit);
+    query
+      .order_by(published.asc())
+      .then_order_by(id.asc())
+      .limit(limit)
+      .offset(offset)
+      .load::<Self>(conn)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::{
+    establish_unpooled_connection,
+    source::{community::*, pending_deletion::*, person::*},
+    traits::Crud,
+  };
+  use serial_test::serial;
+  use url::Url;
+
+  #[test]
+  #[serial]
+  fn test_crud() {
+    let conn = establish_unpooled_connection();
+
+    let person_form = PersonForm {
+      name: "pending_deletion_mod".into(),
+      local: Some(false),
+      ..PersonForm::default()
+    };
+    let person = Person::create(&conn, &person_form).unwrap();
+
+    let community_form = CommunityForm {
+      name: "pending_deletion_community".to_string(),
+      title: "nada".to_owned(),
+      ..CommunityForm::default()
+    };
+    let community = Community::create(&conn, &community_form).unwrap();
+
+    let form = PendingDeletionForm {
+      actor_id: person.id,
+      community_id: community.id,
+      activity_id: Url::parse("https://example.com/activities/remove/1")
+        .unwrap()
+        .into(),
+      object_id: community.actor_id.clone(),
+      removal: true,
+      reason: Some("spam".into()),
+      restore: false,
+    };
+    let inserted = PendingDeletion::create(&conn, &form).unwrap();
+    let read = PendingDeletion::read(&conn, inserted.id).unwrap();
+    let listed = PendingDeletion::list(&conn, Some(community.id), None, None).unwrap();
+    let num_deleted = PendingDeletion::delete(&conn, inserted.id).unwrap();
+    let listed_after_delete = PendingDeletion::list(&conn, Some(community.id), None, None).unwrap();
+
+    Community::delete(&conn, community.id).unwrap();
+    Person::delete(&conn, person.id).unwrap();
+
+    let expected = PendingDeletion {
+      id: inserted.id,
+      actor_id: person.id,
+      community_id: community.id,
+      activity_id: form.activity_id,
+      object_id: community.actor_id,
+      removal: true,
+      reason: Some("spam".into()),
+      published: inserted.published,
+      restore: false,
+    };
+    assert_eq!(expected, read);
+    assert_eq!(vec![expected], listed);
+    assert_eq!(1, num_deleted);
+    assert!(listed_after_delete.is_empty());
+  }
+}
@@ -1,11 +1,21 @@
 use crate::{
+  apub_id_variants,
   naive_now,
-  newtypes::{DbUrl, PersonId},
+  newtypes::{CommunityId, PersonId},
   schema::person::dsl::*,
-  source::person::{Person, PersonForm},
-  traits::Crud,
+  source::person::{Person, PersonForm, PersonSafe},
+  traits::{Crud, ToSafe},
+};
+use diesel::{
+  dsl::*,
+  result::Error,
+  BoolExpressionMethods,
+  ExpressionMethods,
+  PgConnection,
+  PgTextExpressionMethods,
+  QueryDsl,
+  RunQueryDsl,
 };
-use diesel::{dsl::*, result::Error, ExpressionMethods, PgConnection, QueryDsl, RunQueryDsl};
 use url::Url;
 
 mod safe_type {
@@ -237,11 +247,11 @@ impl Person {
 
   pub fn read_from_apub_id(conn: &PgConnection, object_id: Url) -> Result<Option<Self>, Error> {
     use crate::schema::person::dsl::*;
-    let object_id: DbUrl = object_id.into();
+    let object_ids = apub_id_variants(object_id);
     Ok(
       person
         .filter(deleted.eq(false))
-        .filter(actor_id.eq(object_id))
+        .filter(actor_id.eq_any(object_ids))
         .first::<Person>(conn)
         .ok()
         .map(Into::into),
@@ -258,11 +268,45 @@ impl Person {
       .set(deleted.eq(new_deleted))
       .get_result::<Self>(conn)
   }
+
+  /// Persons whose name or display name starts with the prefix, and who posted or commented in the
+  /// community. Both name columns have a trigram index, so this is fast enough to run on each
+  /// keystroke.
+  pub fn search_for_mention(
+    conn: &PgConnection,
+    in_community_id: CommunityId,
+    prefix: &str,
+    limit: i64,
+  ) -> Result<Vec<PersonSafe>, Error> {
+    use crate::schema::{comment, post};
+    let pattern = format!("{}%", prefix.replace("%", "\\%").replace("_", "\\_"));
+    let posters = post::table
+      .filter(post::community_id.eq(in_community_id))
+      .select(post::creator_id);
+    let commenters = comment::table
+      .inner_join(post::table)
+      .filter(post::community_id.eq(in_community_id))
+      .select(comment::creator_id);
+    person
+      .filter(name.ilike(&pattern).or(display_name.ilike(&pattern)))
+      .filter(id.eq_any(posters).or(id.eq_any(commenters)))
+      .filter(deleted.eq(false))
+      .filter(banned.eq(false))
+      .order_by(name)
+      .limit(limit)
+      .select(Person::safe_columns_tuple())
+      .load::<PersonSafe>(conn)
+  }
 }
 
 #[cfg(test)]
 mod tests {
-  use crate::{establish_unpooled_connection, source::person::*, traits::Crud};
+  use crate::{
+    establish_unpooled_connection,
+    source::{comment::*, community::*, person::*, post::*},
+    traits::Crud,
+  };
+  use serial_test::serial;
 
   #[test]
   fn test_crud() {
@@ -308,4 +352,64 @@ mod tests {
     assert_eq!(expected_person, updated_person);
     assert_eq!(1, num_deleted);
   }
+
+  #[test]
+  #[serial]
+  fn test_search_for_mention() {
+    let conn = establish_unpooled_connection();
+
+    let person = |name: &str, display_name: Option<&str>| {
+      let form = PersonForm {
+        name: name.into(),
+        display_name: display_name.map(|d| Some(d.to_string())),
+        ..PersonForm::default()
+      };
+      Person::create(&conn, &form).unwrap()
+    };
+    let poster = person("mention_poster", None);
+    let commenter = person("other_name", Some("Mention Commenter"));
+    let outsider = person("mention_outsider", None);
+
+    let community_form = CommunityForm {
+      name: "mention_community".to_string(),
+      title: "nada".to_owned(),
+      ..CommunityForm::default()
+    };
+    let community = Community::create(&conn, &community_form).unwrap();
+    let post_form = PostForm {
+      name: "A test post".into(),
+      creator_id: poster.id,
+      community_id: community.id,
+      ..PostForm::default()
+    };
+    let post = Post::create(&conn, &post_form).unwrap();
+    let comment_form = CommentForm {
+      content: "A test comment".into(),
+      creator_id: commenter.id,
+      post_id: post.id,
+      ..CommentForm::default()
+    };
+    Comment::create(&conn, &comment_form).unwrap();
+
+    let search = |prefix: &str| {
+      Person::search_for_mention(&conn, community.id, prefix, 10)
+        .unwrap()
+        .into_iter()
+        .map(|p| p.id)
+        .collect::<Vec<_>>()
+    };
+    let by_prefix = search("MENTION");
+    let by_name = search("mention_p");
+    // Wildcards in the input are matched literally
+    let wildcard = search("%");
+
+    Community::delete(&conn, community.id).unwrap();
+    for p in [&poster, &commenter, &outsider] {
+      Person::delete(&conn, p.id).unwrap();
+    }
+
+    assert_eq!(vec![poster.id, commenter.id], by_prefix);
+    assert_eq!(vec![poster.id], by_name);
+    assert!(wildcard.is_empty());
+  }
 }
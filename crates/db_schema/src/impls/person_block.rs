@@ -17,6 +17,34 @@ impl PersonBlock {
       .filter(target_id.eq(for_recipient_id))
       .first::<Self>(conn)
   }
+
+  /// Returns true if the person blocks the target, and also prevents them from interacting
+  pub fn prevents_interaction(
+    conn: &PgConnection,
+    for_person_id: PersonId,
+    for_target_id: PersonId,
+  ) -> Result<bool, Error> {
+    use crate::schema::person_block::dsl::*;
+    select(exists(
+      person_block
+        .filter(person_id.eq(for_person_id))
+        .filter(target_id.eq(for_target_id))
+        .filter(prevent_interaction.eq(true)),
+    ))
+    .get_result(conn)
+  }
+
+  /// Returns the ids of all persons who block the given person
+  pub fn read_blockers(
+    conn: &PgConnection,
+    for_target_id: PersonId,
+  ) -> Result<Vec<PersonId>, Error> {
+    use crate::schema::person_block::dsl::*;
+    person_block
+      .filter(target_id.eq(for_target_id))
+      .select(person_id)
+      .load::<PersonId>(conn)
+  }
 }
 
 impl Blockable for PersonBlock {
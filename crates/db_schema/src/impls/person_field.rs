@@ -0,0 +1,100 @@
+use crate::{newtypes::PersonId, source::person_field::*};
+use diesel::{dsl::*, result::Error, *};
+
+impl PersonField {
+  /// Sets exactly the given fields on the profile, in the order of their position
+  pub fn replace_for_person(
+    conn: &PgConnection,
+    for_person_id: PersonId,
+    forms: &[PersonFieldForm],
+  ) -> Result<Vec<Self>, Error> {
+    use crate::schema::person_field::dsl::*;
+    conn.transaction(|| {
+      let fields = forms
+        .iter()
+        .map(|form| {
+          insert_into(person_field)
+            .values(form)
+            .on_conflict((person_id, position))
+            .do_update()
+            .set(form)
+            .get_result::<Self>(conn)
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+      delete(
+        person_field
+          .filter(person_id.eq(for_person_id))
+          .filter(position.ge(forms.len() as i32)),
+      )
+      .execute(conn)?;
+      Ok(fields)
+    })
+  }
+
+  /// Ordered by position
+  pub fn list_for_person(conn: &PgConnection, for_person_id: PersonId) -> Result<Vec<Self>, Error> {
+    use crate::schema::person_field::dsl::*;
+    person_field
+      .filter(person_id.eq(for_person_id))
+      .order_by(position.asc())
+      .load::<Self>(conn)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::{
+    establish_unpooled_connection,
+    source::{person::*, person_field::*},
+    traits::Crud,
+  };
+  use serial_test::serial;
+
+  #[test]
+  #[serial]
+  fn test_person_field() {
+    let conn = establish_unpooled_connection();
+
+    let new_person = PersonForm {
+      name: "person_field_person".into(),
+      ..PersonForm::default()
+    };
+    let inserted_person = Person::create(&conn, &new_person).unwrap();
+
+    let field_forms = |fields: &[(&str, &str)]| -> Vec<PersonFieldForm> {
+      fields
+        .iter()
+        .enumerate()
+        .map(|(position, (name, value))| PersonFieldForm {
+          person_id: inserted_person.id,
+          name: name.to_string(),
+          value: value.to_string(),
+          position: position as i32,
+        })
+        .collect()
+    };
+    let website = ("Website", "https://example.com");
+    let pronouns = ("Pronouns", "they/them");
+    let inserted = PersonField::replace_for_person(
+      &conn,
+      inserted_person.id,
+      &field_forms(&[website, pronouns]),
+    )
+    .unwrap();
+    assert_eq!(
+      inserted,
+      PersonField::list_for_person(&conn, inserted_person.id).unwrap()
+    );
+
+    // Fields which are gone are removed, and the remaining ones move up
+    let replaced =
+      PersonField::replace_for_person(&conn, inserted_person.id, &field_forms(&[pronouns]))
+        .unwrap();
+    let listed = PersonField::list_for_person(&conn, inserted_person.id).unwrap();
+    assert_eq!(replaced, listed);
+    assert_eq!(1, listed.len());
+    assert_eq!("Pronouns", listed[0].name);
+
+    Person::delete(&conn, inserted_person.id).unwrap();
+  }
+}
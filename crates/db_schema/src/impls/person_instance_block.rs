@@ -0,0 +1,65 @@
+use crate::{
+  newtypes::PersonId,
+  source::person_instance_block::{PersonInstanceBlock, PersonInstanceBlockForm},
+  traits::Blockable,
+};
+use diesel::{dsl::*, result::Error, *};
+
+impl PersonInstanceBlock {
+  pub fn for_person(conn: &PgConnection, for_person_id: PersonId) -> Result<Vec<Self>, Error> {
+    use crate::schema::person_instance_block::dsl::*;
+    person_instance_block
+      .filter(person_id.eq(for_person_id))
+      .order_by(domain)
+      .load::<Self>(conn)
+  }
+
+  /// Returns `like` patterns which match the actor ids on all domains blocked by the person. They
+  /// are used to filter out content from these instances.
+  pub fn actor_id_patterns(
+    conn: &PgConnection,
+    for_person_id: PersonId,
+  ) -> Result<Vec<String>, Error> {
+    use crate::schema::person_instance_block::dsl::*;
+    let domains = person_instance_block
+      .filter(person_id.eq(for_person_id))
+      .select(domain)
+      .load::<String>(conn)?;
+    Ok(
+      domains
+        .iter()
+        .map(|d| d.replace('%', "\\%").replace('_', "\\_"))
+        // Actor ids may include a port, or consist only of the domain
+        .flat_map(|d| {
+          vec![
+            format!("%://{}/%", d),
+            format!("%://{}:%", d),
+            format!("%://{}", d),
+          ]
+        })
+        .collect(),
+    )
+  }
+}
+
+impl Blockable for PersonInstanceBlock {
+  type Form = PersonInstanceBlockForm;
+  fn block(conn: &PgConnection, form: &Self::Form) -> Result<Self, Error> {
+    use crate::schema::person_instance_block::dsl::*;
+    insert_into(person_instance_block)
+      .values(form)
+      .on_conflict((person_id, domain))
+      .do_update()
+      .set(form)
+      .get_result::<Self>(conn)
+  }
+  fn unblock(conn: &PgConnection, form: &Self::Form) -> Result<usize, Error> {
+    use crate::schema::person_instance_block::dsl::*;
+    diesel::delete(
+      person_instance_block
+        .filter(person_id.eq(form.person_id))
+        .filter(domain.eq(&form.domain)),
+    )
+    .execute(conn)
+  }
+}
@@ -0,0 +1,203 @@
+use crate::{
+  newtypes::{PersonId, PostId},
+  source::poll::*,
+};
+use chrono::NaiveDateTime;
+use diesel::{dsl::*, result::Error, *};
+
+impl Poll {
+  pub fn upsert(conn: &PgConnection, form: &PollForm) -> Result<Self, Error> {
+    use crate::schema::poll::dsl::*;
+    insert_into(poll)
+      .values(form)
+      .on_conflict(post_id)
+      .do_update()
+      .set(form)
+      .get_result::<Self>(conn)
+  }
+
+  pub fn read_for_post(conn: &PgConnection, for_post_id: PostId) -> Result<Self, Error> {
+    use crate::schema::poll::dsl::*;
+    poll.filter(post_id.eq(for_post_id)).first::<Self>(conn)
+  }
+
+  pub fn has_ended(&self, current_time: NaiveDateTime) -> bool {
+    self.end_time.map(|e| e <= current_time).unwrap_or(false)
+  }
+}
+
+impl PollOption {
+  /// Sets the options of the poll in the given order, replacing any existing ones. Vote counts of
+  /// existing options are only overwritten if given in the form.
+  pub fn replace_for_poll(
+    conn: &PgConnection,
+    for_poll_id: i32,
+    forms: &[PollOptionForm],
+  ) -> Result<Vec<Self>, Error> {
+    use crate::schema::poll_option::dsl::*;
+    conn.transaction(|| {
+      let options = forms
+        .iter()
+        .map(|form| {
+          insert_into(poll_option)
+            .values(form)
+            .on_conflict((poll_id, position))
+            .do_update()
+            .set(form)
+            .get_result::<Self>(conn)
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+      delete(
+        poll_option
+          .filter(poll_id.eq(for_poll_id))
+          .filter(position.ge(forms.len() as i32)),
+      )
+      .execute(conn)?;
+      Ok(options)
+    })
+  }
+
+  /// Ordered by position
+  pub fn list_for_poll(conn: &PgConnection, for_poll_id: i32) -> Result<Vec<Self>, Error> {
+    use crate::schema::poll_option::dsl::*;
+    poll_option
+      .filter(poll_id.eq(for_poll_id))
+      .order_by(position.asc())
+      .load::<Self>(conn)
+  }
+
+  /// Recounts the votes of a local poll
+  pub fn update_vote_counts(conn: &PgConnection, for_poll_id: i32) -> Result<Vec<Self>, Error> {
+    use crate::schema::{poll_option::dsl::*, poll_vote};
+    let option_ids = poll_option
+      .filter(poll_id.eq(for_poll_id))
+      .select(id)
+      .load::<i32>(conn)?;
+    for option_id in option_ids {
+      let count = poll_vote::table
+        .filter(poll_vote::poll_option_id.eq(option_id))
+        .select(count_star())
+        .first::<i64>(conn)?;
+      diesel::update(poll_option.find(option_id))
+        .set(votes.eq(count as i32))
+        .execute(conn)?;
+    }
+    PollOption::list_for_poll(conn, for_poll_id)
+  }
+}
+
+impl PollVote {
+  /// Returns the number of new votes, so 0 if the person already voted for this option
+  pub fn vote(conn: &PgConnection, form: &PollVoteForm) -> Result<usize, Error> {
+    use crate::schema::poll_vote::dsl::*;
+    insert_into(poll_vote)
+      .values(form)
+      .on_conflict_do_nothing()
+      .execute(conn)
+  }
+
+  /// The options which the person voted for
+  pub fn list_for_person(
+    conn: &PgConnection,
+    for_poll_id: i32,
+    for_person_id: PersonId,
+  ) -> Result<Vec<i32>, Error> {
+    use crate::schema::{poll_option, poll_vote::dsl::*};
+    poll_vote
+      .inner_join(poll_option::table)
+      .filter(poll_option::poll_id.eq(for_poll_id))
+      .filter(person_id.eq(for_person_id))
+      .select(poll_option_id)
+      .order_by(poll_option::position.asc())
+      .load::<i32>(conn)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::{
+    establish_unpooled_connection,
+    source::{community::*, person::*, poll::*, post::*},
+    traits::Crud,
+  };
+  use serial_test::serial;
+
+  #[test]
+  #[serial]
+  fn test_poll() {
+    let conn = establish_unpooled_connection();
+
+    let person_form = PersonForm {
+      name: "poll_voter".into(),
+      ..PersonForm::default()
+    };
+    let person = Person::create(&conn, &person_form).unwrap();
+    let community_form = CommunityForm {
+      name: "poll_community".to_string(),
+      title: "nada".to_owned(),
+      ..CommunityForm::default()
+    };
+    let community = Community::create(&conn, &community_form).unwrap();
+    let post_form = PostForm {
+      name: "Which one?".into(),
+      creator_id: person.id,
+      community_id: community.id,
+      ..PostForm::default()
+    };
+    let post = Post::create(&conn, &post_form).unwrap();
+
+    let poll_form = PollForm {
+      post_id: post.id,
+      multiple_choice: false,
+      end_time: None,
+    };
+    let poll = Poll::upsert(&conn, &poll_form).unwrap();
+    let option_forms = |names: &[&str]| {
+      names
+        .iter()
+        .enumerate()
+        .map(|(i, n)| PollOptionForm {
+          poll_id: poll.id,
+          name: n.to_string(),
+          position: i as i32,
+          votes: None,
+        })
+        .collect::<Vec<_>>()
+    };
+    let options =
+      PollOption::replace_for_poll(&conn, poll.id, &option_forms(&["a", "b", "c"])).unwrap();
+
+    let vote_form = PollVoteForm {
+      poll_option_id: options[1].id,
+      person_id: person.id,
+    };
+    let first_vote = PollVote::vote(&conn, &vote_form).unwrap();
+    let repeated_vote = PollVote::vote(&conn, &vote_form).unwrap();
+    let counted = PollOption::update_vote_counts(&conn, poll.id).unwrap();
+    let my_votes = PollVote::list_for_person(&conn, poll.id, person.id).unwrap();
+
+    // Replacing keeps the vote counts, and drops options which are gone
+    let replaced =
+      PollOption::replace_for_poll(&conn, poll.id, &option_forms(&["a", "b"])).unwrap();
+    let listed = PollOption::list_for_poll(&conn, poll.id).unwrap();
+    let read_poll = Poll::read_for_post(&conn, post.id).unwrap();
+
+    Community::delete(&conn, community.id).unwrap();
+    Person::delete(&conn, person.id).unwrap();
+
+    assert_eq!(1, first_vote);
+    assert_eq!(0, repeated_vote);
+    assert_eq!(
+      vec![0, 1, 0],
+      counted.iter().map(|o| o.votes).collect::<Vec<_>>()
+    );
+    assert_eq!(vec![options[1].id], my_votes);
+    assert_eq!(replaced, listed);
+    assert_eq!(
+      vec![0, 1],
+      listed.iter().map(|o| o.votes).collect::<Vec<_>>()
+    );
+    assert_eq!(poll, read_poll);
+    assert!(!read_poll.has_ended(crate::naive_now()));
+  }
+}
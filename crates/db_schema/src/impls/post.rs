@@ -1,19 +1,34 @@
 use crate::{
+  aggregates::reaction_count::ReactionCount,
+  apub_id_variants,
+  functions::{coalesce, search_headline},
+  limit_and_offset,
   naive_now,
-  newtypes::{CommunityId, DbUrl, PersonId, PostId},
+  newtypes::{CommunityId, DbUrl, PersonId, PostId, SavedCollectionId},
   source::post::{
     Post,
     PostForm,
     PostLike,
     PostLikeForm,
+    PostReaction,
+    PostReactionForm,
     PostRead,
     PostReadForm,
     PostSaved,
     PostSavedForm,
   },
-  traits::{Crud, DeleteableOrRemoveable, Likeable, Readable, Saveable},
+  traits::{Crud, DeleteableOrRemoveable, Likeable, Reactable, Readable, Saveable},
+};
+use diesel::{
+  dsl::*,
+  result::Error,
+  BoolExpressionMethods,
+  ExpressionMethods,
+  NullableExpressionMethods,
+  PgConnection,
+  QueryDsl,
+  RunQueryDsl,
 };
-use diesel::{dsl::*, result::Error, ExpressionMethods, PgConnection, QueryDsl, RunQueryDsl};
 use url::Url;
 
 impl Crud for Post {
@@ -43,19 +58,69 @@ impl Crud for Post {
 }
 
 impl Post {
+  /// Posts for the outbox of the community, newest first
   pub fn list_for_community(
     conn: &PgConnection,
     the_community_id: CommunityId,
+    page: Option<i64>,
+    limit: Option<i64>,
   ) -> Result<Vec<Self>, Error> {
     use crate::schema::post::dsl::*;
+    let (limit, offset) = limit_and_offset(page, limit);
     post
       .filter(community_id.eq(the_community_id))
+      .filter(pending.eq(false))
       .then_order_by(published.desc())
-      .then_order_by(stickied.desc())
-      .limit(20)
+      .then_order_by(id.desc())
+      .limit(limit)
+      .offset(offset)
       .load::<Self>(conn)
   }
 
+  pub fn count_for_community(
+    conn: &PgConnection,
+    the_community_id: CommunityId,
+  ) -> Result<i64, Error> {
+    use crate::schema::post::dsl::*;
+    post
+      .filter(community_id.eq(the_community_id))
+      .filter(pending.eq(false))
+      .count()
+      .get_result(conn)
+  }
+
+  /// Published posts for the outbox of the person, newest first
+  pub fn list_for_creator(
+    conn: &PgConnection,
+    the_creator_id: PersonId,
+    page: Option<i64>,
+    limit: Option<i64>,
+  ) -> Result<Vec<Self>, Error> {
+    use crate::schema::post::dsl::*;
+    let (limit, offset) = limit_and_offset(page, limit);
+    post
+      .filter(creator_id.eq(the_creator_id))
+      .filter(deleted.eq(false))
+      .filter(removed.eq(false))
+      .filter(pending.eq(false))
+      .then_order_by(published.desc())
+      .then_order_by(id.desc())
+      .limit(limit)
+      .offset(offset)
+      .load::<Self>(conn)
+  }
+
+  pub fn count_for_creator(conn: &PgConnection, the_creator_id: PersonId) -> Result<i64, Error> {
+    use crate::schema::post::dsl::*;
+    post
+      .filter(creator_id.eq(the_creator_id))
+      .filter(deleted.eq(false))
+      .filter(removed.eq(false))
+      .filter(pending.eq(false))
+      .count()
+      .get_result(conn)
+  }
+
   pub fn update_ap_id(conn: &PgConnection, post_id: PostId, apub_id: DbUrl) -> Result<Self, Error> {
     use crate::schema::post::dsl::*;
 
@@ -64,6 +129,80 @@ impl Post {
       .get_result::<Self>(conn)
   }
 
+  /// All posts of the creator, including deleted, removed and pending ones
+  pub fn list_all_for_creator(
+    conn: &PgConnection,
+    for_creator_id: PersonId,
+  ) -> Result<Vec<Self>, Error> {
+    use crate::schema::post::dsl::*;
+    post
+      .filter(creator_id.eq(for_creator_id))
+      .order_by(id)
+      .load::<Self>(conn)
+  }
+
+  pub fn list_ids_for_community(
+    conn: &PgConnection,
+    for_community_id: CommunityId,
+  ) -> Result<Vec<PostId>, Error> {
+    use crate::schema::post::dsl::*;
+    post
+      .filter(community_id.eq(for_community_id))
+      .select(id)
+      .load::<PostId>(conn)
+  }
+
+  /// Distinct communities of the given posts
+  pub fn list_community_ids(
+    conn: &PgConnection,
+    post_ids: Vec<PostId>,
+  ) -> Result<Vec<CommunityId>, Error> {
+    use crate::schema::post::dsl::*;
+    post
+      .filter(id.eq_any(post_ids))
+      .select(community_id)
+      .distinct()
+      .load::<CommunityId>(conn)
+  }
+
+  /// Links, thumbnails and attachments of the posts. These can point to uploaded images, which are
+  /// purged together with the posts.
+  pub fn list_media_urls(conn: &PgConnection, post_ids: Vec<PostId>) -> Result<Vec<DbUrl>, Error> {
+    use crate::schema::{post::dsl::*, post_attachment};
+    let links = post
+      .filter(id.eq_any(post_ids.clone()))
+      .select((url, thumbnail_url))
+      .load::<(Option<DbUrl>, Option<DbUrl>)>(conn)?;
+    let attachments = post_attachment::table
+      .filter(post_attachment::post_id.eq_any(post_ids))
+      .select(post_attachment::url)
+      .load::<DbUrl>(conn)?;
+    Ok(
+      links
+        .into_iter()
+        .flat_map(|(link, thumbnail)| link.into_iter().chain(thumbnail))
+        .chain(attachments)
+        .collect(),
+    )
+  }
+
+  /// Excerpts of the posts with the matches of the search term highlighted. Posts without a body
+  /// get their title highlighted instead.
+  pub fn search_snippets(
+    conn: &PgConnection,
+    post_ids: Vec<PostId>,
+    search_term: &str,
+  ) -> Result<Vec<(PostId, String)>, Error> {
+    use crate::schema::post::dsl::*;
+    post
+      .filter(id.eq_any(post_ids))
+      .select((
+        id,
+        search_headline(coalesce(body, name), search_term, language_id),
+      ))
+      .load::<(PostId, String)>(conn)
+  }
+
   pub fn permadelete_for_creator(
     conn: &PgConnection,
     for_creator_id: PersonId,
@@ -106,6 +245,50 @@ impl Post {
       .get_result::<Self>(conn)
   }
 
+  /// Checks if the original post, or any cross-post of it, was posted in the community
+  pub fn is_cross_posted_to(
+    conn: &PgConnection,
+    original_post_id: PostId,
+    the_community_id: CommunityId,
+  ) -> Result<bool, Error> {
+    use crate::schema::post::dsl::*;
+    diesel::select(exists(
+      post
+        .filter(
+          id.eq(original_post_id)
+            .or(cross_post_of.eq(original_post_id)),
+        )
+        .filter(community_id.eq(the_community_id)),
+    ))
+    .get_result(conn)
+  }
+
+  /// Permanently deletes posts which were deleted by their creator (or removed, if
+  /// `purge_removed` is true) before the given time. Posts with comments, unresolved reports or a
+  /// legal hold are kept.
+  pub fn purge_expired(
+    conn: &PgConnection,
+    purge_removed: bool,
+    before: chrono::NaiveDateTime,
+  ) -> Result<usize, Error> {
+    use crate::schema::{comment, legal_hold, post::dsl::*, post_report};
+    let comments = comment::table.filter(comment::post_id.eq(id));
+    let unresolved_reports = post_report::table
+      .filter(post_report::post_id.eq(id))
+      .filter(post_report::resolved.eq(false));
+    let holds = legal_hold::table.filter(legal_hold::post_id.eq(id.nullable()));
+    let expired = post
+      .filter(updated.lt(before))
+      .filter(not(exists(comments)))
+      .filter(not(exists(unresolved_reports)))
+      .filter(not(exists(holds)));
+    if purge_removed {
+      diesel::delete(expired.filter(removed.eq(true))).execute(conn)
+    } else {
+      diesel::delete(expired.filter(deleted.eq(true)).filter(removed.eq(false))).execute(conn)
+    }
+  }
+
   pub fn update_removed_for_creator(
     conn: &PgConnection,
     for_creator_id: PersonId,
@@ -115,7 +298,9 @@ impl Post {
     use crate::schema::post::dsl::*;
 
     let mut update = diesel::update(post).into_boxed();
-    update = update.filter(creator_id.eq(for_creator_id));
+    update = update
+      .filter(creator_id.eq(for_creator_id))
+      .filter(removed.ne(new_removed));
 
     if let Some(for_community_id) = for_community_id {
       update = update.filter(community_id.eq(for_community_id));
@@ -137,6 +322,28 @@ impl Post {
       .get_result::<Self>(conn)
   }
 
+  pub fn update_pending(
+    conn: &PgConnection,
+    post_id: PostId,
+    new_pending: bool,
+  ) -> Result<Self, Error> {
+    use crate::schema::post::dsl::*;
+    diesel::update(post.find(post_id))
+      .set(pending.eq(new_pending))
+      .get_result::<Self>(conn)
+  }
+
+  pub fn update_shadow_removed(
+    conn: &PgConnection,
+    post_id: PostId,
+    new_shadow_removed: bool,
+  ) -> Result<Self, Error> {
+    use crate::schema::post::dsl::*;
+    diesel::update(post.find(post_id))
+      .set(shadow_removed.eq(new_shadow_removed))
+      .get_result::<Self>(conn)
+  }
+
   pub fn update_stickied(
     conn: &PgConnection,
     post_id: PostId,
@@ -148,6 +355,16 @@ impl Post {
       .get_result::<Self>(conn)
   }
 
+  /// Number of posts which are currently stickied in the community
+  pub fn count_stickied(conn: &PgConnection, for_community_id: CommunityId) -> Result<i64, Error> {
+    use crate::schema::post::dsl::*;
+    post
+      .filter(community_id.eq(for_community_id))
+      .filter(stickied.eq(true))
+      .count()
+      .get_result(conn)
+  }
+
   pub fn is_post_creator(person_id: PersonId, post_creator_id: PersonId) -> bool {
     person_id == post_creator_id
   }
@@ -163,10 +380,10 @@ impl Post {
   }
   pub fn read_from_apub_id(conn: &PgConnection, object_id: Url) -> Result<Option<Self>, Error> {
     use crate::schema::post::dsl::*;
-    let object_id: DbUrl = object_id.into();
+    let object_ids = apub_id_variants(object_id);
     Ok(
       post
-        .filter(ap_id.eq(object_id))
+        .filter(ap_id.eq_any(object_ids))
         .first::<Post>(conn)
         .ok()
         .map(Into::into),
@@ -197,6 +414,45 @@ impl Likeable for PostLike {
   }
 }
 
+impl Reactable for PostReaction {
+  type Form = PostReactionForm;
+  type IdType = PostId;
+  fn react(conn: &PgConnection, form: &PostReactionForm) -> Result<usize, Error> {
+    use crate::schema::post_reaction::dsl::*;
+    insert_into(post_reaction)
+      .values(form)
+      .on_conflict((post_id, person_id, emoji))
+      .do_nothing()
+      .execute(conn)
+  }
+  fn unreact(
+    conn: &PgConnection,
+    person_id: PersonId,
+    post_id: PostId,
+    emoji: &str,
+  ) -> Result<usize, Error> {
+    use crate::schema::post_reaction::dsl;
+    diesel::delete(
+      dsl::post_reaction
+        .filter(dsl::post_id.eq(post_id))
+        .filter(dsl::person_id.eq(person_id))
+        .filter(dsl::emoji.eq(emoji)),
+    )
+    .execute(conn)
+  }
+  fn counts(
+    conn: &PgConnection,
+    post_ids: Vec<PostId>,
+  ) -> Result<Vec<(PostId, ReactionCount)>, Error> {
+    use crate::schema::post_reaction::dsl;
+    let reactions = dsl::post_reaction
+      .filter(dsl::post_id.eq_any(post_ids))
+      .select((dsl::post_id, dsl::emoji))
+      .load::<(PostId, String)>(conn)?;
+    Ok(ReactionCount::count(reactions))
+  }
+}
+
 impl Saveable for PostSaved {
   type Form = PostSavedForm;
   fn save(conn: &PgConnection, post_saved_form: &PostSavedForm) -> Result<Self, Error> {
@@ -219,6 +475,26 @@ impl Saveable for PostSaved {
   }
 }
 
+impl PostSaved {
+  /// Files an already saved post into the given collection, or back into the unfiled list if
+  /// `new_collection_id` is `None`.
+  pub fn move_to_collection(
+    conn: &PgConnection,
+    for_person_id: PersonId,
+    for_post_id: PostId,
+    new_collection_id: Option<SavedCollectionId>,
+  ) -> Result<Self, Error> {
+    use crate::schema::post_saved::dsl::*;
+    diesel::update(
+      post_saved
+        .filter(person_id.eq(for_person_id))
+        .filter(post_id.eq(for_post_id)),
+    )
+    .set(collection_id.eq(new_collection_id))
+    .get_result::<Self>(conn)
+  }
+}
+
 impl Readable for PostRead {
   type Form = PostReadForm;
   fn mark_as_read(conn: &PgConnection, post_read_form: &PostReadForm) -> Result<Self, Error> {
@@ -250,6 +526,8 @@ impl DeleteableOrRemoveable for Post {
     self.embed_title = None;
     self.embed_description = None;
     self.embed_html = None;
+    self.embed_video_url = None;
+    self.embed_provider_name = None;
     self.thumbnail_url = None;
 
     self
@@ -259,15 +537,19 @@ impl DeleteableOrRemoveable for Post {
 #[cfg(test)]
 mod tests {
   use crate::{
+    aggregates::reaction_count::ReactionCount,
     establish_unpooled_connection,
+    newtypes::DbUrl,
     source::{
       community::{Community, CommunityForm},
       person::*,
       post::*,
+      post_attachment::{PostAttachment, PostAttachmentForm},
     },
-    traits::{Crud, Likeable, Readable, Saveable},
+    traits::{Crud, Likeable, Reactable, Readable, Saveable},
   };
   use serial_test::serial;
+  use url::Url;
 
   #[test]
   #[serial]
@@ -318,6 +600,12 @@ mod tests {
       thumbnail_url: None,
       ap_id: inserted_post.ap_id.to_owned(),
       local: true,
+      shadow_removed: false,
+      cross_post_of: None,
+      embed_video_url: None,
+      embed_provider_name: None,
+      language_id: Default::default(),
+      pending: false,
     };
 
     // Post Like
@@ -337,10 +625,27 @@ mod tests {
       score: 1,
     };
 
+    // Post Reaction
+    let post_reaction_form = PostReactionForm {
+      post_id: inserted_post.id,
+      person_id: inserted_person.id,
+      emoji: "🎉".to_string(),
+    };
+    let reacted = PostReaction::react(&conn, &post_reaction_form).unwrap();
+    // Reacting twice with the same emoji has no effect
+    let reacted_again = PostReaction::react(&conn, &post_reaction_form).unwrap();
+    let other_reaction_form = PostReactionForm {
+      emoji: "👀".to_string(),
+      ..post_reaction_form.clone()
+    };
+    PostReaction::react(&conn, &other_reaction_form).unwrap();
+    let reaction_counts = PostReaction::counts(&conn, vec![inserted_post.id]).unwrap();
+
     // Post Save
     let post_saved_form = PostSavedForm {
       post_id: inserted_post.id,
       person_id: inserted_person.id,
+      collection_id: None,
     };
 
     let inserted_post_saved = PostSaved::save(&conn, &post_saved_form).unwrap();
@@ -350,6 +655,7 @@ mod tests {
       post_id: inserted_post.id,
       person_id: inserted_person.id,
       published: inserted_post_saved.published,
+      collection_id: None,
     };
 
     // Post Read
@@ -368,8 +674,17 @@ mod tests {
     };
 
     let read_post = Post::read(&conn, inserted_post.id).unwrap();
+    let community_posts =
+      Post::list_for_community(&conn, inserted_community.id, Some(1), Some(20)).unwrap();
+    let community_posts_page_2 =
+      Post::list_for_community(&conn, inserted_community.id, Some(2), Some(20)).unwrap();
+    let creator_posts = Post::list_for_creator(&conn, inserted_person.id, None, None).unwrap();
+    let community_post_count = Post::count_for_community(&conn, inserted_community.id).unwrap();
+    let creator_post_count = Post::count_for_creator(&conn, inserted_person.id).unwrap();
     let updated_post = Post::update(&conn, inserted_post.id, &new_post).unwrap();
     let like_removed = PostLike::remove(&conn, inserted_person.id, inserted_post.id).unwrap();
+    let reaction_removed =
+      PostReaction::unreact(&conn, inserted_person.id, inserted_post.id, "🎉").unwrap();
     let saved_removed = PostSaved::unsave(&conn, &post_saved_form).unwrap();
     let read_removed = PostRead::mark_as_unread(&conn, &post_read_form).unwrap();
     let num_deleted = Post::delete(&conn, inserted_post.id).unwrap();
@@ -377,14 +692,111 @@ mod tests {
     Person::delete(&conn, inserted_person.id).unwrap();
 
     assert_eq!(expected_post, read_post);
+    assert_eq!(vec![expected_post.clone()], community_posts);
+    assert!(community_posts_page_2.is_empty());
+    assert_eq!(vec![expected_post.clone()], creator_posts);
+    assert_eq!(1, community_post_count);
+    assert_eq!(1, creator_post_count);
     assert_eq!(expected_post, inserted_post);
     assert_eq!(expected_post, updated_post);
     assert_eq!(expected_post_like, inserted_post_like);
     assert_eq!(expected_post_saved, inserted_post_saved);
     assert_eq!(expected_post_read, inserted_post_read);
     assert_eq!(1, like_removed);
+    assert_eq!(1, reacted);
+    assert_eq!(0, reacted_again);
+    assert_eq!(
+      vec![
+        (
+          inserted_post.id,
+          ReactionCount {
+            emoji: "🎉".to_string(),
+            count: 1
+          }
+        ),
+        (
+          inserted_post.id,
+          ReactionCount {
+            emoji: "👀".to_string(),
+            count: 1
+          }
+        ),
+      ],
+      reaction_counts
+    );
+    assert_eq!(1, reaction_removed);
     assert_eq!(1, saved_removed);
     assert_eq!(1, read_removed);
     assert_eq!(1, num_deleted);
   }
+
+  #[test]
+  #[serial]
+  fn test_list_media_urls() {
+    let conn = establish_unpooled_connection();
+
+    let new_person = PersonForm {
+      name: "media_urls_person".into(),
+      ..PersonForm::default()
+    };
+    let inserted_person = Person::create(&conn, &new_person).unwrap();
+    let new_community = CommunityForm {
+      name: "media_urls_community".to_string(),
+      title: "nada".to_owned(),
+      ..CommunityForm::default()
+    };
+    let inserted_community = Community::create(&conn, &new_community).unwrap();
+
+    let link: DbUrl = Url::parse("https://example.com/pictrs/image/link.jpg")
+      .unwrap()
+      .into();
+    let thumbnail: DbUrl = Url::parse("https://example.com/pictrs/image/thumbnail.jpg")
+      .unwrap()
+      .into();
+    let attachment: DbUrl = Url::parse("https://example.com/pictrs/image/attachment.png")
+      .unwrap()
+      .into();
+    let new_post = PostForm {
+      name: "A post with images".into(),
+      creator_id: inserted_person.id,
+      community_id: inserted_community.id,
+      url: Some(link.clone()),
+      thumbnail_url: Some(thumbnail.clone()),
+      ..PostForm::default()
+    };
+    let inserted_post = Post::create(&conn, &new_post).unwrap();
+    let attachment_form = PostAttachmentForm {
+      post_id: inserted_post.id,
+      url: attachment.clone(),
+      media_type: None,
+      name: None,
+      position: 0,
+    };
+    PostAttachment::replace_for_post(&conn, inserted_post.id, &[attachment_form]).unwrap();
+    let text_post = PostForm {
+      name: "A post without images".into(),
+      creator_id: inserted_person.id,
+      community_id: inserted_community.id,
+      ..PostForm::default()
+    };
+    let inserted_text_post = Post::create(&conn, &text_post).unwrap();
+
+    let post_ids = Post::list_ids_for_community(&conn, inserted_community.id).unwrap();
+    assert_eq!(2, post_ids.len());
+    assert_eq!(
+      vec![link, thumbnail, attachment],
+      Post::list_media_urls(&conn, post_ids).unwrap()
+    );
+    assert_eq!(
+      vec![inserted_post.id, inserted_text_post.id],
+      Post::list_all_for_creator(&conn, inserted_person.id)
+        .unwrap()
+        .into_iter()
+        .map(|p| p.id)
+        .collect::<Vec<_>>()
+    );
+
+    Community::delete(&conn, inserted_community.id).unwrap();
+    Person::delete(&conn, inserted_person.id).unwrap();
+  }
 }
@@ -0,0 +1,119 @@
+use crate::{newtypes::PostId, source::post_attachment::*};
+use diesel::{dsl::*, result::Error, *};
+
+impl PostAttachment {
+  /// Sets exactly the given attachments on the post, in the order of their position
+  pub fn replace_for_post(
+    conn: &PgConnection,
+    for_post_id: PostId,
+    forms: &[PostAttachmentForm],
+  ) -> Result<Vec<Self>, Error> {
+    use crate::schema::post_attachment::dsl::*;
+    conn.transaction(|| {
+      let attachments = forms
+        .iter()
+        .map(|form| {
+          insert_into(post_attachment)
+            .values(form)
+            .on_conflict((post_id, position))
+            .do_update()
+            .set(form)
+            .get_result::<Self>(conn)
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+      delete(
+        post_attachment
+          .filter(post_id.eq(for_post_id))
+          .filter(position.ge(forms.len() as i32)),
+      )
+      .execute(conn)?;
+      Ok(attachments)
+    })
+  }
+
+  /// Ordered by position
+  pub fn list_for_post(conn: &PgConnection, for_post_id: PostId) -> Result<Vec<Self>, Error> {
+    use crate::schema::post_attachment::dsl::*;
+    post_attachment
+      .filter(post_id.eq(for_post_id))
+      .order_by(position.asc())
+      .load::<Self>(conn)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::{
+    establish_unpooled_connection,
+    source::{community::*, person::*, post::*, post_attachment::*},
+    traits::Crud,
+  };
+  use serial_test::serial;
+  use url::Url;
+
+  #[test]
+  #[serial]
+  fn test_post_attachment() {
+    let conn = establish_unpooled_connection();
+
+    let new_person = PersonForm {
+      name: "post_attachment_person".into(),
+      ..PersonForm::default()
+    };
+    let inserted_person = Person::create(&conn, &new_person).unwrap();
+
+    let new_community = CommunityForm {
+      name: "post_attachment_community".to_string(),
+      title: "nada".to_owned(),
+      ..CommunityForm::default()
+    };
+    let inserted_community = Community::create(&conn, &new_community).unwrap();
+
+    let new_post = PostForm {
+      name: "A test post".into(),
+      creator_id: inserted_person.id,
+      community_id: inserted_community.id,
+      ..PostForm::default()
+    };
+    let inserted_post = Post::create(&conn, &new_post).unwrap();
+
+    let attachment_forms = |urls: &[&str]| -> Vec<PostAttachmentForm> {
+      urls
+        .iter()
+        .enumerate()
+        .map(|(position, url)| PostAttachmentForm {
+          post_id: inserted_post.id,
+          url: Url::parse(url).unwrap().into(),
+          media_type: Some("image/png".to_string()),
+          name: None,
+          position: position as i32,
+        })
+        .collect()
+    };
+    let first = "https://example.com/1.png";
+    let second = "https://example.com/2.png";
+    let inserted = PostAttachment::replace_for_post(
+      &conn,
+      inserted_post.id,
+      &attachment_forms(&[first, second]),
+    )
+    .unwrap();
+    assert_eq!(
+      inserted,
+      PostAttachment::list_for_post(&conn, inserted_post.id).unwrap()
+    );
+
+    // Attachments which are gone are removed, and the remaining ones move up
+    let replaced =
+      PostAttachment::replace_for_post(&conn, inserted_post.id, &attachment_forms(&[second]))
+        .unwrap();
+    let listed = PostAttachment::list_for_post(&conn, inserted_post.id).unwrap();
+    assert_eq!(replaced, listed);
+    assert_eq!(1, listed.len());
+    assert_eq!(second, listed[0].url.to_string());
+
+    Post::delete(&conn, inserted_post.id).unwrap();
+    Community::delete(&conn, inserted_community.id).unwrap();
+    Person::delete(&conn, inserted_person.id).unwrap();
+  }
+}
@@ -0,0 +1,106 @@
+use crate::{limit_and_offset, newtypes::PersonId, source::post_draft::*, traits::Crud};
+use diesel::{insert_into, result::Error, ExpressionMethods, PgConnection, QueryDsl, RunQueryDsl};
+
+impl Crud for PostDraft {
+  type Form = PostDraftForm;
+  type IdType = i32;
+  fn create(conn: &PgConnection, form: &Self::Form) -> Result<Self, Error> {
+    use crate::schema::post_draft::dsl::*;
+    insert_into(post_draft)
+      .values(form)
+      .get_result::<Self>(conn)
+  }
+
+  fn read(conn: &PgConnection, id_: Self::IdType) -> Result<Self, Error> {
+    use crate::schema::post_draft::dsl::*;
+    post_draft.find(id_).first::<Self>(conn)
+  }
+
+  fn update(conn: &PgConnection, id_: Self::IdType, form: &Self::Form) -> Result<Self, Error> {
+    use crate::schema::post_draft::dsl::*;
+    diesel::update(post_draft.find(id_))
+      .set(form)
+      .get_result::<Self>(conn)
+  }
+
+  fn delete(conn: &PgConnection, id_: Self::IdType) -> Result<usize, Error> {
+    use crate::schema::post_draft::dsl::*;
+    diesel::delete(post_draft.find(id_)).execute(conn)
+  }
+}
+
+impl PostDraft {
+  /// Newest first
+  pub fn list_for_creator(
+    conn: &PgConnection,
+    for_creator_id: PersonId,
+    page: Option<i64>,
+    limit: Option<i64>,
+  ) -> Result<Vec<Self>, Error> {
+    use crate::schema::post_draft::dsl::*;
+    let (limit, offset) = limit_and_offset(page, limit);
+    post_draft
+      .filter(creator_id.eq(for_creator_id))
+      .order_by(published.desc())
+      .then_order_by(id.desc())
+      .limit(limit)
+      .offset(offset)
+      .load::<Self>(conn)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::{
+    establish_unpooled_connection,
+    naive_now,
+    source::{person::*, post_draft::*},
+    traits::Crud,
+  };
+  use serial_test::serial;
+
+  #[test]
+  #[serial]
+  fn test_crud() {
+    let conn = establish_unpooled_connection();
+
+    let person_form = PersonForm {
+      name: "post_draft_creator".into(),
+      ..PersonForm::default()
+    };
+    let person = Person::create(&conn, &person_form).unwrap();
+
+    let form = PostDraftForm {
+      creator_id: person.id,
+      community_id: None,
+      name: Some("A post about".into()),
+      url: None,
+      body: Some("some text".into()),
+      nsfw: false,
+      updated: None,
+    };
+    let inserted = PostDraft::create(&conn, &form).unwrap();
+
+    let update_form = PostDraftForm {
+      name: Some("A post about drafts".into()),
+      body: None,
+      updated: Some(naive_now()),
+      ..form
+    };
+    let updated = PostDraft::update(&conn, inserted.id, &update_form).unwrap();
+    let listed = PostDraft::list_for_creator(&conn, person.id, None, None).unwrap();
+    let num_deleted = PostDraft::delete(&conn, inserted.id).unwrap();
+    let listed_after_delete = PostDraft::list_for_creator(&conn, person.id, None, None).unwrap();
+
+    Person::delete(&conn, person.id).unwrap();
+
+    assert_eq!(Some("A post about".to_string()), inserted.name);
+    assert_eq!(Some("A post about drafts".to_string()), updated.name);
+    // Saving without a body clears it
+    assert_eq!(None, updated.body);
+    assert!(updated.updated.is_some());
+    assert_eq!(vec![updated], listed);
+    assert_eq!(1, num_deleted);
+    assert!(listed_after_delete.is_empty());
+  }
+}
@@ -1,6 +1,6 @@
 use crate::{
   naive_now,
-  newtypes::{PersonId, PostReportId},
+  newtypes::{PersonId, PostId, PostReportId},
   source::post_report::*,
   traits::Reportable,
 };
@@ -26,16 +26,19 @@ impl Reportable for PostReport {
   /// * `conn` - the postgres connection
   /// * `report_id` - the id of the report to resolve
   /// * `by_resolver_id` - the id of the user resolving the report
+  /// * `note` - an optional explanation of the resolution
   fn resolve(
     conn: &PgConnection,
     report_id: Self::IdType,
     by_resolver_id: PersonId,
+    note: Option<String>,
   ) -> Result<usize, Error> {
     use crate::schema::post_report::dsl::*;
     update(post_report.find(report_id))
       .set((
         resolved.eq(true),
         resolver_id.eq(by_resolver_id),
+        resolution_note.eq(note),
         updated.eq(naive_now()),
       ))
       .execute(conn)
@@ -56,8 +59,49 @@ impl Reportable for PostReport {
       .set((
         resolved.eq(false),
         resolver_id.eq(by_resolver_id),
+        resolution_note.eq(None::<String>),
         updated.eq(naive_now()),
       ))
       .execute(conn)
   }
+
+  /// escalates a post report to the admins
+  ///
+  /// * `conn` - the postgres connection
+  /// * `report_id` - the id of the report to escalate
+  fn escalate(conn: &PgConnection, report_id: Self::IdType) -> Result<usize, Error> {
+    use crate::schema::post_report::dsl::*;
+    update(post_report.find(report_id))
+      .set((escalated.eq(true), updated.eq(naive_now())))
+      .execute(conn)
+  }
+}
+
+impl PostReport {
+  /// resolves all open reports of a post, for example once it has been removed
+  ///
+  /// * `conn` - the postgres connection
+  /// * `for_post_id` - the id of the reported post
+  /// * `by_resolver_id` - the id of the user resolving the reports
+  /// * `note` - an optional explanation of the resolution
+  pub fn resolve_all_for_post(
+    conn: &PgConnection,
+    for_post_id: PostId,
+    by_resolver_id: PersonId,
+    note: Option<String>,
+  ) -> Result<usize, Error> {
+    use crate::schema::post_report::dsl::*;
+    update(
+      post_report
+        .filter(post_id.eq(for_post_id))
+        .filter(resolved.eq(false)),
+    )
+    .set((
+      resolved.eq(true),
+      resolver_id.eq(by_resolver_id),
+      resolution_note.eq(note),
+      updated.eq(naive_now()),
+    ))
+    .execute(conn)
+  }
 }
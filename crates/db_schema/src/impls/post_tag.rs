@@ -0,0 +1,176 @@
+use crate::{
+  newtypes::{CommunityId, PostId},
+  source::post_tag::*,
+  traits::Crud,
+};
+use diesel::{dsl::*, result::Error, *};
+
+impl Crud for PostTag {
+  type Form = PostTagForm;
+  type IdType = i32;
+  fn create(conn: &PgConnection, form: &Self::Form) -> Result<Self, Error> {
+    use crate::schema::post_tag::dsl::*;
+    insert_into(post_tag).values(form).get_result::<Self>(conn)
+  }
+
+  fn read(conn: &PgConnection, id_: Self::IdType) -> Result<Self, Error> {
+    use crate::schema::post_tag::dsl::*;
+    post_tag.find(id_).first::<Self>(conn)
+  }
+
+  fn update(conn: &PgConnection, id_: Self::IdType, form: &Self::Form) -> Result<Self, Error> {
+    use crate::schema::post_tag::dsl::*;
+    diesel::update(post_tag.find(id_))
+      .set(form)
+      .get_result::<Self>(conn)
+  }
+
+  fn delete(conn: &PgConnection, id_: Self::IdType) -> Result<usize, Error> {
+    use crate::schema::post_tag::dsl::*;
+    diesel::delete(post_tag.find(id_)).execute(conn)
+  }
+}
+
+impl PostTag {
+  /// Used for tags of remote communities, which are only known by name
+  pub fn upsert(conn: &PgConnection, form: &PostTagForm) -> Result<Self, Error> {
+    use crate::schema::post_tag::dsl::*;
+    insert_into(post_tag)
+      .values(form)
+      .on_conflict((community_id, name))
+      .do_update()
+      .set(form)
+      .get_result::<Self>(conn)
+  }
+
+  /// Ordered by name
+  pub fn list_for_community(
+    conn: &PgConnection,
+    for_community_id: CommunityId,
+  ) -> Result<Vec<Self>, Error> {
+    use crate::schema::post_tag::dsl::*;
+    post_tag
+      .filter(community_id.eq(for_community_id))
+      .order_by(name.asc())
+      .load::<Self>(conn)
+  }
+
+  /// Ordered by name
+  pub fn list_for_post(conn: &PgConnection, for_post_id: PostId) -> Result<Vec<Self>, Error> {
+    use crate::schema::{post_tag, post_tag_assignment};
+    post_tag::table
+      .inner_join(post_tag_assignment::table)
+      .filter(post_tag_assignment::post_id.eq(for_post_id))
+      .select(post_tag::all_columns)
+      .order_by(post_tag::name.asc())
+      .load::<Self>(conn)
+  }
+}
+
+impl PostTagAssignment {
+  /// Sets exactly the given tags on the post
+  pub fn replace_for_post(
+    conn: &PgConnection,
+    for_post_id: PostId,
+    tag_ids: &[i32],
+  ) -> Result<Vec<Self>, Error> {
+    use crate::schema::post_tag_assignment::dsl::*;
+    conn.transaction(|| {
+      delete(
+        post_tag_assignment
+          .filter(post_id.eq(for_post_id))
+          .filter(not(tag_id.eq_any(tag_ids))),
+      )
+      .execute(conn)?;
+      let forms: Vec<PostTagAssignmentForm> = tag_ids
+        .iter()
+        .map(|t| PostTagAssignmentForm {
+          post_id: for_post_id,
+          tag_id: *t,
+        })
+        .collect();
+      insert_into(post_tag_assignment)
+        .values(&forms)
+        .on_conflict_do_nothing()
+        .execute(conn)?;
+      post_tag_assignment
+        .filter(post_id.eq(for_post_id))
+        .load::<Self>(conn)
+    })
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::{
+    establish_unpooled_connection,
+    source::{community::*, person::*, post::*, post_tag::*},
+    traits::Crud,
+  };
+  use serial_test::serial;
+
+  #[test]
+  #[serial]
+  fn test_post_tag() {
+    let conn = establish_unpooled_connection();
+
+    let new_person = PersonForm {
+      name: "post_tag_person".into(),
+      ..PersonForm::default()
+    };
+    let inserted_person = Person::create(&conn, &new_person).unwrap();
+
+    let new_community = CommunityForm {
+      name: "post_tag_community".to_string(),
+      title: "nada".to_owned(),
+      ..CommunityForm::default()
+    };
+    let inserted_community = Community::create(&conn, &new_community).unwrap();
+
+    let new_post = PostForm {
+      name: "A test post".into(),
+      creator_id: inserted_person.id,
+      community_id: inserted_community.id,
+      ..PostForm::default()
+    };
+    let inserted_post = Post::create(&conn, &new_post).unwrap();
+
+    let tag_form = |name: &str| PostTagForm {
+      community_id: inserted_community.id,
+      name: name.to_string(),
+      updated: None,
+    };
+    let question = PostTag::create(&conn, &tag_form("Question")).unwrap();
+    let news = PostTag::create(&conn, &tag_form("News")).unwrap();
+    // The same name can't be used twice in a community, but upsert returns the existing tag
+    assert!(PostTag::create(&conn, &tag_form("News")).is_err());
+    assert_eq!(
+      news.id,
+      PostTag::upsert(&conn, &tag_form("News")).unwrap().id
+    );
+    assert_eq!(
+      vec![news.clone(), question.clone()],
+      PostTag::list_for_community(&conn, inserted_community.id).unwrap()
+    );
+
+    let assigned =
+      PostTagAssignment::replace_for_post(&conn, inserted_post.id, &[question.id, news.id])
+        .unwrap();
+    assert_eq!(2, assigned.len());
+    PostTagAssignment::replace_for_post(&conn, inserted_post.id, &[news.id]).unwrap();
+    assert_eq!(
+      vec![news.clone()],
+      PostTag::list_for_post(&conn, inserted_post.id).unwrap()
+    );
+
+    // Deleting a tag also removes it from the posts
+    PostTag::delete(&conn, news.id).unwrap();
+    assert!(PostTag::list_for_post(&conn, inserted_post.id)
+      .unwrap()
+      .is_empty());
+
+    Post::delete(&conn, inserted_post.id).unwrap();
+    Community::delete(&conn, inserted_community.id).unwrap();
+    Person::delete(&conn, inserted_person.id).unwrap();
+  }
+}
@@ -1,4 +1,5 @@
 use crate::{
+  apub_id_variants,
   naive_now,
   newtypes::{DbUrl, PersonId, PrivateMessageId},
   source::private_message::*,
@@ -117,10 +118,10 @@ impl PrivateMessage {
     object_id: Url,
   ) -> Result<Option<Self>, LemmyError> {
     use crate::schema::private_message::dsl::*;
-    let object_id: DbUrl = object_id.into();
+    let object_ids = apub_id_variants(object_id);
     Ok(
       private_message
-        .filter(ap_id.eq(object_id))
+        .filter(ap_id.eq_any(object_ids))
         .first::<PrivateMessage>(conn)
         .ok()
         .map(Into::into),
@@ -183,6 +184,7 @@ mod tests {
       published: inserted_private_message.published,
       ap_id: inserted_private_message.ap_id.to_owned(),
       local: true,
+      thread_id: inserted_private_message.thread_id,
     };
 
     let read_private_message = PrivateMessage::read(&conn, inserted_private_message.id).unwrap();
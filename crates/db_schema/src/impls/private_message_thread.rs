@@ -0,0 +1,90 @@
+use crate::{newtypes::PersonId, source::private_message_thread::*};
+use diesel::{result::Error, *};
+
+impl PrivateMessageThread {
+  pub fn read(conn: &PgConnection, thread_id: i32) -> Result<Self, Error> {
+    use crate::schema::private_message_thread::dsl::*;
+    private_message_thread.find(thread_id).first::<Self>(conn)
+  }
+
+  /// The thread between two persons, in any order
+  pub fn read_for_persons(
+    conn: &PgConnection,
+    first_person_id: PersonId,
+    second_person_id: PersonId,
+  ) -> Result<Self, Error> {
+    use crate::schema::private_message_thread::dsl::*;
+    let (a, b) = if first_person_id.0 <= second_person_id.0 {
+      (first_person_id, second_person_id)
+    } else {
+      (second_person_id, first_person_id)
+    };
+    private_message_thread
+      .filter(person_a_id.eq(a))
+      .filter(person_b_id.eq(b))
+      .first::<Self>(conn)
+  }
+
+  pub fn is_participant(&self, person_id: PersonId) -> bool {
+    self.person_a_id == person_id || self.person_b_id == person_id
+  }
+
+  /// The participant who isn't the given person
+  pub fn other_person_id(&self, person_id: PersonId) -> PersonId {
+    if self.person_a_id == person_id {
+      self.person_b_id
+    } else {
+      self.person_a_id
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::{
+    establish_unpooled_connection,
+    source::{person::*, private_message::*, private_message_thread::*},
+    traits::Crud,
+  };
+  use serial_test::serial;
+
+  #[test]
+  #[serial]
+  fn test_private_message_thread() {
+    let conn = establish_unpooled_connection();
+
+    let person_form = |name: &str| PersonForm {
+      name: name.into(),
+      ..PersonForm::default()
+    };
+    let alice = Person::create(&conn, &person_form("thread_alice")).unwrap();
+    let bob = Person::create(&conn, &person_form("thread_bob")).unwrap();
+    let carol = Person::create(&conn, &person_form("thread_carol")).unwrap();
+
+    let message_form = |creator_id, recipient_id| PrivateMessageForm {
+      content: "A test private message".into(),
+      creator_id,
+      recipient_id,
+      ..PrivateMessageForm::default()
+    };
+    let first = PrivateMessage::create(&conn, &message_form(alice.id, bob.id)).unwrap();
+    let reply = PrivateMessage::create(&conn, &message_form(bob.id, alice.id)).unwrap();
+    let other = PrivateMessage::create(&conn, &message_form(carol.id, alice.id)).unwrap();
+
+    // Both directions end up in the same thread, other persons get their own
+    assert_eq!(first.thread_id, reply.thread_id);
+    assert_ne!(first.thread_id, other.thread_id);
+
+    let thread = PrivateMessageThread::read_for_persons(&conn, bob.id, alice.id).unwrap();
+    assert_eq!(first.thread_id, thread.id);
+    assert!(thread.newest_message_time >= reply.published);
+    assert!(thread.is_participant(alice.id));
+    assert!(!thread.is_participant(carol.id));
+    assert_eq!(bob.id, thread.other_person_id(alice.id));
+    assert_eq!(alice.id, thread.other_person_id(bob.id));
+
+    Person::delete(&conn, alice.id).unwrap();
+    Person::delete(&conn, bob.id).unwrap();
+    Person::delete(&conn, carol.id).unwrap();
+  }
+}
@@ -0,0 +1,112 @@
+use crate::{newtypes::LocalUserId, source::push_subscription::*};
+use diesel::{dsl::*, result::Error, *};
+
+impl PushSubscription {
+  /// Browsers keep the endpoint when the keys are renewed, so an existing subscription is updated.
+  /// It's also moved to the new user if someone else logs in on the same browser.
+  pub fn upsert(conn: &PgConnection, form: &PushSubscriptionForm) -> Result<Self, Error> {
+    use crate::schema::push_subscription::dsl::*;
+    insert_into(push_subscription)
+      .values(form)
+      .on_conflict(endpoint)
+      .do_update()
+      .set(form)
+      .get_result::<Self>(conn)
+  }
+
+  pub fn list_for_local_user(
+    conn: &PgConnection,
+    for_local_user_id: LocalUserId,
+  ) -> Result<Vec<Self>, Error> {
+    use crate::schema::push_subscription::dsl::*;
+    push_subscription
+      .filter(local_user_id.eq(for_local_user_id))
+      .order_by(published)
+      .load::<Self>(conn)
+  }
+
+  pub fn delete_for_local_user(
+    conn: &PgConnection,
+    for_local_user_id: LocalUserId,
+    for_endpoint: &str,
+  ) -> Result<usize, Error> {
+    use crate::schema::push_subscription::dsl::*;
+    delete(
+      push_subscription
+        .filter(local_user_id.eq(for_local_user_id))
+        .filter(endpoint.eq(for_endpoint)),
+    )
+    .execute(conn)
+  }
+
+  /// For subscriptions which the push service reports as expired
+  pub fn delete(conn: &PgConnection, subscription_id: i32) -> Result<usize, Error> {
+    use crate::schema::push_subscription::dsl::*;
+    delete(push_subscription.find(subscription_id)).execute(conn)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::{
+    establish_unpooled_connection,
+    source::{local_user::*, person::*, push_subscription::*},
+    traits::Crud,
+  };
+  use serial_test::serial;
+
+  #[test]
+  #[serial]
+  fn test_push_subscription() {
+    let conn = establish_unpooled_connection();
+
+    let mut local_users = vec![];
+    for name in ["push_subscription_alice", "push_subscription_bob"] {
+      let new_person = PersonForm {
+        name: name.into(),
+        ..PersonForm::default()
+      };
+      let inserted_person = Person::create(&conn, &new_person).unwrap();
+      let new_local_user = LocalUserForm {
+        person_id: Some(inserted_person.id),
+        password_encrypted: Some("push_subscription_pass".to_string()),
+        ..LocalUserForm::default()
+      };
+      local_users.push(LocalUser::create(&conn, &new_local_user).unwrap());
+    }
+    let (alice, bob) = (&local_users[0], &local_users[1]);
+
+    let form = |local_user_id, auth: &str| PushSubscriptionForm {
+      local_user_id,
+      endpoint: "https://push.example.com/send/abc".to_string(),
+      p256dh: "p256dh".to_string(),
+      auth: auth.to_string(),
+    };
+    let inserted = PushSubscription::upsert(&conn, &form(alice.id, "auth1")).unwrap();
+    assert_eq!(
+      vec![inserted.clone()],
+      PushSubscription::list_for_local_user(&conn, alice.id).unwrap()
+    );
+
+    // Registering the same endpoint again replaces the keys and owner
+    let updated = PushSubscription::upsert(&conn, &form(bob.id, "auth2")).unwrap();
+    assert_eq!(inserted.id, updated.id);
+    assert_eq!("auth2", updated.auth);
+    assert!(PushSubscription::list_for_local_user(&conn, alice.id)
+      .unwrap()
+      .is_empty());
+
+    assert_eq!(
+      0,
+      PushSubscription::delete_for_local_user(&conn, alice.id, &updated.endpoint).unwrap()
+    );
+    assert_eq!(
+      1,
+      PushSubscription::delete_for_local_user(&conn, bob.id, &updated.endpoint).unwrap()
+    );
+
+    for local_user in &local_users {
+      Person::delete(&conn, local_user.person_id).unwrap();
+    }
+  }
+}
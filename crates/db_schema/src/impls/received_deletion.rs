@@ -0,0 +1,165 @@
+use crate::{limit_and_offset, newtypes::DbUrl, source::received_deletion::*};
+use diesel::{
+  insert_into,
+  result::Error,
+  sql_query,
+  update,
+  ExpressionMethods,
+  OptionalExtension,
+  PgConnection,
+  QueryDsl,
+  RunQueryDsl,
+};
+
+impl ReceivedDeletion {
+  pub fn create(conn: &PgConnection, form: &ReceivedDeletionForm) -> Result<Self, Error> {
+    use crate::schema::received_deletion::dsl::*;
+    insert_into(received_deletion)
+      .values(form)
+      .get_result::<Self>(conn)
+  }
+
+  /// All deletions received from the given actor, newest first
+  pub fn list_for_actor(
+    conn: &PgConnection,
+    for_actor_id: &DbUrl,
+    page: Option<i64>,
+    limit: Option<i64>,
+  ) -> Result<Vec<Self>, Error> {
+    use crate::schema::received_deletion::dsl::*;
+    let (limit, offset) = limit_and_offset(page, limit);
+    received_deletion
+      .filter(actor_id.eq(for_actor_id))
+      .order_by(published.desc())
+      .then_order_by(id.desc())
+      .limit(limit)
+      .offset(offset)
+      .load::<Self>(conn)
+  }
+
+  /// Marks a deletion which was held back, or releases it once it was applied after all
+  pub fn update_held(
+    conn: &PgConnection,
+    for_activity_id: &DbUrl,
+    new_held: bool,
+  ) -> Result<usize, Error> {
+    use crate::schema::received_deletion::dsl::*;
+    update(received_deletion.filter(activity_id.eq(for_activity_id)))
+      .set(held.eq(new_held))
+      .execute(conn)
+  }
+
+  /// The newest deletion of the object with the given kind which passed verification, regardless
+  /// of who sent it
+  pub fn read_latest_accepted(
+    conn: &PgConnection,
+    for_object_id: &DbUrl,
+    for_kind: &str,
+  ) -> Result<Option<Self>, Error> {
+    use crate::schema::received_deletion::dsl::*;
+    received_deletion
+      .filter(object_id.eq(for_object_id))
+      .filter(kind.eq(for_kind))
+      .filter(accepted.eq(true))
+      .order_by(published.desc())
+      .then_order_by(id.desc())
+      .first::<Self>(conn)
+      .optional()
+  }
+
+  /// When received deletions started to be logged. Objects which were deleted before that (and
+  /// whose deletion isn't in the activity table anymore) have no deletion in the log.
+  pub fn log_started(conn: &PgConnection) -> Result<Option<chrono::NaiveDateTime>, Error> {
+    #[derive(QueryableByName)]
+    struct Migration {
+      #[sql_type = "diesel::sql_types::Timestamp"]
+      run_on: chrono::NaiveDateTime,
+    }
+    let migrations =
+      sql_query("select run_on from __diesel_schema_migrations where version = '20211221154420'")
+        .load::<Migration>(conn)?;
+    Ok(migrations.into_iter().next().map(|m| m.run_on))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::{
+    establish_unpooled_connection,
+    newtypes::DbUrl,
+    schema::received_deletion,
+    source::received_deletion::*,
+  };
+  use diesel::{ExpressionMethods, QueryDsl, RunQueryDsl};
+  use serial_test::serial;
+  use url::Url;
+
+  #[test]
+  #[serial]
+  fn test_list_for_actor() {
+    let conn = establish_unpooled_connection();
+
+    let actor_id: DbUrl = Url::parse("https://example.com/u/deleter").unwrap().into();
+    let other_actor_id: DbUrl = Url::parse("https://example.com/u/other").unwrap().into();
+    let form = |actor_id: &DbUrl, kind: &str, accepted: bool| ReceivedDeletionForm {
+      actor_id: actor_id.clone(),
+      activity_id: Url::parse(&format!("https://example.com/activities/{}", kind))
+        .unwrap()
+        .into(),
+      kind: kind.to_string(),
+      object_id: Url::parse("https://lemmy.tld/post/1").unwrap().into(),
+      accepted,
+    };
+    let delete = ReceivedDeletion::create(&conn, &form(&actor_id, "Delete", true)).unwrap();
+    let undo = ReceivedDeletion::create(&conn, &form(&actor_id, "UndoDelete", false)).unwrap();
+    let other = ReceivedDeletion::create(&conn, &form(&other_actor_id, "Remove", true)).unwrap();
+
+    let listed = ReceivedDeletion::list_for_actor(&conn, &actor_id, None, None).unwrap();
+
+    let ids = vec![delete.id, undo.id, other.id];
+    diesel::delete(received_deletion::table.filter(received_deletion::id.eq_any(ids)))
+      .execute(&conn)
+      .unwrap();
+
+    assert_eq!(vec![undo, delete], listed);
+  }
+
+  #[test]
+  #[serial]
+  fn test_read_latest_accepted() {
+    let conn = establish_unpooled_connection();
+
+    let object_id: DbUrl = Url::parse("https://lemmy.tld/post/latest").unwrap().into();
+    let form = |activity: &str, kind: &str, accepted: bool| ReceivedDeletionForm {
+      actor_id: Url::parse("https://example.com/u/deleter").unwrap().into(),
+      activity_id: Url::parse(&format!("https://example.com/activities/{}", activity))
+        .unwrap()
+        .into(),
+      kind: kind.to_string(),
+      object_id: object_id.clone(),
+      accepted,
+    };
+    let first = ReceivedDeletion::create(&conn, &form("first", "Delete", true)).unwrap();
+    let second = ReceivedDeletion::create(&conn, &form("second", "Delete", true)).unwrap();
+    let rejected = ReceivedDeletion::create(&conn, &form("rejected", "Delete", false)).unwrap();
+    let remove = ReceivedDeletion::create(&conn, &form("remove", "Remove", true)).unwrap();
+    ReceivedDeletion::update_held(&conn, &second.activity_id, true).unwrap();
+
+    let latest = ReceivedDeletion::read_latest_accepted(&conn, &object_id, "Delete").unwrap();
+    let other_object: DbUrl = Url::parse("https://lemmy.tld/post/other").unwrap().into();
+    let none = ReceivedDeletion::read_latest_accepted(&conn, &other_object, "Delete").unwrap();
+    let log_started = ReceivedDeletion::log_started(&conn).unwrap();
+
+    let ids = vec![first.id, second.id, rejected.id, remove.id];
+    diesel::delete(received_deletion::table.filter(received_deletion::id.eq_any(ids)))
+      .execute(&conn)
+      .unwrap();
+
+    let latest = latest.unwrap();
+    assert_eq!(second.id, latest.id);
+    assert!(latest.held);
+    assert!(!first.held);
+    assert!(none.is_none());
+    assert!(log_started.unwrap() < first.published);
+  }
+}
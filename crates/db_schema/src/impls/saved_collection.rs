@@ -0,0 +1,132 @@
+use crate::{
+  newtypes::{PersonId, SavedCollectionId},
+  source::saved_collection::*,
+  traits::Crud,
+};
+use diesel::{insert_into, result::Error, ExpressionMethods, PgConnection, QueryDsl, RunQueryDsl};
+
+impl Crud for SavedCollection {
+  type Form = SavedCollectionForm;
+  type IdType = SavedCollectionId;
+  fn create(conn: &PgConnection, form: &Self::Form) -> Result<Self, Error> {
+    use crate::schema::saved_collection::dsl::*;
+    insert_into(saved_collection)
+      .values(form)
+      .get_result::<Self>(conn)
+  }
+
+  fn read(conn: &PgConnection, id_: Self::IdType) -> Result<Self, Error> {
+    use crate::schema::saved_collection::dsl::*;
+    saved_collection.find(id_).first::<Self>(conn)
+  }
+
+  fn update(conn: &PgConnection, id_: Self::IdType, form: &Self::Form) -> Result<Self, Error> {
+    use crate::schema::saved_collection::dsl::*;
+    diesel::update(saved_collection.find(id_))
+      .set(form)
+      .get_result::<Self>(conn)
+  }
+
+  /// Items in the collection are kept, they move back into the unfiled list
+  fn delete(conn: &PgConnection, id_: Self::IdType) -> Result<usize, Error> {
+    use crate::schema::saved_collection::dsl::*;
+    diesel::delete(saved_collection.find(id_)).execute(conn)
+  }
+}
+
+impl SavedCollection {
+  pub fn list_for_person(conn: &PgConnection, for_person_id: PersonId) -> Result<Vec<Self>, Error> {
+    use crate::schema::saved_collection::dsl::*;
+    saved_collection
+      .filter(person_id.eq(for_person_id))
+      .order_by(name)
+      .load::<Self>(conn)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::{
+    establish_unpooled_connection,
+    naive_now,
+    source::{community::*, person::*, post::*, saved_collection::*},
+    traits::{Crud, Saveable},
+  };
+  use serial_test::serial;
+
+  #[test]
+  #[serial]
+  fn test_crud() {
+    let conn = establish_unpooled_connection();
+
+    let person_form = PersonForm {
+      name: "saved_collection_owner".into(),
+      ..PersonForm::default()
+    };
+    let person = Person::create(&conn, &person_form).unwrap();
+
+    let community_form = CommunityForm {
+      name: "saved_collection_community".to_string(),
+      title: "nada".to_owned(),
+      ..CommunityForm::default()
+    };
+    let community = Community::create(&conn, &community_form).unwrap();
+
+    let post_form = PostForm {
+      name: "A post to keep".into(),
+      creator_id: person.id,
+      community_id: community.id,
+      ..PostForm::default()
+    };
+    let post = Post::create(&conn, &post_form).unwrap();
+
+    let form = SavedCollectionForm {
+      person_id: person.id,
+      name: "Recipes".into(),
+      updated: None,
+    };
+    let inserted = SavedCollection::create(&conn, &form).unwrap();
+
+    let update_form = SavedCollectionForm {
+      name: "Cooking".into(),
+      updated: Some(naive_now()),
+      ..form
+    };
+    let updated = SavedCollection::update(&conn, inserted.id, &update_form).unwrap();
+    let listed = SavedCollection::list_for_person(&conn, person.id).unwrap();
+
+    let saved_form = PostSavedForm {
+      post_id: post.id,
+      person_id: person.id,
+      collection_id: Some(inserted.id),
+    };
+    let saved = PostSaved::save(&conn, &saved_form).unwrap();
+    let moved = PostSaved::move_to_collection(&conn, person.id, post.id, None).unwrap();
+    let moved_back =
+      PostSaved::move_to_collection(&conn, person.id, post.id, Some(inserted.id)).unwrap();
+
+    let num_deleted = SavedCollection::delete(&conn, inserted.id).unwrap();
+    // The post stays saved, without a collection
+    let resaved = PostSaved::save(
+      &conn,
+      &PostSavedForm {
+        collection_id: None,
+        ..saved_form
+      },
+    )
+    .unwrap();
+
+    Community::delete(&conn, community.id).unwrap();
+    Person::delete(&conn, person.id).unwrap();
+
+    assert_eq!("Cooking", updated.name);
+    assert!(updated.updated.is_some());
+    assert_eq!(vec![updated], listed);
+    assert_eq!(Some(inserted.id), saved.collection_id);
+    assert_eq!(None, moved.collection_id);
+    assert_eq!(Some(inserted.id), moved_back.collection_id);
+    assert_eq!(1, num_deleted);
+    assert_eq!(saved.id, resaved.id);
+    assert_eq!(None, resaved.collection_id);
+  }
+}
@@ -0,0 +1,144 @@
+use crate::{limit_and_offset, newtypes::PersonId, source::scheduled_post::*, traits::Crud};
+use chrono::NaiveDateTime;
+use diesel::{
+  insert_into,
+  result::Error,
+  ExpressionMethods,
+  OptionalExtension,
+  PgConnection,
+  QueryDsl,
+  RunQueryDsl,
+};
+
+impl Crud for ScheduledPost {
+  type Form = ScheduledPostForm;
+  type IdType = i32;
+  fn create(conn: &PgConnection, form: &Self::Form) -> Result<Self, Error> {
+    use crate::schema::scheduled_post::dsl::*;
+    insert_into(scheduled_post)
+      .values(form)
+      .get_result::<Self>(conn)
+  }
+
+  fn read(conn: &PgConnection, id_: Self::IdType) -> Result<Self, Error> {
+    use crate::schema::scheduled_post::dsl::*;
+    scheduled_post.find(id_).first::<Self>(conn)
+  }
+
+  fn update(conn: &PgConnection, id_: Self::IdType, form: &Self::Form) -> Result<Self, Error> {
+    use crate::schema::scheduled_post::dsl::*;
+    diesel::update(scheduled_post.find(id_))
+      .set(form)
+      .get_result::<Self>(conn)
+  }
+
+  fn delete(conn: &PgConnection, id_: Self::IdType) -> Result<usize, Error> {
+    use crate::schema::scheduled_post::dsl::*;
+    diesel::delete(scheduled_post.find(id_)).execute(conn)
+  }
+}
+
+impl ScheduledPost {
+  /// All scheduled posts which should be published at or before the given time, oldest first
+  pub fn list_due(conn: &PgConnection, before: NaiveDateTime) -> Result<Vec<Self>, Error> {
+    use crate::schema::scheduled_post::dsl::*;
+    scheduled_post
+      .filter(publish_at.le(before))
+      .order_by(publish_at.asc())
+      .then_order_by(id.asc())
+      .load::<Self>(conn)
+  }
+
+  /// Deletes the scheduled post and returns it, so that it can be published. Only one caller can
+  /// claim it, any others get `None`.
+  pub fn claim(conn: &PgConnection, scheduled_post_id: i32) -> Result<Option<Self>, Error> {
+    use crate::schema::scheduled_post::dsl::*;
+    diesel::delete(scheduled_post.find(scheduled_post_id))
+      .get_result::<Self>(conn)
+      .optional()
+  }
+
+  /// The posts which the person scheduled, in the order in which they are published
+  pub fn list_for_creator(
+    conn: &PgConnection,
+    for_creator_id: PersonId,
+    page: Option<i64>,
+    limit: Option<i64>,
+  ) -> Result<Vec<Self>, Error> {
+    use crate::schema::scheduled_post::dsl::*;
+    let (limit, offset) = limit_and_offset(page, limit);
+    scheduled_post
+      .filter(creator_id.eq(for_creator_id))
+      .order_by(publish_at.asc())
+      .then_order_by(id.asc())
+      .limit(limit)
+      .offset(offset)
+      .load::<Self>(conn)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::{
+    establish_unpooled_connection,
+    naive_now,
+    source::{community::*, person::*, scheduled_post::*},
+    traits::Crud,
+  };
+  use chrono::Duration;
+  use serial_test::serial;
+
+  #[test]
+  #[serial]
+  fn test_list_due() {
+    let conn = establish_unpooled_connection();
+
+    let person_form = PersonForm {
+      name: "scheduled_post_creator".into(),
+      ..PersonForm::default()
+    };
+    let person = Person::create(&conn, &person_form).unwrap();
+
+    let community_form = CommunityForm {
+      name: "scheduled_post_community".to_string(),
+      title: "nada".to_owned(),
+      ..CommunityForm::default()
+    };
+    let community = Community::create(&conn, &community_form).unwrap();
+
+    let now = naive_now();
+    let schedule = |name: &str, publish_at| {
+      let form = ScheduledPostForm {
+        creator_id: person.id,
+        community_id: community.id,
+        name: name.into(),
+        url: None,
+        body: None,
+        nsfw: None,
+        publish_at,
+        language_id: None,
+      };
+      ScheduledPost::create(&conn, &form).unwrap()
+    };
+    let later = schedule("later", now + Duration::hours(2));
+    let soon = schedule("soon", now + Duration::hours(1));
+
+    let due_now = ScheduledPost::list_due(&conn, now).unwrap();
+    let due_in_three_hours = ScheduledPost::list_due(&conn, now + Duration::hours(3)).unwrap();
+    let read_soon = ScheduledPost::read(&conn, soon.id).unwrap();
+    let listed = ScheduledPost::list_for_creator(&conn, person.id, None, None).unwrap();
+    let claimed = ScheduledPost::claim(&conn, soon.id).unwrap();
+    let claimed_again = ScheduledPost::claim(&conn, soon.id).unwrap();
+
+    Community::delete(&conn, community.id).unwrap();
+    Person::delete(&conn, person.id).unwrap();
+
+    assert!(due_now.is_empty());
+    assert_eq!(vec![soon.clone(), later.clone()], due_in_three_hours);
+    assert_eq!(soon, read_soon);
+    assert!(!read_soon.nsfw);
+    assert_eq!(vec![soon.clone(), later], listed);
+    assert_eq!(Some(soon), claimed);
+    assert_eq!(None, claimed_again);
+  }
+}
@@ -7,6 +7,20 @@ impl Secret {
   pub fn init(conn: &PgConnection) -> Result<Secret, Error> {
     read_secrets(conn)
   }
+
+  pub fn update_instance_keys(
+    conn: &PgConnection,
+    private_key: &str,
+    public_key: &str,
+  ) -> Result<Secret, Error> {
+    use crate::schema::secret::dsl::*;
+    diesel::update(secret)
+      .set((
+        instance_private_key.eq(private_key),
+        instance_public_key.eq(public_key),
+      ))
+      .get_result::<Secret>(conn)
+  }
 }
 
 fn read_secrets(conn: &PgConnection) -> Result<Secret, Error> {
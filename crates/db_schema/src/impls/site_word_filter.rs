@@ -0,0 +1,85 @@
+use crate::{source::site_word_filter::*, WordFilterAction};
+use diesel::{insert_into, result::Error, ExpressionMethods, PgConnection, QueryDsl, RunQueryDsl};
+use lemmy_utils::utils::WordFilter;
+
+impl SiteWordFilter {
+  /// Changes the action if the pattern already exists
+  pub fn upsert(conn: &PgConnection, form: &SiteWordFilterForm) -> Result<Self, Error> {
+    use crate::schema::site_word_filter::dsl::*;
+    insert_into(site_word_filter)
+      .values(form)
+      .on_conflict(pattern)
+      .do_update()
+      .set(form)
+      .get_result::<Self>(conn)
+  }
+
+  pub fn delete(conn: &PgConnection, word_filter_id: i32) -> Result<usize, Error> {
+    use crate::schema::site_word_filter::dsl::*;
+    diesel::delete(site_word_filter.find(word_filter_id)).execute(conn)
+  }
+
+  pub fn list(conn: &PgConnection) -> Result<Vec<Self>, Error> {
+    use crate::schema::site_word_filter::dsl::*;
+    site_word_filter.order_by(pattern.asc()).load::<Self>(conn)
+  }
+
+  /// Loads the table into the word filter of lemmy_utils. It has to be called after each change to
+  /// the table.
+  pub fn refresh_cache(conn: &PgConnection) -> Result<(), Error> {
+    let (block, replace): (Vec<_>, Vec<_>) = SiteWordFilter::list(conn)?
+      .into_iter()
+      .partition(|f| f.action == WordFilterAction::Block.to_string());
+    let block: Vec<String> = block.into_iter().map(|f| f.pattern).collect();
+    let replace: Vec<String> = replace.into_iter().map(|f| f.pattern).collect();
+    WordFilter::new(&block, &replace).set();
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::{establish_unpooled_connection, source::site_word_filter::*, WordFilterAction};
+  use lemmy_utils::utils::{check_blocked_words, remove_slurs};
+  use serial_test::serial;
+
+  #[test]
+  #[serial]
+  fn test_site_word_filter() {
+    let conn = establish_unpooled_connection();
+
+    let block_form = SiteWordFilterForm {
+      pattern: r"\bwordfilterblock\b".to_string(),
+      action: WordFilterAction::Replace.to_string(),
+    };
+    SiteWordFilter::upsert(&conn, &block_form).unwrap();
+    // Changes the action of the existing pattern
+    let block_form = SiteWordFilterForm {
+      action: WordFilterAction::Block.to_string(),
+      ..block_form
+    };
+    let blocked = SiteWordFilter::upsert(&conn, &block_form).unwrap();
+    let replace_form = SiteWordFilterForm {
+      pattern: r"wordfilterreplace\w*".to_string(),
+      action: WordFilterAction::Replace.to_string(),
+    };
+    let replaced = SiteWordFilter::upsert(&conn, &replace_form).unwrap();
+    SiteWordFilter::refresh_cache(&conn).unwrap();
+    let listed = SiteWordFilter::list(&conn).unwrap();
+
+    let blocked_err = check_blocked_words("some WordFilterBlock text").is_err();
+    let replaced_text = remove_slurs("a wordfilterreplaced word", &None);
+    let not_blocked = check_blocked_words("some wordfilterreplace text").is_ok();
+
+    SiteWordFilter::delete(&conn, blocked.id).unwrap();
+    SiteWordFilter::delete(&conn, replaced.id).unwrap();
+    SiteWordFilter::refresh_cache(&conn).unwrap();
+
+    assert_eq!(2, listed.len());
+    assert_eq!("Block", blocked.action);
+    assert!(blocked_err);
+    assert_eq!("a *removed* word", replaced_text);
+    assert!(not_blocked);
+    assert!(check_blocked_words("some wordfilterblock text").is_ok());
+  }
+}
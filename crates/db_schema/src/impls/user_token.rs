@@ -0,0 +1,171 @@
+use crate::{hash_token, newtypes::LocalUserId, source::user_token::*, TokenScope};
+use diesel::{dsl::*, result::Error, *};
+use std::str::FromStr;
+
+impl UserToken {
+  /// Stores the hash of a new token
+  pub fn create(
+    conn: &PgConnection,
+    for_local_user_id: LocalUserId,
+    token_name: &str,
+    token: &str,
+    token_scopes: &[TokenScope],
+  ) -> Result<Self, Error> {
+    use crate::schema::user_token::dsl::*;
+    let form = UserTokenForm {
+      local_user_id: for_local_user_id,
+      name: token_name.to_string(),
+      token_hash: hash_token(token),
+      scopes: token_scopes.iter().map(ToString::to_string).collect(),
+    };
+    insert_into(user_token)
+      .values(form)
+      .get_result::<Self>(conn)
+  }
+
+  /// Finds the token, and records that it was used
+  pub fn read_from_token(conn: &PgConnection, token: &str) -> Result<Self, Error> {
+    use crate::schema::user_token::dsl::*;
+    update(user_token.filter(token_hash.eq(hash_token(token))))
+      .set(last_used.eq(now))
+      .get_result::<Self>(conn)
+  }
+
+  pub fn list_for_local_user(
+    conn: &PgConnection,
+    for_local_user_id: LocalUserId,
+  ) -> Result<Vec<Self>, Error> {
+    use crate::schema::user_token::dsl::*;
+    user_token
+      .filter(local_user_id.eq(for_local_user_id))
+      .order_by(published.desc())
+      .load::<Self>(conn)
+  }
+
+  /// Revokes a token, if it belongs to the given user
+  pub fn delete_for_local_user(
+    conn: &PgConnection,
+    token_id: i32,
+    for_local_user_id: LocalUserId,
+  ) -> Result<usize, Error> {
+    use crate::schema::user_token::dsl::*;
+    delete(
+      user_token
+        .find(token_id)
+        .filter(local_user_id.eq(for_local_user_id)),
+    )
+    .execute(conn)
+  }
+
+  pub fn delete_all_for_local_user(
+    conn: &PgConnection,
+    for_local_user_id: LocalUserId,
+  ) -> Result<usize, Error> {
+    use crate::schema::user_token::dsl::*;
+    delete(user_token.filter(local_user_id.eq(for_local_user_id))).execute(conn)
+  }
+
+  pub fn token_scopes(&self) -> Vec<TokenScope> {
+    self
+      .scopes
+      .iter()
+      .filter_map(|s| TokenScope::from_str(s).ok())
+      .collect()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::{
+    establish_unpooled_connection,
+    source::{local_user::*, person::*, user_token::*},
+    traits::Crud,
+    TokenScope,
+  };
+  use serial_test::serial;
+
+  #[test]
+  #[serial]
+  fn test_user_token() {
+    let conn = establish_unpooled_connection();
+
+    let new_person = PersonForm {
+      name: "user_token_person".into(),
+      ..PersonForm::default()
+    };
+    let inserted_person = Person::create(&conn, &new_person).unwrap();
+    let new_local_user = LocalUserForm {
+      person_id: Some(inserted_person.id),
+      password_encrypted: Some("user_token_pass".to_string()),
+      ..LocalUserForm::default()
+    };
+    let inserted_local_user = LocalUser::create(&conn, &new_local_user).unwrap();
+
+    let inserted_token = UserToken::create(
+      &conn,
+      inserted_local_user.id,
+      "bot",
+      "lemmy_pat_secret",
+      &[TokenScope::Read, TokenScope::Moderate],
+    )
+    .unwrap();
+    assert_ne!("lemmy_pat_secret", inserted_token.token_hash);
+    assert_eq!(vec!["Read", "Moderate"], inserted_token.scopes);
+    assert_eq!(
+      vec![TokenScope::Read, TokenScope::Moderate],
+      inserted_token.token_scopes()
+    );
+    assert!(inserted_token.last_used.is_none());
+
+    let read_token = UserToken::read_from_token(&conn, "lemmy_pat_secret").unwrap();
+    assert_eq!(inserted_token.id, read_token.id);
+    assert!(read_token.last_used.is_some());
+    assert!(UserToken::read_from_token(&conn, "lemmy_pat_wrong").is_err());
+    assert_eq!(
+      1,
+      UserToken::list_for_local_user(&conn, inserted_local_user.id)
+        .unwrap()
+        .len()
+    );
+
+    let other_person = PersonForm {
+      name: "user_token_other".into(),
+      ..PersonForm::default()
+    };
+    let other_person = Person::create(&conn, &other_person).unwrap();
+    let other_user = LocalUserForm {
+      person_id: Some(other_person.id),
+      password_encrypted: Some("user_token_pass".to_string()),
+      ..LocalUserForm::default()
+    };
+    let other_local_user = LocalUser::create(&conn, &other_user).unwrap();
+    assert_eq!(
+      0,
+      UserToken::delete_for_local_user(&conn, inserted_token.id, other_local_user.id).unwrap()
+    );
+    assert_eq!(
+      1,
+      UserToken::delete_for_local_user(&conn, inserted_token.id, inserted_local_user.id).unwrap()
+    );
+    assert!(UserToken::read_from_token(&conn, "lemmy_pat_secret").is_err());
+
+    for name in ["first", "second"] {
+      UserToken::create(
+        &conn,
+        inserted_local_user.id,
+        name,
+        &format!("lemmy_pat_{}", name),
+        &[TokenScope::Read],
+      )
+      .unwrap();
+    }
+    assert_eq!(
+      2,
+      UserToken::delete_all_for_local_user(&conn, inserted_local_user.id).unwrap()
+    );
+    assert!(UserToken::read_from_token(&conn, "lemmy_pat_first").is_err());
+
+    Person::delete(&conn, inserted_person.id).unwrap();
+    Person::delete(&conn, other_person.id).unwrap();
+  }
+}
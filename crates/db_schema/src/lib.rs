@@ -10,6 +10,7 @@ extern crate diesel_migrations;
 extern crate strum_macros;
 
 pub mod aggregates;
+pub mod clock;
 pub mod impls;
 pub mod newtypes;
 pub mod schema;
@@ -25,6 +26,7 @@ use lemmy_utils::LemmyError;
 use once_cell::sync::Lazy;
 use regex::Regex;
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 use std::{env, env::VarError};
 use url::Url;
 
@@ -44,6 +46,9 @@ pub enum SortType {
   TopAll,
   MostComments,
   NewComments,
+  Controversial,
+  /// Communities which are growing quickly. Other listings treat it like `Hot`.
+  Trending,
 }
 
 #[derive(EnumString, Display, Debug, Serialize, Deserialize, Clone, Copy)]
@@ -54,6 +59,39 @@ pub enum ListingType {
   Community,
 }
 
+/// How often a local user receives a summary email, stored as text in the local_user table
+#[derive(EnumString, Display, Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+pub enum DigestFrequency {
+  Never,
+  Daily,
+  Weekly,
+}
+
+/// What happens to content matching a pattern of the word filter, stored as text in the
+/// site_word_filter table
+#[derive(EnumString, Display, Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+pub enum WordFilterAction {
+  Block,
+  Replace,
+}
+
+/// What happens to content matching an automod rule, stored as text in the automod_rule table
+#[derive(EnumString, Display, Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+pub enum AutomodAction {
+  Remove,
+  Report,
+  RequireApproval,
+}
+
+/// What a personal access token allows, stored as a text array in the user_token table
+#[derive(EnumString, Display, Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+pub enum TokenScope {
+  Read,
+  Write,
+  /// Moderator and admin actions, which additionally need the read or write scope
+  Moderate,
+}
+
 #[derive(EnumString, Display, Debug, Serialize, Deserialize, Clone, Copy)]
 pub enum SearchType {
   All,
@@ -73,6 +111,17 @@ pub fn fuzzy_search(q: &str) -> String {
   format!("%{}%", replaced)
 }
 
+/// Hex encoded SHA-256 hash, so that secret tokens aren't stored in plain text
+pub(crate) fn hash_token(token: &str) -> String {
+  let mut hasher = Sha256::new();
+  hasher.update(token);
+  hasher
+    .finalize()
+    .iter()
+    .map(|byte| format!("{:02x}", byte))
+    .collect()
+}
+
 pub fn limit_and_offset(page: Option<i64>, limit: Option<i64>) -> (i64, i64) {
   let page = page.unwrap_or(1);
   let limit = limit.unwrap_or(10);
@@ -112,6 +161,23 @@ pub fn diesel_option_overwrite_to_url(
   }
 }
 
+/// Returns the forms under which an apub id may be stored in the database. Other instances don't
+/// always agree with the form we stored, so eg `https://example.com/c/main/` and
+/// `https://example.com/c/main` need to resolve to the same object. Differences in the casing of
+/// the host are already normalized by the url parser.
+pub fn apub_id_variants(object_id: Url) -> Vec<DbUrl> {
+  let path = object_id.path().to_owned();
+  if path == "/" || path.is_empty() {
+    return vec![object_id.into()];
+  }
+  let mut alternative = object_id.clone();
+  match path.strip_suffix('/') {
+    Some(stripped) => alternative.set_path(stripped),
+    None => alternative.set_path(&format!("{}/", path)),
+  }
+  vec![object_id.into(), alternative.into()]
+}
+
 embed_migrations!();
 
 pub fn establish_unpooled_connection() -> PgConnection {
@@ -143,6 +209,54 @@ pub mod functions {
   sql_function! {
     fn hot_rank(score: BigInt, time: Timestamp) -> Integer;
   }
+
+  sql_function! {
+    fn controversy_rank(upvotes: BigInt, downvotes: BigInt) -> Double;
+  }
+
+  sql_function! {
+    fn comment_path_depth(path: Text) -> Integer;
+  }
+
+  #[derive(SqlType)]
+  #[postgres(type_name = "tsvector")]
+  pub struct TsVector;
+
+  #[derive(SqlType)]
+  #[postgres(type_name = "tsquery")]
+  pub struct TsQuery;
+
+  sql_function! {
+    /// Parses a search term for all text search configs. This can use the search indexes, but
+    /// the matches need to be checked again with `search_tsquery_for_language`.
+    fn search_tsquery(term: Text) -> TsQuery;
+  }
+
+  sql_function! {
+    fn search_tsquery_for_language(term: Text, language_id: Integer) -> TsQuery;
+  }
+
+  sql_function! {
+    fn ts_rank_cd(vector: TsVector, query: TsQuery) -> Float;
+  }
+
+  sql_function! {
+    fn coalesce(x: Nullable<Text>, y: Text) -> Text;
+  }
+
+  sql_function! {
+    /// Excerpts of the content around the matches of the search term, which are highlighted with
+    /// markdown bold
+    fn search_headline(content: Text, term: Text, language_id: Integer) -> Text;
+  }
+
+  diesel_infix_operator!(Matches, " @@ ", backend: diesel::pg::Pg);
+
+  /// The search_vector columns are maintained by triggers, and left out of the schema so that
+  /// they aren't loaded with every post and comment
+  pub fn search_vector(table: &str) -> diesel::expression::SqlLiteral<TsVector> {
+    diesel::dsl::sql(&format!("{}.search_vector", table))
+  }
 }
 
 #[cfg(test)]
@@ -175,6 +289,24 @@ mod tests {
     );
   }
 
+  #[test]
+  fn test_apub_id_variants() {
+    let expected: Vec<DbUrl> = vec![
+      Url::parse("https://example.com/c/main").unwrap().into(),
+      Url::parse("https://example.com/c/main/").unwrap().into(),
+    ];
+    let variants = apub_id_variants(Url::parse("https://example.com/c/main").unwrap());
+    assert_eq!(expected, variants);
+
+    let variants = apub_id_variants(Url::parse("https://Example.COM/c/main/").unwrap());
+    assert!(variants.contains(&expected[0]));
+    assert!(variants.contains(&expected[1]));
+
+    let root = Url::parse("https://example.com/").unwrap();
+    let expected: Vec<DbUrl> = vec![root.clone().into()];
+    assert_eq!(expected, apub_id_variants(root));
+  }
+
   #[test]
   fn test_diesel_option_overwrite_to_url() {
     assert!(matches!(diesel_option_overwrite_to_url(&None), Ok(None)));
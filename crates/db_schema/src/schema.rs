@@ -1,3 +1,14 @@
+table! {
+    account_export (id) {
+        id -> Int4,
+        local_user_id -> Int4,
+        token -> Text,
+        data -> Nullable<Text>,
+        published -> Timestamp,
+        finished -> Nullable<Timestamp>,
+    }
+}
+
 table! {
     activity (id) {
         id -> Int4,
@@ -7,6 +18,62 @@ table! {
         updated -> Nullable<Timestamp>,
         ap_id -> Text,
         sensitive -> Nullable<Bool>,
+        forwarded -> Bool,
+        held -> Bool,
+    }
+}
+
+table! {
+    activity_send_queue (id) {
+        id -> Int4,
+        activity_id -> Text,
+        inbox -> Text,
+        actor_id -> Text,
+        activity -> Text,
+        retries -> Int4,
+        next_attempt -> Timestamp,
+        published -> Timestamp,
+        request_id -> Nullable<Text>,
+    }
+}
+
+table! {
+    appeal (id) {
+        id -> Int4,
+        creator_id -> Int4,
+        community_id -> Int4,
+        post_id -> Nullable<Int4>,
+        comment_id -> Nullable<Int4>,
+        reason -> Text,
+        status -> Text,
+        resolver_id -> Nullable<Int4>,
+        published -> Timestamp,
+        updated -> Nullable<Timestamp>,
+    }
+}
+
+table! {
+    automod_rule (id) {
+        id -> Int4,
+        community_id -> Int4,
+        title_regex -> Nullable<Text>,
+        body_regex -> Nullable<Text>,
+        link_domain -> Nullable<Text>,
+        account_age_below_days -> Nullable<Int4>,
+        karma_below -> Nullable<Int4>,
+        action -> Text,
+        reason -> Nullable<Text>,
+        published -> Timestamp,
+        updated -> Nullable<Timestamp>,
+    }
+}
+
+table! {
+    captcha_answer (id) {
+        id -> Int4,
+        uuid -> Text,
+        answer -> Text,
+        published -> Timestamp,
     }
 }
 
@@ -24,6 +91,8 @@ table! {
         deleted -> Bool,
         ap_id -> Varchar,
         local -> Bool,
+        path -> Text,
+        language_id -> Int4,
     }
 }
 
@@ -35,6 +104,8 @@ table! {
         upvotes -> Int8,
         downvotes -> Int8,
         published -> Timestamp,
+        child_count -> Int4,
+        hot_rank -> Int4,
     }
 }
 
@@ -60,6 +131,17 @@ table! {
         resolver_id -> Nullable<Int4>,
         published -> Timestamp,
         updated -> Nullable<Timestamp>,
+        resolution_note -> Nullable<Text>,
+        escalated -> Bool,
+    }
+}
+
+table! {
+    comment_revision (id) {
+        id -> Int4,
+        comment_id -> Int4,
+        content -> Text,
+        published -> Timestamp,
     }
 }
 
@@ -69,6 +151,7 @@ table! {
         comment_id -> Int4,
         person_id -> Int4,
         published -> Timestamp,
+        collection_id -> Nullable<Int4>,
     }
 }
 
@@ -93,6 +176,8 @@ table! {
         followers_url -> Varchar,
         inbox_url -> Varchar,
         shared_inbox_url -> Nullable<Varchar>,
+        require_post_approval -> Bool,
+        category_id -> Nullable<Int4>,
     }
 }
 
@@ -108,6 +193,27 @@ table! {
         users_active_week -> Int8,
         users_active_month -> Int8,
         users_active_half_year -> Int8,
+        trending_score -> Float8,
+    }
+}
+
+table! {
+    site_word_filter (id) {
+        id -> Int4,
+        pattern -> Text,
+        action -> Text,
+        published -> Timestamp,
+    }
+}
+
+table! {
+    legal_hold (id) {
+        id -> Int4,
+        post_id -> Nullable<Int4>,
+        comment_id -> Nullable<Int4>,
+        admin_person_id -> Int4,
+        reason -> Text,
+        published -> Timestamp,
     }
 }
 
@@ -121,12 +227,21 @@ table! {
     }
 }
 
+table! {
+    community_language (id) {
+        id -> Int4,
+        community_id -> Int4,
+        language_id -> Int4,
+    }
+}
+
 table! {
     community_moderator (id) {
         id -> Int4,
         community_id -> Int4,
         person_id -> Int4,
         published -> Timestamp,
+        rank -> Int4,
     }
 }
 
@@ -136,6 +251,40 @@ table! {
         community_id -> Int4,
         person_id -> Int4,
         published -> Timestamp,
+        expires -> Nullable<Timestamp>,
+    }
+}
+
+table! {
+    community_wiki_page (id) {
+        id -> Int4,
+        community_id -> Int4,
+        creator_id -> Int4,
+        title -> Varchar,
+        content -> Text,
+        deleted -> Bool,
+        published -> Timestamp,
+        updated -> Nullable<Timestamp>,
+        ap_id -> Varchar,
+        local -> Bool,
+    }
+}
+
+table! {
+    community_wiki_page_revision (id) {
+        id -> Int4,
+        wiki_page_id -> Int4,
+        editor_id -> Int4,
+        content -> Text,
+        published -> Timestamp,
+    }
+}
+
+table! {
+    language (id) {
+        id -> Int4,
+        code -> Varchar,
+        name -> Text,
     }
 }
 
@@ -159,6 +308,21 @@ table! {
         show_new_post_notifs -> Bool,
         email_verified -> Bool,
         accepted_application -> Bool,
+        totp_secret -> Nullable<Text>,
+        totp_enabled -> Bool,
+        email_digest_frequency -> Text,
+        email_digest_sent -> Nullable<Timestamp>,
+        totp_last_step -> Nullable<Int8>,
+        totp_failed_attempts -> Int4,
+        totp_last_failure -> Nullable<Timestamp>,
+    }
+}
+
+table! {
+    local_user_language (id) {
+        id -> Int4,
+        local_user_id -> Int4,
+        language_id -> Int4,
     }
 }
 
@@ -194,6 +358,18 @@ table! {
     }
 }
 
+table! {
+    mod_automod_action (id) {
+        id -> Int4,
+        automod_rule_id -> Nullable<Int4>,
+        community_id -> Int4,
+        post_id -> Nullable<Int4>,
+        comment_id -> Nullable<Int4>,
+        action -> Text,
+        when_ -> Timestamp,
+    }
+}
+
 table! {
     mod_ban (id) {
         id -> Int4,
@@ -216,6 +392,7 @@ table! {
         banned -> Nullable<Bool>,
         expires -> Nullable<Timestamp>,
         when_ -> Timestamp,
+        public -> Bool,
     }
 }
 
@@ -233,10 +410,13 @@ table! {
     mod_remove_comment (id) {
         id -> Int4,
         mod_person_id -> Int4,
-        comment_id -> Int4,
+        comment_id -> Nullable<Int4>,
         reason -> Nullable<Text>,
         removed -> Nullable<Bool>,
         when_ -> Timestamp,
+        reason_translations -> Nullable<Jsonb>,
+        reason_override -> Bool,
+        public -> Bool,
     }
 }
 
@@ -244,11 +424,14 @@ table! {
     mod_remove_community (id) {
         id -> Int4,
         mod_person_id -> Int4,
-        community_id -> Int4,
+        community_id -> Nullable<Int4>,
         reason -> Nullable<Text>,
         removed -> Nullable<Bool>,
         expires -> Nullable<Timestamp>,
         when_ -> Timestamp,
+        reason_translations -> Nullable<Jsonb>,
+        reason_override -> Bool,
+        public -> Bool,
     }
 }
 
@@ -256,10 +439,36 @@ table! {
     mod_remove_post (id) {
         id -> Int4,
         mod_person_id -> Int4,
-        post_id -> Int4,
+        post_id -> Nullable<Int4>,
         reason -> Nullable<Text>,
         removed -> Nullable<Bool>,
         when_ -> Timestamp,
+        reason_translations -> Nullable<Jsonb>,
+        reason_override -> Bool,
+        public -> Bool,
+    }
+}
+
+table! {
+    mod_resolve_appeal (id) {
+        id -> Int4,
+        mod_person_id -> Int4,
+        appeal_id -> Int4,
+        granted -> Bool,
+        reason -> Nullable<Text>,
+        when_ -> Timestamp,
+    }
+}
+
+table! {
+    mod_shadow_remove_post (id) {
+        id -> Int4,
+        mod_person_id -> Int4,
+        post_id -> Int4,
+        reason -> Nullable<Text>,
+        shadow_removed -> Nullable<Bool>,
+        when_ -> Timestamp,
+        reason_override -> Bool,
     }
 }
 
@@ -282,6 +491,20 @@ table! {
     }
 }
 
+table! {
+    pending_deletion (id) {
+        id -> Int4,
+        actor_id -> Int4,
+        community_id -> Int4,
+        activity_id -> Text,
+        object_id -> Text,
+        removal -> Bool,
+        reason -> Nullable<Text>,
+        published -> Timestamp,
+        restore -> Bool,
+    }
+}
+
 table! {
     person (id) {
         id -> Int4,
@@ -318,6 +541,26 @@ table! {
     }
 }
 
+table! {
+    person_community_karma (person_id, community_id) {
+        person_id -> Int4,
+        community_id -> Int4,
+        post_count -> Int8,
+        post_score -> Int8,
+        comment_count -> Int8,
+        comment_score -> Int8,
+    }
+}
+
+table! {
+    person_monthly_activity (person_id, month) {
+        person_id -> Int4,
+        month -> Timestamp,
+        post_count -> Int8,
+        comment_count -> Int8,
+    }
+}
+
 table! {
     person_ban (id) {
         id -> Int4,
@@ -326,6 +569,25 @@ table! {
     }
 }
 
+table! {
+    person_field (id) {
+        id -> Int4,
+        person_id -> Int4,
+        name -> Text,
+        value -> Text,
+        position -> Int4,
+    }
+}
+
+table! {
+    person_instance_block (id) {
+        id -> Int4,
+        person_id -> Int4,
+        domain -> Varchar,
+        published -> Timestamp,
+    }
+}
+
 table! {
     person_mention (id) {
         id -> Int4,
@@ -336,6 +598,35 @@ table! {
     }
 }
 
+table! {
+    poll (id) {
+        id -> Int4,
+        post_id -> Int4,
+        multiple_choice -> Bool,
+        end_time -> Nullable<Timestamp>,
+        published -> Timestamp,
+    }
+}
+
+table! {
+    poll_option (id) {
+        id -> Int4,
+        poll_id -> Int4,
+        name -> Varchar,
+        position -> Int4,
+        votes -> Int4,
+    }
+}
+
+table! {
+    poll_vote (id) {
+        id -> Int4,
+        poll_option_id -> Int4,
+        person_id -> Int4,
+        published -> Timestamp,
+    }
+}
+
 table! {
     post (id) {
         id -> Int4,
@@ -357,6 +648,12 @@ table! {
         thumbnail_url -> Nullable<Text>,
         ap_id -> Varchar,
         local -> Bool,
+        shadow_removed -> Bool,
+        cross_post_of -> Nullable<Int4>,
+        embed_video_url -> Nullable<Text>,
+        embed_provider_name -> Nullable<Text>,
+        language_id -> Int4,
+        pending -> Bool,
     }
 }
 
@@ -372,6 +669,53 @@ table! {
         published -> Timestamp,
         newest_comment_time_necro -> Timestamp,
         newest_comment_time -> Timestamp,
+        stickied_at -> Nullable<Timestamp>,
+        hot_rank -> Int4,
+        hot_rank_active -> Int4,
+    }
+}
+
+table! {
+    post_attachment (id) {
+        id -> Int4,
+        post_id -> Int4,
+        url -> Text,
+        media_type -> Nullable<Text>,
+        name -> Nullable<Text>,
+        position -> Int4,
+    }
+}
+
+table! {
+    post_draft (id) {
+        id -> Int4,
+        creator_id -> Int4,
+        community_id -> Nullable<Int4>,
+        name -> Nullable<Varchar>,
+        url -> Nullable<Text>,
+        body -> Nullable<Text>,
+        nsfw -> Bool,
+        published -> Timestamp,
+        updated -> Nullable<Timestamp>,
+    }
+}
+
+table! {
+    post_tag (id) {
+        id -> Int4,
+        community_id -> Int4,
+        name -> Varchar,
+        published -> Timestamp,
+        updated -> Nullable<Timestamp>,
+    }
+}
+
+table! {
+    post_tag_assignment (id) {
+        id -> Int4,
+        post_id -> Int4,
+        tag_id -> Int4,
+        published -> Timestamp,
     }
 }
 
@@ -407,6 +751,8 @@ table! {
         resolver_id -> Nullable<Int4>,
         published -> Timestamp,
         updated -> Nullable<Timestamp>,
+        resolution_note -> Nullable<Text>,
+        escalated -> Bool,
     }
 }
 
@@ -416,6 +762,18 @@ table! {
         post_id -> Int4,
         person_id -> Int4,
         published -> Timestamp,
+        collection_id -> Nullable<Int4>,
+    }
+}
+
+table! {
+    push_subscription (id) {
+        id -> Int4,
+        local_user_id -> Int4,
+        endpoint -> Text,
+        p256dh -> Text,
+        auth -> Text,
+        published -> Timestamp,
     }
 }
 
@@ -431,6 +789,55 @@ table! {
         updated -> Nullable<Timestamp>,
         ap_id -> Varchar,
         local -> Bool,
+        thread_id -> Int4,
+    }
+}
+
+table! {
+    private_message_thread (id) {
+        id -> Int4,
+        person_a_id -> Int4,
+        person_b_id -> Int4,
+        published -> Timestamp,
+        newest_message_time -> Timestamp,
+    }
+}
+
+table! {
+    received_deletion (id) {
+        id -> Int4,
+        actor_id -> Text,
+        activity_id -> Text,
+        kind -> Text,
+        object_id -> Text,
+        accepted -> Bool,
+        published -> Timestamp,
+        held -> Bool,
+    }
+}
+
+table! {
+    scheduled_post (id) {
+        id -> Int4,
+        creator_id -> Int4,
+        community_id -> Int4,
+        name -> Varchar,
+        url -> Nullable<Text>,
+        body -> Nullable<Text>,
+        nsfw -> Bool,
+        publish_at -> Timestamp,
+        published -> Timestamp,
+        language_id -> Int4,
+    }
+}
+
+table! {
+    saved_collection (id) {
+        id -> Int4,
+        person_id -> Int4,
+        name -> Varchar,
+        published -> Timestamp,
+        updated -> Nullable<Timestamp>,
     }
 }
 
@@ -453,6 +860,8 @@ table! {
         require_application -> Bool,
         application_question -> Nullable<Text>,
         private_instance -> Bool,
+        captcha_provider -> Text,
+        captcha_site_key -> Nullable<Text>,
     }
 }
 
@@ -477,6 +886,7 @@ table! {
         person_id -> Int4,
         target_id -> Int4,
         published -> Timestamp,
+        prevent_interaction -> Bool,
     }
 }
 
@@ -504,6 +914,8 @@ table! {
         deleted -> Bool,
         ap_id -> Varchar,
         local -> Bool,
+        path -> Text,
+        language_id -> Int4,
     }
 }
 
@@ -561,6 +973,8 @@ table! {
   secret(id) {
     id -> Int4,
     jwt_secret -> Varchar,
+    instance_private_key -> Nullable<Text>,
+    instance_public_key -> Nullable<Text>,
   }
 }
 
@@ -574,6 +988,74 @@ table! {
   }
 }
 
+table! {
+  external_identity (id) {
+    id -> Int4,
+    local_user_id -> Int4,
+    provider -> Text,
+    subject -> Text,
+    published -> Timestamp,
+  }
+}
+
+table! {
+  oauth_state (id) {
+    id -> Int4,
+    state -> Text,
+    provider -> Text,
+    redirect_uri -> Text,
+    code_verifier -> Text,
+    local_user_id -> Nullable<Int4>,
+    published -> Timestamp,
+  }
+}
+
+table! {
+    federation_conflict (id) {
+        id -> Int4,
+        object_id -> Varchar,
+        activity_id -> Varchar,
+        local_updated -> Timestamp,
+        remote_updated -> Nullable<Timestamp>,
+        published -> Timestamp,
+    }
+}
+
+table! {
+    instance_send_failure (id) {
+        id -> Int4,
+        domain -> Varchar,
+        failures -> Int4,
+        last_failure -> Timestamp,
+    }
+}
+
+table! {
+    federation_instance (id) {
+        id -> Int4,
+        domain -> Varchar,
+        blocked -> Bool,
+        published -> Timestamp,
+    }
+}
+
+table! {
+    instance (id) {
+        id -> Int4,
+        domain -> Varchar,
+        software -> Nullable<Varchar>,
+        version -> Nullable<Varchar>,
+        users -> Nullable<Int8>,
+        users_active_month -> Nullable<Int8>,
+        published -> Timestamp,
+        updated -> Nullable<Timestamp>,
+        name -> Nullable<Varchar>,
+        description -> Nullable<Text>,
+        icon -> Nullable<Text>,
+        blocks_us -> Bool,
+    }
+}
+
 table! {
     registration_application (id) {
         id -> Int4,
@@ -585,6 +1067,135 @@ table! {
     }
 }
 
+table! {
+    login_session (id) {
+        id -> Int4,
+        local_user_id -> Int4,
+        user_agent -> Nullable<Text>,
+        ip -> Nullable<Text>,
+        published -> Timestamp,
+        last_used -> Timestamp,
+    }
+}
+
+table! {
+    user_token (id) {
+        id -> Int4,
+        local_user_id -> Int4,
+        name -> Text,
+        token_hash -> Text,
+        scopes -> Array<Text>,
+        published -> Timestamp,
+        last_used -> Nullable<Timestamp>,
+    }
+}
+
+table! {
+    admin_purge_comment (id) {
+        id -> Int4,
+        admin_person_id -> Int4,
+        post_id -> Int4,
+        reason -> Nullable<Text>,
+        when_ -> Timestamp,
+    }
+}
+
+table! {
+    admin_purge_community (id) {
+        id -> Int4,
+        admin_person_id -> Int4,
+        reason -> Nullable<Text>,
+        when_ -> Timestamp,
+    }
+}
+
+table! {
+    admin_purge_person (id) {
+        id -> Int4,
+        admin_person_id -> Int4,
+        reason -> Nullable<Text>,
+        when_ -> Timestamp,
+    }
+}
+
+table! {
+    admin_purge_post (id) {
+        id -> Int4,
+        admin_person_id -> Int4,
+        community_id -> Int4,
+        reason -> Nullable<Text>,
+        when_ -> Timestamp,
+    }
+}
+
+table! {
+    post_reaction (id) {
+        id -> Int4,
+        post_id -> Int4,
+        person_id -> Int4,
+        emoji -> Text,
+        published -> Timestamp,
+    }
+}
+
+table! {
+    comment_reaction (id) {
+        id -> Int4,
+        comment_id -> Int4,
+        person_id -> Int4,
+        emoji -> Text,
+        published -> Timestamp,
+    }
+}
+
+table! {
+    custom_emoji (id) {
+        id -> Int4,
+        shortcode -> Varchar,
+        image_url -> Text,
+        category -> Varchar,
+        published -> Timestamp,
+        updated -> Nullable<Timestamp>,
+    }
+}
+
+table! {
+    community_category (id) {
+        id -> Int4,
+        name -> Varchar,
+        published -> Timestamp,
+        updated -> Nullable<Timestamp>,
+    }
+}
+
+table! {
+    multi_community (id) {
+        id -> Int4,
+        person_id -> Int4,
+        name -> Varchar,
+        published -> Timestamp,
+        updated -> Nullable<Timestamp>,
+    }
+}
+
+table! {
+    multi_community_community (id) {
+        id -> Int4,
+        multi_community_id -> Int4,
+        community_id -> Int4,
+        published -> Timestamp,
+    }
+}
+
+table! {
+    feed_token (id) {
+        id -> Int4,
+        local_user_id -> Int4,
+        token_hash -> Text,
+        published -> Timestamp,
+    }
+}
+
 joinable!(comment_alias_1 -> person_alias_1 (creator_id));
 joinable!(comment -> comment_alias_1 (parent_id));
 joinable!(person_mention -> person_alias_1 (recipient_id));
@@ -595,8 +1206,14 @@ joinable!(post_report -> person_alias_2 (resolver_id));
 joinable!(comment_report -> person_alias_2 (resolver_id));
 
 joinable!(person_block -> person (person_id));
+joinable!(person_instance_block -> person (person_id));
 joinable!(person_block -> person_alias_1 (target_id));
 
+joinable!(appeal -> comment (comment_id));
+joinable!(appeal -> community (community_id));
+joinable!(appeal -> person (creator_id));
+joinable!(appeal -> post (post_id));
+joinable!(automod_rule -> community (community_id));
 joinable!(comment -> person (creator_id));
 joinable!(comment -> post (post_id));
 joinable!(comment_aggregates -> comment (comment_id));
@@ -604,9 +1221,16 @@ joinable!(comment_like -> comment (comment_id));
 joinable!(comment_like -> person (person_id));
 joinable!(comment_like -> post (post_id));
 joinable!(comment_report -> comment (comment_id));
+joinable!(comment_revision -> comment (comment_id));
 joinable!(comment_saved -> comment (comment_id));
 joinable!(comment_saved -> person (person_id));
+joinable!(comment_saved -> saved_collection (collection_id));
+joinable!(saved_collection -> person (person_id));
 joinable!(community_aggregates -> community (community_id));
+joinable!(community -> community_category (category_id));
+joinable!(multi_community -> person (person_id));
+joinable!(multi_community_community -> multi_community (multi_community_id));
+joinable!(multi_community_community -> community (community_id));
 joinable!(community_block -> community (community_id));
 joinable!(community_block -> person (person_id));
 joinable!(community_follower -> community (community_id));
@@ -615,6 +1239,10 @@ joinable!(community_moderator -> community (community_id));
 joinable!(community_moderator -> person (person_id));
 joinable!(community_person_ban -> community (community_id));
 joinable!(community_person_ban -> person (person_id));
+joinable!(community_wiki_page -> community (community_id));
+joinable!(community_wiki_page -> person (creator_id));
+joinable!(community_wiki_page_revision -> community_wiki_page (wiki_page_id));
+joinable!(community_wiki_page_revision -> person (editor_id));
 joinable!(local_user -> person (person_id));
 joinable!(mod_add_community -> community (community_id));
 joinable!(mod_transfer_community -> community (community_id));
@@ -627,16 +1255,37 @@ joinable!(mod_remove_community -> community (community_id));
 joinable!(mod_remove_community -> person (mod_person_id));
 joinable!(mod_remove_post -> person (mod_person_id));
 joinable!(mod_remove_post -> post (post_id));
+joinable!(mod_automod_action -> automod_rule (automod_rule_id));
+joinable!(mod_automod_action -> comment (comment_id));
+joinable!(mod_automod_action -> community (community_id));
+joinable!(mod_automod_action -> post (post_id));
+joinable!(mod_resolve_appeal -> appeal (appeal_id));
+joinable!(mod_resolve_appeal -> person (mod_person_id));
+joinable!(mod_shadow_remove_post -> person (mod_person_id));
+joinable!(mod_shadow_remove_post -> post (post_id));
 joinable!(mod_sticky_post -> person (mod_person_id));
 joinable!(mod_sticky_post -> post (post_id));
 joinable!(password_reset_request -> local_user (local_user_id));
+joinable!(pending_deletion -> community (community_id));
+joinable!(pending_deletion -> person (actor_id));
 joinable!(person_aggregates -> person (person_id));
+joinable!(person_community_karma -> community (community_id));
+joinable!(person_community_karma -> person (person_id));
+joinable!(person_monthly_activity -> person (person_id));
 joinable!(person_ban -> person (person_id));
+joinable!(person_field -> person (person_id));
 joinable!(person_mention -> comment (comment_id));
 joinable!(person_mention -> person (recipient_id));
+joinable!(poll -> post (post_id));
+joinable!(poll_option -> poll (poll_id));
+joinable!(poll_vote -> person (person_id));
+joinable!(poll_vote -> poll_option (poll_option_id));
 joinable!(post -> community (community_id));
 joinable!(post -> person (creator_id));
 joinable!(post_aggregates -> post (post_id));
+joinable!(post_attachment -> post (post_id));
+joinable!(post_draft -> community (community_id));
+joinable!(post_draft -> person (creator_id));
 joinable!(post_like -> person (person_id));
 joinable!(post_like -> post (post_id));
 joinable!(post_read -> person (person_id));
@@ -644,26 +1293,71 @@ joinable!(post_read -> post (post_id));
 joinable!(post_report -> post (post_id));
 joinable!(post_saved -> person (person_id));
 joinable!(post_saved -> post (post_id));
+joinable!(post_saved -> saved_collection (collection_id));
+joinable!(post_tag -> community (community_id));
+joinable!(account_export -> local_user (local_user_id));
+joinable!(post_tag_assignment -> post (post_id));
+joinable!(post_tag_assignment -> post_tag (tag_id));
+joinable!(scheduled_post -> community (community_id));
+joinable!(scheduled_post -> person (creator_id));
+joinable!(scheduled_post -> language (language_id));
 joinable!(site -> person (creator_id));
 joinable!(site_aggregates -> site (site_id));
 joinable!(email_verification -> local_user (local_user_id));
+joinable!(external_identity -> local_user (local_user_id));
+joinable!(oauth_state -> local_user (local_user_id));
 joinable!(registration_application -> local_user (local_user_id));
 joinable!(registration_application -> person (admin_id));
+joinable!(login_session -> local_user (local_user_id));
+joinable!(user_token -> local_user (local_user_id));
+joinable!(feed_token -> local_user (local_user_id));
+joinable!(admin_purge_comment -> person (admin_person_id));
+joinable!(admin_purge_comment -> post (post_id));
+joinable!(admin_purge_community -> person (admin_person_id));
+joinable!(admin_purge_person -> person (admin_person_id));
+joinable!(admin_purge_post -> community (community_id));
+joinable!(admin_purge_post -> person (admin_person_id));
+joinable!(post_reaction -> person (person_id));
+joinable!(post_reaction -> post (post_id));
+joinable!(comment_reaction -> comment (comment_id));
+joinable!(comment_reaction -> person (person_id));
+joinable!(private_message -> private_message_thread (thread_id));
+joinable!(push_subscription -> local_user (local_user_id));
+joinable!(post -> language (language_id));
+joinable!(comment -> language (language_id));
+joinable!(local_user_language -> local_user (local_user_id));
+joinable!(local_user_language -> language (language_id));
+joinable!(community_language -> community (community_id));
+joinable!(community_language -> language (language_id));
+joinable!(legal_hold -> post (post_id));
+joinable!(legal_hold -> comment (comment_id));
+joinable!(legal_hold -> person (admin_person_id));
 
 allow_tables_to_appear_in_same_query!(
+  account_export,
   activity,
+  activity_send_queue,
+  appeal,
+  automod_rule,
+  captcha_answer,
   comment,
   comment_aggregates,
   community_block,
   comment_like,
   comment_report,
+  comment_revision,
   comment_saved,
   community,
   community_aggregates,
   community_follower,
+  community_language,
   community_moderator,
   community_person_ban,
+  community_wiki_page,
+  community_wiki_page_revision,
+  language,
   local_user,
+  local_user_language,
   mod_add,
   mod_add_community,
   mod_transfer_community,
@@ -673,25 +1367,66 @@ allow_tables_to_appear_in_same_query!(
   mod_remove_comment,
   mod_remove_community,
   mod_remove_post,
+  mod_automod_action,
+  mod_resolve_appeal,
+  mod_shadow_remove_post,
   mod_sticky_post,
   password_reset_request,
+  pending_deletion,
   person,
   person_aggregates,
+  person_community_karma,
+  person_monthly_activity,
   person_ban,
   person_block,
+  person_field,
+  person_instance_block,
+  saved_collection,
   person_mention,
+  poll,
+  poll_option,
+  poll_vote,
   post,
   post_aggregates,
+  post_attachment,
+  post_draft,
   post_like,
   post_read,
   post_report,
   post_saved,
+  post_tag,
+  post_tag_assignment,
   private_message,
+  private_message_thread,
+  push_subscription,
+  received_deletion,
+  scheduled_post,
   site,
   site_aggregates,
+  site_word_filter,
+  legal_hold,
   comment_alias_1,
   person_alias_1,
   person_alias_2,
   email_verification,
-  registration_application
+  external_identity,
+  oauth_state,
+  federation_conflict,
+  federation_instance,
+  instance,
+  instance_send_failure,
+  registration_application,
+  login_session,
+  user_token,
+  admin_purge_comment,
+  admin_purge_community,
+  admin_purge_person,
+  admin_purge_post,
+  post_reaction,
+  comment_reaction,
+  custom_emoji,
+  community_category,
+  multi_community,
+  multi_community_community,
+  feed_token
 );
@@ -0,0 +1,19 @@
+use crate::{newtypes::LocalUserId, schema::account_export};
+
+#[derive(Clone, Queryable, Identifiable, PartialEq, Debug)]
+#[table_name = "account_export"]
+pub struct AccountExport {
+  pub id: i32,
+  pub local_user_id: LocalUserId,
+  pub token: String,
+  pub data: Option<String>,
+  pub published: chrono::NaiveDateTime,
+  pub finished: Option<chrono::NaiveDateTime>,
+}
+
+#[derive(Insertable, AsChangeset)]
+#[table_name = "account_export"]
+pub struct AccountExportForm {
+  pub local_user_id: LocalUserId,
+  pub token: String,
+}
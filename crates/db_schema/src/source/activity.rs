@@ -12,6 +12,8 @@ pub struct Activity {
   pub updated: Option<chrono::NaiveDateTime>,
   pub ap_id: DbUrl,
   pub sensitive: Option<bool>,
+  pub forwarded: bool,
+  pub held: bool,
 }
 
 #[derive(Insertable, AsChangeset)]
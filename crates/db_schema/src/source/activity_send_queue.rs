@@ -0,0 +1,42 @@
+use crate::{
+  newtypes::DbUrl,
+  schema::{activity_send_queue, instance_send_failure},
+};
+use serde::{Deserialize, Serialize};
+
+/// An outgoing activity which still needs to be delivered to the inbox
+#[derive(Clone, Queryable, Identifiable, PartialEq, Debug, Serialize, Deserialize)]
+#[table_name = "activity_send_queue"]
+pub struct QueuedActivity {
+  pub id: i32,
+  pub activity_id: DbUrl,
+  pub inbox: DbUrl,
+  /// The activity is signed with this actor's key when sending
+  pub actor_id: DbUrl,
+  pub activity: String,
+  pub retries: i32,
+  pub next_attempt: chrono::NaiveDateTime,
+  pub published: chrono::NaiveDateTime,
+  /// Id of the http request or websocket message which created the delivery
+  pub request_id: Option<String>,
+}
+
+#[derive(Insertable)]
+#[table_name = "activity_send_queue"]
+pub struct QueuedActivityForm {
+  pub activity_id: DbUrl,
+  pub inbox: DbUrl,
+  pub actor_id: DbUrl,
+  pub activity: String,
+  pub request_id: Option<String>,
+}
+
+#[derive(Clone, Queryable, Identifiable, PartialEq, Debug, Serialize, Deserialize)]
+#[table_name = "instance_send_failure"]
+pub struct InstanceSendFailure {
+  pub id: i32,
+  pub domain: String,
+  /// Number of failed deliveries since the last successful one
+  pub failures: i32,
+  pub last_failure: chrono::NaiveDateTime,
+}
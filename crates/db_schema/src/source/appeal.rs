@@ -0,0 +1,79 @@
+use crate::{
+  newtypes::{CommentId, CommunityId, PersonId, PostId},
+  schema::appeal,
+};
+use diesel::{
+  backend::Backend,
+  deserialize::FromSql,
+  serialize::{Output, ToSql},
+  sql_types::Text,
+};
+use serde::{Deserialize, Serialize};
+use std::{io::Write, str::FromStr};
+
+#[derive(
+  EnumString,
+  Display,
+  Debug,
+  Serialize,
+  Deserialize,
+  Clone,
+  Copy,
+  PartialEq,
+  AsExpression,
+  FromSqlRow,
+)]
+#[sql_type = "Text"]
+pub enum AppealStatus {
+  Pending,
+  /// The removal was confirmed by a mod
+  Upheld,
+  /// The removed object was restored
+  Granted,
+}
+
+impl<DB: Backend> ToSql<Text, DB> for AppealStatus
+where
+  String: ToSql<Text, DB>,
+{
+  fn to_sql<W: Write>(&self, out: &mut Output<W, DB>) -> diesel::serialize::Result {
+    self.to_string().to_sql(out)
+  }
+}
+
+impl<DB: Backend> FromSql<Text, DB> for AppealStatus
+where
+  String: FromSql<Text, DB>,
+{
+  fn from_sql(bytes: Option<&DB::RawValue>) -> diesel::deserialize::Result<Self> {
+    let str = String::from_sql(bytes)?;
+    Ok(AppealStatus::from_str(&str)?)
+  }
+}
+
+/// An appeal against the removal of a post, comment or community. For community appeals, both
+/// post_id and comment_id are empty.
+#[derive(Clone, Queryable, Identifiable, PartialEq, Debug, Serialize, Deserialize)]
+#[table_name = "appeal"]
+pub struct Appeal {
+  pub id: i32,
+  pub creator_id: PersonId,
+  pub community_id: CommunityId,
+  pub post_id: Option<PostId>,
+  pub comment_id: Option<CommentId>,
+  pub reason: String,
+  pub status: AppealStatus,
+  pub resolver_id: Option<PersonId>,
+  pub published: chrono::NaiveDateTime,
+  pub updated: Option<chrono::NaiveDateTime>,
+}
+
+#[derive(Insertable, AsChangeset, Clone)]
+#[table_name = "appeal"]
+pub struct AppealForm {
+  pub creator_id: PersonId,
+  pub community_id: CommunityId,
+  pub post_id: Option<PostId>,
+  pub comment_id: Option<CommentId>,
+  pub reason: String,
+}
@@ -0,0 +1,46 @@
+use crate::{newtypes::CommunityId, schema::automod_rule};
+use serde::{Deserialize, Serialize};
+
+/// A rule which moderators define for their community. Every condition which is set needs to match
+/// for the action to be applied.
+#[derive(Clone, Queryable, Identifiable, PartialEq, Debug, Serialize, Deserialize)]
+#[table_name = "automod_rule"]
+pub struct AutomodRule {
+  pub id: i32,
+  pub community_id: CommunityId,
+  pub title_regex: Option<String>,
+  pub body_regex: Option<String>,
+  pub link_domain: Option<String>,
+  pub account_age_below_days: Option<i32>,
+  pub karma_below: Option<i32>,
+  pub action: String,
+  pub reason: Option<String>,
+  pub published: chrono::NaiveDateTime,
+  pub updated: Option<chrono::NaiveDateTime>,
+}
+
+#[derive(Insertable, AsChangeset, Clone)]
+#[table_name = "automod_rule"]
+#[changeset_options(treat_none_as_null = "true")]
+pub struct AutomodRuleForm {
+  pub community_id: CommunityId,
+  pub title_regex: Option<String>,
+  pub body_regex: Option<String>,
+  pub link_domain: Option<String>,
+  pub account_age_below_days: Option<i32>,
+  pub karma_below: Option<i32>,
+  pub action: String,
+  pub reason: Option<String>,
+  pub updated: Option<chrono::NaiveDateTime>,
+}
+
+/// The parts of a post or comment which automod rules are checked against. Comments don't have a
+/// title or url.
+#[derive(Debug)]
+pub struct AutomodContent<'a> {
+  pub title: Option<&'a str>,
+  pub body: Option<&'a str>,
+  pub url: Option<&'a str>,
+  pub creator_published: chrono::NaiveDateTime,
+  pub creator_karma: i64,
+}
@@ -0,0 +1,17 @@
+use crate::schema::captcha_answer;
+
+#[derive(Queryable, Identifiable, PartialEq, Debug)]
+#[table_name = "captcha_answer"]
+pub struct CaptchaAnswer {
+  pub id: i32,
+  pub uuid: String,
+  pub answer: String,
+  pub published: chrono::NaiveDateTime,
+}
+
+#[derive(Insertable)]
+#[table_name = "captcha_answer"]
+pub struct CaptchaAnswerForm {
+  pub uuid: String,
+  pub answer: String,
+}
@@ -1,6 +1,6 @@
 use crate::{
-  newtypes::{CommentId, DbUrl, PersonId, PostId},
-  schema::{comment, comment_alias_1, comment_like, comment_saved},
+  newtypes::{CommentId, DbUrl, LanguageId, PersonId, PostId, SavedCollectionId},
+  schema::{comment, comment_alias_1, comment_like, comment_reaction, comment_saved},
   source::post::Post,
 };
 use serde::{Deserialize, Serialize};
@@ -30,6 +30,9 @@ pub struct Comment {
   pub deleted: bool,
   pub ap_id: DbUrl,
   pub local: bool,
+  /// Ids of all ancestors and the comment itself, separated by dots. Maintained by the database.
+  pub path: String,
+  pub language_id: LanguageId,
 }
 
 #[derive(
@@ -50,6 +53,9 @@ pub struct CommentAlias1 {
   pub deleted: bool,
   pub ap_id: DbUrl,
   pub local: bool,
+  /// Ids of all ancestors and the comment itself, separated by dots. Maintained by the database.
+  pub path: String,
+  pub language_id: LanguageId,
 }
 
 #[derive(Insertable, AsChangeset, Clone, Default)]
@@ -66,6 +72,7 @@ pub struct CommentForm {
   pub deleted: Option<bool>,
   pub ap_id: Option<DbUrl>,
   pub local: Option<bool>,
+  pub language_id: Option<LanguageId>,
 }
 
 #[derive(Identifiable, Queryable, Associations, PartialEq, Debug, Clone)]
@@ -89,6 +96,25 @@ pub struct CommentLikeForm {
   pub score: i16,
 }
 
+#[derive(Identifiable, Queryable, Associations, PartialEq, Debug)]
+#[belongs_to(Comment)]
+#[table_name = "comment_reaction"]
+pub struct CommentReaction {
+  pub id: i32,
+  pub comment_id: CommentId,
+  pub person_id: PersonId,
+  pub emoji: String,
+  pub published: chrono::NaiveDateTime,
+}
+
+#[derive(Insertable, Clone)]
+#[table_name = "comment_reaction"]
+pub struct CommentReactionForm {
+  pub comment_id: CommentId,
+  pub person_id: PersonId,
+  pub emoji: String,
+}
+
 #[derive(Identifiable, Queryable, Associations, PartialEq, Debug)]
 #[belongs_to(Comment)]
 #[table_name = "comment_saved"]
@@ -97,6 +123,7 @@ pub struct CommentSaved {
   pub comment_id: CommentId,
   pub person_id: PersonId,
   pub published: chrono::NaiveDateTime,
+  pub collection_id: Option<SavedCollectionId>,
 }
 
 #[derive(Insertable, AsChangeset)]
@@ -104,4 +131,5 @@ pub struct CommentSaved {
 pub struct CommentSavedForm {
   pub comment_id: CommentId,
   pub person_id: PersonId,
+  pub collection_id: Option<SavedCollectionId>,
 }
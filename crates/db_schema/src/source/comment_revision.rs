@@ -0,0 +1,21 @@
+use crate::{newtypes::CommentId, schema::comment_revision};
+use serde::{Deserialize, Serialize};
+
+/// The content of a comment before it was edited
+#[derive(Clone, Queryable, Identifiable, PartialEq, Debug, Serialize, Deserialize)]
+#[table_name = "comment_revision"]
+pub struct CommentRevision {
+  pub id: i32,
+  pub comment_id: CommentId,
+  pub content: String,
+  /// When this version of the comment was written
+  pub published: chrono::NaiveDateTime,
+}
+
+#[derive(Insertable)]
+#[table_name = "comment_revision"]
+pub struct CommentRevisionForm {
+  pub comment_id: CommentId,
+  pub content: String,
+  pub published: chrono::NaiveDateTime,
+}
@@ -26,6 +26,8 @@ pub struct Community {
   pub followers_url: DbUrl,
   pub inbox_url: DbUrl,
   pub shared_inbox_url: Option<DbUrl>,
+  pub require_post_approval: bool,
+  pub category_id: Option<i32>,
 }
 
 /// A safe representation of community, without the sensitive info
@@ -45,6 +47,8 @@ pub struct CommunitySafe {
   pub local: bool,
   pub icon: Option<DbUrl>,
   pub banner: Option<DbUrl>,
+  pub require_post_approval: bool,
+  pub category_id: Option<i32>,
 }
 
 #[derive(Insertable, AsChangeset, Debug, Default)]
@@ -68,6 +72,8 @@ pub struct CommunityForm {
   pub followers_url: Option<DbUrl>,
   pub inbox_url: Option<DbUrl>,
   pub shared_inbox_url: Option<Option<DbUrl>>,
+  pub require_post_approval: Option<bool>,
+  pub category_id: Option<Option<i32>>,
 }
 
 #[derive(Identifiable, Queryable, Associations, PartialEq, Debug)]
@@ -78,6 +84,8 @@ pub struct CommunityModerator {
   pub community_id: CommunityId,
   pub person_id: PersonId,
   pub published: chrono::NaiveDateTime,
+  /// Position in the list of mods, the top mod has rank 0. New mods are added at the bottom.
+  pub rank: i32,
 }
 
 #[derive(Insertable, AsChangeset, Clone)]
@@ -95,6 +103,7 @@ pub struct CommunityPersonBan {
   pub community_id: CommunityId,
   pub person_id: PersonId,
   pub published: chrono::NaiveDateTime,
+  pub expires: Option<chrono::NaiveDateTime>,
 }
 
 #[derive(Insertable, AsChangeset, Clone)]
@@ -102,6 +111,7 @@ pub struct CommunityPersonBan {
 pub struct CommunityPersonBanForm {
   pub community_id: CommunityId,
   pub person_id: PersonId,
+  pub expires: Option<chrono::NaiveDateTime>,
 }
 
 #[derive(Identifiable, Queryable, Associations, PartialEq, Debug)]
@@ -0,0 +1,20 @@
+use crate::schema::community_category;
+use serde::{Deserialize, Serialize};
+
+/// A topic which the admins defined, like Technology or Gaming. Communities can be assigned to one
+/// category each.
+#[derive(Clone, Queryable, Identifiable, PartialEq, Debug, Serialize, Deserialize)]
+#[table_name = "community_category"]
+pub struct CommunityCategory {
+  pub id: i32,
+  pub name: String,
+  pub published: chrono::NaiveDateTime,
+  pub updated: Option<chrono::NaiveDateTime>,
+}
+
+#[derive(Insertable, AsChangeset, Clone)]
+#[table_name = "community_category"]
+pub struct CommunityCategoryForm {
+  pub name: String,
+  pub updated: Option<chrono::NaiveDateTime>,
+}
@@ -0,0 +1,52 @@
+use crate::{
+  newtypes::{CommunityId, DbUrl, PersonId},
+  schema::{community_wiki_page, community_wiki_page_revision},
+};
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Queryable, Identifiable, PartialEq, Debug, Serialize, Deserialize)]
+#[table_name = "community_wiki_page"]
+pub struct CommunityWikiPage {
+  pub id: i32,
+  pub community_id: CommunityId,
+  pub creator_id: PersonId,
+  pub title: String,
+  pub content: String,
+  pub deleted: bool,
+  pub published: chrono::NaiveDateTime,
+  pub updated: Option<chrono::NaiveDateTime>,
+  pub ap_id: DbUrl,
+  pub local: bool,
+}
+
+#[derive(Insertable, AsChangeset, Clone, Default)]
+#[table_name = "community_wiki_page"]
+pub struct CommunityWikiPageForm {
+  pub community_id: CommunityId,
+  pub creator_id: PersonId,
+  pub title: String,
+  pub content: String,
+  pub deleted: Option<bool>,
+  pub published: Option<chrono::NaiveDateTime>,
+  pub updated: Option<chrono::NaiveDateTime>,
+  pub ap_id: Option<DbUrl>,
+  pub local: Option<bool>,
+}
+
+#[derive(Clone, Queryable, Identifiable, PartialEq, Debug, Serialize, Deserialize)]
+#[table_name = "community_wiki_page_revision"]
+pub struct CommunityWikiPageRevision {
+  pub id: i32,
+  pub wiki_page_id: i32,
+  pub editor_id: PersonId,
+  pub content: String,
+  pub published: chrono::NaiveDateTime,
+}
+
+#[derive(Insertable)]
+#[table_name = "community_wiki_page_revision"]
+pub struct CommunityWikiPageRevisionForm {
+  pub wiki_page_id: i32,
+  pub editor_id: PersonId,
+  pub content: String,
+}
@@ -0,0 +1,25 @@
+use crate::{newtypes::DbUrl, schema::custom_emoji};
+use serde::{Deserialize, Serialize};
+
+/// An emoji which an admin added to the instance. It is used in text as `:shortcode:`.
+#[derive(Clone, Queryable, Identifiable, PartialEq, Debug, Serialize, Deserialize)]
+#[table_name = "custom_emoji"]
+pub struct CustomEmoji {
+  pub id: i32,
+  /// Without the surrounding colons
+  pub shortcode: String,
+  pub image_url: DbUrl,
+  /// Used to group the emoji in the picker
+  pub category: String,
+  pub published: chrono::NaiveDateTime,
+  pub updated: Option<chrono::NaiveDateTime>,
+}
+
+#[derive(Insertable, AsChangeset, Clone)]
+#[table_name = "custom_emoji"]
+pub struct CustomEmojiForm {
+  pub shortcode: String,
+  pub image_url: DbUrl,
+  pub category: String,
+  pub updated: Option<chrono::NaiveDateTime>,
+}
@@ -0,0 +1,19 @@
+use crate::{newtypes::LocalUserId, schema::external_identity};
+
+#[derive(Queryable, Identifiable, PartialEq, Debug, Clone)]
+#[table_name = "external_identity"]
+pub struct ExternalIdentity {
+  pub id: i32,
+  pub local_user_id: LocalUserId,
+  pub provider: String,
+  pub subject: String,
+  pub published: chrono::NaiveDateTime,
+}
+
+#[derive(Insertable)]
+#[table_name = "external_identity"]
+pub struct ExternalIdentityForm {
+  pub local_user_id: LocalUserId,
+  pub provider: String,
+  pub subject: String,
+}
@@ -0,0 +1,23 @@
+use crate::{newtypes::DbUrl, schema::federation_conflict};
+use serde::{Deserialize, Serialize};
+
+/// An incoming update which was ignored, because the local version of the object is newer
+#[derive(Clone, Queryable, Identifiable, PartialEq, Debug, Serialize, Deserialize)]
+#[table_name = "federation_conflict"]
+pub struct FederationConflict {
+  pub id: i32,
+  pub object_id: DbUrl,
+  pub activity_id: DbUrl,
+  pub local_updated: chrono::NaiveDateTime,
+  pub remote_updated: Option<chrono::NaiveDateTime>,
+  pub published: chrono::NaiveDateTime,
+}
+
+#[derive(Insertable, AsChangeset)]
+#[table_name = "federation_conflict"]
+pub struct FederationConflictForm {
+  pub object_id: DbUrl,
+  pub activity_id: DbUrl,
+  pub local_updated: chrono::NaiveDateTime,
+  pub remote_updated: Option<chrono::NaiveDateTime>,
+}
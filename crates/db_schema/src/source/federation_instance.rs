@@ -0,0 +1,27 @@
+use crate::schema::federation_instance;
+use serde::{Deserialize, Serialize};
+
+/// An instance which an admin added to the federation allowlist or blocklist
+#[derive(Clone, Queryable, Identifiable, PartialEq, Debug, Serialize, Deserialize)]
+#[table_name = "federation_instance"]
+pub struct FederationInstance {
+  pub id: i32,
+  pub domain: String,
+  /// If false, the instance is on the allowlist
+  pub blocked: bool,
+  pub published: chrono::NaiveDateTime,
+}
+
+#[derive(Insertable, AsChangeset)]
+#[table_name = "federation_instance"]
+pub struct FederationInstanceForm {
+  pub domain: String,
+  pub blocked: bool,
+}
+
+/// Domains from the federation_instance table
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct FederationInstanceLists {
+  pub allowed: Vec<String>,
+  pub blocked: Vec<String>,
+}
@@ -0,0 +1,20 @@
+use crate::{newtypes::LocalUserId, schema::feed_token};
+use serde::{Deserialize, Serialize};
+
+#[derive(Queryable, Identifiable, PartialEq, Debug, Clone, Serialize, Deserialize)]
+#[table_name = "feed_token"]
+pub struct FeedToken {
+  pub id: i32,
+  pub local_user_id: LocalUserId,
+  #[serde(skip)]
+  pub token_hash: String,
+  pub published: chrono::NaiveDateTime,
+}
+
+#[derive(Insertable, AsChangeset)]
+#[table_name = "feed_token"]
+pub struct FeedTokenForm {
+  pub local_user_id: LocalUserId,
+  pub token_hash: String,
+  pub published: chrono::NaiveDateTime,
+}
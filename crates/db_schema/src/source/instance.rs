@@ -0,0 +1,45 @@
+use crate::{newtypes::DbUrl, schema::instance};
+use serde::{Deserialize, Serialize};
+
+/// A federated instance, with the software and user counts from its nodeinfo, and the metadata
+/// from its instance actor
+#[derive(Clone, Queryable, Identifiable, PartialEq, Debug, Serialize, Deserialize)]
+#[table_name = "instance"]
+pub struct Instance {
+  pub id: i32,
+  pub domain: String,
+  pub software: Option<String>,
+  pub version: Option<String>,
+  pub users: Option<i64>,
+  pub users_active_month: Option<i64>,
+  pub published: chrono::NaiveDateTime,
+  /// When the nodeinfo was last fetched successfully
+  pub updated: Option<chrono::NaiveDateTime>,
+  pub name: Option<String>,
+  pub description: Option<String>,
+  pub icon: Option<DbUrl>,
+  /// True if the instance has sent us a block activity
+  pub blocks_us: bool,
+}
+
+#[derive(Insertable, AsChangeset)]
+#[table_name = "instance"]
+#[changeset_options(treat_none_as_null = "true")]
+pub struct InstanceForm {
+  pub domain: String,
+  pub software: Option<String>,
+  pub version: Option<String>,
+  pub users: Option<i64>,
+  pub users_active_month: Option<i64>,
+  pub updated: Option<chrono::NaiveDateTime>,
+}
+
+#[derive(Insertable, AsChangeset)]
+#[table_name = "instance"]
+#[changeset_options(treat_none_as_null = "true")]
+pub struct InstanceActorForm {
+  pub domain: String,
+  pub name: Option<String>,
+  pub description: Option<String>,
+  pub icon: Option<DbUrl>,
+}
@@ -0,0 +1,44 @@
+use crate::{
+  newtypes::{CommunityId, LanguageId, LocalUserId},
+  schema::{community_language, language, local_user_language},
+};
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Queryable, Identifiable, PartialEq, Debug, Serialize, Deserialize)]
+#[table_name = "language"]
+pub struct Language {
+  pub id: LanguageId,
+  /// ISO 639-1 code, or `und` for undetermined
+  pub code: String,
+  pub name: String,
+}
+
+#[derive(Clone, Queryable, Identifiable, PartialEq, Debug, Serialize, Deserialize)]
+#[table_name = "local_user_language"]
+pub struct LocalUserLanguage {
+  pub id: i32,
+  pub local_user_id: LocalUserId,
+  pub language_id: LanguageId,
+}
+
+#[derive(Insertable, AsChangeset, Clone)]
+#[table_name = "local_user_language"]
+pub struct LocalUserLanguageForm {
+  pub local_user_id: LocalUserId,
+  pub language_id: LanguageId,
+}
+
+#[derive(Clone, Queryable, Identifiable, PartialEq, Debug, Serialize, Deserialize)]
+#[table_name = "community_language"]
+pub struct CommunityLanguage {
+  pub id: i32,
+  pub community_id: CommunityId,
+  pub language_id: LanguageId,
+}
+
+#[derive(Insertable, AsChangeset, Clone)]
+#[table_name = "community_language"]
+pub struct CommunityLanguageForm {
+  pub community_id: CommunityId,
+  pub language_id: LanguageId,
+}
@@ -0,0 +1,27 @@
+use crate::{
+  newtypes::{CommentId, PersonId, PostId},
+  schema::legal_hold,
+};
+use serde::{Deserialize, Serialize};
+
+/// Exempts a post or comment from the automatic purge of deleted and removed content. Exactly one
+/// of `post_id` and `comment_id` is set.
+#[derive(Clone, Queryable, Identifiable, PartialEq, Debug, Serialize, Deserialize)]
+#[table_name = "legal_hold"]
+pub struct LegalHold {
+  pub id: i32,
+  pub post_id: Option<PostId>,
+  pub comment_id: Option<CommentId>,
+  pub admin_person_id: PersonId,
+  pub reason: String,
+  pub published: chrono::NaiveDateTime,
+}
+
+#[derive(Insertable, AsChangeset)]
+#[table_name = "legal_hold"]
+pub struct LegalHoldForm {
+  pub post_id: Option<PostId>,
+  pub comment_id: Option<CommentId>,
+  pub admin_person_id: PersonId,
+  pub reason: String,
+}
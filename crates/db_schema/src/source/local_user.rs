@@ -25,6 +25,13 @@ pub struct LocalUser {
   pub show_new_post_notifs: bool,
   pub email_verified: bool,
   pub accepted_application: bool,
+  pub totp_secret: Option<String>,
+  pub totp_enabled: bool,
+  pub email_digest_frequency: String,
+  pub email_digest_sent: Option<chrono::NaiveDateTime>,
+  pub totp_last_step: Option<i64>,
+  pub totp_failed_attempts: i32,
+  pub totp_last_failure: Option<chrono::NaiveDateTime>,
 }
 
 // TODO redo these, check table defaults
@@ -47,6 +54,9 @@ pub struct LocalUserForm {
   pub show_new_post_notifs: Option<bool>,
   pub email_verified: Option<bool>,
   pub accepted_application: Option<bool>,
+  pub totp_secret: Option<Option<String>>,
+  pub totp_enabled: Option<bool>,
+  pub email_digest_frequency: Option<String>,
 }
 
 /// A local user view that removes password encrypted
@@ -70,4 +80,6 @@ pub struct LocalUserSettings {
   pub show_new_post_notifs: bool,
   pub email_verified: bool,
   pub accepted_application: bool,
+  pub totp_enabled: bool,
+  pub email_digest_frequency: String,
 }
@@ -0,0 +1,21 @@
+use crate::{newtypes::LocalUserId, schema::login_session};
+use serde::{Deserialize, Serialize};
+
+#[derive(Queryable, Identifiable, PartialEq, Debug, Clone, Serialize, Deserialize)]
+#[table_name = "login_session"]
+pub struct LoginSession {
+  pub id: i32,
+  pub local_user_id: LocalUserId,
+  pub user_agent: Option<String>,
+  pub ip: Option<String>,
+  pub published: chrono::NaiveDateTime,
+  pub last_used: chrono::NaiveDateTime,
+}
+
+#[derive(Insertable)]
+#[table_name = "login_session"]
+pub struct LoginSessionForm {
+  pub local_user_id: LocalUserId,
+  pub user_agent: Option<String>,
+  pub ip: Option<String>,
+}
@@ -1,18 +1,51 @@
+pub mod account_export;
 pub mod activity;
+pub mod activity_send_queue;
+pub mod appeal;
+pub mod automod_rule;
+pub mod captcha_answer;
 pub mod comment;
 pub mod comment_report;
+pub mod comment_revision;
 pub mod community;
 pub mod community_block;
+pub mod community_category;
+pub mod community_wiki_page;
+pub mod custom_emoji;
 pub mod email_verification;
+pub mod external_identity;
+pub mod federation_conflict;
+pub mod federation_instance;
+pub mod feed_token;
+pub mod instance;
+pub mod language;
+pub mod legal_hold;
 pub mod local_user;
+pub mod login_session;
 pub mod moderator;
+pub mod multi_community;
+pub mod oauth_state;
 pub mod password_reset_request;
+pub mod pending_deletion;
 pub mod person;
 pub mod person_block;
+pub mod person_field;
+pub mod person_instance_block;
 pub mod person_mention;
+pub mod poll;
 pub mod post;
+pub mod post_attachment;
+pub mod post_draft;
 pub mod post_report;
+pub mod post_tag;
 pub mod private_message;
+pub mod private_message_thread;
+pub mod push_subscription;
+pub mod received_deletion;
 pub mod registration_application;
+pub mod saved_collection;
+pub mod scheduled_post;
 pub mod secret;
 pub mod site;
+pub mod site_word_filter;
+pub mod user_token;
@@ -1,14 +1,21 @@
 use crate::{
   newtypes::{CommentId, CommunityId, PersonId, PostId},
   schema::{
+    admin_purge_comment,
+    admin_purge_community,
+    admin_purge_person,
+    admin_purge_post,
     mod_add,
     mod_add_community,
+    mod_automod_action,
     mod_ban,
     mod_ban_from_community,
     mod_lock_post,
     mod_remove_comment,
     mod_remove_community,
     mod_remove_post,
+    mod_resolve_appeal,
+    mod_shadow_remove_post,
     mod_sticky_post,
     mod_transfer_community,
   },
@@ -20,10 +27,15 @@ use serde::{Deserialize, Serialize};
 pub struct ModRemovePost {
   pub id: i32,
   pub mod_person_id: PersonId,
-  pub post_id: PostId,
+  /// None if the target was permanently deleted
+  pub post_id: Option<PostId>,
   pub reason: Option<String>,
   pub removed: Option<bool>,
   pub when_: chrono::NaiveDateTime,
+  pub reason_translations: Option<serde_json::Value>,
+  pub reason_override: bool,
+  /// Whether the action is included in the federated modlog
+  pub public: bool,
 }
 
 #[derive(Insertable, AsChangeset)]
@@ -33,6 +45,9 @@ pub struct ModRemovePostForm {
   pub post_id: PostId,
   pub reason: Option<String>,
   pub removed: Option<bool>,
+  pub reason_translations: Option<serde_json::Value>,
+  pub reason_override: bool,
+  pub public: Option<bool>,
 }
 
 #[derive(Clone, Queryable, Identifiable, PartialEq, Debug, Serialize, Deserialize)]
@@ -53,6 +68,71 @@ pub struct ModLockPostForm {
   pub locked: Option<bool>,
 }
 
+#[derive(Clone, Queryable, Identifiable, PartialEq, Debug, Serialize, Deserialize)]
+#[table_name = "mod_shadow_remove_post"]
+pub struct ModShadowRemovePost {
+  pub id: i32,
+  pub mod_person_id: PersonId,
+  pub post_id: PostId,
+  pub reason: Option<String>,
+  pub shadow_removed: Option<bool>,
+  pub when_: chrono::NaiveDateTime,
+  pub reason_override: bool,
+}
+
+#[derive(Insertable, AsChangeset)]
+#[table_name = "mod_shadow_remove_post"]
+pub struct ModShadowRemovePostForm {
+  pub mod_person_id: PersonId,
+  pub post_id: PostId,
+  pub reason: Option<String>,
+  pub shadow_removed: Option<bool>,
+  pub reason_override: bool,
+}
+
+#[derive(Clone, Queryable, Identifiable, PartialEq, Debug, Serialize, Deserialize)]
+#[table_name = "mod_resolve_appeal"]
+pub struct ModResolveAppeal {
+  pub id: i32,
+  pub mod_person_id: PersonId,
+  pub appeal_id: i32,
+  pub granted: bool,
+  pub reason: Option<String>,
+  pub when_: chrono::NaiveDateTime,
+}
+
+#[derive(Insertable, AsChangeset)]
+#[table_name = "mod_resolve_appeal"]
+pub struct ModResolveAppealForm {
+  pub mod_person_id: PersonId,
+  pub appeal_id: i32,
+  pub granted: bool,
+  pub reason: Option<String>,
+}
+
+/// Logged when an automod rule is applied. The rule is unset once it gets deleted.
+#[derive(Clone, Queryable, Identifiable, PartialEq, Debug, Serialize, Deserialize)]
+#[table_name = "mod_automod_action"]
+pub struct ModAutomodAction {
+  pub id: i32,
+  pub automod_rule_id: Option<i32>,
+  pub community_id: CommunityId,
+  pub post_id: Option<PostId>,
+  pub comment_id: Option<CommentId>,
+  pub action: String,
+  pub when_: chrono::NaiveDateTime,
+}
+
+#[derive(Insertable, AsChangeset)]
+#[table_name = "mod_automod_action"]
+pub struct ModAutomodActionForm {
+  pub automod_rule_id: Option<i32>,
+  pub community_id: CommunityId,
+  pub post_id: Option<PostId>,
+  pub comment_id: Option<CommentId>,
+  pub action: String,
+}
+
 #[derive(Clone, Queryable, Identifiable, PartialEq, Debug, Serialize, Deserialize)]
 #[table_name = "mod_sticky_post"]
 pub struct ModStickyPost {
@@ -76,10 +156,15 @@ pub struct ModStickyPostForm {
 pub struct ModRemoveComment {
   pub id: i32,
   pub mod_person_id: PersonId,
-  pub comment_id: CommentId,
+  /// None if the target was permanently deleted
+  pub comment_id: Option<CommentId>,
   pub reason: Option<String>,
   pub removed: Option<bool>,
   pub when_: chrono::NaiveDateTime,
+  pub reason_translations: Option<serde_json::Value>,
+  pub reason_override: bool,
+  /// Whether the action is included in the federated modlog
+  pub public: bool,
 }
 
 #[derive(Insertable, AsChangeset)]
@@ -89,6 +174,9 @@ pub struct ModRemoveCommentForm {
   pub comment_id: CommentId,
   pub reason: Option<String>,
   pub removed: Option<bool>,
+  pub reason_translations: Option<serde_json::Value>,
+  pub reason_override: bool,
+  pub public: Option<bool>,
 }
 
 #[derive(Clone, Queryable, Identifiable, PartialEq, Debug, Serialize, Deserialize)]
@@ -96,11 +184,16 @@ pub struct ModRemoveCommentForm {
 pub struct ModRemoveCommunity {
   pub id: i32,
   pub mod_person_id: PersonId,
-  pub community_id: CommunityId,
+  /// None if the target was permanently deleted
+  pub community_id: Option<CommunityId>,
   pub reason: Option<String>,
   pub removed: Option<bool>,
   pub expires: Option<chrono::NaiveDateTime>,
   pub when_: chrono::NaiveDateTime,
+  pub reason_translations: Option<serde_json::Value>,
+  pub reason_override: bool,
+  /// Whether the action is included in the federated modlog
+  pub public: bool,
 }
 
 #[derive(Insertable, AsChangeset)]
@@ -110,7 +203,10 @@ pub struct ModRemoveCommunityForm {
   pub community_id: CommunityId,
   pub reason: Option<String>,
   pub removed: Option<bool>,
+  pub reason_translations: Option<serde_json::Value>,
   pub expires: Option<chrono::NaiveDateTime>,
+  pub reason_override: bool,
+  pub public: Option<bool>,
 }
 
 #[derive(Clone, Queryable, Identifiable, PartialEq, Debug, Serialize, Deserialize)]
@@ -124,6 +220,8 @@ pub struct ModBanFromCommunity {
   pub banned: Option<bool>,
   pub expires: Option<chrono::NaiveDateTime>,
   pub when_: chrono::NaiveDateTime,
+  /// Whether the action is included in the federated modlog
+  pub public: bool,
 }
 
 #[derive(Insertable, AsChangeset)]
@@ -135,6 +233,7 @@ pub struct ModBanFromCommunityForm {
   pub reason: Option<String>,
   pub banned: Option<bool>,
   pub expires: Option<chrono::NaiveDateTime>,
+  pub public: Option<bool>,
 }
 
 #[derive(Clone, Queryable, Identifiable, PartialEq, Debug, Serialize, Deserialize)]
@@ -216,3 +315,71 @@ pub struct ModAddForm {
   pub other_person_id: PersonId,
   pub removed: Option<bool>,
 }
+
+#[derive(Clone, Queryable, Identifiable, PartialEq, Debug, Serialize, Deserialize)]
+#[table_name = "admin_purge_person"]
+pub struct AdminPurgePerson {
+  pub id: i32,
+  pub admin_person_id: PersonId,
+  pub reason: Option<String>,
+  pub when_: chrono::NaiveDateTime,
+}
+
+#[derive(Insertable, AsChangeset)]
+#[table_name = "admin_purge_person"]
+pub struct AdminPurgePersonForm {
+  pub admin_person_id: PersonId,
+  pub reason: Option<String>,
+}
+
+#[derive(Clone, Queryable, Identifiable, PartialEq, Debug, Serialize, Deserialize)]
+#[table_name = "admin_purge_community"]
+pub struct AdminPurgeCommunity {
+  pub id: i32,
+  pub admin_person_id: PersonId,
+  pub reason: Option<String>,
+  pub when_: chrono::NaiveDateTime,
+}
+
+#[derive(Insertable, AsChangeset)]
+#[table_name = "admin_purge_community"]
+pub struct AdminPurgeCommunityForm {
+  pub admin_person_id: PersonId,
+  pub reason: Option<String>,
+}
+
+#[derive(Clone, Queryable, Identifiable, PartialEq, Debug, Serialize, Deserialize)]
+#[table_name = "admin_purge_post"]
+pub struct AdminPurgePost {
+  pub id: i32,
+  pub admin_person_id: PersonId,
+  pub community_id: CommunityId,
+  pub reason: Option<String>,
+  pub when_: chrono::NaiveDateTime,
+}
+
+#[derive(Insertable, AsChangeset)]
+#[table_name = "admin_purge_post"]
+pub struct AdminPurgePostForm {
+  pub admin_person_id: PersonId,
+  pub community_id: CommunityId,
+  pub reason: Option<String>,
+}
+
+#[derive(Clone, Queryable, Identifiable, PartialEq, Debug, Serialize, Deserialize)]
+#[table_name = "admin_purge_comment"]
+pub struct AdminPurgeComment {
+  pub id: i32,
+  pub admin_person_id: PersonId,
+  pub post_id: PostId,
+  pub reason: Option<String>,
+  pub when_: chrono::NaiveDateTime,
+}
+
+#[derive(Insertable, AsChangeset)]
+#[table_name = "admin_purge_comment"]
+pub struct AdminPurgeCommentForm {
+  pub admin_person_id: PersonId,
+  pub post_id: PostId,
+  pub reason: Option<String>,
+}
@@ -0,0 +1,30 @@
+use crate::{
+  newtypes::{CommunityId, MultiCommunityId, PersonId},
+  schema::{multi_community, multi_community_community},
+};
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Queryable, Identifiable, PartialEq, Debug, Serialize, Deserialize)]
+#[table_name = "multi_community"]
+pub struct MultiCommunity {
+  pub id: MultiCommunityId,
+  pub person_id: PersonId,
+  pub name: String,
+  pub published: chrono::NaiveDateTime,
+  pub updated: Option<chrono::NaiveDateTime>,
+}
+
+#[derive(Insertable, AsChangeset)]
+#[table_name = "multi_community"]
+pub struct MultiCommunityForm {
+  pub person_id: PersonId,
+  pub name: String,
+  pub updated: Option<chrono::NaiveDateTime>,
+}
+
+#[derive(Insertable)]
+#[table_name = "multi_community_community"]
+pub struct MultiCommunityCommunityForm {
+  pub multi_community_id: MultiCommunityId,
+  pub community_id: CommunityId,
+}
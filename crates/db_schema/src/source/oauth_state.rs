@@ -0,0 +1,24 @@
+use crate::{newtypes::LocalUserId, schema::oauth_state};
+
+#[derive(Queryable, Identifiable, PartialEq, Debug, Clone)]
+#[table_name = "oauth_state"]
+pub struct OAuthState {
+  pub id: i32,
+  pub state: String,
+  pub provider: String,
+  pub redirect_uri: String,
+  pub code_verifier: String,
+  /// Set if the provider account is linked to this user, instead of logging in
+  pub local_user_id: Option<LocalUserId>,
+  pub published: chrono::NaiveDateTime,
+}
+
+#[derive(Insertable)]
+#[table_name = "oauth_state"]
+pub struct OAuthStateForm {
+  pub state: String,
+  pub provider: String,
+  pub redirect_uri: String,
+  pub code_verifier: String,
+  pub local_user_id: Option<LocalUserId>,
+}
@@ -0,0 +1,36 @@
+use crate::{
+  newtypes::{CommunityId, DbUrl, PersonId},
+  schema::pending_deletion,
+};
+use serde::{Deserialize, Serialize};
+
+/// A deletion or removal of local content by a remote actor, which needs to be approved by a local
+/// mod before it is applied.
+#[derive(Clone, Queryable, Identifiable, PartialEq, Debug, Serialize, Deserialize)]
+#[table_name = "pending_deletion"]
+pub struct PendingDeletion {
+  pub id: i32,
+  pub actor_id: PersonId,
+  pub community_id: CommunityId,
+  pub activity_id: DbUrl,
+  /// The post, comment or community which is deleted
+  pub object_id: DbUrl,
+  /// True if it was removed by a mod, false if it was deleted by the creator
+  pub removal: bool,
+  pub reason: Option<String>,
+  pub published: chrono::NaiveDateTime,
+  /// True if the deletion or removal is undone, so that the object is restored
+  pub restore: bool,
+}
+
+#[derive(Insertable, AsChangeset)]
+#[table_name = "pending_deletion"]
+pub struct PendingDeletionForm {
+  pub actor_id: PersonId,
+  pub community_id: CommunityId,
+  pub activity_id: DbUrl,
+  pub object_id: DbUrl,
+  pub removal: bool,
+  pub reason: Option<String>,
+  pub restore: bool,
+}
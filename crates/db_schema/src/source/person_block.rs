@@ -13,6 +13,8 @@ pub struct PersonBlock {
   pub person_id: PersonId,
   pub target_id: PersonId,
   pub published: chrono::NaiveDateTime,
+  /// The blocked person also can't comment on posts and comments of the blocker
+  pub prevent_interaction: bool,
 }
 
 #[derive(Insertable, AsChangeset)]
@@ -20,4 +22,5 @@ pub struct PersonBlock {
 pub struct PersonBlockForm {
   pub person_id: PersonId,
   pub target_id: PersonId,
+  pub prevent_interaction: Option<bool>,
 }
@@ -0,0 +1,25 @@
+use crate::{newtypes::PersonId, schema::person_field};
+use serde::{Deserialize, Serialize};
+
+/// Maximum number of fields on a profile, the same as in Mastodon
+pub const MAX_PERSON_FIELDS: usize = 4;
+
+#[derive(Clone, Queryable, Identifiable, PartialEq, Debug, Serialize, Deserialize)]
+#[table_name = "person_field"]
+pub struct PersonField {
+  pub id: i32,
+  pub person_id: PersonId,
+  pub name: String,
+  /// Markdown, usually a link
+  pub value: String,
+  pub position: i32,
+}
+
+#[derive(Insertable, AsChangeset, Clone)]
+#[table_name = "person_field"]
+pub struct PersonFieldForm {
+  pub person_id: PersonId,
+  pub name: String,
+  pub value: String,
+  pub position: i32,
+}
@@ -0,0 +1,21 @@
+use crate::{
+  newtypes::{PersonId, PersonInstanceBlockId},
+  schema::person_instance_block,
+};
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Queryable, Identifiable, PartialEq, Debug, Serialize, Deserialize)]
+#[table_name = "person_instance_block"]
+pub struct PersonInstanceBlock {
+  pub id: PersonInstanceBlockId,
+  pub person_id: PersonId,
+  pub domain: String,
+  pub published: chrono::NaiveDateTime,
+}
+
+#[derive(Insertable, AsChangeset)]
+#[table_name = "person_instance_block"]
+pub struct PersonInstanceBlockForm {
+  pub person_id: PersonId,
+  pub domain: String,
+}
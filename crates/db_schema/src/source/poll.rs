@@ -0,0 +1,60 @@
+use crate::{
+  newtypes::{PersonId, PostId},
+  schema::{poll, poll_option, poll_vote},
+};
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Queryable, Identifiable, PartialEq, Debug, Serialize, Deserialize)]
+#[table_name = "poll"]
+pub struct Poll {
+  pub id: i32,
+  pub post_id: PostId,
+  pub multiple_choice: bool,
+  /// No more votes are accepted after this time
+  pub end_time: Option<chrono::NaiveDateTime>,
+  pub published: chrono::NaiveDateTime,
+}
+
+#[derive(Insertable, AsChangeset)]
+#[table_name = "poll"]
+#[changeset_options(treat_none_as_null = "true")]
+pub struct PollForm {
+  pub post_id: PostId,
+  pub multiple_choice: bool,
+  pub end_time: Option<chrono::NaiveDateTime>,
+}
+
+#[derive(Clone, Queryable, Identifiable, PartialEq, Debug, Serialize, Deserialize)]
+#[table_name = "poll_option"]
+pub struct PollOption {
+  pub id: i32,
+  pub poll_id: i32,
+  pub name: String,
+  pub position: i32,
+  pub votes: i32,
+}
+
+#[derive(Insertable, AsChangeset)]
+#[table_name = "poll_option"]
+pub struct PollOptionForm {
+  pub poll_id: i32,
+  pub name: String,
+  pub position: i32,
+  pub votes: Option<i32>,
+}
+
+#[derive(Clone, Queryable, Identifiable, PartialEq, Debug, Serialize, Deserialize)]
+#[table_name = "poll_vote"]
+pub struct PollVote {
+  pub id: i32,
+  pub poll_option_id: i32,
+  pub person_id: PersonId,
+  pub published: chrono::NaiveDateTime,
+}
+
+#[derive(Insertable)]
+#[table_name = "poll_vote"]
+pub struct PollVoteForm {
+  pub poll_option_id: i32,
+  pub person_id: PersonId,
+}
@@ -1,6 +1,6 @@
 use crate::{
-  newtypes::{CommunityId, DbUrl, PersonId, PostId},
-  schema::{post, post_like, post_read, post_saved},
+  newtypes::{CommunityId, DbUrl, LanguageId, PersonId, PostId, SavedCollectionId},
+  schema::{post, post_like, post_reaction, post_read, post_saved},
 };
 use serde::{Deserialize, Serialize};
 #[derive(Clone, Queryable, Identifiable, PartialEq, Debug, Serialize, Deserialize)]
@@ -25,6 +25,12 @@ pub struct Post {
   pub thumbnail_url: Option<DbUrl>,
   pub ap_id: DbUrl,
   pub local: bool,
+  pub shadow_removed: bool,
+  pub cross_post_of: Option<PostId>,
+  pub embed_video_url: Option<DbUrl>,
+  pub embed_provider_name: Option<String>,
+  pub language_id: LanguageId,
+  pub pending: bool,
 }
 
 #[derive(Insertable, AsChangeset, Default)]
@@ -48,6 +54,11 @@ pub struct PostForm {
   pub thumbnail_url: Option<DbUrl>,
   pub ap_id: Option<DbUrl>,
   pub local: Option<bool>,
+  pub cross_post_of: Option<PostId>,
+  pub embed_video_url: Option<DbUrl>,
+  pub embed_provider_name: Option<String>,
+  pub language_id: Option<LanguageId>,
+  pub pending: Option<bool>,
 }
 
 #[derive(Identifiable, Queryable, Associations, PartialEq, Debug)]
@@ -69,6 +80,25 @@ pub struct PostLikeForm {
   pub score: i16,
 }
 
+#[derive(Identifiable, Queryable, Associations, PartialEq, Debug)]
+#[belongs_to(Post)]
+#[table_name = "post_reaction"]
+pub struct PostReaction {
+  pub id: i32,
+  pub post_id: PostId,
+  pub person_id: PersonId,
+  pub emoji: String,
+  pub published: chrono::NaiveDateTime,
+}
+
+#[derive(Insertable, Clone)]
+#[table_name = "post_reaction"]
+pub struct PostReactionForm {
+  pub post_id: PostId,
+  pub person_id: PersonId,
+  pub emoji: String,
+}
+
 #[derive(Identifiable, Queryable, Associations, PartialEq, Debug)]
 #[belongs_to(Post)]
 #[table_name = "post_saved"]
@@ -77,6 +107,7 @@ pub struct PostSaved {
   pub post_id: PostId,
   pub person_id: PersonId,
   pub published: chrono::NaiveDateTime,
+  pub collection_id: Option<SavedCollectionId>,
 }
 
 #[derive(Insertable, AsChangeset)]
@@ -84,6 +115,7 @@ pub struct PostSaved {
 pub struct PostSavedForm {
   pub post_id: PostId,
   pub person_id: PersonId,
+  pub collection_id: Option<SavedCollectionId>,
 }
 
 #[derive(Identifiable, Queryable, Associations, PartialEq, Debug)]
@@ -0,0 +1,27 @@
+use crate::{
+  newtypes::{DbUrl, PostId},
+  schema::post_attachment,
+};
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Queryable, Identifiable, PartialEq, Debug, Serialize, Deserialize)]
+#[table_name = "post_attachment"]
+pub struct PostAttachment {
+  pub id: i32,
+  pub post_id: PostId,
+  pub url: DbUrl,
+  pub media_type: Option<String>,
+  /// Title or alt text
+  pub name: Option<String>,
+  pub position: i32,
+}
+
+#[derive(Insertable, AsChangeset, Clone)]
+#[table_name = "post_attachment"]
+pub struct PostAttachmentForm {
+  pub post_id: PostId,
+  pub url: DbUrl,
+  pub media_type: Option<String>,
+  pub name: Option<String>,
+  pub position: i32,
+}
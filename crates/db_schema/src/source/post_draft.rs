@@ -0,0 +1,33 @@
+use crate::{
+  newtypes::{CommunityId, DbUrl, PersonId},
+  schema::post_draft,
+};
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Queryable, Identifiable, PartialEq, Debug, Serialize, Deserialize)]
+#[table_name = "post_draft"]
+pub struct PostDraft {
+  pub id: i32,
+  pub creator_id: PersonId,
+  pub community_id: Option<CommunityId>,
+  pub name: Option<String>,
+  pub url: Option<DbUrl>,
+  pub body: Option<String>,
+  pub nsfw: bool,
+  pub published: chrono::NaiveDateTime,
+  pub updated: Option<chrono::NaiveDateTime>,
+}
+
+/// A draft is always saved as a whole, so empty fields also clear the stored value
+#[derive(Insertable, AsChangeset)]
+#[table_name = "post_draft"]
+#[changeset_options(treat_none_as_null = "true")]
+pub struct PostDraftForm {
+  pub creator_id: PersonId,
+  pub community_id: Option<CommunityId>,
+  pub name: Option<String>,
+  pub url: Option<DbUrl>,
+  pub body: Option<String>,
+  pub nsfw: bool,
+  pub updated: Option<chrono::NaiveDateTime>,
+}
@@ -22,6 +22,9 @@ pub struct PostReport {
   pub resolver_id: Option<PersonId>,
   pub published: chrono::NaiveDateTime,
   pub updated: Option<chrono::NaiveDateTime>,
+  pub resolution_note: Option<String>,
+  /// Whether a mod passed the report on to the admins
+  pub escalated: bool,
 }
 
 #[derive(Insertable, AsChangeset, Clone)]
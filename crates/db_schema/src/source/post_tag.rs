@@ -0,0 +1,39 @@
+use crate::{
+  newtypes::{CommunityId, PostId},
+  schema::{post_tag, post_tag_assignment},
+};
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Queryable, Identifiable, PartialEq, Debug, Serialize, Deserialize)]
+#[table_name = "post_tag"]
+pub struct PostTag {
+  pub id: i32,
+  pub community_id: CommunityId,
+  pub name: String,
+  pub published: chrono::NaiveDateTime,
+  pub updated: Option<chrono::NaiveDateTime>,
+}
+
+#[derive(Insertable, AsChangeset, Clone)]
+#[table_name = "post_tag"]
+pub struct PostTagForm {
+  pub community_id: CommunityId,
+  pub name: String,
+  pub updated: Option<chrono::NaiveDateTime>,
+}
+
+#[derive(Clone, Queryable, Identifiable, PartialEq, Debug, Serialize, Deserialize)]
+#[table_name = "post_tag_assignment"]
+pub struct PostTagAssignment {
+  pub id: i32,
+  pub post_id: PostId,
+  pub tag_id: i32,
+  pub published: chrono::NaiveDateTime,
+}
+
+#[derive(Insertable, AsChangeset, Clone)]
+#[table_name = "post_tag_assignment"]
+pub struct PostTagAssignmentForm {
+  pub post_id: PostId,
+  pub tag_id: i32,
+}
@@ -19,6 +19,8 @@ pub struct PrivateMessage {
   pub updated: Option<chrono::NaiveDateTime>,
   pub ap_id: DbUrl,
   pub local: bool,
+  /// Assigned by the database when the message is inserted
+  pub thread_id: i32,
 }
 
 #[derive(Insertable, AsChangeset, Default)]
@@ -0,0 +1,13 @@
+use crate::{newtypes::PersonId, schema::private_message_thread};
+use serde::{Deserialize, Serialize};
+
+/// Threads are created by the database when the first message between two persons is inserted
+#[derive(Clone, Queryable, Identifiable, PartialEq, Debug, Serialize, Deserialize)]
+#[table_name = "private_message_thread"]
+pub struct PrivateMessageThread {
+  pub id: i32,
+  pub person_a_id: PersonId,
+  pub person_b_id: PersonId,
+  pub published: chrono::NaiveDateTime,
+  pub newest_message_time: chrono::NaiveDateTime,
+}
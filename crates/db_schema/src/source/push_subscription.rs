@@ -0,0 +1,21 @@
+use crate::{newtypes::LocalUserId, schema::push_subscription};
+
+#[derive(Clone, Queryable, Identifiable, PartialEq, Debug)]
+#[table_name = "push_subscription"]
+pub struct PushSubscription {
+  pub id: i32,
+  pub local_user_id: LocalUserId,
+  pub endpoint: String,
+  pub p256dh: String,
+  pub auth: String,
+  pub published: chrono::NaiveDateTime,
+}
+
+#[derive(Insertable, AsChangeset)]
+#[table_name = "push_subscription"]
+pub struct PushSubscriptionForm {
+  pub local_user_id: LocalUserId,
+  pub endpoint: String,
+  pub p256dh: String,
+  pub auth: String,
+}
@@ -0,0 +1,31 @@
+use crate::{newtypes::DbUrl, schema::received_deletion};
+use serde::{Deserialize, Serialize};
+
+/// A deletion or removal which was received from another instance, and whether it passed
+/// verification.
+#[derive(Clone, Queryable, Identifiable, PartialEq, Debug, Serialize, Deserialize)]
+#[table_name = "received_deletion"]
+pub struct ReceivedDeletion {
+  pub id: i32,
+  pub actor_id: DbUrl,
+  pub activity_id: DbUrl,
+  /// One of Delete, UndoDelete, Remove or UndoRemove
+  pub kind: String,
+  /// The object which is deleted or restored
+  pub object_id: DbUrl,
+  pub accepted: bool,
+  pub published: chrono::NaiveDateTime,
+  /// Passed verification, but wasn't applied because it is waiting for review by local mods, or
+  /// was discarded for lack of attestation
+  pub held: bool,
+}
+
+#[derive(Insertable, AsChangeset)]
+#[table_name = "received_deletion"]
+pub struct ReceivedDeletionForm {
+  pub actor_id: DbUrl,
+  pub activity_id: DbUrl,
+  pub kind: String,
+  pub object_id: DbUrl,
+  pub accepted: bool,
+}
@@ -0,0 +1,23 @@
+use crate::{
+  newtypes::{PersonId, SavedCollectionId},
+  schema::saved_collection,
+};
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Queryable, Identifiable, PartialEq, Debug, Serialize, Deserialize)]
+#[table_name = "saved_collection"]
+pub struct SavedCollection {
+  pub id: SavedCollectionId,
+  pub person_id: PersonId,
+  pub name: String,
+  pub published: chrono::NaiveDateTime,
+  pub updated: Option<chrono::NaiveDateTime>,
+}
+
+#[derive(Insertable, AsChangeset)]
+#[table_name = "saved_collection"]
+pub struct SavedCollectionForm {
+  pub person_id: PersonId,
+  pub name: String,
+  pub updated: Option<chrono::NaiveDateTime>,
+}
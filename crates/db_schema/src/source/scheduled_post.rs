@@ -0,0 +1,35 @@
+use crate::{
+  newtypes::{CommunityId, DbUrl, LanguageId, PersonId},
+  schema::scheduled_post,
+};
+use serde::{Deserialize, Serialize};
+
+/// A post which was created with a publish time in the future. It only becomes a real post once
+/// that time has come.
+#[derive(Clone, Queryable, Identifiable, PartialEq, Debug, Serialize, Deserialize)]
+#[table_name = "scheduled_post"]
+pub struct ScheduledPost {
+  pub id: i32,
+  pub creator_id: PersonId,
+  pub community_id: CommunityId,
+  pub name: String,
+  pub url: Option<DbUrl>,
+  pub body: Option<String>,
+  pub nsfw: bool,
+  pub publish_at: chrono::NaiveDateTime,
+  pub published: chrono::NaiveDateTime,
+  pub language_id: LanguageId,
+}
+
+#[derive(Insertable, AsChangeset)]
+#[table_name = "scheduled_post"]
+pub struct ScheduledPostForm {
+  pub creator_id: PersonId,
+  pub community_id: CommunityId,
+  pub name: String,
+  pub url: Option<DbUrl>,
+  pub body: Option<String>,
+  pub nsfw: Option<bool>,
+  pub publish_at: chrono::NaiveDateTime,
+  pub language_id: Option<LanguageId>,
+}
@@ -5,4 +5,7 @@ use crate::schema::secret;
 pub struct Secret {
   pub id: i32,
   pub jwt_secret: String,
+  /// Keypair of the instance actor, generated on startup if missing
+  pub instance_private_key: Option<String>,
+  pub instance_public_key: Option<String>,
 }
@@ -24,6 +24,10 @@ pub struct Site {
   pub require_application: bool,
   pub application_question: Option<String>,
   pub private_instance: bool,
+  /// One of builtin, hcaptcha or recaptcha
+  pub captcha_provider: String,
+  /// Public key of the hCaptcha or reCAPTCHA widget
+  pub captcha_site_key: Option<String>,
 }
 
 #[derive(Insertable, AsChangeset, Default)]
@@ -45,4 +49,6 @@ pub struct SiteForm {
   pub require_application: Option<bool>,
   pub application_question: Option<Option<String>>,
   pub private_instance: Option<bool>,
+  pub captcha_provider: Option<String>,
+  pub captcha_site_key: Option<Option<String>>,
 }
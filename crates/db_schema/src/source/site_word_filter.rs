@@ -0,0 +1,20 @@
+use crate::schema::site_word_filter;
+use serde::{Deserialize, Serialize};
+
+/// A regex pattern which an admin added to the instance-wide word filter
+#[derive(Clone, Queryable, Identifiable, PartialEq, Debug, Serialize, Deserialize)]
+#[table_name = "site_word_filter"]
+pub struct SiteWordFilter {
+  pub id: i32,
+  pub pattern: String,
+  /// One of [`crate::WordFilterAction`]
+  pub action: String,
+  pub published: chrono::NaiveDateTime,
+}
+
+#[derive(Insertable, AsChangeset)]
+#[table_name = "site_word_filter"]
+pub struct SiteWordFilterForm {
+  pub pattern: String,
+  pub action: String,
+}
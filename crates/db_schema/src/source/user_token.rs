@@ -0,0 +1,25 @@
+use crate::{newtypes::LocalUserId, schema::user_token};
+use serde::{Deserialize, Serialize};
+
+#[derive(Queryable, Identifiable, PartialEq, Debug, Clone, Serialize, Deserialize)]
+#[table_name = "user_token"]
+pub struct UserToken {
+  pub id: i32,
+  pub local_user_id: LocalUserId,
+  pub name: String,
+  #[serde(skip)]
+  pub token_hash: String,
+  /// Each one of [`crate::TokenScope`]
+  pub scopes: Vec<String>,
+  pub published: chrono::NaiveDateTime,
+  pub last_used: Option<chrono::NaiveDateTime>,
+}
+
+#[derive(Insertable)]
+#[table_name = "user_token"]
+pub struct UserTokenForm {
+  pub local_user_id: LocalUserId,
+  pub name: String,
+  pub token_hash: String,
+  pub scopes: Vec<String>,
+}
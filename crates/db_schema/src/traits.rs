@@ -1,4 +1,7 @@
-use crate::newtypes::{CommunityId, PersonId};
+use crate::{
+  aggregates::reaction_count::ReactionCount,
+  newtypes::{CommunityId, PersonId},
+};
 use diesel::{result::Error, PgConnection};
 
 pub trait Crud {
@@ -64,6 +67,30 @@ pub trait Likeable {
     Self: Sized;
 }
 
+pub trait Reactable {
+  type Form;
+  type IdType;
+  /// Does nothing if the person already reacted with the same emoji
+  fn react(conn: &PgConnection, form: &Self::Form) -> Result<usize, Error>
+  where
+    Self: Sized;
+  fn unreact(
+    conn: &PgConnection,
+    person_id: PersonId,
+    item_id: Self::IdType,
+    emoji: &str,
+  ) -> Result<usize, Error>
+  where
+    Self: Sized;
+  /// Counts the reactions of the given items, per emoji and with the most used first
+  fn counts(
+    conn: &PgConnection,
+    item_ids: Vec<Self::IdType>,
+  ) -> Result<Vec<(Self::IdType, ReactionCount)>, Error>
+  where
+    Self: Sized;
+}
+
 pub trait Bannable {
   type Form;
   fn ban(conn: &PgConnection, form: &Self::Form) -> Result<Self, Error>
@@ -114,6 +141,7 @@ pub trait Reportable {
     conn: &PgConnection,
     report_id: Self::IdType,
     resolver_id: PersonId,
+    resolution_note: Option<String>,
   ) -> Result<usize, Error>
   where
     Self: Sized;
@@ -124,6 +152,9 @@ pub trait Reportable {
   ) -> Result<usize, Error>
   where
     Self: Sized;
+  fn escalate(conn: &PgConnection, report_id: Self::IdType) -> Result<usize, Error>
+  where
+    Self: Sized;
 }
 
 pub trait DeleteableOrRemoveable {
@@ -0,0 +1,212 @@
+use crate::local_user_view::LocalUserSettingsView;
+use diesel::{result::Error, *};
+use lemmy_db_schema::{
+  newtypes::{DbUrl, LocalUserId, PersonId},
+  schema::{
+    comment,
+    comment_like,
+    comment_saved,
+    community,
+    community_block,
+    community_follower,
+    person,
+    person_block,
+    post,
+    post_like,
+    post_saved,
+  },
+  source::{comment::Comment, post::Post},
+};
+use serde::{Deserialize, Serialize};
+
+/// All data of a local user, in a form which can be imported on another instance. Other objects
+/// are referenced by their activitypub id.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct AccountArchiveView {
+  pub local_user_view: LocalUserSettingsView,
+  pub posts: Vec<Post>,
+  pub comments: Vec<Comment>,
+  pub post_votes: Vec<ArchivedVote>,
+  pub comment_votes: Vec<ArchivedVote>,
+  pub followed_communities: Vec<DbUrl>,
+  pub saved_posts: Vec<DbUrl>,
+  pub saved_comments: Vec<DbUrl>,
+  pub blocked_communities: Vec<DbUrl>,
+  pub blocked_persons: Vec<DbUrl>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct ArchivedVote {
+  pub object_id: DbUrl,
+  pub score: i16,
+}
+
+impl AccountArchiveView {
+  pub fn read(conn: &PgConnection, local_user_id: LocalUserId) -> Result<Self, Error> {
+    let local_user_view = LocalUserSettingsView::read(conn, local_user_id)?;
+    let person_id: PersonId = local_user_view.person.id;
+
+    let posts = post::table
+      .filter(post::creator_id.eq(person_id))
+      .order_by(post::published.asc())
+      .load::<Post>(conn)?;
+    let comments = comment::table
+      .filter(comment::creator_id.eq(person_id))
+      .order_by(comment::published.asc())
+      .load::<Comment>(conn)?;
+
+    let post_votes = post_like::table
+      .inner_join(post::table)
+      .filter(post_like::person_id.eq(person_id))
+      .select((post::ap_id, post_like::score))
+      .load::<(DbUrl, i16)>(conn)?;
+    let comment_votes = comment_like::table
+      .inner_join(comment::table)
+      .filter(comment_like::person_id.eq(person_id))
+      .select((comment::ap_id, comment_like::score))
+      .load::<(DbUrl, i16)>(conn)?;
+
+    let followed_communities = community_follower::table
+      .inner_join(community::table)
+      .filter(community_follower::person_id.eq(person_id))
+      .select(community::actor_id)
+      .load::<DbUrl>(conn)?;
+    let saved_posts = post_saved::table
+      .inner_join(post::table)
+      .filter(post_saved::person_id.eq(person_id))
+      .select(post::ap_id)
+      .load::<DbUrl>(conn)?;
+    let saved_comments = comment_saved::table
+      .inner_join(comment::table)
+      .filter(comment_saved::person_id.eq(person_id))
+      .select(comment::ap_id)
+      .load::<DbUrl>(conn)?;
+    let blocked_communities = community_block::table
+      .inner_join(community::table)
+      .filter(community_block::person_id.eq(person_id))
+      .select(community::actor_id)
+      .load::<DbUrl>(conn)?;
+    let blocked_persons = person_block::table
+      .inner_join(person::table.on(person_block::target_id.eq(person::id)))
+      .filter(person_block::person_id.eq(person_id))
+      .select(person::actor_id)
+      .load::<DbUrl>(conn)?;
+
+    let into_votes = |votes: Vec<(DbUrl, i16)>| {
+      votes
+        .into_iter()
+        .map(|(object_id, score)| ArchivedVote { object_id, score })
+        .collect()
+    };
+    Ok(AccountArchiveView {
+      local_user_view,
+      posts,
+      comments,
+      post_votes: into_votes(post_votes),
+      comment_votes: into_votes(comment_votes),
+      followed_communities,
+      saved_posts,
+      saved_comments,
+      blocked_communities,
+      blocked_persons,
+    })
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::account_archive_view::{AccountArchiveView, ArchivedVote};
+  use lemmy_db_schema::{
+    establish_unpooled_connection,
+    source::{community::*, local_user::*, person::*, person_block::*, post::*},
+    traits::{Blockable, Crud, Followable, Likeable, Saveable},
+  };
+  use serial_test::serial;
+
+  #[test]
+  #[serial]
+  fn test_account_archive() {
+    let conn = establish_unpooled_connection();
+
+    let new_person = PersonForm {
+      name: "archive_person".into(),
+      ..PersonForm::default()
+    };
+    let inserted_person = Person::create(&conn, &new_person).unwrap();
+    let new_local_user = LocalUserForm {
+      person_id: Some(inserted_person.id),
+      password_encrypted: Some("archive_pass".to_string()),
+      ..LocalUserForm::default()
+    };
+    let inserted_local_user = LocalUser::create(&conn, &new_local_user).unwrap();
+    let other_person_form = PersonForm {
+      name: "archive_blocked".into(),
+      ..PersonForm::default()
+    };
+    let other_person = Person::create(&conn, &other_person_form).unwrap();
+
+    let new_community = CommunityForm {
+      name: "archive_community".to_string(),
+      title: "nada".to_owned(),
+      ..CommunityForm::default()
+    };
+    let inserted_community = Community::create(&conn, &new_community).unwrap();
+
+    let new_post = PostForm {
+      name: "An archived post".into(),
+      creator_id: inserted_person.id,
+      community_id: inserted_community.id,
+      ..PostForm::default()
+    };
+    let inserted_post = Post::create(&conn, &new_post).unwrap();
+
+    let follow_form = CommunityFollowerForm {
+      community_id: inserted_community.id,
+      person_id: inserted_person.id,
+      pending: false,
+    };
+    CommunityFollower::follow(&conn, &follow_form).unwrap();
+    let like_form = PostLikeForm {
+      post_id: inserted_post.id,
+      person_id: inserted_person.id,
+      score: 1,
+    };
+    PostLike::like(&conn, &like_form).unwrap();
+    let saved_form = PostSavedForm {
+      post_id: inserted_post.id,
+      person_id: inserted_person.id,
+      collection_id: None,
+    };
+    PostSaved::save(&conn, &saved_form).unwrap();
+    let block_form = PersonBlockForm {
+      person_id: inserted_person.id,
+      target_id: other_person.id,
+      prevent_interaction: None,
+    };
+    PersonBlock::block(&conn, &block_form).unwrap();
+
+    let archive = AccountArchiveView::read(&conn, inserted_local_user.id).unwrap();
+
+    Community::delete(&conn, inserted_community.id).unwrap();
+    Person::delete(&conn, inserted_person.id).unwrap();
+    Person::delete(&conn, other_person.id).unwrap();
+
+    assert_eq!(inserted_person.id, archive.local_user_view.person.id);
+    assert_eq!(vec![inserted_post.clone()], archive.posts);
+    assert!(archive.comments.is_empty());
+    assert_eq!(
+      vec![ArchivedVote {
+        object_id: inserted_post.ap_id.clone(),
+        score: 1
+      }],
+      archive.post_votes
+    );
+    assert_eq!(
+      vec![inserted_community.actor_id],
+      archive.followed_communities
+    );
+    assert_eq!(vec![inserted_post.ap_id], archive.saved_posts);
+    assert_eq!(vec![other_person.actor_id], archive.blocked_persons);
+    assert!(archive.blocked_communities.is_empty());
+  }
+}
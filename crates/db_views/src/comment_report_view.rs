@@ -170,6 +170,16 @@ impl CommentReportView {
       query.select(count(comment_report::id)).first::<i64>(conn)
     }
   }
+
+  /// Returns the count of unresolved comment reports which mods escalated to the admins
+  pub fn get_escalated_report_count(conn: &PgConnection) -> Result<i64, Error> {
+    use diesel::dsl::*;
+    comment_report::table
+      .filter(comment_report::resolved.eq(false))
+      .filter(comment_report::escalated.eq(true))
+      .select(count(comment_report::id))
+      .first::<i64>(conn)
+  }
 }
 
 pub struct CommentReportQueryBuilder<'a> {
@@ -180,6 +190,7 @@ pub struct CommentReportQueryBuilder<'a> {
   page: Option<i64>,
   limit: Option<i64>,
   unresolved_only: Option<bool>,
+  escalated_only: Option<bool>,
 }
 
 impl<'a> CommentReportQueryBuilder<'a> {
@@ -192,6 +203,7 @@ impl<'a> CommentReportQueryBuilder<'a> {
       page: None,
       limit: None,
       unresolved_only: Some(true),
+      escalated_only: None,
     }
   }
 
@@ -215,6 +227,11 @@ impl<'a> CommentReportQueryBuilder<'a> {
     self
   }
 
+  pub fn escalated_only<T: MaybeOptional<bool>>(mut self, escalated_only: T) -> Self {
+    self.escalated_only = escalated_only.get_optional();
+    self
+  }
+
   pub fn list(self) -> Result<Vec<CommentReportView>, Error> {
     let mut query = comment_report::table
       .inner_join(comment::table)
@@ -264,6 +281,10 @@ impl<'a> CommentReportQueryBuilder<'a> {
       query = query.filter(comment_report::resolved.eq(false));
     }
 
+    if self.escalated_only.unwrap_or(false) {
+      query = query.filter(comment_report::escalated.eq(true));
+    }
+
     let (limit, offset) = limit_and_offset(self.page, self.limit);
 
     query = query
@@ -424,6 +445,8 @@ mod tests {
         description: None,
         updated: None,
         banner: None,
+        require_post_approval: false,
+        category_id: None,
         published: inserted_community.published,
       },
       creator: PersonSafe {
@@ -472,6 +495,8 @@ mod tests {
         upvotes: 0,
         downvotes: 0,
         published: agg.published,
+        child_count: 0,
+        hot_rank: 1728,
       },
       my_vote: None,
       resolver: None,
@@ -519,8 +544,30 @@ mod tests {
       CommentReportView::get_report_count(&conn, inserted_timmy.id, false, None).unwrap();
     assert_eq!(2, report_count);
 
+    // Escalate jessicas report to the admins
+    CommentReport::escalate(&conn, inserted_jessica_report.id).unwrap();
+    let escalated_reports = CommentReportQueryBuilder::create(&conn, inserted_timmy.id, false)
+      .escalated_only(true)
+      .list()
+      .unwrap();
+    assert_eq!(1, escalated_reports.len());
+    assert_eq!(
+      inserted_jessica_report.id,
+      escalated_reports[0].comment_report.id
+    );
+    assert_eq!(
+      1,
+      CommentReportView::get_escalated_report_count(&conn).unwrap()
+    );
+
     // Try to resolve the report
-    CommentReport::resolve(&conn, inserted_jessica_report.id, inserted_timmy.id).unwrap();
+    CommentReport::resolve(
+      &conn,
+      inserted_jessica_report.id,
+      inserted_timmy.id,
+      Some("spam".into()),
+    )
+    .unwrap();
     let read_jessica_report_view_after_resolve =
       CommentReportView::read(&conn, inserted_jessica_report.id, inserted_timmy.id).unwrap();
 
@@ -528,9 +575,15 @@ mod tests {
     expected_jessica_report_view_after_resolve
       .comment_report
       .resolved = true;
+    expected_jessica_report_view_after_resolve
+      .comment_report
+      .escalated = true;
     expected_jessica_report_view_after_resolve
       .comment_report
       .resolver_id = Some(inserted_timmy.id);
+    expected_jessica_report_view_after_resolve
+      .comment_report
+      .resolution_note = Some("spam".into());
     expected_jessica_report_view_after_resolve
       .comment_report
       .updated = read_jessica_report_view_after_resolve
@@ -572,6 +625,19 @@ mod tests {
     let report_count_after_resolved =
       CommentReportView::get_report_count(&conn, inserted_timmy.id, false, None).unwrap();
     assert_eq!(1, report_count_after_resolved);
+    assert_eq!(
+      0,
+      CommentReportView::get_escalated_report_count(&conn).unwrap()
+    );
+
+    // Resolve the remaining report along with all others for the comment
+    let num_resolved =
+      CommentReport::resolve_all_for_comment(&conn, inserted_comment.id, inserted_timmy.id, None)
+        .unwrap();
+    assert_eq!(1, num_resolved);
+    let report_count_after_bulk_resolve =
+      CommentReportView::get_report_count(&conn, inserted_timmy.id, false, None).unwrap();
+    assert_eq!(0, report_count_after_bulk_resolve);
 
     Person::delete(&conn, inserted_timmy.id).unwrap();
     Person::delete(&conn, inserted_sara.id).unwrap();
@@ -1,10 +1,17 @@
 use diesel::{result::Error, *};
 use lemmy_db_schema::{
-  aggregates::comment_aggregates::CommentAggregates,
-  functions::hot_rank,
-  fuzzy_search,
+  aggregates::{comment_aggregates::CommentAggregates, reaction_count::ReactionCount},
+  functions::{
+    comment_path_depth,
+    controversy_rank,
+    search_tsquery,
+    search_tsquery_for_language,
+    search_vector,
+    ts_rank_cd,
+    Matches,
+  },
   limit_and_offset,
-  newtypes::{CommentId, CommunityId, DbUrl, PersonId, PostId},
+  newtypes::{CommentId, CommunityId, DbUrl, LanguageId, PersonId, PostId, SavedCollectionId},
   schema::{
     comment,
     comment_aggregates,
@@ -14,6 +21,7 @@ use lemmy_db_schema::{
     community,
     community_block,
     community_follower,
+    community_language,
     community_person_ban,
     person,
     person_alias_1,
@@ -21,17 +29,20 @@ use lemmy_db_schema::{
     post,
   },
   source::{
-    comment::{Comment, CommentAlias1, CommentSaved},
+    comment::{Comment, CommentAlias1, CommentReaction, CommentSaved},
     community::{Community, CommunityFollower, CommunityPersonBan, CommunitySafe},
+    local_user::LocalUser,
     person::{Person, PersonAlias1, PersonSafe, PersonSafeAlias1},
     person_block::PersonBlock,
+    person_instance_block::PersonInstanceBlock,
     post::Post,
   },
-  traits::{MaybeOptional, ToSafe, ViewToVec},
+  traits::{MaybeOptional, Reactable, ToSafe, ViewToVec},
   ListingType,
   SortType,
 };
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
 #[derive(Debug, PartialEq, Serialize, Deserialize, Clone)]
 pub struct CommentView {
@@ -46,6 +57,9 @@ pub struct CommentView {
   pub saved: bool,                         // Left join to CommentSaved
   pub creator_blocked: bool,               // Left join to PersonBlock
   pub my_vote: Option<i16>,                // Left join to CommentLike
+  pub reactions: Vec<ReactionCount>,
+  /// Excerpt with the matches highlighted, only for search results
+  pub snippet: Option<String>,
 }
 
 type CommentViewTuple = (
@@ -153,7 +167,7 @@ impl CommentView {
       comment_like
     };
 
-    Ok(CommentView {
+    let mut comment_view = CommentView {
       comment,
       recipient,
       post,
@@ -165,7 +179,18 @@ impl CommentView {
       saved: saved.is_some(),
       creator_blocked: creator_blocked.is_some(),
       my_vote,
-    })
+      reactions: CommentReaction::counts(conn, vec![comment_id])?
+        .into_iter()
+        .map(|(_, r)| r)
+        .collect(),
+      snippet: None,
+    };
+    if let Some(person_id) = my_person_id {
+      if !LocalUser::show_scores(conn, person_id)? {
+        comment_view.hide_scores();
+      }
+    }
+    Ok(comment_view)
   }
 
   /// Gets the recipient person id.
@@ -216,6 +241,13 @@ impl CommentView {
       .select(count(comment::id))
       .first::<i64>(conn)
   }
+
+  /// Blanks out the votes, for users who don't want to see them
+  pub fn hide_scores(&mut self) {
+    self.counts.score = 0;
+    self.counts.upvotes = 0;
+    self.counts.downvotes = 0;
+  }
 }
 
 pub struct CommentQueryBuilder<'a> {
@@ -224,14 +256,22 @@ pub struct CommentQueryBuilder<'a> {
   sort: Option<SortType>,
   community_id: Option<CommunityId>,
   community_actor_id: Option<DbUrl>,
+  category_id: Option<i32>,
   post_id: Option<PostId>,
+  parent_id: Option<CommentId>,
+  max_depth: Option<i32>,
   creator_id: Option<PersonId>,
   recipient_id: Option<PersonId>,
   my_person_id: Option<PersonId>,
   search_term: Option<String>,
   saved_only: Option<bool>,
+  saved_collection_id: Option<SavedCollectionId>,
   unread_only: Option<bool>,
   show_bot_accounts: Option<bool>,
+  show_scores: Option<bool>,
+  languages: Option<Vec<LanguageId>>,
+  published_after: Option<chrono::NaiveDateTime>,
+  published_before: Option<chrono::NaiveDateTime>,
   page: Option<i64>,
   limit: Option<i64>,
 }
@@ -244,14 +284,22 @@ impl<'a> CommentQueryBuilder<'a> {
       sort: None,
       community_id: None,
       community_actor_id: None,
+      category_id: None,
       post_id: None,
+      parent_id: None,
+      max_depth: None,
       creator_id: None,
       recipient_id: None,
       my_person_id: None,
       search_term: None,
       saved_only: None,
+      saved_collection_id: None,
       unread_only: None,
       show_bot_accounts: None,
+      show_scores: None,
+      languages: None,
+      published_after: None,
+      published_before: None,
       page: None,
       limit: None,
     }
@@ -272,6 +320,21 @@ impl<'a> CommentQueryBuilder<'a> {
     self
   }
 
+  /// Only list direct replies to this comment, and with `max_depth` their replies
+  pub fn parent_id<T: MaybeOptional<CommentId>>(mut self, parent_id: T) -> Self {
+    self.parent_id = parent_id.get_optional();
+    self
+  }
+
+  /// Lists whole branches instead of single comments. `page` and `limit` then apply to the top
+  /// level comments (or the direct replies of `parent_id`), and each of them is returned with its
+  /// replies down to `max_depth` levels below. Replies further down can be fetched by passing the
+  /// deepest comment as `parent_id`, its `child_count` shows if there are any.
+  pub fn max_depth<T: MaybeOptional<i32>>(mut self, max_depth: T) -> Self {
+    self.max_depth = max_depth.get_optional();
+    self
+  }
+
   pub fn creator_id<T: MaybeOptional<PersonId>>(mut self, creator_id: T) -> Self {
     self.creator_id = creator_id.get_optional();
     self
@@ -297,6 +360,11 @@ impl<'a> CommentQueryBuilder<'a> {
     self
   }
 
+  pub fn category_id<T: MaybeOptional<i32>>(mut self, category_id: T) -> Self {
+    self.category_id = category_id.get_optional();
+    self
+  }
+
   pub fn search_term<T: MaybeOptional<String>>(mut self, search_term: T) -> Self {
     self.search_term = search_term.get_optional();
     self
@@ -307,16 +375,54 @@ impl<'a> CommentQueryBuilder<'a> {
     self
   }
 
+  /// Only has an effect together with `saved_only`
+  pub fn saved_collection_id<T: MaybeOptional<SavedCollectionId>>(
+    mut self,
+    saved_collection_id: T,
+  ) -> Self {
+    self.saved_collection_id = saved_collection_id.get_optional();
+    self
+  }
+
   pub fn unread_only<T: MaybeOptional<bool>>(mut self, unread_only: T) -> Self {
     self.unread_only = unread_only.get_optional();
     self
   }
 
+  pub fn show_scores<T: MaybeOptional<bool>>(mut self, show_scores: T) -> Self {
+    self.show_scores = show_scores.get_optional();
+    self
+  }
+
   pub fn show_bot_accounts<T: MaybeOptional<bool>>(mut self, show_bot_accounts: T) -> Self {
     self.show_bot_accounts = show_bot_accounts.get_optional();
     self
   }
 
+  /// Only comments in these languages, or with undetermined language. An empty list shows all.
+  pub fn languages<T: MaybeOptional<Vec<LanguageId>>>(mut self, languages: T) -> Self {
+    self.languages = languages.get_optional();
+    self
+  }
+
+  /// Only comments which were published after this time
+  pub fn published_after<T: MaybeOptional<chrono::NaiveDateTime>>(
+    mut self,
+    published_after: T,
+  ) -> Self {
+    self.published_after = published_after.get_optional();
+    self
+  }
+
+  /// Only comments which were published before this time
+  pub fn published_before<T: MaybeOptional<chrono::NaiveDateTime>>(
+    mut self,
+    published_before: T,
+  ) -> Self {
+    self.published_before = published_before.get_optional();
+    self
+  }
+
   pub fn page<T: MaybeOptional<i64>>(mut self, page: T) -> Self {
     self.page = page.get_optional();
     self
@@ -328,6 +434,31 @@ impl<'a> CommentQueryBuilder<'a> {
   }
 
   pub fn list(self) -> Result<Vec<CommentView>, Error> {
+    let mut views = self.load(None)?;
+    if let Some(max_depth) = self.max_depth {
+      let branches = views
+        .iter()
+        .filter(|c| c.counts.child_count > 0)
+        .map(|c| &c.comment)
+        .collect::<Vec<_>>();
+      // The branches are siblings, so they all start at the same depth
+      if let Some(first) = branches.first() {
+        let branches = Branches {
+          paths: branches.iter().map(|c| c.path.to_owned()).collect(),
+          max_depth: first.depth() as i32 + max_depth,
+        };
+        let replies = self.load(Some(&branches))?;
+        views.extend(replies);
+      }
+    }
+    if !self.show_scores.unwrap_or(true) {
+      views.iter_mut().for_each(CommentView::hide_scores);
+    }
+    Ok(views)
+  }
+
+  /// Loads a page of comments, or with `branches` all replies below these comments
+  fn load(&self, branches: Option<&Branches>) -> Result<Vec<CommentView>, Error> {
     use diesel::dsl::*;
 
     // The left join below will return None in this case
@@ -421,22 +552,85 @@ impl<'a> CommentQueryBuilder<'a> {
       query = query.filter(comment::creator_id.eq(creator_id));
     };
 
+    if let Some(published_after) = self.published_after {
+      query = query.filter(comment::published.gt(published_after));
+    }
+
+    if let Some(published_before) = self.published_before {
+      query = query.filter(comment::published.lt(published_before));
+    }
+
     if let Some(community_id) = self.community_id {
       query = query.filter(post::community_id.eq(community_id));
     }
 
-    if let Some(community_actor_id) = self.community_actor_id {
+    if let Some(community_actor_id) = &self.community_actor_id {
       query = query.filter(community::actor_id.eq(community_actor_id))
     }
 
+    if let Some(category_id) = self.category_id {
+      query = query.filter(community::category_id.eq(category_id));
+    }
+
+    if let Some(branches) = branches {
+      // Each branch is a range of the path index, so this is still a single indexed query
+      let mut in_branches = comment::table.select(comment::id).into_boxed();
+      for path in &branches.paths {
+        in_branches = in_branches.or_filter(comment::path.like(format!("{}.%", path)));
+      }
+      query = query
+        .filter(comment::id.eq_any(in_branches))
+        .filter(comment_path_depth(comment::path).le(branches.max_depth));
+    } else if let Some(parent_id) = self.parent_id {
+      query = query.filter(comment::parent_id.eq(parent_id));
+    } else if self.max_depth.is_some() {
+      query = query.filter(comment::parent_id.is_null());
+    }
+
     if let Some(post_id) = self.post_id {
       query = query.filter(comment::post_id.eq(post_id));
+    } else {
+      // Comments of removed posts are only listed on the post itself. They are not removed
+      // individually, so they reappear once the post is restored.
+      query = query.filter(post::removed.eq(false));
     };
 
-    if let Some(search_term) = self.search_term {
-      query = query.filter(comment::content.ilike(fuzzy_search(&search_term)));
+    if let Some(search_term) = &self.search_term {
+      query = query
+        .filter(Matches::new(
+          search_vector("comment"),
+          search_tsquery(search_term.to_owned()),
+        ))
+        .filter(Matches::new(
+          search_vector("comment"),
+          search_tsquery_for_language(search_term.to_owned(), comment::language_id),
+        ));
     };
 
+    if let Some(languages) = self.languages.as_ref().filter(|l| !l.is_empty()) {
+      query = query.filter(
+        comment::language_id
+          .eq(LanguageId::default())
+          .or(comment::language_id.eq_any(languages.to_owned())),
+      );
+    }
+
+    // Hide comments in languages which the community doesn't use, if it defined any
+    query = query.filter(
+      comment::language_id
+        .eq(LanguageId::default())
+        .or(not(exists(community_language::table.filter(
+          community_language::community_id.eq(post::community_id),
+        ))))
+        .or(
+          comment::language_id.eq_any(
+            community_language::table
+              .filter(community_language::community_id.eq(post::community_id))
+              .select(community_language::language_id),
+          ),
+        ),
+    );
+
     if let Some(listing_type) = self.listing_type {
       query = match listing_type {
         ListingType::Subscribed => query.filter(community_follower::person_id.is_not_null()), // TODO could be this: and(community_follower::person_id.eq(person_id_join)),
@@ -447,6 +641,9 @@ impl<'a> CommentQueryBuilder<'a> {
 
     if self.saved_only.unwrap_or(false) {
       query = query.filter(comment_saved::id.is_not_null());
+      if let Some(saved_collection_id) = self.saved_collection_id {
+        query = query.filter(comment_saved::collection_id.eq(saved_collection_id));
+      }
     }
 
     if !self.show_bot_accounts.unwrap_or(true) {
@@ -454,12 +651,17 @@ impl<'a> CommentQueryBuilder<'a> {
     };
 
     query = match self.sort.unwrap_or(SortType::New) {
-      SortType::Hot | SortType::Active => query
-        .order_by(hot_rank(comment_aggregates::score, comment_aggregates::published).desc())
+      SortType::Hot | SortType::Active | SortType::Trending => query
+        .order_by(comment_aggregates::hot_rank.desc())
         .then_order_by(comment_aggregates::published.desc()),
       SortType::New | SortType::MostComments | SortType::NewComments => {
         query.order_by(comment::published.desc())
       }
+      SortType::Controversial => query
+        .order_by(
+          controversy_rank(comment_aggregates::upvotes, comment_aggregates::downvotes).desc(),
+        )
+        .then_order_by(comment_aggregates::published.desc()),
       SortType::TopAll => query.order_by(comment_aggregates::score.desc()),
       SortType::TopYear => query
         .filter(comment::published.gt(now - 1.years()))
@@ -475,24 +677,68 @@ impl<'a> CommentQueryBuilder<'a> {
         .order_by(comment_aggregates::score.desc()),
     };
 
-    // Don't show blocked communities or persons
-    if self.my_person_id.is_some() {
+    // Without an explicit sort, the best matches of a search come first
+    if let (Some(search_term), None) = (&self.search_term, self.sort) {
+      query = query
+        .order_by(
+          ts_rank_cd(
+            search_vector("comment"),
+            search_tsquery_for_language(search_term.to_owned(), comment::language_id),
+          )
+          .desc(),
+        )
+        .then_order_by(comment::published.desc());
+    }
+
+    // Don't show blocked communities, persons or instances
+    if let Some(my_person_id) = self.my_person_id {
       query = query.filter(community_block::person_id.is_null());
       query = query.filter(person_block::person_id.is_null());
+      for pattern in PersonInstanceBlock::actor_id_patterns(self.conn, my_person_id)? {
+        query = query
+          .filter(community::actor_id.not_like(pattern.clone()))
+          .filter(person::actor_id.not_like(pattern));
+      }
     }
 
-    let (limit, offset) = limit_and_offset(self.page, self.limit);
+    // Replies of a branch are not paginated, the depth limits how many are loaded
+    if branches.is_none() {
+      let (limit, offset) = limit_and_offset(self.page, self.limit);
+      query = query.limit(limit).offset(offset);
+    }
 
     // Note: deleted and removed comments are done on the front side
-    let res = query
-      .limit(limit)
-      .offset(offset)
-      .load::<CommentViewTuple>(self.conn)?;
+    let res = query.load::<CommentViewTuple>(self.conn)?;
 
-    Ok(CommentView::from_tuple_to_vec(res))
+    let mut views = CommentView::from_tuple_to_vec(res);
+    let comment_ids = views.iter().map(|v| v.comment.id).collect();
+    let mut reactions: HashMap<CommentId, Vec<ReactionCount>> = HashMap::new();
+    for (comment_id, reaction) in CommentReaction::counts(self.conn, comment_ids)? {
+      reactions.entry(comment_id).or_default().push(reaction);
+    }
+    for view in views.iter_mut() {
+      view.reactions = reactions.remove(&view.comment.id).unwrap_or_default();
+    }
+    if let Some(search_term) = &self.search_term {
+      let comment_ids = views.iter().map(|v| v.comment.id).collect();
+      let mut snippets: HashMap<CommentId, String> =
+        Comment::search_snippets(self.conn, comment_ids, search_term)?
+          .into_iter()
+          .collect();
+      for view in views.iter_mut() {
+        view.snippet = snippets.remove(&view.comment.id);
+      }
+    }
+    Ok(views)
   }
 }
 
+/// Replies below the comments with these paths, down to an absolute depth in the comment tree
+struct Branches {
+  paths: Vec<String>,
+  max_depth: i32,
+}
+
 impl ViewToVec for CommentView {
   type DbTuple = CommentViewTuple;
   fn from_tuple_to_vec(items: Vec<Self::DbTuple>) -> Vec<Self> {
@@ -510,6 +756,8 @@ impl ViewToVec for CommentView {
         saved: a.9.is_some(),
         creator_blocked: a.10.is_some(),
         my_vote: a.11,
+        reactions: vec![],
+        snippet: None,
       })
       .collect::<Vec<Self>>()
   }
@@ -521,8 +769,15 @@ mod tests {
   use lemmy_db_schema::{
     aggregates::comment_aggregates::CommentAggregates,
     establish_unpooled_connection,
-    source::{comment::*, community::*, person::*, person_block::PersonBlockForm, post::*},
-    traits::{Blockable, Crud, Likeable},
+    source::{
+      comment::*,
+      community::*,
+      language::Language,
+      person::*,
+      person_block::PersonBlockForm,
+      post::*,
+    },
+    traits::{Blockable, Crud, Likeable, Reactable},
   };
   use serial_test::serial;
 
@@ -584,6 +839,7 @@ mod tests {
     let timmy_blocks_sara_form = PersonBlockForm {
       person_id: inserted_person.id,
       target_id: inserted_person_2.id,
+      prevent_interaction: None,
     };
 
     let inserted_block = PersonBlock::block(&conn, &timmy_blocks_sara_form).unwrap();
@@ -593,9 +849,21 @@ mod tests {
       person_id: inserted_person.id,
       target_id: inserted_person_2.id,
       published: inserted_block.published,
+      prevent_interaction: false,
     };
 
     assert_eq!(expected_block, inserted_block);
+    assert!(
+      !PersonBlock::prevents_interaction(&conn, inserted_person.id, inserted_person_2.id).unwrap()
+    );
+    let prevent_interaction_form = PersonBlockForm {
+      prevent_interaction: Some(true),
+      ..timmy_blocks_sara_form
+    };
+    PersonBlock::block(&conn, &prevent_interaction_form).unwrap();
+    assert!(
+      PersonBlock::prevents_interaction(&conn, inserted_person.id, inserted_person_2.id).unwrap()
+    );
 
     let comment_like_form = CommentLikeForm {
       comment_id: inserted_comment.id,
@@ -606,11 +874,23 @@ mod tests {
 
     let _inserted_comment_like = CommentLike::like(&conn, &comment_like_form).unwrap();
 
+    let comment_reaction_form = CommentReactionForm {
+      comment_id: inserted_comment.id,
+      person_id: inserted_person.id,
+      emoji: "👍".to_string(),
+    };
+    CommentReaction::react(&conn, &comment_reaction_form).unwrap();
+
     let agg = CommentAggregates::read(&conn, inserted_comment.id).unwrap();
 
     let expected_comment_view_no_person = CommentView {
       creator_banned_from_community: false,
       my_vote: None,
+      reactions: vec![ReactionCount {
+        emoji: "👍".to_string(),
+        count: 1,
+      }],
+      snippet: None,
       subscribed: false,
       saved: false,
       creator_blocked: false,
@@ -627,6 +907,8 @@ mod tests {
         ap_id: inserted_comment.ap_id,
         updated: None,
         local: true,
+        path: inserted_comment.path.to_owned(),
+        language_id: Default::default(),
       },
       creator: PersonSafe {
         id: inserted_person.id,
@@ -668,6 +950,12 @@ mod tests {
         thumbnail_url: None,
         ap_id: inserted_post.ap_id.to_owned(),
         local: true,
+        shadow_removed: false,
+        cross_post_of: None,
+        embed_video_url: None,
+        embed_provider_name: None,
+        language_id: Default::default(),
+        pending: false,
       },
       community: CommunitySafe {
         id: inserted_community.id,
@@ -682,6 +970,8 @@ mod tests {
         description: None,
         updated: None,
         banner: None,
+        require_post_approval: false,
+        category_id: None,
         published: inserted_community.published,
       },
       counts: CommentAggregates {
@@ -691,6 +981,8 @@ mod tests {
         upvotes: 1,
         downvotes: 0,
         published: agg.published,
+        child_count: 1,
+        hot_rank: 1728,
       },
     };
 
@@ -711,6 +1003,22 @@ mod tests {
     let read_comment_from_blocked_person =
       CommentView::read(&conn, inserted_comment_2.id, Some(inserted_person.id)).unwrap();
 
+    // The comment with both up and downvotes is the most controversial
+    for (person_id, score) in [(inserted_person.id, -1), (inserted_person_2.id, 1)] {
+      let form = CommentLikeForm {
+        comment_id: inserted_comment_2.id,
+        post_id: inserted_post.id,
+        person_id,
+        score,
+      };
+      CommentLike::like(&conn, &form).unwrap();
+    }
+    let read_comment_views_controversial = CommentQueryBuilder::create(&conn)
+      .post_id(inserted_post.id)
+      .sort(SortType::Controversial)
+      .list()
+      .unwrap();
+
     let like_removed = CommentLike::remove(&conn, inserted_person.id, inserted_comment.id).unwrap();
     let num_deleted = Comment::delete(&conn, inserted_comment.id).unwrap();
     Comment::delete(&conn, inserted_comment_2.id).unwrap();
@@ -734,7 +1042,221 @@ mod tests {
     // Make sure block set the creator blocked
     assert!(read_comment_from_blocked_person.creator_blocked);
 
+    assert_eq!(
+      vec![inserted_comment_2.id, inserted_comment.id],
+      read_comment_views_controversial
+        .iter()
+        .map(|c| c.comment.id)
+        .collect::<Vec<_>>()
+    );
+
     assert_eq!(1, num_deleted);
     assert_eq!(1, like_removed);
   }
+
+  #[test]
+  #[serial]
+  fn test_comments_of_removed_post() {
+    let conn = establish_unpooled_connection();
+
+    let new_person = PersonForm {
+      name: "removed_post_commenter".into(),
+      ..PersonForm::default()
+    };
+    let inserted_person = Person::create(&conn, &new_person).unwrap();
+
+    let new_community = CommunityForm {
+      name: "test community removed post".to_string(),
+      title: "nada".to_owned(),
+      ..CommunityForm::default()
+    };
+    let inserted_community = Community::create(&conn, &new_community).unwrap();
+
+    let new_post = PostForm {
+      name: "A post which gets removed".into(),
+      creator_id: inserted_person.id,
+      community_id: inserted_community.id,
+      ..PostForm::default()
+    };
+    let inserted_post = Post::create(&conn, &new_post).unwrap();
+
+    let comment_form = CommentForm {
+      content: "A comment on a removed post".into(),
+      creator_id: inserted_person.id,
+      post_id: inserted_post.id,
+      ..CommentForm::default()
+    };
+    let inserted_comment = Comment::create(&conn, &comment_form).unwrap();
+
+    let list_community = || {
+      CommentQueryBuilder::create(&conn)
+        .community_id(inserted_community.id)
+        .list()
+        .unwrap()
+    };
+    let list_post = || {
+      CommentQueryBuilder::create(&conn)
+        .post_id(inserted_post.id)
+        .list()
+        .unwrap()
+    };
+
+    assert_eq!(1, list_community().len());
+
+    Post::update_removed(&conn, inserted_post.id, true).unwrap();
+    let comments_after_remove = list_community();
+    let post_comments_after_remove = list_post();
+
+    Post::update_removed(&conn, inserted_post.id, false).unwrap();
+    let comments_after_restore = list_community();
+
+    Comment::delete(&conn, inserted_comment.id).unwrap();
+    Post::delete(&conn, inserted_post.id).unwrap();
+    Community::delete(&conn, inserted_community.id).unwrap();
+    Person::delete(&conn, inserted_person.id).unwrap();
+
+    assert!(comments_after_remove.is_empty());
+    assert_eq!(1, post_comments_after_remove.len());
+    assert_eq!(1, comments_after_restore.len());
+    assert!(!comments_after_restore[0].comment.removed);
+  }
+
+  #[test]
+  #[serial]
+  fn test_search_snippets() {
+    let conn = establish_unpooled_connection();
+
+    let person_form = PersonForm {
+      name: "search_snippets_person".into(),
+      ..PersonForm::default()
+    };
+    let inserted_person = Person::create(&conn, &person_form).unwrap();
+    let community_form = CommunityForm {
+      name: "search_snippets".to_string(),
+      title: "nada".to_owned(),
+      ..CommunityForm::default()
+    };
+    let inserted_community = Community::create(&conn, &community_form).unwrap();
+
+    let english = Language::read_id_from_code(&conn, "en").unwrap();
+    let post_form = PostForm {
+      name: "Morning run".into(),
+      creator_id: inserted_person.id,
+      community_id: inserted_community.id,
+      language_id: Some(english),
+      ..PostForm::default()
+    };
+    let inserted_post = Post::create(&conn, &post_form).unwrap();
+    let comment_form = CommentForm {
+      content: "We run every morning before work".into(),
+      creator_id: inserted_person.id,
+      post_id: inserted_post.id,
+      language_id: Some(english),
+      ..CommentForm::default()
+    };
+    let inserted_comment = Comment::create(&conn, &comment_form).unwrap();
+
+    let list = |search_term: Option<String>| {
+      CommentQueryBuilder::create(&conn)
+        .post_id(inserted_post.id)
+        .search_term(search_term)
+        .list()
+        .unwrap()
+        .into_iter()
+        .map(|c| c.snippet)
+        .collect::<Vec<_>>()
+    };
+    // The excerpt starts at the first match
+    let searched = list(Some("running".to_string()));
+    let listed = list(None);
+    // Posts without a body get their title highlighted
+    let post_snippets = Post::search_snippets(&conn, vec![inserted_post.id], "running").unwrap();
+
+    Comment::delete(&conn, inserted_comment.id).unwrap();
+    Post::delete(&conn, inserted_post.id).unwrap();
+    Community::delete(&conn, inserted_community.id).unwrap();
+    Person::delete(&conn, inserted_person.id).unwrap();
+
+    assert_eq!(
+      vec![Some("**run** every morning before work".to_string())],
+      searched
+    );
+    assert_eq!(vec![None], listed);
+    assert_eq!(
+      vec![(inserted_post.id, "Morning **run**".to_string())],
+      post_snippets
+    );
+  }
+
+  #[test]
+  #[serial]
+  fn test_comment_tree() {
+    let conn = establish_unpooled_connection();
+
+    let person_form = PersonForm {
+      name: "comment_tree_person".into(),
+      ..PersonForm::default()
+    };
+    let inserted_person = Person::create(&conn, &person_form).unwrap();
+    let community_form = CommunityForm {
+      name: "comment_tree_community".to_string(),
+      title: "nada".to_owned(),
+      ..CommunityForm::default()
+    };
+    let inserted_community = Community::create(&conn, &community_form).unwrap();
+    let post_form = PostForm {
+      name: "A post with a comment tree".into(),
+      creator_id: inserted_person.id,
+      community_id: inserted_community.id,
+      ..PostForm::default()
+    };
+    let inserted_post = Post::create(&conn, &post_form).unwrap();
+
+    let comment = |parent_id| {
+      let form = CommentForm {
+        content: "A comment in a tree".into(),
+        creator_id: inserted_person.id,
+        post_id: inserted_post.id,
+        parent_id,
+        ..CommentForm::default()
+      };
+      Comment::create(&conn, &form).unwrap().id
+    };
+    let first_root = comment(None);
+    let child = comment(Some(first_root));
+    let grandchild = comment(Some(child));
+    let second_root = comment(None);
+
+    let ids = |comments: Vec<CommentView>| {
+      comments
+        .into_iter()
+        .map(|c| (c.comment.id, c.counts.child_count))
+        .collect::<Vec<_>>()
+    };
+    let list = |parent_id, max_depth, limit| {
+      CommentQueryBuilder::create(&conn)
+        .post_id(inserted_post.id)
+        .parent_id(parent_id)
+        .max_depth(max_depth)
+        .sort(SortType::New)
+        .limit(limit)
+        .list()
+        .unwrap()
+    };
+    // The limit only counts top level comments
+    let first_page = ids(list(None, 1, 2));
+    let single_branch = ids(list(None, 2, 1));
+    let children = ids(list(Some(child), 0, 10));
+
+    Post::delete(&conn, inserted_post.id).unwrap();
+    Community::delete(&conn, inserted_community.id).unwrap();
+    Person::delete(&conn, inserted_person.id).unwrap();
+
+    assert_eq!(
+      vec![(second_root, 0), (first_root, 1), (child, 1)],
+      first_page
+    );
+    assert_eq!(vec![(second_root, 0)], single_branch);
+    assert_eq!(vec![(grandchild, 0)], children);
+  }
 }
@@ -1,11 +1,14 @@
 #[cfg(test)]
 extern crate serial_test;
 
+pub mod account_archive_view;
 pub mod comment_report_view;
 pub mod comment_view;
 pub mod local_user_view;
+pub mod poll_view;
 pub mod post_report_view;
 pub mod post_view;
+pub mod private_message_thread_view;
 pub mod private_message_view;
 pub mod registration_application_view;
 pub mod site_view;
@@ -0,0 +1,38 @@
+use diesel::{result::Error, OptionalExtension, PgConnection};
+use lemmy_db_schema::{
+  newtypes::{PersonId, PostId},
+  source::poll::{Poll, PollOption, PollVote},
+};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PollView {
+  pub poll: Poll,
+  pub options: Vec<PollOption>,
+  /// Ids of the options which the current user voted for
+  pub my_votes: Vec<i32>,
+}
+
+impl PollView {
+  /// Returns None if the post has no poll
+  pub fn read_for_post(
+    conn: &PgConnection,
+    post_id: PostId,
+    my_person_id: Option<PersonId>,
+  ) -> Result<Option<Self>, Error> {
+    let poll = match Poll::read_for_post(conn, post_id).optional()? {
+      Some(p) => p,
+      None => return Ok(None),
+    };
+    let options = PollOption::list_for_poll(conn, poll.id)?;
+    let my_votes = match my_person_id {
+      Some(person_id) => PollVote::list_for_person(conn, poll.id, person_id)?,
+      None => vec![],
+    };
+    Ok(Some(PollView {
+      poll,
+      options,
+      my_votes,
+    }))
+  }
+}
@@ -154,6 +154,16 @@ impl PostReportView {
       query.select(count(post_report::id)).first::<i64>(conn)
     }
   }
+
+  /// Returns the count of unresolved post reports which mods escalated to the admins
+  pub fn get_escalated_report_count(conn: &PgConnection) -> Result<i64, Error> {
+    use diesel::dsl::*;
+    post_report::table
+      .filter(post_report::resolved.eq(false))
+      .filter(post_report::escalated.eq(true))
+      .select(count(post_report::id))
+      .first::<i64>(conn)
+  }
 }
 
 pub struct PostReportQueryBuilder<'a> {
@@ -164,6 +174,7 @@ pub struct PostReportQueryBuilder<'a> {
   page: Option<i64>,
   limit: Option<i64>,
   unresolved_only: Option<bool>,
+  escalated_only: Option<bool>,
 }
 
 impl<'a> PostReportQueryBuilder<'a> {
@@ -176,6 +187,7 @@ impl<'a> PostReportQueryBuilder<'a> {
       page: None,
       limit: None,
       unresolved_only: Some(true),
+      escalated_only: None,
     }
   }
 
@@ -199,6 +211,11 @@ impl<'a> PostReportQueryBuilder<'a> {
     self
   }
 
+  pub fn escalated_only<T: MaybeOptional<bool>>(mut self, escalated_only: T) -> Self {
+    self.escalated_only = escalated_only.get_optional();
+    self
+  }
+
   pub fn list(self) -> Result<Vec<PostReportView>, Error> {
     let mut query = post_report::table
       .inner_join(post::table)
@@ -244,6 +261,10 @@ impl<'a> PostReportQueryBuilder<'a> {
       query = query.filter(post_report::resolved.eq(false));
     }
 
+    if self.escalated_only.unwrap_or(false) {
+      query = query.filter(post_report::escalated.eq(true));
+    }
+
     let (limit, offset) = limit_and_offset(self.page, self.limit);
 
     query = query
@@ -402,6 +423,8 @@ mod tests {
         description: None,
         updated: None,
         banner: None,
+        require_post_approval: false,
+        category_id: None,
         published: inserted_community.published,
       },
       creator: PersonSafe {
@@ -455,6 +478,9 @@ mod tests {
         published: agg.published,
         newest_comment_time_necro: inserted_post.published,
         newest_comment_time: inserted_post.published,
+        stickied_at: None,
+        hot_rank: 1728,
+        hot_rank_active: 1728,
       },
       resolver: None,
     };
@@ -502,8 +528,30 @@ mod tests {
       PostReportView::get_report_count(&conn, inserted_timmy.id, false, None).unwrap();
     assert_eq!(2, report_count);
 
+    // Escalate jessicas report to the admins
+    PostReport::escalate(&conn, inserted_jessica_report.id).unwrap();
+    let escalated_reports = PostReportQueryBuilder::create(&conn, inserted_timmy.id, false)
+      .escalated_only(true)
+      .list()
+      .unwrap();
+    assert_eq!(1, escalated_reports.len());
+    assert_eq!(
+      inserted_jessica_report.id,
+      escalated_reports[0].post_report.id
+    );
+    assert_eq!(
+      1,
+      PostReportView::get_escalated_report_count(&conn).unwrap()
+    );
+
     // Try to resolve the report
-    PostReport::resolve(&conn, inserted_jessica_report.id, inserted_timmy.id).unwrap();
+    PostReport::resolve(
+      &conn,
+      inserted_jessica_report.id,
+      inserted_timmy.id,
+      Some("spam".into()),
+    )
+    .unwrap();
     let read_jessica_report_view_after_resolve =
       PostReportView::read(&conn, inserted_jessica_report.id, inserted_timmy.id).unwrap();
 
@@ -511,9 +559,15 @@ mod tests {
     expected_jessica_report_view_after_resolve
       .post_report
       .resolved = true;
+    expected_jessica_report_view_after_resolve
+      .post_report
+      .escalated = true;
     expected_jessica_report_view_after_resolve
       .post_report
       .resolver_id = Some(inserted_timmy.id);
+    expected_jessica_report_view_after_resolve
+      .post_report
+      .resolution_note = Some("spam".into());
     expected_jessica_report_view_after_resolve
       .post_report
       .updated = read_jessica_report_view_after_resolve.post_report.updated;
@@ -553,6 +607,18 @@ mod tests {
     let report_count_after_resolved =
       PostReportView::get_report_count(&conn, inserted_timmy.id, false, None).unwrap();
     assert_eq!(1, report_count_after_resolved);
+    assert_eq!(
+      0,
+      PostReportView::get_escalated_report_count(&conn).unwrap()
+    );
+
+    // Resolve the remaining report along with all others for the post
+    let num_resolved =
+      PostReport::resolve_all_for_post(&conn, inserted_post.id, inserted_timmy.id, None).unwrap();
+    assert_eq!(1, num_resolved);
+    let report_count_after_bulk_resolve =
+      PostReportView::get_report_count(&conn, inserted_timmy.id, false, None).unwrap();
+    assert_eq!(0, report_count_after_bulk_resolve);
 
     Person::delete(&conn, inserted_timmy.id).unwrap();
     Person::delete(&conn, inserted_sara.id).unwrap();
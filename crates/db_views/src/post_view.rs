@@ -1,34 +1,56 @@
 use diesel::{pg::Pg, result::Error, *};
 use lemmy_db_schema::{
-  aggregates::post_aggregates::PostAggregates,
-  functions::hot_rank,
-  fuzzy_search,
+  aggregates::{post_aggregates::PostAggregates, reaction_count::ReactionCount},
+  functions::{
+    controversy_rank,
+    search_tsquery,
+    search_tsquery_for_language,
+    search_vector,
+    ts_rank_cd,
+    Matches,
+  },
   limit_and_offset,
-  newtypes::{CommunityId, DbUrl, PersonId, PostId},
+  newtypes::{
+    CommunityId,
+    DbUrl,
+    LanguageId,
+    MultiCommunityId,
+    PersonId,
+    PostId,
+    SavedCollectionId,
+  },
   schema::{
     community,
     community_block,
     community_follower,
+    community_language,
+    community_moderator,
     community_person_ban,
+    multi_community_community,
     person,
+    person_alias_1,
     person_block,
     post,
     post_aggregates,
     post_like,
     post_read,
     post_saved,
+    post_tag_assignment,
   },
   source::{
     community::{Community, CommunityFollower, CommunityPersonBan, CommunitySafe},
+    local_user::LocalUser,
     person::{Person, PersonSafe},
     person_block::PersonBlock,
-    post::{Post, PostRead, PostSaved},
+    person_instance_block::PersonInstanceBlock,
+    post::{Post, PostReaction, PostRead, PostSaved},
   },
-  traits::{MaybeOptional, ToSafe, ViewToVec},
+  traits::{MaybeOptional, Reactable, ToSafe, ViewToVec},
   ListingType,
   SortType,
 };
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use tracing::debug;
 
 #[derive(Debug, PartialEq, Serialize, Deserialize, Clone)]
@@ -43,6 +65,9 @@ pub struct PostView {
   pub read: bool,            // Left join to PostRead
   pub creator_blocked: bool, // Left join to PersonBlock
   pub my_vote: Option<i16>,  // Left join to PostLike
+  pub reactions: Vec<ReactionCount>,
+  /// Excerpt with the matches highlighted, only for search results
+  pub snippet: Option<String>,
 }
 
 type PostViewTuple = (
@@ -147,7 +172,7 @@ impl PostView {
       post_like
     };
 
-    Ok(PostView {
+    let mut post_view = PostView {
       post,
       creator,
       community,
@@ -158,7 +183,25 @@ impl PostView {
       read: read.is_some(),
       creator_blocked: creator_blocked.is_some(),
       my_vote,
-    })
+      reactions: PostReaction::counts(conn, vec![post_id])?
+        .into_iter()
+        .map(|(_, r)| r)
+        .collect(),
+      snippet: None,
+    };
+    if let Some(person_id) = my_person_id {
+      if !LocalUser::show_scores(conn, person_id)? {
+        post_view.hide_scores();
+      }
+    }
+    Ok(post_view)
+  }
+
+  /// Blanks out the votes, for users who don't want to see them
+  pub fn hide_scores(&mut self) {
+    self.counts.score = 0;
+    self.counts.upvotes = 0;
+    self.counts.downvotes = 0;
   }
 }
 
@@ -169,13 +212,25 @@ pub struct PostQueryBuilder<'a> {
   creator_id: Option<PersonId>,
   community_id: Option<CommunityId>,
   community_actor_id: Option<DbUrl>,
+  category_id: Option<i32>,
+  multi_community_id: Option<MultiCommunityId>,
   my_person_id: Option<PersonId>,
   search_term: Option<String>,
   url_search: Option<String>,
+  url_domain: Option<String>,
   show_nsfw: Option<bool>,
   show_bot_accounts: Option<bool>,
   show_read_posts: Option<bool>,
+  show_scores: Option<bool>,
   saved_only: Option<bool>,
+  saved_collection_id: Option<SavedCollectionId>,
+  unread_only: Option<bool>,
+  tag_id: Option<i32>,
+  cross_posts_of: Option<PostId>,
+  languages: Option<Vec<LanguageId>>,
+  pending_only: Option<bool>,
+  published_after: Option<chrono::NaiveDateTime>,
+  published_before: Option<chrono::NaiveDateTime>,
   page: Option<i64>,
   limit: Option<i64>,
 }
@@ -189,13 +244,25 @@ impl<'a> PostQueryBuilder<'a> {
       creator_id: None,
       community_id: None,
       community_actor_id: None,
+      category_id: None,
+      multi_community_id: None,
       my_person_id: None,
       search_term: None,
       url_search: None,
+      url_domain: None,
       show_nsfw: None,
       show_bot_accounts: None,
       show_read_posts: None,
+      show_scores: None,
       saved_only: None,
+      saved_collection_id: None,
+      unread_only: None,
+      tag_id: None,
+      cross_posts_of: None,
+      languages: None,
+      pending_only: None,
+      published_after: None,
+      published_before: None,
       page: None,
       limit: None,
     }
@@ -226,6 +293,21 @@ impl<'a> PostQueryBuilder<'a> {
     self
   }
 
+  /// Only posts in communities of this category
+  pub fn category_id<T: MaybeOptional<i32>>(mut self, category_id: T) -> Self {
+    self.category_id = category_id.get_optional();
+    self
+  }
+
+  /// Only posts in the communities of this multi-community
+  pub fn multi_community_id<T: MaybeOptional<MultiCommunityId>>(
+    mut self,
+    multi_community_id: T,
+  ) -> Self {
+    self.multi_community_id = multi_community_id.get_optional();
+    self
+  }
+
   pub fn creator_id<T: MaybeOptional<PersonId>>(mut self, creator_id: T) -> Self {
     self.creator_id = creator_id.get_optional();
     self
@@ -241,6 +323,13 @@ impl<'a> PostQueryBuilder<'a> {
     self
   }
 
+  /// Only posts which link to this domain or its subdomains. Needs to be a valid domain, as it
+  /// isn't escaped.
+  pub fn url_domain<T: MaybeOptional<String>>(mut self, url_domain: T) -> Self {
+    self.url_domain = url_domain.get_optional();
+    self
+  }
+
   pub fn show_nsfw<T: MaybeOptional<bool>>(mut self, show_nsfw: T) -> Self {
     self.show_nsfw = show_nsfw.get_optional();
     self
@@ -251,6 +340,11 @@ impl<'a> PostQueryBuilder<'a> {
     self
   }
 
+  pub fn show_scores<T: MaybeOptional<bool>>(mut self, show_scores: T) -> Self {
+    self.show_scores = show_scores.get_optional();
+    self
+  }
+
   pub fn show_read_posts<T: MaybeOptional<bool>>(mut self, show_read_posts: T) -> Self {
     self.show_read_posts = show_read_posts.get_optional();
     self
@@ -261,6 +355,61 @@ impl<'a> PostQueryBuilder<'a> {
     self
   }
 
+  /// Only has an effect together with `saved_only`
+  pub fn saved_collection_id<T: MaybeOptional<SavedCollectionId>>(
+    mut self,
+    saved_collection_id: T,
+  ) -> Self {
+    self.saved_collection_id = saved_collection_id.get_optional();
+    self
+  }
+
+  pub fn unread_only<T: MaybeOptional<bool>>(mut self, unread_only: T) -> Self {
+    self.unread_only = unread_only.get_optional();
+    self
+  }
+
+  pub fn tag_id<T: MaybeOptional<i32>>(mut self, tag_id: T) -> Self {
+    self.tag_id = tag_id.get_optional();
+    self
+  }
+
+  /// Only the given post and all cross-posts of it
+  pub fn cross_posts_of<T: MaybeOptional<PostId>>(mut self, cross_posts_of: T) -> Self {
+    self.cross_posts_of = cross_posts_of.get_optional();
+    self
+  }
+
+  /// Only posts in these languages, or with undetermined language. An empty list shows all.
+  pub fn languages<T: MaybeOptional<Vec<LanguageId>>>(mut self, languages: T) -> Self {
+    self.languages = languages.get_optional();
+    self
+  }
+
+  /// Only posts which are waiting for approval by the mods
+  pub fn pending_only<T: MaybeOptional<bool>>(mut self, pending_only: T) -> Self {
+    self.pending_only = pending_only.get_optional();
+    self
+  }
+
+  /// Only posts which were published after this time
+  pub fn published_after<T: MaybeOptional<chrono::NaiveDateTime>>(
+    mut self,
+    published_after: T,
+  ) -> Self {
+    self.published_after = published_after.get_optional();
+    self
+  }
+
+  /// Only posts which were published before this time
+  pub fn published_before<T: MaybeOptional<chrono::NaiveDateTime>>(
+    mut self,
+    published_before: T,
+  ) -> Self {
+    self.published_before = published_before.get_optional();
+    self
+  }
+
   pub fn page<T: MaybeOptional<i64>>(mut self, page: T) -> Self {
     self.page = page.get_optional();
     self
@@ -355,33 +504,124 @@ impl<'a> PostQueryBuilder<'a> {
     if let Some(community_id) = self.community_id {
       query = query
         .filter(post::community_id.eq(community_id))
-        .then_order_by(post_aggregates::stickied.desc());
+        .then_order_by(post_aggregates::stickied.desc())
+        .then_order_by(post_aggregates::stickied_at.asc());
     }
 
     if let Some(community_actor_id) = self.community_actor_id {
       query = query
         .filter(community::actor_id.eq(community_actor_id))
-        .then_order_by(post_aggregates::stickied.desc());
+        .then_order_by(post_aggregates::stickied.desc())
+        .then_order_by(post_aggregates::stickied_at.asc());
     }
 
+    if let Some(category_id) = self.category_id {
+      query = query.filter(community::category_id.eq(category_id));
+    }
+
+    if let Some(multi_community_id) = self.multi_community_id {
+      query = query.filter(
+        post::community_id.eq_any(
+          multi_community_community::table
+            .filter(multi_community_community::multi_community_id.eq(multi_community_id))
+            .select(multi_community_community::community_id),
+        ),
+      );
+    }
+
+    if let Some(tag_id) = self.tag_id {
+      query = query.filter(
+        post::id.eq_any(
+          post_tag_assignment::table
+            .filter(post_tag_assignment::tag_id.eq(tag_id))
+            .select(post_tag_assignment::post_id),
+        ),
+      );
+    }
+
+    if let Some(cross_posts_of) = self.cross_posts_of {
+      query = query.filter(
+        post::id
+          .eq(cross_posts_of)
+          .or(post::cross_post_of.eq(cross_posts_of)),
+      );
+    }
+
+    if let Some(languages) = self.languages.filter(|l| !l.is_empty()) {
+      query = query.filter(
+        post::language_id
+          .eq(LanguageId::default())
+          .or(post::language_id.eq_any(languages)),
+      );
+    }
+
+    // Hide posts in languages which the community doesn't use, if it defined any
+    query = query.filter(
+      post::language_id
+        .eq(LanguageId::default())
+        .or(not(exists(community_language::table.filter(
+          community_language::community_id.eq(post::community_id),
+        ))))
+        .or(
+          post::language_id.eq_any(
+            community_language::table
+              .filter(community_language::community_id.eq(post::community_id))
+              .select(community_language::language_id),
+          ),
+        ),
+    );
+
     if let Some(url_search) = self.url_search {
       query = query.filter(post::url.eq(url_search));
     }
 
-    if let Some(search_term) = self.search_term {
-      let searcher = fuzzy_search(&search_term);
+    if let Some(url_domain) = self.url_domain {
+      let host = format!("http%://{}", url_domain);
+      let subdomain = format!("http%://%.{}", url_domain);
       query = query.filter(
-        post::name
-          .ilike(searcher.to_owned())
-          .or(post::body.ilike(searcher)),
+        post::url
+          .ilike(host.to_owned())
+          .or(post::url.ilike(format!("{}/%", host)))
+          .or(post::url.ilike(subdomain.to_owned()))
+          .or(post::url.ilike(format!("{}/%", subdomain))),
       );
     }
 
+    if let Some(search_term) = &self.search_term {
+      query = query
+        .filter(Matches::new(
+          search_vector("post"),
+          search_tsquery(search_term.to_owned()),
+        ))
+        .filter(Matches::new(
+          search_vector("post"),
+          search_tsquery_for_language(search_term.to_owned(), post::language_id),
+        ));
+      // Without an explicit sort, the best matches come first
+      if self.sort.is_none() {
+        query = query.then_order_by(
+          ts_rank_cd(
+            search_vector("post"),
+            search_tsquery_for_language(search_term.to_owned(), post::language_id),
+          )
+          .desc(),
+        );
+      }
+    }
+
     // If its for a specific person, show the removed / deleted
     if let Some(creator_id) = self.creator_id {
       query = query.filter(post::creator_id.eq(creator_id));
     }
 
+    if let Some(published_after) = self.published_after {
+      query = query.filter(post::published.gt(published_after));
+    }
+
+    if let Some(published_before) = self.published_before {
+      query = query.filter(post::published.lt(published_before));
+    }
+
     if !self.show_nsfw.unwrap_or(false) {
       query = query
         .filter(post::nsfw.eq(false))
@@ -394,6 +634,9 @@ impl<'a> PostQueryBuilder<'a> {
 
     if self.saved_only.unwrap_or(false) {
       query = query.filter(post_saved::id.is_not_null());
+      if let Some(saved_collection_id) = self.saved_collection_id {
+        query = query.filter(post_saved::collection_id.eq(saved_collection_id));
+      }
     }
     // Only hide the read posts, if the saved_only is false. Otherwise ppl with the hide_read
     // setting wont be able to see saved posts.
@@ -401,26 +644,70 @@ impl<'a> PostQueryBuilder<'a> {
       query = query.filter(post_read::id.is_null());
     }
 
-    // Don't show blocked communities or persons
-    if self.my_person_id.is_some() {
+    if self.unread_only.unwrap_or(false) {
+      query = query.filter(post_read::id.is_null());
+    }
+
+    // Don't show blocked communities, persons or instances
+    if let Some(my_person_id) = self.my_person_id {
       query = query.filter(community_block::person_id.is_null());
       query = query.filter(person_block::person_id.is_null());
+      for pattern in PersonInstanceBlock::actor_id_patterns(self.conn, my_person_id)? {
+        query = query
+          .filter(community::actor_id.not_like(pattern.clone()))
+          .filter(person::actor_id.not_like(pattern));
+      }
+    }
+
+    // Shadow removed posts are only visible to their creator, and to mods of the community and
+    // admins
+    query = query.filter(
+      post::shadow_removed
+        .eq(false)
+        .or(post::creator_id.eq(person_id_join))
+        .or(exists(
+          community_moderator::table.filter(
+            community_moderator::community_id
+              .eq(post::community_id)
+              .and(community_moderator::person_id.eq(person_id_join)),
+          ),
+        ))
+        .or(exists(
+          person_alias_1::table.filter(
+            person_alias_1::id
+              .eq(person_id_join)
+              .and(person_alias_1::admin.eq(true)),
+          ),
+        )),
+    );
+
+    // Pending posts are only visible to their creator, until they are approved
+    if self.pending_only.unwrap_or(false) {
+      query = query
+        .filter(post::pending.eq(true))
+        .filter(post::removed.eq(false))
+        .filter(post::deleted.eq(false));
+    } else {
+      query = query.filter(
+        post::pending
+          .eq(false)
+          .or(post::creator_id.eq(person_id_join)),
+      );
     }
 
     query = match self.sort.unwrap_or(SortType::Hot) {
       SortType::Active => query
-        .then_order_by(
-          hot_rank(
-            post_aggregates::score,
-            post_aggregates::newest_comment_time_necro,
-          )
-          .desc(),
-        )
+        .then_order_by(post_aggregates::hot_rank_active.desc())
         .then_order_by(post_aggregates::newest_comment_time_necro.desc()),
-      SortType::Hot => query
-        .then_order_by(hot_rank(post_aggregates::score, post_aggregates::published).desc())
+      SortType::Hot | SortType::Trending => query
+        .then_order_by(post_aggregates::hot_rank.desc())
         .then_order_by(post_aggregates::published.desc()),
       SortType::New => query.then_order_by(post_aggregates::published.desc()),
+      SortType::Controversial => query
+        .then_order_by(
+          controversy_rank(post_aggregates::upvotes, post_aggregates::downvotes).desc(),
+        )
+        .then_order_by(post_aggregates::published.desc()),
       SortType::MostComments => query.then_order_by(post_aggregates::comments.desc()),
       SortType::NewComments => query.then_order_by(post_aggregates::newest_comment_time.desc()),
       SortType::TopAll => query.then_order_by(post_aggregates::score.desc()),
@@ -452,7 +739,29 @@ impl<'a> PostQueryBuilder<'a> {
 
     let res = query.load::<PostViewTuple>(self.conn)?;
 
-    Ok(PostView::from_tuple_to_vec(res))
+    let mut views = PostView::from_tuple_to_vec(res);
+    let post_ids = views.iter().map(|v| v.post.id).collect();
+    let mut reactions: HashMap<PostId, Vec<ReactionCount>> = HashMap::new();
+    for (post_id, reaction) in PostReaction::counts(self.conn, post_ids)? {
+      reactions.entry(post_id).or_default().push(reaction);
+    }
+    for view in views.iter_mut() {
+      view.reactions = reactions.remove(&view.post.id).unwrap_or_default();
+    }
+    if let Some(search_term) = &self.search_term {
+      let post_ids = views.iter().map(|v| v.post.id).collect();
+      let mut snippets: HashMap<PostId, String> =
+        Post::search_snippets(self.conn, post_ids, search_term)?
+          .into_iter()
+          .collect();
+      for view in views.iter_mut() {
+        view.snippet = snippets.remove(&view.post.id);
+      }
+    }
+    if !self.show_scores.unwrap_or(true) {
+      views.iter_mut().for_each(PostView::hide_scores);
+    }
+    Ok(views)
   }
 }
 
@@ -472,6 +781,8 @@ impl ViewToVec for PostView {
         read: a.7.is_some(),
         creator_blocked: a.8.is_some(),
         my_vote: a.9,
+        reactions: vec![],
+        snippet: None,
       })
       .collect::<Vec<Self>>()
   }
@@ -483,18 +794,25 @@ mod tests {
   use lemmy_db_schema::{
     aggregates::post_aggregates::PostAggregates,
     establish_unpooled_connection,
+    newtypes::LanguageId,
     source::{
       community::*,
       community_block::{CommunityBlock, CommunityBlockForm},
+      language::{CommunityLanguage, Language},
+      local_user::{LocalUser, LocalUserForm},
+      multi_community::*,
       person::*,
       person_block::{PersonBlock, PersonBlockForm},
+      person_instance_block::{PersonInstanceBlock, PersonInstanceBlockForm},
       post::*,
+      post_tag::*,
     },
-    traits::{Blockable, Crud, Likeable},
+    traits::{Blockable, Crud, Followable, Joinable, Likeable, Readable},
     ListingType,
     SortType,
   };
   use serial_test::serial;
+  use url::Url;
 
   #[test]
   #[serial]
@@ -550,6 +868,7 @@ mod tests {
     let person_block = PersonBlockForm {
       person_id: inserted_person.id,
       target_id: inserted_blocked_person.id,
+      prevent_interaction: None,
     };
 
     PersonBlock::block(&conn, &person_block).unwrap();
@@ -571,7 +890,7 @@ mod tests {
       ..PostForm::default()
     };
 
-    let _inserted_bot_post = Post::create(&conn, &new_bot_post).unwrap();
+    let inserted_bot_post = Post::create(&conn, &new_bot_post).unwrap();
 
     let post_like_form = PostLikeForm {
       post_id: inserted_post.id,
@@ -633,8 +952,16 @@ mod tests {
         thumbnail_url: None,
         ap_id: inserted_post.ap_id.to_owned(),
         local: true,
+        shadow_removed: false,
+        cross_post_of: None,
+        embed_video_url: None,
+        embed_provider_name: None,
+        language_id: Default::default(),
+        pending: false,
       },
       my_vote: None,
+      reactions: vec![],
+      snippet: None,
       creator: PersonSafe {
         id: inserted_person.id,
         name: person_name,
@@ -668,6 +995,8 @@ mod tests {
         description: None,
         updated: None,
         banner: None,
+        require_post_approval: false,
+        category_id: None,
         published: inserted_community.published,
       },
       counts: PostAggregates {
@@ -681,6 +1010,9 @@ mod tests {
         published: agg.published,
         newest_comment_time_necro: inserted_post.published,
         newest_comment_time: inserted_post.published,
+        stickied_at: None,
+        hot_rank: 1728,
+        hot_rank_active: 1728,
       },
       subscribed: false,
       read: false,
@@ -688,6 +1020,162 @@ mod tests {
       creator_blocked: false,
     };
 
+    // Shadow removed posts are hidden from anonymous users, but not from their creator
+    Post::update_shadow_removed(&conn, inserted_bot_post.id, true).unwrap();
+    let read_post_listings_shadow_removed_no_person = PostQueryBuilder::create(&conn)
+      .listing_type(ListingType::Community)
+      .sort(SortType::New)
+      .community_id(inserted_community.id)
+      .list()
+      .unwrap();
+    let read_post_listings_shadow_removed_creator = PostQueryBuilder::create(&conn)
+      .listing_type(ListingType::Community)
+      .sort(SortType::New)
+      .community_id(inserted_community.id)
+      .my_person_id(inserted_bot.id)
+      .list()
+      .unwrap();
+    // Subscribing to the community doesn't make them visible, but moderating it does
+    let follower_form = CommunityFollowerForm {
+      community_id: inserted_community.id,
+      person_id: inserted_person.id,
+      pending: false,
+    };
+    CommunityFollower::follow(&conn, &follower_form).unwrap();
+    let read_post_listings_shadow_removed_follower = PostQueryBuilder::create(&conn)
+      .listing_type(ListingType::Community)
+      .sort(SortType::New)
+      .community_id(inserted_community.id)
+      .my_person_id(inserted_person.id)
+      .list()
+      .unwrap();
+    let moderator_form = CommunityModeratorForm {
+      community_id: inserted_community.id,
+      person_id: inserted_person.id,
+    };
+    CommunityModerator::join(&conn, &moderator_form).unwrap();
+    let read_post_listings_shadow_removed_moderator = PostQueryBuilder::create(&conn)
+      .listing_type(ListingType::Community)
+      .sort(SortType::New)
+      .community_id(inserted_community.id)
+      .my_person_id(inserted_person.id)
+      .list()
+      .unwrap();
+    CommunityModerator::leave(&conn, &moderator_form).unwrap();
+    CommunityFollower::unfollow(&conn, &follower_form).unwrap();
+
+    // Pending posts are also only listed for their creator, and in the queue of the mods
+    Post::update_pending(&conn, inserted_bot_post.id, true).unwrap();
+    let read_post_listings_pending_creator = PostQueryBuilder::create(&conn)
+      .community_id(inserted_community.id)
+      .my_person_id(inserted_bot.id)
+      .list()
+      .unwrap();
+    let read_post_listings_pending_other = PostQueryBuilder::create(&conn)
+      .community_id(inserted_community.id)
+      .my_person_id(inserted_person.id)
+      .list()
+      .unwrap();
+    let read_post_listings_pending_only = PostQueryBuilder::create(&conn)
+      .community_id(inserted_community.id)
+      .my_person_id(inserted_bot.id)
+      .pending_only(true)
+      .list()
+      .unwrap();
+    Post::update_pending(&conn, inserted_bot_post.id, false).unwrap();
+
+    // Only the tagged post is listed when filtering by tag
+    let tag_form = PostTagForm {
+      community_id: inserted_community.id,
+      name: "Discussion".to_string(),
+      updated: None,
+    };
+    let inserted_tag = PostTag::create(&conn, &tag_form).unwrap();
+    PostTagAssignment::replace_for_post(&conn, inserted_post.id, &[inserted_tag.id]).unwrap();
+    let read_post_listings_tagged = PostQueryBuilder::create(&conn)
+      .community_id(inserted_community.id)
+      .tag_id(inserted_tag.id)
+      .list()
+      .unwrap();
+
+    // A multi-community lists the posts of its communities, an empty one lists nothing
+    let multi_form = MultiCommunityForm {
+      person_id: inserted_person.id,
+      name: "Everything".to_string(),
+      updated: None,
+    };
+    let inserted_multi = MultiCommunity::create(&conn, &multi_form).unwrap();
+    let multi_query = || {
+      PostQueryBuilder::create(&conn)
+        .multi_community_id(inserted_multi.id)
+        .show_bot_accounts(false)
+        .list()
+        .unwrap()
+    };
+    let read_post_listings_empty_multi = multi_query();
+    MultiCommunity::replace_communities(&conn, inserted_multi.id, &[inserted_community.id])
+      .unwrap();
+    let read_post_listings_multi = multi_query();
+    let read_post_listings_published_after = PostQueryBuilder::create(&conn)
+      .community_id(inserted_community.id)
+      .published_after(inserted_post.published)
+      .list()
+      .unwrap();
+    let read_post_listings_community = PostQueryBuilder::create(&conn)
+      .community_id(inserted_community.id)
+      .show_bot_accounts(false)
+      .list()
+      .unwrap();
+
+    // The post disappears from the unread posts once it is marked as read
+    let unread_query = || {
+      PostQueryBuilder::create(&conn)
+        .community_id(inserted_community.id)
+        .show_bot_accounts(false)
+        .my_person_id(inserted_person.id)
+        .unread_only(true)
+        .list()
+        .unwrap()
+    };
+    let read_post_listings_unread = unread_query();
+    let post_read_form = PostReadForm {
+      post_id: inserted_post.id,
+      person_id: inserted_person.id,
+    };
+    PostRead::mark_as_read(&conn, &post_read_form).unwrap();
+    let read_post_listings_unread_after_read = unread_query();
+
+    // The original post and its cross-post are listed together
+    let cross_post_form = PostForm {
+      name: "A cross-post of the test post".into(),
+      creator_id: inserted_person.id,
+      community_id: inserted_community.id,
+      cross_post_of: Some(inserted_post.id),
+      ..PostForm::default()
+    };
+    let inserted_cross_post = Post::create(&conn, &cross_post_form).unwrap();
+    let read_post_listings_cross_posts = PostQueryBuilder::create(&conn)
+      .sort(SortType::New)
+      .cross_posts_of(inserted_post.id)
+      .list()
+      .unwrap();
+    let read_post_listings_hidden_scores = PostQueryBuilder::create(&conn)
+      .sort(SortType::New)
+      .cross_posts_of(inserted_post.id)
+      .show_scores(false)
+      .list()
+      .unwrap();
+    // Reading a single post applies the preference of the person
+    let local_user_form = LocalUserForm {
+      person_id: Some(inserted_person.id),
+      password_encrypted: Some("tegan_pass".to_string()),
+      show_scores: Some(false),
+      ..LocalUserForm::default()
+    };
+    LocalUser::create(&conn, &local_user_form).unwrap();
+    let read_post_hidden_scores =
+      PostView::read(&conn, inserted_post.id, Some(inserted_person.id)).unwrap();
+
     // Test a community block
     let community_block = CommunityBlockForm {
       person_id: inserted_person.id,
@@ -704,6 +1192,28 @@ mod tests {
       .list()
       .unwrap();
 
+    // Test an instance block, which hides the posts of the community on that instance
+    CommunityBlock::unblock(&conn, &community_block).unwrap();
+    let instance_block = PersonInstanceBlockForm {
+      person_id: inserted_person.id,
+      domain: Url::parse(&inserted_community.actor_id.to_string())
+        .unwrap()
+        .domain()
+        .unwrap()
+        .to_string(),
+    };
+    PersonInstanceBlock::block(&conn, &instance_block).unwrap();
+
+    let read_post_listings_with_person_after_instance_block = PostQueryBuilder::create(&conn)
+      .listing_type(ListingType::Community)
+      .sort(SortType::New)
+      .show_bot_accounts(false)
+      .community_id(inserted_community.id)
+      .my_person_id(inserted_person.id)
+      .list()
+      .unwrap();
+    PersonInstanceBlock::unblock(&conn, &instance_block).unwrap();
+
     // TODO More needs to be added here
     let mut expected_post_listing_with_user = expected_post_listing_no_person.to_owned();
     expected_post_listing_with_user.my_vote = Some(1);
@@ -740,11 +1250,308 @@ mod tests {
     // Should be 2 posts, with the bot post, and the blocked
     assert_eq!(3, read_post_listings_no_person.len());
 
+    // The shadow removed bot post is only visible to the bot itself and to mods
+    assert_eq!(2, read_post_listings_shadow_removed_no_person.len());
+    assert_eq!(3, read_post_listings_shadow_removed_creator.len());
+    assert!(read_post_listings_shadow_removed_follower
+      .iter()
+      .all(|p| p.post.id != inserted_bot_post.id));
+    assert!(read_post_listings_shadow_removed_moderator
+      .iter()
+      .any(|p| p.post.id == inserted_bot_post.id));
+
+    assert_eq!(3, read_post_listings_pending_creator.len());
+    assert!(read_post_listings_pending_other
+      .iter()
+      .all(|p| p.post.id != inserted_bot_post.id));
+    assert_eq!(1, read_post_listings_pending_only.len());
+    assert_eq!(
+      inserted_bot_post.id,
+      read_post_listings_pending_only[0].post.id
+    );
+
+    assert_eq!(1, read_post_listings_tagged.len());
+    assert_eq!(inserted_post.id, read_post_listings_tagged[0].post.id);
+
+    assert!(!read_post_listings_published_after
+      .iter()
+      .any(|p| p.post.id == inserted_post.id));
+    assert_eq!(0, read_post_listings_empty_multi.len());
+    assert_eq!(
+      read_post_listings_community.len(),
+      read_post_listings_multi.len()
+    );
+    assert!(read_post_listings_multi
+      .iter()
+      .any(|p| p.post.id == inserted_post.id));
+
+    assert_eq!(1, read_post_listings_unread.len());
+    assert_eq!(0, read_post_listings_unread_after_read.len());
+
+    assert_eq!(2, read_post_listings_cross_posts.len());
+    assert_eq!(
+      inserted_cross_post.id,
+      read_post_listings_cross_posts[0].post.id
+    );
+    assert_eq!(inserted_post.id, read_post_listings_cross_posts[1].post.id);
+
+    assert_eq!(1, read_post_listings_cross_posts[1].counts.score);
+    assert_eq!(0, read_post_listings_hidden_scores[1].counts.score);
+    assert_eq!(0, read_post_listings_hidden_scores[1].counts.upvotes);
+    assert_eq!(0, read_post_hidden_scores.counts.score);
+
     // Should be 0 posts after the community block
     assert_eq!(0, read_post_listings_with_person_after_block.len());
+    assert_eq!(0, read_post_listings_with_person_after_instance_block.len());
 
     assert_eq!(expected_post_like, inserted_post_like);
     assert_eq!(1, like_removed);
     assert_eq!(1, num_deleted);
   }
+
+  #[test]
+  #[serial]
+  fn test_stickied_order() {
+    let conn = establish_unpooled_connection();
+
+    let person_form = PersonForm {
+      name: "stickied_order_person".into(),
+      ..PersonForm::default()
+    };
+    let inserted_person = Person::create(&conn, &person_form).unwrap();
+    let community_form = CommunityForm {
+      name: "stickied_order".to_string(),
+      title: "nada".to_owned(),
+      ..CommunityForm::default()
+    };
+    let inserted_community = Community::create(&conn, &community_form).unwrap();
+
+    let create_post = |name: &str| {
+      let form = PostForm {
+        name: name.into(),
+        creator_id: inserted_person.id,
+        community_id: inserted_community.id,
+        ..PostForm::default()
+      };
+      Post::create(&conn, &form).unwrap().id
+    };
+    let first = create_post("first");
+    let second = create_post("second");
+    let third = create_post("third");
+
+    // Stickied posts come first, in the order they were stickied
+    Post::update_stickied(&conn, third, true).unwrap();
+    Post::update_stickied(&conn, first, true).unwrap();
+    let stickied_count = Post::count_stickied(&conn, inserted_community.id).unwrap();
+    let listed = PostQueryBuilder::create(&conn)
+      .sort(SortType::New)
+      .community_id(inserted_community.id)
+      .list()
+      .unwrap()
+      .into_iter()
+      .map(|p| p.post.id)
+      .collect::<Vec<_>>();
+
+    Post::update_stickied(&conn, third, false).unwrap();
+    let unstickied_aggregates = PostAggregates::read(&conn, third).unwrap();
+
+    Community::delete(&conn, inserted_community.id).unwrap();
+    Person::delete(&conn, inserted_person.id).unwrap();
+
+    assert_eq!(2, stickied_count);
+    assert_eq!(vec![third, first, second], listed);
+    assert!(!unstickied_aggregates.stickied);
+    assert_eq!(None, unstickied_aggregates.stickied_at);
+  }
+
+  #[test]
+  #[serial]
+  fn test_language_filter() {
+    let conn = establish_unpooled_connection();
+
+    let person_form = PersonForm {
+      name: "language_filter_person".into(),
+      ..PersonForm::default()
+    };
+    let inserted_person = Person::create(&conn, &person_form).unwrap();
+    let community_form = CommunityForm {
+      name: "language_filter".to_string(),
+      title: "nada".to_owned(),
+      ..CommunityForm::default()
+    };
+    let inserted_community = Community::create(&conn, &community_form).unwrap();
+
+    let english = Language::read_id_from_code(&conn, "en").unwrap();
+    let german = Language::read_id_from_code(&conn, "de").unwrap();
+    let create_post = |language_id: LanguageId| {
+      let form = PostForm {
+        name: "language post".into(),
+        creator_id: inserted_person.id,
+        community_id: inserted_community.id,
+        language_id: Some(language_id),
+        ..PostForm::default()
+      };
+      Post::create(&conn, &form).unwrap().id
+    };
+    let undetermined_post = create_post(LanguageId::default());
+    let english_post = create_post(english);
+    let german_post = create_post(german);
+
+    let list = |languages: Vec<LanguageId>| {
+      PostQueryBuilder::create(&conn)
+        .sort(SortType::New)
+        .community_id(inserted_community.id)
+        .languages(languages)
+        .list()
+        .unwrap()
+        .into_iter()
+        .map(|p| p.post.id)
+        .collect::<Vec<_>>()
+    };
+    let all = list(vec![]);
+    let english_only = list(vec![english]);
+    CommunityLanguage::replace(&conn, inserted_community.id, &[german]).unwrap();
+    let community_german = list(vec![]);
+
+    Community::delete(&conn, inserted_community.id).unwrap();
+    Person::delete(&conn, inserted_person.id).unwrap();
+
+    assert_eq!(vec![german_post, english_post, undetermined_post], all);
+    assert_eq!(vec![english_post, undetermined_post], english_only);
+    assert_eq!(vec![german_post, undetermined_post], community_german);
+  }
+
+  #[test]
+  #[serial]
+  fn test_full_text_search() {
+    let conn = establish_unpooled_connection();
+
+    let person_form = PersonForm {
+      name: "full_text_search_person".into(),
+      ..PersonForm::default()
+    };
+    let inserted_person = Person::create(&conn, &person_form).unwrap();
+    let community_form = CommunityForm {
+      name: "full_text_search".to_string(),
+      title: "nada".to_owned(),
+      ..CommunityForm::default()
+    };
+    let inserted_community = Community::create(&conn, &community_form).unwrap();
+
+    let english = Language::read_id_from_code(&conn, "en").unwrap();
+    let create_post = |name: &str, body: &str, language_id: LanguageId| {
+      let form = PostForm {
+        name: name.into(),
+        body: Some(body.into()),
+        creator_id: inserted_person.id,
+        community_id: inserted_community.id,
+        language_id: Some(language_id),
+        ..PostForm::default()
+      };
+      Post::create(&conn, &form).unwrap().id
+    };
+    let body_post = create_post("Weekend plans", "Going for a run in the park", english);
+    let title_post = create_post("Running shoes", "Which ones do you like?", english);
+    let undetermined_post = create_post("Laufschuhe", "Welche sind gut?", LanguageId::default());
+
+    let search = |term: &str, sort: Option<SortType>| {
+      PostQueryBuilder::create(&conn)
+        .community_id(inserted_community.id)
+        .search_term(term.to_string())
+        .sort(sort)
+        .list()
+        .unwrap()
+        .into_iter()
+        .map(|p| p.post.id)
+        .collect::<Vec<_>>()
+    };
+    // Words are stemmed, and matches in the title rank higher
+    let stemmed = search("runs", None);
+    let stemmed_new = search("runs", Some(SortType::New));
+    // Languages without a config are matched by their plain words
+    let plain = search("laufschuhe", None);
+    let excluded = search("running -shoes", None);
+    let snippets = PostQueryBuilder::create(&conn)
+      .community_id(inserted_community.id)
+      .search_term("runs".to_string())
+      .list()
+      .unwrap()
+      .into_iter()
+      .map(|p| p.snippet)
+      .collect::<Vec<_>>();
+
+    Community::delete(&conn, inserted_community.id).unwrap();
+    Person::delete(&conn, inserted_person.id).unwrap();
+
+    assert_eq!(vec![title_post, body_post], stemmed);
+    assert_eq!(vec![title_post, body_post], stemmed_new);
+    assert_eq!(vec![undetermined_post], plain);
+    assert_eq!(vec![body_post], excluded);
+    assert_eq!(
+      vec![
+        Some("Which ones do you like?".to_string()),
+        Some("Going for a **run** in the park".to_string())
+      ],
+      snippets
+    );
+  }
+
+  #[test]
+  #[serial]
+  fn test_search_filters() {
+    let conn = establish_unpooled_connection();
+
+    let person_form = PersonForm {
+      name: "search_filters_person".into(),
+      ..PersonForm::default()
+    };
+    let inserted_person = Person::create(&conn, &person_form).unwrap();
+    let community_form = CommunityForm {
+      name: "search_filters".to_string(),
+      title: "nada".to_owned(),
+      ..CommunityForm::default()
+    };
+    let inserted_community = Community::create(&conn, &community_form).unwrap();
+
+    let create_post = |url: Option<&str>| {
+      let form = PostForm {
+        name: "search filter post".into(),
+        url: url.map(|u| Url::parse(u).unwrap().into()),
+        creator_id: inserted_person.id,
+        community_id: inserted_community.id,
+        ..PostForm::default()
+      };
+      Post::create(&conn, &form).unwrap()
+    };
+    let domain_post = create_post(Some("https://example.com/article"));
+    let subdomain_post = create_post(Some("https://news.example.com"));
+    let other_domain_post = create_post(Some("https://notexample.com/article"));
+    let text_post = create_post(None);
+
+    let list = |query: PostQueryBuilder| {
+      query
+        .community_id(inserted_community.id)
+        .sort(SortType::New)
+        .list()
+        .unwrap()
+        .into_iter()
+        .map(|p| p.post.id)
+        .collect::<Vec<_>>()
+    };
+    let domain = list(PostQueryBuilder::create(&conn).url_domain("example.com".to_string()));
+    let before =
+      list(PostQueryBuilder::create(&conn).published_before(other_domain_post.published));
+    let between = list(
+      PostQueryBuilder::create(&conn)
+        .published_after(domain_post.published)
+        .published_before(text_post.published),
+    );
+
+    Community::delete(&conn, inserted_community.id).unwrap();
+    Person::delete(&conn, inserted_person.id).unwrap();
+
+    assert_eq!(vec![subdomain_post.id, domain_post.id], domain);
+    assert_eq!(vec![subdomain_post.id, domain_post.id], before);
+    assert_eq!(vec![other_domain_post.id, subdomain_post.id], between);
+  }
 }
@@ -0,0 +1,157 @@
+use diesel::{result::Error, *};
+use lemmy_db_schema::{
+  limit_and_offset,
+  newtypes::PersonId,
+  schema::{person, private_message, private_message_thread},
+  source::{
+    person::{Person, PersonSafe},
+    private_message_thread::PrivateMessageThread,
+  },
+  traits::ToSafe,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone)]
+pub struct PrivateMessageThreadView {
+  pub thread: PrivateMessageThread,
+  /// The participant who isn't the current user
+  pub other_person: PersonSafe,
+  /// Messages in the thread which were sent to the current user and not read yet
+  pub unread_count: i64,
+}
+
+impl PrivateMessageThreadView {
+  pub fn read(conn: &PgConnection, thread_id: i32, my_person_id: PersonId) -> Result<Self, Error> {
+    let thread = PrivateMessageThread::read(conn, thread_id)?;
+    if !thread.is_participant(my_person_id) {
+      return Err(Error::NotFound);
+    }
+    Ok(Self::from_threads(conn, vec![thread], my_person_id)?.remove(0))
+  }
+
+  /// The threads of the person, with the most recently active first
+  pub fn list_for_person(
+    conn: &PgConnection,
+    my_person_id: PersonId,
+    page: Option<i64>,
+    limit: Option<i64>,
+  ) -> Result<Vec<Self>, Error> {
+    let (limit, offset) = limit_and_offset(page, limit);
+    let threads = private_message_thread::table
+      .filter(
+        private_message_thread::person_a_id
+          .eq(my_person_id)
+          .or(private_message_thread::person_b_id.eq(my_person_id)),
+      )
+      .order_by(private_message_thread::newest_message_time.desc())
+      .then_order_by(private_message_thread::id.desc())
+      .limit(limit)
+      .offset(offset)
+      .load::<PrivateMessageThread>(conn)?;
+    Self::from_threads(conn, threads, my_person_id)
+  }
+
+  fn from_threads(
+    conn: &PgConnection,
+    threads: Vec<PrivateMessageThread>,
+    my_person_id: PersonId,
+  ) -> Result<Vec<Self>, Error> {
+    let thread_ids: Vec<i32> = threads.iter().map(|t| t.id).collect();
+    let other_person_ids: Vec<PersonId> = threads
+      .iter()
+      .map(|t| t.other_person_id(my_person_id))
+      .collect();
+
+    let persons: HashMap<PersonId, PersonSafe> = person::table
+      .filter(person::id.eq_any(other_person_ids))
+      .select(Person::safe_columns_tuple())
+      .load::<PersonSafe>(conn)?
+      .into_iter()
+      .map(|p| (p.id, p))
+      .collect();
+    // Diesel can't combine group by with a count, so the unread messages are counted here
+    let mut unread_counts: HashMap<i32, i64> = HashMap::new();
+    for thread_id in private_message::table
+      .filter(private_message::thread_id.eq_any(thread_ids))
+      .filter(private_message::recipient_id.eq(my_person_id))
+      .filter(private_message::read.eq(false))
+      .filter(private_message::deleted.eq(false))
+      .select(private_message::thread_id)
+      .load::<i32>(conn)?
+    {
+      *unread_counts.entry(thread_id).or_insert(0) += 1;
+    }
+
+    threads
+      .into_iter()
+      .map(|thread| {
+        let other_person = persons
+          .get(&thread.other_person_id(my_person_id))
+          .cloned()
+          .ok_or(Error::NotFound)?;
+        Ok(PrivateMessageThreadView {
+          unread_count: unread_counts.get(&thread.id).copied().unwrap_or(0),
+          thread,
+          other_person,
+        })
+      })
+      .collect()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::private_message_thread_view::PrivateMessageThreadView;
+  use lemmy_db_schema::{
+    establish_unpooled_connection,
+    source::{person::*, private_message::*},
+    traits::Crud,
+  };
+  use serial_test::serial;
+
+  #[test]
+  #[serial]
+  fn test_private_message_thread_view() {
+    let conn = establish_unpooled_connection();
+
+    let person_form = |name: &str| PersonForm {
+      name: name.into(),
+      ..PersonForm::default()
+    };
+    let alice = Person::create(&conn, &person_form("thread_view_alice")).unwrap();
+    let bob = Person::create(&conn, &person_form("thread_view_bob")).unwrap();
+    let carol = Person::create(&conn, &person_form("thread_view_carol")).unwrap();
+
+    let message_form = |creator_id, recipient_id| PrivateMessageForm {
+      content: "A test private message".into(),
+      creator_id,
+      recipient_id,
+      ..PrivateMessageForm::default()
+    };
+    let from_bob = PrivateMessage::create(&conn, &message_form(bob.id, alice.id)).unwrap();
+    PrivateMessage::create(&conn, &message_form(bob.id, alice.id)).unwrap();
+    PrivateMessage::create(&conn, &message_form(alice.id, bob.id)).unwrap();
+    let from_carol = PrivateMessage::create(&conn, &message_form(carol.id, alice.id)).unwrap();
+    PrivateMessage::update_read(&conn, from_carol.id, true).unwrap();
+
+    let threads = PrivateMessageThreadView::list_for_person(&conn, alice.id, None, None).unwrap();
+    let bob_thread = PrivateMessageThreadView::read(&conn, from_bob.thread_id, bob.id).unwrap();
+    let not_participant = PrivateMessageThreadView::read(&conn, from_bob.thread_id, carol.id);
+
+    Person::delete(&conn, alice.id).unwrap();
+    Person::delete(&conn, bob.id).unwrap();
+    Person::delete(&conn, carol.id).unwrap();
+
+    // Most recently active first
+    assert_eq!(2, threads.len());
+    assert_eq!(carol.id, threads[0].other_person.id);
+    assert_eq!(0, threads[0].unread_count);
+    assert_eq!(bob.id, threads[1].other_person.id);
+    assert_eq!(2, threads[1].unread_count);
+
+    assert_eq!(alice.id, bob_thread.other_person.id);
+    assert_eq!(1, bob_thread.unread_count);
+    assert!(not_participant.is_err());
+  }
+}
@@ -58,6 +58,7 @@ pub struct PrivateMessageQueryBuilder<'a> {
   conn: &'a PgConnection,
   recipient_id: PersonId,
   unread_only: Option<bool>,
+  thread_id: Option<i32>,
   page: Option<i64>,
   limit: Option<i64>,
 }
@@ -68,6 +69,7 @@ impl<'a> PrivateMessageQueryBuilder<'a> {
       conn,
       recipient_id,
       unread_only: None,
+      thread_id: None,
       page: None,
       limit: None,
     }
@@ -78,6 +80,11 @@ impl<'a> PrivateMessageQueryBuilder<'a> {
     self
   }
 
+  pub fn thread_id<T: MaybeOptional<i32>>(mut self, thread_id: T) -> Self {
+    self.thread_id = thread_id.get_optional();
+    self
+  }
+
   pub fn page<T: MaybeOptional<i64>>(mut self, page: T) -> Self {
     self.page = page.get_optional();
     self
@@ -114,6 +121,10 @@ impl<'a> PrivateMessageQueryBuilder<'a> {
       )
     }
 
+    if let Some(thread_id) = self.thread_id {
+      query = query.filter(private_message::thread_id.eq(thread_id));
+    }
+
     let (limit, offset) = limit_and_offset(self.page, self.limit);
 
     query = query
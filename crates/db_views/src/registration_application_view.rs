@@ -57,8 +57,8 @@ impl RegistrationApplicationView {
   }
 
   /// Returns the current unread registration_application count
-  pub fn get_unread_count(conn: &PgConnection, verified_email_only: bool) -> Result<i64, Error> {
-    let mut query = registration_application::table
+  pub fn get_unread_count(conn: &PgConnection) -> Result<i64, Error> {
+    registration_application::table
       .inner_join(local_user::table.on(registration_application::local_user_id.eq(local_user::id)))
       .inner_join(person::table.on(local_user::person_id.eq(person::id)))
       .left_join(
@@ -66,13 +66,6 @@ impl RegistrationApplicationView {
           .on(registration_application::admin_id.eq(person_alias_1::id.nullable())),
       )
       .filter(registration_application::admin_id.is_null())
-      .into_boxed();
-
-    if verified_email_only {
-      query = query.filter(local_user::email_verified.eq(true))
-    }
-
-    query
       .select(count(registration_application::id))
       .first::<i64>(conn)
   }
@@ -81,7 +74,6 @@ impl RegistrationApplicationView {
 pub struct RegistrationApplicationQueryBuilder<'a> {
   conn: &'a PgConnection,
   unread_only: Option<bool>,
-  verified_email_only: Option<bool>,
   page: Option<i64>,
   limit: Option<i64>,
 }
@@ -91,7 +83,6 @@ impl<'a> RegistrationApplicationQueryBuilder<'a> {
     RegistrationApplicationQueryBuilder {
       conn,
       unread_only: None,
-      verified_email_only: None,
       page: None,
       limit: None,
     }
@@ -102,11 +93,6 @@ impl<'a> RegistrationApplicationQueryBuilder<'a> {
     self
   }
 
-  pub fn verified_email_only<T: MaybeOptional<bool>>(mut self, verified_email_only: T) -> Self {
-    self.verified_email_only = verified_email_only.get_optional();
-    self
-  }
-
   pub fn page<T: MaybeOptional<i64>>(mut self, page: T) -> Self {
     self.page = page.get_optional();
     self
@@ -138,10 +124,6 @@ impl<'a> RegistrationApplicationQueryBuilder<'a> {
       query = query.filter(registration_application::admin_id.is_null())
     }
 
-    if self.verified_email_only.unwrap_or(false) {
-      query = query.filter(local_user::email_verified.eq(true))
-    }
-
     let (limit, offset) = limit_and_offset(self.page, self.limit);
 
     query = query
@@ -280,6 +262,8 @@ mod tests {
         show_new_post_notifs: inserted_sara_local_user.show_new_post_notifs,
         email_verified: inserted_sara_local_user.email_verified,
         accepted_application: inserted_sara_local_user.accepted_application,
+        totp_enabled: inserted_sara_local_user.totp_enabled,
+        email_digest_frequency: inserted_sara_local_user.email_digest_frequency.clone(),
       },
       creator: PersonSafe {
         id: inserted_sara_person.id,
@@ -320,7 +304,7 @@ mod tests {
     );
 
     // Make sure the counts are correct
-    let unread_count = RegistrationApplicationView::get_unread_count(&conn, false).unwrap();
+    let unread_count = RegistrationApplicationView::get_unread_count(&conn).unwrap();
     assert_eq!(unread_count, 2);
 
     // Approve the application
@@ -379,8 +363,7 @@ mod tests {
     assert_eq!(apps_after_resolve, vec![read_jess_app_view]);
 
     // Make sure the counts are correct
-    let unread_count_after_approve =
-      RegistrationApplicationView::get_unread_count(&conn, false).unwrap();
+    let unread_count_after_approve = RegistrationApplicationView::get_unread_count(&conn).unwrap();
     assert_eq!(unread_count_after_approve, 1);
 
     // Make sure the not undenied_only has all the apps
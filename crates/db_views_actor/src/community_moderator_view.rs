@@ -28,7 +28,7 @@ impl CommunityModeratorView {
         Person::safe_columns_tuple(),
       ))
       .filter(community_moderator::community_id.eq(community_id))
-      .order_by(community_moderator::published)
+      .order_by(community_moderator::rank)
       .load::<CommunityModeratorViewTuple>(conn)?;
 
     Ok(Self::from_tuple_to_vec(res))
@@ -62,10 +62,7 @@ impl CommunityModeratorView {
       // A hacky workaround instead of group_bys
       // https://stackoverflow.com/questions/24042359/how-to-join-only-one-row-in-joined-table-with-postgres
       .distinct_on(community_moderator::community_id)
-      .order_by((
-        community_moderator::community_id,
-        community_moderator::person_id,
-      ))
+      .order_by((community_moderator::community_id, community_moderator::rank))
       .load::<CommunityModeratorViewTuple>(conn)?;
 
     Ok(Self::from_tuple_to_vec(res))
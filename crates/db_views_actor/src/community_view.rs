@@ -6,10 +6,18 @@ use lemmy_db_schema::{
   fuzzy_search,
   limit_and_offset,
   newtypes::{CommunityId, PersonId},
-  schema::{community, community_aggregates, community_block, community_follower},
+  schema::{
+    community,
+    community_aggregates,
+    community_block,
+    community_category,
+    community_follower,
+  },
   source::{
     community::{Community, CommunityFollower, CommunitySafe},
     community_block::CommunityBlock,
+    community_category::CommunityCategory,
+    person_instance_block::PersonInstanceBlock,
   },
   traits::{MaybeOptional, ToSafe, ViewToVec},
   ListingType,
@@ -23,6 +31,7 @@ pub struct CommunityView {
   pub subscribed: bool,
   pub blocked: bool,
   pub counts: CommunityAggregates,
+  pub category: Option<CommunityCategory>,
 }
 
 type CommunityViewTuple = (
@@ -30,6 +39,7 @@ type CommunityViewTuple = (
   CommunityAggregates,
   Option<CommunityFollower>,
   Option<CommunityBlock>,
+  Option<CommunityCategory>,
 );
 
 impl CommunityView {
@@ -41,7 +51,7 @@ impl CommunityView {
     // The left join below will return None in this case
     let person_id_join = my_person_id.unwrap_or(PersonId(-1));
 
-    let (community, counts, follower, blocked) = community::table
+    let (community, counts, follower, blocked, category) = community::table
       .find(community_id)
       .inner_join(community_aggregates::table)
       .left_join(
@@ -58,11 +68,13 @@ impl CommunityView {
             .and(community_block::person_id.eq(person_id_join)),
         ),
       )
+      .left_join(community_category::table)
       .select((
         Community::safe_columns_tuple(),
         community_aggregates::all_columns,
         community_follower::all_columns.nullable(),
         community_block::all_columns.nullable(),
+        community_category::all_columns.nullable(),
       ))
       .first::<CommunityViewTuple>(conn)?;
 
@@ -71,6 +83,7 @@ impl CommunityView {
       subscribed: follower.is_some(),
       blocked: blocked.is_some(),
       counts,
+      category,
     })
   }
 
@@ -108,6 +121,7 @@ pub struct CommunityQueryBuilder<'a> {
   my_person_id: Option<PersonId>,
   show_nsfw: Option<bool>,
   search_term: Option<String>,
+  category_id: Option<i32>,
   page: Option<i64>,
   limit: Option<i64>,
 }
@@ -121,6 +135,7 @@ impl<'a> CommunityQueryBuilder<'a> {
       sort: None,
       show_nsfw: None,
       search_term: None,
+      category_id: None,
       page: None,
       limit: None,
     }
@@ -146,6 +161,11 @@ impl<'a> CommunityQueryBuilder<'a> {
     self
   }
 
+  pub fn category_id<T: MaybeOptional<i32>>(mut self, category_id: T) -> Self {
+    self.category_id = category_id.get_optional();
+    self
+  }
+
   pub fn my_person_id<T: MaybeOptional<PersonId>>(mut self, my_person_id: T) -> Self {
     self.my_person_id = my_person_id.get_optional();
     self
@@ -181,11 +201,13 @@ impl<'a> CommunityQueryBuilder<'a> {
             .and(community_block::person_id.eq(person_id_join)),
         ),
       )
+      .left_join(community_category::table)
       .select((
         Community::safe_columns_tuple(),
         community_aggregates::all_columns,
         community_follower::all_columns.nullable(),
         community_block::all_columns.nullable(),
+        community_category::all_columns.nullable(),
       ))
       .into_boxed();
 
@@ -197,10 +219,19 @@ impl<'a> CommunityQueryBuilder<'a> {
         .or_filter(community::description.ilike(searcher));
     };
 
+    if let Some(category_id) = self.category_id {
+      query = query.filter(community::category_id.eq(category_id));
+    }
+
     match self.sort.unwrap_or(SortType::Hot) {
       SortType::New => query = query.order_by(community::published.desc()),
       SortType::TopAll => query = query.order_by(community_aggregates::subscribers.desc()),
       SortType::TopMonth => query = query.order_by(community_aggregates::users_active_month.desc()),
+      SortType::Trending => {
+        query = query
+          .order_by(community_aggregates::trending_score.desc())
+          .then_order_by(community_aggregates::subscribers.desc())
+      }
       // Covers all other sorts, including hot
       _ => {
         query = query
@@ -227,9 +258,12 @@ impl<'a> CommunityQueryBuilder<'a> {
       };
     }
 
-    // Don't show blocked communities
-    if self.my_person_id.is_some() {
+    // Don't show blocked communities, or communities on blocked instances
+    if let Some(my_person_id) = self.my_person_id {
       query = query.filter(community_block::person_id.is_null());
+      for pattern in PersonInstanceBlock::actor_id_patterns(self.conn, my_person_id)? {
+        query = query.filter(community::actor_id.not_like(pattern));
+      }
     }
 
     let (limit, offset) = limit_and_offset(self.page, self.limit);
@@ -254,6 +288,7 @@ impl ViewToVec for CommunityView {
         counts: a.1.to_owned(),
         subscribed: a.2.is_some(),
         blocked: a.3.is_some(),
+        category: a.4.to_owned(),
       })
       .collect::<Vec<Self>>()
   }
@@ -3,6 +3,8 @@ pub mod community_follower_view;
 pub mod community_moderator_view;
 pub mod community_person_ban_view;
 pub mod community_view;
+pub mod multi_community_view;
 pub mod person_block_view;
 pub mod person_mention_view;
+pub mod person_stats_view;
 pub mod person_view;
@@ -0,0 +1,64 @@
+use diesel::{result::Error, *};
+use lemmy_db_schema::{
+  newtypes::{MultiCommunityId, PersonId},
+  schema::{community, multi_community_community},
+  source::{
+    community::{Community, CommunitySafe},
+    multi_community::MultiCommunity,
+  },
+  traits::{Crud, ToSafe},
+};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct MultiCommunityView {
+  pub multi_community: MultiCommunity,
+  pub communities: Vec<CommunitySafe>,
+}
+
+impl MultiCommunityView {
+  pub fn read(conn: &PgConnection, multi_community_id: MultiCommunityId) -> Result<Self, Error> {
+    let multi_community = MultiCommunity::read(conn, multi_community_id)?;
+    let communities = multi_community_community::table
+      .inner_join(community::table)
+      .select(Community::safe_columns_tuple())
+      .filter(multi_community_community::multi_community_id.eq(multi_community_id))
+      .order_by(community::title)
+      .load::<CommunitySafe>(conn)?;
+    Ok(MultiCommunityView {
+      multi_community,
+      communities,
+    })
+  }
+
+  pub fn list_for_person(conn: &PgConnection, person_id: PersonId) -> Result<Vec<Self>, Error> {
+    let multi_communities = MultiCommunity::list_for_person(conn, person_id)?;
+    let ids: Vec<MultiCommunityId> = multi_communities.iter().map(|m| m.id).collect();
+    let entries = multi_community_community::table
+      .inner_join(community::table)
+      .select((
+        multi_community_community::multi_community_id,
+        Community::safe_columns_tuple(),
+      ))
+      .filter(multi_community_community::multi_community_id.eq_any(ids))
+      .order_by(community::title)
+      .load::<(MultiCommunityId, CommunitySafe)>(conn)?;
+
+    Ok(
+      multi_communities
+        .into_iter()
+        .map(|multi_community| {
+          let communities = entries
+            .iter()
+            .filter(|(id, _)| *id == multi_community.id)
+            .map(|(_, c)| c.to_owned())
+            .collect();
+          MultiCommunityView {
+            multi_community,
+            communities,
+          }
+        })
+        .collect(),
+    )
+  }
+}
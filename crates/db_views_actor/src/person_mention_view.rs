@@ -1,7 +1,7 @@
 use diesel::{result::Error, *};
 use lemmy_db_schema::{
   aggregates::comment_aggregates::CommentAggregates,
-  functions::hot_rank,
+  functions::controversy_rank,
   limit_and_offset,
   newtypes::{PersonId, PersonMentionId},
   schema::{
@@ -169,6 +169,13 @@ impl PersonMentionView {
       .select(count(person_mention::id))
       .first::<i64>(conn)
   }
+
+  /// Blanks out the votes, for users who don't want to see them
+  pub fn hide_scores(&mut self) {
+    self.counts.score = 0;
+    self.counts.upvotes = 0;
+    self.counts.downvotes = 0;
+  }
 }
 
 pub struct PersonMentionQueryBuilder<'a> {
@@ -177,6 +184,7 @@ pub struct PersonMentionQueryBuilder<'a> {
   recipient_id: Option<PersonId>,
   sort: Option<SortType>,
   unread_only: Option<bool>,
+  show_scores: Option<bool>,
   page: Option<i64>,
   limit: Option<i64>,
 }
@@ -189,6 +197,7 @@ impl<'a> PersonMentionQueryBuilder<'a> {
       recipient_id: None,
       sort: None,
       unread_only: None,
+      show_scores: None,
       page: None,
       limit: None,
     }
@@ -199,6 +208,11 @@ impl<'a> PersonMentionQueryBuilder<'a> {
     self
   }
 
+  pub fn show_scores<T: MaybeOptional<bool>>(mut self, show_scores: T) -> Self {
+    self.show_scores = show_scores.get_optional();
+    self
+  }
+
   pub fn unread_only<T: MaybeOptional<bool>>(mut self, unread_only: T) -> Self {
     self.unread_only = unread_only.get_optional();
     self
@@ -297,12 +311,17 @@ impl<'a> PersonMentionQueryBuilder<'a> {
     }
 
     query = match self.sort.unwrap_or(SortType::Hot) {
-      SortType::Hot | SortType::Active => query
-        .order_by(hot_rank(comment_aggregates::score, comment_aggregates::published).desc())
+      SortType::Hot | SortType::Active | SortType::Trending => query
+        .order_by(comment_aggregates::hot_rank.desc())
         .then_order_by(comment_aggregates::published.desc()),
       SortType::New | SortType::MostComments | SortType::NewComments => {
         query.order_by(comment::published.desc())
       }
+      SortType::Controversial => query
+        .order_by(
+          controversy_rank(comment_aggregates::upvotes, comment_aggregates::downvotes).desc(),
+        )
+        .then_order_by(comment_aggregates::published.desc()),
       SortType::TopAll => query.order_by(comment_aggregates::score.desc()),
       SortType::TopYear => query
         .filter(comment::published.gt(now - 1.years()))
@@ -325,7 +344,11 @@ impl<'a> PersonMentionQueryBuilder<'a> {
       .offset(offset)
       .load::<PersonMentionViewTuple>(self.conn)?;
 
-    Ok(PersonMentionView::from_tuple_to_vec(res))
+    let mut views = PersonMentionView::from_tuple_to_vec(res);
+    if !self.show_scores.unwrap_or(true) {
+      views.iter_mut().for_each(PersonMentionView::hide_scores);
+    }
+    Ok(views)
   }
 }
 
@@ -0,0 +1,77 @@
+use crate::person_view::PersonViewSafe;
+use diesel::{result::Error, *};
+use lemmy_db_schema::{
+  aggregates::{
+    person_aggregates::PersonAggregates,
+    person_community_karma::PersonCommunityKarma,
+    person_monthly_activity::PersonMonthlyActivity,
+  },
+  naive_now,
+  newtypes::PersonId,
+  schema::{community, person_community_karma},
+  source::community::{Community, CommunitySafe},
+  traits::{ToSafe, ViewToVec},
+};
+use serde::{Deserialize, Serialize};
+
+/// Only the communities where the person has the most karma are returned
+const MAX_KARMA_COMMUNITIES: i64 = 50;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PersonStatsView {
+  pub person_id: PersonId,
+  pub account_age_days: i64,
+  pub counts: PersonAggregates,
+  pub community_karma: Vec<PersonCommunityKarmaView>,
+  pub activity: Vec<PersonMonthlyActivity>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PersonCommunityKarmaView {
+  pub community: CommunitySafe,
+  pub karma: PersonCommunityKarma,
+}
+
+type PersonCommunityKarmaViewTuple = (CommunitySafe, PersonCommunityKarma);
+
+impl PersonStatsView {
+  /// Activity is counted per calendar month, for the last `months` months
+  pub fn read(conn: &PgConnection, person_id: PersonId, months: u32) -> Result<Self, Error> {
+    let person_view = PersonViewSafe::read(conn, person_id)?;
+
+    let community_karma = person_community_karma::table
+      .inner_join(community::table)
+      .select((
+        Community::safe_columns_tuple(),
+        person_community_karma::all_columns,
+      ))
+      .filter(person_community_karma::person_id.eq(person_id))
+      .order_by((person_community_karma::post_score + person_community_karma::comment_score).desc())
+      .then_order_by(community::id)
+      .limit(MAX_KARMA_COMMUNITIES)
+      .load::<PersonCommunityKarmaViewTuple>(conn)?;
+
+    let activity = PersonMonthlyActivity::list_for_person(conn, person_id, months)?;
+
+    Ok(PersonStatsView {
+      person_id,
+      account_age_days: (naive_now() - person_view.person.published).num_days(),
+      counts: person_view.counts,
+      community_karma: PersonCommunityKarmaView::from_tuple_to_vec(community_karma),
+      activity,
+    })
+  }
+}
+
+impl ViewToVec for PersonCommunityKarmaView {
+  type DbTuple = PersonCommunityKarmaViewTuple;
+  fn from_tuple_to_vec(items: Vec<Self::DbTuple>) -> Vec<Self> {
+    items
+      .iter()
+      .map(|a| Self {
+        community: a.0.to_owned(),
+        karma: a.1.to_owned(),
+      })
+      .collect::<Vec<Self>>()
+  }
+}
@@ -101,13 +101,13 @@ impl<'a> PersonQueryBuilder<'a> {
     }
 
     query = match self.sort.unwrap_or(SortType::Hot) {
-      SortType::Hot => query
+      SortType::Hot | SortType::Trending => query
         .order_by(person_aggregates::comment_score.desc())
         .then_order_by(person::published.desc()),
       SortType::Active => query
         .order_by(person_aggregates::comment_score.desc())
         .then_order_by(person::published.desc()),
-      SortType::New | SortType::MostComments | SortType::NewComments => {
+      SortType::New | SortType::MostComments | SortType::NewComments | SortType::Controversial => {
         query.order_by(person::published.desc())
       }
       SortType::TopAll => query.order_by(person_aggregates::comment_score.desc()),
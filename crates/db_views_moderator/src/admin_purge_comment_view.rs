@@ -0,0 +1,79 @@
+use diesel::{result::Error, *};
+use lemmy_db_schema::{
+  limit_and_offset,
+  newtypes::{CommunityId, PersonId},
+  schema::{admin_purge_comment, community, person, post},
+  source::{
+    community::{Community, CommunitySafe},
+    moderator::AdminPurgeComment,
+    person::{Person, PersonSafe},
+    post::Post,
+  },
+  traits::{ToSafe, ViewToVec},
+};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct AdminPurgeCommentView {
+  pub admin_purge_comment: AdminPurgeComment,
+  pub admin: PersonSafe,
+  pub post: Post,
+  pub community: CommunitySafe,
+}
+
+type AdminPurgeCommentViewTuple = (AdminPurgeComment, PersonSafe, Post, CommunitySafe);
+
+impl AdminPurgeCommentView {
+  pub fn list(
+    conn: &PgConnection,
+    community_id: Option<CommunityId>,
+    admin_person_id: Option<PersonId>,
+    page: Option<i64>,
+    limit: Option<i64>,
+  ) -> Result<Vec<Self>, Error> {
+    let mut query = admin_purge_comment::table
+      .inner_join(person::table.on(admin_purge_comment::admin_person_id.eq(person::id)))
+      .inner_join(post::table)
+      .inner_join(community::table.on(post::community_id.eq(community::id)))
+      .select((
+        admin_purge_comment::all_columns,
+        Person::safe_columns_tuple(),
+        post::all_columns,
+        Community::safe_columns_tuple(),
+      ))
+      .into_boxed();
+
+    if let Some(community_id) = community_id {
+      query = query.filter(post::community_id.eq(community_id));
+    };
+
+    if let Some(admin_person_id) = admin_person_id {
+      query = query.filter(admin_purge_comment::admin_person_id.eq(admin_person_id));
+    };
+
+    let (limit, offset) = limit_and_offset(page, limit);
+
+    let res = query
+      .limit(limit)
+      .offset(offset)
+      .order_by(admin_purge_comment::when_.desc())
+      .load::<AdminPurgeCommentViewTuple>(conn)?;
+
+    Ok(Self::from_tuple_to_vec(res))
+  }
+}
+
+impl ViewToVec for AdminPurgeCommentView {
+  type DbTuple = AdminPurgeCommentViewTuple;
+  fn from_tuple_to_vec(items: Vec<Self::DbTuple>) -> Vec<Self> {
+    items
+      .iter()
+      .map(|a| Self {
+        admin_purge_comment: a.0.to_owned(),
+        admin: a.1.to_owned(),
+        post: a.2.to_owned(),
+        community: a.3.to_owned(),
+      })
+      .collect::<Vec<Self>>()
+  }
+}
@@ -1,10 +1,18 @@
+pub mod admin_purge_comment_view;
+pub mod admin_purge_community_view;
+pub mod admin_purge_person_view;
+pub mod admin_purge_post_view;
 pub mod mod_add_community_view;
 pub mod mod_add_view;
+pub mod mod_automod_action_view;
 pub mod mod_ban_from_community_view;
 pub mod mod_ban_view;
 pub mod mod_lock_post_view;
 pub mod mod_remove_comment_view;
 pub mod mod_remove_community_view;
 pub mod mod_remove_post_view;
+pub mod mod_resolve_appeal_view;
+pub mod mod_shadow_remove_post_view;
 pub mod mod_sticky_post_view;
 pub mod mod_transfer_community_view;
+pub mod public_modlog_view;
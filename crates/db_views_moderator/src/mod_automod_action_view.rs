@@ -0,0 +1,91 @@
+use diesel::{result::Error, *};
+use lemmy_db_schema::{
+  limit_and_offset,
+  newtypes::{CommunityId, PersonId},
+  schema::{automod_rule, comment, community, mod_automod_action, post},
+  source::{
+    automod_rule::AutomodRule,
+    comment::Comment,
+    community::{Community, CommunitySafe},
+    moderator::ModAutomodAction,
+    post::Post,
+  },
+  traits::{ToSafe, ViewToVec},
+};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ModAutomodActionView {
+  pub mod_automod_action: ModAutomodAction,
+  pub automod_rule: Option<AutomodRule>,
+  pub post: Option<Post>,
+  pub comment: Option<Comment>,
+  pub community: CommunitySafe,
+}
+
+type ModAutomodActionViewTuple = (
+  ModAutomodAction,
+  Option<AutomodRule>,
+  Option<Post>,
+  Option<Comment>,
+  CommunitySafe,
+);
+
+impl ModAutomodActionView {
+  /// Automod actions don't have a moderator, so there are none when filtering by moderator
+  pub fn list(
+    conn: &PgConnection,
+    community_id: Option<CommunityId>,
+    mod_person_id: Option<PersonId>,
+    page: Option<i64>,
+    limit: Option<i64>,
+  ) -> Result<Vec<Self>, Error> {
+    if mod_person_id.is_some() {
+      return Ok(vec![]);
+    }
+
+    let mut query = mod_automod_action::table
+      .left_join(automod_rule::table)
+      .left_join(post::table)
+      .left_join(comment::table)
+      .inner_join(community::table)
+      .select((
+        mod_automod_action::all_columns,
+        automod_rule::all_columns.nullable(),
+        post::all_columns.nullable(),
+        comment::all_columns.nullable(),
+        Community::safe_columns_tuple(),
+      ))
+      .into_boxed();
+
+    if let Some(community_id) = community_id {
+      query = query.filter(mod_automod_action::community_id.eq(community_id));
+    };
+
+    let (limit, offset) = limit_and_offset(page, limit);
+
+    let res = query
+      .limit(limit)
+      .offset(offset)
+      .order_by(mod_automod_action::when_.desc())
+      .load::<ModAutomodActionViewTuple>(conn)?;
+
+    Ok(Self::from_tuple_to_vec(res))
+  }
+}
+
+impl ViewToVec for ModAutomodActionView {
+  type DbTuple = ModAutomodActionViewTuple;
+  fn from_tuple_to_vec(items: Vec<Self::DbTuple>) -> Vec<Self> {
+    items
+      .iter()
+      .map(|a| Self {
+        mod_automod_action: a.0.to_owned(),
+        automod_rule: a.1.to_owned(),
+        post: a.2.to_owned(),
+        comment: a.3.to_owned(),
+        community: a.4.to_owned(),
+      })
+      .collect::<Vec<Self>>()
+  }
+}
@@ -67,6 +67,26 @@ impl ModBanFromCommunityView {
 
     Ok(Self::from_tuple_to_vec(res))
   }
+
+  /// Reads the entries with the given ids, in no particular order
+  pub fn list_for_ids(conn: &PgConnection, ids: Vec<i32>) -> Result<Vec<Self>, Error> {
+    let res = mod_ban_from_community::table
+      .inner_join(person::table.on(mod_ban_from_community::mod_person_id.eq(person::id)))
+      .inner_join(community::table)
+      .inner_join(
+        person_alias_1::table.on(mod_ban_from_community::other_person_id.eq(person_alias_1::id)),
+      )
+      .select((
+        mod_ban_from_community::all_columns,
+        Person::safe_columns_tuple(),
+        Community::safe_columns_tuple(),
+        PersonAlias1::safe_columns_tuple(),
+      ))
+      .filter(mod_ban_from_community::id.eq_any(ids))
+      .load::<ModBanFromCommunityViewTuple>(conn)?;
+
+    Ok(Self::from_tuple_to_vec(res))
+  }
 }
 
 impl ViewToVec for ModBanFromCommunityView {
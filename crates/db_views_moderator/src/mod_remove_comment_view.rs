@@ -75,6 +75,28 @@ impl ModRemoveCommentView {
 
     Ok(Self::from_tuple_to_vec(res))
   }
+
+  /// Reads the entries with the given ids, in no particular order
+  pub fn list_for_ids(conn: &PgConnection, ids: Vec<i32>) -> Result<Vec<Self>, Error> {
+    let res = mod_remove_comment::table
+      .inner_join(person::table)
+      .inner_join(comment::table)
+      .inner_join(person_alias_1::table.on(comment::creator_id.eq(person_alias_1::id)))
+      .inner_join(post::table.on(comment::post_id.eq(post::id)))
+      .inner_join(community::table.on(post::community_id.eq(community::id)))
+      .select((
+        mod_remove_comment::all_columns,
+        Person::safe_columns_tuple(),
+        comment::all_columns,
+        PersonAlias1::safe_columns_tuple(),
+        post::all_columns,
+        Community::safe_columns_tuple(),
+      ))
+      .filter(mod_remove_comment::id.eq_any(ids))
+      .load::<ModRemoveCommentViewTuple>(conn)?;
+
+    Ok(Self::from_tuple_to_vec(res))
+  }
 }
 
 impl ViewToVec for ModRemoveCommentView {
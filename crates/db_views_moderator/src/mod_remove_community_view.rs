@@ -52,6 +52,22 @@ impl ModRemoveCommunityView {
 
     Ok(Self::from_tuple_to_vec(res))
   }
+
+  /// Reads the entries with the given ids, in no particular order
+  pub fn list_for_ids(conn: &PgConnection, ids: Vec<i32>) -> Result<Vec<Self>, Error> {
+    let res = mod_remove_community::table
+      .inner_join(person::table)
+      .inner_join(community::table)
+      .select((
+        mod_remove_community::all_columns,
+        Person::safe_columns_tuple(),
+        Community::safe_columns_tuple(),
+      ))
+      .filter(mod_remove_community::id.eq_any(ids))
+      .load::<ModRemoveCommunityTuple>(conn)?;
+
+    Ok(Self::from_tuple_to_vec(res))
+  }
 }
 
 impl ViewToVec for ModRemoveCommunityView {
@@ -61,6 +61,24 @@ impl ModRemovePostView {
 
     Ok(Self::from_tuple_to_vec(res))
   }
+
+  /// Reads the entries with the given ids, in no particular order
+  pub fn list_for_ids(conn: &PgConnection, ids: Vec<i32>) -> Result<Vec<Self>, Error> {
+    let res = mod_remove_post::table
+      .inner_join(person::table)
+      .inner_join(post::table)
+      .inner_join(community::table.on(post::community_id.eq(community::id)))
+      .select((
+        mod_remove_post::all_columns,
+        Person::safe_columns_tuple(),
+        post::all_columns,
+        Community::safe_columns_tuple(),
+      ))
+      .filter(mod_remove_post::id.eq_any(ids))
+      .load::<ModRemovePostViewTuple>(conn)?;
+
+    Ok(Self::from_tuple_to_vec(res))
+  }
 }
 
 impl ViewToVec for ModRemovePostView {
@@ -0,0 +1,79 @@
+use diesel::{result::Error, *};
+use lemmy_db_schema::{
+  limit_and_offset,
+  newtypes::{CommunityId, PersonId},
+  schema::{appeal, community, mod_resolve_appeal, person},
+  source::{
+    appeal::Appeal,
+    community::{Community, CommunitySafe},
+    moderator::ModResolveAppeal,
+    person::{Person, PersonSafe},
+  },
+  traits::{ToSafe, ViewToVec},
+};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ModResolveAppealView {
+  pub mod_resolve_appeal: ModResolveAppeal,
+  pub moderator: PersonSafe,
+  pub appeal: Appeal,
+  pub community: CommunitySafe,
+}
+
+type ModResolveAppealViewTuple = (ModResolveAppeal, PersonSafe, Appeal, CommunitySafe);
+
+impl ModResolveAppealView {
+  pub fn list(
+    conn: &PgConnection,
+    community_id: Option<CommunityId>,
+    mod_person_id: Option<PersonId>,
+    page: Option<i64>,
+    limit: Option<i64>,
+  ) -> Result<Vec<Self>, Error> {
+    let mut query = mod_resolve_appeal::table
+      .inner_join(person::table.on(mod_resolve_appeal::mod_person_id.eq(person::id)))
+      .inner_join(appeal::table)
+      .inner_join(community::table.on(appeal::community_id.eq(community::id)))
+      .select((
+        mod_resolve_appeal::all_columns,
+        Person::safe_columns_tuple(),
+        appeal::all_columns,
+        Community::safe_columns_tuple(),
+      ))
+      .into_boxed();
+
+    if let Some(community_id) = community_id {
+      query = query.filter(appeal::community_id.eq(community_id));
+    };
+
+    if let Some(mod_person_id) = mod_person_id {
+      query = query.filter(mod_resolve_appeal::mod_person_id.eq(mod_person_id));
+    };
+
+    let (limit, offset) = limit_and_offset(page, limit);
+
+    let res = query
+      .limit(limit)
+      .offset(offset)
+      .order_by(mod_resolve_appeal::when_.desc())
+      .load::<ModResolveAppealViewTuple>(conn)?;
+
+    Ok(Self::from_tuple_to_vec(res))
+  }
+}
+
+impl ViewToVec for ModResolveAppealView {
+  type DbTuple = ModResolveAppealViewTuple;
+  fn from_tuple_to_vec(items: Vec<Self::DbTuple>) -> Vec<Self> {
+    items
+      .iter()
+      .map(|a| Self {
+        mod_resolve_appeal: a.0.to_owned(),
+        moderator: a.1.to_owned(),
+        appeal: a.2.to_owned(),
+        community: a.3.to_owned(),
+      })
+      .collect::<Vec<Self>>()
+  }
+}
@@ -0,0 +1,79 @@
+use diesel::{result::Error, *};
+use lemmy_db_schema::{
+  limit_and_offset,
+  newtypes::{CommunityId, PersonId},
+  schema::{community, mod_shadow_remove_post, person, post},
+  source::{
+    community::{Community, CommunitySafe},
+    moderator::ModShadowRemovePost,
+    person::{Person, PersonSafe},
+    post::Post,
+  },
+  traits::{ToSafe, ViewToVec},
+};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ModShadowRemovePostView {
+  pub mod_shadow_remove_post: ModShadowRemovePost,
+  pub moderator: PersonSafe,
+  pub post: Post,
+  pub community: CommunitySafe,
+}
+
+type ModShadowRemovePostViewTuple = (ModShadowRemovePost, PersonSafe, Post, CommunitySafe);
+
+impl ModShadowRemovePostView {
+  pub fn list(
+    conn: &PgConnection,
+    community_id: Option<CommunityId>,
+    mod_person_id: Option<PersonId>,
+    page: Option<i64>,
+    limit: Option<i64>,
+  ) -> Result<Vec<Self>, Error> {
+    let mut query = mod_shadow_remove_post::table
+      .inner_join(person::table)
+      .inner_join(post::table)
+      .inner_join(community::table.on(post::community_id.eq(community::id)))
+      .select((
+        mod_shadow_remove_post::all_columns,
+        Person::safe_columns_tuple(),
+        post::all_columns,
+        Community::safe_columns_tuple(),
+      ))
+      .into_boxed();
+
+    if let Some(community_id) = community_id {
+      query = query.filter(post::community_id.eq(community_id));
+    };
+
+    if let Some(mod_person_id) = mod_person_id {
+      query = query.filter(mod_shadow_remove_post::mod_person_id.eq(mod_person_id));
+    };
+
+    let (limit, offset) = limit_and_offset(page, limit);
+
+    let res = query
+      .limit(limit)
+      .offset(offset)
+      .order_by(mod_shadow_remove_post::when_.desc())
+      .load::<ModShadowRemovePostViewTuple>(conn)?;
+
+    Ok(Self::from_tuple_to_vec(res))
+  }
+}
+
+impl ViewToVec for ModShadowRemovePostView {
+  type DbTuple = ModShadowRemovePostViewTuple;
+  fn from_tuple_to_vec(items: Vec<Self::DbTuple>) -> Vec<Self> {
+    items
+      .iter()
+      .map(|a| Self {
+        mod_shadow_remove_post: a.0.to_owned(),
+        moderator: a.1.to_owned(),
+        post: a.2.to_owned(),
+        community: a.3.to_owned(),
+      })
+      .collect::<Vec<Self>>()
+  }
+}
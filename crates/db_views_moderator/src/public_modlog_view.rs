@@ -0,0 +1,216 @@
+use crate::{
+  mod_ban_from_community_view::ModBanFromCommunityView,
+  mod_remove_comment_view::ModRemoveCommentView,
+  mod_remove_community_view::ModRemoveCommunityView,
+  mod_remove_post_view::ModRemovePostView,
+};
+use diesel::{
+  result::Error,
+  sql_types::{BigInt, Integer, Text},
+  *,
+};
+use lemmy_db_schema::limit_and_offset;
+use std::collections::HashMap;
+
+/// A mod action which is marked as public and was taken in a local community
+#[derive(Debug, Clone)]
+pub enum PublicModlogView {
+  RemovePost(Box<ModRemovePostView>),
+  RemoveComment(Box<ModRemoveCommentView>),
+  RemoveCommunity(Box<ModRemoveCommunityView>),
+  BanFromCommunity(Box<ModBanFromCommunityView>),
+}
+
+#[derive(QueryableByName)]
+struct PublicModlogEntry {
+  #[sql_type = "Text"]
+  kind: String,
+  #[sql_type = "Integer"]
+  id: i32,
+}
+
+/// Pages over all mod tables together, after leaving out private actions and those in remote
+/// communities. Entries whose target was permanently deleted are left out as well.
+const PUBLIC_MODLOG_QUERY: &str = "
+select kind, id from (
+  select 'remove_post' as kind, m.id, m.when_ from mod_remove_post m
+    join post p on p.id = m.post_id
+    join community c on c.id = p.community_id
+    where m.public and c.local
+  union all
+  select 'remove_comment', m.id, m.when_ from mod_remove_comment m
+    join comment co on co.id = m.comment_id
+    join post p on p.id = co.post_id
+    join community c on c.id = p.community_id
+    where m.public and c.local
+  union all
+  select 'remove_community', m.id, m.when_ from mod_remove_community m
+    join community c on c.id = m.community_id
+    where m.public and c.local
+  union all
+  select 'ban_from_community', m.id, m.when_ from mod_ban_from_community m
+    join community c on c.id = m.community_id
+    where m.public and c.local
+) entries
+order by when_ desc, kind, id desc
+limit $1 offset $2";
+
+impl PublicModlogView {
+  /// Newest first
+  pub fn list(
+    conn: &PgConnection,
+    page: Option<i64>,
+    limit: Option<i64>,
+  ) -> Result<Vec<Self>, Error> {
+    let (limit, offset) = limit_and_offset(page, limit);
+    let entries = sql_query(PUBLIC_MODLOG_QUERY)
+      .bind::<BigInt, _>(limit)
+      .bind::<BigInt, _>(offset)
+      .load::<PublicModlogEntry>(conn)?;
+    let ids = |kind: &str| {
+      entries
+        .iter()
+        .filter(|e| e.kind == kind)
+        .map(|e| e.id)
+        .collect::<Vec<_>>()
+    };
+
+    let mut posts = ModRemovePostView::list_for_ids(conn, ids("remove_post"))?
+      .into_iter()
+      .map(|v| (v.mod_remove_post.id, v))
+      .collect::<HashMap<_, _>>();
+    let mut comments = ModRemoveCommentView::list_for_ids(conn, ids("remove_comment"))?
+      .into_iter()
+      .map(|v| (v.mod_remove_comment.id, v))
+      .collect::<HashMap<_, _>>();
+    let mut communities = ModRemoveCommunityView::list_for_ids(conn, ids("remove_community"))?
+      .into_iter()
+      .map(|v| (v.mod_remove_community.id, v))
+      .collect::<HashMap<_, _>>();
+    let mut bans = ModBanFromCommunityView::list_for_ids(conn, ids("ban_from_community"))?
+      .into_iter()
+      .map(|v| (v.mod_ban_from_community.id, v))
+      .collect::<HashMap<_, _>>();
+
+    Ok(
+      entries
+        .iter()
+        .filter_map(|e| match e.kind.as_str() {
+          "remove_post" => posts.remove(&e.id).map(|p| Self::RemovePost(Box::new(p))),
+          "remove_comment" => comments
+            .remove(&e.id)
+            .map(|c| Self::RemoveComment(Box::new(c))),
+          "remove_community" => communities
+            .remove(&e.id)
+            .map(|c| Self::RemoveCommunity(Box::new(c))),
+          "ban_from_community" => bans
+            .remove(&e.id)
+            .map(|b| Self::BanFromCommunity(Box::new(b))),
+          _ => None,
+        })
+        .collect(),
+    )
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::public_modlog_view::*;
+  use lemmy_db_schema::{
+    establish_unpooled_connection,
+    source::{community::*, moderator::*, person::*, post::*},
+    traits::Crud,
+  };
+  use serial_test::serial;
+
+  #[test]
+  #[serial]
+  fn test_public_modlog() {
+    let conn = establish_unpooled_connection();
+
+    let person_form = PersonForm {
+      name: "public_modlog_mod".into(),
+      ..PersonForm::default()
+    };
+    let moderator = Person::create(&conn, &person_form).unwrap();
+    let person_form = PersonForm {
+      name: "public_modlog_banned".into(),
+      ..PersonForm::default()
+    };
+    let banned = Person::create(&conn, &person_form).unwrap();
+    let community_form = CommunityForm {
+      name: "public_modlog".to_string(),
+      title: "nada".to_owned(),
+      ..CommunityForm::default()
+    };
+    let community = Community::create(&conn, &community_form).unwrap();
+    let community_form = CommunityForm {
+      name: "public_modlog_remote".to_string(),
+      title: "nada".to_owned(),
+      local: Some(false),
+      ..CommunityForm::default()
+    };
+    let remote_community = Community::create(&conn, &community_form).unwrap();
+    let mut posts = vec![];
+    for community_id in [community.id, remote_community.id] {
+      let post_form = PostForm {
+        name: "public modlog".into(),
+        creator_id: banned.id,
+        community_id,
+        ..PostForm::default()
+      };
+      posts.push(Post::create(&conn, &post_form).unwrap());
+    }
+    let remove_post = |post_id, public| {
+      let form = ModRemovePostForm {
+        mod_person_id: moderator.id,
+        post_id,
+        reason: None,
+        removed: None,
+        reason_translations: None,
+        reason_override: false,
+        public,
+      };
+      ModRemovePost::create(&conn, &form).unwrap()
+    };
+
+    let first_removal = remove_post(posts[0].id, None);
+    remove_post(posts[0].id, Some(false));
+    remove_post(posts[1].id, None);
+    let ban_form = ModBanFromCommunityForm {
+      mod_person_id: moderator.id,
+      other_person_id: banned.id,
+      community_id: community.id,
+      reason: None,
+      banned: None,
+      expires: None,
+      public: None,
+    };
+    let ban = ModBanFromCommunity::create(&conn, &ban_form).unwrap();
+    let last_removal = remove_post(posts[0].id, None);
+
+    let first_page = PublicModlogView::list(&conn, Some(1), Some(2)).unwrap();
+    let second_page = PublicModlogView::list(&conn, Some(2), Some(2)).unwrap();
+
+    Person::delete(&conn, moderator.id).unwrap();
+    Person::delete(&conn, banned.id).unwrap();
+    Community::delete(&conn, community.id).unwrap();
+    Community::delete(&conn, remote_community.id).unwrap();
+
+    let ids = |page: Vec<PublicModlogView>| {
+      page
+        .into_iter()
+        .map(|v| match v {
+          PublicModlogView::RemovePost(r) => ("remove_post", r.mod_remove_post.id),
+          PublicModlogView::BanFromCommunity(b) => ("ban", b.mod_ban_from_community.id),
+          _ => unreachable!(),
+        })
+        .collect::<Vec<_>>()
+    };
+    assert_eq!(
+      vec![("remove_post", last_removal.id), ("ban", ban.id)],
+      ids(first_page)
+    );
+    assert_eq!(vec![("remove_post", first_removal.id)], ids(second_page));
+  }
+}
@@ -1,11 +1,18 @@
 use actix_web::{error::ErrorBadRequest, *};
 use anyhow::anyhow;
-use chrono::{DateTime, NaiveDateTime, Utc};
+use chrono::{DateTime, Duration, NaiveDateTime, Utc};
 use diesel::PgConnection;
 use lemmy_api_common::blocking;
 use lemmy_db_schema::{
-  newtypes::LocalUserId,
-  source::{community::Community, local_user::LocalUser, person::Person},
+  naive_now,
+  newtypes::PostId,
+  source::{
+    community::Community,
+    feed_token::FeedToken,
+    local_user::LocalUser,
+    person::Person,
+    post::Post,
+  },
   traits::Crud,
   ListingType,
   SortType,
@@ -16,7 +23,7 @@ use lemmy_db_views::{
   site_view::SiteView,
 };
 use lemmy_db_views_actor::person_mention_view::{PersonMentionQueryBuilder, PersonMentionView};
-use lemmy_utils::{claims::Claims, utils::markdown_to_html, LemmyError};
+use lemmy_utils::{utils::markdown_to_html, LemmyError};
 use lemmy_websocket::LemmyContext;
 use once_cell::sync::Lazy;
 use rss::{
@@ -33,13 +40,17 @@ use strum::ParseError;
 #[derive(Deserialize)]
 struct Params {
   sort: Option<String>,
+  /// Only list the posts or comments of the last `hour`, `day`, `week`, `month` or `year`
+  time: Option<String>,
 }
 
 enum RequestType {
   Community,
   User,
+  Post,
   Front,
   Inbox,
+  Replies,
 }
 
 pub fn config(cfg: &mut web::ServiceConfig) {
@@ -63,8 +74,9 @@ async fn get_all_feed(
   info: web::Query<Params>,
   context: web::Data<LemmyContext>,
 ) -> Result<HttpResponse, Error> {
-  let sort_type = get_sort_type(info).map_err(ErrorBadRequest)?;
-  Ok(get_feed_data(&context, ListingType::All, sort_type).await?)
+  let sort_type = get_sort_type(&info).map_err(ErrorBadRequest)?;
+  let published_after = get_published_after(&info).map_err(ErrorBadRequest)?;
+  Ok(get_feed_data(&context, ListingType::All, sort_type, published_after).await?)
 }
 
 #[tracing::instrument(skip_all)]
@@ -72,8 +84,9 @@ async fn get_local_feed(
   info: web::Query<Params>,
   context: web::Data<LemmyContext>,
 ) -> Result<HttpResponse, Error> {
-  let sort_type = get_sort_type(info).map_err(ErrorBadRequest)?;
-  Ok(get_feed_data(&context, ListingType::Local, sort_type).await?)
+  let sort_type = get_sort_type(&info).map_err(ErrorBadRequest)?;
+  let published_after = get_published_after(&info).map_err(ErrorBadRequest)?;
+  Ok(get_feed_data(&context, ListingType::Local, sort_type, published_after).await?)
 }
 
 #[tracing::instrument(skip_all)]
@@ -81,6 +94,7 @@ async fn get_feed_data(
   context: &LemmyContext,
   listing_type: ListingType,
   sort_type: SortType,
+  published_after: Option<NaiveDateTime>,
 ) -> Result<HttpResponse, LemmyError> {
   let site_view = blocking(context.pool(), SiteView::read).await??;
 
@@ -88,6 +102,7 @@ async fn get_feed_data(
     PostQueryBuilder::create(conn)
       .listing_type(listing_type)
       .sort(sort_type)
+      .published_after(published_after)
       .list()
   })
   .await??;
@@ -123,7 +138,8 @@ async fn get_feed(
   info: web::Query<Params>,
   context: web::Data<LemmyContext>,
 ) -> Result<HttpResponse, Error> {
-  let sort_type = get_sort_type(info).map_err(ErrorBadRequest)?;
+  let sort_type = get_sort_type(&info).map_err(ErrorBadRequest)?;
+  let published_after = get_published_after(&info).map_err(ErrorBadRequest)?;
 
   let req_type: String = req.match_info().get("type").unwrap_or("none").parse()?;
   let param: String = req.match_info().get("name").unwrap_or("none").parse()?;
@@ -131,25 +147,46 @@ async fn get_feed(
   let request_type = match req_type.as_str() {
     "u" => RequestType::User,
     "c" => RequestType::Community,
+    "post" => RequestType::Post,
     "front" => RequestType::Front,
     "inbox" => RequestType::Inbox,
+    "replies" => RequestType::Replies,
     _ => return Err(ErrorBadRequest(LemmyError::from(anyhow!("wrong_type")))),
   };
 
-  let jwt_secret = context.secret().jwt_secret.to_owned();
   let protocol_and_hostname = context.settings().get_protocol_and_hostname();
 
   let builder = blocking(context.pool(), move |conn| match request_type {
-    RequestType::User => get_feed_user(conn, &sort_type, &param, &protocol_and_hostname),
-    RequestType::Community => get_feed_community(conn, &sort_type, &param, &protocol_and_hostname),
+    RequestType::User => get_feed_user(
+      conn,
+      &sort_type,
+      published_after,
+      &param,
+      &protocol_and_hostname,
+    ),
+    RequestType::Community => get_feed_community(
+      conn,
+      &sort_type,
+      published_after,
+      &param,
+      &protocol_and_hostname,
+    ),
+    RequestType::Post => get_feed_post(
+      conn,
+      &sort_type,
+      published_after,
+      &param,
+      &protocol_and_hostname,
+    ),
     RequestType::Front => get_feed_front(
       conn,
-      &jwt_secret,
       &sort_type,
+      published_after,
       &param,
       &protocol_and_hostname,
     ),
-    RequestType::Inbox => get_feed_inbox(conn, &jwt_secret, &param, &protocol_and_hostname),
+    RequestType::Inbox => get_feed_inbox(conn, &param, &protocol_and_hostname),
+    RequestType::Replies => get_feed_replies(conn, &param, &protocol_and_hostname),
   })
   .await?
   .map_err(ErrorBadRequest)?;
@@ -163,7 +200,7 @@ async fn get_feed(
   )
 }
 
-fn get_sort_type(info: web::Query<Params>) -> Result<SortType, ParseError> {
+fn get_sort_type(info: &Params) -> Result<SortType, ParseError> {
   let sort_query = info
     .sort
     .to_owned()
@@ -171,10 +208,36 @@ fn get_sort_type(info: web::Query<Params>) -> Result<SortType, ParseError> {
   SortType::from_str(&sort_query)
 }
 
+fn get_published_after(info: &Params) -> Result<Option<NaiveDateTime>, LemmyError> {
+  let time_window = match info.time.as_deref() {
+    None => return Ok(None),
+    Some("hour") => Duration::hours(1),
+    Some("day") => Duration::days(1),
+    Some("week") => Duration::weeks(1),
+    Some("month") => Duration::days(30),
+    Some("year") => Duration::days(365),
+    Some(_) => return Err(LemmyError::from_message("invalid_time_window")),
+  };
+  Ok(Some(naive_now() - time_window))
+}
+
+/// Private feeds are authenticated with the feed token of the user, see `GenerateFeedToken`
+fn read_local_user_from_feed_token(
+  conn: &PgConnection,
+  feed_token: &str,
+) -> Result<LocalUser, LemmyError> {
+  let local_user_id = FeedToken::read_from_token(conn, feed_token)
+    .map_err(LemmyError::from)
+    .map_err(|e| e.with_message("invalid_feed_token"))?
+    .local_user_id;
+  Ok(LocalUser::read(conn, local_user_id)?)
+}
+
 #[tracing::instrument(skip_all)]
 fn get_feed_user(
   conn: &PgConnection,
   sort_type: &SortType,
+  published_after: Option<NaiveDateTime>,
   user_name: &str,
   protocol_and_hostname: &str,
 ) -> Result<ChannelBuilder, LemmyError> {
@@ -184,6 +247,7 @@ fn get_feed_user(
   let posts = PostQueryBuilder::create(conn)
     .listing_type(ListingType::All)
     .sort(*sort_type)
+    .published_after(published_after)
     .creator_id(person.id)
     .list()?;
 
@@ -203,6 +267,7 @@ fn get_feed_user(
 fn get_feed_community(
   conn: &PgConnection,
   sort_type: &SortType,
+  published_after: Option<NaiveDateTime>,
   community_name: &str,
   protocol_and_hostname: &str,
 ) -> Result<ChannelBuilder, LemmyError> {
@@ -212,6 +277,7 @@ fn get_feed_community(
   let posts = PostQueryBuilder::create(conn)
     .listing_type(ListingType::All)
     .sort(*sort_type)
+    .published_after(published_after)
     .community_id(community.id)
     .list()?;
 
@@ -234,14 +300,13 @@ fn get_feed_community(
 #[tracing::instrument(skip_all)]
 fn get_feed_front(
   conn: &PgConnection,
-  jwt_secret: &str,
   sort_type: &SortType,
-  jwt: &str,
+  published_after: Option<NaiveDateTime>,
+  feed_token: &str,
   protocol_and_hostname: &str,
 ) -> Result<ChannelBuilder, LemmyError> {
   let site_view = SiteView::read(conn)?;
-  let local_user_id = LocalUserId(Claims::decode(jwt, jwt_secret)?.claims.sub);
-  let local_user = LocalUser::read(conn, local_user_id)?;
+  let local_user = read_local_user_from_feed_token(conn, feed_token)?;
 
   let posts = PostQueryBuilder::create(conn)
     .listing_type(ListingType::Subscribed)
@@ -249,6 +314,7 @@ fn get_feed_front(
     .show_bot_accounts(local_user.show_bot_accounts)
     .show_read_posts(local_user.show_read_posts)
     .sort(*sort_type)
+    .published_after(published_after)
     .list()?;
 
   let items = create_post_items(posts, protocol_and_hostname)?;
@@ -270,29 +336,18 @@ fn get_feed_front(
 #[tracing::instrument(skip_all)]
 fn get_feed_inbox(
   conn: &PgConnection,
-  jwt_secret: &str,
-  jwt: &str,
+  feed_token: &str,
   protocol_and_hostname: &str,
 ) -> Result<ChannelBuilder, LemmyError> {
   let site_view = SiteView::read(conn)?;
-  let local_user_id = LocalUserId(Claims::decode(jwt, jwt_secret)?.claims.sub);
-  let local_user = LocalUser::read(conn, local_user_id)?;
+  let local_user = read_local_user_from_feed_token(conn, feed_token)?;
   let person_id = local_user.person_id;
-  let show_bot_accounts = local_user.show_bot_accounts;
-
-  let sort = SortType::New;
-
-  let replies = CommentQueryBuilder::create(conn)
-    .recipient_id(person_id)
-    .my_person_id(person_id)
-    .show_bot_accounts(show_bot_accounts)
-    .sort(sort)
-    .list()?;
 
+  let replies = list_replies(conn, &local_user)?;
   let mentions = PersonMentionQueryBuilder::create(conn)
     .recipient_id(person_id)
     .my_person_id(person_id)
-    .sort(sort)
+    .sort(SortType::New)
     .list()?;
 
   let items = create_reply_and_mention_items(replies, mentions, protocol_and_hostname)?;
@@ -311,6 +366,102 @@ fn get_feed_inbox(
   Ok(channel_builder)
 }
 
+#[tracing::instrument(skip_all)]
+fn get_feed_replies(
+  conn: &PgConnection,
+  feed_token: &str,
+  protocol_and_hostname: &str,
+) -> Result<ChannelBuilder, LemmyError> {
+  let site_view = SiteView::read(conn)?;
+  let local_user = read_local_user_from_feed_token(conn, feed_token)?;
+
+  let replies = list_replies(conn, &local_user)?;
+  let items = create_reply_and_mention_items(replies, vec![], protocol_and_hostname)?;
+
+  let mut channel_builder = ChannelBuilder::default();
+  channel_builder
+    .namespaces(RSS_NAMESPACE.to_owned())
+    .title(format!("{} - Replies", site_view.site.name))
+    .link(format!("{}/inbox", protocol_and_hostname,))
+    .items(items);
+
+  if let Some(site_desc) = site_view.site.description {
+    channel_builder.description(&site_desc);
+  }
+
+  Ok(channel_builder)
+}
+
+fn list_replies(
+  conn: &PgConnection,
+  local_user: &LocalUser,
+) -> Result<Vec<CommentView>, LemmyError> {
+  let person_id = local_user.person_id;
+  let replies = CommentQueryBuilder::create(conn)
+    .recipient_id(person_id)
+    .my_person_id(person_id)
+    .show_bot_accounts(local_user.show_bot_accounts)
+    .sort(SortType::New)
+    .list()?;
+  Ok(replies)
+}
+
+/// The comments of a post, the post itself is not included
+#[tracing::instrument(skip_all)]
+fn get_feed_post(
+  conn: &PgConnection,
+  sort_type: &SortType,
+  published_after: Option<NaiveDateTime>,
+  post_id: &str,
+  protocol_and_hostname: &str,
+) -> Result<ChannelBuilder, LemmyError> {
+  let site_view = SiteView::read(conn)?;
+  let post_id = post_id
+    .parse()
+    .map(PostId)
+    .map_err(|_| LemmyError::from_message("couldnt_find_post"))?;
+  let post = Post::read(conn, post_id)?;
+  let community = Community::read(conn, post.community_id)?;
+  if post.deleted || post.removed || community.deleted || community.removed {
+    return Err(LemmyError::from_message("couldnt_find_post"));
+  }
+
+  let comments = CommentQueryBuilder::create(conn)
+    .listing_type(ListingType::All)
+    .post_id(post.id)
+    .sort(*sort_type)
+    .published_after(published_after)
+    .list()?;
+
+  let items = comments
+    .iter()
+    .filter(|c| !c.comment.deleted && !c.comment.removed)
+    .map(|c| {
+      let comment_url = format!(
+        "{}/post/{}/comment/{}",
+        protocol_and_hostname, c.post.id, c.comment.id
+      );
+      build_item(
+        &format!("Comment from {}", c.creator.name),
+        &c.creator.name,
+        &c.comment.published,
+        &comment_url,
+        &c.comment.content,
+        protocol_and_hostname,
+      )
+    })
+    .collect::<Result<Vec<Item>, LemmyError>>()?;
+
+  let mut channel_builder = ChannelBuilder::default();
+  channel_builder
+    .namespaces(RSS_NAMESPACE.to_owned())
+    .title(format!("{} - {}", site_view.site.name, post.name))
+    .link(format!("{}/post/{}", protocol_and_hostname, post.id))
+    .items(items);
+
+  Ok(channel_builder)
+}
+
 #[tracing::instrument(skip_all)]
 fn create_reply_and_mention_items(
   replies: Vec<CommentView>,
@@ -325,6 +476,7 @@ fn create_reply_and_mention_items(
         protocol_and_hostname, r.post.id, r.comment.id
       );
       build_item(
+        &format!("Reply from {}", r.creator.name),
         &r.creator.name,
         &r.comment.published,
         &reply_url,
@@ -342,6 +494,7 @@ fn create_reply_and_mention_items(
         protocol_and_hostname, m.post.id, m.comment.id
       );
       build_item(
+        &format!("Reply from {}", m.creator.name),
         &m.creator.name,
         &m.comment.published,
         &mention_url,
@@ -357,6 +510,7 @@ fn create_reply_and_mention_items(
 
 #[tracing::instrument(skip_all)]
 fn build_item(
+  title: &str,
   creator_name: &str,
   published: &NaiveDateTime,
   url: &str,
@@ -364,7 +518,7 @@ fn build_item(
   protocol_and_hostname: &str,
 ) -> Result<Item, LemmyError> {
   let mut i = ItemBuilder::default();
-  i.title(format!("Reply from {}", creator_name));
+  i.title(title.to_owned());
   let author_url = format!("{}/u/{}", protocol_and_hostname, creator_name);
   i.author(format!(
     "/u/{} <a href=\"{}\">(link)</a>",
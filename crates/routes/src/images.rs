@@ -1,14 +1,23 @@
-use actix_http::header::{HeaderName, ACCEPT_ENCODING, HOST};
+use actix_http::header::{
+  HeaderName,
+  ACCEPT_ENCODING,
+  CONTENT_LENGTH,
+  CONTENT_TYPE,
+  HOST,
+  TRANSFER_ENCODING,
+};
 use actix_web::{body::BodyStream, http::StatusCode, web::Data, *};
 use anyhow::anyhow;
-use futures::stream::{Stream, StreamExt};
-use lemmy_utils::{claims::Claims, rate_limit::RateLimit, LemmyError};
+use futures::stream::StreamExt;
+use lemmy_api_common::get_local_user_view_from_jwt;
+use lemmy_utils::{rate_limit::RateLimit, LemmyError};
 use lemmy_websocket::LemmyContext;
-use reqwest::Body;
 use reqwest_middleware::{ClientWithMiddleware, RequestBuilder};
 use serde::{Deserialize, Serialize};
 use std::time::Duration;
 
+mod validation;
+
 pub fn config(cfg: &mut web::ServiceConfig, client: ClientWithMiddleware, rate_limit: &RateLimit) {
   cfg
     .app_data(Data::new(client))
@@ -45,8 +54,10 @@ fn adapt_request(
   client: &ClientWithMiddleware,
   url: String,
 ) -> RequestBuilder {
-  // remove accept-encoding header so that pictrs doesnt compress the response
-  const INVALID_HEADERS: &[HeaderName] = &[ACCEPT_ENCODING, HOST];
+  // remove accept-encoding header so that pictrs doesnt compress the response. uploads are
+  // modified before they are forwarded, so the body length is set by reqwest.
+  const INVALID_HEADERS: &[HeaderName] =
+    &[ACCEPT_ENCODING, HOST, CONTENT_LENGTH, TRANSFER_ENCODING];
 
   let client_request = client
     .request(request.method().clone(), url)
@@ -66,7 +77,7 @@ fn adapt_request(
 
 async fn upload(
   req: HttpRequest,
-  body: web::Payload,
+  mut body: web::Payload,
   client: web::Data<ClientWithMiddleware>,
   context: web::Data<LemmyContext>,
 ) -> Result<HttpResponse, Error> {
@@ -75,11 +86,35 @@ async fn upload(
     .cookie("jwt")
     .expect("No auth header for picture upload");
 
-  if Claims::decode(jwt.value(), &context.secret().jwt_secret).is_err() {
+  // Same checks as for api requests, so that revoked sessions can't upload anymore
+  if get_local_user_view_from_jwt(jwt.value(), context.pool(), context.secret())
+    .await
+    .is_err()
+  {
     return Ok(HttpResponse::Unauthorized().finish());
   };
 
-  let image_url = format!("{}/image", pictrs_url(context.settings().pictrs_url)?);
+  let settings = context.settings();
+  let image_url = format!("{}/image", pictrs_url(settings.pictrs_url)?);
+  let config = settings.image_upload;
+
+  let mut form = vec![];
+  while let Some(chunk) = body.next().await {
+    let chunk = chunk?;
+    if form.len() + chunk.len() > config.max_size {
+      return Err(LemmyError::from_message("image_too_large").into());
+    }
+    form.extend_from_slice(&chunk);
+  }
+
+  let content_type = req
+    .headers()
+    .get(CONTENT_TYPE)
+    .and_then(|t| t.to_str().ok())
+    .unwrap_or_default();
+  let boundary = validation::parse_boundary(content_type)?;
+  let mut parts = validation::split_multipart(&form, &boundary)?;
+  validation::validate_parts(&mut parts, &config)?;
 
   let mut client_req = adapt_request(&req, &client, image_url);
 
@@ -88,7 +123,7 @@ async fn upload(
   };
 
   let res = client_req
-    .body(Body::wrap_stream(make_send(body)))
+    .body(validation::join_multipart(&parts, &boundary))
     .send()
     .await
     .map_err(error::ErrorBadRequest)?;
@@ -195,45 +230,3 @@ async fn delete(
 fn pictrs_url(pictrs_url: Option<String>) -> Result<String, LemmyError> {
   pictrs_url.ok_or_else(|| anyhow!("images_disabled").into())
 }
-
-fn make_send<S>(mut stream: S) -> impl Stream<Item = S::Item> + Send + Unpin + 'static
-where
-  S: Stream + Unpin + 'static,
-  S::Item: Send,
-{
-  // NOTE: the 8 here is arbitrary
-  let (tx, rx) = tokio::sync::mpsc::channel(8);
-
-  // NOTE: spawning stream into a new task can potentially hit this bug:
-  // - https://github.com/actix/actix-web/issues/1679
-  //
-  // Since 4.0.0-beta.2 this issue is incredibly less frequent. I have not personally reproduced it.
-  // That said, it is still technically possible to encounter.
-  actix_web::rt::spawn(async move {
-    while let Some(res) = stream.next().await {
-      if tx.send(res).await.is_err() {
-        break;
-      }
-    }
-  });
-
-  SendStream { rx }
-}
-
-struct SendStream<T> {
-  rx: tokio::sync::mpsc::Receiver<T>,
-}
-
-impl<T> Stream for SendStream<T>
-where
-  T: Send,
-{
-  type Item = T;
-
-  fn poll_next(
-    mut self: std::pin::Pin<&mut Self>,
-    cx: &mut std::task::Context<'_>,
-  ) -> std::task::Poll<Option<Self::Item>> {
-    std::pin::Pin::new(&mut self.rx).poll_recv(cx)
-  }
-}
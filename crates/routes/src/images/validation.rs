@@ -0,0 +1,283 @@
+use image::{io::Reader, ImageFormat};
+use lemmy_utils::{settings::structs::ImageUploadConfig, LemmyError};
+use std::{
+  convert::{TryFrom, TryInto},
+  io::Cursor,
+};
+
+/// A single part of a `multipart/form-data` body, as sent by clients to upload images.
+pub(super) struct Part {
+  /// Raw header lines of the part, without the empty line which terminates them
+  headers: Vec<u8>,
+  data: Vec<u8>,
+}
+
+/// Extracts the boundary parameter from a `multipart/form-data` content type.
+pub(super) fn parse_boundary(content_type: &str) -> Result<String, LemmyError> {
+  let mut params = content_type.split(';').map(str::trim);
+  if !params
+    .next()
+    .map(|t| t.eq_ignore_ascii_case("multipart/form-data"))
+    .unwrap_or(false)
+  {
+    return Err(LemmyError::from_message("invalid_image"));
+  }
+  params
+    .filter_map(|p| p.split_once('='))
+    .find(|(key, _)| key.eq_ignore_ascii_case("boundary"))
+    .map(|(_, value)| value.trim_matches('"').to_string())
+    .filter(|b| !b.is_empty())
+    .ok_or_else(|| LemmyError::from_message("invalid_image"))
+}
+
+pub(super) fn split_multipart(body: &[u8], boundary: &str) -> Result<Vec<Part>, LemmyError> {
+  let invalid = || LemmyError::from_message("invalid_image");
+  let delimiter = format!("\r\n--{}", boundary).into_bytes();
+  // The first delimiter is not preceded by a line break, unless there is a preamble
+  let mut body_with_crlf = b"\r\n".to_vec();
+  body_with_crlf.extend_from_slice(body);
+
+  let mut rest = &body_with_crlf[find(&body_with_crlf, &delimiter).ok_or_else(invalid)?..];
+  let mut parts = vec![];
+  loop {
+    rest = &rest[delimiter.len()..];
+    if rest.starts_with(b"--") {
+      return Ok(parts);
+    }
+    let line_end = find(rest, b"\r\n").ok_or_else(invalid)?;
+    rest = &rest[line_end..];
+    let next = find(rest, &delimiter).ok_or_else(invalid)?;
+    let part = &rest[..next];
+    let part = if let Some(part) = part.strip_prefix(b"\r\n\r\n") {
+      Part {
+        headers: vec![],
+        data: part.to_vec(),
+      }
+    } else {
+      let headers_end = find(part, b"\r\n\r\n").ok_or_else(invalid)?;
+      Part {
+        headers: part[2..headers_end].to_vec(),
+        data: part[headers_end + 4..].to_vec(),
+      }
+    };
+    parts.push(part);
+    rest = &rest[next..];
+  }
+}
+
+pub(super) fn join_multipart(parts: &[Part], boundary: &str) -> Vec<u8> {
+  let mut body = vec![];
+  for part in parts {
+    body.extend_from_slice(format!("--{}\r\n", boundary).as_bytes());
+    body.extend_from_slice(&part.headers);
+    if !part.headers.is_empty() {
+      body.extend_from_slice(b"\r\n");
+    }
+    body.extend_from_slice(b"\r\n");
+    body.extend_from_slice(&part.data);
+    body.extend_from_slice(b"\r\n");
+  }
+  body.extend_from_slice(format!("--{}--\r\n", boundary).as_bytes());
+  body
+}
+
+/// Checks all parts of the form, and strips their metadata if enabled. Parts without a filename are
+/// checked too, as pictrs would store them as well.
+pub(super) fn validate_parts(
+  parts: &mut [Part],
+  config: &ImageUploadConfig,
+) -> Result<(), LemmyError> {
+  for part in parts.iter_mut() {
+    let format = validate_image(&part.data, config)?;
+    if config.strip_metadata {
+      part.data = strip_metadata(&part.data, format)?;
+    }
+  }
+  Ok(())
+}
+
+/// Detects the image type from the file content, ignoring the content type given by the client,
+/// and checks its dimensions.
+fn validate_image(data: &[u8], config: &ImageUploadConfig) -> Result<ImageFormat, LemmyError> {
+  const ALLOWED_FORMATS: &[ImageFormat] = &[
+    ImageFormat::Png,
+    ImageFormat::Jpeg,
+    ImageFormat::Gif,
+    ImageFormat::WebP,
+  ];
+  let format = image::guess_format(data)
+    .ok()
+    .filter(|f| ALLOWED_FORMATS.contains(f))
+    .ok_or_else(|| LemmyError::from_message("image_type_not_allowed"))?;
+
+  let mut reader = Reader::new(Cursor::new(data));
+  reader.set_format(format);
+  let (width, height) = reader
+    .into_dimensions()
+    .map_err(|e| LemmyError::from(e).with_message("invalid_image"))?;
+  if width > config.max_dimension || height > config.max_dimension {
+    return Err(LemmyError::from_message("image_dimensions_too_large"));
+  }
+  Ok(format)
+}
+
+/// Removes EXIF, XMP and similar metadata without re-encoding the image. GIF files can't contain
+/// EXIF data, so they are returned unchanged.
+fn strip_metadata(data: &[u8], format: ImageFormat) -> Result<Vec<u8>, LemmyError> {
+  let stripped = match format {
+    ImageFormat::Jpeg => strip_jpeg(data),
+    ImageFormat::Png => strip_png(data),
+    ImageFormat::WebP => strip_webp(data),
+    _ => Some(data.to_vec()),
+  };
+  stripped.ok_or_else(|| LemmyError::from_message("invalid_image"))
+}
+
+fn strip_jpeg(data: &[u8]) -> Option<Vec<u8>> {
+  // APP1 holds EXIF and XMP, APP13 holds IPTC, FE is a comment
+  const REMOVED_MARKERS: &[u8] = &[0xE1, 0xED, 0xFE];
+  // Start of scan, after which only compressed image data follows
+  const SOS: u8 = 0xDA;
+
+  let mut out = data.get(..2)?.to_vec();
+  let mut pos = 2;
+  loop {
+    // Markers may be preceded by any number of fill bytes
+    while *data.get(pos + 1)? == 0xFF {
+      pos += 1;
+    }
+    if data[pos] != 0xFF {
+      return None;
+    }
+    let marker = data[pos + 1];
+    let length = u16::from_be_bytes([*data.get(pos + 2)?, *data.get(pos + 3)?]) as usize;
+    let segment = data.get(pos..pos + 2 + length)?;
+    if marker == SOS {
+      out.extend_from_slice(&data[pos..]);
+      return Some(out);
+    }
+    if !REMOVED_MARKERS.contains(&marker) {
+      out.extend_from_slice(segment);
+    }
+    pos += segment.len();
+  }
+}
+
+fn strip_png(data: &[u8]) -> Option<Vec<u8>> {
+  const REMOVED_CHUNKS: &[&[u8]] = &[b"eXIf", b"tEXt", b"zTXt", b"iTXt", b"tIME"];
+
+  let mut out = data.get(..8)?.to_vec();
+  let mut pos = 8;
+  while pos < data.len() {
+    let length = u32::from_be_bytes(data.get(pos..pos + 4)?.try_into().ok()?) as usize;
+    // Length, type, data and crc
+    let chunk = data.get(pos..pos + 12 + length)?;
+    if !REMOVED_CHUNKS.contains(&&chunk[4..8]) {
+      out.extend_from_slice(chunk);
+    }
+    pos += chunk.len();
+  }
+  Some(out)
+}
+
+fn strip_webp(data: &[u8]) -> Option<Vec<u8>> {
+  const REMOVED_CHUNKS: &[&[u8]] = &[b"EXIF", b"XMP "];
+  // Flags in the extended format header which announce the removed chunks
+  const METADATA_FLAGS: u8 = 0x04 | 0x08;
+
+  let mut out = data.get(..12)?.to_vec();
+  let mut pos = 12;
+  while pos < data.len() {
+    let length = u32::from_le_bytes(data.get(pos + 4..pos + 8)?.try_into().ok()?) as usize;
+    // Chunks are padded to an even length
+    let padded = length + length % 2;
+    let chunk = data.get(pos..(pos + 8 + padded).min(data.len()))?;
+    let kind = &chunk[..4];
+    if kind == b"VP8X" {
+      let mut chunk = chunk.to_vec();
+      *chunk.get_mut(8)? &= !METADATA_FLAGS;
+      out.extend_from_slice(&chunk);
+    } else if !REMOVED_CHUNKS.contains(&kind) {
+      out.extend_from_slice(chunk);
+    }
+    pos += 8 + padded;
+  }
+  let riff_size = u32::try_from(out.len() - 8).ok()?;
+  out[4..8].copy_from_slice(&riff_size.to_le_bytes());
+  Some(out)
+}
+
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+  haystack.windows(needle.len()).position(|w| w == needle)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use image::{DynamicImage, ImageOutputFormat, RgbImage};
+
+  fn encode(format: ImageOutputFormat) -> Vec<u8> {
+    let mut data = vec![];
+    DynamicImage::ImageRgb8(RgbImage::new(4, 3))
+      .write_to(&mut data, format)
+      .unwrap();
+    data
+  }
+
+  #[test]
+  fn test_multipart_roundtrip() {
+    let body =
+      b"--abc\r\nContent-Disposition: form-data; name=\"images[]\"; filename=\"a.png\"\r\n\
+      Content-Type: image/png\r\n\r\nfile\r\ncontent\r\n--abc--\r\n";
+    let boundary = parse_boundary("multipart/form-data; boundary=\"abc\"").unwrap();
+    assert_eq!("abc", boundary);
+    let parts = split_multipart(body, &boundary).unwrap();
+    assert_eq!(1, parts.len());
+    assert_eq!(b"file\r\ncontent".to_vec(), parts[0].data);
+    assert_eq!(body.to_vec(), join_multipart(&parts, &boundary));
+
+    assert!(parse_boundary("image/png").is_err());
+    assert!(split_multipart(b"--abc\r\n\r\nunterminated", "abc").is_err());
+  }
+
+  #[test]
+  fn test_validate_image() {
+    let config = ImageUploadConfig::default();
+    let png = encode(ImageOutputFormat::Png);
+    assert_eq!(ImageFormat::Png, validate_image(&png, &config).unwrap());
+    assert!(validate_image(b"<svg></svg>", &config).is_err());
+    let mut parts = vec![Part {
+      headers: b"Content-Disposition: form-data; name=\"images[]\"".to_vec(),
+      data: b"<svg></svg>".to_vec(),
+    }];
+    assert!(validate_parts(&mut parts, &config).is_err());
+
+    let small = ImageUploadConfig {
+      max_dimension: 3,
+      ..config
+    };
+    assert!(validate_image(&png, &small).is_err());
+  }
+
+  #[test]
+  fn test_strip_metadata() {
+    let jpeg = encode(ImageOutputFormat::Jpeg(90));
+    let mut with_exif = jpeg[..2].to_vec();
+    with_exif.extend_from_slice(&[0xFF, 0xE1, 0x00, 0x08]);
+    with_exif.extend_from_slice(b"Exif\0\0");
+    with_exif.extend_from_slice(&jpeg[2..]);
+    assert_eq!(jpeg, strip_metadata(&with_exif, ImageFormat::Jpeg).unwrap());
+
+    let png = encode(ImageOutputFormat::Png);
+    let mut with_text = png[..33].to_vec();
+    with_text.extend_from_slice(&[0, 0, 0, 4]);
+    with_text.extend_from_slice(b"tEXtGPS!");
+    with_text.extend_from_slice(&[0, 0, 0, 0]);
+    with_text.extend_from_slice(&png[33..]);
+    let stripped = strip_metadata(&with_text, ImageFormat::Png).unwrap();
+    assert_eq!(png, stripped);
+    assert!(image::load_from_memory(&stripped).is_ok());
+
+    assert!(strip_metadata(&png[..20], ImageFormat::Png).is_err());
+  }
+}
@@ -1,4 +1,5 @@
 pub mod feeds;
 pub mod images;
+pub mod metrics;
 pub mod nodeinfo;
 pub mod webfinger;
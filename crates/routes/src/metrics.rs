@@ -0,0 +1,64 @@
+use actix_web::{http::header, web, HttpRequest, HttpResponse};
+use lemmy_api_common::blocking;
+use lemmy_db_schema::source::activity_send_queue::QueuedActivity;
+use lemmy_utils::{metrics::Gauge, settings::structs::Settings, LemmyError};
+use lemmy_websocket::{messages::GetUsersOnline, LemmyContext};
+
+pub fn config(cfg: &mut web::ServiceConfig, settings: &Settings) {
+  if settings.metrics.enabled {
+    cfg.route("/metrics", web::get().to(get_metrics));
+  }
+}
+
+/// Serves the metrics in the Prometheus text format
+async fn get_metrics(
+  req: HttpRequest,
+  context: web::Data<LemmyContext>,
+) -> Result<HttpResponse, LemmyError> {
+  if let Some(token) = context.settings().metrics.bearer_token {
+    let authorization = req
+      .headers()
+      .get(header::AUTHORIZATION)
+      .and_then(|h| h.to_str().ok());
+    if authorization != Some(&format!("Bearer {}", token)) {
+      return Ok(HttpResponse::Unauthorized().finish());
+    }
+  }
+
+  let pool_state = context.pool().state();
+  let federation_queue_size = blocking(context.pool(), QueuedActivity::count).await??;
+  let websocket_connections = context.chat_server().send(GetUsersOnline).await?;
+  let gauges = [
+    Gauge {
+      name: "lemmy_db_pool_max_size",
+      help: "Maximum number of database connections",
+      value: context.pool().max_size() as f64,
+    },
+    Gauge {
+      name: "lemmy_db_pool_connections",
+      help: "Open database connections",
+      value: pool_state.connections as f64,
+    },
+    Gauge {
+      name: "lemmy_db_pool_idle_connections",
+      help: "Open database connections which are not in use",
+      value: pool_state.idle_connections as f64,
+    },
+    Gauge {
+      name: "lemmy_federation_queue_size",
+      help: "Outgoing activity deliveries which are waiting in the queue",
+      value: federation_queue_size as f64,
+    },
+    Gauge {
+      name: "lemmy_websocket_connections",
+      help: "Open websocket connections",
+      value: websocket_connections as f64,
+    },
+  ];
+
+  Ok(
+    HttpResponse::Ok()
+      .content_type("text/plain; version=0.0.4")
+      .body(context.metrics().render(&gauges)),
+  )
+}
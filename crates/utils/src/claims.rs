@@ -12,6 +12,9 @@ pub struct Claims {
   pub iss: String,
   /// Time when this token was issued as UNIX-timestamp in seconds
   pub iat: i64,
+  /// Id of the login session, which is missing in tokens that were issued before sessions existed
+  #[serde(default)]
+  pub sid: Option<i32>,
 }
 
 impl Claims {
@@ -24,11 +27,17 @@ impl Claims {
     Ok(decode::<Claims>(jwt, &key, &v)?)
   }
 
-  pub fn jwt(local_user_id: i32, jwt_secret: &str, hostname: &str) -> Result<Jwt, LemmyError> {
+  pub fn jwt(
+    local_user_id: i32,
+    session_id: i32,
+    jwt_secret: &str,
+    hostname: &str,
+  ) -> Result<Jwt, LemmyError> {
     let my_claims = Claims {
       sub: local_user_id,
       iss: hostname.to_string(),
       iat: Utc::now().timestamp(),
+      sid: Some(session_id),
     };
 
     let key = EncodingKey::from_secret(jwt_secret.as_ref());
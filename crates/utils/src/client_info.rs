@@ -0,0 +1,39 @@
+use std::future::Future;
+
+/// Details of the client which sent the current http request
+#[derive(Clone, Debug, Default)]
+pub struct ClientInfo {
+  pub user_agent: Option<String>,
+  pub ip: Option<String>,
+}
+
+tokio::task_local! {
+  static CLIENT_INFO: ClientInfo;
+}
+
+/// Runs the future with the given client details, so that API handlers can record them
+pub async fn with_client_info<F: Future>(client_info: ClientInfo, f: F) -> F::Output {
+  CLIENT_INFO.scope(client_info, f).await
+}
+
+/// The client of the current http request, or nothing for websocket messages
+pub fn current_client_info() -> ClientInfo {
+  CLIENT_INFO.try_with(Clone::clone).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::client_info::{current_client_info, with_client_info, ClientInfo};
+
+  #[actix_rt::test]
+  async fn test_client_info() {
+    assert_eq!(None, current_client_info().user_agent);
+    let client_info = ClientInfo {
+      user_agent: Some("Mozilla/5.0".to_string()),
+      ip: Some("203.0.113.7".to_string()),
+    };
+    let inner = with_client_info(client_info, async { current_client_info() }).await;
+    assert_eq!(Some("Mozilla/5.0".to_string()), inner.user_agent);
+    assert_eq!(Some("203.0.113.7".to_string()), inner.ip);
+  }
+}
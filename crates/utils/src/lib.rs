@@ -4,16 +4,21 @@ extern crate strum_macros;
 extern crate smart_default;
 
 pub mod apub;
+pub mod client_info;
 pub mod email;
+pub mod metrics;
 pub mod rate_limit;
 pub mod request;
+pub mod request_id;
 pub mod settings;
+pub mod totp;
 
 pub mod claims;
 #[cfg(test)]
 mod test;
 pub mod utils;
 pub mod version;
+pub mod web_push;
 
 mod sensitive;
 
@@ -0,0 +1,172 @@
+use once_cell::sync::Lazy;
+use std::{
+  collections::BTreeMap,
+  fmt::Write,
+  sync::{
+    atomic::{AtomicI64, Ordering},
+    Mutex,
+  },
+  time::Duration,
+};
+
+/// Upper bounds of the latency histogram buckets, in seconds
+const LATENCY_BUCKETS: [f64; 11] = [
+  0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0,
+];
+
+/// Metrics which are collected while the server is running. Values which can be read directly,
+/// like the state of the database pool, are passed to `render` when metrics are scraped instead.
+#[derive(Default)]
+pub struct Metrics {
+  blocking_in_flight: AtomicI64,
+  endpoint_latencies: Mutex<BTreeMap<&'static str, Histogram>>,
+}
+
+pub static METRICS: Lazy<Metrics> = Lazy::new(Metrics::default);
+
+#[derive(Default, Clone)]
+struct Histogram {
+  /// Non-cumulative count for each bucket in `LATENCY_BUCKETS`
+  buckets: [u64; LATENCY_BUCKETS.len()],
+  sum: f64,
+  count: u64,
+}
+
+/// A gauge whose value is read when the metrics are rendered
+pub struct Gauge {
+  pub name: &'static str,
+  pub help: &'static str,
+  pub value: f64,
+}
+
+/// Counts a database operation as queued or running, until it is dropped
+pub struct BlockingGuard<'a>(&'a Metrics);
+
+impl<'a> Drop for BlockingGuard<'a> {
+  fn drop(&mut self) {
+    self.0.blocking_in_flight.fetch_sub(1, Ordering::Relaxed);
+  }
+}
+
+impl Metrics {
+  pub fn track_blocking(&self) -> BlockingGuard<'_> {
+    self.blocking_in_flight.fetch_add(1, Ordering::Relaxed);
+    BlockingGuard(self)
+  }
+
+  pub fn observe_endpoint_latency(&self, endpoint: &'static str, duration: Duration) {
+    let seconds = duration.as_secs_f64();
+    let mut latencies = self
+      .endpoint_latencies
+      .lock()
+      .expect("lock endpoint latencies");
+    let histogram = latencies.entry(endpoint).or_default();
+    if let Some(i) = LATENCY_BUCKETS.iter().position(|b| seconds <= *b) {
+      histogram.buckets[i] += 1;
+    }
+    histogram.sum += seconds;
+    histogram.count += 1;
+  }
+
+  /// Renders all metrics in the Prometheus text format
+  pub fn render(&self, gauges: &[Gauge]) -> String {
+    let mut out = String::new();
+    let blocking = Gauge {
+      name: "lemmy_db_blocking_in_flight",
+      help: "Database operations which are waiting for or running on a blocking thread",
+      value: self.blocking_in_flight.load(Ordering::Relaxed) as f64,
+    };
+    for gauge in gauges.iter().chain(std::iter::once(&blocking)) {
+      // Writing to a String can't fail
+      let _ = writeln!(out, "# HELP {} {}", gauge.name, gauge.help);
+      let _ = writeln!(out, "# TYPE {} gauge", gauge.name);
+      let _ = writeln!(out, "{} {}", gauge.name, gauge.value);
+    }
+
+    let name = "lemmy_api_request_duration_seconds";
+    let _ = writeln!(out, "# HELP {} Duration of HTTP API requests", name);
+    let _ = writeln!(out, "# TYPE {} histogram", name);
+    let latencies = self
+      .endpoint_latencies
+      .lock()
+      .expect("lock endpoint latencies")
+      .clone();
+    for (endpoint, histogram) in latencies {
+      let mut cumulative = 0;
+      for (bound, count) in LATENCY_BUCKETS.iter().zip(histogram.buckets.iter()) {
+        cumulative += count;
+        let _ = writeln!(
+          out,
+          "{}_bucket{{endpoint=\"{}\",le=\"{}\"}} {}",
+          name, endpoint, bound, cumulative
+        );
+      }
+      let _ = writeln!(
+        out,
+        "{}_bucket{{endpoint=\"{}\",le=\"+Inf\"}} {}",
+        name, endpoint, histogram.count
+      );
+      let _ = writeln!(
+        out,
+        "{}_sum{{endpoint=\"{}\"}} {}",
+        name, endpoint, histogram.sum
+      );
+      let _ = writeln!(
+        out,
+        "{}_count{{endpoint=\"{}\"}} {}",
+        name, endpoint, histogram.count
+      );
+    }
+    out
+  }
+}
+
+/// Name of a request type without its module path, used as endpoint label
+pub fn endpoint_label<T>() -> &'static str {
+  let name = std::any::type_name::<T>();
+  name.rsplit("::").next().unwrap_or(name)
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::metrics::{endpoint_label, Gauge, Metrics};
+  use std::time::Duration;
+
+  struct GetPosts;
+
+  #[test]
+  fn test_render() {
+    let metrics = Metrics::default();
+    let guard = metrics.track_blocking();
+    metrics.observe_endpoint_latency(endpoint_label::<GetPosts>(), Duration::from_millis(20));
+    metrics.observe_endpoint_latency(endpoint_label::<GetPosts>(), Duration::from_secs(20));
+    let rendered = metrics.render(&[Gauge {
+      name: "lemmy_websocket_connections",
+      help: "Open websocket connections",
+      value: 3.0,
+    }]);
+    drop(guard);
+
+    assert!(rendered.contains("# TYPE lemmy_websocket_connections gauge\n"));
+    assert!(rendered.contains("\nlemmy_websocket_connections 3\n"));
+    assert!(rendered.contains("\nlemmy_db_blocking_in_flight 1\n"));
+    assert!(rendered.contains(
+      "\nlemmy_api_request_duration_seconds_bucket{endpoint=\"GetPosts\",le=\"0.01\"} 0\n"
+    ));
+    assert!(rendered.contains(
+      "\nlemmy_api_request_duration_seconds_bucket{endpoint=\"GetPosts\",le=\"0.025\"} 1\n"
+    ));
+    assert!(rendered.contains(
+      "\nlemmy_api_request_duration_seconds_bucket{endpoint=\"GetPosts\",le=\"10\"} 1\n"
+    ));
+    assert!(rendered.contains(
+      "\nlemmy_api_request_duration_seconds_bucket{endpoint=\"GetPosts\",le=\"+Inf\"} 2\n"
+    ));
+    assert!(
+      rendered.contains("\nlemmy_api_request_duration_seconds_count{endpoint=\"GetPosts\"} 2\n")
+    );
+    assert!(metrics
+      .render(&[])
+      .contains("\nlemmy_db_blocking_in_flight 0\n"));
+  }
+}
@@ -1,7 +1,13 @@
-use crate::{settings::structs::RateLimitConfig, utils::get_ip, IpAddr, LemmyError};
+use crate::{
+  settings::structs::{RateLimitConfig, Settings},
+  utils::get_ip,
+  IpAddr,
+  LemmyError,
+};
 use actix_web::dev::{Service, ServiceRequest, ServiceResponse, Transform};
 use futures::future::{ok, Ready};
-use rate_limiter::{RateLimitType, RateLimiter};
+use rate_limiter::{RateLimitStore, RateLimitType, RateLimiter};
+use redis_store::RedisRateLimitStore;
 use std::{
   future::Future,
   pin::Pin,
@@ -11,18 +17,17 @@ use std::{
 use tokio::sync::Mutex;
 
 pub mod rate_limiter;
+pub mod redis_store;
 
 #[derive(Debug, Clone)]
 pub struct RateLimit {
-  // it might be reasonable to use a std::sync::Mutex here, since we don't need to lock this
-  // across await points
-  pub rate_limiter: Arc<Mutex<RateLimiter>>,
+  pub rate_limiter: Arc<dyn RateLimitStore>,
   pub rate_limit_config: RateLimitConfig,
 }
 
 #[derive(Debug, Clone)]
 pub struct RateLimited {
-  rate_limiter: Arc<Mutex<RateLimiter>>,
+  rate_limiter: Arc<dyn RateLimitStore>,
   rate_limit_config: RateLimitConfig,
   type_: RateLimitType,
 }
@@ -33,6 +38,19 @@ pub struct RateLimitedMiddleware<S> {
 }
 
 impl RateLimit {
+  /// Keeps the rate limits in Redis if it is configured, so that they are shared between multiple
+  /// lemmy_server processes. Otherwise they are kept in memory.
+  pub async fn new(settings: &Settings) -> Result<Self, LemmyError> {
+    let rate_limiter: Arc<dyn RateLimitStore> = match &settings.redis {
+      Some(redis) => Arc::new(RedisRateLimitStore::connect(&redis.url).await?),
+      None => Arc::new(Mutex::new(RateLimiter::default())),
+    };
+    Ok(RateLimit {
+      rate_limiter,
+      rate_limit_config: settings.rate_limit.to_owned().unwrap_or_default(),
+    })
+  }
+
   pub fn message(&self) -> RateLimited {
     self.kind(RateLimitType::Message)
   }
@@ -71,94 +89,31 @@ impl RateLimited {
   where
     E: From<LemmyError>,
   {
-    // Does not need to be blocking because the RwLock in settings never held across await points,
-    // and the operation here locks only long enough to clone
     let rate_limit = self.rate_limit_config;
+    let (rate, per) = match self.type_ {
+      RateLimitType::Message => (rate_limit.message, rate_limit.message_per_second),
+      RateLimitType::Post => (rate_limit.post, rate_limit.post_per_second),
+      RateLimitType::Register => (rate_limit.register, rate_limit.register_per_second),
+      RateLimitType::Image => (rate_limit.image, rate_limit.image_per_second),
+      RateLimitType::Comment => (rate_limit.comment, rate_limit.comment_per_second),
+    };
+    // Posts and registrations are only counted if they succeed
+    let count_after = matches!(self.type_, RateLimitType::Post | RateLimitType::Register);
 
     // before
-    {
-      let mut limiter = self.rate_limiter.lock().await;
-
-      match self.type_ {
-        RateLimitType::Message => {
-          limiter.check_rate_limit_full(
-            self.type_,
-            &ip_addr,
-            rate_limit.message,
-            rate_limit.message_per_second,
-            false,
-          )?;
-
-          drop(limiter);
-          return fut.await;
-        }
-        RateLimitType::Post => {
-          limiter.check_rate_limit_full(
-            self.type_,
-            &ip_addr,
-            rate_limit.post,
-            rate_limit.post_per_second,
-            true,
-          )?;
-        }
-        RateLimitType::Register => {
-          limiter.check_rate_limit_full(
-            self.type_,
-            &ip_addr,
-            rate_limit.register,
-            rate_limit.register_per_second,
-            true,
-          )?;
-        }
-        RateLimitType::Image => {
-          limiter.check_rate_limit_full(
-            self.type_,
-            &ip_addr,
-            rate_limit.image,
-            rate_limit.image_per_second,
-            false,
-          )?;
-        }
-        RateLimitType::Comment => {
-          limiter.check_rate_limit_full(
-            self.type_,
-            &ip_addr,
-            rate_limit.comment,
-            rate_limit.comment_per_second,
-            false,
-          )?;
-        }
-      };
-    }
+    self
+      .rate_limiter
+      .check_rate_limit(self.type_, &ip_addr, rate, per, count_after)
+      .await?;
 
     let res = fut.await;
 
     // after
-    {
-      let mut limiter = self.rate_limiter.lock().await;
-      if res.is_ok() {
-        match self.type_ {
-          RateLimitType::Post => {
-            limiter.check_rate_limit_full(
-              self.type_,
-              &ip_addr,
-              rate_limit.post,
-              rate_limit.post_per_second,
-              false,
-            )?;
-          }
-          RateLimitType::Register => {
-            limiter.check_rate_limit_full(
-              self.type_,
-              &ip_addr,
-              rate_limit.register,
-              rate_limit.register_per_second,
-              false,
-            )?;
-          }
-          _ => (),
-        };
-      }
+    if count_after && res.is_ok() {
+      self
+        .rate_limiter
+        .check_rate_limit(self.type_, &ip_addr, rate, per, false)
+        .await?;
     }
 
     res
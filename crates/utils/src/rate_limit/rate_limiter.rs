@@ -1,6 +1,8 @@
 use crate::{IpAddr, LemmyError};
-use std::{collections::HashMap, time::SystemTime};
+use async_trait::async_trait;
+use std::{collections::HashMap, fmt::Debug, time::SystemTime};
 use strum::IntoEnumIterator;
+use tokio::sync::Mutex;
 use tracing::debug;
 
 #[derive(Debug, Clone)]
@@ -10,7 +12,7 @@ struct RateLimitBucket {
 }
 
 #[derive(Eq, PartialEq, Hash, Debug, EnumIter, Copy, Clone, AsRefStr)]
-pub(crate) enum RateLimitType {
+pub enum RateLimitType {
   Message,
   Register,
   Post,
@@ -18,6 +20,21 @@ pub(crate) enum RateLimitType {
   Comment,
 }
 
+/// Storage for the rate limit buckets
+#[async_trait]
+pub trait RateLimitStore: Debug + Send + Sync {
+  /// Allows `rate` actions per `per` seconds for each type and IP. If `check_only` is set, the
+  /// action isn't counted.
+  async fn check_rate_limit(
+    &self,
+    type_: RateLimitType,
+    ip: &IpAddr,
+    rate: i32,
+    per: i32,
+    check_only: bool,
+  ) -> Result<(), LemmyError>;
+}
+
 /// Rate limiting based on rate type and IP addr
 #[derive(Debug, Clone, Default)]
 pub struct RateLimiter {
@@ -46,7 +63,7 @@ impl RateLimiter {
   }
 
   #[allow(clippy::float_cmp)]
-  pub(super) fn check_rate_limit_full(
+  fn check_rate_limit_full(
     &mut self,
     type_: RateLimitType,
     ip: &IpAddr,
@@ -79,14 +96,7 @@ impl RateLimiter {
             time_passed,
             rate_limit.allowance
           );
-          let error = LemmyError::from(anyhow::anyhow!(
-            "Too many requests. type: {}, IP: {}, {} per {} seconds",
-            type_.as_ref(),
-            ip,
-            rate,
-            per
-          ));
-          Err(error.with_message("too_many_requests"))
+          Err(rate_limit_error(type_, ip, rate, per))
         } else {
           if !check_only {
             rate_limit.allowance -= 1.0;
@@ -101,3 +111,37 @@ impl RateLimiter {
     }
   }
 }
+
+/// Keeps the rate limits in memory, so they only apply to a single lemmy_server process
+#[async_trait]
+impl RateLimitStore for Mutex<RateLimiter> {
+  async fn check_rate_limit(
+    &self,
+    type_: RateLimitType,
+    ip: &IpAddr,
+    rate: i32,
+    per: i32,
+    check_only: bool,
+  ) -> Result<(), LemmyError> {
+    self
+      .lock()
+      .await
+      .check_rate_limit_full(type_, ip, rate, per, check_only)
+  }
+}
+
+pub(super) fn rate_limit_error(
+  type_: RateLimitType,
+  ip: &IpAddr,
+  rate: i32,
+  per: i32,
+) -> LemmyError {
+  let error = LemmyError::from(anyhow::anyhow!(
+    "Too many requests. type: {}, IP: {}, {} per {} seconds",
+    type_.as_ref(),
+    ip,
+    rate,
+    per
+  ));
+  error.with_message("too_many_requests")
+}
@@ -0,0 +1,92 @@
+use crate::{
+  rate_limit::rate_limiter::{rate_limit_error, RateLimitStore, RateLimitType},
+  IpAddr,
+  LemmyError,
+};
+use async_trait::async_trait;
+use once_cell::sync::Lazy;
+use redis::{aio::ConnectionManager, Client, Script};
+use std::fmt::{Debug, Formatter};
+use tracing::warn;
+
+/// Same algorithm as the in-memory rate limiter, run atomically on the Redis server. The server
+/// time is used so that clock differences between lemmy_server processes don't matter. Buckets
+/// expire once they would be full again anyway.
+static CHECK_RATE_LIMIT: Lazy<Script> = Lazy::new(|| {
+  Script::new(
+    r#"
+local rate = tonumber(ARGV[1])
+local per = tonumber(ARGV[2])
+local check_only = ARGV[3] == "1"
+local time = redis.call("TIME")
+local now = tonumber(time[1]) + tonumber(time[2]) / 1000000
+local bucket = redis.call("HMGET", KEYS[1], "allowance", "last_checked")
+local allowance = tonumber(bucket[1]) or rate
+local last_checked = tonumber(bucket[2]) or now
+allowance = allowance + (now - last_checked) * (rate / per)
+if not check_only and allowance > rate then
+  allowance = rate
+end
+local allowed = allowance >= 1
+if allowed and not check_only then
+  allowance = allowance - 1
+end
+redis.call("HSET", KEYS[1], "allowance", tostring(allowance), "last_checked", tostring(now))
+redis.call("EXPIRE", KEYS[1], per)
+if allowed then
+  return 1
+end
+return 0
+"#,
+  )
+});
+
+/// Keeps the rate limits in Redis, so that they are shared between all lemmy_server processes
+#[derive(Clone)]
+pub struct RedisRateLimitStore {
+  connection: ConnectionManager,
+}
+
+impl RedisRateLimitStore {
+  pub async fn connect(url: &str) -> Result<Self, LemmyError> {
+    let client = Client::open(url)?;
+    let connection = ConnectionManager::new(client).await?;
+    Ok(RedisRateLimitStore { connection })
+  }
+}
+
+impl Debug for RedisRateLimitStore {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    f.debug_struct("RedisRateLimitStore").finish()
+  }
+}
+
+#[async_trait]
+impl RateLimitStore for RedisRateLimitStore {
+  async fn check_rate_limit(
+    &self,
+    type_: RateLimitType,
+    ip: &IpAddr,
+    rate: i32,
+    per: i32,
+    check_only: bool,
+  ) -> Result<(), LemmyError> {
+    let key = format!("lemmy:rate_limit:{}:{}", type_.as_ref(), ip);
+    let allowed: Result<bool, _> = CHECK_RATE_LIMIT
+      .key(key)
+      .arg(rate)
+      .arg(per)
+      .arg(check_only)
+      .invoke_async(&mut self.connection.clone())
+      .await;
+    match allowed {
+      Ok(true) => Ok(()),
+      Ok(false) => Err(rate_limit_error(type_, ip, rate, per)),
+      // Rate limits are not worth taking the site down while Redis is unavailable
+      Err(e) => {
+        warn!("Failed to check rate limit in Redis: {}", e);
+        Ok(())
+      }
+    }
+  }
+}
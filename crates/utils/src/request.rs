@@ -1,6 +1,8 @@
 use crate::{settings::structs::Settings, version::VERSION, LemmyError};
 use anyhow::anyhow;
+use once_cell::sync::Lazy;
 use percent_encoding::{utf8_percent_encode, NON_ALPHANUMERIC};
+use regex::Regex;
 use reqwest_middleware::ClientWithMiddleware;
 use serde::{Deserialize, Serialize};
 use std::future::Future;
@@ -9,6 +11,14 @@ use tracing::error;
 use url::Url;
 use webpage::HTML;
 
+static LINK_TAG_REGEX: Lazy<Regex> =
+  Lazy::new(|| Regex::new(r"(?i)<link\s[^>]*>").expect("compile regex"));
+static IFRAME_SRC_REGEX: Lazy<Regex> = Lazy::new(|| {
+  Regex::new(r#"(?i)<iframe\s[^>]*src\s*=\s*["']([^"']+)["']"#).expect("compile regex")
+});
+static HREF_REGEX: Lazy<Regex> =
+  Lazy::new(|| Regex::new(r#"(?i)href\s*=\s*["']([^"']+)["']"#).expect("compile regex"));
+
 #[derive(Clone, Debug, Error)]
 #[error("Error sending request, {0}")]
 struct SendError(pub String);
@@ -51,12 +61,24 @@ where
   response.expect("retry http request")
 }
 
-#[derive(Deserialize, Serialize, Debug, PartialEq, Clone)]
+#[derive(Deserialize, Serialize, Debug, PartialEq, Clone, Default)]
 pub struct SiteMetadata {
   pub title: Option<String>,
   pub description: Option<String>,
   image: Option<Url>,
   pub html: Option<String>,
+  /// Embeddable player for the linked video, either a video file or a page for use in an iframe
+  pub embed_video_url: Option<Url>,
+  pub provider_name: Option<String>,
+}
+
+/// Only the parts of an oEmbed response which are needed for video embeds
+#[derive(Deserialize, Debug)]
+struct OEmbedResponse {
+  #[serde(rename = "type")]
+  kind: String,
+  html: Option<String>,
+  provider_name: Option<String>,
 }
 
 /// Fetches the post link html tags (like title, description, image, etc)
@@ -71,11 +93,57 @@ pub async fn fetch_site_metadata(
     .await
     .map_err(|e| RecvError(e.to_string()))?;
 
-  let tags = html_to_site_metadata(&html)?;
+  let mut tags = html_to_site_metadata(&html)?;
+
+  // Sites without OpenGraph video tags may still offer a player through oEmbed. Errors are
+  // ignored here, as the remaining metadata is still useful.
+  if tags.embed_video_url.is_none() {
+    if let Some(oembed_url) = oembed_url(&html, url) {
+      if let Ok(oembed) = fetch_oembed(client, &oembed_url).await {
+        tags.embed_video_url = oembed_video_url(&oembed);
+        tags.provider_name = tags.provider_name.or(oembed.provider_name);
+      }
+    }
+  }
 
   Ok(tags)
 }
 
+async fn fetch_oembed(
+  client: &ClientWithMiddleware,
+  oembed_url: &Url,
+) -> Result<OEmbedResponse, LemmyError> {
+  let response = client.get(oembed_url.as_str()).send().await?;
+  Ok(
+    response
+      .json()
+      .await
+      .map_err(|e| RecvError(e.to_string()))?,
+  )
+}
+
+/// Finds the json oEmbed endpoint which a page announces in its `<link>` tags
+fn oembed_url(html: &str, page_url: &Url) -> Option<Url> {
+  LINK_TAG_REGEX
+    .find_iter(html)
+    .map(|tag| tag.as_str())
+    .find(|tag| tag.to_lowercase().contains("application/json+oembed"))
+    .and_then(|tag| HREF_REGEX.captures(tag))
+    .and_then(|href| page_url.join(&href[1].replace("&amp;", "&")).ok())
+}
+
+/// oEmbed only provides html for videos, so take the player url from its iframe
+fn oembed_video_url(oembed: &OEmbedResponse) -> Option<Url> {
+  if oembed.kind != "video" {
+    return None;
+  }
+  let html = oembed.html.as_ref()?;
+  let src = IFRAME_SRC_REGEX.captures(html)?;
+  Url::parse(&src[1].replace("&amp;", "&"))
+    .ok()
+    .filter(|u| u.scheme() == "https" || u.scheme() == "http")
+}
+
 fn html_to_site_metadata(html: &str) -> Result<SiteMetadata, LemmyError> {
   let page = HTML::from_string(html.to_string(), None)?;
 
@@ -98,6 +166,15 @@ fn html_to_site_metadata(html: &str) -> Result<SiteMetadata, LemmyError> {
     .get(0)
     .map(|ogo| Url::parse(&ogo.url).ok())
     .flatten();
+  let og_video = page.opengraph.videos.first().and_then(|ogo| {
+    let url = ogo.properties.get("secure_url").unwrap_or(&ogo.url);
+    Url::parse(url).ok()
+  });
+  let og_site_name = page
+    .opengraph
+    .properties
+    .get("site_name")
+    .map(|t| t.to_string());
 
   let title = og_title.or(page_title);
   let description = og_description.or(page_description);
@@ -108,6 +185,8 @@ fn html_to_site_metadata(html: &str) -> Result<SiteMetadata, LemmyError> {
     description,
     image,
     html: None,
+    embed_video_url: og_video,
+    provider_name: og_site_name,
   })
 }
 
@@ -155,6 +234,53 @@ pub(crate) async fn fetch_pictrs(
   }
 }
 
+#[derive(Deserialize, Debug)]
+struct PictrsPurgeResponse {
+  msg: String,
+}
+
+/// Permanently deletes an image which was uploaded to the local pictrs, with all its variants.
+/// Returns without doing anything if the url points elsewhere.
+pub async fn purge_image_from_pictrs(
+  client: &ClientWithMiddleware,
+  settings: &Settings,
+  image_url: &Url,
+) -> Result<(), LemmyError> {
+  let local_prefix = format!("{}/pictrs/image/", settings.get_protocol_and_hostname());
+  let alias = match image_url.as_str().strip_prefix(&local_prefix) {
+    Some(alias) => alias.split(&['?', '#'][..]).next().unwrap_or(alias),
+    None => return Ok(()),
+  };
+  let pictrs_url = settings
+    .pictrs_url
+    .to_owned()
+    .ok_or_else(|| anyhow!("pictrs_url not set up in config"))?;
+  let api_key = settings
+    .pictrs_api_key
+    .to_owned()
+    .ok_or_else(|| anyhow!("pictrs_api_key not set up in config"))?;
+
+  let purge_url = format!(
+    "{}/internal/purge?alias={}",
+    pictrs_url,
+    utf8_percent_encode(alias, NON_ALPHANUMERIC)
+  );
+  let response: PictrsPurgeResponse = client
+    .post(&purge_url)
+    .header("X-Api-Token", api_key)
+    .send()
+    .await?
+    .json()
+    .await
+    .map_err(|e| RecvError(e.to_string()))?;
+
+  if response.msg == "ok" {
+    Ok(())
+  } else {
+    Err(anyhow!("{}", &response.msg).into())
+  }
+}
+
 /// Both are options, since the URL might be either an html page, or an image
 /// Returns the SiteMetadata, and a Pictrs URL, if there is a picture associated
 pub async fn fetch_site_data(
@@ -222,6 +348,86 @@ async fn is_image_content_type(client: &ClientWithMiddleware, url: &Url) -> Resu
   }
 }
 
+/// Software and usage which a remote instance reports in its nodeinfo. Only the fields which are
+/// stored are parsed, and all of them are optional as implementations differ a lot.
+#[derive(Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct NodeInfo {
+  pub software: Option<NodeInfoSoftware>,
+  pub usage: Option<NodeInfoUsage>,
+}
+
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+pub struct NodeInfoSoftware {
+  pub name: Option<String>,
+  pub version: Option<String>,
+}
+
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+pub struct NodeInfoUsage {
+  pub users: Option<NodeInfoUsers>,
+}
+
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct NodeInfoUsers {
+  pub total: Option<i64>,
+  pub active_month: Option<i64>,
+}
+
+#[derive(Deserialize, Debug)]
+struct NodeInfoWellKnown {
+  links: Vec<NodeInfoWellKnownLink>,
+}
+
+#[derive(Deserialize, Debug)]
+struct NodeInfoWellKnownLink {
+  rel: String,
+  href: Url,
+}
+
+/// Fetches the nodeinfo of the instance, following the link from its `/.well-known/nodeinfo`
+#[tracing::instrument(skip_all)]
+pub async fn fetch_nodeinfo(
+  client: &ClientWithMiddleware,
+  settings: &Settings,
+  domain: &str,
+) -> Result<NodeInfo, LemmyError> {
+  let well_known_url = format!(
+    "{}://{}/.well-known/nodeinfo",
+    settings.get_protocol_string(),
+    domain
+  );
+  let response = client.get(&well_known_url).send().await?;
+  let well_known: NodeInfoWellKnown = response
+    .json()
+    .await
+    .map_err(|e| RecvError(e.to_string()))?;
+  let href = nodeinfo_href(&well_known, domain).ok_or_else(|| anyhow!("No nodeinfo link"))?;
+
+  let response = client.get(href.as_str()).send().await?;
+  Ok(
+    response
+      .json()
+      .await
+      .map_err(|e| RecvError(e.to_string()))?,
+  )
+}
+
+/// Picks the newest supported nodeinfo version. Links to other domains are ignored, so that
+/// instances can't make us send requests elsewhere.
+fn nodeinfo_href(well_known: &NodeInfoWellKnown, domain: &str) -> Option<Url> {
+  well_known
+    .links
+    .iter()
+    .filter(|l| {
+      l.rel
+        .starts_with("http://nodeinfo.diaspora.software/ns/schema/2.")
+    })
+    .filter(|l| l.href.host_str() == Some(domain))
+    .max_by(|a, b| a.rel.cmp(&b.rel))
+    .map(|l| l.href.clone())
+}
+
 pub fn build_user_agent(settings: &Settings) -> String {
   format!(
     "Lemmy/{}; +{}",
@@ -232,7 +438,17 @@ pub fn build_user_agent(settings: &Settings) -> String {
 
 #[cfg(test)]
 mod tests {
-  use crate::request::{build_user_agent, fetch_site_metadata};
+  use crate::request::{
+    build_user_agent,
+    fetch_site_metadata,
+    html_to_site_metadata,
+    nodeinfo_href,
+    oembed_url,
+    oembed_video_url,
+    NodeInfo,
+    NodeInfoWellKnown,
+    OEmbedResponse,
+  };
   use url::Url;
 
   use super::SiteMetadata;
@@ -255,6 +471,8 @@ mod tests {
         description: Some("BHOJPUR: A district leader of the outlawed Netra Bikram Chand alias Biplav-led outfit has been arrested. According to District Police".to_string()),
         image: Some(Url::parse("https://www.redspark.nu/wp-content/uploads/2020/03/netra-bikram-chand-attends-program-1272019033653-1000x0-845x653-1.jpg").unwrap()),
         html: None,
+        embed_video_url: None,
+        provider_name: Some("Redspark".to_string()),
       }, sample_res);
 
     let youtube_url = Url::parse("https://www.youtube.com/watch?v=IquO_TcMZIQ").unwrap();
@@ -265,9 +483,81 @@ mod tests {
         description: Some("An interactive discussion on wealth inequality and the “Great Game” on the control of natural resources.In this webinar organized jointly by the Henry George...".to_string()),
         image: Some(Url::parse("https://i.ytimg.com/vi/IquO_TcMZIQ/maxresdefault.jpg").unwrap()),
         html: None,
+        embed_video_url: Some(Url::parse("https://www.youtube.com/embed/IquO_TcMZIQ").unwrap()),
+        provider_name: Some("YouTube".to_string()),
       }, youtube_res);
   }
 
+  #[test]
+  fn test_video_metadata() {
+    let page_url = Url::parse("https://video.example.com/watch/1").unwrap();
+    let html = r#"<html><head>
+      <meta property="og:site_name" content="Example Video">
+      <meta property="og:video" content="http://video.example.com/embed/1">
+      <meta property="og:video:secure_url" content="https://video.example.com/embed/1">
+      <link rel="alternate" type="application/json+oembed"
+        href="/oembed?format=json&amp;url=https%3A%2F%2Fvideo.example.com%2Fwatch%2F1">
+      </head></html>"#;
+
+    let metadata = html_to_site_metadata(html).unwrap();
+    assert_eq!(
+      Some(Url::parse("https://video.example.com/embed/1").unwrap()),
+      metadata.embed_video_url
+    );
+    assert_eq!(Some("Example Video".to_string()), metadata.provider_name);
+    assert_eq!(
+      Some(
+        Url::parse(
+          "https://video.example.com/oembed?format=json&url=https%3A%2F%2Fvideo.example.com%2Fwatch%2F1"
+        )
+        .unwrap()
+      ),
+      oembed_url(html, &page_url)
+    );
+
+    let mut oembed = OEmbedResponse {
+      kind: "video".to_string(),
+      html: Some(r#"<iframe width="480" src="https://video.example.com/embed/1?autoplay=0&amp;t=5"></iframe>"#.to_string()),
+      provider_name: None,
+    };
+    assert_eq!(
+      Some(Url::parse("https://video.example.com/embed/1?autoplay=0&t=5").unwrap()),
+      oembed_video_url(&oembed)
+    );
+    oembed.kind = "rich".to_string();
+    assert_eq!(None, oembed_video_url(&oembed));
+  }
+
+  #[test]
+  fn test_nodeinfo() {
+    let well_known: NodeInfoWellKnown = serde_json::from_str(
+      r#"{"links": [
+        {"rel": "http://nodeinfo.diaspora.software/ns/schema/2.0", "href": "https://example.com/nodeinfo/2.0.json"},
+        {"rel": "http://nodeinfo.diaspora.software/ns/schema/2.1", "href": "https://example.com/nodeinfo/2.1.json"},
+        {"rel": "http://nodeinfo.diaspora.software/ns/schema/2.1", "href": "https://other.example.com/nodeinfo/2.1.json"}
+      ]}"#,
+    )
+    .unwrap();
+    assert_eq!(
+      Some(Url::parse("https://example.com/nodeinfo/2.1.json").unwrap()),
+      nodeinfo_href(&well_known, "example.com")
+    );
+    assert_eq!(None, nodeinfo_href(&well_known, "unknown.example.com"));
+
+    let nodeinfo: NodeInfo = serde_json::from_str(
+      r#"{"version": "2.1", "software": {"name": "mastodon", "version": "3.4.6"},
+        "protocols": ["activitypub"], "usage": {"users": {"total": 120, "activeMonth": 17}},
+        "openRegistrations": false}"#,
+    )
+    .unwrap();
+    let software = nodeinfo.software.unwrap();
+    let users = nodeinfo.usage.unwrap().users.unwrap();
+    assert_eq!(Some("mastodon".to_string()), software.name);
+    assert_eq!(Some("3.4.6".to_string()), software.version);
+    assert_eq!(Some(120), users.total);
+    assert_eq!(Some(17), users.active_month);
+  }
+
   // #[test]
   // fn test_pictshare() {
   //   let res = fetch_pictshare("https://upload.wikimedia.org/wikipedia/en/2/27/The_Mandalorian_logo.jpg");
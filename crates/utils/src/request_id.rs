@@ -0,0 +1,35 @@
+use std::future::Future;
+use uuid::Uuid;
+
+tokio::task_local! {
+  static REQUEST_ID: String;
+}
+
+/// Generates an id for work which doesn't come from an http request, like websocket messages
+pub fn generate_request_id() -> String {
+  Uuid::new_v4().to_string()
+}
+
+/// Runs the future with the given request id, so that it can be attached to work which happens
+/// outside of the request's tracing span, like activity deliveries.
+pub async fn with_request_id<F: Future>(request_id: String, f: F) -> F::Output {
+  REQUEST_ID.scope(request_id, f).await
+}
+
+/// The id of the http request or websocket message which is currently being handled
+pub fn current_request_id() -> Option<String> {
+  REQUEST_ID.try_with(|id| id.clone()).ok()
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::request_id::{current_request_id, with_request_id};
+
+  #[actix_rt::test]
+  async fn test_request_id() {
+    assert_eq!(None, current_request_id());
+    let inner = with_request_id("abc".to_string(), async { current_request_id() }).await;
+    assert_eq!(Some("abc".to_string()), inner);
+    assert_eq!(None, current_request_id());
+  }
+}
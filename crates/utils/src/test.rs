@@ -3,9 +3,14 @@ use crate::{
   utils::{
     is_valid_actor_name,
     is_valid_display_name,
+    is_valid_domain,
+    is_valid_emoji_shortcode,
     is_valid_matrix_id,
     is_valid_post_title,
+    is_valid_reaction,
     remove_slurs,
+    render_emojis,
+    scrape_text_for_emojis,
     scrape_text_for_mentions,
     slur_check,
     slurs_vec_to_str,
@@ -55,6 +60,51 @@ fn test_valid_post_title() {
   assert!(!is_valid_post_title("\n \n \n \n    		")); // tabs/spaces/newlines
 }
 
+#[test]
+fn test_valid_reaction() {
+  assert!(is_valid_reaction("👍"));
+  assert!(is_valid_reaction("👩‍🚀"));
+  assert!(is_valid_reaction("🇺🇦"));
+  assert!(!is_valid_reaction(""));
+  assert!(!is_valid_reaction("+1"));
+  assert!(!is_valid_reaction("😃 😃"));
+  assert!(!is_valid_reaction("ä"));
+}
+
+#[test]
+fn test_emoji_shortcodes() {
+  assert!(is_valid_emoji_shortcode("blobcat"));
+  assert!(is_valid_emoji_shortcode("party_parrot2"));
+  assert!(!is_valid_emoji_shortcode(":blobcat:"));
+  assert!(!is_valid_emoji_shortcode("x"));
+  assert!(!is_valid_emoji_shortcode("blob cat"));
+
+  let text = "Hello :blobcat: and :party_parrot:, at 12:30:00 :blobcat:";
+  assert_eq!(
+    vec!["blobcat".to_string(), "party_parrot".to_string()],
+    scrape_text_for_emojis(text)
+  );
+
+  let emojis = vec![(
+    "blobcat".to_string(),
+    "https://example.com/blobcat.png".parse().unwrap(),
+  )];
+  assert_eq!(
+    "Hi ![:blobcat:](https://example.com/blobcat.png \"emoji\") :unknown:",
+    render_emojis("Hi :blobcat: :unknown:", &emojis)
+  );
+}
+
+#[test]
+fn test_valid_domain() {
+  assert!(is_valid_domain("example.com"));
+  assert!(is_valid_domain("news.bbc.co.uk"));
+  assert!(!is_valid_domain("localhost"));
+  assert!(!is_valid_domain("https://example.com"));
+  assert!(!is_valid_domain("example.com/path"));
+  assert!(!is_valid_domain("%.com"));
+}
+
 #[test]
 fn test_valid_matrix_id() {
   assert!(is_valid_matrix_id("@dess:matrix.org"));
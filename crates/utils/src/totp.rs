@@ -0,0 +1,195 @@
+//! Time-based one-time passwords (RFC 6238) for two-factor authentication, with the parameters
+//! which all common authenticator apps use: HMAC-SHA1, 6 digits and a 30 second step.
+
+use crate::LemmyError;
+use chrono::{Duration, NaiveDateTime};
+use openssl::{hash::MessageDigest, pkey::PKey, sign::Signer};
+use percent_encoding::{utf8_percent_encode, AsciiSet, NON_ALPHANUMERIC};
+use rand::{thread_rng, RngCore};
+
+const TOTP_DIGITS: u32 = 6;
+const TOTP_STEP_SECONDS: i64 = 30;
+/// Number of steps before and after the current one which are also accepted, to allow for clock
+/// drift between server and phone
+const TOTP_ALLOWED_SKEW: i64 = 1;
+/// Wrong tokens after which further attempts are rejected for a while
+const TOTP_MAX_FAILED_ATTEMPTS: i32 = 5;
+const TOTP_LOCKOUT_MINUTES: i64 = 15;
+const SECRET_LENGTH_BYTES: usize = 20;
+const BASE32_ALPHABET: &[u8; 32] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
+/// Characters which are escaped in the label and issuer of the url
+const URL_COMPONENT: &AsciiSet = &NON_ALPHANUMERIC.remove(b'.').remove(b'-').remove(b'_');
+
+/// Generates a new random secret, base32 encoded as expected by authenticator apps
+pub fn generate_totp_secret() -> String {
+  let mut secret = [0u8; SECRET_LENGTH_BYTES];
+  thread_rng().fill_bytes(&mut secret);
+  base32_encode(&secret)
+}
+
+/// The `otpauth://` url which authenticator apps expect, usually shown to the user as a QR code
+pub fn build_totp_url(secret: &str, username: &str, hostname: &str) -> String {
+  let username = utf8_percent_encode(username, URL_COMPONENT);
+  let hostname = utf8_percent_encode(hostname, URL_COMPONENT);
+  format!(
+    "otpauth://totp/{}:{}?secret={}&issuer={}&algorithm=SHA1&digits={}&period={}",
+    hostname, username, secret, hostname, TOTP_DIGITS, TOTP_STEP_SECONDS
+  )
+}
+
+/// Checks a token which the user entered against the secret, at the given unix time. Returns the
+/// time step which the token belongs to, so that it can't be used a second time.
+pub fn check_totp_token(
+  secret: &str,
+  token: &str,
+  unix_time: i64,
+) -> Result<Option<i64>, LemmyError> {
+  let key = base32_decode(secret).ok_or_else(|| LemmyError::from_message("invalid_totp_secret"))?;
+  let token = token.trim();
+  if token.len() != TOTP_DIGITS as usize || !token.chars().all(|c| c.is_ascii_digit()) {
+    return Ok(None);
+  }
+  let step = unix_time / TOTP_STEP_SECONDS;
+  for counter in (step - TOTP_ALLOWED_SKEW)..=(step + TOTP_ALLOWED_SKEW) {
+    if generate_totp_token(&key, counter as u64)? == token {
+      return Ok(Some(counter));
+    }
+  }
+  Ok(None)
+}
+
+/// Whether there were too many wrong tokens recently. Once the limit is reached, each further
+/// wrong token blocks attempts again, until a correct token is entered.
+pub fn is_totp_locked(
+  failed_attempts: i32,
+  last_failure: Option<NaiveDateTime>,
+  now: NaiveDateTime,
+) -> bool {
+  match last_failure {
+    Some(last_failure) => {
+      failed_attempts >= TOTP_MAX_FAILED_ATTEMPTS
+        && last_failure > now - Duration::minutes(TOTP_LOCKOUT_MINUTES)
+    }
+    None => false,
+  }
+}
+
+/// HOTP value (RFC 4226) for the given counter
+fn generate_totp_token(key: &[u8], counter: u64) -> Result<String, LemmyError> {
+  let pkey = PKey::hmac(key)?;
+  let mut signer = Signer::new(MessageDigest::sha1(), &pkey)?;
+  signer.update(&counter.to_be_bytes())?;
+  let hmac = signer.sign_to_vec()?;
+
+  let offset = (hmac[hmac.len() - 1] & 0xf) as usize;
+  let binary = u32::from_be_bytes([
+    hmac[offset] & 0x7f,
+    hmac[offset + 1],
+    hmac[offset + 2],
+    hmac[offset + 3],
+  ]);
+  let code = binary % 10u32.pow(TOTP_DIGITS);
+  Ok(format!("{:0width$}", code, width = TOTP_DIGITS as usize))
+}
+
+fn base32_encode(data: &[u8]) -> String {
+  let mut res = String::new();
+  let mut buffer: u32 = 0;
+  let mut bits = 0;
+  for byte in data {
+    buffer = (buffer << 8) | u32::from(*byte);
+    bits += 8;
+    while bits >= 5 {
+      bits -= 5;
+      res.push(BASE32_ALPHABET[((buffer >> bits) & 0x1f) as usize] as char);
+    }
+  }
+  if bits > 0 {
+    res.push(BASE32_ALPHABET[((buffer << (5 - bits)) & 0x1f) as usize] as char);
+  }
+  res
+}
+
+fn base32_decode(data: &str) -> Option<Vec<u8>> {
+  let mut res = vec![];
+  let mut buffer: u32 = 0;
+  let mut bits = 0;
+  for c in data.trim_end_matches('=').chars() {
+    let value = BASE32_ALPHABET
+      .iter()
+      .position(|a| *a as char == c.to_ascii_uppercase())?;
+    buffer = (buffer << 5) | value as u32;
+    bits += 5;
+    if bits >= 8 {
+      bits -= 8;
+      res.push(((buffer >> bits) & 0xff) as u8);
+    }
+  }
+  Some(res)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  // Test vectors from RFC 6238, using the last 6 digits
+  const RFC_SECRET: &[u8] = b"12345678901234567890";
+
+  #[test]
+  fn test_generate_totp_token() {
+    let token = |time: i64| generate_totp_token(RFC_SECRET, (time / 30) as u64).unwrap();
+    assert_eq!("287082", token(59));
+    assert_eq!("081804", token(1111111109));
+    assert_eq!("050471", token(1111111111));
+    assert_eq!("005924", token(1234567890));
+  }
+
+  #[test]
+  fn test_check_totp_token() {
+    let secret = base32_encode(RFC_SECRET);
+    assert_eq!("GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ", secret);
+    let step = Some(1111111109 / 30);
+    assert_eq!(
+      step,
+      check_totp_token(&secret, "081804", 1111111109).unwrap()
+    );
+    // Tokens of the neighbouring steps are accepted, but nothing further away
+    assert_eq!(
+      step,
+      check_totp_token(&secret, "081804", 1111111109 + 30).unwrap()
+    );
+    assert_eq!(
+      None,
+      check_totp_token(&secret, "081804", 1111111109 + 90).unwrap()
+    );
+    assert_eq!(
+      None,
+      check_totp_token(&secret, "12345", 1111111109).unwrap()
+    );
+  }
+
+  #[test]
+  fn test_build_totp_url() {
+    let url = build_totp_url("SECRET", "us:er&issuer=evil", "lemmy.tld");
+    assert_eq!(
+      "otpauth://totp/lemmy.tld:us%3Aer%26issuer%3Devil?secret=SECRET&issuer=lemmy.tld&algorithm=SHA1&digits=6&period=30",
+      url
+    );
+  }
+
+  #[test]
+  fn test_is_totp_locked() {
+    let now = chrono::Utc::now().naive_utc();
+    assert!(!is_totp_locked(0, None, now));
+    assert!(!is_totp_locked(4, Some(now), now));
+    assert!(is_totp_locked(5, Some(now - Duration::minutes(14)), now));
+    assert!(!is_totp_locked(5, Some(now - Duration::minutes(16)), now));
+  }
+
+  #[test]
+  fn test_generated_secret_round_trip() {
+    let secret = generate_totp_secret();
+    assert_eq!(32, secret.len());
+    assert_eq!(SECRET_LENGTH_BYTES, base32_decode(&secret).unwrap().len());
+  }
+}
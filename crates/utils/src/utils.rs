@@ -4,7 +4,8 @@ use chrono::{DateTime, FixedOffset, NaiveDateTime};
 use itertools::Itertools;
 use once_cell::sync::Lazy;
 use rand::{distributions::Alphanumeric, thread_rng, Rng};
-use regex::Regex;
+use regex::{Regex, RegexBuilder};
+use std::sync::RwLock;
 use url::Url;
 
 static MENTIONS_REGEX: Lazy<Regex> = Lazy::new(|| {
@@ -12,6 +13,15 @@ static MENTIONS_REGEX: Lazy<Regex> = Lazy::new(|| {
 });
 static VALID_ACTOR_NAME_REGEX: Lazy<Regex> =
   Lazy::new(|| Regex::new(r"^[a-zA-Z0-9_]{3,}$").expect("compile regex"));
+static VALID_EMOJI_SHORTCODE_REGEX: Lazy<Regex> =
+  Lazy::new(|| Regex::new(r"^[a-zA-Z0-9_]{2,64}$").expect("compile regex"));
+// The prefix makes sure that times like 12:30:00 aren't taken for shortcodes
+static EMOJI_SHORTCODES_REGEX: Lazy<Regex> = Lazy::new(|| {
+  Regex::new(r"(?P<prefix>^|[^a-zA-Z0-9_:]):(?P<shortcode>[a-zA-Z0-9_]{2,64}):")
+    .expect("compile regex")
+});
+static VALID_DOMAIN_REGEX: Lazy<Regex> =
+  Lazy::new(|| Regex::new(r"^([a-zA-Z0-9-]+\.)+[a-zA-Z]{2,}$").expect("compile regex"));
 static VALID_POST_TITLE_REGEX: Lazy<Regex> =
   Lazy::new(|| Regex::new(r".*\S{3,}.*").expect("compile regex"));
 static VALID_MATRIX_ID_REGEX: Lazy<Regex> = Lazy::new(|| {
@@ -23,6 +33,65 @@ static CLEAN_URL_PARAMS_REGEX: Lazy<Regex> = Lazy::new(|| {
     .expect("compile regex")
 });
 
+/// Patterns which admins added through the api. Kept in memory so that checking content doesn't
+/// need a database query, and so that changes take effect without a restart.
+static WORD_FILTER: Lazy<RwLock<WordFilter>> = Lazy::new(Default::default);
+
+/// The instance-wide word filter, which is applied in addition to the slur filter from the config
+#[derive(Clone, Debug, Default)]
+pub struct WordFilter {
+  /// Content which matches is rejected, even where slurs would only be replaced
+  block: Option<Regex>,
+  /// Matches are treated the same as those of the slur filter
+  replace: Option<Regex>,
+}
+
+impl WordFilter {
+  /// Invalid patterns are skipped, they should be rejected with [`check_word_filter_pattern`]
+  /// before they are stored.
+  pub fn new(block: &[String], replace: &[String]) -> Self {
+    WordFilter {
+      block: combine_patterns(block),
+      replace: combine_patterns(replace),
+    }
+  }
+
+  pub fn get() -> Self {
+    WORD_FILTER.read().expect("read word filter").to_owned()
+  }
+
+  /// Replaces the word filter which is used for all following content checks
+  pub fn set(self) {
+    *WORD_FILTER.write().expect("write word filter") = self;
+  }
+}
+
+fn combine_patterns(patterns: &[String]) -> Option<Regex> {
+  let combined = patterns
+    .iter()
+    .filter(|p| check_word_filter_pattern(p).is_ok())
+    .map(|p| format!("(?:{})", p))
+    .join("|");
+  if combined.is_empty() {
+    return None;
+  }
+  RegexBuilder::new(&combined)
+    .case_insensitive(true)
+    .build()
+    .ok()
+}
+
+pub fn check_word_filter_pattern(pattern: &str) -> Result<(), LemmyError> {
+  if pattern.trim().is_empty() {
+    return Err(LemmyError::from_message("invalid_word_filter_pattern"));
+  }
+  RegexBuilder::new(pattern)
+    .case_insensitive(true)
+    .build()
+    .map(|_| ())
+    .map_err(|e| LemmyError::from(e).with_message("invalid_word_filter_pattern"))
+}
+
 pub fn naive_from_unix(time: i64) -> NaiveDateTime {
   NaiveDateTime::from_timestamp(time, 0)
 }
@@ -32,11 +101,12 @@ pub fn convert_datetime(datetime: NaiveDateTime) -> DateTime<FixedOffset> {
 }
 
 pub fn remove_slurs(test: &str, slur_regex: &Option<Regex>) -> String {
-  if let Some(slur_regex) = slur_regex {
-    slur_regex.replace_all(test, "*removed*").to_string()
-  } else {
-    test.to_string()
+  let mut text = test.to_string();
+  let word_filter = WordFilter::get();
+  for regex in slur_regex.iter().chain(word_filter.replace.iter()) {
+    text = regex.replace_all(&text, "*removed*").to_string();
   }
+  text
 }
 
 pub(crate) fn slur_check<'a>(
@@ -63,7 +133,22 @@ pub(crate) fn slur_check<'a>(
 pub fn check_slurs(text: &str, slur_regex: &Option<Regex>) -> Result<(), LemmyError> {
   if let Err(slurs) = slur_check(text, slur_regex) {
     let error = LemmyError::from(anyhow::anyhow!("{}", slurs_vec_to_str(slurs)));
-    Err(error.with_message("slurs"))
+    return Err(error.with_message("slurs"));
+  }
+  let word_filter = WordFilter::get();
+  if let Err(slurs) = slur_check(text, &word_filter.replace) {
+    let error = LemmyError::from(anyhow::anyhow!("{}", slurs_vec_to_str(slurs)));
+    return Err(error.with_message("slurs"));
+  }
+  check_blocked_words(text)
+}
+
+/// Rejects text which matches a blocking pattern of the word filter. This is also needed for
+/// content which only goes through [`remove_slurs`].
+pub fn check_blocked_words(text: &str) -> Result<(), LemmyError> {
+  if let Err(words) = slur_check(text, &WordFilter::get().block) {
+    let error = LemmyError::from(anyhow::anyhow!("{}", slurs_vec_to_str(words)));
+    Err(error.with_message("blocked_words"))
   } else {
     Ok(())
   }
@@ -151,6 +236,50 @@ pub fn is_valid_post_title(title: &str) -> bool {
   VALID_POST_TITLE_REGEX.is_match(title) && !has_newline(title)
 }
 
+/// A reaction is a single emoji, possibly combined from several code points like flags or skin
+/// tones. Text isn't allowed.
+pub fn is_valid_reaction(emoji: &str) -> bool {
+  let len = emoji.chars().count();
+  len > 0
+    && len <= 10
+    && emoji
+      .chars()
+      .all(|c| !c.is_ascii() && !c.is_alphanumeric() && !c.is_whitespace())
+}
+
+/// Shortcodes of custom emoji are given without the surrounding colons
+pub fn is_valid_emoji_shortcode(shortcode: &str) -> bool {
+  VALID_EMOJI_SHORTCODE_REGEX.is_match(shortcode)
+}
+
+/// A plain domain like `example.com`, without scheme, port or path
+pub fn is_valid_domain(domain: &str) -> bool {
+  VALID_DOMAIN_REGEX.is_match(domain)
+}
+
+/// Returns the shortcodes of all custom emoji which are used in the text, without colons
+pub fn scrape_text_for_emojis(text: &str) -> Vec<String> {
+  EMOJI_SHORTCODES_REGEX
+    .captures_iter(text)
+    .map(|caps| caps["shortcode"].to_string())
+    .unique()
+    .collect()
+}
+
+/// Replaces the shortcodes of the given emoji with inline markdown images. Other shortcodes are
+/// kept as they are.
+pub fn render_emojis(text: &str, emojis: &[(String, Url)]) -> String {
+  EMOJI_SHORTCODES_REGEX
+    .replace_all(text, |caps: &regex::Captures| {
+      let shortcode = &caps["shortcode"];
+      match emojis.iter().find(|(s, _)| s == shortcode) {
+        Some((_, url)) => format!("{}![:{}:]({} \"emoji\")", &caps["prefix"], shortcode, url),
+        None => caps[0].to_string(),
+      }
+    })
+    .into_owned()
+}
+
 pub fn get_ip(conn_info: &ConnectionInfo) -> IpAddr {
   IpAddr(
     conn_info
@@ -0,0 +1,365 @@
+//! Web Push (RFC 8030) with encrypted payloads (RFC 8291) and VAPID authentication (RFC 8292).
+
+use crate::{settings::structs::WebPushConfig, LemmyError};
+use anyhow::anyhow;
+use openssl::{
+  bn::BigNumContext,
+  derive::Deriver,
+  ec::{EcGroup, EcKey, EcPoint, PointConversionForm},
+  ecdsa::EcdsaSig,
+  hash::{hash, MessageDigest},
+  nid::Nid,
+  pkey::{PKey, Private, Public},
+  sign::Signer,
+  symm::{encrypt_aead, Cipher},
+};
+use rand::{thread_rng, RngCore};
+use reqwest::StatusCode;
+use reqwest_middleware::ClientWithMiddleware;
+use serde::Serialize;
+use std::net::IpAddr;
+use tokio::net::lookup_host;
+use url::{Host, Url};
+
+/// How long the push service keeps the message if the device is offline, in seconds
+const PUSH_TTL_SECONDS: i64 = 24 * 60 * 60;
+/// VAPID tokens may be valid for at most 24 hours
+const VAPID_VALIDITY_SECONDS: i64 = 12 * 60 * 60;
+const RECORD_SIZE: u32 = 4096;
+
+/// The content of a notification, as it is passed to the service worker of the client
+#[derive(Serialize, Debug)]
+pub struct PushNotification {
+  pub title: String,
+  pub body: String,
+  pub url: String,
+}
+
+/// Sends a notification to a push subscription. Returns false if the push service reports that the
+/// subscription doesn't exist anymore, so that it can be deleted.
+pub async fn send_web_push(
+  client: &ClientWithMiddleware,
+  config: &WebPushConfig,
+  endpoint: &str,
+  p256dh: &str,
+  auth: &str,
+  notification: &PushNotification,
+) -> Result<bool, LemmyError> {
+  let endpoint = Url::parse(endpoint)?;
+  // The host could resolve to a different address by now
+  check_push_endpoint(&endpoint).await?;
+  let payload = serde_json::to_vec(notification)?;
+  let body = encrypt_payload(p256dh, auth, &payload)?;
+  let authorization = vapid_authorization(
+    &endpoint,
+    &config.vapid_private_key,
+    &config.subject,
+    chrono::Utc::now().timestamp(),
+  )?;
+
+  let response = client
+    .post(endpoint)
+    .header("Authorization", authorization)
+    .header("Content-Encoding", "aes128gcm")
+    .header("Content-Type", "application/octet-stream")
+    .header("TTL", PUSH_TTL_SECONDS.to_string())
+    .body(body)
+    .send()
+    .await?;
+  match response.status() {
+    StatusCode::NOT_FOUND | StatusCode::GONE => Ok(false),
+    status if status.is_success() => Ok(true),
+    status => Err(anyhow!("Push service responded with {}", status).into()),
+  }
+}
+
+/// Push services are public hosts, so endpoints which resolve to loopback or private addresses are
+/// rejected. Otherwise subscriptions could be used to send requests into the internal network.
+pub async fn check_push_endpoint(endpoint: &Url) -> Result<(), LemmyError> {
+  if endpoint.scheme() != "https" {
+    return Err(LemmyError::from_message("invalid_push_subscription"));
+  }
+  let addresses: Vec<IpAddr> = match endpoint.host() {
+    Some(Host::Ipv4(ip)) => vec![IpAddr::V4(ip)],
+    Some(Host::Ipv6(ip)) => vec![IpAddr::V6(ip)],
+    Some(Host::Domain(domain)) => {
+      let port = endpoint.port_or_known_default().unwrap_or(443);
+      lookup_host((domain, port))
+        .await
+        .map_err(LemmyError::from)
+        .map_err(|e| e.with_message("invalid_push_subscription"))?
+        .map(|a| a.ip())
+        .collect()
+    }
+    None => vec![],
+  };
+  if addresses.is_empty() || !addresses.iter().all(is_public_address) {
+    return Err(LemmyError::from_message("invalid_push_subscription"));
+  }
+  Ok(())
+}
+
+fn is_public_address(ip: &IpAddr) -> bool {
+  match ip {
+    IpAddr::V4(ip) => {
+      let [a, b, ..] = ip.octets();
+      !(ip.is_private()
+        || ip.is_loopback()
+        || ip.is_link_local()
+        || ip.is_unspecified()
+        || ip.is_broadcast()
+        || ip.is_documentation()
+        || ip.is_multicast()
+        // Shared address space of carrier-grade NAT, 100.64.0.0/10
+        || (a == 100 && (b & 0xc0) == 64))
+    }
+    IpAddr::V6(ip) => match ip.to_ipv4_mapped() {
+      Some(ip) => is_public_address(&IpAddr::V4(ip)),
+      None => {
+        let first = ip.segments()[0];
+        !(ip.is_loopback()
+          || ip.is_unspecified()
+          || ip.is_multicast()
+          // Unique local, fc00::/7
+          || (first & 0xfe00) == 0xfc00
+          // Link local, fe80::/10
+          || (first & 0xffc0) == 0xfe80)
+      }
+    },
+  }
+}
+
+/// The public VAPID key, which clients need to pass as `applicationServerKey` when subscribing
+pub fn vapid_public_key(private_key_pem: &str) -> Result<String, LemmyError> {
+  let key = read_vapid_key(private_key_pem)?;
+  Ok(base64url(&public_key_bytes(&key)?))
+}
+
+/// Encrypts the payload for the subscription with the `aes128gcm` content encoding
+pub fn encrypt_payload(p256dh: &str, auth: &str, payload: &[u8]) -> Result<Vec<u8>, LemmyError> {
+  let group = EcGroup::from_curve_name(Nid::X9_62_PRIME256V1)?;
+  let server_key = EcKey::generate(&group)?;
+  let mut salt = [0u8; 16];
+  thread_rng().fill_bytes(&mut salt);
+  encrypt_payload_with(p256dh, auth, payload, &server_key, &salt)
+}
+
+fn encrypt_payload_with(
+  p256dh: &str,
+  auth: &str,
+  payload: &[u8],
+  server_key: &EcKey<Private>,
+  salt: &[u8],
+) -> Result<Vec<u8>, LemmyError> {
+  let invalid = || LemmyError::from_message("invalid_push_subscription");
+  let client_public = base64url_decode(p256dh).ok_or_else(invalid)?;
+  let auth_secret = base64url_decode(auth).ok_or_else(invalid)?;
+  let client_key = public_key_from_bytes(&client_public)?;
+  let server_public = public_key_bytes(server_key)?;
+
+  let server_pkey = PKey::from_ec_key(server_key.clone())?;
+  let mut deriver = Deriver::new(&server_pkey)?;
+  deriver.set_peer(&client_key)?;
+  let ecdh_secret = deriver.derive_to_vec()?;
+
+  let mut key_info = b"WebPush: info\0".to_vec();
+  key_info.extend_from_slice(&client_public);
+  key_info.extend_from_slice(&server_public);
+  let ikm = hkdf(&auth_secret, &ecdh_secret, &key_info, 32)?;
+  let content_key = hkdf(salt, &ikm, b"Content-Encoding: aes128gcm\0", 16)?;
+  let nonce = hkdf(salt, &ikm, b"Content-Encoding: nonce\0", 12)?;
+
+  // Everything fits into a single record, which is marked as the last one by the delimiter
+  let mut plaintext = payload.to_vec();
+  plaintext.push(2);
+  let mut tag = [0u8; 16];
+  let ciphertext = encrypt_aead(
+    Cipher::aes_128_gcm(),
+    &content_key,
+    Some(&nonce),
+    &[],
+    &plaintext,
+    &mut tag,
+  )?;
+
+  let mut body = salt.to_vec();
+  body.extend_from_slice(&RECORD_SIZE.to_be_bytes());
+  body.push(server_public.len() as u8);
+  body.extend_from_slice(&server_public);
+  body.extend_from_slice(&ciphertext);
+  body.extend_from_slice(&tag);
+  Ok(body)
+}
+
+/// Value of the `Authorization` header, with a token signed by the instance's VAPID key
+fn vapid_authorization(
+  endpoint: &Url,
+  private_key_pem: &str,
+  subject: &str,
+  unix_time: i64,
+) -> Result<String, LemmyError> {
+  let key = read_vapid_key(private_key_pem)?;
+  let header = serde_json::json!({"typ": "JWT", "alg": "ES256"});
+  let claims = serde_json::json!({
+    "aud": endpoint.origin().ascii_serialization(),
+    "exp": unix_time + VAPID_VALIDITY_SECONDS,
+    "sub": subject,
+  });
+  let signing_input = format!(
+    "{}.{}",
+    base64url(&serde_json::to_vec(&header)?),
+    base64url(&serde_json::to_vec(&claims)?)
+  );
+
+  // JWS uses the raw r and s values instead of the DER encoding
+  let digest = hash(MessageDigest::sha256(), signing_input.as_bytes())?;
+  let signature = EcdsaSig::sign(&digest, &key)?;
+  let mut raw_signature = signature.r().to_vec_padded(32)?;
+  raw_signature.extend(signature.s().to_vec_padded(32)?);
+
+  Ok(format!(
+    "vapid t={}.{}, k={}",
+    signing_input,
+    base64url(&raw_signature),
+    base64url(&public_key_bytes(&key)?)
+  ))
+}
+
+fn read_vapid_key(private_key_pem: &str) -> Result<EcKey<Private>, LemmyError> {
+  let key = PKey::private_key_from_pem(private_key_pem.as_bytes())
+    .ok()
+    .and_then(|k| k.ec_key().ok())
+    .ok_or_else(|| LemmyError::from_message("invalid_vapid_private_key"))?;
+  Ok(key)
+}
+
+fn public_key_bytes<T: openssl::pkey::HasPublic>(key: &EcKey<T>) -> Result<Vec<u8>, LemmyError> {
+  let mut ctx = BigNumContext::new()?;
+  Ok(
+    key
+      .public_key()
+      .to_bytes(key.group(), PointConversionForm::UNCOMPRESSED, &mut ctx)?,
+  )
+}
+
+fn public_key_from_bytes(bytes: &[u8]) -> Result<PKey<Public>, LemmyError> {
+  let group = EcGroup::from_curve_name(Nid::X9_62_PRIME256V1)?;
+  let mut ctx = BigNumContext::new()?;
+  let point = EcPoint::from_bytes(&group, bytes, &mut ctx)
+    .map_err(|_| LemmyError::from_message("invalid_push_subscription"))?;
+  Ok(PKey::from_ec_key(EcKey::from_public_key(&group, &point)?)?)
+}
+
+/// HKDF (RFC 5869) with SHA-256, for outputs of at most one block
+fn hkdf(salt: &[u8], ikm: &[u8], info: &[u8], length: usize) -> Result<Vec<u8>, LemmyError> {
+  let prk = hmac_sha256(salt, ikm)?;
+  let mut info = info.to_vec();
+  info.push(1);
+  let mut okm = hmac_sha256(&prk, &info)?;
+  okm.truncate(length);
+  Ok(okm)
+}
+
+fn hmac_sha256(key: &[u8], data: &[u8]) -> Result<Vec<u8>, LemmyError> {
+  let pkey = PKey::hmac(key)?;
+  let mut signer = Signer::new(MessageDigest::sha256(), &pkey)?;
+  signer.update(data)?;
+  Ok(signer.sign_to_vec()?)
+}
+
+fn base64url(data: &[u8]) -> String {
+  base64::encode_config(data, base64::URL_SAFE_NO_PAD)
+}
+
+/// Browsers use base64url, but some clients send the keys with padding
+fn base64url_decode(data: &str) -> Option<Vec<u8>> {
+  base64::decode_config(data.trim_end_matches('='), base64::URL_SAFE_NO_PAD).ok()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use openssl::bn::BigNum;
+
+  // Example from RFC 8291, Appendix A
+  const PLAINTEXT: &[u8] = b"When I grow up, I want to be a watermelon";
+  const SERVER_PRIVATE: &str = "yfWPiYE-n46HLnH0KqZOF1fJJU3MYrct3AELtAQ-oRw";
+  const CLIENT_PUBLIC: &str =
+    "BCVxsr7N_eNgVRqvHtD0zTZsEc6-VV-JvLexhqUzORcxaOzi6-AYWXvTBHm4bjyPjs7Vd8pZGH6SRpkNtoIAiw4";
+  const AUTH_SECRET: &str = "BTBZMqHH6r4Tts7J_aSIgg";
+  const SALT: &str = "DGv6ra1nlYgDCS1FRnbzlw";
+  const ENCRYPTED: &str = "DGv6ra1nlYgDCS1FRnbzlwAAEABBBP4z9KsN6nGRTbVYI_c7VJSPQTBtkgcy27mlmlMoZIIgDll6e3vCYLocInmYWAmS6TlzAC8wEqKK6PBru3jl7A_yl95bQpu6cVPTpK4Mqgkf1CXztLVBSt2Ks3oZwbuwXPXLWyouBWLVWGNWQexSgSxsj_Qulcy4a-fN";
+
+  fn server_key() -> EcKey<Private> {
+    let group = EcGroup::from_curve_name(Nid::X9_62_PRIME256V1).unwrap();
+    let private = BigNum::from_slice(&base64url_decode(SERVER_PRIVATE).unwrap()).unwrap();
+    let ctx = BigNumContext::new().unwrap();
+    let mut public = EcPoint::new(&group).unwrap();
+    public.mul_generator(&group, &private, &ctx).unwrap();
+    EcKey::from_private_components(&group, &private, &public).unwrap()
+  }
+
+  #[test]
+  fn test_encrypt_payload() {
+    let salt = base64url_decode(SALT).unwrap();
+    let encrypted =
+      encrypt_payload_with(CLIENT_PUBLIC, AUTH_SECRET, PLAINTEXT, &server_key(), &salt).unwrap();
+    assert_eq!(ENCRYPTED, base64url(&encrypted));
+
+    assert!(encrypt_payload("invalid", AUTH_SECRET, PLAINTEXT).is_err());
+  }
+
+  #[test]
+  fn test_vapid_authorization() {
+    let pem = String::from_utf8(server_key().private_key_to_pem().unwrap()).unwrap();
+    let endpoint = Url::parse("https://push.example.com/send/abc?x=1").unwrap();
+    let header = vapid_authorization(&endpoint, &pem, "mailto:admin@example.com", 0).unwrap();
+
+    let (token, key) = header
+      .strip_prefix("vapid t=")
+      .unwrap()
+      .split_once(", k=")
+      .unwrap();
+    assert_eq!(vapid_public_key(&pem).unwrap(), key);
+    let parts: Vec<&str> = token.split('.').collect();
+    let claims: serde_json::Value =
+      serde_json::from_slice(&base64url_decode(parts[1]).unwrap()).unwrap();
+    assert_eq!("https://push.example.com", claims["aud"]);
+    assert_eq!(VAPID_VALIDITY_SECONDS, claims["exp"]);
+
+    let signature = base64url_decode(parts[2]).unwrap();
+    let signature = EcdsaSig::from_private_components(
+      BigNum::from_slice(&signature[..32]).unwrap(),
+      BigNum::from_slice(&signature[32..]).unwrap(),
+    )
+    .unwrap();
+    let digest = hash(
+      MessageDigest::sha256(),
+      format!("{}.{}", parts[0], parts[1]).as_bytes(),
+    )
+    .unwrap();
+    assert!(signature.verify(&digest, &server_key()).unwrap());
+  }
+
+  #[test]
+  fn test_is_public_address() {
+    for ip in ["93.184.216.34", "2606:2800:220:1:248:1893:25c8:1946"] {
+      assert!(is_public_address(&ip.parse().unwrap()), "{}", ip);
+    }
+    for ip in [
+      "127.0.0.1",
+      "10.1.2.3",
+      "172.16.0.1",
+      "192.168.1.1",
+      "169.254.169.254",
+      "100.64.0.1",
+      "0.0.0.0",
+      "::1",
+      "fd00::1",
+      "fe80::1",
+      "::ffff:127.0.0.1",
+    ] {
+      assert!(!is_public_address(&ip.parse().unwrap()), "{}", ip);
+    }
+  }
+}
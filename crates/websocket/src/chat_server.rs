@@ -1,4 +1,5 @@
 use crate::{
+  message_bus::{MessageBus, Room},
   messages::*,
   serialize_websocket_message,
   LemmyContext,
@@ -21,6 +22,7 @@ use lemmy_db_schema::{
 use lemmy_utils::{
   location_info,
   rate_limit::RateLimit,
+  request_id::{generate_request_id, with_request_id},
   settings::structs::Settings,
   ConnectionId,
   IpAddr,
@@ -36,6 +38,7 @@ use std::{
   str::FromStr,
 };
 use tokio::macros::support::Pin;
+use tracing::{info_span, Instrument};
 
 type MessageHandlerType = fn(
   context: LemmyContext,
@@ -83,9 +86,6 @@ pub struct ChatServer {
   /// Rate limiting based on rate type and IP addr
   pub(super) rate_limiter: RateLimit,
 
-  /// A list of the current captchas
-  pub(super) captchas: Vec<CaptchaItem>,
-
   message_handler: MessageHandlerType,
   message_handler_crud: MessageHandlerCrudType,
 
@@ -93,10 +93,14 @@ pub struct ChatServer {
   client: ClientWithMiddleware,
 
   activity_queue: QueueHandle,
+
+  /// Forwards websocket messages to other lemmy_server processes, if there are multiple
+  pub(super) message_bus: Option<MessageBus>,
 }
 
 pub struct SessionInfo {
   pub addr: Recipient<WsMessage>,
+  pub close: Recipient<CloseSession>,
   pub ip: IpAddr,
 }
 
@@ -114,6 +118,7 @@ impl ChatServer {
     activity_queue: QueueHandle,
     settings: Settings,
     secret: Secret,
+    message_bus: Option<MessageBus>,
   ) -> ChatServer {
     ChatServer {
       sessions: HashMap::new(),
@@ -124,13 +129,13 @@ impl ChatServer {
       rng: rand::thread_rng(),
       pool,
       rate_limiter,
-      captchas: Vec::new(),
       message_handler,
       message_handler_crud,
       client,
       activity_queue,
       settings,
       secret,
+      message_bus,
     }
   }
 
@@ -250,17 +255,8 @@ impl ChatServer {
     OP: OperationType + ToString,
     Response: Serialize,
   {
-    let res_str = &serialize_websocket_message(op, response)?;
-    if let Some(sessions) = self.post_rooms.get(&post_id) {
-      for id in sessions {
-        if let Some(my_id) = websocket_id {
-          if *id == my_id {
-            continue;
-          }
-        }
-        self.sendit(res_str, *id);
-      }
-    }
+    let res_str = serialize_websocket_message(op, response)?;
+    self.send_room_message(Room::Post(post_id), res_str, websocket_id);
     Ok(())
   }
 
@@ -275,17 +271,8 @@ impl ChatServer {
     OP: OperationType + ToString,
     Response: Serialize,
   {
-    let res_str = &serialize_websocket_message(op, response)?;
-    if let Some(sessions) = self.community_rooms.get(&community_id) {
-      for id in sessions {
-        if let Some(my_id) = websocket_id {
-          if *id == my_id {
-            continue;
-          }
-        }
-        self.sendit(res_str, *id);
-      }
-    }
+    let res_str = serialize_websocket_message(op, response)?;
+    self.send_room_message(Room::Community(community_id), res_str, websocket_id);
     Ok(())
   }
 
@@ -300,17 +287,8 @@ impl ChatServer {
     OP: OperationType + ToString,
     Response: Serialize,
   {
-    let res_str = &serialize_websocket_message(op, response)?;
-    if let Some(sessions) = self.mod_rooms.get(&community_id) {
-      for id in sessions {
-        if let Some(my_id) = websocket_id {
-          if *id == my_id {
-            continue;
-          }
-        }
-        self.sendit(res_str, *id);
-      }
-    }
+    let res_str = serialize_websocket_message(op, response)?;
+    self.send_room_message(Room::Mod(community_id), res_str, websocket_id);
     Ok(())
   }
 
@@ -324,15 +302,8 @@ impl ChatServer {
     OP: OperationType + ToString,
     Response: Serialize,
   {
-    let res_str = &serialize_websocket_message(op, response)?;
-    for id in self.sessions.keys() {
-      if let Some(my_id) = websocket_id {
-        if *id == my_id {
-          continue;
-        }
-      }
-      self.sendit(res_str, *id);
-    }
+    let res_str = serialize_websocket_message(op, response)?;
+    self.send_room_message(Room::All, res_str, websocket_id);
     Ok(())
   }
 
@@ -347,18 +318,102 @@ impl ChatServer {
     OP: OperationType + ToString,
     Response: Serialize,
   {
-    let res_str = &serialize_websocket_message(op, response)?;
-    if let Some(sessions) = self.user_rooms.get(&recipient_id) {
-      for id in sessions {
-        if let Some(my_id) = websocket_id {
-          if *id == my_id {
-            continue;
-          }
+    let res_str = serialize_websocket_message(op, response)?;
+    self.send_room_message(Room::User(recipient_id), res_str, websocket_id);
+    Ok(())
+  }
+
+  /// Sends the response to everyone in the community and post rooms, like a normal post or comment
+  /// update. Moderators of the community get the same message with the removal info added, and
+  /// only they can see the reason.
+  pub fn send_removal_message<OP, Response>(
+    &self,
+    op: &OP,
+    response: &Response,
+    removal: &RemovalInfo,
+    community_id: CommunityId,
+    post_id: Option<PostId>,
+    websocket_id: Option<ConnectionId>,
+  ) -> Result<(), LemmyError>
+  where
+    OP: OperationType + ToString,
+    Response: Serialize,
+  {
+    #[derive(Serialize)]
+    struct WithRemoval<'a, Response> {
+      #[serde(flatten)]
+      response: &'a Response,
+      removal: &'a RemovalInfo,
+    }
+
+    let res_str = serialize_websocket_message(op, response)?;
+    let mod_message = serialize_websocket_message(op, &WithRemoval { response, removal })?;
+    let room = Room::Removal {
+      community_id,
+      post_id,
+      mod_message,
+    };
+    self.send_room_message(room, res_str, websocket_id);
+    Ok(())
+  }
+
+  /// Sends the message to the sessions of this process, and publishes it for the other processes
+  /// if there are any
+  fn send_room_message(&self, room: Room, message: String, websocket_id: Option<ConnectionId>) {
+    self.deliver_room_message(&room, &message, websocket_id);
+    if let Some(message_bus) = &self.message_bus {
+      message_bus.publish(room, message);
+    }
+  }
+
+  /// Sends the message to all sessions of this process in the room, except for `websocket_id`
+  pub(super) fn deliver_room_message(
+    &self,
+    room: &Room,
+    message: &str,
+    websocket_id: Option<ConnectionId>,
+  ) {
+    let mut sessions: HashSet<&ConnectionId> = HashSet::new();
+    let mut mod_sessions = None;
+    match room {
+      Room::All => sessions.extend(self.sessions.keys()),
+      Room::Post(post_id) => sessions.extend(self.post_rooms.get(post_id).into_iter().flatten()),
+      Room::Community(community_id) => {
+        sessions.extend(self.community_rooms.get(community_id).into_iter().flatten())
+      }
+      Room::Mod(community_id) => {
+        sessions.extend(self.mod_rooms.get(community_id).into_iter().flatten())
+      }
+      Room::User(user_id) => sessions.extend(self.user_rooms.get(user_id).into_iter().flatten()),
+      Room::Removal {
+        community_id,
+        post_id,
+        mod_message,
+      } => {
+        for room in &[CommunityId(0), *community_id] {
+          sessions.extend(self.community_rooms.get(room).into_iter().flatten());
+        }
+        if let Some(post_id) = post_id {
+          sessions.extend(self.post_rooms.get(post_id).into_iter().flatten());
+        }
+        if let Some(room_sessions) = self.mod_rooms.get(community_id) {
+          sessions.extend(room_sessions);
+          mod_sessions = Some((room_sessions, mod_message));
         }
-        self.sendit(res_str, *id);
       }
     }
-    Ok(())
+
+    for id in sessions {
+      if Some(*id) == websocket_id {
+        continue;
+      }
+      match mod_sessions {
+        Some((mod_sessions, mod_message)) if mod_sessions.contains(id) => {
+          self.sendit(mod_message, *id)
+        }
+        _ => self.sendit(message, *id),
+      }
+    }
   }
 
   pub fn send_comment<OP>(
@@ -471,7 +526,9 @@ impl ChatServer {
     };
     let message_handler_crud = self.message_handler_crud;
     let message_handler = self.message_handler;
-    async move {
+    let request_id = generate_request_id();
+    let span = info_span!("Websocket message", request_id = %request_id);
+    let fut = async move {
       let json: Value = serde_json::from_str(&msg.msg)?;
       let data = &json["data"].to_string();
       let op = &json["op"]
@@ -482,6 +539,7 @@ impl ChatServer {
         let fut = (message_handler_crud)(context, msg.id, user_operation_crud.clone(), data);
         match user_operation_crud {
           UserOperationCrud::Register => rate_limiter.register().wrap(ip, fut).await,
+          UserOperationCrud::AuthenticateWithOAuth => rate_limiter.register().wrap(ip, fut).await,
           UserOperationCrud::CreatePost => rate_limiter.post().wrap(ip, fut).await,
           UserOperationCrud::CreateCommunity => rate_limiter.register().wrap(ip, fut).await,
           UserOperationCrud::CreateComment => rate_limiter.comment().wrap(ip, fut).await,
@@ -492,9 +550,11 @@ impl ChatServer {
         let fut = (message_handler)(context, msg.id, user_operation.clone(), data);
         match user_operation {
           UserOperation::GetCaptcha => rate_limiter.post().wrap(ip, fut).await,
+          UserOperation::ResendVerificationEmail => rate_limiter.register().wrap(ip, fut).await,
           _ => rate_limiter.message().wrap(ip, fut).await,
         }
       }
-    }
+    };
+    with_request_id(request_id, fut).instrument(span)
   }
 }
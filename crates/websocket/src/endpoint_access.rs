@@ -0,0 +1,241 @@
+use crate::{UserOperation, UserOperationCrud};
+use lemmy_api_common::token_scope::EndpointAccess;
+use std::str::FromStr;
+
+/// The access of the endpoint with the given request type name. Unknown endpoints need a login
+/// session, so that personal access tokens can't be used for them by accident.
+pub fn endpoint_access(endpoint: &str) -> EndpointAccess {
+  if let Ok(op) = UserOperation::from_str(endpoint) {
+    op.access()
+  } else if let Ok(op) = UserOperationCrud::from_str(endpoint) {
+    op.access()
+  } else {
+    EndpointAccess::LoginSession
+  }
+}
+
+impl UserOperation {
+  pub fn access(&self) -> EndpointAccess {
+    // No wildcard, so that every new operation needs a decision
+    match self {
+      UserOperation::Login
+      | UserOperation::GetCaptcha
+      | UserOperation::MarkCommentAsRead
+      | UserOperation::SaveComment
+      | UserOperation::CreateCommentLike
+      | UserOperation::ReactComment
+      | UserOperation::UnreactComment
+      | UserOperation::CreateCommentReport
+      | UserOperation::CreatePostLike
+      | UserOperation::ReactPost
+      | UserOperation::UnreactPost
+      | UserOperation::MarkPostAsRead
+      | UserOperation::SavePost
+      | UserOperation::VotePoll
+      | UserOperation::CreatePostReport
+      | UserOperation::GetUnreadCount
+      | UserOperation::VerifyEmail
+      | UserOperation::ResendVerificationEmail
+      | UserOperation::FollowCommunity
+      | UserOperation::GetReplies
+      | UserOperation::GetPersonMentions
+      | UserOperation::MarkPersonMentionAsRead
+      | UserOperation::GetModlog
+      | UserOperation::GetFederatedInstances
+      | UserOperation::Search
+      | UserOperation::MentionAutocomplete
+      | UserOperation::ResolveObject
+      | UserOperation::MarkAllAsRead
+      | UserOperation::SaveUserSettings
+      | UserOperation::PasswordReset
+      | UserOperation::PasswordChange
+      | UserOperation::MarkPrivateMessageAsRead
+      | UserOperation::UserJoin
+      | UserOperation::PostJoin
+      | UserOperation::CommunityJoin
+      | UserOperation::GetSiteMetadata
+      | UserOperation::BlockCommunity
+      | UserOperation::PreviewCommunity
+      | UserOperation::BlockPerson
+      | UserOperation::BlockInstance
+      | UserOperation::MoveSaved
+      | UserOperation::GetSaved
+      | UserOperation::GetPersonStats
+      | UserOperation::ImportAccountData => EndpointAccess::Regular,
+      UserOperation::LockPost
+      | UserOperation::ShadowRemovePost
+      | UserOperation::StickyPost
+      | UserOperation::ListPendingPosts
+      | UserOperation::ApprovePost
+      | UserOperation::RejectPost
+      | UserOperation::ResolveCommentReport
+      | UserOperation::EscalateCommentReport
+      | UserOperation::ListCommentReports
+      | UserOperation::ResolvePostReport
+      | UserOperation::EscalatePostReport
+      | UserOperation::ListPostReports
+      | UserOperation::GetReportCount
+      | UserOperation::BulkResolveReports
+      | UserOperation::BanFromCommunity
+      | UserOperation::AddModToCommunity
+      | UserOperation::ListPendingDeletions
+      | UserOperation::ResolvePendingDeletion
+      | UserOperation::AddAdmin
+      | UserOperation::GetUnreadRegistrationApplicationCount
+      | UserOperation::ListRegistrationApplications
+      | UserOperation::ApproveRegistrationApplication
+      | UserOperation::ListReceivedDeletions
+      | UserOperation::ListFederationSettings
+      | UserOperation::AddBlockedInstance
+      | UserOperation::RemoveBlockedInstance
+      | UserOperation::AddAllowedInstance
+      | UserOperation::RemoveAllowedInstance
+      | UserOperation::ListWordFilters
+      | UserOperation::AddWordFilter
+      | UserOperation::RemoveWordFilter
+      | UserOperation::ListLegalHolds
+      | UserOperation::AddLegalHold
+      | UserOperation::RemoveLegalHold
+      | UserOperation::PurgePerson
+      | UserOperation::PurgeCommunity
+      | UserOperation::PurgePost
+      | UserOperation::PurgeComment
+      | UserOperation::BanPerson
+      | UserOperation::RemoveContentAndBan
+      | UserOperation::TransferCommunity
+      | UserOperation::SetCommunityCategory
+      | UserOperation::TransferSite
+      | UserOperation::GetSiteConfig
+      | UserOperation::SaveSiteConfig
+      | UserOperation::ModJoin => EndpointAccess::Moderate,
+      UserOperation::CreateUserToken
+      | UserOperation::ListUserTokens
+      | UserOperation::RevokeUserToken
+      | UserOperation::GenerateFeedToken
+      | UserOperation::ListSessions
+      | UserOperation::RevokeSession
+      | UserOperation::RevokeAllSessions
+      | UserOperation::ChangePassword
+      | UserOperation::GenerateTotpSecret
+      | UserOperation::UpdateTotp
+      | UserOperation::ExportAccountData
+      | UserOperation::GetAccountExport
+      | UserOperation::RegisterPushSubscription
+      | UserOperation::DeletePushSubscription => EndpointAccess::LoginSession,
+    }
+  }
+}
+
+impl UserOperationCrud {
+  pub fn access(&self) -> EndpointAccess {
+    // No wildcard, so that every new operation needs a decision
+    match self {
+      UserOperationCrud::GetSite
+      | UserOperationCrud::CreateCommunity
+      | UserOperationCrud::ListCommunities
+      | UserOperationCrud::GetCommunity
+      | UserOperationCrud::CreateAppeal
+      | UserOperationCrud::ListCommunityCategories
+      | UserOperationCrud::ListCustomEmojis
+      | UserOperationCrud::ListPostTags
+      | UserOperationCrud::GetWikiPage
+      | UserOperationCrud::ListWikiPages
+      | UserOperationCrud::CreatePost
+      | UserOperationCrud::CrossPost
+      | UserOperationCrud::GetPost
+      | UserOperationCrud::GetPosts
+      | UserOperationCrud::EditPost
+      | UserOperationCrud::DeletePost
+      | UserOperationCrud::SchedulePost
+      | UserOperationCrud::ListScheduledPosts
+      | UserOperationCrud::DeleteScheduledPost
+      | UserOperationCrud::SaveDraft
+      | UserOperationCrud::ListDrafts
+      | UserOperationCrud::DeleteDraft
+      | UserOperationCrud::CreateSavedCollection
+      | UserOperationCrud::ListSavedCollections
+      | UserOperationCrud::EditSavedCollection
+      | UserOperationCrud::DeleteSavedCollection
+      | UserOperationCrud::CreateMultiCommunity
+      | UserOperationCrud::ListMultiCommunities
+      | UserOperationCrud::EditMultiCommunity
+      | UserOperationCrud::DeleteMultiCommunity
+      | UserOperationCrud::CreateComment
+      | UserOperationCrud::GetComment
+      | UserOperationCrud::GetComments
+      | UserOperationCrud::GetCommentChildren
+      | UserOperationCrud::EditComment
+      | UserOperationCrud::DeleteComment
+      | UserOperationCrud::Register
+      | UserOperationCrud::GetPersonDetails
+      | UserOperationCrud::ListOAuthProviders
+      | UserOperationCrud::CreatePrivateMessage
+      | UserOperationCrud::GetPrivateMessages
+      | UserOperationCrud::GetPrivateMessageThread
+      | UserOperationCrud::EditPrivateMessage
+      | UserOperationCrud::DeletePrivateMessage => EndpointAccess::Regular,
+      UserOperationCrud::CreateSite
+      | UserOperationCrud::EditSite
+      | UserOperationCrud::EditCommunity
+      | UserOperationCrud::DeleteCommunity
+      | UserOperationCrud::RemoveCommunity
+      | UserOperationCrud::ListAppeals
+      | UserOperationCrud::ResolveAppeal
+      | UserOperationCrud::CreateCommunityCategory
+      | UserOperationCrud::EditCommunityCategory
+      | UserOperationCrud::DeleteCommunityCategory
+      | UserOperationCrud::CreateCustomEmoji
+      | UserOperationCrud::EditCustomEmoji
+      | UserOperationCrud::DeleteCustomEmoji
+      | UserOperationCrud::CreatePostTag
+      | UserOperationCrud::EditPostTag
+      | UserOperationCrud::DeletePostTag
+      | UserOperationCrud::CreateAutomodRule
+      | UserOperationCrud::ListAutomodRules
+      | UserOperationCrud::EditAutomodRule
+      | UserOperationCrud::DeleteAutomodRule
+      | UserOperationCrud::CreateWikiPage
+      | UserOperationCrud::EditWikiPage
+      | UserOperationCrud::DeleteWikiPage
+      | UserOperationCrud::RemovePost
+      | UserOperationCrud::RemoveComment
+      | UserOperationCrud::GetCommentEditHistory => EndpointAccess::Moderate,
+      UserOperationCrud::DeleteAccount
+      | UserOperationCrud::StartOAuth
+      | UserOperationCrud::AuthenticateWithOAuth => EndpointAccess::LoginSession,
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::endpoint_access::endpoint_access;
+  use lemmy_api_common::token_scope::EndpointAccess;
+
+  #[test]
+  fn test_endpoint_access() {
+    assert_eq!(EndpointAccess::Regular, endpoint_access("GetPosts"));
+    assert_eq!(EndpointAccess::Regular, endpoint_access("CreateComment"));
+    assert_eq!(EndpointAccess::Moderate, endpoint_access("LockPost"));
+    assert_eq!(
+      EndpointAccess::Moderate,
+      endpoint_access("TransferCommunity")
+    );
+    assert_eq!(
+      EndpointAccess::LoginSession,
+      endpoint_access("ChangePassword")
+    );
+    assert_eq!(
+      EndpointAccess::LoginSession,
+      endpoint_access("DeleteAccount")
+    );
+    assert_eq!(
+      EndpointAccess::LoginSession,
+      endpoint_access("ExportAccountData")
+    );
+    assert_eq!(
+      EndpointAccess::LoginSession,
+      endpoint_access("NotAnEndpoint")
+    );
+  }
+}
@@ -1,10 +1,10 @@
 use crate::{
   chat_server::{ChatServer, SessionInfo},
+  message_bus::BusMessage,
   messages::*,
   OperationType,
 };
-use actix::{Actor, Context, Handler, ResponseFuture};
-use lemmy_db_schema::naive_now;
+use actix::{Actor, AsyncContext, Context, Handler, ResponseFuture};
 use lemmy_utils::ConnectionId;
 use rand::Rng;
 use serde::Serialize;
@@ -15,6 +15,12 @@ impl Actor for ChatServer {
   /// We are going to use simple Context, we just need ability to communicate
   /// with other actors.
   type Context = Context<Self>;
+
+  fn started(&mut self, ctx: &mut Self::Context) {
+    if let Some(message_bus) = self.message_bus.clone() {
+      actix::spawn(message_bus.subscribe(ctx.address()));
+    }
+  }
 }
 
 /// Handler for Connect message.
@@ -32,6 +38,7 @@ impl Handler<Connect> for ChatServer {
       id,
       SessionInfo {
         addr: msg.addr,
+        close: msg.close,
         ip: msg.ip,
       },
     );
@@ -133,19 +140,61 @@ where
   }
 }
 
-impl<Response> Handler<SendModRoomMessage<Response>> for ChatServer
+impl<OP, Response> Handler<SendModRoomMessage<OP, Response>> for ChatServer
 where
+  OP: OperationType + ToString,
   Response: Serialize,
 {
   type Result = ();
 
-  fn handle(&mut self, msg: SendModRoomMessage<Response>, _: &mut Context<Self>) {
+  fn handle(&mut self, msg: SendModRoomMessage<OP, Response>, _: &mut Context<Self>) {
     self
       .send_mod_room_message(&msg.op, &msg.response, msg.community_id, msg.websocket_id)
       .ok();
   }
 }
 
+impl<OP, Response> Handler<SendRemovalMessage<OP, Response>> for ChatServer
+where
+  OP: OperationType + ToString,
+  Response: Serialize,
+{
+  type Result = ();
+
+  fn handle(&mut self, msg: SendRemovalMessage<OP, Response>, _: &mut Context<Self>) {
+    self
+      .send_removal_message(
+        &msg.op,
+        &msg.response,
+        &msg.removal,
+        msg.community_id,
+        msg.post_id,
+        msg.websocket_id,
+      )
+      .ok();
+  }
+}
+
+/// Handler for CloseAllSessions message.
+impl Handler<CloseAllSessions> for ChatServer {
+  type Result = ();
+
+  fn handle(&mut self, _: CloseAllSessions, _: &mut Context<Self>) {
+    for session in self.sessions.values() {
+      let _ = session.close.do_send(CloseSession);
+    }
+  }
+}
+
+/// Handler for messages which were sent by other lemmy_server processes
+impl Handler<BusMessage> for ChatServer {
+  type Result = ();
+
+  fn handle(&mut self, msg: BusMessage, _: &mut Context<Self>) {
+    self.deliver_room_message(&msg.room, &msg.message, None);
+  }
+}
+
 impl<OP> Handler<SendPost<OP>> for ChatServer
 where
   OP: OperationType + ToString,
@@ -233,30 +282,3 @@ impl Handler<GetCommunityUsersOnline> for ChatServer {
     }
   }
 }
-
-impl Handler<CaptchaItem> for ChatServer {
-  type Result = ();
-
-  fn handle(&mut self, msg: CaptchaItem, _: &mut Context<Self>) {
-    self.captchas.push(msg);
-  }
-}
-
-impl Handler<CheckCaptcha> for ChatServer {
-  type Result = bool;
-
-  fn handle(&mut self, msg: CheckCaptcha, _: &mut Context<Self>) -> Self::Result {
-    // Remove all the ones that are past the expire time
-    self.captchas.retain(|x| x.expires.gt(&naive_now()));
-
-    let check = self
-      .captchas
-      .iter()
-      .any(|r| r.uuid == msg.uuid && r.answer.to_lowercase() == msg.answer.to_lowercase());
-
-    // Remove this uuid so it can't be re-checked (Checks only work once)
-    self.captchas.retain(|x| x.uuid != msg.uuid);
-
-    check
-  }
-}
@@ -5,12 +5,18 @@ use crate::chat_server::ChatServer;
 use actix::Addr;
 use background_jobs::QueueHandle;
 use lemmy_db_schema::{source::secret::Secret, DbPool};
-use lemmy_utils::{settings::structs::Settings, LemmyError};
+use lemmy_utils::{
+  metrics::{Metrics, METRICS},
+  settings::structs::Settings,
+  LemmyError,
+};
 use reqwest_middleware::ClientWithMiddleware;
 use serde::Serialize;
 
 pub mod chat_server;
+pub mod endpoint_access;
 pub mod handlers;
+pub mod message_bus;
 pub mod messages;
 pub mod routes;
 pub mod send;
@@ -54,6 +60,9 @@ impl LemmyContext {
   pub fn activity_queue(&self) -> &QueueHandle {
     &self.activity_queue
   }
+  pub fn metrics(&self) -> &'static Metrics {
+    &METRICS
+  }
   pub fn settings(&self) -> Settings {
     // TODO hacky solution to be able to hotload the settings.
     Settings::get()
@@ -104,20 +113,40 @@ pub enum UserOperation {
   MarkCommentAsRead,
   SaveComment,
   CreateCommentLike,
+  ReactComment,
+  UnreactComment,
   CreateCommentReport,
   ResolveCommentReport,
+  EscalateCommentReport,
   ListCommentReports,
   CreatePostLike,
+  ReactPost,
+  UnreactPost,
   LockPost,
+  ShadowRemovePost,
   StickyPost,
+  ListPendingPosts,
+  ApprovePost,
+  RejectPost,
   MarkPostAsRead,
   SavePost,
+  VotePoll,
   CreatePostReport,
   ResolvePostReport,
+  EscalatePostReport,
   ListPostReports,
   GetReportCount,
+  BulkResolveReports,
   GetUnreadCount,
   VerifyEmail,
+  ResendVerificationEmail,
+  CreateUserToken,
+  ListUserTokens,
+  RevokeUserToken,
+  GenerateFeedToken,
+  ListSessions,
+  RevokeSession,
+  RevokeAllSessions,
   FollowCommunity,
   GetReplies,
   GetPersonMentions,
@@ -125,16 +154,38 @@ pub enum UserOperation {
   GetModlog,
   BanFromCommunity,
   AddModToCommunity,
+  ListPendingDeletions,
+  ResolvePendingDeletion,
   AddAdmin,
   GetUnreadRegistrationApplicationCount,
   ListRegistrationApplications,
   ApproveRegistrationApplication,
+  ListReceivedDeletions,
+  GetFederatedInstances,
+  ListFederationSettings,
+  AddBlockedInstance,
+  RemoveBlockedInstance,
+  AddAllowedInstance,
+  RemoveAllowedInstance,
+  ListWordFilters,
+  AddWordFilter,
+  RemoveWordFilter,
+  ListLegalHolds,
+  AddLegalHold,
+  RemoveLegalHold,
+  PurgePerson,
+  PurgeCommunity,
+  PurgePost,
+  PurgeComment,
   BanPerson,
+  RemoveContentAndBan,
   Search,
+  MentionAutocomplete,
   ResolveObject,
   MarkAllAsRead,
   SaveUserSettings,
   TransferCommunity,
+  SetCommunityCategory,
   TransferSite,
   PasswordReset,
   PasswordChange,
@@ -148,7 +199,19 @@ pub enum UserOperation {
   ChangePassword,
   GetSiteMetadata,
   BlockCommunity,
+  PreviewCommunity,
   BlockPerson,
+  BlockInstance,
+  MoveSaved,
+  GetSaved,
+  GetPersonStats,
+  GenerateTotpSecret,
+  UpdateTotp,
+  ExportAccountData,
+  GetAccountExport,
+  ImportAccountData,
+  RegisterPushSubscription,
+  DeletePushSubscription,
 }
 
 #[derive(EnumString, Display, Debug, Clone)]
@@ -164,27 +227,82 @@ pub enum UserOperationCrud {
   EditCommunity,
   DeleteCommunity,
   RemoveCommunity,
+  // Appeal
+  CreateAppeal,
+  ListAppeals,
+  ResolveAppeal,
+  // Community category
+  CreateCommunityCategory,
+  ListCommunityCategories,
+  EditCommunityCategory,
+  DeleteCommunityCategory,
+  // Custom emoji
+  CreateCustomEmoji,
+  ListCustomEmojis,
+  EditCustomEmoji,
+  DeleteCustomEmoji,
+  // Post tag
+  CreatePostTag,
+  ListPostTags,
+  EditPostTag,
+  DeletePostTag,
+  // Automod rule
+  CreateAutomodRule,
+  ListAutomodRules,
+  EditAutomodRule,
+  DeleteAutomodRule,
+  // Wiki page
+  CreateWikiPage,
+  GetWikiPage,
+  ListWikiPages,
+  EditWikiPage,
+  DeleteWikiPage,
   // Post
   CreatePost,
+  CrossPost,
   GetPost,
   GetPosts,
   EditPost,
   DeletePost,
   RemovePost,
+  // Scheduled post
+  SchedulePost,
+  ListScheduledPosts,
+  DeleteScheduledPost,
+  // Post draft
+  SaveDraft,
+  ListDrafts,
+  DeleteDraft,
+  // Saved collection
+  CreateSavedCollection,
+  ListSavedCollections,
+  EditSavedCollection,
+  DeleteSavedCollection,
+  // Multi-community
+  CreateMultiCommunity,
+  ListMultiCommunities,
+  EditMultiCommunity,
+  DeleteMultiCommunity,
   // Comment
   CreateComment,
   GetComment,
   GetComments,
+  GetCommentChildren,
   EditComment,
   DeleteComment,
   RemoveComment,
+  GetCommentEditHistory,
   // User
   Register,
   GetPersonDetails,
   DeleteAccount,
+  ListOAuthProviders,
+  StartOAuth,
+  AuthenticateWithOAuth,
   // Private Message
   CreatePrivateMessage,
   GetPrivateMessages,
+  GetPrivateMessageThread,
   EditPrivateMessage,
   DeletePrivateMessage,
 }
@@ -0,0 +1,103 @@
+use crate::chat_server::ChatServer;
+use actix::{clock::sleep, Addr, Message};
+use anyhow::anyhow;
+use futures::StreamExt;
+use lemmy_db_schema::newtypes::{CommunityId, LocalUserId, PostId};
+use lemmy_utils::LemmyError;
+use redis::{aio::ConnectionManager, AsyncCommands, Client};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use tracing::warn;
+
+const CHANNEL: &str = "lemmy:websocket";
+
+/// The sessions which receive a websocket message
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub enum Room {
+  All,
+  Post(PostId),
+  Community(CommunityId),
+  Mod(CommunityId),
+  User(LocalUserId),
+  /// The community and post rooms, where moderators get `mod_message` instead
+  Removal {
+    community_id: CommunityId,
+    post_id: Option<PostId>,
+    mod_message: String,
+  },
+}
+
+/// A websocket message which was published by one of the lemmy_server processes
+#[derive(Serialize, Deserialize, Message)]
+#[rtype(result = "()")]
+pub struct BusMessage {
+  node_id: u64,
+  pub room: Room,
+  pub message: String,
+}
+
+/// Forwards websocket messages between all lemmy_server processes which use the same Redis
+/// server, so that each of them can send the messages to its own websocket sessions.
+#[derive(Clone)]
+pub struct MessageBus {
+  /// Identifies this process, to skip the messages which it published itself
+  node_id: u64,
+  client: Client,
+  connection: ConnectionManager,
+}
+
+impl MessageBus {
+  pub async fn connect(url: &str) -> Result<Self, LemmyError> {
+    let client = Client::open(url)?;
+    let connection = ConnectionManager::new(client.clone()).await?;
+    Ok(MessageBus {
+      node_id: rand::random(),
+      client,
+      connection,
+    })
+  }
+
+  pub(crate) fn publish(&self, room: Room, message: String) {
+    let message = BusMessage {
+      node_id: self.node_id,
+      room,
+      message,
+    };
+    let mut connection = self.connection.clone();
+    actix::spawn(async move {
+      let publish = async {
+        let payload = serde_json::to_string(&message)?;
+        connection.publish::<_, _, i64>(CHANNEL, payload).await?;
+        Ok(()) as Result<(), LemmyError>
+      };
+      if let Err(e) = publish.await {
+        warn!("Failed to publish websocket message: {}", e);
+      }
+    });
+  }
+
+  /// Passes the messages of other processes to the chat server. Subscribes again if the
+  /// connection is lost.
+  pub(crate) async fn subscribe(self, chat_server: Addr<ChatServer>) {
+    loop {
+      if let Err(e) = self.forward_messages(&chat_server).await {
+        warn!("Lost subscription to websocket messages: {}", e);
+      }
+      sleep(Duration::from_secs(5)).await;
+    }
+  }
+
+  async fn forward_messages(&self, chat_server: &Addr<ChatServer>) -> Result<(), LemmyError> {
+    let mut pubsub = self.client.get_async_connection().await?.into_pubsub();
+    pubsub.subscribe(CHANNEL).await?;
+    let mut messages = pubsub.on_message();
+    while let Some(message) = messages.next().await {
+      let payload: String = message.get_payload()?;
+      let message: BusMessage = serde_json::from_str(&payload)?;
+      if message.node_id != self.node_id {
+        chat_server.do_send(message);
+      }
+    }
+    Err(anyhow!("Connection closed").into())
+  }
+}
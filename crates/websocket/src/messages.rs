@@ -1,7 +1,6 @@
-use crate::UserOperation;
 use actix::{prelude::*, Recipient};
 use lemmy_api_common::{comment::CommentResponse, post::PostResponse};
-use lemmy_db_schema::newtypes::{CommunityId, LocalUserId, PostId};
+use lemmy_db_schema::newtypes::{CommunityId, LocalUserId, PersonId, PostId};
 use lemmy_utils::{ConnectionId, IpAddr};
 use serde::{Deserialize, Serialize};
 
@@ -10,6 +9,11 @@ use serde::{Deserialize, Serialize};
 #[rtype(result = "()")]
 pub struct WsMessage(pub String);
 
+/// Chat server sends this to close the session, when the server is shutting down
+#[derive(Message)]
+#[rtype(result = "()")]
+pub struct CloseSession;
+
 /// Message for chat server communications
 
 /// New chat session is created
@@ -17,6 +21,7 @@ pub struct WsMessage(pub String);
 #[rtype(usize)]
 pub struct Connect {
   pub addr: Recipient<WsMessage>,
+  pub close: Recipient<CloseSession>,
   pub ip: IpAddr,
 }
 
@@ -28,6 +33,11 @@ pub struct Disconnect {
   pub ip: IpAddr,
 }
 
+/// Closes all sessions, when the server is shutting down
+#[derive(Message)]
+#[rtype(result = "()")]
+pub struct CloseAllSessions;
+
 /// The messages sent to websocket clients
 #[derive(Serialize, Deserialize, Message)]
 #[rtype(result = "Result<String, std::convert::Infallible>")]
@@ -66,13 +76,33 @@ pub struct SendCommunityRoomMessage<OP: ToString, Response> {
 
 #[derive(Message)]
 #[rtype(result = "()")]
-pub struct SendModRoomMessage<Response> {
-  pub op: UserOperation,
+pub struct SendModRoomMessage<OP: ToString, Response> {
+  pub op: OP,
   pub response: Response,
   pub community_id: CommunityId,
   pub websocket_id: Option<ConnectionId>,
 }
 
+/// Details of a mod action, which are only sent to moderators of the affected community.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RemovalInfo {
+  pub mod_person_id: PersonId,
+  pub reason: Option<String>,
+}
+
+/// Sends a removal to the community and post rooms. Moderators of the community additionally get
+/// the `removal` info.
+#[derive(Message)]
+#[rtype(result = "()")]
+pub(crate) struct SendRemovalMessage<OP: ToString, Response> {
+  pub op: OP,
+  pub response: Response,
+  pub removal: RemovalInfo,
+  pub community_id: CommunityId,
+  pub post_id: Option<PostId>,
+  pub websocket_id: Option<ConnectionId>,
+}
+
 #[derive(Message)]
 #[rtype(result = "()")]
 pub(crate) struct SendPost<OP: ToString> {
@@ -132,18 +162,3 @@ pub struct GetPostUsersOnline {
 pub struct GetCommunityUsersOnline {
   pub community_id: CommunityId,
 }
-
-#[derive(Message, Debug)]
-#[rtype(result = "()")]
-pub struct CaptchaItem {
-  pub uuid: String,
-  pub answer: String,
-  pub expires: chrono::NaiveDateTime,
-}
-
-#[derive(Message)]
-#[rtype(bool)]
-pub struct CheckCaptcha {
-  pub uuid: String,
-  pub answer: String,
-}
@@ -1,6 +1,6 @@
 use crate::{
   chat_server::ChatServer,
-  messages::{Connect, Disconnect, StandardMessage, WsMessage},
+  messages::{CloseSession, Connect, Disconnect, StandardMessage, WsMessage},
   LemmyContext,
 };
 use actix::prelude::*;
@@ -60,7 +60,8 @@ impl Actor for WsSession {
     self
       .cs_addr
       .send(Connect {
-        addr: addr.recipient(),
+        addr: addr.clone().recipient(),
+        close: addr.recipient(),
         ip: self.ip.to_owned(),
       })
       .into_actor(self)
@@ -95,6 +96,18 @@ impl Handler<WsMessage> for WsSession {
   }
 }
 
+impl Handler<CloseSession> for WsSession {
+  type Result = ();
+
+  fn handle(&mut self, _: CloseSession, ctx: &mut Self::Context) {
+    ctx.close(Some(ws::CloseReason {
+      code: ws::CloseCode::Restart,
+      description: None,
+    }));
+    ctx.stop();
+  }
+}
+
 /// WebSocket message handler
 impl StreamHandler<Result<ws::Message, ws::ProtocolError>> for WsSession {
   fn handle(&mut self, result: Result<ws::Message, ws::ProtocolError>, ctx: &mut Self::Context) {
@@ -1,5 +1,12 @@
 use crate::{
-  messages::{SendComment, SendCommunityRoomMessage, SendPost, SendUserRoomMessage},
+  messages::{
+    RemovalInfo,
+    SendComment,
+    SendCommunityRoomMessage,
+    SendPost,
+    SendRemovalMessage,
+    SendUserRoomMessage,
+  },
   LemmyContext,
   OperationType,
 };
@@ -19,6 +26,7 @@ use lemmy_db_schema::{
     person::Person,
     person_mention::{PersonMention, PersonMentionForm},
     post::Post,
+    push_subscription::PushSubscription,
   },
   traits::{Crud, DeleteableOrRemoveable},
 };
@@ -29,7 +37,17 @@ use lemmy_db_views::{
   private_message_view::PrivateMessageView,
 };
 use lemmy_db_views_actor::community_view::CommunityView;
-use lemmy_utils::{utils::MentionData, ConnectionId, LemmyError};
+use lemmy_utils::{
+  settings::structs::WebPushConfig,
+  utils::MentionData,
+  web_push::{send_web_push, PushNotification},
+  ConnectionId,
+  LemmyError,
+};
+use tracing::warn;
+
+/// Only the beginning of the content is included, as push services limit the message size
+const PUSH_BODY_MAX_CHARS: usize = 200;
 
 pub async fn send_post_ws_message<OP: ToString + Send + OperationType + 'static>(
   post_id: PostId,
@@ -38,22 +56,74 @@ pub async fn send_post_ws_message<OP: ToString + Send + OperationType + 'static>
   person_id: Option<PersonId>,
   context: &LemmyContext,
 ) -> Result<PostResponse, LemmyError> {
-  let post_view = blocking(context.pool(), move |conn| {
-    PostView::read(conn, post_id, person_id)
-  })
-  .await??;
+  let (post_view, sent_post_view) = read_post_views(post_id, person_id, context).await?;
+
+  context.chat_server().do_send(SendPost {
+    op,
+    post: PostResponse {
+      post_view: sent_post_view,
+    },
+    websocket_id,
+  });
+
+  Ok(PostResponse { post_view })
+}
 
+/// Like [`send_post_ws_message`], but also sends the removal reason and mod to community mods.
+pub async fn send_post_removal_ws_message<OP: ToString + Send + Clone + OperationType + 'static>(
+  post_id: PostId,
+  op: OP,
+  removal: RemovalInfo,
+  websocket_id: Option<ConnectionId>,
+  person_id: Option<PersonId>,
+  recipient_ids: Vec<LocalUserId>,
+  context: &LemmyContext,
+) -> Result<PostResponse, LemmyError> {
+  let (post_view, sent_post_view) = read_post_views(post_id, person_id, context).await?;
   let res = PostResponse { post_view };
+  let res_sent = PostResponse {
+    post_view: sent_post_view,
+  };
 
-  context.chat_server().do_send(SendPost {
+  for recipient_id in recipient_ids {
+    context.chat_server().do_send(SendUserRoomMessage {
+      op: op.clone(),
+      response: res_sent.clone(),
+      local_recipient_id: recipient_id,
+      websocket_id,
+    });
+  }
+
+  context.chat_server().do_send(SendRemovalMessage {
     op,
-    post: res.clone(),
+    community_id: res.post_view.community.id,
+    post_id: Some(post_id),
+    response: res_sent,
+    removal,
     websocket_id,
   });
 
   Ok(res)
 }
 
+/// Reads the post for the person, and without their data for everyone else it is sent to. The
+/// others see the scores, even if the person hides them.
+async fn read_post_views(
+  post_id: PostId,
+  person_id: Option<PersonId>,
+  context: &LemmyContext,
+) -> Result<(PostView, PostView), LemmyError> {
+  blocking(context.pool(), move |conn| -> Result<_, LemmyError> {
+    let sent_post_view = PostView::read(conn, post_id, None)?;
+    let post_view = match person_id {
+      Some(_) => PostView::read(conn, post_id, person_id)?,
+      None => sent_post_view.clone(),
+    };
+    Ok((post_view, sent_post_view))
+  })
+  .await?
+}
+
 // TODO: in many call sites in apub crate, we are setting an empty vec for recipient_ids,
 //       we should get the actual recipient actors from somewhere
 pub async fn send_comment_ws_message_simple<OP: ToString + Send + OperationType + 'static>(
@@ -73,14 +143,7 @@ pub async fn send_comment_ws_message<OP: ToString + Send + OperationType + 'stat
   recipient_ids: Vec<LocalUserId>,
   context: &LemmyContext,
 ) -> Result<CommentResponse, LemmyError> {
-  let mut view = blocking(context.pool(), move |conn| {
-    CommentView::read(conn, comment_id, person_id)
-  })
-  .await??;
-
-  if view.comment.deleted || view.comment.removed {
-    view.comment = view.comment.blank_out_deleted_or_removed_info();
-  }
+  let (view, sent_view) = read_comment_views(comment_id, person_id, context).await?;
 
   let mut res = CommentResponse {
     comment_view: view,
@@ -91,7 +154,10 @@ pub async fn send_comment_ws_message<OP: ToString + Send + OperationType + 'stat
 
   context.chat_server().do_send(SendComment {
     op,
-    comment: res.clone(),
+    comment: CommentResponse {
+      comment_view: sent_view,
+      ..res.clone()
+    },
     websocket_id,
   });
 
@@ -102,6 +168,76 @@ pub async fn send_comment_ws_message<OP: ToString + Send + OperationType + 'stat
   Ok(res)
 }
 
+/// Like [`send_comment_ws_message`], but also sends the removal reason and mod to community mods.
+pub async fn send_comment_removal_ws_message<
+  OP: ToString + Send + Clone + OperationType + 'static,
+>(
+  comment_id: CommentId,
+  op: OP,
+  removal: RemovalInfo,
+  websocket_id: Option<ConnectionId>,
+  person_id: Option<PersonId>,
+  recipient_ids: Vec<LocalUserId>,
+  context: &LemmyContext,
+) -> Result<CommentResponse, LemmyError> {
+  let (view, sent_view) = read_comment_views(comment_id, person_id, context).await?;
+
+  let res = CommentResponse {
+    comment_view: view,
+    recipient_ids: Vec::new(),
+    // The sent out form id should be null
+    form_id: None,
+  };
+  let res_sent = CommentResponse {
+    comment_view: sent_view,
+    ..res.clone()
+  };
+
+  for recipient_id in recipient_ids {
+    context.chat_server().do_send(SendUserRoomMessage {
+      op: op.clone(),
+      response: res_sent.clone(),
+      local_recipient_id: recipient_id,
+      websocket_id,
+    });
+  }
+
+  context.chat_server().do_send(SendRemovalMessage {
+    op,
+    community_id: res.comment_view.community.id,
+    post_id: Some(res.comment_view.post.id),
+    response: res_sent,
+    removal,
+    websocket_id,
+  });
+
+  Ok(res)
+}
+
+/// Like [`read_post_views`], with the content of deleted or removed comments blanked out
+async fn read_comment_views(
+  comment_id: CommentId,
+  person_id: Option<PersonId>,
+  context: &LemmyContext,
+) -> Result<(CommentView, CommentView), LemmyError> {
+  blocking(context.pool(), move |conn| -> Result<_, LemmyError> {
+    let mut sent_view = CommentView::read(conn, comment_id, None)?;
+    if sent_view.comment.deleted || sent_view.comment.removed {
+      sent_view.comment = sent_view.comment.blank_out_deleted_or_removed_info();
+    }
+    let view = match person_id {
+      Some(_) => {
+        let mut view = CommentView::read(conn, comment_id, person_id)?;
+        view.comment = sent_view.comment.clone();
+        view
+      }
+      None => sent_view.clone(),
+    };
+    Ok((view, sent_view))
+  })
+  .await?
+}
+
 pub async fn send_community_ws_message<OP: ToString + Send + OperationType + 'static>(
   community_id: CommunityId,
   op: OP,
@@ -114,7 +250,10 @@ pub async fn send_community_ws_message<OP: ToString + Send + OperationType + 'st
   })
   .await??;
 
-  let res = CommunityResponse { community_view };
+  let res = CommunityResponse {
+    community_view,
+    action_taken: None,
+  };
 
   // Strip out the person id and subscribed when sending to others
   let mut res_mut = res.clone();
@@ -130,6 +269,54 @@ pub async fn send_community_ws_message<OP: ToString + Send + OperationType + 'st
   Ok(res)
 }
 
+/// Like [`send_community_ws_message`], but also sends the removal reason and mod to community
+/// mods.
+pub async fn send_community_removal_ws_message<
+  OP: ToString + Send + Clone + OperationType + 'static,
+>(
+  community_id: CommunityId,
+  op: OP,
+  removal: RemovalInfo,
+  websocket_id: Option<ConnectionId>,
+  person_id: Option<PersonId>,
+  recipient_ids: Vec<LocalUserId>,
+  context: &LemmyContext,
+) -> Result<CommunityResponse, LemmyError> {
+  let community_view = blocking(context.pool(), move |conn| {
+    CommunityView::read(conn, community_id, person_id)
+  })
+  .await??;
+
+  let res = CommunityResponse {
+    community_view,
+    action_taken: None,
+  };
+
+  // Strip out the person id and subscribed when sending to others
+  let mut res_mut = res.clone();
+  res_mut.community_view.subscribed = false;
+
+  for recipient_id in recipient_ids {
+    context.chat_server().do_send(SendUserRoomMessage {
+      op: op.clone(),
+      response: res_mut.clone(),
+      local_recipient_id: recipient_id,
+      websocket_id,
+    });
+  }
+
+  context.chat_server().do_send(SendRemovalMessage {
+    op,
+    response: res_mut,
+    removal,
+    community_id,
+    post_id: None,
+    websocket_id,
+  });
+
+  Ok(res)
+}
+
 pub async fn send_pm_ws_message<OP: ToString + Send + OperationType + 'static>(
   private_message_id: PrivateMessageId,
   op: OP,
@@ -217,7 +404,13 @@ pub async fn send_local_notifs(
           "Person Mention",
           &comment.content,
           &context.settings(),
-        )
+        );
+        send_push_to_user(
+          &mention_user_view,
+          format!("Mentioned by {}", person.name),
+          &comment.content,
+          context,
+        );
       }
     }
   }
@@ -252,7 +445,13 @@ pub async fn send_local_notifs(
                 "Comment Reply",
                 &comment.content,
                 &context.settings(),
-              )
+              );
+              send_push_to_user(
+                &parent_user_view,
+                format!("Reply from {}", person.name),
+                &comment.content,
+                context,
+              );
             }
           }
         }
@@ -282,7 +481,13 @@ pub async fn send_local_notifs(
               "Post Reply",
               &comment.content,
               &context.settings(),
-            )
+            );
+            send_push_to_user(
+              &parent_user_view,
+              format!("Reply from {}", person.name),
+              &comment.content,
+              context,
+            );
           }
         }
       }
@@ -290,3 +495,69 @@ pub async fn send_local_notifs(
   };
   Ok(recipient_ids)
 }
+
+/// Sends a Web Push notification to all subscribed devices of the user in the background, if Web
+/// Push is enabled on this instance
+pub fn send_push_to_user(
+  local_user_view: &LocalUserView,
+  title: String,
+  content: &str,
+  context: &LemmyContext,
+) {
+  let settings = context.settings();
+  let config = match settings.web_push.clone() {
+    Some(config) => config,
+    None => return,
+  };
+  if local_user_view.person.banned {
+    return;
+  }
+
+  let notification = PushNotification {
+    title,
+    body: content.chars().take(PUSH_BODY_MAX_CHARS).collect(),
+    url: format!("{}/inbox", settings.get_protocol_and_hostname()),
+  };
+  let local_user_id = local_user_view.local_user.id;
+  let context = context.clone();
+  actix_web::rt::spawn(async move {
+    if let Err(e) = send_push_notifications(local_user_id, &notification, &config, &context).await {
+      warn!("Failed to send push notifications: {}", e);
+    }
+  });
+}
+
+async fn send_push_notifications(
+  local_user_id: LocalUserId,
+  notification: &PushNotification,
+  config: &WebPushConfig,
+  context: &LemmyContext,
+) -> Result<(), LemmyError> {
+  let subscriptions = blocking(context.pool(), move |conn| {
+    PushSubscription::list_for_local_user(conn, local_user_id)
+  })
+  .await??;
+  for subscription in subscriptions {
+    let sent = send_web_push(
+      context.client(),
+      config,
+      &subscription.endpoint,
+      &subscription.p256dh,
+      &subscription.auth,
+      notification,
+    )
+    .await;
+    match sent {
+      Ok(true) => {}
+      // The user unsubscribed or the subscription expired
+      Ok(false) => {
+        blocking(context.pool(), move |conn| {
+          PushSubscription::delete(conn, subscription.id)
+        })
+        .await??;
+      }
+      Err(e) => warn!("Failed to send push notification: {}", e),
+    }
+  }
+  Ok(())
+}
@@ -0,0 +1 @@
+alter table activity drop column forwarded;
@@ -0,0 +1,3 @@
+-- Tracks whether a received activity was already forwarded to community followers, so that the
+-- same activity is never announced twice.
+alter table activity add column forwarded boolean not null default false;
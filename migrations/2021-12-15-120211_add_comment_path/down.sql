@@ -0,0 +1,6 @@
+drop view comment_alias_1;
+drop trigger comment_set_path on comment;
+drop function comment_set_path;
+drop index idx_comment_path;
+alter table comment drop column path;
+create view comment_alias_1 as select * from comment;
@@ -0,0 +1,43 @@
+-- Materialized path of each comment, made of the ids from the thread root down to the comment
+-- itself, eg `0.12.57.103`. Allows measuring and selecting comment subtrees without recursive
+-- queries.
+alter table comment add column path text not null default '0';
+
+with recursive comment_tree (id, path) as (
+  select id, '0.' || id
+  from comment
+  where parent_id is null
+  union all
+  select c.id, t.path || '.' || c.id
+  from comment c
+  join comment_tree t on c.parent_id = t.id
+)
+update comment c
+set path = t.path
+from comment_tree t
+where c.id = t.id;
+
+create index idx_comment_path on comment (path text_pattern_ops);
+
+create or replace function comment_set_path()
+returns trigger language plpgsql
+as $$
+begin
+  IF NEW.parent_id IS NULL THEN
+    NEW.path := '0.' || NEW.id;
+  ELSE
+    select c.path || '.' || NEW.id into NEW.path
+    from comment c
+    where c.id = NEW.parent_id;
+  END IF;
+  return NEW;
+end $$;
+
+create trigger comment_set_path
+before insert on comment
+for each row
+execute procedure comment_set_path();
+
+-- The alias view needs to be recreated to pick up the new column
+drop view comment_alias_1;
+create view comment_alias_1 as select * from comment;
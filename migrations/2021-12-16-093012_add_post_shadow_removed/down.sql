@@ -0,0 +1,2 @@
+drop table mod_shadow_remove_post;
+alter table post drop column shadow_removed;
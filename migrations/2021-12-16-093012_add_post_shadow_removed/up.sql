@@ -0,0 +1,11 @@
+-- Shadow removed posts are only shown to their creator and to subscribers of the community
+alter table post add column shadow_removed boolean not null default false;
+
+create table mod_shadow_remove_post (
+  id serial primary key,
+  mod_person_id int references person on update cascade on delete cascade not null,
+  post_id int references post on update cascade on delete cascade not null,
+  reason text,
+  shadow_removed boolean default true,
+  when_ timestamp not null default now()
+);
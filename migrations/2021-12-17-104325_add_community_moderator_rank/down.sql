@@ -0,0 +1,3 @@
+drop trigger community_moderator_set_rank on community_moderator;
+drop function community_moderator_set_rank;
+alter table community_moderator drop column rank;
@@ -0,0 +1,31 @@
+-- Explicit order of community moderators, with the top mod (owner) at rank 0. Previously the order
+-- was only given by insertion time.
+alter table community_moderator add column rank int;
+
+update community_moderator cm
+set rank = r.rank
+from (
+  select id, row_number() over (partition by community_id order by published, id) - 1 as rank
+  from community_moderator
+) r
+where cm.id = r.id;
+
+alter table community_moderator alter column rank set not null;
+
+-- New mods are added at the bottom of the list
+create or replace function community_moderator_set_rank()
+returns trigger language plpgsql
+as $$
+begin
+  IF NEW.rank IS NULL THEN
+    select coalesce(max(cm.rank) + 1, 0) into NEW.rank
+    from community_moderator cm
+    where cm.community_id = NEW.community_id;
+  END IF;
+  return NEW;
+end $$;
+
+create trigger community_moderator_set_rank
+before insert on community_moderator
+for each row
+execute procedure community_moderator_set_rank();
@@ -0,0 +1,3 @@
+alter table mod_remove_post drop column reason_translations;
+alter table mod_remove_comment drop column reason_translations;
+alter table mod_remove_community drop column reason_translations;
@@ -0,0 +1,4 @@
+-- Translations of the removal reason, as a json object of language code to reason
+alter table mod_remove_post add column reason_translations jsonb;
+alter table mod_remove_comment add column reason_translations jsonb;
+alter table mod_remove_community add column reason_translations jsonb;
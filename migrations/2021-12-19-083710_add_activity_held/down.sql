@@ -0,0 +1,2 @@
+drop index idx_activity_held;
+alter table activity drop column held;
@@ -0,0 +1,4 @@
+-- Local deletion activities which were not sent out because of federation.hold_deletions, and
+-- are waiting to be sent once the option is disabled.
+alter table activity add column held boolean not null default false;
+create index idx_activity_held on activity (published) where held;
@@ -0,0 +1,14 @@
+-- Deletions and removals of local content which were received from other instances, and are
+-- waiting for review by a local mod (see federation.review_received_deletions).
+create table pending_deletion (
+  id serial primary key,
+  actor_id int references person on update cascade on delete cascade not null,
+  community_id int references community on update cascade on delete cascade not null,
+  activity_id text not null,
+  object_id text not null,
+  removal boolean not null,
+  reason text,
+  published timestamp not null default now()
+);
+
+create index idx_pending_deletion_community on pending_deletion (community_id);
@@ -0,0 +1 @@
+drop table received_deletion;
@@ -0,0 +1,14 @@
+-- Deletions and removals received from other instances, with the result of verifying them. This
+-- allows admins to investigate all deletions made by a given remote actor.
+create table received_deletion (
+  id serial primary key,
+  actor_id text not null,
+  activity_id text not null,
+  -- Delete, UndoDelete, Remove or UndoRemove
+  kind text not null,
+  object_id text not null,
+  accepted boolean not null,
+  published timestamp not null default now()
+);
+
+create index idx_received_deletion_actor on received_deletion (actor_id);
@@ -0,0 +1,2 @@
+drop table mod_resolve_appeal;
+drop table appeal;
@@ -0,0 +1,32 @@
+-- Appeals of users against the removal of their post, comment or community
+create table appeal (
+  id serial primary key,
+  creator_id int references person on update cascade on delete cascade not null,
+  community_id int references community on update cascade on delete cascade not null,
+  post_id int references post on update cascade on delete cascade,
+  comment_id int references comment on update cascade on delete cascade,
+  reason text not null,
+  status text not null default 'Pending',
+  resolver_id int references person on update cascade on delete cascade,
+  published timestamp not null default now(),
+  updated timestamp null
+);
+
+create index idx_appeal_community on appeal (community_id);
+
+-- Each user can only have a single open appeal for the same object
+create unique index idx_appeal_pending on appeal (
+  creator_id,
+  community_id,
+  coalesce(post_id, 0),
+  coalesce(comment_id, 0)
+) where status = 'Pending';
+
+create table mod_resolve_appeal (
+  id serial primary key,
+  mod_person_id int references person on update cascade on delete cascade not null,
+  appeal_id int references appeal on update cascade on delete cascade not null,
+  granted boolean not null,
+  reason text,
+  when_ timestamp not null default now()
+);
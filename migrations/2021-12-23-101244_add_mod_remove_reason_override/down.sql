@@ -0,0 +1,4 @@
+alter table mod_remove_post drop column reason_override;
+alter table mod_remove_comment drop column reason_override;
+alter table mod_remove_community drop column reason_override;
+alter table mod_shadow_remove_post drop column reason_override;
@@ -0,0 +1,5 @@
+-- Set if an admin removed without a reason, although moderation.require_removal_reason is enabled
+alter table mod_remove_post add column reason_override boolean not null default false;
+alter table mod_remove_comment add column reason_override boolean not null default false;
+alter table mod_remove_community add column reason_override boolean not null default false;
+alter table mod_shadow_remove_post add column reason_override boolean not null default false;
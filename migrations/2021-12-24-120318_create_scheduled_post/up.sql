@@ -0,0 +1,15 @@
+-- Posts which are created with a publish time in the future. They are kept here until the time
+-- has come, and only then inserted into the post table and federated.
+create table scheduled_post (
+  id serial primary key,
+  creator_id int references person on update cascade on delete cascade not null,
+  community_id int references community on update cascade on delete cascade not null,
+  name varchar(200) not null,
+  url text,
+  body text,
+  nsfw boolean default false not null,
+  publish_at timestamp not null,
+  published timestamp not null default now()
+);
+
+create index idx_scheduled_post_publish_at on scheduled_post (publish_at);
@@ -0,0 +1,2 @@
+alter table local_user drop column totp_secret;
+alter table local_user drop column totp_enabled;
@@ -0,0 +1,3 @@
+-- An enrolled secret only takes effect once the user confirmed it with a valid token
+alter table local_user add column totp_secret text;
+alter table local_user add column totp_enabled boolean default false not null;
@@ -0,0 +1,15 @@
+-- Unfinished posts which are stored on the server, so that they can be resumed from any device.
+-- All fields are optional, because a draft can be saved at any point while writing.
+create table post_draft (
+  id serial primary key,
+  creator_id int references person on update cascade on delete cascade not null,
+  community_id int references community on update cascade on delete cascade,
+  name varchar(200),
+  url text,
+  body text,
+  nsfw boolean default false not null,
+  published timestamp not null default now(),
+  updated timestamp
+);
+
+create index idx_post_draft_creator on post_draft (creator_id);
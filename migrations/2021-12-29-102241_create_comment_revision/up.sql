@@ -0,0 +1,10 @@
+-- Previous versions of edited comments. Each row holds the content which the comment had from
+-- `published` until it was edited again.
+create table comment_revision (
+  id serial primary key,
+  comment_id int references comment on update cascade on delete cascade not null,
+  content text not null,
+  published timestamp not null
+);
+
+create index idx_comment_revision_comment on comment_revision (comment_id);
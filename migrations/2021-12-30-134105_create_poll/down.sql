@@ -0,0 +1,3 @@
+drop table poll_vote;
+drop table poll_option;
+drop table poll;
@@ -0,0 +1,27 @@
+-- Polls which are attached to posts, federated as ActivityStreams Question
+create table poll (
+  id serial primary key,
+  post_id int references post on update cascade on delete cascade not null unique,
+  multiple_choice boolean default false not null,
+  end_time timestamp,
+  published timestamp not null default now()
+);
+
+-- For local polls, votes is counted from poll_vote. For remote polls it is the total from the
+-- instance of the poll.
+create table poll_option (
+  id serial primary key,
+  poll_id int references poll on update cascade on delete cascade not null,
+  name varchar(200) not null,
+  position int not null,
+  votes int default 0 not null,
+  unique (poll_id, position)
+);
+
+create table poll_vote (
+  id serial primary key,
+  poll_option_id int references poll_option on update cascade on delete cascade not null,
+  person_id int references person on update cascade on delete cascade not null,
+  published timestamp not null default now(),
+  unique (poll_option_id, person_id)
+);
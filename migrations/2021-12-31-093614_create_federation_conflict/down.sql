@@ -0,0 +1 @@
+drop table federation_conflict;
@@ -0,0 +1,12 @@
+-- Incoming updates which were rejected, because the local version of the object was edited more
+-- recently. Kept so that admins can look into federation problems.
+create table federation_conflict (
+  id serial primary key,
+  object_id varchar(255) not null,
+  activity_id varchar(255) not null,
+  local_updated timestamp not null,
+  remote_updated timestamp,
+  published timestamp not null default now()
+);
+
+create index idx_federation_conflict_object on federation_conflict (object_id);
@@ -0,0 +1,8 @@
+-- Instances which admins added to the federation allowlist or blocklist through the api. These
+-- are used in addition to the lists in the config file.
+create table federation_instance (
+  id serial primary key,
+  domain varchar(255) unique not null,
+  blocked boolean not null,
+  published timestamp not null default now()
+);
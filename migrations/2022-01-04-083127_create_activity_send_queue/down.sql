@@ -0,0 +1,2 @@
+drop table activity_send_queue;
+drop table instance_send_failure;
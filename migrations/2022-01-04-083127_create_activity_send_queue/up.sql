@@ -0,0 +1,23 @@
+-- Outgoing activities which still need to be delivered, one row per inbox. Rows are deleted once
+-- the delivery succeeded, or after too many failed attempts.
+create table activity_send_queue (
+  id serial primary key,
+  activity_id text not null,
+  inbox text not null,
+  actor_id text not null,
+  activity text not null,
+  retries int not null default 0,
+  next_attempt timestamp not null default now(),
+  published timestamp not null default now()
+);
+
+create index idx_activity_send_queue_next_attempt on activity_send_queue (next_attempt);
+
+-- Consecutive failed deliveries to an instance. While an instance is failing, deliveries to it
+-- are postponed without even trying, so that unreachable instances don't hold up the queue.
+create table instance_send_failure (
+  id serial primary key,
+  domain varchar(255) unique not null,
+  failures int not null,
+  last_failure timestamp not null
+);
@@ -0,0 +1,2 @@
+drop table post_tag_assignment;
+drop table post_tag;
@@ -0,0 +1,19 @@
+-- Tags which the moderators of a community define, and which can be assigned to its posts
+create table post_tag (
+  id serial primary key,
+  community_id int references community on update cascade on delete cascade not null,
+  name varchar(40) not null,
+  published timestamp not null default now(),
+  updated timestamp null,
+  unique (community_id, name)
+);
+
+create table post_tag_assignment (
+  id serial primary key,
+  post_id int references post on update cascade on delete cascade not null,
+  tag_id int references post_tag on update cascade on delete cascade not null,
+  published timestamp not null default now(),
+  unique (post_id, tag_id)
+);
+
+create index idx_post_tag_assignment_tag on post_tag_assignment (tag_id);
@@ -0,0 +1,4 @@
+drop trigger private_message_assign_thread on private_message;
+drop function private_message_assign_thread;
+alter table private_message drop column thread_id;
+drop table private_message_thread;
@@ -0,0 +1,45 @@
+-- A conversation between two persons, the one with the lower id is always person_a
+create table private_message_thread (
+  id serial primary key,
+  person_a_id int references person on update cascade on delete cascade not null,
+  person_b_id int references person on update cascade on delete cascade not null,
+  published timestamp not null default now(),
+  newest_message_time timestamp not null default now(),
+  unique (person_a_id, person_b_id),
+  check (person_a_id <= person_b_id)
+);
+
+insert into private_message_thread (person_a_id, person_b_id, published, newest_message_time)
+select least(creator_id, recipient_id), greatest(creator_id, recipient_id), min(published), max(published)
+from private_message
+group by 1, 2;
+
+alter table private_message add column thread_id int references private_message_thread on update cascade on delete cascade;
+
+update private_message pm
+set thread_id = t.id
+from private_message_thread t
+where t.person_a_id = least(pm.creator_id, pm.recipient_id)
+  and t.person_b_id = greatest(pm.creator_id, pm.recipient_id);
+
+alter table private_message alter column thread_id set not null;
+
+create index idx_private_message_thread_id on private_message (thread_id);
+
+-- Every new message is put into the thread of its two participants, which is created if necessary
+create or replace function private_message_assign_thread()
+returns trigger language plpgsql
+as $$
+begin
+  insert into private_message_thread (person_a_id, person_b_id, published, newest_message_time)
+  values (least(NEW.creator_id, NEW.recipient_id), greatest(NEW.creator_id, NEW.recipient_id), NEW.published, NEW.published)
+  on conflict (person_a_id, person_b_id)
+  do update set newest_message_time = greatest(private_message_thread.newest_message_time, excluded.newest_message_time)
+  returning id into NEW.thread_id;
+  return NEW;
+end $$;
+
+create trigger private_message_assign_thread
+before insert on private_message
+for each row
+execute procedure private_message_assign_thread();
@@ -0,0 +1,12 @@
+-- Archives of all data of a local user, which are generated in the background
+create table account_export (
+  id serial primary key,
+  local_user_id int references local_user on update cascade on delete cascade not null,
+  token text not null unique,
+  -- The archive as json, null until it is generated
+  data text,
+  published timestamp not null default now(),
+  finished timestamp
+);
+
+create index idx_account_export_local_user on account_export (local_user_id);
@@ -0,0 +1,12 @@
+-- Web Push subscriptions of local users, one per browser or device
+create table push_subscription (
+  id serial primary key,
+  local_user_id int references local_user on update cascade on delete cascade not null,
+  endpoint text not null unique,
+  -- Public key and authentication secret of the subscription, base64url encoded
+  p256dh text not null,
+  auth text not null,
+  published timestamp not null default now()
+);
+
+create index idx_push_subscription_local_user on push_subscription (local_user_id);
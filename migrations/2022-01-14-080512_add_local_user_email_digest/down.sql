@@ -0,0 +1,2 @@
+alter table local_user drop column email_digest_frequency;
+alter table local_user drop column email_digest_sent;
@@ -0,0 +1,3 @@
+-- Periodic summary emails, which users can turn off by setting the frequency to 'Never'
+alter table local_user add column email_digest_frequency text default 'Weekly' not null;
+alter table local_user add column email_digest_sent timestamp;
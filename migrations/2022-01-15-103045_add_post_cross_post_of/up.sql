@@ -0,0 +1,4 @@
+-- Points to the original post, if this post is a cross-post into another community
+alter table post add column cross_post_of int references post on update cascade on delete set null;
+
+create index idx_post_cross_post_of on post (cross_post_of);
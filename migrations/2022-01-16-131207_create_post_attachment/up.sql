@@ -0,0 +1,10 @@
+-- Additional media and links of federated posts, which only have a single url in Lemmy
+create table post_attachment (
+  id serial primary key,
+  post_id int references post on update cascade on delete cascade not null,
+  url text not null,
+  media_type text,
+  name text,
+  position int not null,
+  unique (post_id, position)
+);
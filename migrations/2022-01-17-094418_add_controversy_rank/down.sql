@@ -0,0 +1,4 @@
+drop index idx_post_aggregates_stickied_controversy;
+drop index idx_post_aggregates_controversy;
+drop index idx_comment_aggregates_controversy;
+drop function controversy_rank;
@@ -0,0 +1,18 @@
+-- Controversial content has many votes, which are split evenly between up and down
+create or replace function controversy_rank(
+  upvotes numeric,
+  downvotes numeric)
+returns float as $$
+begin
+  if downvotes <= 0 or upvotes <= 0 then
+    return 0;
+  else
+    return (upvotes + downvotes) ^ (least(upvotes, downvotes) / greatest(upvotes, downvotes));
+  end if;
+end; $$
+LANGUAGE plpgsql
+IMMUTABLE;
+
+create index idx_post_aggregates_stickied_controversy on post_aggregates (stickied desc, controversy_rank(upvotes, downvotes) desc);
+create index idx_post_aggregates_controversy on post_aggregates (controversy_rank(upvotes, downvotes) desc);
+create index idx_comment_aggregates_controversy on comment_aggregates (controversy_rank(upvotes, downvotes) desc);
@@ -0,0 +1,2 @@
+drop table community_wiki_page_revision;
+drop table community_wiki_page;
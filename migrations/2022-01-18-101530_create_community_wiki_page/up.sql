@@ -0,0 +1,25 @@
+-- Markdown pages which the mods of a community maintain, for example rules or an FAQ
+create table community_wiki_page (
+  id serial primary key,
+  community_id int references community on update cascade on delete cascade not null,
+  creator_id int references person on update cascade on delete cascade not null,
+  title varchar(200) not null,
+  content text not null,
+  deleted boolean default false not null,
+  published timestamp not null default now(),
+  updated timestamp,
+  ap_id varchar(255) not null unique default generate_unique_changeme(),
+  local boolean default true not null,
+  unique (community_id, title)
+);
+
+-- Every version of a wiki page, including the current one
+create table community_wiki_page_revision (
+  id serial primary key,
+  wiki_page_id int references community_wiki_page on update cascade on delete cascade not null,
+  editor_id int references person on update cascade on delete cascade not null,
+  content text not null,
+  published timestamp not null default now()
+);
+
+create index idx_community_wiki_page_revision_page on community_wiki_page_revision (wiki_page_id);
@@ -0,0 +1,3 @@
+drop index idx_community_person_ban_expires;
+
+alter table community_person_ban drop column expires;
@@ -0,0 +1,3 @@
+alter table community_person_ban add column expires timestamp;
+
+create index idx_community_person_ban_expires on community_person_ban (expires) where expires is not null;
@@ -0,0 +1,3 @@
+drop index idx_person_name_trgm, idx_person_display_name_trgm;
+
+-- The extension is left installed, as other database objects might have started using it
@@ -0,0 +1,5 @@
+create extension if not exists pg_trgm;
+
+-- Prefix searches for mention autocomplete
+create index idx_person_name_trgm on person using gin (name gin_trgm_ops);
+create index idx_person_display_name_trgm on person using gin (display_name gin_trgm_ops);
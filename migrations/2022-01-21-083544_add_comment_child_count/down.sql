@@ -0,0 +1,4 @@
+drop trigger comment_aggregates_child_count on comment;
+drop function comment_aggregates_child_count;
+
+alter table comment_aggregates drop column child_count;
@@ -0,0 +1,33 @@
+-- Number of direct replies, so that clients know which collapsed comments can be expanded
+alter table comment_aggregates add column child_count int not null default 0;
+
+update comment_aggregates ca
+set child_count = c.child_count
+from (
+  select parent_id, count(*) as child_count
+  from comment
+  where parent_id is not null
+  group by parent_id
+) c
+where ca.comment_id = c.parent_id;
+
+create function comment_aggregates_child_count()
+returns trigger language plpgsql
+as $$
+begin
+  IF (TG_OP = 'INSERT' and NEW.parent_id is not null) THEN
+    update comment_aggregates
+    set child_count = child_count + 1
+    where comment_id = NEW.parent_id;
+  ELSIF (TG_OP = 'DELETE' and OLD.parent_id is not null) THEN
+    update comment_aggregates
+    set child_count = child_count - 1
+    where comment_id = OLD.parent_id;
+  END IF;
+  return null;
+end $$;
+
+create trigger comment_aggregates_child_count
+after insert or delete on comment
+for each row
+execute procedure comment_aggregates_child_count();
@@ -0,0 +1 @@
+drop function comment_path_depth;
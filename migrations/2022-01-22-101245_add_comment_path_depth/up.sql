@@ -0,0 +1,8 @@
+-- Depth of a comment in its thread, computed from the materialized path, with top-level comments
+-- at 0. Lets a branch of a comment tree be loaded down to a given depth in a single query.
+create or replace function comment_path_depth(path text)
+returns int as $$
+  select length(path) - length(replace(path, '.', '')) - 1
+$$
+LANGUAGE sql
+IMMUTABLE;
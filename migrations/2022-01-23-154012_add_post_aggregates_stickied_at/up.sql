@@ -0,0 +1,33 @@
+-- Several posts can be stickied in a community, they are listed in the order they were stickied
+alter table post_aggregates add column stickied_at timestamp;
+
+update post_aggregates pa
+set stickied_at = coalesce(p.updated, p.published)
+from post p
+where pa.post_id = p.id and p.stickied;
+
+create or replace function post_aggregates_stickied()
+returns trigger language plpgsql
+as $$
+begin
+  update post_aggregates pa
+  set stickied = NEW.stickied,
+  stickied_at = case when NEW.stickied then now() else null end
+  where pa.post_id = NEW.id;
+
+  return null;
+end $$;
+
+-- Federated posts can already be stickied when they are inserted
+create or replace function post_aggregates_post()
+returns trigger language plpgsql
+as $$
+begin
+  IF (TG_OP = 'INSERT') THEN
+    insert into post_aggregates (post_id, published, newest_comment_time, newest_comment_time_necro, stickied, stickied_at)
+    values (NEW.id, NEW.published, NEW.published, NEW.published, NEW.stickied, case when NEW.stickied then now() else null end);
+  ELSIF (TG_OP = 'DELETE') THEN
+    delete from post_aggregates where post_id = OLD.id;
+  END IF;
+  return null;
+end $$;
@@ -0,0 +1,9 @@
+-- Key/value pairs shown on user profiles, like the profile metadata of Mastodon
+create table person_field (
+  id serial primary key,
+  person_id int references person on update cascade on delete cascade not null,
+  name text not null,
+  value text not null,
+  position int not null,
+  unique (person_id, position)
+);
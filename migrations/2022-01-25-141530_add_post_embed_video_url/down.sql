@@ -0,0 +1,2 @@
+alter table post drop column embed_video_url;
+alter table post drop column embed_provider_name;
@@ -0,0 +1,2 @@
+alter table post add column embed_video_url text;
+alter table post add column embed_provider_name text;
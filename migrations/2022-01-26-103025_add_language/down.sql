@@ -0,0 +1,8 @@
+drop table community_language;
+drop table local_user_language;
+drop view comment_alias_1;
+alter table comment drop column language_id;
+create view comment_alias_1 as select * from comment;
+alter table post drop column language_id;
+alter table scheduled_post drop column language_id;
+drop table language;
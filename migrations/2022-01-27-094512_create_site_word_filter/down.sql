@@ -0,0 +1 @@
+drop table site_word_filter;
@@ -0,0 +1,8 @@
+-- Regex patterns which admins added through the api. These are used in addition to the slur
+-- filter in the config file. The action is either 'Block' or 'Replace'.
+create table site_word_filter (
+  id serial primary key,
+  pattern text unique not null,
+  action text not null,
+  published timestamp not null default now()
+);
@@ -0,0 +1,2 @@
+drop table mod_automod_action;
+drop table automod_rule;
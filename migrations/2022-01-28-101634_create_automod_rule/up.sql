@@ -0,0 +1,27 @@
+-- Rules which moderators define for their community. All conditions which are set need to match
+-- for the action to be applied to a new or edited post or comment.
+create table automod_rule (
+  id serial primary key,
+  community_id int references community on update cascade on delete cascade not null,
+  title_regex text,
+  body_regex text,
+  link_domain text,
+  account_age_below_days int,
+  karma_below int,
+  action text not null,
+  reason text,
+  published timestamp not null default now(),
+  updated timestamp
+);
+
+create index idx_automod_rule_community on automod_rule (community_id);
+
+create table mod_automod_action (
+  id serial primary key,
+  automod_rule_id int references automod_rule on update cascade on delete set null,
+  community_id int references community on update cascade on delete cascade not null,
+  post_id int references post on update cascade on delete cascade,
+  comment_id int references comment on update cascade on delete cascade,
+  action text not null,
+  when_ timestamp not null default now()
+);
@@ -0,0 +1,2 @@
+alter table post drop column pending;
+alter table community drop column require_post_approval;
@@ -0,0 +1,5 @@
+-- Posts in communities which require approval are pending until a moderator approves them
+alter table community add column require_post_approval boolean not null default false;
+alter table post add column pending boolean not null default false;
+
+create index idx_post_pending on post (community_id) where pending;
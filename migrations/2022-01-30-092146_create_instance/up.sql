@@ -0,0 +1,12 @@
+-- Software and user counts of federated instances, as reported by their nodeinfo. Rows are
+-- updated periodically by a background task.
+create table instance (
+  id serial primary key,
+  domain varchar(255) unique not null,
+  software varchar(255),
+  version varchar(255),
+  users bigint,
+  users_active_month bigint,
+  published timestamp not null default now(),
+  updated timestamp
+);
@@ -0,0 +1,2 @@
+alter table secret drop column instance_private_key;
+alter table secret drop column instance_public_key;
@@ -0,0 +1,4 @@
+-- Keypair of the instance actor, which signs fetches in signed fetch mode. The keys are
+-- generated on startup, as that can't be done in sql.
+alter table secret add column instance_private_key text;
+alter table secret add column instance_public_key text;
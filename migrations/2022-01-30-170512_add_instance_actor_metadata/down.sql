@@ -0,0 +1,4 @@
+alter table instance drop column name;
+alter table instance drop column description;
+alter table instance drop column icon;
+alter table instance drop column blocks_us;
@@ -0,0 +1,6 @@
+-- Metadata which remote instances federate through their instance actor, and whether they block
+-- the local instance.
+alter table instance add column name varchar(255);
+alter table instance add column description text;
+alter table instance add column icon text;
+alter table instance add column blocks_us boolean not null default false;
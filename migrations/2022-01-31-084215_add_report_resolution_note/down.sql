@@ -0,0 +1,2 @@
+alter table comment_report drop column resolution_note;
+alter table post_report drop column resolution_note;
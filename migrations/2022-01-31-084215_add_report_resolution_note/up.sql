@@ -0,0 +1,3 @@
+-- Optional note from the moderator which resolved the report
+alter table comment_report add column resolution_note text;
+alter table post_report add column resolution_note text;
@@ -0,0 +1 @@
+alter table person_block drop column prevent_interaction;
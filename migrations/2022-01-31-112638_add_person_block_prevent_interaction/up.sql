@@ -0,0 +1,2 @@
+-- If set, the blocked person also can't comment on posts and comments of the blocker
+alter table person_block add column prevent_interaction boolean not null default false;
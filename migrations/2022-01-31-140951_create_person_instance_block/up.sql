@@ -0,0 +1,9 @@
+-- Instances which a user has blocked for themselves. Posts, comments and communities from these
+-- domains are hidden in their listings.
+create table person_instance_block (
+  id serial primary key,
+  person_id int references person on update cascade on delete cascade not null,
+  domain varchar(255) not null,
+  published timestamp not null default now(),
+  unique(person_id, domain)
+);
@@ -0,0 +1,3 @@
+alter table post_saved drop column collection_id;
+alter table comment_saved drop column collection_id;
+drop table saved_collection;
@@ -0,0 +1,13 @@
+-- User-defined folders for saved posts and comments. Items without a collection stay in the
+-- default (unfiled) list.
+create table saved_collection (
+  id serial primary key,
+  person_id int references person on update cascade on delete cascade not null,
+  name varchar(100) not null,
+  published timestamp not null default now(),
+  updated timestamp,
+  unique(person_id, name)
+);
+
+alter table post_saved add column collection_id int references saved_collection on update cascade on delete set null;
+alter table comment_saved add column collection_id int references saved_collection on update cascade on delete set null;
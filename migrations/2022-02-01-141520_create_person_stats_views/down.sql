@@ -0,0 +1,2 @@
+drop view person_community_karma;
+drop view person_monthly_activity;
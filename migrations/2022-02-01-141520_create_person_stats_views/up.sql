@@ -0,0 +1,39 @@
+-- Karma of a person, split by the community where the posts and comments were made. Filtering
+-- on person_id is pushed down into both branches, so the creator indexes are used.
+create view person_community_karma as
+select
+  person_id,
+  community_id,
+  sum(post_count)::bigint as post_count,
+  sum(post_score)::bigint as post_score,
+  sum(comment_count)::bigint as comment_count,
+  sum(comment_score)::bigint as comment_score
+from (
+  select p.creator_id as person_id, p.community_id, 1 as post_count, pa.score as post_score,
+    0 as comment_count, 0::bigint as comment_score
+  from post p
+  join post_aggregates pa on pa.post_id = p.id
+  union all
+  select c.creator_id, p.community_id, 0, 0, 1, ca.score
+  from comment c
+  join comment_aggregates ca on ca.comment_id = c.id
+  join post p on p.id = c.post_id
+) as k
+group by person_id, community_id;
+
+-- Number of posts and comments a person made per calendar month
+create view person_monthly_activity as
+select
+  person_id,
+  month,
+  sum(post_count)::bigint as post_count,
+  sum(comment_count)::bigint as comment_count
+from (
+  select creator_id as person_id, date_trunc('month', published) as month, 1 as post_count,
+    0 as comment_count
+  from post
+  union all
+  select creator_id, date_trunc('month', published), 0, 1
+  from comment
+) as a
+group by person_id, month;
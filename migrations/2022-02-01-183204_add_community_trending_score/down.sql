@@ -0,0 +1,3 @@
+drop function community_aggregates_trending;
+drop index idx_community_aggregates_trending_score;
+alter table community_aggregates drop column trending_score;
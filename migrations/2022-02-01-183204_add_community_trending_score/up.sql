@@ -0,0 +1,42 @@
+alter table community_aggregates add column trending_score float8 not null default 0;
+
+create index idx_community_aggregates_trending_score on community_aggregates (trending_score desc);
+
+-- Trending score of every community, from the posts and comments of the last 7 days compared to
+-- the 7 days before, and from the subscribers gained during the last 7 days compared to the
+-- subscribers before. Both parts grow with the amount of activity or new subscribers, and are
+-- boosted by the growth rate, so that small but quickly growing communities also show up.
+create or replace function community_aggregates_trending()
+returns table(community_id_ integer, score_ float8)
+language sql
+as
+$$
+  select
+    ca.community_id,
+    ln(1 + coalesce(a.recent, 0)) * (1 + coalesce(a.recent, 0))::float8 / (1 + coalesce(a.prior, 0))
+    + ln(1 + coalesce(f.new_subscribers, 0)) * (1 + coalesce(f.new_subscribers, 0))::float8
+      / (1 + greatest(ca.subscribers - coalesce(f.new_subscribers, 0), 0))
+  from community_aggregates ca
+  left join (
+    select
+      community_id,
+      count(*) filter (where published > ('now'::timestamp - '7 days'::interval)) as recent,
+      count(*) filter (where published <= ('now'::timestamp - '7 days'::interval)) as prior
+    from (
+      select p.community_id, p.published from post p
+      where p.published > ('now'::timestamp - '14 days'::interval)
+      union all
+      select p.community_id, c.published from comment c
+      inner join post p on c.post_id = p.id
+      where c.published > ('now'::timestamp - '14 days'::interval)
+    ) x
+    group by community_id
+  ) a on a.community_id = ca.community_id
+  left join (
+    select community_id, count(*) as new_subscribers
+    from community_follower
+    where published > ('now'::timestamp - '7 days'::interval)
+    and pending is not true
+    group by community_id
+  ) f on f.community_id = ca.community_id;
+$$;
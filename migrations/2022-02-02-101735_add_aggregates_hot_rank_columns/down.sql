@@ -0,0 +1,15 @@
+drop index idx_post_aggregates_stickied_hot;
+drop index idx_post_aggregates_hot;
+drop index idx_post_aggregates_stickied_active;
+drop index idx_post_aggregates_active;
+drop index idx_comment_aggregates_hot;
+
+alter table post_aggregates drop column hot_rank;
+alter table post_aggregates drop column hot_rank_active;
+alter table comment_aggregates drop column hot_rank;
+
+create index idx_post_aggregates_stickied_hot on post_aggregates (stickied desc, hot_rank(score, published) desc, published desc);
+create index idx_post_aggregates_hot on post_aggregates (hot_rank(score, published) desc, published desc);
+create index idx_post_aggregates_stickied_active on post_aggregates (stickied desc, hot_rank(score, newest_comment_time) desc, newest_comment_time desc);
+create index idx_post_aggregates_active on post_aggregates (hot_rank(score, newest_comment_time) desc, newest_comment_time desc);
+create index idx_comment_aggregates_hot on comment_aggregates (hot_rank(score, published) desc, published desc);
@@ -0,0 +1,22 @@
+-- Store the hot ranks instead of calculating them in every query. They are refreshed by a
+-- scheduled task, for recent content only. 1728 is the rank of new content with a score of 1.
+alter table post_aggregates add column hot_rank integer not null default 1728;
+alter table post_aggregates add column hot_rank_active integer not null default 1728;
+alter table comment_aggregates add column hot_rank integer not null default 1728;
+
+update post_aggregates set
+  hot_rank = hot_rank(score, published),
+  hot_rank_active = hot_rank(score, newest_comment_time_necro);
+update comment_aggregates set hot_rank = hot_rank(score, published);
+
+drop index idx_post_aggregates_stickied_hot;
+drop index idx_post_aggregates_hot;
+drop index idx_post_aggregates_stickied_active;
+drop index idx_post_aggregates_active;
+drop index idx_comment_aggregates_hot;
+
+create index idx_post_aggregates_stickied_hot on post_aggregates (stickied desc, hot_rank desc, published desc);
+create index idx_post_aggregates_hot on post_aggregates (hot_rank desc, published desc);
+create index idx_post_aggregates_stickied_active on post_aggregates (stickied desc, hot_rank_active desc, newest_comment_time_necro desc);
+create index idx_post_aggregates_active on post_aggregates (hot_rank_active desc, newest_comment_time_necro desc);
+create index idx_comment_aggregates_hot on comment_aggregates (hot_rank desc, published desc);
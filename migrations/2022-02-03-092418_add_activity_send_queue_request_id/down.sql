@@ -0,0 +1 @@
+alter table activity_send_queue drop column request_id;
@@ -0,0 +1,2 @@
+-- Id of the http request or websocket message which created the delivery, for tracing
+alter table activity_send_queue add column request_id text;
@@ -0,0 +1,3 @@
+alter table site drop column captcha_site_key;
+alter table site drop column captcha_provider;
+drop table captcha_answer;
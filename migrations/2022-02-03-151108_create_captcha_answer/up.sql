@@ -0,0 +1,14 @@
+-- Answers of the built-in captcha, stored in the database so that every lemmy_server process can
+-- check them
+create table captcha_answer (
+  id serial primary key,
+  uuid text not null unique,
+  answer text not null,
+  published timestamp not null default now()
+);
+
+create index idx_captcha_answer_published on captcha_answer (published);
+
+alter table site add column captcha_provider text not null default 'builtin'
+  check (captcha_provider in ('builtin', 'hcaptcha', 'recaptcha'));
+alter table site add column captcha_site_key text;
@@ -0,0 +1,12 @@
+-- Accounts of OpenID Connect providers which are linked to a local user. The subject is the
+-- stable user identifier which the provider returns.
+create table external_identity (
+  id serial primary key,
+  local_user_id int references local_user on update cascade on delete cascade not null,
+  provider text not null,
+  subject text not null,
+  published timestamp not null default now(),
+  unique (provider, subject)
+);
+
+create index idx_external_identity_local_user on external_identity (local_user_id);
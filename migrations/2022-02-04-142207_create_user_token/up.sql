@@ -0,0 +1,13 @@
+-- Personal access tokens, which let bots use the API without a login session. Only a hash of the
+-- token is stored.
+create table user_token (
+  id serial primary key,
+  local_user_id int references local_user on update cascade on delete cascade not null,
+  name text not null,
+  token_hash text not null unique,
+  scopes text[] not null check (scopes <@ array['Read', 'Write', 'Moderate']),
+  published timestamp not null default now(),
+  last_used timestamp
+);
+
+create index idx_user_token_local_user on user_token (local_user_id);
@@ -0,0 +1,12 @@
+-- Every login creates a session, whose id is part of the JWT. Deleting the session revokes the
+-- token.
+create table login_session (
+  id serial primary key,
+  local_user_id int references local_user on update cascade on delete cascade not null,
+  user_agent text,
+  ip text,
+  published timestamp not null default now(),
+  last_used timestamp not null default now()
+);
+
+create index idx_login_session_local_user on login_session (local_user_id);
@@ -0,0 +1,31 @@
+-- Mod log entries of admins permanently deleting content. The purged object itself is gone, so
+-- only its community (for posts) or post (for comments) is kept.
+create table admin_purge_person (
+  id serial primary key,
+  admin_person_id int references person on update cascade on delete cascade not null,
+  reason text,
+  when_ timestamp not null default now()
+);
+
+create table admin_purge_community (
+  id serial primary key,
+  admin_person_id int references person on update cascade on delete cascade not null,
+  reason text,
+  when_ timestamp not null default now()
+);
+
+create table admin_purge_post (
+  id serial primary key,
+  admin_person_id int references person on update cascade on delete cascade not null,
+  community_id int references community on update cascade on delete cascade not null,
+  reason text,
+  when_ timestamp not null default now()
+);
+
+create table admin_purge_comment (
+  id serial primary key,
+  admin_person_id int references person on update cascade on delete cascade not null,
+  post_id int references post on update cascade on delete cascade not null,
+  reason text,
+  when_ timestamp not null default now()
+);
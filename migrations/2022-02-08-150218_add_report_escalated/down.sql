@@ -0,0 +1,2 @@
+alter table post_report drop column escalated;
+alter table comment_report drop column escalated;
@@ -0,0 +1,6 @@
+-- Reports which mods escalated to the admins of the instance
+alter table post_report add column escalated boolean not null default false;
+alter table comment_report add column escalated boolean not null default false;
+
+create index idx_post_report_escalated on post_report (published) where escalated and not resolved;
+create index idx_comment_report_escalated on comment_report (published) where escalated and not resolved;
@@ -0,0 +1,19 @@
+-- Emoji reactions, in addition to votes. A person can react with several different emojis to the
+-- same post or comment, but only once with each.
+create table post_reaction (
+  id serial primary key,
+  post_id int references post on update cascade on delete cascade not null,
+  person_id int references person on update cascade on delete cascade not null,
+  emoji text not null,
+  published timestamp not null default now(),
+  unique (post_id, person_id, emoji)
+);
+
+create table comment_reaction (
+  id serial primary key,
+  comment_id int references comment on update cascade on delete cascade not null,
+  person_id int references person on update cascade on delete cascade not null,
+  emoji text not null,
+  published timestamp not null default now(),
+  unique (comment_id, person_id, emoji)
+);
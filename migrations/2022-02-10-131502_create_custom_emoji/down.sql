@@ -0,0 +1 @@
+drop table custom_emoji;
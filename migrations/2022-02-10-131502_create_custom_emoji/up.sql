@@ -0,0 +1,10 @@
+-- Custom emoji of this instance, which can be used in posts and comments with their shortcode,
+-- like :blobcat:
+create table custom_emoji (
+  id serial primary key,
+  shortcode varchar(64) not null unique,
+  image_url text not null,
+  category varchar(64) not null,
+  published timestamp not null default now(),
+  updated timestamp null
+);
@@ -0,0 +1,3 @@
+alter table community drop column category_id;
+
+drop table community_category;
@@ -0,0 +1,11 @@
+-- Topics which the admins define, like Technology or Gaming, to make communities easier to find
+create table community_category (
+  id serial primary key,
+  name varchar(64) not null unique,
+  published timestamp not null default now(),
+  updated timestamp null
+);
+
+alter table community add column category_id int references community_category on update cascade on delete set null;
+
+create index idx_community_category on community (category_id);
@@ -0,0 +1,2 @@
+drop table multi_community_community;
+drop table multi_community;
@@ -0,0 +1,8 @@
+-- Secret for the private RSS feeds of a user. Feed readers can't log in, and the login jwt
+-- shouldn't end up in feed urls.
+create table feed_token (
+  id serial primary key,
+  local_user_id int references local_user on update cascade on delete cascade not null unique,
+  token_hash text not null unique,
+  published timestamp not null default now()
+);
@@ -0,0 +1,9 @@
+drop trigger comment_search_vector on comment;
+drop function comment_search_vector;
+alter table comment drop column search_vector;
+
+drop trigger post_search_vector on post;
+drop function post_search_vector;
+alter table post drop column search_vector;
+
+drop function search_tsquery_for_language, search_tsquery, search_document, search_config;
@@ -0,0 +1,102 @@
+-- Full text search for posts and comments. The documents are stemmed with the text search config
+-- of their language, and additionally contain the plain words, so that they can also be found in
+-- languages which Postgres has no config for.
+
+-- Languages without a config only get the plain words
+create function search_config(language_id int) returns regconfig
+language sql stable as $$
+  select coalesce((
+    select case code
+      when 'da' then 'danish'
+      when 'de' then 'german'
+      when 'en' then 'english'
+      when 'es' then 'spanish'
+      when 'fi' then 'finnish'
+      when 'fr' then 'french'
+      when 'hu' then 'hungarian'
+      when 'it' then 'italian'
+      when 'nb' then 'norwegian'
+      when 'nl' then 'dutch'
+      when 'nn' then 'norwegian'
+      when 'no' then 'norwegian'
+      when 'pt' then 'portuguese'
+      when 'ro' then 'romanian'
+      when 'ru' then 'russian'
+      when 'sv' then 'swedish'
+      when 'tr' then 'turkish'
+    end
+    from language where id = language_id
+  ), 'simple')::regconfig
+$$;
+
+create function search_document(config regconfig, content text) returns tsvector
+language sql immutable as $$
+  select to_tsvector(config, coalesce(content, '')) || to_tsvector('simple', coalesce(content, ''))
+$$;
+
+-- Search terms match the plain words, and their stemmed forms in all configs of search_config().
+-- Supports the websearch syntax, like "quoted phrases", or and -excluded. Matching this can use the
+-- indexes, but the configs of other languages may miss excluded words, so the matches are checked
+-- again with search_tsquery_for_language().
+create function search_tsquery(term text) returns tsquery
+language sql immutable as $$
+  select websearch_to_tsquery('simple', term)
+    || websearch_to_tsquery('danish', term)
+    || websearch_to_tsquery('dutch', term)
+    || websearch_to_tsquery('english', term)
+    || websearch_to_tsquery('finnish', term)
+    || websearch_to_tsquery('french', term)
+    || websearch_to_tsquery('german', term)
+    || websearch_to_tsquery('hungarian', term)
+    || websearch_to_tsquery('italian', term)
+    || websearch_to_tsquery('norwegian', term)
+    || websearch_to_tsquery('portuguese', term)
+    || websearch_to_tsquery('romanian', term)
+    || websearch_to_tsquery('russian', term)
+    || websearch_to_tsquery('spanish', term)
+    || websearch_to_tsquery('swedish', term)
+    || websearch_to_tsquery('turkish', term)
+$$;
+
+-- Search terms for documents in the given language
+create function search_tsquery_for_language(term text, language_id int) returns tsquery
+language sql stable as $$
+  select websearch_to_tsquery('simple', term) || websearch_to_tsquery(search_config(language_id), term)
+$$;
+
+-- Post titles rank higher than the body
+alter table post add column search_vector tsvector;
+
+create function post_search_vector() returns trigger
+language plpgsql as $$
+declare
+  config regconfig := search_config(new.language_id);
+begin
+  new.search_vector := setweight(search_document(config, new.name), 'A')
+    || setweight(search_document(config, new.body), 'B');
+  return new;
+end $$;
+
+create trigger post_search_vector before insert or update of name, body, language_id on post
+for each row execute procedure post_search_vector();
+
+update post set search_vector = setweight(search_document(search_config(language_id), name), 'A')
+  || setweight(search_document(search_config(language_id), body), 'B');
+
+create index idx_post_search_vector on post using gin(search_vector);
+
+alter table comment add column search_vector tsvector;
+
+create function comment_search_vector() returns trigger
+language plpgsql as $$
+begin
+  new.search_vector := search_document(search_config(new.language_id), new.content);
+  return new;
+end $$;
+
+create trigger comment_search_vector before insert or update of content, language_id on comment
+for each row execute procedure comment_search_vector();
+
+update comment set search_vector = search_document(search_config(language_id), content);
+
+create index idx_comment_search_vector on comment using gin(search_vector);
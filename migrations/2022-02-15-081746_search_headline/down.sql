@@ -0,0 +1 @@
+drop function search_headline(text, text, int);
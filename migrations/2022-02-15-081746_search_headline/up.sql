@@ -0,0 +1,11 @@
+-- Highlights the matches of a search term in a post or comment, for showing them in the search
+-- results. The matches are wrapped in markdown bold, as the content is markdown too.
+create function search_headline(content text, term text, language_id int) returns text
+language sql stable as $$
+  select ts_headline(
+    search_config(language_id),
+    content,
+    search_tsquery_for_language(term, language_id),
+    'StartSel=**, StopSel=**, MaxWords=35, MinWords=15, MaxFragments=3, FragmentDelimiter=" … "'
+  )
+$$;
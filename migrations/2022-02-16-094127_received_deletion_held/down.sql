@@ -0,0 +1,2 @@
+drop index idx_received_deletion_object;
+alter table received_deletion drop column held;
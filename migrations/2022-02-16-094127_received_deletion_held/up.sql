@@ -0,0 +1,29 @@
+-- Received deletions which passed verification, but were not applied because they are waiting
+-- for review by local mods, or were discarded for lack of attestation. Undos of them are ignored.
+alter table received_deletion add column held boolean not null default false;
+
+update received_deletion r set held = true
+where exists (select 1 from pending_deletion p where p.activity_id = r.activity_id);
+
+create index idx_received_deletion_object on received_deletion (object_id, published);
+
+-- Deletions which were received before they were logged are still in the activity table (for the
+-- last six months), so that undos of them can be matched too.
+insert into received_deletion (actor_id, activity_id, kind, object_id, accepted, published)
+select distinct on (d->>'id')
+  d->>'actor',
+  d->>'id',
+  d->>'type',
+  coalesce(d->'object'->>'id', d->>'object'),
+  true,
+  a.published
+from activity a,
+  lateral (
+    select case when a.data->>'type' = 'Announce' then a.data->'object' else a.data end as d
+  ) deletion
+where not a.local
+  and d->>'type' in ('Delete', 'Remove')
+  and d->>'actor' is not null
+  and coalesce(d->'object'->>'id', d->>'object') is not null
+  and not exists (select 1 from received_deletion r where r.activity_id = d->>'id')
+order by d->>'id', a.published;
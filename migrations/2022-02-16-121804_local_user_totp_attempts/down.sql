@@ -0,0 +1,3 @@
+alter table local_user drop column totp_last_step;
+alter table local_user drop column totp_failed_attempts;
+alter table local_user drop column totp_last_failure;
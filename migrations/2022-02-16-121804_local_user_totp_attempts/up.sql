@@ -0,0 +1,5 @@
+-- The time step of the last accepted token, so that each token can only be used once
+alter table local_user add column totp_last_step bigint;
+-- Wrong tokens since the last accepted one, to limit guessing
+alter table local_user add column totp_failed_attempts int not null default 0;
+alter table local_user add column totp_last_failure timestamp;
@@ -0,0 +1,13 @@
+-- Authorization requests which were started with an OpenID Connect provider. The state is checked
+-- when the provider redirects back, and the code verifier proves to the provider that the code is
+-- redeemed by whoever started the request (PKCE).
+create table oauth_state (
+  id serial primary key,
+  state text unique not null,
+  provider text not null,
+  redirect_uri text not null,
+  code_verifier text not null,
+  -- Set if the provider account is linked to this user, instead of logging in
+  local_user_id int references local_user on update cascade on delete cascade,
+  published timestamp not null default now()
+);
@@ -0,0 +1 @@
+drop table legal_hold;
@@ -0,0 +1,14 @@
+-- Posts and comments which admins exempted from the automatic purge of deleted and removed
+-- content, for example because of a legal retention requirement. Exactly one of post_id and
+-- comment_id is set.
+create table legal_hold (
+  id serial primary key,
+  post_id int references post on update cascade on delete cascade,
+  comment_id int references comment on update cascade on delete cascade,
+  admin_person_id int references person on update cascade on delete cascade not null,
+  reason text not null,
+  published timestamp not null default now(),
+  check ((post_id is null) != (comment_id is null)),
+  unique (post_id),
+  unique (comment_id)
+);
@@ -0,0 +1,17 @@
+delete from mod_remove_post where post_id is null;
+alter table mod_remove_post drop constraint mod_remove_post_post_id_fkey;
+alter table mod_remove_post add constraint mod_remove_post_post_id_fkey
+  foreign key (post_id) references post on update cascade on delete cascade;
+alter table mod_remove_post alter column post_id set not null;
+
+delete from mod_remove_comment where comment_id is null;
+alter table mod_remove_comment drop constraint mod_remove_comment_comment_id_fkey;
+alter table mod_remove_comment add constraint mod_remove_comment_comment_id_fkey
+  foreign key (comment_id) references comment on update cascade on delete cascade;
+alter table mod_remove_comment alter column comment_id set not null;
+
+delete from mod_remove_community where community_id is null;
+alter table mod_remove_community drop constraint mod_remove_community_community_id_fkey;
+alter table mod_remove_community add constraint mod_remove_community_community_id_fkey
+  foreign key (community_id) references community on update cascade on delete cascade;
+alter table mod_remove_community alter column community_id set not null;
@@ -0,0 +1,16 @@
+-- Keep removal entries in the mod log when their target is permanently deleted, for example by
+-- a purge. The target column is set to null instead, which marks the entry as orphaned.
+alter table mod_remove_post alter column post_id drop not null;
+alter table mod_remove_post drop constraint mod_remove_post_post_id_fkey;
+alter table mod_remove_post add constraint mod_remove_post_post_id_fkey
+  foreign key (post_id) references post on update cascade on delete set null;
+
+alter table mod_remove_comment alter column comment_id drop not null;
+alter table mod_remove_comment drop constraint mod_remove_comment_comment_id_fkey;
+alter table mod_remove_comment add constraint mod_remove_comment_comment_id_fkey
+  foreign key (comment_id) references comment on update cascade on delete set null;
+
+alter table mod_remove_community alter column community_id drop not null;
+alter table mod_remove_community drop constraint mod_remove_community_community_id_fkey;
+alter table mod_remove_community add constraint mod_remove_community_community_id_fkey
+  foreign key (community_id) references community on update cascade on delete set null;
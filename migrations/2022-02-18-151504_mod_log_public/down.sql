@@ -0,0 +1,4 @@
+alter table mod_remove_post drop column public;
+alter table mod_remove_comment drop column public;
+alter table mod_remove_community drop column public;
+alter table mod_ban_from_community drop column public;
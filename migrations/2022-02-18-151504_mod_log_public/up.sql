@@ -0,0 +1,5 @@
+-- Whether a mod action is included in the federated modlog
+alter table mod_remove_post add column public boolean not null default true;
+alter table mod_remove_comment add column public boolean not null default true;
+alter table mod_remove_community add column public boolean not null default true;
+alter table mod_ban_from_community add column public boolean not null default true;
@@ -0,0 +1 @@
+alter table pending_deletion drop column restore;
@@ -0,0 +1,2 @@
+-- Undos of deletions and removals are held for review as well, and restore the object once approved
+alter table pending_deletion add column restore boolean not null default false;
@@ -1,10 +1,20 @@
 use actix_web::*;
 use lemmy_api::Perform;
-use lemmy_api_common::{comment::*, community::*, person::*, post::*, site::*, websocket::*};
+use lemmy_api_common::{
+  comment::*,
+  community::*,
+  person::*,
+  post::*,
+  site::*,
+  token_scope::with_required_scope,
+  websocket::*,
+};
 use lemmy_api_crud::PerformCrud;
-use lemmy_utils::rate_limit::RateLimit;
-use lemmy_websocket::{routes::chat_route, LemmyContext};
+use lemmy_db_schema::TokenScope;
+use lemmy_utils::{metrics::endpoint_label, rate_limit::RateLimit};
+use lemmy_websocket::{endpoint_access::endpoint_access, routes::chat_route, LemmyContext};
 use serde::Deserialize;
+use std::time::Instant;
 
 pub fn config(cfg: &mut web::ServiceConfig, rate_limit: &RateLimit) {
   cfg.service(
@@ -23,11 +33,44 @@ pub fn config(cfg: &mut web::ServiceConfig, rate_limit: &RateLimit) {
           .route("/config", web::get().to(route_get::<GetSiteConfig>))
           .route("/config", web::put().to(route_post::<SaveSiteConfig>)),
       )
+      .service(
+        web::scope("/community_category")
+          .wrap(rate_limit.message())
+          .route(
+            "",
+            web::post().to(route_post_crud::<CreateCommunityCategory>),
+          )
+          .route("", web::put().to(route_post_crud::<EditCommunityCategory>))
+          .route(
+            "/delete",
+            web::post().to(route_post_crud::<DeleteCommunityCategory>),
+          )
+          .route(
+            "/list",
+            web::get().to(route_get_crud::<ListCommunityCategories>),
+          ),
+      )
+      .service(
+        web::scope("/custom_emoji")
+          .wrap(rate_limit.message())
+          .route("", web::post().to(route_post_crud::<CreateCustomEmoji>))
+          .route("", web::put().to(route_post_crud::<EditCustomEmoji>))
+          .route(
+            "/delete",
+            web::post().to(route_post_crud::<DeleteCustomEmoji>),
+          )
+          .route("/list", web::get().to(route_get_crud::<ListCustomEmojis>)),
+      )
       .service(
         web::resource("/modlog")
           .wrap(rate_limit.message())
           .route(web::get().to(route_get::<GetModlog>)),
       )
+      .service(
+        web::resource("/federated_instances")
+          .wrap(rate_limit.message())
+          .route(web::get().to(route_get::<GetFederatedInstances>)),
+      )
       .service(
         web::resource("/search")
           .wrap(rate_limit.message())
@@ -53,6 +96,7 @@ pub fn config(cfg: &mut web::ServiceConfig, rate_limit: &RateLimit) {
           .route("/list", web::get().to(route_get_crud::<ListCommunities>))
           .route("/follow", web::post().to(route_post::<FollowCommunity>))
           .route("/block", web::post().to(route_post::<BlockCommunity>))
+          .route("/preview", web::get().to(route_get::<PreviewCommunity>))
           .route(
             "/delete",
             web::post().to(route_post_crud::<DeleteCommunity>),
@@ -63,8 +107,57 @@ pub fn config(cfg: &mut web::ServiceConfig, rate_limit: &RateLimit) {
             web::post().to(route_post_crud::<RemoveCommunity>),
           )
           .route("/transfer", web::post().to(route_post::<TransferCommunity>))
+          .route(
+            "/category",
+            web::put().to(route_post::<SetCommunityCategory>),
+          )
           .route("/ban_user", web::post().to(route_post::<BanFromCommunity>))
           .route("/mod", web::post().to(route_post::<AddModToCommunity>))
+          .route(
+            "/pending_deletion/list",
+            web::get().to(route_get::<ListPendingDeletions>),
+          )
+          .route(
+            "/pending_deletion/resolve",
+            web::put().to(route_post::<ResolvePendingDeletion>),
+          )
+          .route("/appeal", web::post().to(route_post_crud::<CreateAppeal>))
+          .route("/appeal/list", web::get().to(route_get_crud::<ListAppeals>))
+          .route(
+            "/appeal/resolve",
+            web::put().to(route_post_crud::<ResolveAppeal>),
+          )
+          .route("/tag", web::post().to(route_post_crud::<CreatePostTag>))
+          .route("/tag", web::put().to(route_post_crud::<EditPostTag>))
+          .route("/tag/list", web::get().to(route_get_crud::<ListPostTags>))
+          .route(
+            "/tag/delete",
+            web::post().to(route_post_crud::<DeletePostTag>),
+          )
+          .route(
+            "/automod",
+            web::post().to(route_post_crud::<CreateAutomodRule>),
+          )
+          .route(
+            "/automod",
+            web::put().to(route_post_crud::<EditAutomodRule>),
+          )
+          .route(
+            "/automod/list",
+            web::get().to(route_get_crud::<ListAutomodRules>),
+          )
+          .route(
+            "/automod/delete",
+            web::post().to(route_post_crud::<DeleteAutomodRule>),
+          )
+          .route("/wiki", web::get().to(route_get_crud::<GetWikiPage>))
+          .route("/wiki", web::post().to(route_post_crud::<CreateWikiPage>))
+          .route("/wiki", web::put().to(route_post_crud::<EditWikiPage>))
+          .route("/wiki/list", web::get().to(route_get_crud::<ListWikiPages>))
+          .route(
+            "/wiki/delete",
+            web::post().to(route_post_crud::<DeleteWikiPage>),
+          )
           .route("/join", web::post().to(route_post::<CommunityJoin>))
           .route("/mod/join", web::post().to(route_post::<ModJoin>)),
       )
@@ -76,6 +169,16 @@ pub fn config(cfg: &mut web::ServiceConfig, rate_limit: &RateLimit) {
           .wrap(rate_limit.post())
           .route(web::post().to(route_post_crud::<CreatePost>)),
       )
+      .service(
+        web::resource("/post/cross_post")
+          .wrap(rate_limit.post())
+          .route(web::post().to(route_post_crud::<CrossPost>)),
+      )
+      .service(
+        web::resource("/post/schedule")
+          .wrap(rate_limit.post())
+          .route(web::post().to(route_post_crud::<SchedulePost>)),
+      )
       .service(
         web::scope("/post")
           .wrap(rate_limit.message())
@@ -88,17 +191,48 @@ pub fn config(cfg: &mut web::ServiceConfig, rate_limit: &RateLimit) {
             web::post().to(route_post::<MarkPostAsRead>),
           )
           .route("/lock", web::post().to(route_post::<LockPost>))
+          .route(
+            "/shadow_remove",
+            web::post().to(route_post::<ShadowRemovePost>),
+          )
           .route("/sticky", web::post().to(route_post::<StickyPost>))
+          .route(
+            "/pending/list",
+            web::get().to(route_get::<ListPendingPosts>),
+          )
+          .route("/approve", web::post().to(route_post::<ApprovePost>))
+          .route("/reject", web::post().to(route_post::<RejectPost>))
           .route("/list", web::get().to(route_get_crud::<GetPosts>))
           .route("/like", web::post().to(route_post::<CreatePostLike>))
+          .route("/react", web::post().to(route_post::<ReactPost>))
+          .route("/unreact", web::post().to(route_post::<UnreactPost>))
           .route("/save", web::put().to(route_post::<SavePost>))
           .route("/join", web::post().to(route_post::<PostJoin>))
+          .route("/poll/vote", web::post().to(route_post::<VotePoll>))
           .route("/report", web::post().to(route_post::<CreatePostReport>))
           .route(
             "/report/resolve",
             web::put().to(route_post::<ResolvePostReport>),
           )
+          .route(
+            "/report/escalate",
+            web::post().to(route_post::<EscalatePostReport>),
+          )
           .route("/report/list", web::get().to(route_get::<ListPostReports>))
+          .route(
+            "/scheduled/list",
+            web::get().to(route_get_crud::<ListScheduledPosts>),
+          )
+          .route(
+            "/scheduled/delete",
+            web::post().to(route_post_crud::<DeleteScheduledPost>),
+          )
+          .route("/draft", web::post().to(route_post_crud::<SaveDraft>))
+          .route("/draft/list", web::get().to(route_get_crud::<ListDrafts>))
+          .route(
+            "/draft/delete",
+            web::post().to(route_post_crud::<DeleteDraft>),
+          )
           .route(
             "/site_metadata",
             web::get().to(route_get::<GetSiteMetadata>),
@@ -124,13 +258,27 @@ pub fn config(cfg: &mut web::ServiceConfig, rate_limit: &RateLimit) {
             web::post().to(route_post::<MarkCommentAsRead>),
           )
           .route("/like", web::post().to(route_post::<CreateCommentLike>))
+          .route("/react", web::post().to(route_post::<ReactComment>))
+          .route("/unreact", web::post().to(route_post::<UnreactComment>))
           .route("/save", web::put().to(route_post::<SaveComment>))
           .route("/list", web::get().to(route_get_crud::<GetComments>))
+          .route(
+            "/children",
+            web::get().to(route_get_crud::<GetCommentChildren>),
+          )
+          .route(
+            "/history",
+            web::get().to(route_get_crud::<GetCommentEditHistory>),
+          )
           .route("/report", web::post().to(route_post::<CreateCommentReport>))
           .route(
             "/report/resolve",
             web::put().to(route_post::<ResolveCommentReport>),
           )
+          .route(
+            "/report/escalate",
+            web::post().to(route_post::<EscalateCommentReport>),
+          )
           .route(
             "/report/list",
             web::get().to(route_get::<ListCommentReports>),
@@ -141,6 +289,10 @@ pub fn config(cfg: &mut web::ServiceConfig, rate_limit: &RateLimit) {
         web::scope("/private_message")
           .wrap(rate_limit.message())
           .route("/list", web::get().to(route_get_crud::<GetPrivateMessages>))
+          .route(
+            "/thread",
+            web::get().to(route_get_crud::<GetPrivateMessageThread>),
+          )
           .route("", web::post().to(route_post_crud::<CreatePrivateMessage>))
           .route("", web::put().to(route_post_crud::<EditPrivateMessage>))
           .route(
@@ -161,6 +313,32 @@ pub fn config(cfg: &mut web::ServiceConfig, rate_limit: &RateLimit) {
           .wrap(rate_limit.register())
           .route(web::post().to(route_post_crud::<Register>)),
       )
+      .service(
+        // Sends emails, so it gets the same strict limit as registration
+        web::resource("/user/resend_verification_email")
+          .guard(guard::Post())
+          .wrap(rate_limit.register())
+          .route(web::post().to(route_post::<ResendVerificationEmail>)),
+      )
+      // OpenID Connect login
+      .service(
+        web::resource("/oauth/providers")
+          .wrap(rate_limit.message())
+          .route(web::get().to(route_get_crud::<ListOAuthProviders>)),
+      )
+      .service(
+        web::resource("/oauth/start")
+          .guard(guard::Post())
+          .wrap(rate_limit.message())
+          .route(web::post().to(route_post_crud::<StartOAuth>)),
+      )
+      .service(
+        // Can create accounts, so it gets the same strict limit as registration
+        web::resource("/oauth/authenticate")
+          .guard(guard::Post())
+          .wrap(rate_limit.register())
+          .route(web::post().to(route_post_crud::<AuthenticateWithOAuth>)),
+      )
       .service(
         // Handle captcha separately
         web::resource("/user/get_captcha")
@@ -178,10 +356,73 @@ pub fn config(cfg: &mut web::ServiceConfig, rate_limit: &RateLimit) {
             web::post().to(route_post::<MarkPersonMentionAsRead>),
           )
           .route("/replies", web::get().to(route_get::<GetReplies>))
+          .route("/stats", web::get().to(route_get::<GetPersonStats>))
+          .route(
+            "/mention_autocomplete",
+            web::get().to(route_get::<MentionAutocomplete>),
+          )
           .route("/join", web::post().to(route_post::<UserJoin>))
           // Admin action. I don't like that it's in /user
           .route("/ban", web::post().to(route_post::<BanPerson>))
+          .route(
+            "/remove_content_and_ban",
+            web::post().to(route_post::<RemoveContentAndBan>),
+          )
           .route("/block", web::post().to(route_post::<BlockPerson>))
+          .route(
+            "/block_instance",
+            web::post().to(route_post::<BlockInstance>),
+          )
+          .route("/saved", web::get().to(route_get::<GetSaved>))
+          .route("/saved/move", web::put().to(route_post::<MoveSaved>))
+          .route(
+            "/saved/collection",
+            web::post().to(route_post_crud::<CreateSavedCollection>),
+          )
+          .route(
+            "/saved/collection",
+            web::put().to(route_post_crud::<EditSavedCollection>),
+          )
+          .route(
+            "/saved/collection/list",
+            web::get().to(route_get_crud::<ListSavedCollections>),
+          )
+          .route(
+            "/saved/collection/delete",
+            web::post().to(route_post_crud::<DeleteSavedCollection>),
+          )
+          .route(
+            "/multi_community",
+            web::post().to(route_post_crud::<CreateMultiCommunity>),
+          )
+          .route(
+            "/multi_community",
+            web::put().to(route_post_crud::<EditMultiCommunity>),
+          )
+          .route(
+            "/multi_community/list",
+            web::get().to(route_get_crud::<ListMultiCommunities>),
+          )
+          .route(
+            "/multi_community/delete",
+            web::post().to(route_post_crud::<DeleteMultiCommunity>),
+          )
+          .route(
+            "/totp/generate",
+            web::post().to(route_post::<GenerateTotpSecret>),
+          )
+          .route("/totp/update", web::post().to(route_post::<UpdateTotp>))
+          .route("/export", web::post().to(route_post::<ExportAccountData>))
+          .route("/export", web::get().to(route_get::<GetAccountExport>))
+          .route("/import", web::post().to(route_post::<ImportAccountData>))
+          .route(
+            "/push_subscription",
+            web::post().to(route_post::<RegisterPushSubscription>),
+          )
+          .route(
+            "/push_subscription/delete",
+            web::post().to(route_post::<DeletePushSubscription>),
+          )
           // Account actions. I don't like that they're in /user maybe /accounts
           .route("/login", web::post().to(route_post::<Login>))
           .route(
@@ -210,8 +451,31 @@ pub fn config(cfg: &mut web::ServiceConfig, rate_limit: &RateLimit) {
             web::put().to(route_post::<ChangePassword>),
           )
           .route("/report_count", web::get().to(route_get::<GetReportCount>))
+          .route(
+            "/report/bulk_resolve",
+            web::put().to(route_post::<BulkResolveReports>),
+          )
           .route("/unread_count", web::get().to(route_get::<GetUnreadCount>))
-          .route("/verify_email", web::post().to(route_post::<VerifyEmail>)),
+          .route("/verify_email", web::post().to(route_post::<VerifyEmail>))
+          .route("/token", web::post().to(route_post::<CreateUserToken>))
+          .route("/token/list", web::get().to(route_get::<ListUserTokens>))
+          .route(
+            "/token/revoke",
+            web::post().to(route_post::<RevokeUserToken>),
+          )
+          .route(
+            "/feed_token",
+            web::post().to(route_post::<GenerateFeedToken>),
+          )
+          .route("/session/list", web::get().to(route_get::<ListSessions>))
+          .route(
+            "/session/revoke",
+            web::post().to(route_post::<RevokeSession>),
+          )
+          .route(
+            "/session/revoke_all",
+            web::post().to(route_post::<RevokeAllSessions>),
+          ),
       )
       // Admin Actions
       .service(
@@ -229,7 +493,56 @@ pub fn config(cfg: &mut web::ServiceConfig, rate_limit: &RateLimit) {
           .route(
             "/registration_application/approve",
             web::put().to(route_post::<ApproveRegistrationApplication>),
-          ),
+          )
+          .route(
+            "/received_deletion/list",
+            web::get().to(route_get::<ListReceivedDeletions>),
+          )
+          .route(
+            "/federation",
+            web::get().to(route_get::<ListFederationSettings>),
+          )
+          .route(
+            "/federation/block",
+            web::post().to(route_post::<AddBlockedInstance>),
+          )
+          .route(
+            "/federation/unblock",
+            web::post().to(route_post::<RemoveBlockedInstance>),
+          )
+          .route(
+            "/federation/allow",
+            web::post().to(route_post::<AddAllowedInstance>),
+          )
+          .route(
+            "/federation/disallow",
+            web::post().to(route_post::<RemoveAllowedInstance>),
+          )
+          .route("/word_filter", web::get().to(route_get::<ListWordFilters>))
+          .route(
+            "/word_filter/add",
+            web::post().to(route_post::<AddWordFilter>),
+          )
+          .route(
+            "/word_filter/remove",
+            web::post().to(route_post::<RemoveWordFilter>),
+          )
+          .route("/legal_hold", web::get().to(route_get::<ListLegalHolds>))
+          .route(
+            "/legal_hold/add",
+            web::post().to(route_post::<AddLegalHold>),
+          )
+          .route(
+            "/legal_hold/remove",
+            web::post().to(route_post::<RemoveLegalHold>),
+          )
+          .route("/purge/person", web::post().to(route_post::<PurgePerson>))
+          .route(
+            "/purge/community",
+            web::post().to(route_post::<PurgeCommunity>),
+          )
+          .route("/purge/post", web::post().to(route_post::<PurgePost>))
+          .route("/purge/comment", web::post().to(route_post::<PurgeComment>)),
       ),
   );
 }
@@ -242,11 +555,12 @@ where
   Request: Perform,
   Request: Send + 'static,
 {
-  let res = data
-    .perform(&context, None)
-    .await
-    .map(|json| HttpResponse::Ok().json(json))?;
-  Ok(res)
+  let start = Instant::now();
+  let res = data.perform(&context, None).await;
+  context
+    .metrics()
+    .observe_endpoint_latency(endpoint_label::<Request>(), start.elapsed());
+  Ok(HttpResponse::Ok().json(res?))
 }
 
 async fn route_get<'a, Data>(
@@ -256,7 +570,12 @@ async fn route_get<'a, Data>(
 where
   Data: Deserialize<'a> + Send + 'static + Perform,
 {
-  perform::<Data>(data.0, context).await
+  with_required_scope(
+    endpoint_access(endpoint_label::<Data>()),
+    TokenScope::Read,
+    perform::<Data>(data.0, context),
+  )
+  .await
 }
 
 async fn route_post<'a, Data>(
@@ -266,7 +585,12 @@ async fn route_post<'a, Data>(
 where
   Data: Deserialize<'a> + Send + 'static + Perform,
 {
-  perform::<Data>(data.0, context).await
+  with_required_scope(
+    endpoint_access(endpoint_label::<Data>()),
+    TokenScope::Write,
+    perform::<Data>(data.0, context),
+  )
+  .await
 }
 
 async fn perform_crud<Request>(
@@ -277,11 +601,12 @@ where
   Request: PerformCrud,
   Request: Send + 'static,
 {
-  let res = data
-    .perform(&context, None)
-    .await
-    .map(|json| HttpResponse::Ok().json(json))?;
-  Ok(res)
+  let start = Instant::now();
+  let res = data.perform(&context, None).await;
+  context
+    .metrics()
+    .observe_endpoint_latency(endpoint_label::<Request>(), start.elapsed());
+  Ok(HttpResponse::Ok().json(res?))
 }
 
 async fn route_get_crud<'a, Data>(
@@ -291,7 +616,12 @@ async fn route_get_crud<'a, Data>(
 where
   Data: Deserialize<'a> + Send + 'static + PerformCrud,
 {
-  perform_crud::<Data>(data.0, context).await
+  with_required_scope(
+    endpoint_access(endpoint_label::<Data>()),
+    TokenScope::Read,
+    perform_crud::<Data>(data.0, context),
+  )
+  .await
 }
 
 async fn route_post_crud<'a, Data>(
@@ -301,5 +631,10 @@ async fn route_post_crud<'a, Data>(
 where
   Data: Deserialize<'a> + Send + 'static + PerformCrud,
 {
-  perform_crud::<Data>(data.0, context).await
+  with_required_scope(
+    endpoint_access(endpoint_label::<Data>()),
+    TokenScope::Write,
+    perform_crud::<Data>(data.0, context),
+  )
+  .await
 }
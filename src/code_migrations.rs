@@ -18,6 +18,7 @@ use lemmy_db_schema::{
     person::{Person, PersonForm},
     post::Post,
     private_message::PrivateMessage,
+    secret::Secret,
   },
   traits::Crud,
 };
@@ -35,6 +36,7 @@ pub fn run_advanced_migrations(
   private_message_updates_2020_05_05(conn, protocol_and_hostname)?;
   post_thumbnail_url_updates_2020_07_27(conn, protocol_and_hostname)?;
   apub_columns_2021_02_02(conn)?;
+  instance_actor_keys_2022_01_30(conn)?;
 
   Ok(())
 }
@@ -118,6 +120,8 @@ fn community_updates_2020_04_02(
       followers_url: None,
       inbox_url: None,
       shared_inbox_url: None,
+      require_post_approval: None,
+      category_id: None,
     };
 
     Community::update(conn, ccommunity.id, &form)?;
@@ -284,3 +288,13 @@ fn apub_columns_2021_02_02(conn: &PgConnection) -> Result<(), LemmyError> {
 
   Ok(())
 }
+
+fn instance_actor_keys_2022_01_30(conn: &PgConnection) -> Result<(), LemmyError> {
+  let secret = Secret::init(conn)?;
+  if secret.instance_private_key.is_none() {
+    info!("Running instance_actor_keys_2022_01_30");
+    let keypair = generate_actor_keypair()?;
+    Secret::update_instance_keys(conn, &keypair.private_key, &keypair.public_key)?;
+  }
+  Ok(())
+}
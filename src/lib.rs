@@ -3,6 +3,7 @@ pub mod api_routes;
 pub mod code_migrations;
 pub mod root_span_builder;
 pub mod scheduled_tasks;
+pub mod shutdown;
 
 use lemmy_utils::LemmyError;
 use tracing::subscriber::set_global_default;
@@ -10,15 +11,24 @@ use tracing_error::ErrorLayer;
 use tracing_log::LogTracer;
 use tracing_subscriber::{layer::SubscriberExt, EnvFilter, Registry};
 
-pub fn init_tracing() -> Result<(), LemmyError> {
+pub fn init_tracing(json: bool) -> Result<(), LemmyError> {
   LogTracer::init()?;
 
   let env_filter = EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info"));
-  let format_layer = tracing_subscriber::fmt::layer();
+  let (format_layer, json_layer) = if json {
+    let json_layer = tracing_subscriber::fmt::layer()
+      .json()
+      .with_current_span(true)
+      .with_span_list(true);
+    (None, Some(json_layer))
+  } else {
+    (Some(tracing_subscriber::fmt::layer()), None)
+  };
 
   let subscriber = Registry::default()
     .with(env_filter)
     .with(format_layer)
+    .with(json_layer)
     .with(ErrorLayer::default());
 
   set_global_default(subscriber)?;
@@ -2,7 +2,7 @@
 extern crate diesel_migrations;
 
 use actix::prelude::*;
-use actix_web::{web::Data, *};
+use actix_web::{dev::Service, web::Data, *};
 use diesel::{
   r2d2::{ConnectionManager, Pool},
   PgConnection,
@@ -11,29 +11,41 @@ use doku::json::{AutoComments, Formatting};
 use lemmy_api::match_websocket_operation;
 use lemmy_api_common::{blocking, check_private_instance_and_federation_enabled};
 use lemmy_api_crud::match_websocket_operation_crud;
-use lemmy_apub_lib::activity_queue::create_activity_queue;
-use lemmy_db_schema::{get_database_url_from_env, source::secret::Secret};
-use lemmy_routes::{feeds, images, nodeinfo, webfinger};
+use lemmy_apub::{activities::deletion::send_held_deletions, objects::instance::InstanceActor};
+use lemmy_apub_lib::{activity_queue::create_activity_queue, signatures::enable_signed_fetch};
+use lemmy_db_schema::{
+  get_database_url_from_env,
+  source::{
+    federation_instance::FederationInstance,
+    secret::Secret,
+    site_word_filter::SiteWordFilter,
+  },
+};
+use lemmy_routes::{feeds, images, metrics, nodeinfo, webfinger};
 use lemmy_server::{
   api_routes,
   code_migrations::run_advanced_migrations,
   init_tracing,
   root_span_builder::QuieterRootSpanBuilder,
   scheduled_tasks,
+  shutdown::shutdown_on_signal,
 };
 use lemmy_utils::{
-  rate_limit::{rate_limiter::RateLimiter, RateLimit},
+  client_info::{with_client_info, ClientInfo},
+  rate_limit::RateLimit,
   request::build_user_agent,
+  request_id::{generate_request_id, with_request_id},
   settings::structs::Settings,
+  utils::get_ip,
   LemmyError,
 };
-use lemmy_websocket::{chat_server::ChatServer, LemmyContext};
+use lemmy_websocket::{chat_server::ChatServer, message_bus::MessageBus, LemmyContext};
 use reqwest::Client;
 use reqwest_middleware::ClientBuilder;
 use reqwest_tracing::TracingMiddleware;
-use std::{env, sync::Arc, thread};
-use tokio::sync::Mutex;
-use tracing_actix_web::TracingLogger;
+use std::{env, thread};
+use tokio::sync::watch;
+use tracing_actix_web::{RequestId, TracingLogger};
 
 embed_migrations!();
 
@@ -49,10 +61,10 @@ async fn main() -> Result<(), LemmyError> {
     return Ok(());
   }
 
-  init_tracing()?;
-
   let settings = Settings::init().expect("Couldn't initialize settings.");
 
+  init_tracing(settings.json_logging)?;
+
   // Set up the r2d2 connection pool
   let db_url = match get_database_url_from_env() {
     Ok(url) => url,
@@ -69,6 +81,8 @@ async fn main() -> Result<(), LemmyError> {
   blocking(&pool, move |conn| {
     embedded_migrations::run(conn)?;
     run_advanced_migrations(conn, &protocol_and_hostname)?;
+    FederationInstance::refresh_cache(conn)?;
+    SiteWordFilter::refresh_cache(conn)?;
     Ok(()) as Result<(), LemmyError>
   })
   .await??;
@@ -79,14 +93,18 @@ async fn main() -> Result<(), LemmyError> {
   });
 
   // Set up the rate limiter
-  let rate_limiter = RateLimit {
-    rate_limiter: Arc::new(Mutex::new(RateLimiter::default())),
-    rate_limit_config: settings.rate_limit.to_owned().unwrap_or_default(),
-  };
+  let rate_limiter = RateLimit::new(&settings).await?;
 
   // Initialize the secrets
   let conn = pool.get()?;
   let secret = Secret::init(&conn).expect("Couldn't initialize secrets.");
+  if settings.federation.signed_fetch {
+    let private_key = secret
+      .instance_private_key
+      .clone()
+      .ok_or_else(|| LemmyError::from_message("instance_actor_has_no_private_key"))?;
+    enable_signed_fetch(&InstanceActor::local_actor_id(&settings)?, private_key);
+  }
 
   println!(
     "Starting http server at {}:{}",
@@ -105,6 +123,10 @@ async fn main() -> Result<(), LemmyError> {
 
   check_private_instance_and_federation_enabled(&pool, &settings).await?;
 
+  let message_bus = match &settings.redis {
+    Some(redis) => Some(MessageBus::connect(&redis.url).await?),
+    None => None,
+  };
   let chat_server = ChatServer::startup(
     pool.clone(),
     rate_limiter.clone(),
@@ -114,12 +136,33 @@ async fn main() -> Result<(), LemmyError> {
     activity_queue.clone(),
     settings.clone(),
     secret.clone(),
+    message_bus,
   )
   .start();
 
+  // Send out deletions which were held back during maintenance
+  let context = LemmyContext::create(
+    pool.clone(),
+    chat_server.clone(),
+    client.clone(),
+    activity_queue.clone(),
+    settings.clone(),
+    secret.clone(),
+  );
+  send_held_deletions(&context).await?;
+  actix_rt::spawn(scheduled_tasks::publish_scheduled_posts(context.clone()));
+  actix_rt::spawn(scheduled_tasks::lift_expired_bans(context.clone()));
+  actix_rt::spawn(scheduled_tasks::update_instance_software(context.clone()));
+  let (stop_deliveries, deliveries_stopped) = watch::channel(false);
+  let deliveries = actix_rt::spawn(scheduled_tasks::deliver_queued_activities(
+    context,
+    deliveries_stopped,
+  ));
+
   // Create Http server with websocket support
   let settings_bind = settings.clone();
-  HttpServer::new(move || {
+  let shutdown_chat_server = chat_server.clone();
+  let server = HttpServer::new(move || {
     let context = LemmyContext::create(
       pool.clone(),
       chat_server.to_owned(),
@@ -130,6 +173,24 @@ async fn main() -> Result<(), LemmyError> {
     );
     let rate_limiter = rate_limiter.clone();
     App::new()
+      .wrap_fn(|req, srv| {
+        // Make the request id available outside of the request span, for activity deliveries
+        let request_id = req
+          .extensions()
+          .get::<RequestId>()
+          .map(ToString::to_string)
+          .unwrap_or_else(generate_request_id);
+        // Recorded with login sessions
+        let client_info = ClientInfo {
+          user_agent: req
+            .headers()
+            .get(http::header::USER_AGENT)
+            .and_then(|h| h.to_str().ok())
+            .map(ToString::to_string),
+          ip: Some(get_ip(&req.connection_info()).to_string()),
+        };
+        with_request_id(request_id, with_client_info(client_info, srv.call(req)))
+      })
       .wrap(actix_web::middleware::Logger::default())
       .wrap(TracingLogger::<QuieterRootSpanBuilder>::new())
       .app_data(Data::new(context))
@@ -140,11 +201,18 @@ async fn main() -> Result<(), LemmyError> {
       .configure(|cfg| images::config(cfg, client.clone(), &rate_limiter))
       .configure(nodeinfo::config)
       .configure(|cfg| webfinger::config(cfg, &settings))
+      .configure(|cfg| metrics::config(cfg, &settings))
   })
   .bind((settings_bind.bind, settings_bind.port))?
-  .run()
-  .await?;
-
+  .disable_signals()
+  .run();
+  actix_rt::spawn(shutdown_on_signal(server.handle(), shutdown_chat_server));
+  server.await?;
+
+  // The open requests are finished, so no more activities are queued. Wait for the running
+  // deliveries, the remaining ones are sent after the restart.
+  stop_deliveries.send(true).ok();
+  deliveries.await?;
   drop(queue_manager);
 
   Ok(())
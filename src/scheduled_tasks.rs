@@ -2,10 +2,44 @@
 use clokwerk::{Scheduler, TimeUnits};
 // Import week days and WeekDay
 use diesel::{sql_query, PgConnection, RunQueryDsl};
-use lemmy_db_schema::{source::activity::Activity, DbPool};
-use lemmy_utils::LemmyError;
+use lemmy_db_schema::{
+  aggregates::{
+    comment_aggregates::CommentAggregates,
+    community_aggregates::CommunityAggregates,
+    post_aggregates::PostAggregates,
+  },
+  clock::{Clock, SystemClock},
+  source::{
+    account_export::AccountExport,
+    activity::Activity,
+    captcha_answer::CaptchaAnswer,
+    comment::Comment,
+    federation_instance::FederationInstance,
+    local_user::LocalUser,
+    oauth_state::OAuthState,
+    post::Post,
+  },
+  DbPool,
+  DigestFrequency,
+  ListingType,
+  SortType,
+};
+use lemmy_db_views::{
+  comment_view::CommentQueryBuilder,
+  local_user_view::LocalUserView,
+  post_view::PostQueryBuilder,
+};
+use lemmy_db_views_actor::person_mention_view::PersonMentionQueryBuilder;
+use lemmy_utils::{
+  email::send_email,
+  settings::structs::Settings,
+  utils::markdown_to_html,
+  LemmyError,
+};
+use lemmy_websocket::LemmyContext;
 use std::{thread, time::Duration};
-use tracing::info;
+use tokio::sync::watch;
+use tracing::{error, info};
 
 /// Schedules various cleanup tasks for lemmy in a background thread
 pub fn setup(pool: DbPool) -> Result<(), LemmyError> {
@@ -22,12 +56,54 @@ pub fn setup(pool: DbPool) -> Result<(), LemmyError> {
     reindex_aggregates_tables(&conn, true);
   });
 
+  let conn = pool.get()?;
+  update_hot_ranks(&conn, &SystemClock);
+  scheduler.every(10.minutes()).run(move || {
+    update_hot_ranks(&conn, &SystemClock);
+  });
+
+  let conn = pool.get()?;
+  update_trending_communities(&conn);
+  scheduler.every(1.hour()).run(move || {
+    update_trending_communities(&conn);
+  });
+
   let conn = pool.get()?;
   clear_old_activities(&conn);
   scheduler.every(1.weeks()).run(move || {
     clear_old_activities(&conn);
   });
 
+  let conn = pool.get()?;
+  purge_expired_content(&conn, &SystemClock);
+  scheduler.every(1.day()).run(move || {
+    purge_expired_content(&conn, &SystemClock);
+  });
+
+  let conn = pool.get()?;
+  clear_old_account_exports(&conn, &SystemClock);
+  scheduler.every(1.day()).run(move || {
+    clear_old_account_exports(&conn, &SystemClock);
+  });
+
+  let conn = pool.get()?;
+  scheduler.every(1.hour()).run(move || {
+    clear_expired_captchas(&conn);
+    clear_expired_oauth_states(&conn);
+  });
+
+  let conn = pool.get()?;
+  send_email_digests(&conn, &SystemClock);
+  scheduler.every(1.hour()).run(move || {
+    send_email_digests(&conn, &SystemClock);
+  });
+
+  // Other processes may have changed the federation lists
+  let conn = pool.get()?;
+  scheduler.every(1.minute()).run(move || {
+    refresh_federation_instance_cache(&conn);
+  });
+
   // Manually run the scheduler in an event loop
   loop {
     scheduler.run_pending();
@@ -35,17 +111,233 @@ pub fn setup(pool: DbPool) -> Result<(), LemmyError> {
   }
 }
 
+/// Publishes scheduled posts once their time has come. Federating them needs the full context, so
+/// unlike the other tasks this runs on the actix runtime instead of the scheduler thread.
+pub async fn publish_scheduled_posts(context: LemmyContext) {
+  let mut interval = actix_rt::time::interval(Duration::from_secs(60));
+  loop {
+    interval.tick().await;
+    if let Err(e) = lemmy_api_crud::publish_scheduled_posts(&context, &SystemClock).await {
+      error!("Failed to publish scheduled posts: {}", e);
+    }
+  }
+}
+
+/// Lifts expired community and site bans every minute
+pub async fn lift_expired_bans(context: LemmyContext) {
+  let mut interval = actix_rt::time::interval(Duration::from_secs(60));
+  loop {
+    interval.tick().await;
+    if let Err(e) = lemmy_api::lift_expired_community_bans(&context, &SystemClock).await {
+      error!("Failed to lift expired community bans: {}", e);
+    }
+    if let Err(e) = lemmy_api::lift_expired_site_bans(&context, &SystemClock).await {
+      error!("Failed to lift expired site bans: {}", e);
+    }
+  }
+}
+
+/// Drains the outgoing activity queue every two seconds, until `stop` is set. Deliveries which
+/// were already started are finished first.
+pub async fn deliver_queued_activities(context: LemmyContext, stop: watch::Receiver<bool>) {
+  let mut interval = actix_rt::time::interval(Duration::from_secs(2));
+  loop {
+    interval.tick().await;
+    if *stop.borrow() {
+      break;
+    }
+    if let Err(e) =
+      lemmy_apub::activities::send_queue::deliver_queued_activities(&context, &SystemClock).await
+    {
+      error!("Failed to deliver queued activities: {}", e);
+    }
+  }
+}
+
+/// Refreshes the software and user counts of linked instances once a day
+pub async fn update_instance_software(context: LemmyContext) {
+  let mut interval = actix_rt::time::interval(Duration::from_secs(24 * 60 * 60));
+  loop {
+    interval.tick().await;
+    if let Err(e) = lemmy_api::update_instance_software(&context).await {
+      error!("Failed to update instance software: {}", e);
+    }
+  }
+}
+
+/// Account exports are only kept for a week, as they can be large
+fn clear_old_account_exports(conn: &PgConnection, clock: &dyn Clock) {
+  info!("Clearing old account exports...");
+  AccountExport::delete_older_than(conn, clock.now() - chrono::Duration::weeks(1))
+    .expect("clear old account exports");
+  info!("Done.");
+}
+
+fn refresh_federation_instance_cache(conn: &PgConnection) {
+  if let Err(e) = FederationInstance::refresh_cache(conn) {
+    error!("Failed to refresh federation instance cache: {}", e);
+  }
+}
+
+fn clear_expired_captchas(conn: &PgConnection) {
+  info!("Clearing expired captchas...");
+  CaptchaAnswer::delete_expired(conn).expect("clear expired captchas");
+  info!("Done.");
+}
+
+fn clear_expired_oauth_states(conn: &PgConnection) {
+  info!("Clearing expired oauth states...");
+  OAuthState::delete_expired(conn).expect("clear expired oauth states");
+  info!("Done.");
+}
+
+/// Maximum number of unread replies and of unread mentions in each digest
+const DIGEST_MAX_ITEMS: i64 = 10;
+
+/// Emails the users whose daily or weekly digest is due
+fn send_email_digests(conn: &PgConnection, clock: &dyn Clock) {
+  let settings = Settings::get();
+  if !settings.email_digest.enabled || settings.email.is_none() {
+    return;
+  }
+  info!("Sending email digests...");
+  let now = clock.now();
+  let schedules = [
+    (
+      DigestFrequency::Daily,
+      chrono::Duration::days(1),
+      SortType::TopDay,
+    ),
+    (
+      DigestFrequency::Weekly,
+      chrono::Duration::weeks(1),
+      SortType::TopWeek,
+    ),
+  ];
+  for (frequency, period, sort) in schedules {
+    let local_users = LocalUser::list_due_for_email_digest(conn, frequency, now - period)
+      .expect("list users due for email digest");
+    for local_user in local_users {
+      if let Err(e) = send_email_digest(conn, &local_user, sort, &settings) {
+        error!("Failed to send email digest: {}", e);
+      }
+      // Also on failure, otherwise a broken address would be retried every hour
+      LocalUser::update_email_digest_sent(conn, local_user.id, now)
+        .expect("update email digest sent");
+    }
+  }
+  info!("Done.");
+}
+
+/// Nothing is sent if there are no unread replies or mentions and no new posts
+fn send_email_digest(
+  conn: &PgConnection,
+  local_user: &LocalUser,
+  sort: SortType,
+  settings: &Settings,
+) -> Result<(), LemmyError> {
+  let email = match &local_user.email {
+    Some(email) => email,
+    None => return Ok(()),
+  };
+  let person = LocalUserView::read(conn, local_user.id)?.person;
+  if person.banned || person.deleted {
+    return Ok(());
+  }
+
+  let replies = CommentQueryBuilder::create(conn)
+    .sort(SortType::New)
+    .unread_only(true)
+    .recipient_id(person.id)
+    .my_person_id(person.id)
+    .limit(DIGEST_MAX_ITEMS)
+    .list()?;
+  let mentions = PersonMentionQueryBuilder::create(conn)
+    .sort(SortType::New)
+    .unread_only(true)
+    .recipient_id(person.id)
+    .my_person_id(person.id)
+    .limit(DIGEST_MAX_ITEMS)
+    .list()?;
+  let posts = PostQueryBuilder::create(conn)
+    .listing_type(ListingType::Subscribed)
+    .sort(sort)
+    .show_nsfw(local_user.show_nsfw)
+    .show_bot_accounts(local_user.show_bot_accounts)
+    .my_person_id(person.id)
+    .limit(settings.email_digest.top_posts)
+    .list()?;
+  if replies.is_empty() && mentions.is_empty() && posts.is_empty() {
+    return Ok(());
+  }
+
+  let base = settings.get_protocol_and_hostname();
+  let mut html = String::new();
+  let comments = [
+    (
+      "Replies",
+      replies
+        .iter()
+        .map(|r| (&r.creator.name, &r.comment.content, r.comment.id))
+        .collect::<Vec<_>>(),
+    ),
+    (
+      "Mentions",
+      mentions
+        .iter()
+        .map(|m| (&m.creator.name, &m.comment.content, m.comment.id))
+        .collect(),
+    ),
+  ];
+  for (heading, comments) in comments.iter().filter(|(_, c)| !c.is_empty()) {
+    html.push_str(&format!("<h2>{}</h2>", heading));
+    for (creator, content, comment_id) in comments {
+      html.push_str(&format!(
+        "<p><b>{}</b>: <a href=\"{}/comment/{}\">link</a></p>{}",
+        escape_html(creator),
+        base,
+        comment_id,
+        markdown_to_html(content)
+      ));
+    }
+  }
+  if !posts.is_empty() {
+    html.push_str("<h2>Top posts in your subscriptions</h2><ul>");
+    for post in &posts {
+      html.push_str(&format!(
+        "<li><a href=\"{}/post/{}\">{}</a> in {}</li>",
+        base,
+        post.post.id,
+        escape_html(&post.post.name),
+        escape_html(&post.community.name)
+      ));
+    }
+    html.push_str("</ul>");
+  }
+  html.push_str(&format!(
+    "<p><a href=\"{}/settings\">Change how often you receive this email</a></p>",
+    base
+  ));
+
+  let subject = format!("Your digest - {}", settings.hostname);
+  send_email(&subject, email, &person.name, &html, settings)
+}
+
+fn escape_html(text: &str) -> String {
+  text
+    .replace('&', "&amp;")
+    .replace('<', "&lt;")
+    .replace('>', "&gt;")
+    .replace('"', "&quot;")
+}
+
 /// Reindex the aggregates tables every one hour
 /// This is necessary because hot_rank is actually a mutable function:
 /// https://dba.stackexchange.com/questions/284052/how-to-create-an-index-based-on-a-time-based-function-in-postgres?noredirect=1#comment555727_284052
+/// Posts and comments store their hot rank in a column instead, only communities still index the
+/// function.
 fn reindex_aggregates_tables(conn: &PgConnection, concurrently: bool) {
-  for table_name in &[
-    "post_aggregates",
-    "comment_aggregates",
-    "community_aggregates",
-  ] {
-    reindex_table(conn, table_name, concurrently);
-  }
+  reindex_table(conn, "community_aggregates", concurrently);
 }
 
 fn reindex_table(conn: &PgConnection, table_name: &str, concurrently: bool) {
@@ -56,6 +348,26 @@ fn reindex_table(conn: &PgConnection, table_name: &str, concurrently: bool) {
   info!("Done.");
 }
 
+/// Posts and comments older than this keep a hot rank of 0, so that only recent content needs to
+/// be updated
+const HOT_RANK_UPDATE_DAYS: i64 = 7;
+
+/// Recalculate the stored hot ranks of recent posts and comments
+fn update_hot_ranks(conn: &PgConnection, clock: &dyn Clock) {
+  info!("Updating hot ranks...");
+  let since = clock.now() - chrono::Duration::days(HOT_RANK_UPDATE_DAYS);
+  PostAggregates::update_hot_ranks(conn, since).expect("update post hot ranks");
+  CommentAggregates::update_hot_ranks(conn, since).expect("update comment hot ranks");
+  info!("Done.");
+}
+
+/// Recalculate the trending score of communities, which is used by the `Trending` sort
+fn update_trending_communities(conn: &PgConnection) {
+  info!("Updating trending communities...");
+  CommunityAggregates::update_trending_scores(conn).expect("update trending communities");
+  info!("Done.");
+}
+
 /// Clear old activities (this table gets very large)
 fn clear_old_activities(conn: &PgConnection) {
   info!("Clearing old activities...");
@@ -63,6 +375,28 @@ fn clear_old_activities(conn: &PgConnection) {
   info!("Done.");
 }
 
+/// Permanently delete content which was deleted or removed longer ago than configured
+fn purge_expired_content(conn: &PgConnection, clock: &dyn Clock) {
+  let moderation = Settings::get().moderation;
+  let policies = [
+    (false, moderation.purge_deleted_after_days),
+    (true, moderation.purge_removed_after_days),
+  ];
+  for (removed, days) in policies {
+    if let Some(days) = days {
+      info!(
+        "Purging content which was deleted or removed over {} days ago...",
+        days
+      );
+      let before = clock.now() - chrono::Duration::days(days);
+      // Comments first, so that their posts can be purged in the same run
+      Comment::purge_expired(conn, removed, before).expect("purge expired comments");
+      Post::purge_expired(conn, removed, before).expect("purge expired posts");
+      info!("Done.");
+    }
+  }
+}
+
 /// Re-calculate the site and community active counts every 12 hours
 fn active_counts(conn: &PgConnection) {
   info!("Updating active site and community aggregates ...");
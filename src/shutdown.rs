@@ -0,0 +1,39 @@
+use actix::Addr;
+use actix_web::dev::ServerHandle;
+use lemmy_utils::LemmyError;
+use lemmy_websocket::{chat_server::ChatServer, messages::CloseAllSessions};
+use tracing::{error, info};
+
+/// Waits for SIGINT or SIGTERM, then closes all websocket sessions and stops the http server.
+/// Requests which are already running are finished first, so that the activities they send are
+/// stored in the send queue.
+pub async fn shutdown_on_signal(server: ServerHandle, chat_server: Addr<ChatServer>) {
+  if let Err(e) = wait_for_signal().await {
+    error!("Failed to listen for shutdown signals: {}", e);
+    return;
+  }
+  info!("Shutting down, waiting for open requests to finish");
+  if let Err(e) = chat_server.send(CloseAllSessions).await {
+    error!("Failed to close websocket sessions: {}", e);
+  }
+  server.stop(true).await;
+}
+
+#[cfg(unix)]
+async fn wait_for_signal() -> Result<(), LemmyError> {
+  use actix_rt::signal::{
+    ctrl_c,
+    unix::{signal, SignalKind},
+  };
+  use futures::future::select;
+
+  let mut terminate = signal(SignalKind::terminate())?;
+  select(Box::pin(ctrl_c()), Box::pin(terminate.recv())).await;
+  Ok(())
+}
+
+#[cfg(not(unix))]
+async fn wait_for_signal() -> Result<(), LemmyError> {
+  actix_rt::signal::ctrl_c().await?;
+  Ok(())
+}